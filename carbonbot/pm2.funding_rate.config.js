@@ -1,9 +1,16 @@
 // see src/market_type.rs in crypto-markets
 const market_types = {
     binance: ["linear_swap", "inverse_swap"],
+    bitfinex: ["linear_swap"],
     bitget: ["inverse_swap", "linear_swap"],
     bitmex: ["inverse_swap" /*, "quanto_swap"*/], // the funding channel includes all pairs
+    bybit: ["inverse_swap", "linear_swap"],
+    deribit: ["inverse_swap"],
+    ftx: ["linear_swap"],
+    gate: ["inverse_swap", "linear_swap"],
     huobi: ["linear_swap", "inverse_swap"],
+    kucoin: ["inverse_swap", "linear_swap"],
+    mxc: ["inverse_swap", "linear_swap"],
     okex: ["linear_swap", "inverse_swap"],
 };
 
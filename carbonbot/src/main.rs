@@ -141,7 +141,7 @@ pub fn crawl(
         }
     }));
 
-    match msg_type {
+    let result = match msg_type {
         MessageType::Trade => crawl_trade(exchange, market_type, None, on_msg_ext, None),
         MessageType::L2Event => crawl_l2_event(exchange, market_type, None, on_msg_ext, None),
         MessageType::L3Event => crawl_l3_event(exchange, market_type, None, on_msg_ext, None),
@@ -151,11 +151,25 @@ pub fn crawl(
         MessageType::L3Snapshot => {
             crawl_l3_snapshot(exchange, market_type, None, on_msg_ext, None, None)
         }
+        MessageType::BBO => crawl_bbo(exchange, market_type, None, on_msg_ext, None),
         MessageType::Ticker => crawl_ticker(exchange, market_type, None, on_msg_ext, None),
+        MessageType::Candlestick => {
+            crawl_candlestick(exchange, market_type, None, on_msg_ext, None, 60)
+        }
         MessageType::FundingRate => {
             crawl_funding_rate(exchange, market_type, None, on_msg_ext, None)
         }
-        _ => panic!("Not implemented"),
+        MessageType::OpenInterest => {
+            crawl_open_interest(exchange, market_type, None, on_msg_ext, None)
+        }
+        MessageType::Liquidation => {
+            crawl_liquidation(exchange, market_type, None, on_msg_ext, None)
+        }
+        MessageType::MarkPrice => crawl_mark_price(exchange, market_type, None, on_msg_ext, None),
+        MessageType::IndexPrice => crawl_index_price(exchange, market_type, None, on_msg_ext, None),
+    };
+    if let Err(err) = result.and_then(|handle| handle.join()) {
+        error!("{}", err);
     }
 
     for kv in writers_map_clone.iter() {
@@ -0,0 +1,10 @@
+pub use crypto_market_type::MarketType;
+
+pub(crate) fn get_contract_value(market_type: MarketType, _pair: &str) -> Option<f64> {
+    match market_type {
+        // Each inverse contract value is 1 USD, see:
+        // https://support.kraken.com/hc/en-us/articles/360022835771-Inverse-Futures-Contract-Specifications
+        MarketType::InverseSwap | MarketType::InverseFuture => Some(1.0),
+        _ => None,
+    }
+}
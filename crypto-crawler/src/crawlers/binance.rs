@@ -1,14 +1,13 @@
-use core::panic;
 use std::{
     sync::{
-        atomic::{AtomicBool, AtomicUsize, Ordering},
+        atomic::{AtomicUsize, Ordering},
         Arc, Mutex,
     },
     time::Duration,
 };
 
-use super::utils::{check_args, fetch_symbols_retry};
-use crate::{msg::Message, MessageType};
+use super::utils::{check_args, crawl_snapshot, fetch_symbols_retry};
+use crate::{handle::Context, msg::Message, CrawlerError, MessageType};
 use crypto_markets::MarketType;
 use crypto_ws_client::*;
 use log::*;
@@ -44,19 +43,51 @@ gen_crawl_event!(crawl_ticker_inverse, BinanceInverseWSClient, MessageType::Tick
 #[rustfmt::skip]
 gen_crawl_event!(crawl_ticker_linear, BinanceLinearWSClient, MessageType::Ticker, subscribe_ticker);
 
+#[rustfmt::skip]
+gen_crawl_event!(crawl_bbo_spot, BinanceSpotWSClient, MessageType::BBO, subscribe_bbo);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_bbo_inverse, BinanceInverseWSClient, MessageType::BBO, subscribe_bbo);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_bbo_linear, BinanceLinearWSClient, MessageType::BBO, subscribe_bbo);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_bbo_linear_option, BinanceOptionWSClient, MessageType::BBO, subscribe_bbo);
+
+#[rustfmt::skip]
+gen_crawl_event!(crawl_candlestick_spot, BinanceSpotWSClient, MessageType::Candlestick, subscribe_candlestick, interval: u32);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_candlestick_inverse, BinanceInverseWSClient, MessageType::Candlestick, subscribe_candlestick, interval: u32);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_candlestick_linear, BinanceLinearWSClient, MessageType::Candlestick, subscribe_candlestick, interval: u32);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_candlestick_linear_option, BinanceOptionWSClient, MessageType::Candlestick, subscribe_candlestick, interval: u32);
+
+#[rustfmt::skip]
+gen_crawl_event!(crawl_liquidation_inverse, BinanceInverseWSClient, MessageType::Liquidation, subscribe_liquidation);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_liquidation_linear, BinanceLinearWSClient, MessageType::Liquidation, subscribe_liquidation);
+
+#[rustfmt::skip]
+gen_crawl_event!(crawl_mark_price_inverse, BinanceInverseWSClient, MessageType::MarkPrice, subscribe_mark_price);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_mark_price_linear, BinanceLinearWSClient, MessageType::MarkPrice, subscribe_mark_price);
+
+#[rustfmt::skip]
+gen_crawl_event!(crawl_index_price_inverse, BinanceInverseWSClient, MessageType::IndexPrice, subscribe_index_price);
+
 pub(crate) fn crawl_trade(
     market_type: MarketType,
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) -> Option<std::thread::JoinHandle<()>> {
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
     match market_type {
-        MarketType::Spot => crawl_trade_spot(market_type, symbols, on_msg, duration),
+        MarketType::Spot => crawl_trade_spot(market_type, symbols, on_msg, duration, ctx),
         MarketType::InverseFuture | MarketType::InverseSwap => {
-            crawl_trade_inverse(market_type, symbols, on_msg, duration)
+            crawl_trade_inverse(market_type, symbols, on_msg, duration, ctx)
         }
         MarketType::LinearFuture | MarketType::LinearSwap => {
-            crawl_trade_linear(market_type, symbols, on_msg, duration)
+            crawl_trade_linear(market_type, symbols, on_msg, duration, ctx)
         }
         MarketType::EuropeanOption => {
             if symbols.is_none() || symbols.unwrap().is_empty() {
@@ -75,15 +106,18 @@ pub(crate) fn crawl_trade(
                     "BTCUSDT_P@TRADE_ALL".to_string(),
                 ];
 
-                let ws_client = BinanceOptionWSClient::new(on_msg_ext, None);
+                let ws_client = ctx.register(BinanceOptionWSClient::new(on_msg_ext, None));
                 ws_client.subscribe(&channels);
                 ws_client.run(duration);
-                None
+                Ok(())
             } else {
-                crawl_trade_linear_option(market_type, symbols, on_msg, duration)
+                crawl_trade_linear_option(market_type, symbols, on_msg, duration, ctx)
             }
         }
-        _ => panic!("Binance does NOT have the {} market type", market_type),
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
     }
 }
 
@@ -92,19 +126,23 @@ pub(crate) fn crawl_l2_event(
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) -> Option<std::thread::JoinHandle<()>> {
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
     match market_type {
-        MarketType::Spot => crawl_l2_event_spot(market_type, symbols, on_msg, duration),
+        MarketType::Spot => crawl_l2_event_spot(market_type, symbols, on_msg, duration, ctx),
         MarketType::InverseFuture | MarketType::InverseSwap => {
-            crawl_l2_event_inverse(market_type, symbols, on_msg, duration)
+            crawl_l2_event_inverse(market_type, symbols, on_msg, duration, ctx)
         }
         MarketType::LinearFuture | MarketType::LinearSwap => {
-            crawl_l2_event_linear(market_type, symbols, on_msg, duration)
+            crawl_l2_event_linear(market_type, symbols, on_msg, duration, ctx)
         }
         MarketType::EuropeanOption => {
-            crawl_l2_event_linear_option(market_type, symbols, on_msg, duration)
+            crawl_l2_event_linear_option(market_type, symbols, on_msg, duration, ctx)
         }
-        _ => panic!("Binance does NOT have the {} market type", market_type),
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
     }
 }
 
@@ -113,7 +151,8 @@ pub(crate) fn crawl_ticker(
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) -> Option<std::thread::JoinHandle<()>> {
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
     let on_msg_clone = on_msg.clone();
     let on_msg_ext = Arc::new(Mutex::new(move |msg: String| {
         let message = Message::new(
@@ -130,39 +169,43 @@ pub(crate) fn crawl_ticker(
 
         match market_type {
             MarketType::Spot => {
-                let ws_client = BinanceSpotWSClient::new(on_msg_ext, None);
+                let ws_client = ctx.register(BinanceSpotWSClient::new(on_msg_ext, None));
                 ws_client.subscribe(&channels);
                 ws_client.run(duration);
+                Ok(())
             }
             MarketType::InverseFuture | MarketType::InverseSwap => {
-                let ws_client = BinanceInverseWSClient::new(on_msg_ext, None);
+                let ws_client = ctx.register(BinanceInverseWSClient::new(on_msg_ext, None));
                 ws_client.subscribe(&channels);
                 ws_client.run(duration);
+                Ok(())
             }
             MarketType::LinearFuture | MarketType::LinearSwap => {
-                let ws_client = BinanceLinearWSClient::new(on_msg_ext, None);
+                let ws_client = ctx.register(BinanceLinearWSClient::new(on_msg_ext, None));
                 ws_client.subscribe(&channels);
                 ws_client.run(duration);
+                Ok(())
             }
-            _ => panic!(
-                "Binance {} market does NOT have the ticker channel",
-                market_type
-            ),
+            _ => Err(CrawlerError::unsupported_message_type(
+                EXCHANGE_NAME,
+                market_type,
+                MessageType::Ticker,
+            )),
         }
-        None
     } else {
         match market_type {
-            MarketType::Spot => crawl_ticker_spot(market_type, symbols, on_msg, duration),
+            MarketType::Spot => crawl_ticker_spot(market_type, symbols, on_msg, duration, ctx),
             MarketType::InverseFuture | MarketType::InverseSwap => {
-                crawl_ticker_inverse(market_type, symbols, on_msg, duration)
+                crawl_ticker_inverse(market_type, symbols, on_msg, duration, ctx)
             }
             MarketType::LinearFuture | MarketType::LinearSwap => {
-                crawl_ticker_linear(market_type, symbols, on_msg, duration)
+                crawl_ticker_linear(market_type, symbols, on_msg, duration, ctx)
             }
-            _ => panic!(
-                "Binance {} market does NOT have the ticker channel",
-                market_type
-            ),
+            _ => Err(CrawlerError::unsupported_message_type(
+                EXCHANGE_NAME,
+                market_type,
+                MessageType::Ticker,
+            )),
         }
     }
 }
@@ -172,7 +215,8 @@ pub(crate) fn crawl_funding_rate(
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) {
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
     let on_msg_ext = Arc::new(Mutex::new(move |msg: String| {
         let message = Message::new(
             EXCHANGE_NAME.to_string(),
@@ -195,15 +239,176 @@ pub(crate) fn crawl_funding_rate(
 
     match market_type {
         MarketType::InverseSwap => {
-            let ws_client = BinanceInverseWSClient::new(on_msg_ext, None);
+            let ws_client = ctx.register(BinanceInverseWSClient::new(on_msg_ext, None));
             ws_client.subscribe(&channels);
             ws_client.run(duration);
+            Ok(())
         }
         MarketType::LinearSwap => {
-            let ws_client = BinanceLinearWSClient::new(on_msg_ext, None);
+            let ws_client = ctx.register(BinanceLinearWSClient::new(on_msg_ext, None));
             ws_client.subscribe(&channels);
             ws_client.run(duration);
+            Ok(())
         }
-        _ => panic!("Binance {} does NOT have funding rates", market_type),
+        _ => Err(CrawlerError::unsupported_message_type(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::FundingRate,
+        )),
+    }
+}
+
+pub(crate) fn crawl_bbo(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::Spot => crawl_bbo_spot(market_type, symbols, on_msg, duration, ctx),
+        MarketType::InverseFuture | MarketType::InverseSwap => {
+            crawl_bbo_inverse(market_type, symbols, on_msg, duration, ctx)
+        }
+        MarketType::LinearFuture | MarketType::LinearSwap => {
+            crawl_bbo_linear(market_type, symbols, on_msg, duration, ctx)
+        }
+        MarketType::EuropeanOption => {
+            crawl_bbo_linear_option(market_type, symbols, on_msg, duration, ctx)
+        }
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
+    }
+}
+
+pub(crate) fn crawl_candlestick(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    interval: u32,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::Spot => {
+            crawl_candlestick_spot(market_type, symbols, on_msg, duration, interval, ctx)
+        }
+        MarketType::InverseFuture | MarketType::InverseSwap => {
+            crawl_candlestick_inverse(market_type, symbols, on_msg, duration, interval, ctx)
+        }
+        MarketType::LinearFuture | MarketType::LinearSwap => {
+            crawl_candlestick_linear(market_type, symbols, on_msg, duration, interval, ctx)
+        }
+        MarketType::EuropeanOption => {
+            crawl_candlestick_linear_option(market_type, symbols, on_msg, duration, interval, ctx)
+        }
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
+    }
+}
+
+pub(crate) fn crawl_open_interest(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::InverseFuture
+        | MarketType::InverseSwap
+        | MarketType::LinearFuture
+        | MarketType::LinearSwap => crawl_snapshot(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::OpenInterest,
+            symbols,
+            on_msg,
+            None,
+            duration,
+            ctx,
+        ),
+        _ => Err(CrawlerError::unsupported_message_type(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::OpenInterest,
+        )),
+    }
+}
+
+pub(crate) fn crawl_liquidation(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::InverseFuture | MarketType::InverseSwap => {
+            crawl_liquidation_inverse(market_type, symbols, on_msg, duration, ctx)
+        }
+        MarketType::LinearFuture | MarketType::LinearSwap => {
+            crawl_liquidation_linear(market_type, symbols, on_msg, duration, ctx)
+        }
+        _ => Err(CrawlerError::unsupported_message_type(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::Liquidation,
+        )),
+    }
+}
+
+pub(crate) fn crawl_mark_price(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::InverseFuture | MarketType::InverseSwap => {
+            crawl_mark_price_inverse(market_type, symbols, on_msg, duration, ctx)
+        }
+        MarketType::LinearFuture | MarketType::LinearSwap => {
+            crawl_mark_price_linear(market_type, symbols, on_msg, duration, ctx)
+        }
+        _ => Err(CrawlerError::unsupported_message_type(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::MarkPrice,
+        )),
+    }
+}
+
+pub(crate) fn crawl_index_price(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::InverseFuture | MarketType::InverseSwap => {
+            crawl_index_price_inverse(market_type, symbols, on_msg, duration, ctx)
+        }
+        MarketType::LinearFuture | MarketType::LinearSwap => crawl_snapshot(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::IndexPrice,
+            symbols,
+            on_msg,
+            None,
+            duration,
+            ctx,
+        ),
+        _ => Err(CrawlerError::unsupported_message_type(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::IndexPrice,
+        )),
     }
 }
@@ -1,11 +1,11 @@
 use std::sync::{
-    atomic::{AtomicBool, AtomicUsize, Ordering},
+    atomic::{AtomicUsize, Ordering},
     Arc, Mutex,
 };
 use std::time::Duration;
 
-use super::utils::{check_args, fetch_symbols_retry};
-use crate::{msg::Message, MessageType};
+use super::utils::{check_args, crawl_snapshot, fetch_symbols_retry};
+use crate::{handle::Context, msg::Message, CrawlerError, MessageType};
 use crypto_markets::MarketType;
 use crypto_ws_client::*;
 use log::*;
@@ -24,4 +24,34 @@ gen_crawl_event!(crawl_l2_event, BitfinexWSClient, MessageType::L2Event, subscri
 #[rustfmt::skip]
 gen_crawl_event!(crawl_l3_event, BitfinexWSClient, MessageType::L3Event, subscribe_l3_orderbook);
 #[rustfmt::skip]
+gen_crawl_event!(crawl_bbo, BitfinexWSClient, MessageType::BBO, subscribe_bbo);
+#[rustfmt::skip]
 gen_crawl_event!(crawl_ticker, BitfinexWSClient, MessageType::Ticker, subscribe_ticker);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_candlestick, BitfinexWSClient, MessageType::Candlestick, subscribe_candlestick, interval: u32);
+
+pub(crate) fn crawl_funding_rate(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::LinearSwap => crawl_snapshot(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::FundingRate,
+            symbols,
+            on_msg,
+            None,
+            duration,
+            ctx,
+        ),
+        _ => Err(CrawlerError::unsupported_message_type(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::FundingRate,
+        )),
+    }
+}
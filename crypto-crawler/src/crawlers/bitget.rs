@@ -1,12 +1,12 @@
 use std::sync::{
-    atomic::{AtomicBool, AtomicUsize, Ordering},
+    atomic::{AtomicUsize, Ordering},
     Arc, Mutex,
 };
 
 use std::time::Duration;
 
 use super::utils::{check_args, fetch_symbols_retry};
-use crate::{msg::Message, MessageType};
+use crate::{handle::Context, msg::Message, CrawlerError, MessageType};
 use crypto_markets::MarketType;
 use crypto_ws_client::*;
 use log::*;
@@ -22,17 +22,27 @@ gen_crawl_event!(crawl_l2_event_swap, BitgetSwapWSClient, MessageType::L2Event,
 #[rustfmt::skip]
 gen_crawl_event!(crawl_ticker_swap, BitgetSwapWSClient, MessageType::Ticker, subscribe_ticker);
 
+#[rustfmt::skip]
+gen_crawl_event!(crawl_bbo_swap, BitgetSwapWSClient, MessageType::BBO, subscribe_bbo);
+
+#[rustfmt::skip]
+gen_crawl_event!(crawl_candlestick_swap, BitgetSwapWSClient, MessageType::Candlestick, subscribe_candlestick, interval: u32);
+
 pub(crate) fn crawl_trade(
     market_type: MarketType,
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) -> Option<std::thread::JoinHandle<()>> {
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
     match market_type {
         MarketType::InverseSwap | MarketType::LinearSwap => {
-            crawl_trade_swap(market_type, symbols, on_msg, duration)
+            crawl_trade_swap(market_type, symbols, on_msg, duration, ctx)
         }
-        _ => panic!("Bitget does NOT have the {} market type", market_type),
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
     }
 }
 
@@ -41,12 +51,16 @@ pub(crate) fn crawl_l2_event(
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) -> Option<std::thread::JoinHandle<()>> {
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
     match market_type {
         MarketType::InverseSwap | MarketType::LinearSwap => {
-            crawl_l2_event_swap(market_type, symbols, on_msg, duration)
+            crawl_l2_event_swap(market_type, symbols, on_msg, duration, ctx)
         }
-        _ => panic!("Bitget does NOT have the {} market type", market_type),
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
     }
 }
 
@@ -55,12 +69,16 @@ pub(crate) fn crawl_ticker(
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) -> Option<std::thread::JoinHandle<()>> {
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
     match market_type {
         MarketType::InverseSwap | MarketType::LinearSwap => {
-            crawl_ticker_swap(market_type, symbols, on_msg, duration)
+            crawl_ticker_swap(market_type, symbols, on_msg, duration, ctx)
         }
-        _ => panic!("Bitget does NOT have the {} market type", market_type),
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
     }
 }
 
@@ -69,7 +87,8 @@ pub(crate) fn crawl_funding_rate(
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) {
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
     let on_msg_ext = Arc::new(Mutex::new(move |msg: String| {
         let message = Message::new(
             EXCHANGE_NAME.to_string(),
@@ -96,10 +115,52 @@ pub(crate) fn crawl_funding_rate(
 
     match market_type {
         MarketType::InverseSwap | MarketType::LinearSwap => {
-            let ws_client = BitgetSwapWSClient::new(on_msg_ext, None);
+            let ws_client = ctx.register(BitgetSwapWSClient::new(on_msg_ext, None));
             ws_client.subscribe(&channels);
             ws_client.run(duration);
+            Ok(())
         }
-        _ => panic!("Bitget {} does NOT have funding rates", market_type),
+        _ => Err(CrawlerError::unsupported_message_type(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::FundingRate,
+        )),
+    }
+}
+
+pub(crate) fn crawl_bbo(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::InverseSwap | MarketType::LinearSwap => {
+            crawl_bbo_swap(market_type, symbols, on_msg, duration, ctx)
+        }
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
+    }
+}
+
+pub(crate) fn crawl_candlestick(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    interval: u32,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::InverseSwap | MarketType::LinearSwap => {
+            crawl_candlestick_swap(market_type, symbols, on_msg, duration, interval, ctx)
+        }
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
     }
 }
@@ -1,11 +1,11 @@
 use std::sync::{
-    atomic::{AtomicBool, AtomicUsize, Ordering},
+    atomic::{AtomicUsize, Ordering},
     Arc, Mutex,
 };
 use std::time::Duration;
 
 use super::utils::{check_args, fetch_symbols_retry};
-use crate::{msg::Message, MessageType};
+use crate::{handle::Context, msg::Message, CrawlerError, MessageType};
 use crypto_markets::MarketType;
 use crypto_ws_client::*;
 use log::*;
@@ -19,13 +19,28 @@ const MAX_SUBSCRIPTIONS_PER_CONNECTION: usize = 40;
 gen_crawl_event!(crawl_trade, BitmexWSClient, MessageType::Trade, subscribe_trade);
 #[rustfmt::skip]
 gen_crawl_event!(crawl_l2_event, BitmexWSClient, MessageType::L2Event, subscribe_orderbook);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_bbo, BitmexWSClient, MessageType::BBO, subscribe_bbo);
+
+#[rustfmt::skip]
+gen_crawl_event!(crawl_candlestick, BitmexWSClient, MessageType::Candlestick, subscribe_candlestick, interval: u32);
+
+#[rustfmt::skip]
+gen_crawl_event!(crawl_open_interest, BitmexWSClient, MessageType::OpenInterest, subscribe_open_interest);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_liquidation, BitmexWSClient, MessageType::Liquidation, subscribe_liquidation);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_mark_price, BitmexWSClient, MessageType::MarkPrice, subscribe_mark_price);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_index_price, BitmexWSClient, MessageType::IndexPrice, subscribe_index_price);
 
 pub(crate) fn crawl_funding_rate(
     market_type: MarketType,
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) {
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
     let on_msg_ext = Arc::new(Mutex::new(move |msg: String| {
         let message = Message::new(
             EXCHANGE_NAME.to_string(),
@@ -48,10 +63,15 @@ pub(crate) fn crawl_funding_rate(
 
     match market_type {
         MarketType::InverseSwap | MarketType::QuantoSwap => {
-            let ws_client = BitmexWSClient::new(on_msg_ext, None);
+            let ws_client = ctx.register(BitmexWSClient::new(on_msg_ext, None));
             ws_client.subscribe(&channels);
             ws_client.run(duration);
+            Ok(())
         }
-        _ => panic!("BitMEX {} does NOT have funding rates", market_type),
+        _ => Err(CrawlerError::unsupported_message_type(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::FundingRate,
+        )),
     }
 }
@@ -1,11 +1,11 @@
 use std::sync::{
-    atomic::{AtomicBool, AtomicUsize, Ordering},
+    atomic::{AtomicUsize, Ordering},
     Arc, Mutex,
 };
 use std::time::Duration;
 
 use super::utils::{check_args, fetch_symbols_retry};
-use crate::{msg::Message, MessageType};
+use crate::{handle::Context, msg::Message, CrawlerError, MessageType};
 use crypto_markets::MarketType;
 use crypto_ws_client::*;
 use log::*;
@@ -1,12 +1,12 @@
 use std::sync::{
-    atomic::{AtomicBool, AtomicUsize, Ordering},
+    atomic::{AtomicUsize, Ordering},
     Arc, Mutex,
 };
 
 use std::time::Duration;
 
 use super::utils::{check_args, fetch_symbols_retry};
-use crate::{msg::Message, MessageType};
+use crate::{handle::Context, msg::Message, CrawlerError, MessageType};
 use crypto_markets::MarketType;
 use crypto_ws_client::*;
 use log::*;
@@ -22,15 +22,22 @@ gen_crawl_event!(crawl_l2_event_spot, BitzSpotWSClient, MessageType::L2Event, su
 #[rustfmt::skip]
 gen_crawl_event!(crawl_ticker_spot, BitzSpotWSClient, MessageType::Ticker, subscribe_ticker);
 
+#[rustfmt::skip]
+gen_crawl_event!(crawl_candlestick_spot, BitzSpotWSClient, MessageType::Candlestick, subscribe_candlestick, interval: u32);
+
 pub(crate) fn crawl_trade(
     market_type: MarketType,
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) -> Option<std::thread::JoinHandle<()>> {
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
     match market_type {
-        MarketType::Spot => crawl_trade_spot(market_type, symbols, on_msg, duration),
-        _ => panic!("Bitz does NOT have the {} market type", market_type),
+        MarketType::Spot => crawl_trade_spot(market_type, symbols, on_msg, duration, ctx),
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
     }
 }
 
@@ -39,10 +46,14 @@ pub(crate) fn crawl_l2_event(
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) -> Option<std::thread::JoinHandle<()>> {
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
     match market_type {
-        MarketType::Spot => crawl_l2_event_spot(market_type, symbols, on_msg, duration),
-        _ => panic!("Bitz does NOT have the {} market type", market_type),
+        MarketType::Spot => crawl_l2_event_spot(market_type, symbols, on_msg, duration, ctx),
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
     }
 }
 
@@ -51,9 +62,32 @@ pub(crate) fn crawl_ticker(
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) -> Option<std::thread::JoinHandle<()>> {
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::Spot => crawl_ticker_spot(market_type, symbols, on_msg, duration, ctx),
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
+    }
+}
+
+pub(crate) fn crawl_candlestick(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    interval: u32,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
     match market_type {
-        MarketType::Spot => crawl_ticker_spot(market_type, symbols, on_msg, duration),
-        _ => panic!("Bitz does NOT have the {} market type", market_type),
+        MarketType::Spot => {
+            crawl_candlestick_spot(market_type, symbols, on_msg, duration, interval, ctx)
+        }
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
     }
 }
@@ -1,12 +1,12 @@
 use std::sync::{
-    atomic::{AtomicBool, AtomicUsize, Ordering},
+    atomic::{AtomicUsize, Ordering},
     Arc, Mutex,
 };
 
 use std::time::Duration;
 
 use super::utils::{check_args, fetch_symbols_retry};
-use crate::{msg::Message, MessageType};
+use crate::{handle::Context, msg::Message, CrawlerError, MessageType};
 use crypto_markets::MarketType;
 use crypto_ws_client::*;
 use log::*;
@@ -15,6 +15,8 @@ const EXCHANGE_NAME: &str = "bybit";
 // usize::MAX means unlimited
 const MAX_SUBSCRIPTIONS_PER_CONNECTION: usize = usize::MAX;
 
+#[rustfmt::skip]
+gen_crawl_event!(crawl_trade_spot, BybitSpotWSClient, MessageType::Trade, subscribe_trade);
 #[rustfmt::skip]
 gen_crawl_event!(crawl_trade_inverse_future, BybitInverseFutureWSClient, MessageType::Trade, subscribe_trade);
 #[rustfmt::skip]
@@ -22,6 +24,8 @@ gen_crawl_event!(crawl_trade_inverse_swap, BybitInverseSwapWSClient, MessageType
 #[rustfmt::skip]
 gen_crawl_event!(crawl_trade_linear_swap, BybitLinearSwapWSClient, MessageType::Trade, subscribe_trade);
 
+#[rustfmt::skip]
+gen_crawl_event!(crawl_l2_event_spot, BybitSpotWSClient, MessageType::L2Event, subscribe_orderbook);
 #[rustfmt::skip]
 gen_crawl_event!(crawl_l2_event_inverse_future, BybitInverseFutureWSClient, MessageType::L2Event, subscribe_orderbook);
 #[rustfmt::skip]
@@ -29,6 +33,8 @@ gen_crawl_event!(crawl_l2_event_inverse_swap, BybitInverseSwapWSClient, MessageT
 #[rustfmt::skip]
 gen_crawl_event!(crawl_l2_event_linear_swap, BybitLinearSwapWSClient, MessageType::L2Event, subscribe_orderbook);
 
+#[rustfmt::skip]
+gen_crawl_event!(crawl_ticker_spot, BybitSpotWSClient, MessageType::Ticker, subscribe_ticker);
 #[rustfmt::skip]
 gen_crawl_event!(crawl_ticker_inverse_future, BybitInverseFutureWSClient, MessageType::Ticker, subscribe_ticker);
 #[rustfmt::skip]
@@ -36,19 +42,74 @@ gen_crawl_event!(crawl_ticker_inverse_swap, BybitInverseSwapWSClient, MessageTyp
 #[rustfmt::skip]
 gen_crawl_event!(crawl_ticker_linear_swap, BybitLinearSwapWSClient, MessageType::Ticker, subscribe_ticker);
 
+#[rustfmt::skip]
+gen_crawl_event!(crawl_bbo_spot, BybitSpotWSClient, MessageType::BBO, subscribe_bbo);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_bbo_inverse_future, BybitInverseFutureWSClient, MessageType::BBO, subscribe_bbo);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_bbo_inverse_swap, BybitInverseSwapWSClient, MessageType::BBO, subscribe_bbo);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_bbo_linear_swap, BybitLinearSwapWSClient, MessageType::BBO, subscribe_bbo);
+
+#[rustfmt::skip]
+gen_crawl_event!(crawl_candlestick_spot, BybitSpotWSClient, MessageType::Candlestick, subscribe_candlestick, interval: u32);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_candlestick_inverse_future, BybitInverseFutureWSClient, MessageType::Candlestick, subscribe_candlestick, interval: u32);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_candlestick_inverse_swap, BybitInverseSwapWSClient, MessageType::Candlestick, subscribe_candlestick, interval: u32);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_candlestick_linear_swap, BybitLinearSwapWSClient, MessageType::Candlestick, subscribe_candlestick, interval: u32);
+
+#[rustfmt::skip]
+gen_crawl_event!(crawl_open_interest_inverse_future, BybitInverseFutureWSClient, MessageType::OpenInterest, subscribe_open_interest);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_open_interest_inverse_swap, BybitInverseSwapWSClient, MessageType::OpenInterest, subscribe_open_interest);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_open_interest_linear_swap, BybitLinearSwapWSClient, MessageType::OpenInterest, subscribe_open_interest);
+
+#[rustfmt::skip]
+gen_crawl_event!(crawl_liquidation_inverse_future, BybitInverseFutureWSClient, MessageType::Liquidation, subscribe_liquidation);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_liquidation_inverse_swap, BybitInverseSwapWSClient, MessageType::Liquidation, subscribe_liquidation);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_liquidation_linear_swap, BybitLinearSwapWSClient, MessageType::Liquidation, subscribe_liquidation);
+
+#[rustfmt::skip]
+gen_crawl_event!(crawl_mark_price_inverse_future, BybitInverseFutureWSClient, MessageType::MarkPrice, subscribe_mark_price);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_mark_price_inverse_swap, BybitInverseSwapWSClient, MessageType::MarkPrice, subscribe_mark_price);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_mark_price_linear_swap, BybitLinearSwapWSClient, MessageType::MarkPrice, subscribe_mark_price);
+
+#[rustfmt::skip]
+gen_crawl_event!(crawl_index_price_inverse_future, BybitInverseFutureWSClient, MessageType::IndexPrice, subscribe_index_price);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_index_price_inverse_swap, BybitInverseSwapWSClient, MessageType::IndexPrice, subscribe_index_price);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_index_price_linear_swap, BybitLinearSwapWSClient, MessageType::IndexPrice, subscribe_index_price);
+
 pub(crate) fn crawl_trade(
     market_type: MarketType,
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) -> Option<std::thread::JoinHandle<()>> {
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
     match market_type {
+        MarketType::Spot => crawl_trade_spot(market_type, symbols, on_msg, duration, ctx),
         MarketType::InverseFuture => {
-            crawl_trade_inverse_future(market_type, symbols, on_msg, duration)
+            crawl_trade_inverse_future(market_type, symbols, on_msg, duration, ctx)
+        }
+        MarketType::InverseSwap => {
+            crawl_trade_inverse_swap(market_type, symbols, on_msg, duration, ctx)
+        }
+        MarketType::LinearSwap => {
+            crawl_trade_linear_swap(market_type, symbols, on_msg, duration, ctx)
         }
-        MarketType::InverseSwap => crawl_trade_inverse_swap(market_type, symbols, on_msg, duration),
-        MarketType::LinearSwap => crawl_trade_linear_swap(market_type, symbols, on_msg, duration),
-        _ => panic!("Bybit does NOT have the {} market type", market_type),
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
     }
 }
 
@@ -57,18 +118,23 @@ pub(crate) fn crawl_l2_event(
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) -> Option<std::thread::JoinHandle<()>> {
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
     match market_type {
+        MarketType::Spot => crawl_l2_event_spot(market_type, symbols, on_msg, duration, ctx),
         MarketType::InverseFuture => {
-            crawl_l2_event_inverse_future(market_type, symbols, on_msg, duration)
+            crawl_l2_event_inverse_future(market_type, symbols, on_msg, duration, ctx)
         }
         MarketType::InverseSwap => {
-            crawl_l2_event_inverse_swap(market_type, symbols, on_msg, duration)
+            crawl_l2_event_inverse_swap(market_type, symbols, on_msg, duration, ctx)
         }
         MarketType::LinearSwap => {
-            crawl_l2_event_linear_swap(market_type, symbols, on_msg, duration)
+            crawl_l2_event_linear_swap(market_type, symbols, on_msg, duration, ctx)
         }
-        _ => panic!("Bybit does NOT have the {} market type", market_type),
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
     }
 }
 
@@ -77,15 +143,223 @@ pub(crate) fn crawl_ticker(
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) -> Option<std::thread::JoinHandle<()>> {
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::Spot => crawl_ticker_spot(market_type, symbols, on_msg, duration, ctx),
+        MarketType::InverseFuture => {
+            crawl_ticker_inverse_future(market_type, symbols, on_msg, duration, ctx)
+        }
+        MarketType::InverseSwap => {
+            crawl_ticker_inverse_swap(market_type, symbols, on_msg, duration, ctx)
+        }
+        MarketType::LinearSwap => {
+            crawl_ticker_linear_swap(market_type, symbols, on_msg, duration, ctx)
+        }
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
+    }
+}
+
+pub(crate) fn crawl_bbo(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::Spot => crawl_bbo_spot(market_type, symbols, on_msg, duration, ctx),
+        MarketType::InverseFuture => {
+            crawl_bbo_inverse_future(market_type, symbols, on_msg, duration, ctx)
+        }
+        MarketType::InverseSwap => {
+            crawl_bbo_inverse_swap(market_type, symbols, on_msg, duration, ctx)
+        }
+        MarketType::LinearSwap => {
+            crawl_bbo_linear_swap(market_type, symbols, on_msg, duration, ctx)
+        }
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
+    }
+}
+
+pub(crate) fn crawl_candlestick(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    interval: u32,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::Spot => {
+            crawl_candlestick_spot(market_type, symbols, on_msg, duration, interval, ctx)
+        }
+        MarketType::InverseFuture => {
+            crawl_candlestick_inverse_future(market_type, symbols, on_msg, duration, interval, ctx)
+        }
+        MarketType::InverseSwap => {
+            crawl_candlestick_inverse_swap(market_type, symbols, on_msg, duration, interval, ctx)
+        }
+        MarketType::LinearSwap => {
+            crawl_candlestick_linear_swap(market_type, symbols, on_msg, duration, interval, ctx)
+        }
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
+    }
+}
+
+pub(crate) fn crawl_open_interest(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::InverseFuture => {
+            crawl_open_interest_inverse_future(market_type, symbols, on_msg, duration, ctx)
+        }
+        MarketType::InverseSwap => {
+            crawl_open_interest_inverse_swap(market_type, symbols, on_msg, duration, ctx)
+        }
+        MarketType::LinearSwap => {
+            crawl_open_interest_linear_swap(market_type, symbols, on_msg, duration, ctx)
+        }
+        _ => Err(CrawlerError::unsupported_message_type(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::OpenInterest,
+        )),
+    }
+}
+
+pub(crate) fn crawl_liquidation(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
     match market_type {
         MarketType::InverseFuture => {
-            crawl_ticker_inverse_future(market_type, symbols, on_msg, duration)
+            crawl_liquidation_inverse_future(market_type, symbols, on_msg, duration, ctx)
         }
         MarketType::InverseSwap => {
-            crawl_ticker_inverse_swap(market_type, symbols, on_msg, duration)
+            crawl_liquidation_inverse_swap(market_type, symbols, on_msg, duration, ctx)
+        }
+        MarketType::LinearSwap => {
+            crawl_liquidation_linear_swap(market_type, symbols, on_msg, duration, ctx)
+        }
+        _ => Err(CrawlerError::unsupported_message_type(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::Liquidation,
+        )),
+    }
+}
+
+pub(crate) fn crawl_mark_price(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::InverseFuture => {
+            crawl_mark_price_inverse_future(market_type, symbols, on_msg, duration, ctx)
+        }
+        MarketType::InverseSwap => {
+            crawl_mark_price_inverse_swap(market_type, symbols, on_msg, duration, ctx)
+        }
+        MarketType::LinearSwap => {
+            crawl_mark_price_linear_swap(market_type, symbols, on_msg, duration, ctx)
+        }
+        _ => Err(CrawlerError::unsupported_message_type(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::MarkPrice,
+        )),
+    }
+}
+
+pub(crate) fn crawl_index_price(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::InverseFuture => {
+            crawl_index_price_inverse_future(market_type, symbols, on_msg, duration, ctx)
+        }
+        MarketType::InverseSwap => {
+            crawl_index_price_inverse_swap(market_type, symbols, on_msg, duration, ctx)
+        }
+        MarketType::LinearSwap => {
+            crawl_index_price_linear_swap(market_type, symbols, on_msg, duration, ctx)
+        }
+        _ => Err(CrawlerError::unsupported_message_type(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::IndexPrice,
+        )),
+    }
+}
+
+pub(crate) fn crawl_funding_rate(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    let on_msg_ext = Arc::new(Mutex::new(move |msg: String| {
+        let message = Message::new(
+            EXCHANGE_NAME.to_string(),
+            market_type,
+            MessageType::FundingRate,
+            msg,
+        );
+        (on_msg.lock().unwrap())(message);
+    }));
+
+    let symbols: Vec<String> = match symbols {
+        Some(list) if !list.is_empty() => list.to_vec(),
+        _ => fetch_symbols_retry(EXCHANGE_NAME, market_type),
+    };
+    // instrument_info contains funding rates of perpetual swaps
+    let channels: Vec<String> = symbols
+        .into_iter()
+        .map(|symbol| format!("instrument_info.100ms.{}", symbol))
+        .collect();
+
+    match market_type {
+        MarketType::InverseSwap => {
+            let ws_client = ctx.register(BybitInverseSwapWSClient::new(on_msg_ext, None));
+            ws_client.subscribe(&channels);
+            ws_client.run(duration);
+            Ok(())
+        }
+        MarketType::LinearSwap => {
+            let ws_client = ctx.register(BybitLinearSwapWSClient::new(on_msg_ext, None));
+            ws_client.subscribe(&channels);
+            ws_client.run(duration);
+            Ok(())
         }
-        MarketType::LinearSwap => crawl_ticker_linear_swap(market_type, symbols, on_msg, duration),
-        _ => panic!("Bybit does NOT have the {} market type", market_type),
+        _ => Err(CrawlerError::unsupported_message_type(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::FundingRate,
+        )),
     }
 }
@@ -1,12 +1,12 @@
 use std::sync::{
-    atomic::{AtomicBool, AtomicUsize, Ordering},
+    atomic::{AtomicUsize, Ordering},
     Arc, Mutex,
 };
 
 use std::time::Duration;
 
 use super::utils::{check_args, fetch_symbols_retry};
-use crate::{msg::Message, MessageType};
+use crate::{handle::Context, msg::Message, CrawlerError, MessageType};
 use crypto_markets::MarketType;
 use crypto_ws_client::*;
 use log::*;
@@ -18,12 +18,20 @@ const MAX_SUBSCRIPTIONS_PER_CONNECTION: usize = usize::MAX;
 #[rustfmt::skip]
 gen_crawl_event!(crawl_trade_internal, DeribitWSClient, MessageType::Trade, subscribe_trade);
 
+#[rustfmt::skip]
+gen_crawl_event!(crawl_open_interest, DeribitWSClient, MessageType::OpenInterest, subscribe_open_interest);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_mark_price, DeribitWSClient, MessageType::MarkPrice, subscribe_mark_price);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_index_price, DeribitWSClient, MessageType::IndexPrice, subscribe_index_price);
+
 pub(crate) fn crawl_trade(
     market_type: MarketType,
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) -> Option<std::thread::JoinHandle<()>> {
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
     if symbols.is_none() || symbols.unwrap().is_empty() {
         let on_msg_ext = Arc::new(Mutex::new(move |msg: String| {
             let message = Message::new(
@@ -40,22 +48,73 @@ pub(crate) fn crawl_trade(
             MarketType::InverseFuture => vec!["trades.future.any.raw"],
             MarketType::InverseSwap => vec!["trades.BTC-PERPETUAL.raw", "trades.ETH-PERPETUAL.raw"],
             MarketType::EuropeanOption => vec!["trades.option.any.raw"],
-            _ => panic!("Deribit does NOT have the {} market type", market_type),
+            _ => {
+                return Err(CrawlerError::unsupported_market_type(
+                    EXCHANGE_NAME,
+                    market_type,
+                ))
+            }
         }
         .into_iter()
         .map(|x| x.to_string())
         .collect();
 
-        let ws_client = DeribitWSClient::new(on_msg_ext, None);
+        let ws_client = ctx.register(DeribitWSClient::new(on_msg_ext, None));
         ws_client.subscribe(&channels);
         ws_client.run(duration);
-        None
+        Ok(())
     } else {
-        crawl_trade_internal(market_type, symbols, on_msg, duration)
+        crawl_trade_internal(market_type, symbols, on_msg, duration, ctx)
     }
 }
 
 #[rustfmt::skip]
 gen_crawl_event!(crawl_l2_event, DeribitWSClient, MessageType::L2Event, subscribe_orderbook);
 #[rustfmt::skip]
+gen_crawl_event!(crawl_bbo, DeribitWSClient, MessageType::BBO, subscribe_bbo);
+#[rustfmt::skip]
 gen_crawl_event!(crawl_ticker, DeribitWSClient, MessageType::Ticker, subscribe_ticker);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_candlestick, DeribitWSClient, MessageType::Candlestick, subscribe_candlestick, interval: u32);
+
+pub(crate) fn crawl_funding_rate(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    let on_msg_ext = Arc::new(Mutex::new(move |msg: String| {
+        let message = Message::new(
+            EXCHANGE_NAME.to_string(),
+            market_type,
+            MessageType::FundingRate,
+            msg,
+        );
+        (on_msg.lock().unwrap())(message);
+    }));
+
+    let symbols: Vec<String> = match symbols {
+        Some(list) if !list.is_empty() => list.to_vec(),
+        _ => fetch_symbols_retry(EXCHANGE_NAME, market_type),
+    };
+    // ticker contains current_funding and funding_8h of perpetual swaps
+    let channels: Vec<String> = symbols
+        .into_iter()
+        .map(|symbol| format!("ticker.{}.100ms", symbol))
+        .collect();
+
+    match market_type {
+        MarketType::InverseSwap => {
+            let ws_client = ctx.register(DeribitWSClient::new(on_msg_ext, None));
+            ws_client.subscribe(&channels);
+            ws_client.run(duration);
+            Ok(())
+        }
+        _ => Err(CrawlerError::unsupported_message_type(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::FundingRate,
+        )),
+    }
+}
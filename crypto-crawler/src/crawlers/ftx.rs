@@ -1,12 +1,12 @@
 use std::sync::{
-    atomic::{AtomicBool, AtomicUsize, Ordering},
+    atomic::{AtomicUsize, Ordering},
     Arc, Mutex,
 };
 
 use std::time::Duration;
 
-use super::utils::{check_args, fetch_symbols_retry};
-use crate::{msg::Message, MessageType};
+use super::utils::{check_args, crawl_snapshot, fetch_symbols_retry};
+use crate::{handle::Context, msg::Message, CrawlerError, MessageType};
 use crypto_markets::MarketType;
 use crypto_ws_client::*;
 use log::*;
@@ -19,3 +19,109 @@ const MAX_SUBSCRIPTIONS_PER_CONNECTION: usize = usize::MAX;
 gen_crawl_event!(crawl_trade, FtxWSClient, MessageType::Trade, subscribe_trade);
 #[rustfmt::skip]
 gen_crawl_event!(crawl_l2_event, FtxWSClient, MessageType::L2Event, subscribe_orderbook);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_bbo, FtxWSClient, MessageType::BBO, subscribe_bbo);
+
+pub(crate) fn crawl_open_interest(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::LinearFuture | MarketType::LinearSwap | MarketType::Move => crawl_snapshot(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::OpenInterest,
+            symbols,
+            on_msg,
+            None,
+            duration,
+            ctx,
+        ),
+        _ => Err(CrawlerError::unsupported_message_type(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::OpenInterest,
+        )),
+    }
+}
+
+pub(crate) fn crawl_mark_price(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::LinearFuture | MarketType::LinearSwap | MarketType::Move => crawl_snapshot(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::MarkPrice,
+            symbols,
+            on_msg,
+            None,
+            duration,
+            ctx,
+        ),
+        _ => Err(CrawlerError::unsupported_message_type(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::MarkPrice,
+        )),
+    }
+}
+
+pub(crate) fn crawl_index_price(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::LinearFuture | MarketType::LinearSwap | MarketType::Move => crawl_snapshot(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::IndexPrice,
+            symbols,
+            on_msg,
+            None,
+            duration,
+            ctx,
+        ),
+        _ => Err(CrawlerError::unsupported_message_type(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::IndexPrice,
+        )),
+    }
+}
+
+pub(crate) fn crawl_funding_rate(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::LinearSwap => crawl_snapshot(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::FundingRate,
+            symbols,
+            on_msg,
+            None,
+            duration,
+            ctx,
+        ),
+        _ => Err(CrawlerError::unsupported_message_type(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::FundingRate,
+        )),
+    }
+}
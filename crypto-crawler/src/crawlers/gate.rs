@@ -1,12 +1,12 @@
 use std::sync::{
-    atomic::{AtomicBool, AtomicUsize, Ordering},
+    atomic::{AtomicUsize, Ordering},
     Arc, Mutex,
 };
 
 use std::time::Duration;
 
-use super::utils::{check_args, fetch_symbols_retry};
-use crate::{msg::Message, MessageType};
+use super::utils::{check_args, crawl_snapshot, fetch_symbols_retry};
+use crate::{handle::Context, msg::Message, CrawlerError, MessageType};
 use crypto_markets::MarketType;
 use crypto_ws_client::*;
 use log::*;
@@ -40,20 +40,37 @@ gen_crawl_event!(crawl_ticker_linear_swap, GateLinearSwapWSClient, MessageType::
 #[rustfmt::skip]
 gen_crawl_event!(crawl_ticker_linear_future, GateLinearFutureWSClient, MessageType::Ticker, subscribe_ticker);
 
+#[rustfmt::skip]
+gen_crawl_event!(crawl_candlestick_spot, GateSpotWSClient, MessageType::Candlestick, subscribe_candlestick, interval: u32);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_candlestick_inverse_swap, GateInverseSwapWSClient, MessageType::Candlestick, subscribe_candlestick, interval: u32);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_candlestick_linear_swap, GateLinearSwapWSClient, MessageType::Candlestick, subscribe_candlestick, interval: u32);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_candlestick_linear_future, GateLinearFutureWSClient, MessageType::Candlestick, subscribe_candlestick, interval: u32);
+
 pub(crate) fn crawl_trade(
     market_type: MarketType,
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) -> Option<std::thread::JoinHandle<()>> {
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
     match market_type {
-        MarketType::Spot => crawl_trade_spot(market_type, symbols, on_msg, duration),
-        MarketType::InverseSwap => crawl_trade_inverse_swap(market_type, symbols, on_msg, duration),
-        MarketType::LinearSwap => crawl_trade_linear_swap(market_type, symbols, on_msg, duration),
+        MarketType::Spot => crawl_trade_spot(market_type, symbols, on_msg, duration, ctx),
+        MarketType::InverseSwap => {
+            crawl_trade_inverse_swap(market_type, symbols, on_msg, duration, ctx)
+        }
+        MarketType::LinearSwap => {
+            crawl_trade_linear_swap(market_type, symbols, on_msg, duration, ctx)
+        }
         MarketType::LinearFuture => {
-            crawl_trade_linear_future(market_type, symbols, on_msg, duration)
+            crawl_trade_linear_future(market_type, symbols, on_msg, duration, ctx)
         }
-        _ => panic!("Gate does NOT have the {} market type", market_type),
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
     }
 }
 
@@ -62,19 +79,23 @@ pub(crate) fn crawl_l2_event(
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) -> Option<std::thread::JoinHandle<()>> {
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
     match market_type {
-        MarketType::Spot => crawl_l2_event_spot(market_type, symbols, on_msg, duration),
+        MarketType::Spot => crawl_l2_event_spot(market_type, symbols, on_msg, duration, ctx),
         MarketType::InverseSwap => {
-            crawl_l2_event_inverse_swap(market_type, symbols, on_msg, duration)
+            crawl_l2_event_inverse_swap(market_type, symbols, on_msg, duration, ctx)
         }
         MarketType::LinearSwap => {
-            crawl_l2_event_linear_swap(market_type, symbols, on_msg, duration)
+            crawl_l2_event_linear_swap(market_type, symbols, on_msg, duration, ctx)
         }
         MarketType::LinearFuture => {
-            crawl_l2_event_linear_future(market_type, symbols, on_msg, duration)
+            crawl_l2_event_linear_future(market_type, symbols, on_msg, duration, ctx)
         }
-        _ => panic!("Gate does NOT have the {} market type", market_type),
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
     }
 }
 
@@ -83,16 +104,76 @@ pub(crate) fn crawl_ticker(
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) -> Option<std::thread::JoinHandle<()>> {
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::Spot => crawl_ticker_spot(market_type, symbols, on_msg, duration, ctx),
+        MarketType::InverseSwap => {
+            crawl_ticker_inverse_swap(market_type, symbols, on_msg, duration, ctx)
+        }
+        MarketType::LinearSwap => {
+            crawl_ticker_linear_swap(market_type, symbols, on_msg, duration, ctx)
+        }
+        MarketType::LinearFuture => {
+            crawl_ticker_linear_future(market_type, symbols, on_msg, duration, ctx)
+        }
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
+    }
+}
+
+pub(crate) fn crawl_candlestick(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    interval: u32,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
     match market_type {
-        MarketType::Spot => crawl_ticker_spot(market_type, symbols, on_msg, duration),
+        MarketType::Spot => {
+            crawl_candlestick_spot(market_type, symbols, on_msg, duration, interval, ctx)
+        }
         MarketType::InverseSwap => {
-            crawl_ticker_inverse_swap(market_type, symbols, on_msg, duration)
+            crawl_candlestick_inverse_swap(market_type, symbols, on_msg, duration, interval, ctx)
+        }
+        MarketType::LinearSwap => {
+            crawl_candlestick_linear_swap(market_type, symbols, on_msg, duration, interval, ctx)
         }
-        MarketType::LinearSwap => crawl_ticker_linear_swap(market_type, symbols, on_msg, duration),
         MarketType::LinearFuture => {
-            crawl_ticker_linear_future(market_type, symbols, on_msg, duration)
+            crawl_candlestick_linear_future(market_type, symbols, on_msg, duration, interval, ctx)
         }
-        _ => panic!("Gate does NOT have the {} market type", market_type),
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
+    }
+}
+
+pub(crate) fn crawl_funding_rate(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::InverseSwap | MarketType::LinearSwap => crawl_snapshot(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::FundingRate,
+            symbols,
+            on_msg,
+            None,
+            duration,
+            ctx,
+        ),
+        _ => Err(CrawlerError::unsupported_message_type(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::FundingRate,
+        )),
     }
 }
@@ -0,0 +1,24 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+};
+use std::time::Duration;
+
+use super::utils::{check_args, fetch_symbols_retry};
+use crate::{handle::Context, msg::Message, CrawlerError, MessageType};
+use crypto_markets::MarketType;
+use crypto_ws_client::*;
+use log::*;
+
+const EXCHANGE_NAME: &str = "gemini";
+// usize::MAX means unlimited
+const MAX_SUBSCRIPTIONS_PER_CONNECTION: usize = usize::MAX;
+
+// Trades and orderbook updates share the l2 subscription, so messages of both
+// crawlers contain trades as well as orderbook updates
+#[rustfmt::skip]
+gen_crawl_event!(crawl_trade, GeminiWSClient, MessageType::Trade, subscribe_trade);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_l2_event, GeminiWSClient, MessageType::L2Event, subscribe_orderbook);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_candlestick, GeminiWSClient, MessageType::Candlestick, subscribe_candlestick, interval: u32);
@@ -1,12 +1,12 @@
 use std::sync::{
-    atomic::{AtomicBool, AtomicUsize, Ordering},
+    atomic::{AtomicUsize, Ordering},
     Arc, Mutex,
 };
 
 use std::time::Duration;
 
-use super::utils::{check_args, fetch_symbols_retry};
-use crate::{msg::Message, MessageType};
+use super::utils::{check_args, crawl_snapshot, fetch_symbols_retry};
+use crate::{handle::Context, msg::Message, CrawlerError, MessageType};
 use crypto_markets::MarketType;
 use crypto_ws_client::*;
 use log::*;
@@ -46,21 +46,60 @@ gen_crawl_event!(crawl_ticker_inverse_swap, HuobiInverseSwapWSClient, MessageTyp
 #[rustfmt::skip]
 gen_crawl_event!(crawl_ticker_option, HuobiOptionWSClient, MessageType::Ticker, subscribe_ticker);
 
+#[rustfmt::skip]
+gen_crawl_event!(crawl_bbo_spot, HuobiSpotWSClient, MessageType::BBO, subscribe_bbo);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_bbo_inverse_future, HuobiFutureWSClient, MessageType::BBO, subscribe_bbo);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_bbo_linear_swap, HuobiLinearSwapWSClient, MessageType::BBO, subscribe_bbo);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_bbo_inverse_swap, HuobiInverseSwapWSClient, MessageType::BBO, subscribe_bbo);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_bbo_option, HuobiOptionWSClient, MessageType::BBO, subscribe_bbo);
+
+#[rustfmt::skip]
+gen_crawl_event!(crawl_candlestick_spot, HuobiSpotWSClient, MessageType::Candlestick, subscribe_candlestick, interval: u32);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_candlestick_inverse_future, HuobiFutureWSClient, MessageType::Candlestick, subscribe_candlestick, interval: u32);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_candlestick_linear_swap, HuobiLinearSwapWSClient, MessageType::Candlestick, subscribe_candlestick, interval: u32);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_candlestick_inverse_swap, HuobiInverseSwapWSClient, MessageType::Candlestick, subscribe_candlestick, interval: u32);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_candlestick_option, HuobiOptionWSClient, MessageType::Candlestick, subscribe_candlestick, interval: u32);
+
+#[rustfmt::skip]
+gen_crawl_event!(crawl_mark_price_inverse_future, HuobiFutureWSClient, MessageType::MarkPrice, subscribe_mark_price);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_mark_price_inverse_swap, HuobiInverseSwapWSClient, MessageType::MarkPrice, subscribe_mark_price);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_mark_price_linear_swap, HuobiLinearSwapWSClient, MessageType::MarkPrice, subscribe_mark_price);
+
 pub(crate) fn crawl_trade(
     market_type: MarketType,
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) -> Option<std::thread::JoinHandle<()>> {
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
     match market_type {
-        MarketType::Spot => crawl_trade_spot(market_type, symbols, on_msg, duration),
+        MarketType::Spot => crawl_trade_spot(market_type, symbols, on_msg, duration, ctx),
         MarketType::InverseFuture => {
-            crawl_trade_inverse_future(market_type, symbols, on_msg, duration)
+            crawl_trade_inverse_future(market_type, symbols, on_msg, duration, ctx)
+        }
+        MarketType::LinearSwap => {
+            crawl_trade_linear_swap(market_type, symbols, on_msg, duration, ctx)
+        }
+        MarketType::InverseSwap => {
+            crawl_trade_inverse_swap(market_type, symbols, on_msg, duration, ctx)
+        }
+        MarketType::EuropeanOption => {
+            crawl_trade_option(market_type, symbols, on_msg, duration, ctx)
         }
-        MarketType::LinearSwap => crawl_trade_linear_swap(market_type, symbols, on_msg, duration),
-        MarketType::InverseSwap => crawl_trade_inverse_swap(market_type, symbols, on_msg, duration),
-        MarketType::EuropeanOption => crawl_trade_option(market_type, symbols, on_msg, duration),
-        _ => panic!("Huobi does NOT have the {} market type", market_type),
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
     }
 }
 
@@ -69,10 +108,11 @@ pub(crate) fn crawl_l2_event(
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) -> Option<std::thread::JoinHandle<()>> {
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
     match market_type {
         MarketType::Spot => {
-            let on_msg_ext = |msg: String| {
+            let on_msg_ext = move |msg: String| {
                 let message = Message::new(
                     EXCHANGE_NAME.to_string(),
                     market_type,
@@ -88,25 +128,30 @@ pub(crate) fn crawl_l2_event(
             };
             // Huobi Spot market.$symbol.mbp.$levels must use wss://api.huobi.pro/feed
             // or wss://api-aws.huobi.pro/feed
-            let ws_client = HuobiSpotWSClient::new(
+            let ws_client = ctx.register(HuobiSpotWSClient::new(
                 Arc::new(Mutex::new(on_msg_ext)),
                 Some("wss://api.huobi.pro/feed"),
-            );
-            ws_client.subscribe_orderbook(&symbols);
+            ));
+            ws_client.subscribe_orderbook(&symbols)?;
             ws_client.run(duration);
-            None
+            Ok(())
         }
         MarketType::InverseFuture => {
-            crawl_l2_event_inverse_future(market_type, symbols, on_msg, duration)
+            crawl_l2_event_inverse_future(market_type, symbols, on_msg, duration, ctx)
         }
         MarketType::LinearSwap => {
-            crawl_l2_event_linear_swap(market_type, symbols, on_msg, duration)
+            crawl_l2_event_linear_swap(market_type, symbols, on_msg, duration, ctx)
         }
         MarketType::InverseSwap => {
-            crawl_l2_event_inverse_swap(market_type, symbols, on_msg, duration)
+            crawl_l2_event_inverse_swap(market_type, symbols, on_msg, duration, ctx)
+        }
+        MarketType::EuropeanOption => {
+            crawl_l2_event_option(market_type, symbols, on_msg, duration, ctx)
         }
-        MarketType::EuropeanOption => crawl_l2_event_option(market_type, symbols, on_msg, duration),
-        _ => panic!("Huobi does NOT have the {} market type", market_type),
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
     }
 }
 
@@ -115,18 +160,26 @@ pub(crate) fn crawl_ticker(
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) -> Option<std::thread::JoinHandle<()>> {
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
     match market_type {
-        MarketType::Spot => crawl_ticker_spot(market_type, symbols, on_msg, duration),
+        MarketType::Spot => crawl_ticker_spot(market_type, symbols, on_msg, duration, ctx),
         MarketType::InverseFuture => {
-            crawl_ticker_inverse_future(market_type, symbols, on_msg, duration)
+            crawl_ticker_inverse_future(market_type, symbols, on_msg, duration, ctx)
+        }
+        MarketType::LinearSwap => {
+            crawl_ticker_linear_swap(market_type, symbols, on_msg, duration, ctx)
         }
-        MarketType::LinearSwap => crawl_ticker_linear_swap(market_type, symbols, on_msg, duration),
         MarketType::InverseSwap => {
-            crawl_ticker_inverse_swap(market_type, symbols, on_msg, duration)
+            crawl_ticker_inverse_swap(market_type, symbols, on_msg, duration, ctx)
+        }
+        MarketType::EuropeanOption => {
+            crawl_ticker_option(market_type, symbols, on_msg, duration, ctx)
         }
-        MarketType::EuropeanOption => crawl_ticker_option(market_type, symbols, on_msg, duration),
-        _ => panic!("Huobi does NOT have the {} market type", market_type),
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
     }
 }
 
@@ -135,7 +188,8 @@ pub(crate) fn crawl_funding_rate(
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) {
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
     let on_msg_ext = Arc::new(Mutex::new(move |msg: String| {
         let message = Message::new(
             EXCHANGE_NAME.to_string(),
@@ -158,21 +212,191 @@ pub(crate) fn crawl_funding_rate(
 
     match market_type {
         MarketType::InverseSwap => {
-            let ws_client = HuobiInverseSwapWSClient::new(
+            let ws_client = ctx.register(HuobiInverseSwapWSClient::new(
                 on_msg_ext,
                 Some("wss://api.hbdm.com/swap-notification"),
-            );
+            ));
             ws_client.subscribe(&channels);
             ws_client.run(duration);
+            Ok(())
         }
         MarketType::LinearSwap => {
-            let ws_client = HuobiLinearSwapWSClient::new(
+            let ws_client = ctx.register(HuobiLinearSwapWSClient::new(
                 on_msg_ext,
                 Some("wss://api.hbdm.com/linear-swap-notification"),
-            );
+            ));
             ws_client.subscribe(&channels);
             ws_client.run(duration);
+            Ok(())
+        }
+        _ => Err(CrawlerError::unsupported_message_type(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::FundingRate,
+        )),
+    }
+}
+
+pub(crate) fn crawl_bbo(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::Spot => crawl_bbo_spot(market_type, symbols, on_msg, duration, ctx),
+        MarketType::InverseFuture => {
+            crawl_bbo_inverse_future(market_type, symbols, on_msg, duration, ctx)
+        }
+        MarketType::LinearSwap => {
+            crawl_bbo_linear_swap(market_type, symbols, on_msg, duration, ctx)
+        }
+        MarketType::InverseSwap => {
+            crawl_bbo_inverse_swap(market_type, symbols, on_msg, duration, ctx)
+        }
+        MarketType::EuropeanOption => crawl_bbo_option(market_type, symbols, on_msg, duration, ctx),
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
+    }
+}
+
+pub(crate) fn crawl_candlestick(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    interval: u32,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::Spot => {
+            crawl_candlestick_spot(market_type, symbols, on_msg, duration, interval, ctx)
+        }
+        MarketType::InverseFuture => {
+            crawl_candlestick_inverse_future(market_type, symbols, on_msg, duration, interval, ctx)
+        }
+        MarketType::LinearSwap => {
+            crawl_candlestick_linear_swap(market_type, symbols, on_msg, duration, interval, ctx)
+        }
+        MarketType::InverseSwap => {
+            crawl_candlestick_inverse_swap(market_type, symbols, on_msg, duration, interval, ctx)
+        }
+        MarketType::EuropeanOption => {
+            crawl_candlestick_option(market_type, symbols, on_msg, duration, interval, ctx)
+        }
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
+    }
+}
+
+pub(crate) fn crawl_open_interest(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::InverseFuture | MarketType::LinearSwap | MarketType::InverseSwap => {
+            crawl_snapshot(
+                EXCHANGE_NAME,
+                market_type,
+                MessageType::OpenInterest,
+                symbols,
+                on_msg,
+                None,
+                duration,
+                ctx,
+            )
+        }
+        _ => Err(CrawlerError::unsupported_message_type(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::OpenInterest,
+        )),
+    }
+}
+
+pub(crate) fn crawl_liquidation(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::LinearSwap | MarketType::InverseSwap => crawl_snapshot(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::Liquidation,
+            symbols,
+            on_msg,
+            None,
+            duration,
+            ctx,
+        ),
+        _ => Err(CrawlerError::unsupported_message_type(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::Liquidation,
+        )),
+    }
+}
+
+pub(crate) fn crawl_mark_price(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::InverseFuture => {
+            crawl_mark_price_inverse_future(market_type, symbols, on_msg, duration, ctx)
+        }
+        MarketType::InverseSwap => {
+            crawl_mark_price_inverse_swap(market_type, symbols, on_msg, duration, ctx)
         }
-        _ => panic!("Huobi {} does NOT have funding rates", market_type),
+        MarketType::LinearSwap => {
+            crawl_mark_price_linear_swap(market_type, symbols, on_msg, duration, ctx)
+        }
+        _ => Err(CrawlerError::unsupported_message_type(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::MarkPrice,
+        )),
+    }
+}
+
+pub(crate) fn crawl_index_price(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::InverseFuture | MarketType::LinearSwap | MarketType::InverseSwap => {
+            crawl_snapshot(
+                EXCHANGE_NAME,
+                market_type,
+                MessageType::IndexPrice,
+                symbols,
+                on_msg,
+                None,
+                duration,
+                ctx,
+            )
+        }
+        _ => Err(CrawlerError::unsupported_message_type(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::IndexPrice,
+        )),
     }
 }
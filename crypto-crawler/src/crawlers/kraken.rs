@@ -1,11 +1,11 @@
 use std::sync::{
-    atomic::{AtomicBool, AtomicUsize, Ordering},
+    atomic::{AtomicUsize, Ordering},
     Arc, Mutex,
 };
 use std::time::Duration;
 
 use super::utils::{check_args, fetch_symbols_retry};
-use crate::{msg::Message, MessageType};
+use crate::{handle::Context, msg::Message, CrawlerError, MessageType};
 use crypto_markets::MarketType;
 use crypto_ws_client::*;
 use log::*;
@@ -15,8 +15,126 @@ const EXCHANGE_NAME: &str = "kraken";
 const MAX_SUBSCRIPTIONS_PER_CONNECTION: usize = usize::MAX;
 
 #[rustfmt::skip]
-gen_crawl_event!(crawl_trade, KrakenWSClient, MessageType::Trade, subscribe_trade);
+gen_crawl_event!(crawl_trade_spot, KrakenWSClient, MessageType::Trade, subscribe_trade);
 #[rustfmt::skip]
-gen_crawl_event!(crawl_l2_event, KrakenWSClient, MessageType::L2Event, subscribe_orderbook);
+gen_crawl_event!(crawl_trade_futures, KrakenFuturesWSClient, MessageType::Trade, subscribe_trade);
+
+#[rustfmt::skip]
+gen_crawl_event!(crawl_l2_event_spot, KrakenWSClient, MessageType::L2Event, subscribe_orderbook);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_l2_event_futures, KrakenFuturesWSClient, MessageType::L2Event, subscribe_orderbook);
+
+#[rustfmt::skip]
+gen_crawl_event!(crawl_ticker_spot, KrakenWSClient, MessageType::Ticker, subscribe_ticker);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_ticker_futures, KrakenFuturesWSClient, MessageType::Ticker, subscribe_ticker);
+
+#[rustfmt::skip]
+gen_crawl_event!(crawl_bbo_spot, KrakenWSClient, MessageType::BBO, subscribe_bbo);
 #[rustfmt::skip]
-gen_crawl_event!(crawl_ticker, KrakenWSClient, MessageType::Ticker, subscribe_ticker);
+gen_crawl_event!(crawl_bbo_futures, KrakenFuturesWSClient, MessageType::BBO, subscribe_bbo);
+
+#[rustfmt::skip]
+gen_crawl_event!(crawl_candlestick_spot, KrakenWSClient, MessageType::Candlestick, subscribe_candlestick, interval: u32);
+
+pub(crate) fn crawl_trade(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::Spot => crawl_trade_spot(market_type, symbols, on_msg, duration, ctx),
+        MarketType::InverseFuture | MarketType::InverseSwap => {
+            crawl_trade_futures(market_type, symbols, on_msg, duration, ctx)
+        }
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
+    }
+}
+
+pub(crate) fn crawl_l2_event(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::Spot => crawl_l2_event_spot(market_type, symbols, on_msg, duration, ctx),
+        MarketType::InverseFuture | MarketType::InverseSwap => {
+            crawl_l2_event_futures(market_type, symbols, on_msg, duration, ctx)
+        }
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
+    }
+}
+
+pub(crate) fn crawl_ticker(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::Spot => crawl_ticker_spot(market_type, symbols, on_msg, duration, ctx),
+        MarketType::InverseFuture | MarketType::InverseSwap => {
+            crawl_ticker_futures(market_type, symbols, on_msg, duration, ctx)
+        }
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
+    }
+}
+
+pub(crate) fn crawl_bbo(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::Spot => crawl_bbo_spot(market_type, symbols, on_msg, duration, ctx),
+        MarketType::InverseFuture | MarketType::InverseSwap => {
+            crawl_bbo_futures(market_type, symbols, on_msg, duration, ctx)
+        }
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
+    }
+}
+
+pub(crate) fn crawl_candlestick(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    interval: u32,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::Spot => {
+            crawl_candlestick_spot(market_type, symbols, on_msg, duration, interval, ctx)
+        }
+        MarketType::InverseFuture | MarketType::InverseSwap => {
+            Err(CrawlerError::unsupported_message_type(
+                EXCHANGE_NAME,
+                market_type,
+                MessageType::Candlestick,
+            ))
+        }
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
+    }
+}
@@ -1,12 +1,12 @@
 use std::sync::{
-    atomic::{AtomicBool, AtomicUsize, Ordering},
+    atomic::{AtomicUsize, Ordering},
     Arc, Mutex,
 };
 
 use std::time::Duration;
 
-use super::utils::{check_args, fetch_symbols_retry};
-use crate::{msg::Message, MessageType};
+use super::utils::{check_args, crawl_snapshot, fetch_symbols_retry};
+use crate::{handle::Context, msg::Message, CrawlerError, MessageType};
 use crypto_markets::MarketType;
 use crypto_ws_client::*;
 use log::*;
@@ -37,18 +37,38 @@ gen_crawl_event!(crawl_ticker_spot, KuCoinSpotWSClient, MessageType::Ticker, sub
 gen_crawl_event!(crawl_ticker_swap, KuCoinSwapWSClient, MessageType::Ticker, subscribe_ticker);
 #[rustfmt::skip]
 
+#[rustfmt::skip]
+gen_crawl_event!(crawl_bbo_spot, KuCoinSpotWSClient, MessageType::BBO, subscribe_bbo);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_bbo_swap, KuCoinSwapWSClient, MessageType::BBO, subscribe_bbo);
+
+#[rustfmt::skip]
+gen_crawl_event!(crawl_candlestick_spot, KuCoinSpotWSClient, MessageType::Candlestick, subscribe_candlestick, interval: u32);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_candlestick_swap, KuCoinSwapWSClient, MessageType::Candlestick, subscribe_candlestick, interval: u32);
+
+#[rustfmt::skip]
+gen_crawl_event!(crawl_mark_price_swap, KuCoinSwapWSClient, MessageType::MarkPrice, subscribe_mark_price);
+
+#[rustfmt::skip]
+gen_crawl_event!(crawl_index_price_swap, KuCoinSwapWSClient, MessageType::IndexPrice, subscribe_index_price);
+
 pub(crate) fn crawl_trade(
     market_type: MarketType,
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) -> Option<std::thread::JoinHandle<()>> {
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
     match market_type {
-        MarketType::Spot => crawl_trade_spot(market_type, symbols, on_msg, duration),
+        MarketType::Spot => crawl_trade_spot(market_type, symbols, on_msg, duration, ctx),
         MarketType::InverseSwap | MarketType::LinearSwap | MarketType::InverseFuture => {
-            crawl_trade_swap(market_type, symbols, on_msg, duration)
+            crawl_trade_swap(market_type, symbols, on_msg, duration, ctx)
         }
-        _ => panic!("KuCoin does NOT have the {} market type", market_type),
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
     }
 }
 
@@ -57,13 +77,17 @@ pub(crate) fn crawl_l2_event(
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) -> Option<std::thread::JoinHandle<()>> {
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
     match market_type {
-        MarketType::Spot => crawl_l2_event_spot(market_type, symbols, on_msg, duration),
+        MarketType::Spot => crawl_l2_event_spot(market_type, symbols, on_msg, duration, ctx),
         MarketType::InverseSwap | MarketType::LinearSwap | MarketType::InverseFuture => {
-            crawl_l2_event_swap(market_type, symbols, on_msg, duration)
+            crawl_l2_event_swap(market_type, symbols, on_msg, duration, ctx)
         }
-        _ => panic!("KuCoin does NOT have the {} market type", market_type),
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
     }
 }
 
@@ -72,13 +96,17 @@ pub(crate) fn crawl_l3_event(
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) -> Option<std::thread::JoinHandle<()>> {
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
     match market_type {
-        MarketType::Spot => crawl_l3_event_spot(market_type, symbols, on_msg, duration),
+        MarketType::Spot => crawl_l3_event_spot(market_type, symbols, on_msg, duration, ctx),
         MarketType::InverseSwap | MarketType::LinearSwap | MarketType::InverseFuture => {
-            crawl_l3_event_swap(market_type, symbols, on_msg, duration)
+            crawl_l3_event_swap(market_type, symbols, on_msg, duration, ctx)
         }
-        _ => panic!("KuCoin does NOT have the {} market type", market_type),
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
     }
 }
 
@@ -87,12 +115,149 @@ pub(crate) fn crawl_ticker(
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) -> Option<std::thread::JoinHandle<()>> {
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
     match market_type {
-        MarketType::Spot => crawl_ticker_spot(market_type, symbols, on_msg, duration),
+        MarketType::Spot => crawl_ticker_spot(market_type, symbols, on_msg, duration, ctx),
         MarketType::InverseSwap | MarketType::LinearSwap | MarketType::InverseFuture => {
-            crawl_ticker_swap(market_type, symbols, on_msg, duration)
+            crawl_ticker_swap(market_type, symbols, on_msg, duration, ctx)
         }
-        _ => panic!("KuCoin does NOT have the {} market type", market_type),
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
+    }
+}
+
+pub(crate) fn crawl_bbo(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::Spot => crawl_bbo_spot(market_type, symbols, on_msg, duration, ctx),
+        MarketType::InverseSwap | MarketType::LinearSwap | MarketType::InverseFuture => {
+            crawl_bbo_swap(market_type, symbols, on_msg, duration, ctx)
+        }
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
+    }
+}
+
+pub(crate) fn crawl_candlestick(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    interval: u32,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::Spot => {
+            crawl_candlestick_spot(market_type, symbols, on_msg, duration, interval, ctx)
+        }
+        MarketType::InverseSwap | MarketType::LinearSwap | MarketType::InverseFuture => {
+            crawl_candlestick_swap(market_type, symbols, on_msg, duration, interval, ctx)
+        }
+        _ => Err(CrawlerError::unsupported_market_type(
+            EXCHANGE_NAME,
+            market_type,
+        )),
+    }
+}
+
+pub(crate) fn crawl_open_interest(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::InverseSwap | MarketType::LinearSwap | MarketType::InverseFuture => {
+            crawl_snapshot(
+                EXCHANGE_NAME,
+                market_type,
+                MessageType::OpenInterest,
+                symbols,
+                on_msg,
+                None,
+                duration,
+                ctx,
+            )
+        }
+        _ => Err(CrawlerError::unsupported_message_type(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::OpenInterest,
+        )),
+    }
+}
+
+pub(crate) fn crawl_mark_price(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::InverseSwap | MarketType::LinearSwap | MarketType::InverseFuture => {
+            crawl_mark_price_swap(market_type, symbols, on_msg, duration, ctx)
+        }
+        _ => Err(CrawlerError::unsupported_message_type(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::MarkPrice,
+        )),
+    }
+}
+
+pub(crate) fn crawl_index_price(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::InverseSwap | MarketType::LinearSwap | MarketType::InverseFuture => {
+            crawl_index_price_swap(market_type, symbols, on_msg, duration, ctx)
+        }
+        _ => Err(CrawlerError::unsupported_message_type(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::IndexPrice,
+        )),
+    }
+}
+
+pub(crate) fn crawl_funding_rate(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::InverseSwap | MarketType::LinearSwap => crawl_snapshot(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::FundingRate,
+            symbols,
+            on_msg,
+            None,
+            duration,
+            ctx,
+        ),
+        _ => Err(CrawlerError::unsupported_message_type(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::FundingRate,
+        )),
     }
 }
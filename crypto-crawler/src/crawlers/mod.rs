@@ -13,11 +13,14 @@ pub(super) mod coinbase_pro;
 pub(super) mod deribit;
 pub(super) mod ftx;
 pub(super) mod gate;
+pub(super) mod gemini;
 pub(super) mod huobi;
 pub(super) mod kraken;
 pub(super) mod kucoin;
 pub(super) mod mxc;
 pub(super) mod okex;
+pub(super) mod poloniex;
+pub(super) mod upbit;
 pub(super) mod zbg;
 
-pub(super) use utils::crawl_snapshot;
+pub(super) use utils::{check_msg_type, crawl_snapshot, supported_message_types};
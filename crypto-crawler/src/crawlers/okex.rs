@@ -1,12 +1,12 @@
 use std::sync::{
-    atomic::{AtomicBool, AtomicUsize, Ordering},
+    atomic::{AtomicUsize, Ordering},
     Arc, Mutex,
 };
 
 use std::time::Duration;
 
-use super::utils::{check_args, fetch_symbols_retry};
-use crate::{msg::Message, MessageType};
+use super::utils::{check_args, crawl_snapshot, fetch_symbols_retry};
+use crate::{handle::Context, msg::Message, CrawlerError, MessageType};
 use crypto_markets::MarketType;
 use crypto_rest_client::*;
 use crypto_ws_client::*;
@@ -21,12 +21,18 @@ const MAX_SUBSCRIPTIONS_PER_CONNECTION: usize = 256;
 #[rustfmt::skip]
 gen_crawl_event!(crawl_trade_internal, OkexWSClient, MessageType::Trade, subscribe_trade);
 
+#[rustfmt::skip]
+gen_crawl_event!(crawl_mark_price, OkexWSClient, MessageType::MarkPrice, subscribe_mark_price);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_index_price, OkexWSClient, MessageType::IndexPrice, subscribe_index_price);
+
 pub(crate) fn crawl_trade(
     market_type: MarketType,
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) -> Option<std::thread::JoinHandle<()>> {
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
     if market_type == MarketType::EuropeanOption
         && (symbols.is_none() || symbols.unwrap().is_empty())
     {
@@ -47,12 +53,12 @@ pub(crate) fn crawl_trade(
             .map(|x| format!("option/trades:{}", x))
             .collect();
 
-        let ws_client = OkexWSClient::new(on_msg_ext, None);
+        let ws_client = ctx.register(OkexWSClient::new(on_msg_ext, None));
         ws_client.subscribe(&channels);
         ws_client.run(duration);
-        None
+        Ok(())
     } else {
-        crawl_trade_internal(market_type, symbols, on_msg, duration)
+        crawl_trade_internal(market_type, symbols, on_msg, duration, ctx)
     }
 }
 
@@ -62,12 +68,16 @@ gen_crawl_event!(crawl_l2_event, OkexWSClient, MessageType::L2Event, subscribe_o
 #[rustfmt::skip]
 gen_crawl_event!(crawl_ticker, OkexWSClient, MessageType::Ticker, subscribe_ticker);
 
+#[rustfmt::skip]
+gen_crawl_event!(crawl_candlestick, OkexWSClient, MessageType::Candlestick, subscribe_candlestick, interval: u32);
+
 pub(crate) fn crawl_funding_rate(
     market_type: MarketType,
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) {
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
     let on_msg_ext = Arc::new(Mutex::new(move |msg: String| {
         let message = Message::new(
             EXCHANGE_NAME.to_string(),
@@ -90,10 +100,73 @@ pub(crate) fn crawl_funding_rate(
 
     match market_type {
         MarketType::InverseSwap | MarketType::LinearSwap => {
-            let ws_client = OkexWSClient::new(on_msg_ext, None);
+            let ws_client = ctx.register(OkexWSClient::new(on_msg_ext, None));
             ws_client.subscribe(&channels);
             ws_client.run(duration);
+            Ok(())
         }
-        _ => panic!("OKEx {} does NOT have funding rates", market_type),
+        _ => Err(CrawlerError::unsupported_message_type(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::FundingRate,
+        )),
+    }
+}
+
+pub(crate) fn crawl_open_interest(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::InverseFuture
+        | MarketType::LinearFuture
+        | MarketType::InverseSwap
+        | MarketType::LinearSwap => crawl_snapshot(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::OpenInterest,
+            symbols,
+            on_msg,
+            None,
+            duration,
+            ctx,
+        ),
+        _ => Err(CrawlerError::unsupported_message_type(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::OpenInterest,
+        )),
+    }
+}
+
+pub(crate) fn crawl_liquidation(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    match market_type {
+        MarketType::InverseFuture
+        | MarketType::LinearFuture
+        | MarketType::InverseSwap
+        | MarketType::LinearSwap => crawl_snapshot(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::Liquidation,
+            symbols,
+            on_msg,
+            None,
+            duration,
+            ctx,
+        ),
+        _ => Err(CrawlerError::unsupported_message_type(
+            EXCHANGE_NAME,
+            market_type,
+            MessageType::Liquidation,
+        )),
     }
 }
@@ -0,0 +1,24 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+};
+use std::time::Duration;
+
+use super::utils::{check_args, fetch_symbols_retry};
+use crate::{handle::Context, msg::Message, CrawlerError, MessageType};
+use crypto_markets::MarketType;
+use crypto_ws_client::*;
+use log::*;
+
+const EXCHANGE_NAME: &str = "poloniex";
+// usize::MAX means unlimited
+const MAX_SUBSCRIPTIONS_PER_CONNECTION: usize = usize::MAX;
+
+#[rustfmt::skip]
+gen_crawl_event!(crawl_trade, PoloniexWSClient, MessageType::Trade, subscribe_trade);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_l2_event, PoloniexWSClient, MessageType::L2Event, subscribe_orderbook);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_ticker, PoloniexWSClient, MessageType::Ticker, subscribe_ticker);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_candlestick, PoloniexWSClient, MessageType::Candlestick, subscribe_candlestick, interval: u32);
@@ -0,0 +1,99 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+};
+use std::time::Duration;
+
+use super::utils::{check_args, fetch_symbols_retry};
+use crate::{handle::Context, msg::Message, CrawlerError, MessageType};
+use crypto_markets::MarketType;
+use crypto_ws_client::*;
+use log::*;
+
+const EXCHANGE_NAME: &str = "upbit";
+// usize::MAX means unlimited
+const MAX_SUBSCRIPTIONS_PER_CONNECTION: usize = usize::MAX;
+
+#[rustfmt::skip]
+gen_crawl_event!(crawl_trade_internal, UpbitWSClient, MessageType::Trade, subscribe_trade);
+// Every orderbook message of Upbit is a snapshot of top 15 levels
+#[rustfmt::skip]
+gen_crawl_event!(crawl_l2_event_internal, UpbitWSClient, MessageType::L2Event, subscribe_orderbook_snapshot);
+#[rustfmt::skip]
+gen_crawl_event!(crawl_ticker_internal, UpbitWSClient, MessageType::Ticker, subscribe_ticker);
+
+type CrawlFn = fn(
+    MarketType,
+    Option<&[String]>,
+    Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    Option<u64>,
+    &Context,
+) -> Result<(), CrawlerError>;
+
+// Upbit replaces previous subscriptions with every request, so symbols are
+// fetched only once, instead of subscribing new symbols every hour
+fn crawl_fixed_symbols(
+    func: CrawlFn,
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    let symbols = match symbols {
+        Some(list) if !list.is_empty() => list.to_vec(),
+        _ => fetch_symbols_retry(EXCHANGE_NAME, market_type),
+    };
+    func(market_type, Some(&symbols), on_msg, duration, ctx)
+}
+
+pub(crate) fn crawl_trade(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    crawl_fixed_symbols(
+        crawl_trade_internal,
+        market_type,
+        symbols,
+        on_msg,
+        duration,
+        ctx,
+    )
+}
+
+pub(crate) fn crawl_l2_event(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    crawl_fixed_symbols(
+        crawl_l2_event_internal,
+        market_type,
+        symbols,
+        on_msg,
+        duration,
+        ctx,
+    )
+}
+
+pub(crate) fn crawl_ticker(
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
+    crawl_fixed_symbols(
+        crawl_ticker_internal,
+        market_type,
+        symbols,
+        on_msg,
+        duration,
+        ctx,
+    )
+}
@@ -4,10 +4,13 @@ use std::{
 };
 
 use crypto_markets::{fetch_symbols, get_market_types, MarketType};
-use crypto_rest_client::{fetch_l2_snapshot, fetch_l3_snapshot};
+use crypto_rest_client::{
+    fetch_funding_rate, fetch_index_price, fetch_l2_snapshot, fetch_l3_snapshot, fetch_liquidation,
+    fetch_mark_price, fetch_open_interest,
+};
 use log::*;
 
-use crate::{Message, MessageType};
+use crate::{handle::Context, CrawlerError, Message, MessageType};
 
 pub(super) fn fetch_symbols_retry(exchange: &str, market_type: MarketType) -> Vec<String> {
     if std::env::var("https_proxy").is_ok() {
@@ -44,10 +47,14 @@ pub(super) fn fetch_symbols_retry(exchange: &str, market_type: MarketType) -> Ve
     }
 }
 
-pub(super) fn check_args(exchange: &str, market_type: MarketType, symbols: &[String]) {
+pub(super) fn check_args(
+    exchange: &str,
+    market_type: MarketType,
+    symbols: &[String],
+) -> Result<(), CrawlerError> {
     let market_types = get_market_types(exchange);
     if !market_types.contains(&market_type) {
-        panic!("{} does NOT have the {} market type", exchange, market_type);
+        return Err(CrawlerError::unsupported_market_type(exchange, market_type));
     }
 
     let valid_symbols = fetch_symbols_retry(exchange, market_type);
@@ -56,27 +63,189 @@ pub(super) fn check_args(exchange: &str, market_type: MarketType, symbols: &[Str
         .filter(|symbol| !valid_symbols.contains(symbol))
         .cloned()
         .collect();
-    if !invalid_symbols.is_empty() {
-        panic!(
-            "Invalid symbols: {}, {} {} available trading symbols are {}",
-            invalid_symbols.join(","),
-            exchange,
+    if invalid_symbols.is_empty() {
+        Ok(())
+    } else {
+        Err(CrawlerError::InvalidSymbols {
+            exchange: exchange.to_string(),
             market_type,
-            valid_symbols.join(",")
+            symbols: invalid_symbols,
+        })
+    }
+}
+
+const EXCHANGES: &[&str] = &[
+    "binance",
+    "bitfinex",
+    "bitget",
+    "bithumb",
+    "bitmex",
+    "bitstamp",
+    "bitz",
+    "bybit",
+    "coinbase_pro",
+    "deribit",
+    "ftx",
+    "gate",
+    "gemini",
+    "huobi",
+    "kraken",
+    "kucoin",
+    "mxc",
+    "okex",
+    "poloniex",
+    "upbit",
+    "zbg",
+];
+
+pub(crate) fn supported_message_types(exchange: &str, market_type: MarketType) -> Vec<MessageType> {
+    if !EXCHANGES.contains(&exchange) || !get_market_types(exchange).contains(&market_type) {
+        return Vec::new();
+    }
+    // Bitget spot and Bitz swap markets are crawled through RESTful APIs only
+    let websocket = match exchange {
+        "bitget" => market_type != MarketType::Spot,
+        "bitz" => market_type == MarketType::Spot,
+        _ => true,
+    };
+    let level3 = matches!(
+        exchange,
+        "bitfinex" | "bitstamp" | "coinbase_pro" | "kucoin"
+    );
+
+    let mut msg_types = Vec::new();
+    if websocket {
+        msg_types.push(MessageType::Trade);
+        msg_types.push(MessageType::L2Event);
+    }
+    msg_types.push(MessageType::L2Snapshot);
+    if level3 {
+        if websocket {
+            msg_types.push(MessageType::L3Event);
+        }
+        msg_types.push(MessageType::L3Snapshot);
+    }
+    let bbo = websocket
+        && matches!(
+            exchange,
+            "binance"
+                | "bitfinex"
+                | "bitget"
+                | "bitmex"
+                | "bybit"
+                | "deribit"
+                | "ftx"
+                | "huobi"
+                | "kraken"
+                | "kucoin"
         );
+    if bbo {
+        msg_types.push(MessageType::BBO);
+    }
+    let ticker = websocket
+        && match exchange {
+            "bitmex" | "bitstamp" | "ftx" | "gemini" => false,
+            "binance" => market_type != MarketType::EuropeanOption,
+            "mxc" => market_type != MarketType::Spot,
+            _ => true,
+        };
+    if ticker {
+        msg_types.push(MessageType::Ticker);
+    }
+    let candlestick = websocket
+        && match exchange {
+            "bithumb" | "bitstamp" | "coinbase_pro" | "ftx" | "upbit" => false,
+            "kraken" => market_type == MarketType::Spot,
+            _ => true,
+        };
+    if candlestick {
+        msg_types.push(MessageType::Candlestick);
+    }
+    // Bitz and ZBG are out of scope, see crawl_funding_rate()
+    let funding_rate = match exchange {
+        "binance" | "bitget" | "bybit" | "gate" | "huobi" | "kucoin" | "mxc" | "okex" => {
+            market_type == MarketType::InverseSwap || market_type == MarketType::LinearSwap
+        }
+        "bitfinex" | "ftx" => market_type == MarketType::LinearSwap,
+        "bitmex" => market_type == MarketType::InverseSwap || market_type == MarketType::QuantoSwap,
+        "deribit" => market_type == MarketType::InverseSwap,
+        _ => false,
+    };
+    if funding_rate {
+        msg_types.push(MessageType::FundingRate);
+    }
+    let contract = matches!(
+        market_type,
+        MarketType::InverseFuture
+            | MarketType::LinearFuture
+            | MarketType::QuantoFuture
+            | MarketType::InverseSwap
+            | MarketType::LinearSwap
+            | MarketType::QuantoSwap
+    );
+    // Open interest, mark prices and index prices are polled via RESTful APIs
+    // if not pushed via WebSocket
+    let contract_info = match exchange {
+        "binance" | "bitmex" | "bybit" | "huobi" | "kucoin" | "okex" => contract,
+        "deribit" => true,
+        "ftx" => contract || market_type == MarketType::Move,
+        _ => false,
+    };
+    if contract_info {
+        msg_types.push(MessageType::OpenInterest);
     }
+    // Liquidations of Huobi and OKEx are polled via RESTful APIs, Deribit, FTX,
+    // KuCoin and Huobi futures are out of scope, see crawl_liquidation()
+    let liquidation = match exchange {
+        "binance" | "bitmex" | "bybit" | "okex" => contract,
+        "huobi" => market_type == MarketType::InverseSwap || market_type == MarketType::LinearSwap,
+        _ => false,
+    };
+    if liquidation {
+        msg_types.push(MessageType::Liquidation);
+    }
+    if contract_info {
+        msg_types.push(MessageType::MarkPrice);
+        msg_types.push(MessageType::IndexPrice);
+    }
+    msg_types
 }
 
-/// Crawl leve2 or level3 orderbook snapshots through RESTful APIs.
+// Returns an error unless the market of the exchange provides msg_type
+pub(crate) fn check_msg_type(
+    exchange: &str,
+    market_type: MarketType,
+    msg_type: MessageType,
+) -> Result<(), CrawlerError> {
+    if !EXCHANGES.contains(&exchange) {
+        Err(CrawlerError::UnknownExchange(exchange.to_string()))
+    } else if !get_market_types(exchange).contains(&market_type) {
+        Err(CrawlerError::unsupported_market_type(exchange, market_type))
+    } else if !supported_message_types(exchange, market_type).contains(&msg_type) {
+        Err(CrawlerError::unsupported_message_type(
+            exchange,
+            market_type,
+            msg_type,
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Crawl leve2 or level3 orderbook snapshots through RESTful APIs, as well as
+/// funding rates, open interest, liquidations, mark prices and index prices not
+/// pushed via WebSocket.
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn crawl_snapshot(
     exchange: &str,
     market_type: MarketType,
-    msg_type: MessageType, // L2Snapshot or L3Snapshot
+    msg_type: MessageType, // L2Snapshot, L3Snapshot, FundingRate, OpenInterest, Liquidation, MarkPrice or IndexPrice
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     interval: Option<u64>,
     duration: Option<u64>,
-) {
+    ctx: &Context,
+) -> Result<(), CrawlerError> {
     let interval = Duration::from_secs(interval.unwrap_or(60));
     let now = Instant::now();
     loop {
@@ -87,7 +256,7 @@ pub(crate) fn crawl_snapshot(
                 if list.is_empty() {
                     true
                 } else {
-                    check_args(exchange, market_type, &list);
+                    check_args(exchange, market_type, list)?;
                     false
                 }
             }
@@ -101,10 +270,24 @@ pub(crate) fn crawl_snapshot(
         };
 
         for symbol in real_symbols.iter() {
+            if ctx.should_stop() {
+                return Ok(());
+            }
             let resp = match msg_type {
                 MessageType::L2Snapshot => fetch_l2_snapshot(exchange, market_type, symbol),
                 MessageType::L3Snapshot => fetch_l3_snapshot(exchange, market_type, symbol),
-                _ => panic!("msg_type must be L2Snapshot or L3Snapshot"),
+                MessageType::FundingRate => fetch_funding_rate(exchange, market_type, symbol),
+                MessageType::OpenInterest => fetch_open_interest(exchange, market_type, symbol),
+                MessageType::Liquidation => fetch_liquidation(exchange, market_type, symbol),
+                MessageType::MarkPrice => fetch_mark_price(exchange, market_type, symbol),
+                MessageType::IndexPrice => fetch_index_price(exchange, market_type, symbol),
+                _ => {
+                    return Err(CrawlerError::unsupported_message_type(
+                        exchange,
+                        market_type,
+                        msg_type,
+                    ))
+                }
             };
             match resp {
                 Ok(msg) => {
@@ -120,26 +303,33 @@ pub(crate) fn crawl_snapshot(
                 break;
             }
         }
-        if loop_start.elapsed() < interval {
-            std::thread::sleep(interval - loop_start.elapsed());
+        if loop_start.elapsed() < interval && !ctx.sleep(interval - loop_start.elapsed()) {
+            break;
+        }
+        if ctx.should_stop() {
+            break;
         }
     }
+    Ok(())
 }
 
+// `$arg` is passed to `$crawl_func` after symbols, e.g., the interval of candlesticks
 macro_rules! gen_crawl_event {
-    ($func_name:ident, $struct_name:ident, $msg_type:expr, $crawl_func:ident) => {
+    ($func_name:ident, $struct_name:ident, $msg_type:expr, $crawl_func:ident $(, $arg:ident: $arg_ty:ty)?) => {
         pub(crate) fn $func_name(
             market_type: MarketType,
             symbols: Option<&[String]>,
             on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
             duration: Option<u64>,
-        ) -> Option<std::thread::JoinHandle<()>> {
+            $($arg: $arg_ty,)?
+            ctx: &Context,
+        ) -> Result<(), CrawlerError> {
             let is_empty = match symbols {
                 Some(list) => {
                     if list.is_empty() {
                         true
                     } else {
-                        check_args(EXCHANGE_NAME, market_type, &list);
+                        check_args(EXCHANGE_NAME, market_type, list)?;
                         false
                     }
                 }
@@ -153,7 +343,10 @@ macro_rules! gen_crawl_event {
             };
             if real_symbols.is_empty() {
                 error!("real_symbols is empty");
-                panic!("real_symbols is empty");
+                return Err(CrawlerError::NoSymbols {
+                    exchange: EXCHANGE_NAME.to_string(),
+                    market_type,
+                });
             }
 
             let on_msg_ext = Arc::new(Mutex::new(move |msg: String| {
@@ -167,16 +360,17 @@ macro_rules! gen_crawl_event {
             }));
 
             if real_symbols.len() <= MAX_SUBSCRIPTIONS_PER_CONNECTION {
-                let should_stop = Arc::new(AtomicBool::new(false));
-                let ws_client = Arc::new($struct_name::new(on_msg_ext, None));
+                let ws_client = ctx.register($struct_name::new(on_msg_ext, None));
+                ws_client.$crawl_func(&real_symbols $(, $arg)?)?;
 
-                if symbols.is_none() {
-                    let should_stop2 = should_stop.clone();
+                let refresh_thread = if symbols.is_none() {
+                    let ctx2 = ctx.clone();
                     let ws_client2 = ws_client.clone();
 
                     let mut subscribed_symbols = real_symbols.clone();
-                    std::thread::spawn(move || {
-                        while !should_stop2.load(Ordering::Acquire) {
+                    Some(std::thread::spawn(move || {
+                        // update symbols every hour
+                        while ctx2.sleep(Duration::from_secs(3600)) {
                             let latest_symbols = fetch_symbols_retry(EXCHANGE_NAME, market_type);
                             let mut new_symbols: Vec<String> = latest_symbols
                                 .iter()
@@ -186,18 +380,22 @@ macro_rules! gen_crawl_event {
 
                             if !new_symbols.is_empty() {
                                 warn!("Found new symbols: {}", new_symbols.join(", "));
-                                ws_client2.$crawl_func(&new_symbols);
-                                subscribed_symbols.append(&mut new_symbols);
+                                match ws_client2.$crawl_func(&new_symbols $(, $arg)?) {
+                                    Ok(()) => subscribed_symbols.append(&mut new_symbols),
+                                    Err(err) => error!("{}", err),
+                                }
                             }
-                            // update symbols every hour
-                            std::thread::sleep(Duration::from_secs(3600));
                         }
-                    });
-                }
+                    }))
+                } else {
+                    None
+                };
 
-                ws_client.$crawl_func(&real_symbols);
                 ws_client.run(duration);
-                should_stop.store(true, Ordering::Release);
+                ctx.mark_stopped();
+                if let Some(handle) = refresh_thread {
+                    handle.join().unwrap();
+                }
             } else {
                 // split to chunks
                 let mut chunks: Vec<Vec<String>> = Vec::new();
@@ -215,24 +413,23 @@ macro_rules! gen_crawl_event {
 
                 if symbols.is_none() {
                     let num_threads = Arc::new(AtomicUsize::new(chunks.len()));
-                    let last_client = Arc::new($struct_name::new(on_msg_ext.clone(), None));
+                    let last_client = ctx.register($struct_name::new(on_msg_ext.clone(), None));
+                    let mut join_handles: Vec<std::thread::JoinHandle<()>> = Vec::new();
 
                     for chunk in chunks.into_iter() {
-                        let on_msg_ext_clone = on_msg_ext.clone();
-                        let num_threads_clone = num_threads.clone();
-                        let last_client_clone = last_client.clone();
-                        std::thread::spawn(move || {
-                            if chunk.len() < MAX_SUBSCRIPTIONS_PER_CONNECTION {
-                                last_client_clone.$crawl_func(&chunk);
-                                last_client_clone.run(duration);
-                            } else {
-                                let ws_client = $struct_name::new(on_msg_ext_clone, None);
-                                ws_client.$crawl_func(&chunk);
-                                ws_client.run(duration);
-                            }
+                        let ws_client = if chunk.len() < MAX_SUBSCRIPTIONS_PER_CONNECTION {
+                            last_client.clone()
+                        } else {
+                            ctx.register($struct_name::new(on_msg_ext.clone(), None))
+                        };
+                        ws_client.$crawl_func(&chunk $(, $arg)?)?;
 
+                        let num_threads_clone = num_threads.clone();
+                        let handle = std::thread::spawn(move || {
+                            ws_client.run(duration);
                             num_threads_clone.fetch_sub(1, Ordering::SeqCst);
                         });
+                        join_handles.push(handle);
                     }
 
                     let mut subscribed_symbols = real_symbols.clone();
@@ -245,20 +442,26 @@ macro_rules! gen_crawl_event {
                             .collect();
                         if !new_symbols.is_empty() {
                             warn!("Found new symbols: {}", new_symbols.join(", "));
-                            last_client.$crawl_func(&new_symbols);
-                            subscribed_symbols.append(&mut new_symbols);
+                            match last_client.$crawl_func(&new_symbols $(, $arg)?) {
+                                Ok(()) => subscribed_symbols.append(&mut new_symbols),
+                                Err(err) => error!("{}", err),
+                            }
                         }
                         // update symbols every hour
-                        std::thread::sleep(Duration::from_secs(duration.unwrap_or(3600)));
+                        if !ctx.sleep(Duration::from_secs(duration.unwrap_or(3600))) {
+                            break;
+                        }
+                    }
+                    for handle in join_handles {
+                        handle.join().unwrap();
                     }
                 } else {
                     let mut join_handles: Vec<std::thread::JoinHandle<()>> = Vec::new();
 
                     for chunk in chunks.into_iter() {
-                        let on_msg_ext_clone = on_msg_ext.clone();
+                        let ws_client = ctx.register($struct_name::new(on_msg_ext.clone(), None));
+                        ws_client.$crawl_func(&chunk $(, $arg)?)?;
                         let handle = std::thread::spawn(move || {
-                            let ws_client = $struct_name::new(on_msg_ext_clone, None);
-                            ws_client.$crawl_func(&chunk);
                             ws_client.run(duration);
                         });
                         join_handles.push(handle);
@@ -268,7 +471,7 @@ macro_rules! gen_crawl_event {
                     }
                 }
             }
-            None
+            Ok(())
         }
     };
 }
@@ -0,0 +1,120 @@
+use crate::{MarketType, MessageType};
+use crypto_ws_client::WsError;
+
+use std::{any::Any, fmt};
+
+/// Errors returned by `crawl_trade()` and other `crawl_*` functions, or by
+/// `CrawlerHandle::join()` if the crawler failed after it started.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CrawlerError {
+    /// The exchange is not supported by this crate.
+    UnknownExchange(String),
+    /// The exchange doesn't have this market type.
+    UnsupportedMarketType {
+        exchange: String,
+        market_type: MarketType,
+    },
+    /// The market doesn't provide this message type, see `supported_message_types()`.
+    UnsupportedMessageType {
+        exchange: String,
+        market_type: MarketType,
+        msg_type: MessageType,
+    },
+    /// Symbols not listed by the exchange in this market.
+    InvalidSymbols {
+        exchange: String,
+        market_type: MarketType,
+        symbols: Vec<String>,
+    },
+    /// There is nothing to crawl, usually because fetching symbols failed.
+    NoSymbols {
+        exchange: String,
+        market_type: MarketType,
+    },
+    /// The websocket client rejected a subscription.
+    WebSocket(WsError),
+    /// The crawler panicked, with the panic message.
+    Panicked(String),
+}
+
+impl CrawlerError {
+    pub(crate) fn unsupported_market_type(exchange: &str, market_type: MarketType) -> CrawlerError {
+        CrawlerError::UnsupportedMarketType {
+            exchange: exchange.to_string(),
+            market_type,
+        }
+    }
+
+    pub(crate) fn unsupported_message_type(
+        exchange: &str,
+        market_type: MarketType,
+        msg_type: MessageType,
+    ) -> CrawlerError {
+        CrawlerError::UnsupportedMessageType {
+            exchange: exchange.to_string(),
+            market_type,
+            msg_type,
+        }
+    }
+
+    pub(crate) fn panicked(payload: Box<dyn Any + Send>) -> CrawlerError {
+        let msg = if let Some(s) = payload.downcast_ref::<&str>() {
+            s.to_string()
+        } else if let Some(s) = payload.downcast_ref::<String>() {
+            s.clone()
+        } else {
+            "unknown panic".to_string()
+        };
+        CrawlerError::Panicked(msg)
+    }
+}
+
+impl fmt::Display for CrawlerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CrawlerError::UnknownExchange(exchange) => write!(f, "Unknown exchange {}", exchange),
+            CrawlerError::UnsupportedMarketType {
+                exchange,
+                market_type,
+            } => write!(
+                f,
+                "{} does NOT have the {} market type",
+                exchange, market_type
+            ),
+            CrawlerError::UnsupportedMessageType {
+                exchange,
+                market_type,
+                msg_type,
+            } => write!(
+                f,
+                "{} {} does NOT provide {} messages",
+                exchange, market_type, msg_type
+            ),
+            CrawlerError::InvalidSymbols {
+                exchange,
+                market_type,
+                symbols,
+            } => write!(
+                f,
+                "Invalid symbols of {} {}: {}",
+                exchange,
+                market_type,
+                symbols.join(",")
+            ),
+            CrawlerError::NoSymbols {
+                exchange,
+                market_type,
+            } => write!(f, "No symbols to crawl in {} {}", exchange, market_type),
+            CrawlerError::WebSocket(err) => write!(f, "{}", err),
+            CrawlerError::Panicked(msg) => write!(f, "The crawler panicked: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for CrawlerError {}
+
+impl From<WsError> for CrawlerError {
+    fn from(err: WsError) -> Self {
+        CrawlerError::WebSocket(err)
+    }
+}
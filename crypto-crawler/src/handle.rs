@@ -0,0 +1,246 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Arc, Condvar, Mutex,
+    },
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+use crypto_ws_client::WSClient;
+
+use crate::{CrawlerError, Message};
+
+/// Live statistics of a crawler, see `CrawlerHandle::stats()`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CrawlerStats {
+    /// Messages passed to `on_msg`
+    pub messages: u64,
+    /// Websocket clients created so far, zero for RESTful crawlers
+    pub connections: usize,
+    /// Time since the crawler started
+    pub uptime: Duration,
+    /// Time since the last message, None if nothing was received
+    pub last_message_age: Option<Duration>,
+}
+
+type Client = Arc<dyn WSClient<'static> + Send + Sync>;
+
+// State shared by a CrawlerHandle and threads of its crawler
+struct Shared {
+    stopped: Mutex<bool>,
+    // wakes up threads sleeping in Context::sleep()
+    stop_cond: Condvar,
+    running: AtomicBool,
+    // cleared on exit, since clients hold on_msg, which holds Shared
+    clients: Mutex<Vec<Client>>,
+    connections: AtomicUsize,
+    messages: AtomicU64,
+    started: Instant,
+    last_message: Mutex<Option<Instant>>,
+}
+
+// Passed to crawlers, so that their websocket clients and sleeping threads
+// can be stopped from a CrawlerHandle.
+#[derive(Clone)]
+pub(crate) struct Context {
+    shared: Arc<Shared>,
+}
+
+impl Context {
+    fn new() -> Self {
+        Context {
+            shared: Arc::new(Shared {
+                stopped: Mutex::new(false),
+                stop_cond: Condvar::new(),
+                running: AtomicBool::new(true),
+                clients: Mutex::new(Vec::new()),
+                connections: AtomicUsize::new(0),
+                messages: AtomicU64::new(0),
+                started: Instant::now(),
+                last_message: Mutex::new(None),
+            }),
+        }
+    }
+
+    pub fn should_stop(&self) -> bool {
+        *self.shared.stopped.lock().unwrap()
+    }
+
+    // Sleeps until the timeout elapses or the crawler stops, returns false if
+    // the crawler stopped.
+    pub fn sleep(&self, timeout: Duration) -> bool {
+        let guard = self.shared.stopped.lock().unwrap();
+        let (guard, _) = self
+            .shared
+            .stop_cond
+            .wait_timeout_while(guard, timeout, |stopped| !*stopped)
+            .unwrap();
+        !*guard
+    }
+
+    // Takes over a websocket client so that stopping the crawler closes it,
+    // the client is closed right away if the crawler has already stopped.
+    pub fn register<C>(&self, client: C) -> Arc<C>
+    where
+        C: WSClient<'static> + Send + Sync + 'static,
+    {
+        let client = Arc::new(client);
+        self.shared.connections.fetch_add(1, Ordering::Relaxed);
+        let mut clients = self.shared.clients.lock().unwrap();
+        if self.should_stop() {
+            drop(clients);
+            client.close();
+        } else {
+            clients.push(client.clone());
+        }
+        client
+    }
+
+    // Counts messages before passing them to on_msg
+    fn wrap(
+        &self,
+        on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    ) -> Arc<Mutex<dyn FnMut(Message) + 'static + Send>> {
+        let shared = self.shared.clone();
+        Arc::new(Mutex::new(move |msg: Message| {
+            shared.messages.fetch_add(1, Ordering::Relaxed);
+            *shared.last_message.lock().unwrap() = Some(Instant::now());
+            (on_msg.lock().unwrap())(msg);
+        }))
+    }
+
+    // Marks the crawler as stopped and wakes up sleeping threads, returns
+    // false if it was stopped already.
+    pub fn mark_stopped(&self) -> bool {
+        let mut stopped = self.shared.stopped.lock().unwrap();
+        if *stopped {
+            false
+        } else {
+            *stopped = true;
+            self.shared.stop_cond.notify_all();
+            true
+        }
+    }
+
+    // Asks the crawler to stop without waiting, see CrawlerHandle::stop()
+    pub fn stop(&self) {
+        if self.mark_stopped() {
+            let context = self.clone();
+            // close() waits for a pending read, which may take a while
+            std::thread::spawn(move || context.close_clients());
+        }
+    }
+
+    // Closes all websocket clients, which blocks until their run() returns
+    fn close_clients(&self) {
+        let clients: Vec<Client> = self.shared.clients.lock().unwrap().clone();
+        for client in clients.iter() {
+            client.close();
+        }
+    }
+}
+
+// Cleans up after the crawler thread exits, even if it panicked
+struct ExitGuard(Context);
+
+impl Drop for ExitGuard {
+    fn drop(&mut self) {
+        if self.0.mark_stopped() {
+            self.0.close_clients();
+        }
+        self.0.shared.clients.lock().unwrap().clear();
+        self.0.shared.running.store(false, Ordering::Release);
+    }
+}
+
+/// A crawler running in background threads, returned by `crawl_trade()`,
+/// `crawl_l2_event()` and other `crawl_*` functions.
+///
+/// Dropping a handle detaches the crawler, which keeps running until its
+/// duration ends.
+///
+/// ## Example
+///
+/// ```no_run
+/// use std::sync::{Arc, Mutex};
+/// use crypto_crawler::{crawl_trade, MarketType, Message};
+///
+/// let on_msg = Arc::new(Mutex::new(|msg: Message| {
+///     println!("{}", msg);
+/// }));
+///
+/// let handle = crawl_trade("bitmex", MarketType::InverseSwap, None, on_msg, None).unwrap();
+/// std::thread::sleep(std::time::Duration::from_secs(10));
+/// println!("{:?}", handle.stats());
+/// handle.stop();
+/// handle.join().unwrap();
+/// ```
+pub struct CrawlerHandle {
+    context: Context,
+    thread: JoinHandle<Result<(), CrawlerError>>,
+}
+
+impl CrawlerHandle {
+    // Runs `crawl` in a background thread, `on_msg` is wrapped to collect
+    // statistics.
+    pub(crate) fn spawn<F>(
+        on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+        crawl: F,
+    ) -> Self
+    where
+        F: FnOnce(
+                Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+                &Context,
+            ) -> Result<(), CrawlerError>
+            + Send
+            + 'static,
+    {
+        let context = Context::new();
+        let on_msg = context.wrap(on_msg);
+        let guard = ExitGuard(context.clone());
+        let thread = std::thread::spawn(move || {
+            let guard = guard;
+            crawl(on_msg, &guard.0)
+        });
+        CrawlerHandle { context, thread }
+    }
+
+    // Lets callbacks stop the crawler without owning its handle
+    pub(crate) fn context(&self) -> Context {
+        self.context.clone()
+    }
+
+    /// Asks the crawler to stop without waiting, call `join()` to wait.
+    ///
+    /// Websocket clients are closed, symbol-refresh threads and RESTful
+    /// crawlers wake up and exit.
+    pub fn stop(&self) {
+        self.context.stop();
+    }
+
+    /// Waits for the crawler to exit, returns Err if it failed after it
+    /// started, e.g., on invalid symbols.
+    pub fn join(self) -> Result<(), CrawlerError> {
+        self.thread
+            .join()
+            .unwrap_or_else(|err| Err(CrawlerError::panicked(err)))
+    }
+
+    /// Returns false after the crawler exited, because its duration ended,
+    /// it was stopped or it failed.
+    pub fn is_running(&self) -> bool {
+        self.context.shared.running.load(Ordering::Acquire)
+    }
+
+    /// Returns live statistics.
+    pub fn stats(&self) -> CrawlerStats {
+        let shared = &self.context.shared;
+        CrawlerStats {
+            messages: shared.messages.load(Ordering::Relaxed),
+            connections: shared.connections.load(Ordering::Relaxed),
+            uptime: shared.started.elapsed(),
+            last_message_age: shared.last_message.lock().unwrap().map(|x| x.elapsed()),
+        }
+    }
+}
@@ -11,7 +11,7 @@
 //! }));
 //!
 //! // Crawl BitMEX inverse_swap market, for all symbols, only run for 5 seconds
-//! crawl_trade("bitmex", MarketType::InverseSwap, None, on_msg, Some(5));
+//! crawl_trade("bitmex", MarketType::InverseSwap, None, on_msg, Some(5)).unwrap().join().unwrap();
 //! ```
 //!
 //! ## Crawl level2 orderbook update events
@@ -25,7 +25,7 @@
 //! }));
 //!
 //! // Crawl BitMEX inverse_swap market, for all symbols, only run for 5 seconds
-//! crawl_l2_event("bitmex", MarketType::InverseSwap, None, on_msg, Some(5));
+//! crawl_l2_event("bitmex", MarketType::InverseSwap, None, on_msg, Some(5)).unwrap().join().unwrap();
 //! ```
 //!
 //! ## Crawl level2 orderbook snapshots
@@ -39,7 +39,7 @@
 //! }));
 //!
 //! // Crawl BitMEX inverse_swap market level2 orderbook snapshots every 60 seconds, for all symbols, only run for 5 seconds
-//! crawl_l2_snapshot("bitmex", MarketType::InverseSwap, None, on_msg, Some(60), Some(5));
+//! crawl_l2_snapshot("bitmex", MarketType::InverseSwap, None, on_msg, Some(60), Some(5)).unwrap().join().unwrap();
 //! ```
 //!
 //! ## Crawl level3 orderbook update events
@@ -53,7 +53,7 @@
 //! }));
 //!
 //! // Crawl CoinbasePro spot market, for all symbols, only run for 5 seconds
-//! crawl_l3_event("coinbase_pro", MarketType::Spot, None, on_msg, Some(5));
+//! crawl_l3_event("coinbase_pro", MarketType::Spot, None, on_msg, Some(5)).unwrap().join().unwrap();
 //! ```
 //!
 //! ## Crawl level3 orderbook snapshots
@@ -67,16 +67,170 @@
 //! }));
 //!
 //! // Crawl CoinbasePro spot market level2 orderbook snapshots every 60 seconds, for all symbols, only run for 5 seconds
-//! crawl_l3_snapshot("coinbase_pro", MarketType::Spot, None, on_msg, Some(60), Some(5));
+//! crawl_l3_snapshot("coinbase_pro", MarketType::Spot, None, on_msg, Some(60), Some(5)).unwrap().join().unwrap();
+//! ```
+//!
+//! ## Crawl best bid and ask
+//!
+//! ```rust
+//! use std::sync::{Arc, Mutex};
+//! use crypto_crawler::{crawl_bbo, MarketType, Message};
+//!
+//! let on_msg = Arc::new(Mutex::new(|msg: Message| {
+//!     println!("{}", msg);
+//! }));
+//!
+//! // Crawl BitMEX inverse_swap market, for all symbols, only run for 5 seconds
+//! crawl_bbo("bitmex", MarketType::InverseSwap, None, on_msg, Some(5)).unwrap().join().unwrap();
+//! ```
+//!
+//! ## Crawl candlesticks
+//!
+//! ```rust
+//! use std::sync::{Arc, Mutex};
+//! use crypto_crawler::{crawl_candlestick, MarketType, Message};
+//!
+//! let on_msg = Arc::new(Mutex::new(|msg: Message| {
+//!     println!("{}", msg);
+//! }));
+//!
+//! // Crawl 1-minute candlesticks of Binance spot market, for all symbols, only run for 5 seconds
+//! crawl_candlestick("binance", MarketType::Spot, None, on_msg, Some(5), 60).unwrap().join().unwrap();
+//! ```
+//!
+//! ## Crawl open interest
+//!
+//! ```rust
+//! use std::sync::{Arc, Mutex};
+//! use crypto_crawler::{crawl_open_interest, MarketType, Message};
+//!
+//! let on_msg = Arc::new(Mutex::new(|msg: Message| {
+//!     println!("{}", msg);
+//! }));
+//!
+//! // Crawl BitMEX inverse_swap market, for all symbols, only run for 5 seconds
+//! crawl_open_interest("bitmex", MarketType::InverseSwap, None, on_msg, Some(5)).unwrap().join().unwrap();
+//! ```
+//!
+//! ## Stop a crawler
+//!
+//! Every `crawl_*` function runs in background threads and returns a
+//! `CrawlerHandle`.
+//!
+//! ```no_run
+//! use std::sync::{Arc, Mutex};
+//! use crypto_crawler::{crawl_trade, MarketType, Message};
+//!
+//! let on_msg = Arc::new(Mutex::new(|msg: Message| {
+//!     println!("{}", msg);
+//! }));
+//!
+//! let handle = crawl_trade("bitmex", MarketType::InverseSwap, None, on_msg, None).unwrap();
+//! std::thread::sleep(std::time::Duration::from_secs(60));
+//! println!("{:?}", handle.stats());
+//! handle.stop();
+//! handle.join().unwrap();
+//! ```
+//!
+//! ## Handle errors
+//!
+//! `crawl_*` functions return `CrawlerError` for unknown exchanges, market types
+//! and message types, while errors after a crawler started, such as invalid symbols,
+//! are returned by `CrawlerHandle::join()`.
+//!
+//! ```
+//! use std::sync::{Arc, Mutex};
+//! use crypto_crawler::*;
+//!
+//! let on_msg = Arc::new(Mutex::new(|msg: Message| {
+//!     println!("{}", msg);
+//! }));
+//!
+//! // BitMEX doesn't have the ticker channel
+//! assert!(!supported_message_types("bitmex", MarketType::InverseSwap).contains(&MessageType::Ticker));
+//! let err = crawl_ticker("bitmex", MarketType::InverseSwap, None, on_msg, Some(5)).err();
+//! assert!(matches!(err, Some(CrawlerError::UnsupportedMessageType { .. })));
+//! ```
+//!
+//! ## Receive messages from a channel
+//!
+//! ```rust
+//! use crypto_crawler::{crawl_trade, crawl_with_channel, BackpressurePolicy, MarketType};
+//!
+//! // Crawl in a background thread, keep at most 1024 messages and drop the oldest ones
+//! let (handle, receiver) = crawl_with_channel(1024, BackpressurePolicy::DropOldest, |on_msg| {
+//!     crawl_trade("bitmex", MarketType::InverseSwap, None, on_msg, None)
+//! })
+//! .unwrap();
+//! for msg in receiver.iter().take(10) {
+//!     println!("{}", msg);
+//! }
+//! // Dropping the receiver would stop the crawler as well
+//! handle.stop();
+//! handle.join().unwrap();
 //! ```
 mod crawlers;
+mod error;
+mod handle;
 mod msg;
 
 pub use crypto_markets::MarketType;
+use crypto_ws_client::{message_channel, message_stream, MessageSender};
+pub use crypto_ws_client::{BackpressurePolicy, MessageReceiver, MessageStream};
+use handle::Context;
 use std::sync::{Arc, Mutex};
 
+pub use error::CrawlerError;
+pub use handle::{CrawlerHandle, CrawlerStats};
 pub use msg::*;
 
+type CrawlFn = fn(
+    MarketType,
+    Option<&[String]>,
+    Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    Option<u64>,
+    &Context,
+) -> Result<(), CrawlerError>;
+
+// Same as CrawlFn, with the interval of candlesticks
+type CandlestickCrawlFn = fn(
+    MarketType,
+    Option<&[String]>,
+    Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    Option<u64>,
+    u32,
+    &Context,
+) -> Result<(), CrawlerError>;
+
+fn spawn_crawler(
+    func: CrawlFn,
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+) -> CrawlerHandle {
+    let symbols = symbols.map(|list| list.to_vec());
+    CrawlerHandle::spawn(on_msg, move |on_msg, ctx| {
+        func(market_type, symbols.as_deref(), on_msg, duration, ctx)
+    })
+}
+
+/// Returns message types provided by the `market_type` of the `exchange`,
+/// or an empty Vec if the exchange or the market type is unknown.
+///
+/// ## Example
+///
+/// ```
+/// use crypto_crawler::{supported_message_types, MarketType, MessageType};
+///
+/// let msg_types = supported_message_types("bitmex", MarketType::InverseSwap);
+/// assert!(msg_types.contains(&MessageType::FundingRate));
+/// assert!(!msg_types.contains(&MessageType::Ticker));
+/// ```
+pub fn supported_message_types(exchange: &str, market_type: MarketType) -> Vec<MessageType> {
+    crawlers::supported_message_types(exchange, market_type)
+}
+
 /// Crawl realtime trades.
 ///
 /// If `symbols` is None, this function will crawl all trading symbols in the `market_type`,
@@ -87,7 +241,8 @@ pub fn crawl_trade(
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) {
+) -> Result<CrawlerHandle, CrawlerError> {
+    crawlers::check_msg_type(exchange, market_type, MessageType::Trade)?;
     let func = match exchange {
         "binance" => crawlers::binance::crawl_trade,
         "bitfinex" => crawlers::bitfinex::crawl_trade,
@@ -101,18 +256,24 @@ pub fn crawl_trade(
         "deribit" => crawlers::deribit::crawl_trade,
         "ftx" => crawlers::ftx::crawl_trade,
         "gate" => crawlers::gate::crawl_trade,
+        "gemini" => crawlers::gemini::crawl_trade,
         "huobi" => crawlers::huobi::crawl_trade,
         "kraken" => crawlers::kraken::crawl_trade,
         "kucoin" => crawlers::kucoin::crawl_trade,
         "mxc" => crawlers::mxc::crawl_trade,
         "okex" => crawlers::okex::crawl_trade,
+        "poloniex" => crawlers::poloniex::crawl_trade,
+        "upbit" => crawlers::upbit::crawl_trade,
         "zbg" => crawlers::zbg::crawl_trade,
-        _ => panic!("Unknown exchange {}", exchange),
+        _ => {
+            return Err(CrawlerError::unsupported_message_type(
+                exchange,
+                market_type,
+                MessageType::Trade,
+            ))
+        }
     };
-    let handle = func(market_type, symbols, on_msg, duration);
-    if let Some(h) = handle {
-        h.join().expect("The thread panicked");
-    }
+    Ok(spawn_crawler(func, market_type, symbols, on_msg, duration))
 }
 
 /// Crawl level2 orderbook update events.
@@ -122,7 +283,8 @@ pub fn crawl_l2_event(
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) {
+) -> Result<CrawlerHandle, CrawlerError> {
+    crawlers::check_msg_type(exchange, market_type, MessageType::L2Event)?;
     let func = match exchange {
         "binance" => crawlers::binance::crawl_l2_event,
         "bitfinex" => crawlers::bitfinex::crawl_l2_event,
@@ -136,18 +298,24 @@ pub fn crawl_l2_event(
         "deribit" => crawlers::deribit::crawl_l2_event,
         "ftx" => crawlers::ftx::crawl_l2_event,
         "gate" => crawlers::gate::crawl_l2_event,
+        "gemini" => crawlers::gemini::crawl_l2_event,
         "huobi" => crawlers::huobi::crawl_l2_event,
         "kraken" => crawlers::kraken::crawl_l2_event,
         "kucoin" => crawlers::kucoin::crawl_l2_event,
         "mxc" => crawlers::mxc::crawl_l2_event,
         "okex" => crawlers::okex::crawl_l2_event,
+        "poloniex" => crawlers::poloniex::crawl_l2_event,
+        "upbit" => crawlers::upbit::crawl_l2_event,
         "zbg" => crawlers::zbg::crawl_l2_event,
-        _ => panic!("Unknown exchange {}", exchange),
+        _ => {
+            return Err(CrawlerError::unsupported_message_type(
+                exchange,
+                market_type,
+                MessageType::L2Event,
+            ))
+        }
     };
-    let handle = func(market_type, symbols, on_msg, duration);
-    if let Some(h) = handle {
-        h.join().expect("The thread panicked");
-    }
+    Ok(spawn_crawler(func, market_type, symbols, on_msg, duration))
 }
 
 /// Crawl level3 orderbook update events.
@@ -157,19 +325,22 @@ pub fn crawl_l3_event(
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) {
+) -> Result<CrawlerHandle, CrawlerError> {
+    crawlers::check_msg_type(exchange, market_type, MessageType::L3Event)?;
     let func = match exchange {
-        "binance" => panic!("Binance does NOT provide level3 orderbook data"),
         "bitfinex" => crawlers::bitfinex::crawl_l3_event,
         "bitstamp" => crawlers::bitstamp::crawl_l3_event,
         "coinbase_pro" => crawlers::coinbase_pro::crawl_l3_event,
         "kucoin" => crawlers::kucoin::crawl_l3_event,
-        _ => panic!("Unknown exchange {}", exchange),
+        _ => {
+            return Err(CrawlerError::unsupported_message_type(
+                exchange,
+                market_type,
+                MessageType::L3Event,
+            ))
+        }
     };
-    let handle = func(market_type, symbols, on_msg, duration);
-    if let Some(h) = handle {
-        h.join().expect("The thread panicked");
-    }
+    Ok(spawn_crawler(func, market_type, symbols, on_msg, duration))
 }
 
 /// Crawl level2 orderbook snapshots through RESTful APIs.
@@ -180,16 +351,22 @@ pub fn crawl_l2_snapshot(
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     interval: Option<u64>,
     duration: Option<u64>,
-) {
-    crawlers::crawl_snapshot(
-        exchange,
-        market_type,
-        MessageType::L2Snapshot,
-        symbols,
-        on_msg,
-        interval,
-        duration,
-    )
+) -> Result<CrawlerHandle, CrawlerError> {
+    crawlers::check_msg_type(exchange, market_type, MessageType::L2Snapshot)?;
+    let exchange = exchange.to_string();
+    let symbols = symbols.map(|list| list.to_vec());
+    Ok(CrawlerHandle::spawn(on_msg, move |on_msg, ctx| {
+        crawlers::crawl_snapshot(
+            &exchange,
+            market_type,
+            MessageType::L2Snapshot,
+            symbols.as_deref(),
+            on_msg,
+            interval,
+            duration,
+            ctx,
+        )
+    }))
 }
 
 /// Crawl level3 orderbook snapshots through RESTful APIs.
@@ -200,16 +377,22 @@ pub fn crawl_l3_snapshot(
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     interval: Option<u64>,
     duration: Option<u64>,
-) {
-    crawlers::crawl_snapshot(
-        exchange,
-        market_type,
-        MessageType::L3Snapshot,
-        symbols,
-        on_msg,
-        interval,
-        duration,
-    )
+) -> Result<CrawlerHandle, CrawlerError> {
+    crawlers::check_msg_type(exchange, market_type, MessageType::L3Snapshot)?;
+    let exchange = exchange.to_string();
+    let symbols = symbols.map(|list| list.to_vec());
+    Ok(CrawlerHandle::spawn(on_msg, move |on_msg, ctx| {
+        crawlers::crawl_snapshot(
+            &exchange,
+            market_type,
+            MessageType::L3Snapshot,
+            symbols.as_deref(),
+            on_msg,
+            interval,
+            duration,
+            ctx,
+        )
+    }))
 }
 
 /// Crawl 24hr rolling window ticker.
@@ -222,7 +405,8 @@ pub fn crawl_ticker(
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) {
+) -> Result<CrawlerHandle, CrawlerError> {
+    crawlers::check_msg_type(exchange, market_type, MessageType::Ticker)?;
     let func = match exchange {
         "binance" => crawlers::binance::crawl_ticker,
         "bitfinex" => crawlers::bitfinex::crawl_ticker,
@@ -238,30 +422,335 @@ pub fn crawl_ticker(
         "kucoin" => crawlers::kucoin::crawl_ticker,
         "mxc" => crawlers::mxc::crawl_ticker,
         "okex" => crawlers::okex::crawl_ticker,
+        "poloniex" => crawlers::poloniex::crawl_ticker,
+        "upbit" => crawlers::upbit::crawl_ticker,
         "zbg" => crawlers::zbg::crawl_ticker,
-        _ => panic!("Unknown exchange {}", exchange),
+        _ => {
+            return Err(CrawlerError::unsupported_message_type(
+                exchange,
+                market_type,
+                MessageType::Ticker,
+            ))
+        }
     };
-    let handle = func(market_type, symbols, on_msg, duration);
-    if let Some(h) = handle {
-        h.join().expect("The thread panicked");
-    }
+    Ok(spawn_crawler(func, market_type, symbols, on_msg, duration))
+}
+
+/// Crawl best bid and ask.
+///
+/// If `symbols` is None, it means all trading symbols in the `market_type`,
+/// and updates the latest symbols every hour.
+pub fn crawl_bbo(
+    exchange: &str,
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+) -> Result<CrawlerHandle, CrawlerError> {
+    crawlers::check_msg_type(exchange, market_type, MessageType::BBO)?;
+    let func = match exchange {
+        "binance" => crawlers::binance::crawl_bbo,
+        "bitfinex" => crawlers::bitfinex::crawl_bbo,
+        "bitget" => crawlers::bitget::crawl_bbo,
+        "bitmex" => crawlers::bitmex::crawl_bbo,
+        "bybit" => crawlers::bybit::crawl_bbo,
+        "deribit" => crawlers::deribit::crawl_bbo,
+        "ftx" => crawlers::ftx::crawl_bbo,
+        "huobi" => crawlers::huobi::crawl_bbo,
+        "kraken" => crawlers::kraken::crawl_bbo,
+        "kucoin" => crawlers::kucoin::crawl_bbo,
+        _ => {
+            return Err(CrawlerError::unsupported_message_type(
+                exchange,
+                market_type,
+                MessageType::BBO,
+            ))
+        }
+    };
+    Ok(spawn_crawler(func, market_type, symbols, on_msg, duration))
+}
+
+/// Crawl candlesticks, `interval` is in seconds, e.g., 60 for 1m candlesticks.
+///
+/// If `symbols` is None, it means all trading symbols in the `market_type`,
+/// and updates the latest symbols every hour.
+pub fn crawl_candlestick(
+    exchange: &str,
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+    interval: u32,
+) -> Result<CrawlerHandle, CrawlerError> {
+    crawlers::check_msg_type(exchange, market_type, MessageType::Candlestick)?;
+    let func: CandlestickCrawlFn = match exchange {
+        "binance" => crawlers::binance::crawl_candlestick,
+        "bitfinex" => crawlers::bitfinex::crawl_candlestick,
+        "bitget" => crawlers::bitget::crawl_candlestick,
+        "bitmex" => crawlers::bitmex::crawl_candlestick,
+        "bitz" => crawlers::bitz::crawl_candlestick,
+        "bybit" => crawlers::bybit::crawl_candlestick,
+        "deribit" => crawlers::deribit::crawl_candlestick,
+        "gate" => crawlers::gate::crawl_candlestick,
+        "gemini" => crawlers::gemini::crawl_candlestick,
+        "huobi" => crawlers::huobi::crawl_candlestick,
+        "kraken" => crawlers::kraken::crawl_candlestick,
+        "kucoin" => crawlers::kucoin::crawl_candlestick,
+        "mxc" => crawlers::mxc::crawl_candlestick,
+        "okex" => crawlers::okex::crawl_candlestick,
+        "poloniex" => crawlers::poloniex::crawl_candlestick,
+        "zbg" => crawlers::zbg::crawl_candlestick,
+        _ => {
+            return Err(CrawlerError::unsupported_message_type(
+                exchange,
+                market_type,
+                MessageType::Candlestick,
+            ))
+        }
+    };
+    let symbols = symbols.map(|list| list.to_vec());
+    Ok(CrawlerHandle::spawn(on_msg, move |on_msg, ctx| {
+        func(
+            market_type,
+            symbols.as_deref(),
+            on_msg,
+            duration,
+            interval,
+            ctx,
+        )
+    }))
 }
 
 /// Crawl perpetual swap funding rates.
+///
+/// Exchanges without WebSocket channels are polled through RESTful APIs
+/// every minute.
+///
+/// Bitz and ZBG are out of scope: they have perpetual swap markets, but no
+/// public funding rate API of either exchange could be verified, so
+/// `CrawlerError::UnsupportedMessageType` is returned for them.
 pub fn crawl_funding_rate(
     exchange: &str,
     market_type: MarketType,
     symbols: Option<&[String]>,
     on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
     duration: Option<u64>,
-) {
+) -> Result<CrawlerHandle, CrawlerError> {
+    crawlers::check_msg_type(exchange, market_type, MessageType::FundingRate)?;
     let func = match exchange {
         "binance" => crawlers::binance::crawl_funding_rate,
+        "bitfinex" => crawlers::bitfinex::crawl_funding_rate,
         "bitget" => crawlers::bitget::crawl_funding_rate,
         "bitmex" => crawlers::bitmex::crawl_funding_rate,
+        "bybit" => crawlers::bybit::crawl_funding_rate,
+        "deribit" => crawlers::deribit::crawl_funding_rate,
+        "ftx" => crawlers::ftx::crawl_funding_rate,
+        "gate" => crawlers::gate::crawl_funding_rate,
         "huobi" => crawlers::huobi::crawl_funding_rate,
+        "kucoin" => crawlers::kucoin::crawl_funding_rate,
+        "mxc" => crawlers::mxc::crawl_funding_rate,
         "okex" => crawlers::okex::crawl_funding_rate,
-        _ => panic!("{} does NOT have perpetual swap market", exchange),
+        _ => {
+            return Err(CrawlerError::unsupported_message_type(
+                exchange,
+                market_type,
+                MessageType::FundingRate,
+            ))
+        }
+    };
+    Ok(spawn_crawler(func, market_type, symbols, on_msg, duration))
+}
+
+/// Crawl open interest of derivative contracts.
+///
+/// Exchanges without WebSocket channels are polled through RESTful APIs
+/// every minute.
+pub fn crawl_open_interest(
+    exchange: &str,
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+) -> Result<CrawlerHandle, CrawlerError> {
+    crawlers::check_msg_type(exchange, market_type, MessageType::OpenInterest)?;
+    let func = match exchange {
+        "binance" => crawlers::binance::crawl_open_interest,
+        "bitmex" => crawlers::bitmex::crawl_open_interest,
+        "bybit" => crawlers::bybit::crawl_open_interest,
+        "deribit" => crawlers::deribit::crawl_open_interest,
+        "ftx" => crawlers::ftx::crawl_open_interest,
+        "huobi" => crawlers::huobi::crawl_open_interest,
+        "kucoin" => crawlers::kucoin::crawl_open_interest,
+        "okex" => crawlers::okex::crawl_open_interest,
+        _ => {
+            return Err(CrawlerError::unsupported_message_type(
+                exchange,
+                market_type,
+                MessageType::OpenInterest,
+            ))
+        }
     };
-    func(market_type, symbols, on_msg, duration);
+    Ok(spawn_crawler(func, market_type, symbols, on_msg, duration))
+}
+
+/// Crawl liquidation orders of derivative contracts.
+///
+/// Huobi swap markets and OKEx are polled through RESTful APIs every minute,
+/// each poll returns liquidation orders of the last 7 days.
+///
+/// Deribit, FTX, KuCoin and Huobi futures are out of scope, so
+/// `CrawlerError::UnsupportedMessageType` is returned for them: Deribit and FTX
+/// only flag liquidations in trades, KuCoin has no public liquidation API, and
+/// Huobi lists future liquidations per underlying instead of per contract.
+pub fn crawl_liquidation(
+    exchange: &str,
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+) -> Result<CrawlerHandle, CrawlerError> {
+    crawlers::check_msg_type(exchange, market_type, MessageType::Liquidation)?;
+    let func = match exchange {
+        "binance" => crawlers::binance::crawl_liquidation,
+        "bitmex" => crawlers::bitmex::crawl_liquidation,
+        "bybit" => crawlers::bybit::crawl_liquidation,
+        "huobi" => crawlers::huobi::crawl_liquidation,
+        "okex" => crawlers::okex::crawl_liquidation,
+        _ => {
+            return Err(CrawlerError::unsupported_message_type(
+                exchange,
+                market_type,
+                MessageType::Liquidation,
+            ))
+        }
+    };
+    Ok(spawn_crawler(func, market_type, symbols, on_msg, duration))
+}
+
+/// Crawl mark prices of derivative contracts.
+///
+/// Exchanges without WebSocket channels are polled through RESTful APIs
+/// every minute.
+pub fn crawl_mark_price(
+    exchange: &str,
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+) -> Result<CrawlerHandle, CrawlerError> {
+    crawlers::check_msg_type(exchange, market_type, MessageType::MarkPrice)?;
+    let func = match exchange {
+        "binance" => crawlers::binance::crawl_mark_price,
+        "bitmex" => crawlers::bitmex::crawl_mark_price,
+        "bybit" => crawlers::bybit::crawl_mark_price,
+        "deribit" => crawlers::deribit::crawl_mark_price,
+        "ftx" => crawlers::ftx::crawl_mark_price,
+        "huobi" => crawlers::huobi::crawl_mark_price,
+        "kucoin" => crawlers::kucoin::crawl_mark_price,
+        "okex" => crawlers::okex::crawl_mark_price,
+        _ => {
+            return Err(CrawlerError::unsupported_message_type(
+                exchange,
+                market_type,
+                MessageType::MarkPrice,
+            ))
+        }
+    };
+    Ok(spawn_crawler(func, market_type, symbols, on_msg, duration))
+}
+
+/// Crawl index prices of derivative contracts.
+///
+/// Exchanges without WebSocket channels are polled through RESTful APIs
+/// every minute.
+pub fn crawl_index_price(
+    exchange: &str,
+    market_type: MarketType,
+    symbols: Option<&[String]>,
+    on_msg: Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    duration: Option<u64>,
+) -> Result<CrawlerHandle, CrawlerError> {
+    crawlers::check_msg_type(exchange, market_type, MessageType::IndexPrice)?;
+    let func = match exchange {
+        "binance" => crawlers::binance::crawl_index_price,
+        "bitmex" => crawlers::bitmex::crawl_index_price,
+        "bybit" => crawlers::bybit::crawl_index_price,
+        "deribit" => crawlers::deribit::crawl_index_price,
+        "ftx" => crawlers::ftx::crawl_index_price,
+        "huobi" => crawlers::huobi::crawl_index_price,
+        "kucoin" => crawlers::kucoin::crawl_index_price,
+        "okex" => crawlers::okex::crawl_index_price,
+        _ => {
+            return Err(CrawlerError::unsupported_message_type(
+                exchange,
+                market_type,
+                MessageType::IndexPrice,
+            ))
+        }
+    };
+    Ok(spawn_crawler(func, market_type, symbols, on_msg, duration))
+}
+
+// Stops the crawler once the receiving half of tx is dropped
+fn spawn_with_sender<F>(tx: MessageSender<Message>, crawl: F) -> Result<CrawlerHandle, CrawlerError>
+where
+    F: FnOnce(
+        Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    ) -> Result<CrawlerHandle, CrawlerError>,
+{
+    // filled in right after the crawler started, the receiving half can not
+    // be dropped before that
+    let context: Arc<Mutex<Option<Context>>> = Arc::new(Mutex::new(None));
+    let context_clone = context.clone();
+    let on_msg = Arc::new(Mutex::new(move |msg: Message| {
+        if !tx.send(msg) {
+            if let Some(context) = context_clone.lock().unwrap().as_ref() {
+                context.stop();
+            }
+        }
+    }));
+    let handle = crawl(on_msg)?;
+    *context.lock().unwrap() = Some(handle.context());
+    Ok(handle)
+}
+
+/// Runs a crawler in background threads and delivers messages to a bounded channel.
+///
+/// `crawl` receives the `on_msg` callback to pass to one of the `crawl_*`
+/// functions, and its error is returned. The network threads only push
+/// messages into the channel, and `policy` decides what to do when the
+/// channel is full.
+///
+/// The returned `CrawlerHandle` controls the crawler as usual. The channel
+/// is disconnected after the crawler exits, and dropping the receiver stops
+/// the crawler.
+pub fn crawl_with_channel<F>(
+    capacity: usize,
+    policy: BackpressurePolicy,
+    crawl: F,
+) -> Result<(CrawlerHandle, MessageReceiver<Message>), CrawlerError>
+where
+    F: FnOnce(
+        Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    ) -> Result<CrawlerHandle, CrawlerError>,
+{
+    let (tx, rx) = message_channel(capacity, policy);
+    let handle = spawn_with_sender(tx, crawl)?;
+    Ok((handle, rx))
+}
+
+/// Same as `crawl_with_channel()`, but returns an async `Stream`.
+pub fn crawl_with_stream<F>(
+    capacity: usize,
+    policy: BackpressurePolicy,
+    crawl: F,
+) -> Result<(CrawlerHandle, MessageStream<Message>), CrawlerError>
+where
+    F: FnOnce(
+        Arc<Mutex<dyn FnMut(Message) + 'static + Send>>,
+    ) -> Result<CrawlerHandle, CrawlerError>,
+{
+    let (tx, stream) = message_stream(capacity, policy);
+    let handle = spawn_with_sender(tx, crawl)?;
+    Ok((handle, stream))
 }
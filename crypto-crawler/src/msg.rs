@@ -20,6 +20,10 @@ pub enum MessageType {
     Ticker,
     Candlestick,
     FundingRate,
+    OpenInterest,
+    Liquidation,
+    MarkPrice,
+    IndexPrice,
 }
 
 /// Message represents messages received by crawlers.
@@ -5,11 +5,7 @@ use test_case::test_case;
 
 use crypto_crawler::*;
 use crypto_markets::MarketType;
-use std::thread_local;
-use std::{
-    cell::RefCell,
-    sync::{Arc, Mutex},
-};
+use std::sync::{Arc, Mutex};
 
 const EXCHANGE_NAME: &str = "binance";
 
@@ -77,6 +73,33 @@ fn test_crawl_ticker(market_type: MarketType, symbol: &str) {
     )
 }
 
+#[test_case(MarketType::Spot, "BTCUSDT")]
+#[test_case(MarketType::InverseSwap, "BTCUSD_PERP")]
+#[test_case(MarketType::LinearSwap, "BTCUSDT")]
+fn test_crawl_bbo(market_type: MarketType, symbol: &str) {
+    gen_test_code!(
+        crawl_bbo,
+        EXCHANGE_NAME,
+        market_type,
+        symbol,
+        MessageType::BBO
+    )
+}
+
+#[test_case(MarketType::Spot, "BTCUSDT")]
+#[test_case(MarketType::InverseSwap, "BTCUSD_PERP")]
+#[test_case(MarketType::LinearSwap, "BTCUSDT")]
+fn test_crawl_candlestick(market_type: MarketType, symbol: &str) {
+    gen_test_code!(
+        crawl_candlestick,
+        EXCHANGE_NAME,
+        market_type,
+        symbol,
+        MessageType::Candlestick,
+        60
+    )
+}
+
 #[test_case(MarketType::InverseSwap, "BTCUSD_PERP")]
 #[test_case(MarketType::LinearSwap, "BTCUSDT")]
 fn test_crawl_funding_rate(market_type: MarketType, symbol: &str) {
@@ -88,3 +111,39 @@ fn test_crawl_funding_rate(market_type: MarketType, symbol: &str) {
         MessageType::FundingRate
     )
 }
+
+#[test_case(MarketType::InverseSwap, "BTCUSD_PERP")]
+#[test_case(MarketType::LinearSwap, "BTCUSDT")]
+fn test_crawl_open_interest(market_type: MarketType, symbol: &str) {
+    gen_test_code!(
+        crawl_open_interest,
+        EXCHANGE_NAME,
+        market_type,
+        symbol,
+        MessageType::OpenInterest
+    )
+}
+
+#[test_case(MarketType::InverseSwap, "BTCUSD_PERP")]
+#[test_case(MarketType::LinearSwap, "BTCUSDT")]
+fn test_crawl_mark_price(market_type: MarketType, symbol: &str) {
+    gen_test_code!(
+        crawl_mark_price,
+        EXCHANGE_NAME,
+        market_type,
+        symbol,
+        MessageType::MarkPrice
+    )
+}
+
+#[test_case(MarketType::InverseSwap, "BTCUSD_PERP")]
+#[test_case(MarketType::LinearSwap, "BTCUSDT")]
+fn test_crawl_index_price(market_type: MarketType, symbol: &str) {
+    gen_test_code!(
+        crawl_index_price,
+        EXCHANGE_NAME,
+        market_type,
+        symbol,
+        MessageType::IndexPrice
+    )
+}
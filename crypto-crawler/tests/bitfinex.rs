@@ -5,11 +5,7 @@ use test_case::test_case;
 
 use crypto_crawler::*;
 use crypto_markets::MarketType;
-use std::thread_local;
-use std::{
-    cell::RefCell,
-    sync::{Arc, Mutex},
-};
+use std::sync::{Arc, Mutex};
 
 const EXCHANGE_NAME: &str = "bitfinex";
 
@@ -84,3 +80,14 @@ fn test_crawl_ticker(market_type: MarketType, symbol: &str) {
         MessageType::Ticker
     )
 }
+
+#[test_case(MarketType::LinearSwap, "tBTCF0:USTF0")]
+fn test_crawl_funding_rate(market_type: MarketType, symbol: &str) {
+    gen_test_code!(
+        crawl_funding_rate,
+        EXCHANGE_NAME,
+        market_type,
+        symbol,
+        MessageType::FundingRate
+    )
+}
@@ -5,11 +5,7 @@ use test_case::test_case;
 
 use crypto_crawler::*;
 use crypto_markets::MarketType;
-use std::thread_local;
-use std::{
-    cell::RefCell,
-    sync::{Arc, Mutex},
-};
+use std::sync::{Arc, Mutex};
 
 const EXCHANGE_NAME: &str = "bitmex";
 
@@ -58,6 +54,31 @@ fn test_crawl_l2_snapshot(market_type: MarketType, symbol: &str) {
     )
 }
 
+#[test_case(MarketType::InverseSwap, "XBTUSD")]
+#[test_case(MarketType::QuantoSwap, "ETHUSD")]
+fn test_crawl_bbo(market_type: MarketType, symbol: &str) {
+    gen_test_code!(
+        crawl_bbo,
+        EXCHANGE_NAME,
+        market_type,
+        symbol,
+        MessageType::BBO
+    )
+}
+
+#[test_case(MarketType::InverseSwap, "XBTUSD")]
+#[test_case(MarketType::QuantoSwap, "ETHUSD")]
+fn test_crawl_candlestick(market_type: MarketType, symbol: &str) {
+    gen_test_code!(
+        crawl_candlestick,
+        EXCHANGE_NAME,
+        market_type,
+        symbol,
+        MessageType::Candlestick,
+        60
+    )
+}
+
 #[test_case(MarketType::InverseSwap, "XBTUSD")]
 #[test_case(MarketType::QuantoSwap, "ETHUSD")]
 fn test_crawl_funding_rate(market_type: MarketType, symbol: &str) {
@@ -69,3 +90,117 @@ fn test_crawl_funding_rate(market_type: MarketType, symbol: &str) {
         MessageType::FundingRate
     )
 }
+
+#[test_case(MarketType::InverseSwap, "XBTUSD")]
+#[test_case(MarketType::QuantoSwap, "ETHUSD")]
+fn test_crawl_open_interest(market_type: MarketType, symbol: &str) {
+    gen_test_code!(
+        crawl_open_interest,
+        EXCHANGE_NAME,
+        market_type,
+        symbol,
+        MessageType::OpenInterest
+    )
+}
+
+#[test_case(MarketType::InverseSwap, "XBTUSD")]
+#[test_case(MarketType::QuantoSwap, "ETHUSD")]
+fn test_crawl_mark_price(market_type: MarketType, symbol: &str) {
+    gen_test_code!(
+        crawl_mark_price,
+        EXCHANGE_NAME,
+        market_type,
+        symbol,
+        MessageType::MarkPrice
+    )
+}
+
+#[test_case(MarketType::InverseSwap, "XBTUSD")]
+#[test_case(MarketType::QuantoSwap, "ETHUSD")]
+fn test_crawl_index_price(market_type: MarketType, symbol: &str) {
+    gen_test_code!(
+        crawl_index_price,
+        EXCHANGE_NAME,
+        market_type,
+        symbol,
+        MessageType::IndexPrice
+    )
+}
+
+#[test]
+#[ignore] // connects to the live BitMEX server
+fn test_crawl_with_channel() {
+    let (handle, receiver) = crawl_with_channel(1024, BackpressurePolicy::DropOldest, |on_msg| {
+        let symbols = vec!["XBTUSD".to_string()];
+        crawl_trade(
+            EXCHANGE_NAME,
+            MarketType::InverseSwap,
+            Some(&symbols),
+            on_msg,
+            Some(0),
+        )
+    })
+    .unwrap();
+    let messages = receiver.iter().collect::<Vec<Message>>();
+    assert!(!messages.is_empty());
+    assert_eq!(messages[0].exchange, EXCHANGE_NAME.to_string());
+    assert_eq!(messages[0].msg_type, MessageType::Trade);
+    handle.join().unwrap();
+}
+
+#[test]
+#[ignore] // connects to the live BitMEX server
+fn test_crawl_with_channel_receiver_dropped() {
+    let (handle, receiver) = crawl_with_channel(16, BackpressurePolicy::Block, |on_msg| {
+        let symbols = vec!["XBTUSD".to_string()];
+        crawl_l2_event(
+            EXCHANGE_NAME,
+            MarketType::InverseSwap,
+            Some(&symbols),
+            on_msg,
+            None,
+        )
+    })
+    .unwrap();
+    let msg = receiver.recv().unwrap();
+    assert_eq!(msg.msg_type, MessageType::L2Event);
+    drop(receiver);
+
+    for _ in 0..60 {
+        if !handle.is_running() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_secs(1));
+    }
+    assert!(!handle.is_running());
+    handle.join().unwrap();
+}
+
+#[test]
+fn test_crawler_handle_stop() {
+    let on_msg = Arc::new(Mutex::new(|_msg: Message| {}));
+    let symbols = vec!["XBTUSD".to_string()];
+    let handle = crawl_trade(
+        EXCHANGE_NAME,
+        MarketType::InverseSwap,
+        Some(&symbols),
+        on_msg,
+        None,
+    )
+    .unwrap();
+    std::thread::sleep(std::time::Duration::from_secs(5));
+    assert!(handle.is_running());
+    let stats = handle.stats();
+    assert_eq!(stats.connections, 1);
+    assert!(stats.uptime >= std::time::Duration::from_secs(5));
+
+    handle.stop();
+    for _ in 0..60 {
+        if !handle.is_running() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_secs(1));
+    }
+    assert!(!handle.is_running());
+    handle.join().unwrap();
+}
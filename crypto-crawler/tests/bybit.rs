@@ -5,14 +5,11 @@ use test_case::test_case;
 
 use crypto_crawler::*;
 use crypto_markets::MarketType;
-use std::thread_local;
-use std::{
-    cell::RefCell,
-    sync::{Arc, Mutex},
-};
+use std::sync::{Arc, Mutex};
 
 const EXCHANGE_NAME: &str = "bybit";
 
+#[test_case(MarketType::Spot, "BTCUSDT")]
 #[test_case(MarketType::InverseFuture, "BTCUSDU21")]
 #[test_case(MarketType::InverseSwap, "BTCUSD")]
 #[test_case(MarketType::LinearSwap, "BTCUSDT")]
@@ -26,6 +23,7 @@ fn test_crawl_trade(market_type: MarketType, symbol: &str) {
     )
 }
 
+#[test_case(MarketType::Spot, "BTCUSDT")]
 #[test_case(MarketType::InverseFuture, "BTCUSDU21")]
 #[test_case(MarketType::InverseSwap, "BTCUSD")]
 #[test_case(MarketType::LinearSwap, "BTCUSDT")]
@@ -39,6 +37,7 @@ fn test_crawl_l2_event(market_type: MarketType, symbol: &str) {
     )
 }
 
+#[test_case(MarketType::Spot, "BTCUSDT")]
 #[test_case(MarketType::InverseFuture, "BTCUSDU21")]
 #[test_case(MarketType::InverseSwap, "BTCUSD")]
 #[test_case(MarketType::LinearSwap, "BTCUSDT")]
@@ -52,6 +51,7 @@ fn test_crawl_l2_snapshot(market_type: MarketType, symbol: &str) {
     )
 }
 
+#[test_case(MarketType::Spot, "BTCUSDT")]
 #[test_case(MarketType::InverseFuture, "BTCUSDU21")]
 #[test_case(MarketType::InverseSwap, "BTCUSD")]
 #[test_case(MarketType::LinearSwap, "BTCUSDT")]
@@ -64,3 +64,15 @@ fn test_crawl_ticker(market_type: MarketType, symbol: &str) {
         MessageType::Ticker
     )
 }
+
+#[test_case(MarketType::InverseSwap, "BTCUSD")]
+#[test_case(MarketType::LinearSwap, "BTCUSDT")]
+fn test_crawl_funding_rate(market_type: MarketType, symbol: &str) {
+    gen_test_code!(
+        crawl_funding_rate,
+        EXCHANGE_NAME,
+        market_type,
+        symbol,
+        MessageType::FundingRate
+    )
+}
@@ -5,11 +5,7 @@ use test_case::test_case;
 
 use crypto_crawler::*;
 use crypto_markets::MarketType;
-use std::thread_local;
-use std::{
-    cell::RefCell,
-    sync::{Arc, Mutex},
-};
+use std::sync::{Arc, Mutex};
 
 const EXCHANGE_NAME: &str = "deribit";
 
@@ -30,23 +26,21 @@ fn test_crawl_trade(market_type: MarketType, symbol: &str) {
 #[test_case(MarketType::InverseFuture)]
 #[test_case(MarketType::EuropeanOption)]
 fn test_crawl_trade_all(market_type: MarketType) {
-    thread_local! {
-        static MESSAGES: RefCell<Vec<Message>> = RefCell::new(Vec::new());
-    }
-
-    let on_msg = Arc::new(Mutex::new(|msg: Message| {
-        MESSAGES.with(|messages| messages.borrow_mut().push(msg))
+    let messages = Arc::new(Mutex::new(Vec::<Message>::new()));
+    let messages_clone = messages.clone();
+    let on_msg = Arc::new(Mutex::new(move |msg: Message| {
+        messages_clone.lock().unwrap().push(msg);
     }));
-    crawl_trade(EXCHANGE_NAME, market_type, None, on_msg, Some(0));
-
-    MESSAGES.with(|slf| {
-        let messages = slf.borrow();
+    crawl_trade(EXCHANGE_NAME, market_type, None, on_msg, Some(0))
+        .unwrap()
+        .join()
+        .unwrap();
 
-        assert!(!messages.is_empty());
-        assert_eq!(messages[0].exchange, EXCHANGE_NAME.to_string());
-        assert_eq!(messages[0].market_type, market_type);
-        assert_eq!(messages[0].msg_type, MessageType::Trade);
-    });
+    let messages = messages.lock().unwrap();
+    assert!(!messages.is_empty());
+    assert_eq!(messages[0].exchange, EXCHANGE_NAME.to_string());
+    assert_eq!(messages[0].market_type, market_type);
+    assert_eq!(messages[0].msg_type, MessageType::Trade);
 }
 
 #[test_case(MarketType::InverseSwap, "BTC-PERPETUAL")]
@@ -87,3 +81,14 @@ fn test_crawl_ticker(market_type: MarketType, symbol: &str) {
         MessageType::Ticker
     )
 }
+
+#[test_case(MarketType::InverseSwap, "BTC-PERPETUAL")]
+fn test_crawl_funding_rate(market_type: MarketType, symbol: &str) {
+    gen_test_code!(
+        crawl_funding_rate,
+        EXCHANGE_NAME,
+        market_type,
+        symbol,
+        MessageType::FundingRate
+    )
+}
@@ -0,0 +1,167 @@
+use crypto_crawler::*;
+use crypto_markets::MarketType;
+use std::sync::{Arc, Mutex};
+
+fn on_msg() -> Arc<Mutex<dyn FnMut(Message) + 'static + Send>> {
+    Arc::new(Mutex::new(|_msg: Message| {}))
+}
+
+#[test]
+fn unknown_exchange() {
+    assert!(supported_message_types("unknown", MarketType::Spot).is_empty());
+
+    let err = crawl_trade("unknown", MarketType::Spot, None, on_msg(), Some(0)).err();
+    assert_eq!(
+        err,
+        Some(CrawlerError::UnknownExchange("unknown".to_string()))
+    );
+}
+
+#[test]
+fn unsupported_market_type() {
+    assert!(supported_message_types("bitstamp", MarketType::InverseSwap).is_empty());
+
+    let err = crawl_l2_event("bitstamp", MarketType::InverseSwap, None, on_msg(), Some(0)).err();
+    assert_eq!(
+        err,
+        Some(CrawlerError::UnsupportedMarketType {
+            exchange: "bitstamp".to_string(),
+            market_type: MarketType::InverseSwap,
+        })
+    );
+}
+
+#[test]
+fn unsupported_message_type() {
+    let err = crawl_funding_rate("binance", MarketType::Spot, None, on_msg(), Some(0)).err();
+    assert_eq!(
+        err,
+        Some(CrawlerError::UnsupportedMessageType {
+            exchange: "binance".to_string(),
+            market_type: MarketType::Spot,
+            msg_type: MessageType::FundingRate,
+        })
+    );
+
+    let err = crawl_funding_rate("bitz", MarketType::LinearSwap, None, on_msg(), Some(0)).err();
+    assert_eq!(
+        err,
+        Some(CrawlerError::UnsupportedMessageType {
+            exchange: "bitz".to_string(),
+            market_type: MarketType::LinearSwap,
+            msg_type: MessageType::FundingRate,
+        })
+    );
+
+    let err = crawl_funding_rate("zbg", MarketType::InverseSwap, None, on_msg(), Some(0)).err();
+    assert_eq!(
+        err,
+        Some(CrawlerError::UnsupportedMessageType {
+            exchange: "zbg".to_string(),
+            market_type: MarketType::InverseSwap,
+            msg_type: MessageType::FundingRate,
+        })
+    );
+
+    let err = crawl_candlestick(
+        "kraken",
+        MarketType::InverseSwap,
+        None,
+        on_msg(),
+        Some(0),
+        60,
+    )
+    .err();
+    assert_eq!(
+        err,
+        Some(CrawlerError::UnsupportedMessageType {
+            exchange: "kraken".to_string(),
+            market_type: MarketType::InverseSwap,
+            msg_type: MessageType::Candlestick,
+        })
+    );
+
+    let err = crawl_liquidation("deribit", MarketType::InverseSwap, None, on_msg(), Some(0)).err();
+    assert_eq!(
+        err,
+        Some(CrawlerError::UnsupportedMessageType {
+            exchange: "deribit".to_string(),
+            market_type: MarketType::InverseSwap,
+            msg_type: MessageType::Liquidation,
+        })
+    );
+
+    let err = crawl_liquidation("kucoin", MarketType::LinearSwap, None, on_msg(), Some(0)).err();
+    assert_eq!(
+        err,
+        Some(CrawlerError::UnsupportedMessageType {
+            exchange: "kucoin".to_string(),
+            market_type: MarketType::LinearSwap,
+            msg_type: MessageType::Liquidation,
+        })
+    );
+
+    let err = crawl_l3_snapshot("binance", MarketType::Spot, None, on_msg(), None, Some(0)).err();
+    assert!(matches!(
+        err,
+        Some(CrawlerError::UnsupportedMessageType { .. })
+    ));
+}
+
+#[test]
+fn crawl_with_channel_error() {
+    let result = crawl_with_channel(16, BackpressurePolicy::Block, |on_msg| {
+        crawl_ticker("ftx", MarketType::Spot, None, on_msg, Some(0))
+    });
+    assert!(matches!(
+        result.err(),
+        Some(CrawlerError::UnsupportedMessageType { .. })
+    ));
+}
+
+#[test]
+fn message_types() {
+    assert_eq!(
+        supported_message_types("coinbase_pro", MarketType::Spot),
+        vec![
+            MessageType::Trade,
+            MessageType::L2Event,
+            MessageType::L2Snapshot,
+            MessageType::L3Event,
+            MessageType::L3Snapshot,
+            MessageType::Ticker,
+        ]
+    );
+    assert_eq!(
+        supported_message_types("bitmex", MarketType::QuantoSwap),
+        vec![
+            MessageType::Trade,
+            MessageType::L2Event,
+            MessageType::L2Snapshot,
+            MessageType::BBO,
+            MessageType::Candlestick,
+            MessageType::FundingRate,
+            MessageType::OpenInterest,
+            MessageType::Liquidation,
+            MessageType::MarkPrice,
+            MessageType::IndexPrice,
+        ]
+    );
+    assert_eq!(
+        supported_message_types("bitget", MarketType::Spot),
+        vec![MessageType::L2Snapshot]
+    );
+    for market_type in [MarketType::InverseSwap, MarketType::LinearSwap] {
+        assert!(!supported_message_types("zbg", market_type).contains(&MessageType::FundingRate));
+    }
+    for exchange in ["deribit", "ftx", "kucoin"] {
+        assert!(!supported_message_types(exchange, MarketType::InverseSwap)
+            .contains(&MessageType::Liquidation));
+    }
+    assert!(supported_message_types("huobi", MarketType::LinearSwap)
+        .contains(&MessageType::Liquidation));
+    assert!(!supported_message_types("huobi", MarketType::InverseFuture)
+        .contains(&MessageType::Liquidation));
+    assert!(supported_message_types("okex", MarketType::LinearFuture)
+        .contains(&MessageType::Liquidation));
+}
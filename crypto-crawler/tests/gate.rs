@@ -5,11 +5,7 @@ use test_case::test_case;
 
 use crypto_crawler::*;
 use crypto_markets::MarketType;
-use std::thread_local;
-use std::{
-    cell::RefCell,
-    sync::{Arc, Mutex},
-};
+use std::sync::{Arc, Mutex};
 
 const EXCHANGE_NAME: &str = "gate";
 
@@ -18,23 +14,21 @@ const EXCHANGE_NAME: &str = "gate";
 #[test_case(MarketType::LinearSwap)]
 #[test_case(MarketType::LinearFuture; "inconclusive")]
 fn test_crawl_trade_all(market_type: MarketType) {
-    thread_local! {
-        static MESSAGES: RefCell<Vec<Message>> = RefCell::new(Vec::new());
-    }
-
-    let on_msg = Arc::new(Mutex::new(|msg: Message| {
-        MESSAGES.with(|messages| messages.borrow_mut().push(msg))
+    let messages = Arc::new(Mutex::new(Vec::<Message>::new()));
+    let messages_clone = messages.clone();
+    let on_msg = Arc::new(Mutex::new(move |msg: Message| {
+        messages_clone.lock().unwrap().push(msg);
     }));
-    crawl_trade(EXCHANGE_NAME, market_type, None, on_msg, Some(0));
-
-    MESSAGES.with(|slf| {
-        let messages = slf.borrow();
+    crawl_trade(EXCHANGE_NAME, market_type, None, on_msg, Some(0))
+        .unwrap()
+        .join()
+        .unwrap();
 
-        assert!(!messages.is_empty());
-        assert_eq!(messages[0].exchange, EXCHANGE_NAME.to_string());
-        assert_eq!(messages[0].market_type, market_type);
-        assert_eq!(messages[0].msg_type, MessageType::Trade);
-    });
+    let messages = messages.lock().unwrap();
+    assert!(!messages.is_empty());
+    assert_eq!(messages[0].exchange, EXCHANGE_NAME.to_string());
+    assert_eq!(messages[0].market_type, market_type);
+    assert_eq!(messages[0].msg_type, MessageType::Trade);
 }
 
 #[test_case(MarketType::Spot, "BTC_USDT")]
@@ -92,3 +86,15 @@ fn test_crawl_ticker(market_type: MarketType, symbol: &str) {
         MessageType::Ticker
     )
 }
+
+#[test_case(MarketType::InverseSwap, "BTC_USD")]
+#[test_case(MarketType::LinearSwap, "BTC_USDT")]
+fn test_crawl_funding_rate(market_type: MarketType, symbol: &str) {
+    gen_test_code!(
+        crawl_funding_rate,
+        EXCHANGE_NAME,
+        market_type,
+        symbol,
+        MessageType::FundingRate
+    )
+}
@@ -5,11 +5,7 @@ use test_case::test_case;
 
 use crypto_crawler::*;
 use crypto_markets::MarketType;
-use std::thread_local;
-use std::{
-    cell::RefCell,
-    sync::{Arc, Mutex},
-};
+use std::sync::{Arc, Mutex};
 
 const EXCHANGE_NAME: &str = "huobi";
 
@@ -70,6 +66,18 @@ fn test_crawl_funding_rate(market_type: MarketType, symbol: &str) {
     )
 }
 
+#[test_case(MarketType::InverseSwap, "BTC-USD")]
+#[test_case(MarketType::LinearSwap, "BTC-USDT")]
+fn test_crawl_liquidation(market_type: MarketType, symbol: &str) {
+    gen_test_code!(
+        crawl_liquidation,
+        EXCHANGE_NAME,
+        market_type,
+        symbol,
+        MessageType::Liquidation
+    )
+}
+
 #[test_case(MarketType::Spot, "btcusdt")]
 #[test_case(MarketType::InverseFuture, "BTC_CQ")]
 #[test_case(MarketType::InverseSwap, "BTC-USD")]
@@ -5,11 +5,7 @@ use test_case::test_case;
 
 use crypto_crawler::*;
 use crypto_markets::MarketType;
-use std::thread_local;
-use std::{
-    cell::RefCell,
-    sync::{Arc, Mutex},
-};
+use std::sync::{Arc, Mutex};
 
 const EXCHANGE_NAME: &str = "okex";
 
@@ -20,23 +16,21 @@ const EXCHANGE_NAME: &str = "okex";
 #[test_case(MarketType::LinearSwap)]
 #[test_case(MarketType::EuropeanOption)]
 fn test_crawl_trade_all(market_type: MarketType) {
-    thread_local! {
-        static MESSAGES: RefCell<Vec<Message>> = RefCell::new(Vec::new());
-    }
-
-    let on_msg = Arc::new(Mutex::new(|msg: Message| {
-        MESSAGES.with(|messages| messages.borrow_mut().push(msg))
+    let messages = Arc::new(Mutex::new(Vec::<Message>::new()));
+    let messages_clone = messages.clone();
+    let on_msg = Arc::new(Mutex::new(move |msg: Message| {
+        messages_clone.lock().unwrap().push(msg);
     }));
-    crawl_trade(EXCHANGE_NAME, market_type, None, on_msg, Some(0));
-
-    MESSAGES.with(|slf| {
-        let messages = slf.borrow();
+    crawl_trade(EXCHANGE_NAME, market_type, None, on_msg, Some(0))
+        .unwrap()
+        .join()
+        .unwrap();
 
-        assert!(!messages.is_empty());
-        assert_eq!(messages[0].exchange, EXCHANGE_NAME.to_string());
-        assert_eq!(messages[0].market_type, market_type);
-        assert_eq!(messages[0].msg_type, MessageType::Trade);
-    });
+    let messages = messages.lock().unwrap();
+    assert!(!messages.is_empty());
+    assert_eq!(messages[0].exchange, EXCHANGE_NAME.to_string());
+    assert_eq!(messages[0].market_type, market_type);
+    assert_eq!(messages[0].msg_type, MessageType::Trade);
 }
 
 #[test_case(MarketType::Spot, "BTC-USDT")]
@@ -99,6 +93,20 @@ fn test_crawl_funding_rate(market_type: MarketType, symbol: &str) {
     )
 }
 
+#[test_case(MarketType::InverseFuture, "BTC-USD-210924")]
+#[test_case(MarketType::LinearFuture, "BTC-USDT-210924")]
+#[test_case(MarketType::InverseSwap, "BTC-USD-SWAP")]
+#[test_case(MarketType::LinearSwap, "BTC-USDT-SWAP")]
+fn test_crawl_liquidation(market_type: MarketType, symbol: &str) {
+    gen_test_code!(
+        crawl_liquidation,
+        EXCHANGE_NAME,
+        market_type,
+        symbol,
+        MessageType::Liquidation
+    )
+}
+
 #[test_case(MarketType::Spot, "BTC-USDT")]
 #[test_case(MarketType::InverseFuture, "BTC-USD-210924")]
 #[test_case(MarketType::LinearFuture, "BTC-USDT-210924")]
@@ -1,34 +1,31 @@
+// `$arg` is passed after duration, e.g., the interval of candlesticks
 macro_rules! gen_test_code {
-    ($crawl_func:ident, $exchange:expr, $market_type:expr, $symbol:expr, $msg_type:expr) => {{
-        thread_local! {
-            static MESSAGES: RefCell<Vec<Message>> = RefCell::new(Vec::new());
-        }
-
-        let on_msg = Arc::new(Mutex::new(|msg: Message| {
-            MESSAGES.with(|messages| messages.borrow_mut().push(msg))
+    ($crawl_func:ident, $exchange:expr, $market_type:expr, $symbol:expr, $msg_type:expr $(, $arg:expr)?) => {{
+        let messages = Arc::new(Mutex::new(Vec::<Message>::new()));
+        let messages_clone = messages.clone();
+        let on_msg = Arc::new(Mutex::new(move |msg: Message| {
+            messages_clone.lock().unwrap().push(msg);
         }));
         let symbols = vec![$symbol.to_string()];
-        $crawl_func($exchange, $market_type, Some(&symbols), on_msg, Some(0));
-
-        MESSAGES.with(|slf| {
-            let messages = slf.borrow();
+        $crawl_func($exchange, $market_type, Some(&symbols), on_msg, Some(0) $(, $arg)?)
+            .unwrap()
+            .join()
+            .unwrap();
 
-            assert!(!messages.is_empty());
-            assert_eq!(messages[0].exchange, $exchange.to_string());
-            assert_eq!(messages[0].market_type, $market_type);
-            assert_eq!(messages[0].msg_type, $msg_type);
-        });
+        let messages = messages.lock().unwrap();
+        assert!(!messages.is_empty());
+        assert_eq!(messages[0].exchange, $exchange.to_string());
+        assert_eq!(messages[0].market_type, $market_type);
+        assert_eq!(messages[0].msg_type, $msg_type);
     }};
 }
 
 macro_rules! gen_test_snapshot_code {
     ($crawl_func:ident, $exchange:expr, $market_type:expr, $symbol:expr, $msg_type:expr) => {{
-        thread_local! {
-            static MESSAGES: RefCell<Vec<Message>> = RefCell::new(Vec::new());
-        }
-
-        let on_msg = Arc::new(Mutex::new(|msg: Message| {
-            MESSAGES.with(|messages| messages.borrow_mut().push(msg))
+        let messages = Arc::new(Mutex::new(Vec::<Message>::new()));
+        let messages_clone = messages.clone();
+        let on_msg = Arc::new(Mutex::new(move |msg: Message| {
+            messages_clone.lock().unwrap().push(msg);
         }));
         let symbols = vec![$symbol.to_string()];
         $crawl_func(
@@ -38,15 +35,15 @@ macro_rules! gen_test_snapshot_code {
             on_msg,
             None,
             Some(0),
-        );
-
-        MESSAGES.with(|slf| {
-            let messages = slf.borrow();
+        )
+        .unwrap()
+        .join()
+        .unwrap();
 
-            assert!(!messages.is_empty());
-            assert_eq!(messages[0].exchange, $exchange.to_string());
-            assert_eq!(messages[0].market_type, $market_type);
-            assert_eq!(messages[0].msg_type, $msg_type);
-        });
+        let messages = messages.lock().unwrap();
+        assert!(!messages.is_empty());
+        assert_eq!(messages[0].exchange, $exchange.to_string());
+        assert_eq!(messages[0].market_type, $market_type);
+        assert_eq!(messages[0].msg_type, $msg_type);
     }};
 }
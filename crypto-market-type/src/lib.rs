@@ -78,6 +78,7 @@ pub fn get_market_types(exchange: &str) -> Vec<MarketType> {
             MarketType::LinearSwap,
         ],
         "bybit" => vec![
+            MarketType::Spot,
             MarketType::InverseSwap,
             MarketType::LinearSwap,
             MarketType::InverseFuture,
@@ -102,14 +103,19 @@ pub fn get_market_types(exchange: &str) -> Vec<MarketType> {
             MarketType::InverseSwap,
             MarketType::LinearSwap,
         ],
+        "gemini" => vec![MarketType::Spot],
         "huobi" => vec![
             MarketType::Spot,
             MarketType::InverseFuture,
             MarketType::LinearSwap,
             MarketType::InverseSwap,
-            // MarketType::EuropeanOption,
+            MarketType::EuropeanOption,
+        ],
+        "kraken" => vec![
+            MarketType::Spot,
+            MarketType::InverseFuture,
+            MarketType::InverseSwap,
         ],
-        "kraken" => vec![MarketType::Spot],
         "kucoin" => vec![
             MarketType::Spot,
             MarketType::LinearSwap,
@@ -129,6 +135,8 @@ pub fn get_market_types(exchange: &str) -> Vec<MarketType> {
             MarketType::InverseSwap,
             MarketType::EuropeanOption,
         ],
+        "poloniex" => vec![MarketType::Spot],
+        "upbit" => vec![MarketType::Spot],
         "zbg" => vec![
             MarketType::Spot,
             MarketType::InverseSwap,
@@ -8,6 +8,7 @@ use serde_json::Value;
 
 pub(crate) fn fetch_symbols(market_type: MarketType) -> Result<Vec<String>> {
     match market_type {
+        MarketType::Spot => fetch_spot_symbols(),
         MarketType::InverseSwap => fetch_inverse_swap_symbols(),
         MarketType::LinearSwap => fetch_linear_swap_symbols(),
         MarketType::InverseFuture => fetch_inverse_future_symbols(),
@@ -65,6 +66,47 @@ struct Response {
     result: Vec<BybitMarket>,
 }
 
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+struct SpotMarket {
+    name: String,
+    alias: String,
+    baseCurrency: String,
+    quoteCurrency: String,
+    basePrecision: String,
+    quotePrecision: String,
+    minTradeQuantity: String,
+    minTradeAmount: String,
+    minPricePrecision: String,
+    maxTradeQuantity: String,
+    maxTradeAmount: String,
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SpotResponse {
+    ret_code: i64,
+    ret_msg: String,
+    result: Vec<SpotMarket>,
+}
+
+// See https://bybit-exchange.github.io/docs/spot/#t-querysymbol
+fn fetch_spot_markets_raw() -> Result<Vec<SpotMarket>> {
+    let txt = http_get("https://api.bybit.com/spot/v1/symbols", None)?;
+    let resp = serde_json::from_str::<SpotResponse>(&txt)?;
+    assert_eq!(resp.ret_code, 0);
+    Ok(resp.result)
+}
+
+fn fetch_spot_symbols() -> Result<Vec<String>> {
+    let symbols = fetch_spot_markets_raw()?
+        .into_iter()
+        .map(|m| m.name)
+        .collect::<Vec<String>>();
+    Ok(symbols)
+}
+
 // See https://bybit-exchange.github.io/docs/inverse/#t-querysymbol
 fn fetch_markets_raw() -> Result<Vec<BybitMarket>> {
     let txt = http_get("https://api.bybit.com/v2/public/symbols", None)?;
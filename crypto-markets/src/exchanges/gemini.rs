@@ -0,0 +1,22 @@
+use super::utils::http_get;
+use crate::{error::Result, Market, MarketType};
+
+pub(crate) fn fetch_symbols(market_type: MarketType) -> Result<Vec<String>> {
+    match market_type {
+        MarketType::Spot => fetch_spot_symbols(),
+        _ => panic!("Unsupported market_type: {}", market_type),
+    }
+}
+
+pub(crate) fn fetch_markets(_market_type: MarketType) -> Result<Vec<Market>> {
+    Ok(Vec::new())
+}
+
+// see <https://docs.gemini.com/rest-api/#symbols>
+fn fetch_spot_symbols() -> Result<Vec<String>> {
+    let txt = http_get("https://api.gemini.com/v1/symbols", None)?;
+    let symbols = serde_json::from_str::<Vec<String>>(&txt)?;
+    // The REST API returns lowercase symbols, while the WebSocket API v2 uses
+    // uppercase symbols
+    Ok(symbols.into_iter().map(|s| s.to_uppercase()).collect())
+}
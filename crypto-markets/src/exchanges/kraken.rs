@@ -12,6 +12,8 @@ use serde_json::Value;
 pub(crate) fn fetch_symbols(market_type: MarketType) -> Result<Vec<String>> {
     match market_type {
         MarketType::Spot => fetch_spot_symbols(),
+        MarketType::InverseFuture => fetch_inverse_future_symbols(),
+        MarketType::InverseSwap => fetch_inverse_swap_symbols(),
         _ => panic!("Unsupported market_type: {}", market_type),
     }
 }
@@ -45,6 +47,26 @@ struct Response {
     result: HashMap<String, SpotMarket>,
 }
 
+#[derive(Clone, Serialize, Deserialize)]
+#[allow(non_snake_case)]
+struct FuturesMarket {
+    symbol: String,
+    #[serde(rename = "type")]
+    type_: String,
+    underlying: String,
+    tickSize: f64,
+    contractSize: f64,
+    tradeable: bool,
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct FuturesResponse {
+    result: String,
+    instruments: Vec<Value>,
+}
+
 fn check_error_in_body(resp: String) -> Result<String> {
     let obj = serde_json::from_str::<HashMap<String, Value>>(&resp);
     if obj.is_err() {
@@ -95,3 +117,42 @@ fn fetch_spot_symbols() -> Result<Vec<String>> {
         .collect::<Vec<String>>();
     Ok(symbols)
 }
+
+// see <https://docs.futures.kraken.com/#http-api-trading-v3-api-instrument-details-get-instruments>
+fn fetch_futures_markets_raw() -> Result<Vec<FuturesMarket>> {
+    let txt = http_get(
+        "https://futures.kraken.com/derivatives/api/v3/instruments",
+        None,
+    )?;
+    let resp = serde_json::from_str::<FuturesResponse>(&txt)?;
+    if resp.result != "success" {
+        return Err(Error(txt));
+    }
+    // Indices have no type
+    let markets = resp
+        .instruments
+        .into_iter()
+        .filter(|x| x.get("type").and_then(|x| x.as_str()) == Some("futures_inverse"))
+        .map(|x| serde_json::from_value::<FuturesMarket>(x).unwrap())
+        .filter(|m| m.tradeable)
+        .collect::<Vec<FuturesMarket>>();
+    Ok(markets)
+}
+
+// Symbols are lowercase in RESTful APIs but uppercase in WebSocket APIs
+fn fetch_futures_symbols(prefix: &str) -> Result<Vec<String>> {
+    let symbols = fetch_futures_markets_raw()?
+        .into_iter()
+        .filter(|m| m.symbol.starts_with(prefix))
+        .map(|m| m.symbol.to_uppercase())
+        .collect::<Vec<String>>();
+    Ok(symbols)
+}
+
+fn fetch_inverse_future_symbols() -> Result<Vec<String>> {
+    fetch_futures_symbols("fi_")
+}
+
+fn fetch_inverse_swap_symbols() -> Result<Vec<String>> {
+    fetch_futures_symbols("pi_")
+}
@@ -0,0 +1,44 @@
+use super::utils::http_get;
+use crate::{error::Result, Market, MarketType};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+pub(crate) fn fetch_symbols(market_type: MarketType) -> Result<Vec<String>> {
+    match market_type {
+        MarketType::Spot => fetch_spot_symbols(),
+        _ => panic!("Unsupported market_type: {}", market_type),
+    }
+}
+
+pub(crate) fn fetch_markets(_market_type: MarketType) -> Result<Vec<Market>> {
+    Ok(Vec::new())
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SpotMarket {
+    symbol: String,
+    base_currency_name: String,
+    quote_currency_name: String,
+    state: String, // NORMAL, PAUSE, OFFLINE
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+// see <https://docs.poloniex.com/#public-endpoints-reference-data-symbol-information>
+fn fetch_spot_markets_raw() -> Result<Vec<SpotMarket>> {
+    let txt = http_get("https://api.poloniex.com/markets", None)?;
+    let markets = serde_json::from_str::<Vec<SpotMarket>>(&txt)?;
+    Ok(markets)
+}
+
+fn fetch_spot_symbols() -> Result<Vec<String>> {
+    let symbols = fetch_spot_markets_raw()?
+        .into_iter()
+        .filter(|m| m.state == "NORMAL")
+        .map(|m| m.symbol)
+        .collect::<Vec<String>>();
+    Ok(symbols)
+}
@@ -0,0 +1,40 @@
+use super::utils::http_get;
+use crate::{error::Result, Market, MarketType};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+pub(crate) fn fetch_symbols(market_type: MarketType) -> Result<Vec<String>> {
+    match market_type {
+        MarketType::Spot => fetch_spot_symbols(),
+        _ => panic!("Unsupported market_type: {}", market_type),
+    }
+}
+
+pub(crate) fn fetch_markets(_market_type: MarketType) -> Result<Vec<Market>> {
+    Ok(Vec::new())
+}
+
+#[derive(Serialize, Deserialize)]
+struct SpotMarket {
+    market: String, // quote-base, e.g., KRW-BTC
+    english_name: String,
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+// see <https://global-docs.upbit.com/reference/listing-market-list>
+fn fetch_spot_markets_raw() -> Result<Vec<SpotMarket>> {
+    let txt = http_get("https://api.upbit.com/v1/market/all", None)?;
+    let markets = serde_json::from_str::<Vec<SpotMarket>>(&txt)?;
+    Ok(markets)
+}
+
+fn fetch_spot_symbols() -> Result<Vec<String>> {
+    let symbols = fetch_spot_markets_raw()?
+        .into_iter()
+        .map(|m| m.market)
+        .collect::<Vec<String>>();
+    Ok(symbols)
+}
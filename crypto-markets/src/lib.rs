@@ -36,11 +36,14 @@ pub fn fetch_symbols(exchange: &str, market_type: MarketType) -> Result<Vec<Stri
         "deribit" => exchanges::deribit::fetch_symbols(market_type),
         "ftx" => exchanges::ftx::fetch_symbols(market_type),
         "gate" => exchanges::gate::fetch_symbols(market_type),
+        "gemini" => exchanges::gemini::fetch_symbols(market_type),
         "huobi" => exchanges::huobi::fetch_symbols(market_type),
         "kraken" => exchanges::kraken::fetch_symbols(market_type),
         "kucoin" => exchanges::kucoin::fetch_symbols(market_type),
         "mxc" => exchanges::mxc::fetch_symbols(market_type),
         "okex" => exchanges::okex::fetch_symbols(market_type),
+        "poloniex" => exchanges::poloniex::fetch_symbols(market_type),
+        "upbit" => exchanges::upbit::fetch_symbols(market_type),
         "zbg" => exchanges::zbg::fetch_symbols(market_type),
         _ => panic!("Unsupported exchange {}", exchange),
     }
@@ -75,11 +78,14 @@ pub fn fetch_markets(exchange: &str, market_type: MarketType) -> Result<Vec<Mark
         "deribit" => exchanges::deribit::fetch_markets(market_type),
         "ftx" => exchanges::ftx::fetch_markets(market_type),
         "gate" => exchanges::gate::fetch_markets(market_type),
+        "gemini" => exchanges::gemini::fetch_markets(market_type),
         "huobi" => exchanges::huobi::fetch_markets(market_type),
         "kraken" => exchanges::kraken::fetch_markets(market_type),
         "kucoin" => exchanges::kucoin::fetch_markets(market_type),
         "mxc" => exchanges::mxc::fetch_markets(market_type),
         "okex" => exchanges::okex::fetch_markets(market_type),
+        "poloniex" => exchanges::poloniex::fetch_markets(market_type),
+        "upbit" => exchanges::upbit::fetch_markets(market_type),
         "zbg" => exchanges::zbg::fetch_markets(market_type),
         _ => panic!("Unsupported exchange {}", exchange),
     }
@@ -10,6 +10,15 @@ fn fetch_all_symbols() {
     gen_all_symbols!();
 }
 
+#[test]
+fn fetch_spot_symbols() {
+    let symbols = fetch_symbols(EXCHANGE_NAME, MarketType::Spot).unwrap();
+    assert!(!symbols.is_empty());
+    for symbol in symbols.iter() {
+        assert_eq!(symbol.to_uppercase(), *symbol);
+    }
+}
+
 #[test]
 fn fetch_inverse_swap_symbols() {
     let symbols = fetch_symbols(EXCHANGE_NAME, MarketType::InverseSwap).unwrap();
@@ -18,3 +18,21 @@ fn fetch_spot_symbols() {
         assert!(symbol.contains("/"));
     }
 }
+
+#[test]
+fn fetch_inverse_future_symbols() {
+    let symbols = fetch_symbols(EXCHANGE_NAME, MarketType::InverseFuture).unwrap();
+    assert!(!symbols.is_empty());
+    for symbol in symbols.iter() {
+        assert!(symbol.starts_with("FI_"));
+    }
+}
+
+#[test]
+fn fetch_inverse_swap_symbols() {
+    let symbols = fetch_symbols(EXCHANGE_NAME, MarketType::InverseSwap).unwrap();
+    assert!(!symbols.is_empty());
+    for symbol in symbols.iter() {
+        assert!(symbol.starts_with("PI_"));
+    }
+}
@@ -1,8 +1,8 @@
 use crypto_market_type::MarketType;
 
 use crate::{
-    exchanges::utils::calc_quantity_and_volume, MessageType, Order, OrderBookMsg, TradeMsg,
-    TradeSide,
+    exchanges::utils::calc_quantity_and_volume, FundingRateMsg, MessageType, Order, OrderBookMsg,
+    TradeMsg, TradeSide,
 };
 
 use chrono::prelude::*;
@@ -147,3 +147,34 @@ pub(crate) fn parse_l2(market_type: MarketType, msg: &str) -> Result<Vec<OrderBo
 
     Ok(vec![orderbook])
 }
+
+// See https://docs.bitfinex.com/reference#rest-public-derivatives-status
+pub(crate) fn parse_funding_rate(
+    market_type: MarketType,
+    msg: &str,
+) -> Result<Vec<FundingRateMsg>> {
+    let arr = serde_json::from_str::<Vec<Vec<Value>>>(msg)?;
+    let rates: Vec<FundingRateMsg> = arr
+        .into_iter()
+        .map(|raw_msg| {
+            // [KEY, MTS, _, DERIV_PRICE, SPOT_PRICE, _, INSURANCE_FUND_BALANCE, _,
+            //  NEXT_FUNDING_EVT_TIMESTAMP_MS, NEXT_FUNDING_ACCRUED, NEXT_FUNDING_STEP, _,
+            //  CURRENT_FUNDING, ...]
+            let symbol = raw_msg[0].as_str().unwrap();
+            FundingRateMsg {
+                exchange: EXCHANGE_NAME.to_string(),
+                market_type,
+                symbol: symbol.to_string(),
+                pair: crypto_pair::normalize_pair(symbol, EXCHANGE_NAME).unwrap(),
+                msg_type: MessageType::FundingRate,
+                timestamp: raw_msg[1].as_i64().unwrap(),
+                funding_rate: raw_msg[12].as_f64().unwrap(),
+                funding_time: raw_msg[8].as_i64().unwrap(),
+                estimated_rate: raw_msg[9].as_f64(),
+                raw: serde_json::to_value(&raw_msg).unwrap(),
+            }
+        })
+        .collect();
+
+    Ok(rates)
+}
@@ -1,13 +1,15 @@
 use crypto_market_type::MarketType;
 
 use crate::{
-    exchanges::utils::calc_quantity_and_volume, MessageType, Order, OrderBookMsg, TradeMsg,
-    TradeSide,
+    exchanges::utils::calc_quantity_and_volume, FundingRateMsg, MessageType, Order, OrderBookMsg,
+    TradeMsg, TradeSide,
 };
 
+use chrono::DateTime;
+use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 use serde_json::{Result, Value};
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Mutex};
 
 const EXCHANGE_NAME: &str = "bybit";
 
@@ -41,6 +43,39 @@ struct LinearTradeMsg {
     extra: HashMap<String, Value>,
 }
 
+// see https://bybit-exchange.github.io/docs/spot/#t-websockettrade
+#[derive(Serialize, Deserialize)]
+struct SpotTradeMsg {
+    v: String, // trade ID
+    t: i64,
+    p: String,
+    q: String,
+    m: bool, // true means the buyer is the maker
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+// see https://bybit-exchange.github.io/docs/spot/#t-websocketmergeddepth
+#[derive(Serialize, Deserialize)]
+struct SpotOrderbookMsg {
+    s: String,
+    t: i64,
+    b: Vec<[String; 2]>,
+    a: Vec<[String; 2]>,
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SpotWebsocketMsg<T: Sized> {
+    symbol: String,
+    topic: String,
+    data: Vec<T>,
+    f: bool, // true if it is the first message, i.e., a snapshot
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
 #[derive(Serialize, Deserialize)]
 struct WebsocketMsg<T: Sized> {
     topic: String,
@@ -82,6 +117,41 @@ struct RawOrderbookMsg {
 
 pub(crate) fn parse_trade(market_type: MarketType, msg: &str) -> Result<Vec<TradeMsg>> {
     match market_type {
+        MarketType::Spot => {
+            let ws_msg = serde_json::from_str::<SpotWebsocketMsg<SpotTradeMsg>>(msg)?;
+            let symbol = ws_msg.symbol.as_str();
+            let pair = crypto_pair::normalize_pair(symbol, EXCHANGE_NAME).unwrap();
+
+            let trades: Vec<TradeMsg> = ws_msg
+                .data
+                .into_iter()
+                .map(|raw_trade| {
+                    let price = raw_trade.p.parse::<f64>().unwrap();
+                    let quantity = raw_trade.q.parse::<f64>().unwrap();
+                    TradeMsg {
+                        exchange: EXCHANGE_NAME.to_string(),
+                        market_type,
+                        symbol: symbol.to_string(),
+                        pair: pair.clone(),
+                        msg_type: MessageType::Trade,
+                        timestamp: raw_trade.t,
+                        price,
+                        quantity_base: quantity,
+                        quantity_quote: price * quantity,
+                        quantity_contract: None,
+                        side: if raw_trade.m {
+                            TradeSide::Sell
+                        } else {
+                            TradeSide::Buy
+                        },
+                        trade_id: raw_trade.v.clone(),
+                        raw: serde_json::to_value(&raw_trade).unwrap(),
+                    }
+                })
+                .collect();
+
+            Ok(trades)
+        }
         MarketType::InverseSwap | MarketType::InverseFuture => {
             let ws_msg = serde_json::from_str::<WebsocketMsg<InverseTradeMsg>>(msg)?;
 
@@ -153,7 +223,46 @@ pub(crate) fn parse_trade(market_type: MarketType, msg: &str) -> Result<Vec<Trad
     }
 }
 
+fn parse_spot_l2(msg: &str) -> Result<Vec<OrderBookMsg>> {
+    let ws_msg = serde_json::from_str::<SpotWebsocketMsg<SpotOrderbookMsg>>(msg)?;
+    let pair = crypto_pair::normalize_pair(&ws_msg.symbol, EXCHANGE_NAME).unwrap();
+
+    let parse_order = |raw_order: &[String; 2]| -> Order {
+        let price = raw_order[0].parse::<f64>().unwrap();
+        let quantity_base = raw_order[1].parse::<f64>().unwrap();
+
+        Order {
+            price,
+            quantity_base,
+            quantity_quote: price * quantity_base,
+            quantity_contract: None,
+        }
+    };
+
+    let orderbooks = ws_msg
+        .data
+        .iter()
+        .map(|raw_orderbook| OrderBookMsg {
+            exchange: EXCHANGE_NAME.to_string(),
+            market_type: MarketType::Spot,
+            symbol: ws_msg.symbol.clone(),
+            pair: pair.clone(),
+            msg_type: MessageType::L2Event,
+            timestamp: raw_orderbook.t,
+            asks: raw_orderbook.a.iter().map(&parse_order).collect(),
+            bids: raw_orderbook.b.iter().map(&parse_order).collect(),
+            snapshot: ws_msg.f,
+            raw: serde_json::to_value(raw_orderbook).unwrap(),
+        })
+        .collect();
+
+    Ok(orderbooks)
+}
+
 pub(crate) fn parse_l2(market_type: MarketType, msg: &str) -> Result<Vec<OrderBookMsg>> {
+    if market_type == MarketType::Spot {
+        return parse_spot_l2(msg);
+    }
     let ws_msg = serde_json::from_str::<RawOrderbookMsg>(msg)?;
     let symbol = ws_msg.topic.strip_prefix("orderBookL2_25.").unwrap();
     let pair = crypto_pair::normalize_pair(symbol, EXCHANGE_NAME).unwrap();
@@ -235,3 +344,113 @@ pub(crate) fn parse_l2(market_type: MarketType, msg: &str) -> Result<Vec<OrderBo
     }
     Ok(vec![orderbook])
 }
+
+// https://bybit-exchange.github.io/docs/inverse/#t-websocketinstrumentinfo
+// https://bybit-exchange.github.io/docs/linear/#t-websocketinstrumentinfo
+#[derive(Serialize, Deserialize)]
+struct RawInstrumentInfo {
+    symbol: String,
+    funding_rate_e6: Option<Value>, // i64 or String
+    predicted_funding_rate_e6: Option<Value>,
+    next_funding_time: Option<String>,
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+fn value_to_f64(v: &Value) -> f64 {
+    if v.is_string() {
+        v.as_str().unwrap().parse::<f64>().unwrap()
+    } else {
+        v.as_f64().unwrap()
+    }
+}
+
+#[derive(Clone, Default)]
+struct FundingState {
+    funding_rate_e6: Option<Value>,
+    predicted_funding_rate_e6: Option<Value>,
+    next_funding_time: Option<String>,
+}
+
+lazy_static! {
+    // market_type + symbol -> latest funding fields, deltas only carry changed fields
+    static ref FUNDING_STATES: Mutex<HashMap<String, FundingState>> = Mutex::new(HashMap::new());
+}
+
+pub(crate) fn parse_funding_rate(
+    market_type: MarketType,
+    msg: &str,
+) -> Result<Vec<FundingRateMsg>> {
+    let ws_msg = serde_json::from_str::<RawOrderbookMsg>(msg)?;
+    let timestamp = if ws_msg.timestamp_e6.is_i64() {
+        ws_msg.timestamp_e6.as_i64().unwrap()
+    } else {
+        ws_msg
+            .timestamp_e6
+            .as_str()
+            .unwrap()
+            .parse::<i64>()
+            .unwrap()
+    } / 1000;
+    let snapshot = ws_msg.type_ == "snapshot";
+    // A snapshot contains all fields, while a delta contains changed fields only
+    let raw_infos = if snapshot {
+        vec![serde_json::from_value::<RawInstrumentInfo>(ws_msg.data)?]
+    } else {
+        let delta = serde_json::from_value::<HashMap<String, Value>>(ws_msg.data)?;
+        match delta.get("update") {
+            Some(update) => serde_json::from_value::<Vec<RawInstrumentInfo>>(update.clone())?,
+            None => Vec::new(),
+        }
+    };
+
+    let mut states = FUNDING_STATES.lock().unwrap();
+    let mut rates = Vec::new();
+    for raw_msg in raw_infos {
+        let changed = raw_msg.funding_rate_e6.is_some()
+            || raw_msg.predicted_funding_rate_e6.is_some()
+            || raw_msg.next_funding_time.is_some();
+        let key = format!("{}-{}", market_type, raw_msg.symbol);
+        if snapshot {
+            states.insert(key.clone(), FundingState::default());
+        }
+        let state = states.entry(key).or_default();
+        if raw_msg.funding_rate_e6.is_some() {
+            state.funding_rate_e6 = raw_msg.funding_rate_e6.clone();
+        }
+        if raw_msg.predicted_funding_rate_e6.is_some() {
+            state.predicted_funding_rate_e6 = raw_msg.predicted_funding_rate_e6.clone();
+        }
+        if raw_msg.next_funding_time.is_some() {
+            state.next_funding_time = raw_msg.next_funding_time.clone();
+        }
+        if !changed {
+            continue;
+        }
+        // Deltas arriving before any snapshot may lack the current rate
+        if let (Some(funding_rate_e6), Some(next_funding_time)) = (
+            state.funding_rate_e6.as_ref(),
+            state.next_funding_time.as_ref(),
+        ) {
+            let funding_time = DateTime::parse_from_rfc3339(next_funding_time).unwrap();
+
+            rates.push(FundingRateMsg {
+                exchange: EXCHANGE_NAME.to_string(),
+                market_type,
+                symbol: raw_msg.symbol.clone(),
+                pair: crypto_pair::normalize_pair(&raw_msg.symbol, EXCHANGE_NAME).unwrap(),
+                msg_type: MessageType::FundingRate,
+                timestamp,
+                funding_rate: value_to_f64(funding_rate_e6) / 1e6,
+                funding_time: funding_time.timestamp_millis(),
+                estimated_rate: state
+                    .predicted_funding_rate_e6
+                    .as_ref()
+                    .map(|x| value_to_f64(x) / 1e6),
+                raw: serde_json::to_value(&raw_msg).unwrap(),
+            });
+        }
+    }
+
+    Ok(rates)
+}
@@ -1,6 +1,6 @@
 use crypto_market_type::MarketType;
 
-use crate::{MessageType, Order, OrderBookMsg, TradeMsg, TradeSide};
+use crate::{FundingRateMsg, MessageType, Order, OrderBookMsg, TradeMsg, TradeSide};
 
 use serde::{Deserialize, Serialize};
 use serde_json::{Result, Value};
@@ -135,3 +135,39 @@ pub(crate) fn parse_l2(market_type: MarketType, msg: &str) -> Result<Vec<OrderBo
 
     Ok(vec![orderbook])
 }
+
+// https://docs.deribit.com/?javascript#ticker-instrument_name-interval
+#[derive(Serialize, Deserialize)]
+struct RawTickerMsg {
+    timestamp: i64,
+    instrument_name: String,
+    current_funding: f64,
+    funding_8h: f64,
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+pub(crate) fn parse_funding_rate(
+    market_type: MarketType,
+    msg: &str,
+) -> Result<Vec<FundingRateMsg>> {
+    let ws_msg = serde_json::from_str::<WebsocketMsg<RawTickerMsg>>(msg)?;
+    let raw_msg = ws_msg.params.data;
+    // Deribit charges funding continuously, funding_8h is the rate over the last 8 hours,
+    // so funding_time is rounded up to the next 8-hour boundary
+    const EIGHT_HOURS: i64 = 8 * 3600 * 1000;
+    let funding_time = (raw_msg.timestamp / EIGHT_HOURS + 1) * EIGHT_HOURS;
+    let funding_rate = FundingRateMsg {
+        exchange: EXCHANGE_NAME.to_string(),
+        market_type,
+        symbol: raw_msg.instrument_name.clone(),
+        pair: crypto_pair::normalize_pair(&raw_msg.instrument_name, EXCHANGE_NAME).unwrap(),
+        msg_type: MessageType::FundingRate,
+        timestamp: raw_msg.timestamp,
+        funding_rate: raw_msg.funding_8h,
+        funding_time,
+        estimated_rate: None,
+        raw: serde_json::to_value(&raw_msg).unwrap(),
+    };
+    Ok(vec![funding_rate])
+}
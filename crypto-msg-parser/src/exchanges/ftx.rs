@@ -1,11 +1,11 @@
 use crypto_market_type::MarketType;
 
 use crate::{
-    exchanges::utils::calc_quantity_and_volume, MessageType, Order, OrderBookMsg, TradeMsg,
-    TradeSide,
+    exchanges::utils::calc_quantity_and_volume, FundingRateMsg, MessageType, Order, OrderBookMsg,
+    TradeMsg, TradeSide,
 };
 
-use chrono::DateTime;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::{Result, Value};
 use std::collections::HashMap;
@@ -122,3 +122,49 @@ pub(crate) fn parse_l2(market_type: MarketType, msg: &str) -> Result<Vec<OrderBo
 
     Ok(vec![orderbook])
 }
+
+// https://docs.ftx.com/#get-funding-rates
+#[derive(Serialize, Deserialize)]
+struct RawFundingRateMsg {
+    future: String,
+    rate: f64,
+    time: String,
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RestfulMsg<T: Sized> {
+    success: bool,
+    result: T,
+}
+
+pub(crate) fn parse_funding_rate(
+    market_type: MarketType,
+    msg: &str,
+) -> Result<Vec<FundingRateMsg>> {
+    let rest_msg = serde_json::from_str::<RestfulMsg<Vec<RawFundingRateMsg>>>(msg)?;
+    let timestamp = Utc::now().timestamp_millis();
+    let rates: Vec<FundingRateMsg> = rest_msg
+        .result
+        .into_iter()
+        .map(|raw_msg| {
+            let funding_time = DateTime::parse_from_rfc3339(&raw_msg.time).unwrap();
+
+            FundingRateMsg {
+                exchange: EXCHANGE_NAME.to_string(),
+                market_type,
+                symbol: raw_msg.future.clone(),
+                pair: crypto_pair::normalize_pair(&raw_msg.future, EXCHANGE_NAME).unwrap(),
+                msg_type: MessageType::FundingRate,
+                timestamp,
+                funding_rate: raw_msg.rate,
+                funding_time: funding_time.timestamp_millis(),
+                estimated_rate: None,
+                raw: serde_json::to_value(&raw_msg).unwrap(),
+            }
+        })
+        .collect();
+
+    Ok(rates)
+}
@@ -2,8 +2,9 @@ use crypto_market_type::MarketType;
 
 use super::super::utils::calc_quantity_and_volume;
 
-use crate::{MessageType, Order, OrderBookMsg, TradeMsg, TradeSide};
+use crate::{FundingRateMsg, MessageType, Order, OrderBookMsg, TradeMsg, TradeSide};
 
+use chrono::prelude::*;
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 use serde_json::{Result, Value};
@@ -276,3 +277,34 @@ pub(crate) fn parse_l2(market_type: MarketType, msg: &str) -> Result<Vec<OrderBo
 
     Ok(vec![orderbook])
 }
+
+// https://www.gate.io/docs/futures/api/index.html#get-a-single-contract
+#[derive(Serialize, Deserialize)]
+struct RawFundingRateMsg {
+    name: String,
+    funding_rate: String,
+    funding_next_apply: i64,
+    funding_rate_indicative: String,
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+pub(crate) fn parse_funding_rate(
+    market_type: MarketType,
+    msg: &str,
+) -> Result<Vec<FundingRateMsg>> {
+    let raw_msg = serde_json::from_str::<RawFundingRateMsg>(msg)?;
+    let funding_rate = FundingRateMsg {
+        exchange: EXCHANGE_NAME.to_string(),
+        market_type,
+        symbol: raw_msg.name.clone(),
+        pair: crypto_pair::normalize_pair(&raw_msg.name, EXCHANGE_NAME).unwrap(),
+        msg_type: MessageType::FundingRate,
+        timestamp: Utc::now().timestamp_millis(),
+        funding_rate: raw_msg.funding_rate.parse::<f64>().unwrap(),
+        funding_time: raw_msg.funding_next_apply * 1000,
+        estimated_rate: Some(raw_msg.funding_rate_indicative.parse::<f64>().unwrap()),
+        raw: serde_json::to_value(&raw_msg).unwrap(),
+    };
+    Ok(vec![funding_rate])
+}
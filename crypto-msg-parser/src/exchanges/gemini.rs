@@ -0,0 +1,138 @@
+use crypto_market_type::MarketType;
+
+use crate::Order;
+use crate::{MessageType, OrderBookMsg, TradeMsg, TradeSide};
+
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::{Result, Value};
+use std::collections::HashMap;
+
+const EXCHANGE_NAME: &str = "gemini";
+
+// see https://docs.gemini.com/websocket-api/#market-data-version-2
+#[derive(Serialize, Deserialize)]
+struct SpotTradeMsg {
+    #[serde(rename = "type")]
+    type_: String,
+    symbol: String,
+    event_id: i64,
+    timestamp: i64,
+    price: String,
+    quantity: String,
+    side: String, // buy, sell
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+// The first l2_updates message is a snapshot with recent trades, later
+// messages contain only changes
+#[derive(Serialize, Deserialize)]
+struct L2UpdatesMsg {
+    #[serde(rename = "type")]
+    type_: String,
+    symbol: String,
+    changes: Vec<[String; 3]>,
+    trades: Option<Vec<SpotTradeMsg>>,
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct WebsocketMsg {
+    #[serde(rename = "type")]
+    type_: String,
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+fn to_trade_msg(market_type: MarketType, raw_trade: SpotTradeMsg) -> TradeMsg {
+    let price = raw_trade.price.parse::<f64>().unwrap();
+    let quantity = raw_trade.quantity.parse::<f64>().unwrap();
+
+    TradeMsg {
+        exchange: EXCHANGE_NAME.to_string(),
+        market_type,
+        symbol: raw_trade.symbol.clone(),
+        pair: crypto_pair::normalize_pair(&raw_trade.symbol, EXCHANGE_NAME).unwrap(),
+        msg_type: MessageType::Trade,
+        timestamp: raw_trade.timestamp,
+        price,
+        quantity_base: quantity,
+        quantity_quote: price * quantity,
+        quantity_contract: None,
+        side: if raw_trade.side == "sell" {
+            TradeSide::Sell
+        } else {
+            TradeSide::Buy
+        },
+        trade_id: raw_trade.event_id.to_string(),
+        raw: serde_json::to_value(&raw_trade).unwrap(),
+    }
+}
+
+pub(crate) fn parse_trade(market_type: MarketType, msg: &str) -> Result<Vec<TradeMsg>> {
+    let ws_msg = serde_json::from_str::<WebsocketMsg>(msg)?;
+    let raw_trades = if ws_msg.type_ == "l2_updates" {
+        serde_json::from_str::<L2UpdatesMsg>(msg)?
+            .trades
+            .unwrap_or_default()
+    } else {
+        vec![serde_json::from_str::<SpotTradeMsg>(msg)?]
+    };
+
+    let trades: Vec<TradeMsg> = raw_trades
+        .into_iter()
+        .map(|raw_trade| to_trade_msg(market_type, raw_trade))
+        .collect();
+
+    Ok(trades)
+}
+
+fn parse_change(raw_order: &[String; 3]) -> Order {
+    let price = raw_order[1].parse::<f64>().unwrap();
+    let quantity_base = raw_order[2].parse::<f64>().unwrap();
+
+    Order {
+        price,
+        quantity_base,
+        quantity_quote: price * quantity_base,
+        quantity_contract: None,
+    }
+}
+
+pub(crate) fn parse_l2(market_type: MarketType, msg: &str) -> Result<Vec<OrderBookMsg>> {
+    let ws_msg = serde_json::from_str::<WebsocketMsg>(msg)?;
+    if ws_msg.type_ != "l2_updates" {
+        return Ok(Vec::new());
+    }
+    let l2_updates = serde_json::from_str::<L2UpdatesMsg>(msg)?;
+    let symbol = l2_updates.symbol;
+    let pair = crypto_pair::normalize_pair(&symbol, EXCHANGE_NAME).unwrap();
+
+    let orderbook = OrderBookMsg {
+        exchange: EXCHANGE_NAME.to_string(),
+        market_type,
+        symbol,
+        pair,
+        msg_type: MessageType::L2Event,
+        // l2_updates messages have no timestamp
+        timestamp: Utc::now().timestamp_millis(),
+        asks: l2_updates
+            .changes
+            .iter()
+            .filter(|x| x[0] == "sell")
+            .map(parse_change)
+            .collect(),
+        bids: l2_updates
+            .changes
+            .iter()
+            .filter(|x| x[0] == "buy")
+            .map(parse_change)
+            .collect(),
+        snapshot: l2_updates.trades.is_some(),
+        raw: serde_json::from_str(msg)?,
+    };
+
+    Ok(vec![orderbook])
+}
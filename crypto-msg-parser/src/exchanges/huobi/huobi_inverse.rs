@@ -115,7 +115,16 @@ pub(crate) fn parse_l2(market_type: MarketType, msg: &str) -> Result<Vec<OrderBo
         let quantity = raw_order[1];
 
         let (quantity_base, quantity_quote, quantity_contract) =
-            calc_quantity_and_volume(EXCHANGE_NAME, market_type, &pair, price, quantity);
+            if market_type == MarketType::EuropeanOption {
+                // Huobi options are linear, same as LinearSwap
+                let contract_value =
+                    crypto_contract_value::get_contract_value(EXCHANGE_NAME, market_type, &pair)
+                        .unwrap();
+                let quantity_base = quantity * contract_value;
+                (quantity_base, quantity_base * price, Some(quantity))
+            } else {
+                calc_quantity_and_volume(EXCHANGE_NAME, market_type, &pair, price, quantity)
+            };
         Order {
             price,
             quantity_base,
@@ -0,0 +1,185 @@
+use crypto_market_type::MarketType;
+
+use crate::{
+    exchanges::utils::calc_quantity_and_volume, MessageType, Order, OrderBookMsg, TradeMsg,
+    TradeSide,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Result, Value};
+use std::collections::HashMap;
+
+const EXCHANGE_NAME: &str = "kraken";
+
+// https://docs.futures.kraken.com/#websocket-api-public-feeds-trade
+#[derive(Serialize, Deserialize)]
+struct FuturesTradeMsg {
+    product_id: String,
+    uid: String,
+    side: String, // buy, sell
+    #[serde(rename = "type")]
+    type_: String,
+    seq: i64,
+    time: i64,
+    qty: f64,
+    price: f64,
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TradeSnapshot {
+    product_id: String,
+    trades: Vec<FuturesTradeMsg>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RawOrder {
+    price: f64,
+    qty: f64,
+}
+
+// https://docs.futures.kraken.com/#websocket-api-public-feeds-book
+#[derive(Serialize, Deserialize)]
+struct OrderbookSnapshot {
+    product_id: String,
+    timestamp: i64,
+    seq: i64,
+    bids: Vec<RawOrder>,
+    asks: Vec<RawOrder>,
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct OrderbookUpdate {
+    product_id: String,
+    side: String, // buy, sell
+    seq: i64,
+    price: f64,
+    qty: f64,
+    timestamp: i64,
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct WebsocketMsg {
+    feed: String,
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+pub(super) fn parse_trade(market_type: MarketType, msg: &str) -> Result<Vec<TradeMsg>> {
+    let ws_msg = serde_json::from_str::<WebsocketMsg>(msg)?;
+    // A trade_snapshot of recent trades is sent after subscribing
+    let raw_trades = if ws_msg.feed == "trade_snapshot" {
+        serde_json::from_str::<TradeSnapshot>(msg)?.trades
+    } else {
+        vec![serde_json::from_str::<FuturesTradeMsg>(msg)?]
+    };
+
+    let trades: Vec<TradeMsg> = raw_trades
+        .into_iter()
+        .map(|raw_trade| {
+            let pair = crypto_pair::normalize_pair(&raw_trade.product_id, EXCHANGE_NAME).unwrap();
+            let (quantity_base, quantity_quote, quantity_contract) = calc_quantity_and_volume(
+                EXCHANGE_NAME,
+                market_type,
+                &pair,
+                raw_trade.price,
+                raw_trade.qty,
+            );
+            TradeMsg {
+                exchange: EXCHANGE_NAME.to_string(),
+                market_type,
+                symbol: raw_trade.product_id.clone(),
+                pair,
+                msg_type: MessageType::Trade,
+                timestamp: raw_trade.time,
+                price: raw_trade.price,
+                quantity_base,
+                quantity_quote,
+                quantity_contract,
+                side: if raw_trade.side == "sell" {
+                    TradeSide::Sell
+                } else {
+                    TradeSide::Buy
+                },
+                trade_id: raw_trade.uid.clone(),
+                raw: serde_json::to_value(&raw_trade).unwrap(),
+            }
+        })
+        .collect();
+
+    Ok(trades)
+}
+
+pub(super) fn parse_l2(market_type: MarketType, msg: &str) -> Result<Vec<OrderBookMsg>> {
+    let ws_msg = serde_json::from_str::<WebsocketMsg>(msg)?;
+    let snapshot = ws_msg.feed == "book_snapshot";
+
+    let (symbol, timestamp, raw_asks, raw_bids) = if snapshot {
+        let raw_snapshot = serde_json::from_str::<OrderbookSnapshot>(msg)?;
+        (
+            raw_snapshot.product_id,
+            raw_snapshot.timestamp,
+            raw_snapshot.asks,
+            raw_snapshot.bids,
+        )
+    } else {
+        // Each update contains only one price level
+        let raw_update = serde_json::from_str::<OrderbookUpdate>(msg)?;
+        let raw_order = vec![RawOrder {
+            price: raw_update.price,
+            qty: raw_update.qty,
+        }];
+        if raw_update.side == "sell" {
+            (
+                raw_update.product_id,
+                raw_update.timestamp,
+                raw_order,
+                Vec::new(),
+            )
+        } else {
+            (
+                raw_update.product_id,
+                raw_update.timestamp,
+                Vec::new(),
+                raw_order,
+            )
+        }
+    };
+    let pair = crypto_pair::normalize_pair(&symbol, EXCHANGE_NAME).unwrap();
+
+    let parse_order = |raw_order: &RawOrder| -> Order {
+        let (quantity_base, quantity_quote, quantity_contract) = calc_quantity_and_volume(
+            EXCHANGE_NAME,
+            market_type,
+            &pair,
+            raw_order.price,
+            raw_order.qty,
+        );
+        Order {
+            price: raw_order.price,
+            quantity_base,
+            quantity_quote,
+            quantity_contract,
+        }
+    };
+
+    let orderbook = OrderBookMsg {
+        exchange: EXCHANGE_NAME.to_string(),
+        market_type,
+        symbol: symbol.clone(),
+        pair: pair.clone(),
+        msg_type: MessageType::L2Event,
+        timestamp,
+        asks: raw_asks.iter().map(&parse_order).collect(),
+        bids: raw_bids.iter().map(parse_order).collect(),
+        snapshot,
+        raw: serde_json::from_str(msg)?,
+    };
+
+    Ok(vec![orderbook])
+}
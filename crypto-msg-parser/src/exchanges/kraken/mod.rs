@@ -0,0 +1,28 @@
+mod kraken_futures;
+mod kraken_spot;
+
+use crypto_market_type::MarketType;
+
+use crate::{OrderBookMsg, TradeMsg};
+
+use serde_json::Result;
+
+pub(crate) fn parse_trade(market_type: MarketType, msg: &str) -> Result<Vec<TradeMsg>> {
+    match market_type {
+        MarketType::Spot => kraken_spot::parse_trade(market_type, msg),
+        MarketType::InverseFuture | MarketType::InverseSwap => {
+            kraken_futures::parse_trade(market_type, msg)
+        }
+        _ => panic!("Unknown market type {}", market_type),
+    }
+}
+
+pub(crate) fn parse_l2(market_type: MarketType, msg: &str) -> Result<Vec<OrderBookMsg>> {
+    match market_type {
+        MarketType::Spot => kraken_spot::parse_l2(market_type, msg),
+        MarketType::InverseFuture | MarketType::InverseSwap => {
+            kraken_futures::parse_l2(market_type, msg)
+        }
+        _ => panic!("Unknown market type {}", market_type),
+    }
+}
@@ -1,10 +1,11 @@
 use crypto_market_type::MarketType;
 
 use crate::{
-    exchanges::utils::calc_quantity_and_volume, MessageType, Order, OrderBookMsg, TradeMsg,
-    TradeSide,
+    exchanges::utils::calc_quantity_and_volume, FundingRateMsg, MessageType, Order, OrderBookMsg,
+    TradeMsg, TradeSide,
 };
 
+use chrono::prelude::*;
 use serde::{Deserialize, Serialize};
 use serde_json::{Result, Value};
 use std::collections::HashMap;
@@ -142,3 +143,45 @@ pub(crate) fn parse_l2(market_type: MarketType, msg: &str) -> Result<Vec<OrderBo
 
     Ok(vec![orderbook])
 }
+
+// https://docs.kucoin.cc/futures/#get-open-contract-list
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+struct RawFundingRateMsg {
+    symbol: String,
+    fundingFeeRate: f64,
+    predictedFundingFeeRate: f64,
+    nextFundingRateTime: i64, // milliseconds until the next settlement
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RestfulMsg<T: Sized> {
+    code: String,
+    data: T,
+}
+
+pub(crate) fn parse_funding_rate(
+    market_type: MarketType,
+    msg: &str,
+) -> Result<Vec<FundingRateMsg>> {
+    let rest_msg = serde_json::from_str::<RestfulMsg<RawFundingRateMsg>>(msg)?;
+    let raw_msg = rest_msg.data;
+    let timestamp = Utc::now().timestamp_millis();
+    // KuCoin returns the remaining time, so round it to the nearest hour
+    let funding_time = (timestamp + raw_msg.nextFundingRateTime + 1800000) / 3600000 * 3600000;
+    let funding_rate = FundingRateMsg {
+        exchange: EXCHANGE_NAME.to_string(),
+        market_type,
+        symbol: raw_msg.symbol.clone(),
+        pair: crypto_pair::normalize_pair(&raw_msg.symbol, EXCHANGE_NAME).unwrap(),
+        msg_type: MessageType::FundingRate,
+        timestamp,
+        funding_rate: raw_msg.fundingFeeRate,
+        funding_time,
+        estimated_rate: Some(raw_msg.predictedFundingFeeRate),
+        raw: serde_json::to_value(&raw_msg).unwrap(),
+    };
+    Ok(vec![funding_rate])
+}
@@ -3,7 +3,7 @@ mod mxc_swap;
 
 use crypto_market_type::MarketType;
 
-use crate::{OrderBookMsg, TradeMsg};
+use crate::{FundingRateMsg, OrderBookMsg, TradeMsg};
 
 use serde_json::Result;
 
@@ -22,3 +22,14 @@ pub(crate) fn parse_l2(market_type: MarketType, msg: &str) -> Result<Vec<OrderBo
         mxc_swap::parse_l2(market_type, msg)
     }
 }
+
+pub(crate) fn parse_funding_rate(
+    market_type: MarketType,
+    msg: &str,
+) -> Result<Vec<FundingRateMsg>> {
+    if market_type == MarketType::InverseSwap || market_type == MarketType::LinearSwap {
+        mxc_swap::parse_funding_rate(market_type, msg)
+    } else {
+        panic!("MXC {} does NOT have funding rates", market_type);
+    }
+}
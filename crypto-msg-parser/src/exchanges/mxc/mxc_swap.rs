@@ -1,7 +1,7 @@
 use crypto_market_type::MarketType;
 
 use super::super::utils::calc_quantity_and_volume;
-use crate::{MessageType, Order, OrderBookMsg, TradeMsg, TradeSide};
+use crate::{FundingRateMsg, MessageType, Order, OrderBookMsg, TradeMsg, TradeSide};
 
 use serde::{Deserialize, Serialize};
 use serde_json::{Result, Value};
@@ -114,3 +114,43 @@ pub(crate) fn parse_l2(market_type: MarketType, msg: &str) -> Result<Vec<OrderBo
 
     Ok(vec![orderbook])
 }
+
+// https://mxcdevelop.github.io/APIDoc/contract.api.cn.html#5f1d8ac3d3
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+struct RawFundingRateMsg {
+    symbol: String,
+    fundingRate: f64,
+    nextSettleTime: i64,
+    timestamp: i64,
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RestfulMsg<T: Sized> {
+    success: bool,
+    code: i64,
+    data: T,
+}
+
+pub(crate) fn parse_funding_rate(
+    market_type: MarketType,
+    msg: &str,
+) -> Result<Vec<FundingRateMsg>> {
+    let rest_msg = serde_json::from_str::<RestfulMsg<RawFundingRateMsg>>(msg)?;
+    let raw_msg = rest_msg.data;
+    let funding_rate = FundingRateMsg {
+        exchange: EXCHANGE_NAME.to_string(),
+        market_type,
+        symbol: raw_msg.symbol.clone(),
+        pair: crypto_pair::normalize_pair(&raw_msg.symbol, EXCHANGE_NAME).unwrap(),
+        msg_type: MessageType::FundingRate,
+        timestamp: raw_msg.timestamp,
+        funding_rate: raw_msg.fundingRate,
+        funding_time: raw_msg.nextSettleTime,
+        estimated_rate: None,
+        raw: serde_json::to_value(&raw_msg).unwrap(),
+    };
+    Ok(vec![funding_rate])
+}
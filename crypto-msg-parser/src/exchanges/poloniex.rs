@@ -0,0 +1,118 @@
+use crypto_market_type::MarketType;
+
+use crate::Order;
+use crate::{MessageType, OrderBookMsg, TradeMsg, TradeSide};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Result, Value};
+use std::collections::HashMap;
+
+const EXCHANGE_NAME: &str = "poloniex";
+
+// see https://docs.poloniex.com/#public-channels-market-data-trades
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SpotTradeMsg {
+    symbol: String,
+    amount: String,
+    taker_side: String, // buy, sell
+    quantity: String,
+    create_time: i64,
+    price: String,
+    id: i64,
+    ts: i64,
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+// see https://docs.poloniex.com/#public-channels-market-data-book-level-2
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SpotOrderbookMsg {
+    symbol: String,
+    create_time: i64,
+    asks: Vec<[String; 2]>,
+    bids: Vec<[String; 2]>,
+    ts: i64,
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct WebsocketMsg<T: Sized> {
+    channel: String,
+    data: Vec<T>,
+    action: Option<String>, // snapshot, update
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+pub(crate) fn parse_trade(market_type: MarketType, msg: &str) -> Result<Vec<TradeMsg>> {
+    let ws_msg = serde_json::from_str::<WebsocketMsg<SpotTradeMsg>>(msg)?;
+
+    let trades: Vec<TradeMsg> = ws_msg
+        .data
+        .into_iter()
+        .map(|raw_trade| {
+            let price = raw_trade.price.parse::<f64>().unwrap();
+            let quantity = raw_trade.quantity.parse::<f64>().unwrap();
+            TradeMsg {
+                exchange: EXCHANGE_NAME.to_string(),
+                market_type,
+                symbol: raw_trade.symbol.clone(),
+                pair: crypto_pair::normalize_pair(&raw_trade.symbol, EXCHANGE_NAME).unwrap(),
+                msg_type: MessageType::Trade,
+                timestamp: raw_trade.create_time,
+                price,
+                quantity_base: quantity,
+                quantity_quote: price * quantity,
+                quantity_contract: None,
+                side: if raw_trade.taker_side == "sell" {
+                    TradeSide::Sell
+                } else {
+                    TradeSide::Buy
+                },
+                trade_id: raw_trade.id.to_string(),
+                raw: serde_json::to_value(&raw_trade).unwrap(),
+            }
+        })
+        .collect();
+
+    Ok(trades)
+}
+
+fn parse_order(raw_order: &[String; 2]) -> Order {
+    let price = raw_order[0].parse::<f64>().unwrap();
+    let quantity_base = raw_order[1].parse::<f64>().unwrap();
+
+    Order {
+        price,
+        quantity_base,
+        quantity_quote: price * quantity_base,
+        quantity_contract: None,
+    }
+}
+
+pub(crate) fn parse_l2(market_type: MarketType, msg: &str) -> Result<Vec<OrderBookMsg>> {
+    let ws_msg = serde_json::from_str::<WebsocketMsg<SpotOrderbookMsg>>(msg)?;
+    let snapshot = ws_msg.action.as_deref() == Some("snapshot");
+
+    let orderbooks: Vec<OrderBookMsg> = ws_msg
+        .data
+        .iter()
+        .map(|raw_orderbook| OrderBookMsg {
+            exchange: EXCHANGE_NAME.to_string(),
+            market_type,
+            symbol: raw_orderbook.symbol.clone(),
+            pair: crypto_pair::normalize_pair(&raw_orderbook.symbol, EXCHANGE_NAME).unwrap(),
+            msg_type: MessageType::L2Event,
+            timestamp: raw_orderbook.ts,
+            asks: raw_orderbook.asks.iter().map(parse_order).collect(),
+            bids: raw_orderbook.bids.iter().map(parse_order).collect(),
+            snapshot,
+            raw: serde_json::to_value(raw_orderbook).unwrap(),
+        })
+        .collect();
+
+    Ok(orderbooks)
+}
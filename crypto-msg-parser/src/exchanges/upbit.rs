@@ -0,0 +1,114 @@
+use crypto_market_type::MarketType;
+
+use crate::Order;
+use crate::{MessageType, OrderBookMsg, TradeMsg, TradeSide};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Result, Value};
+use std::collections::HashMap;
+
+const EXCHANGE_NAME: &str = "upbit";
+
+// see https://global-docs.upbit.com/reference/websocket-trade
+#[derive(Serialize, Deserialize)]
+struct SpotTradeMsg {
+    #[serde(rename = "type")]
+    type_: String,
+    code: String,
+    timestamp: i64,
+    trade_timestamp: i64,
+    trade_price: f64,
+    trade_volume: f64,
+    ask_bid: String, // ASK, BID
+    sequential_id: i64,
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct OrderbookUnit {
+    ask_price: f64,
+    bid_price: f64,
+    ask_size: f64,
+    bid_size: f64,
+}
+
+// see https://global-docs.upbit.com/reference/websocket-orderbook
+#[derive(Serialize, Deserialize)]
+struct SpotOrderbookMsg {
+    #[serde(rename = "type")]
+    type_: String,
+    code: String,
+    timestamp: i64,
+    orderbook_units: Vec<OrderbookUnit>,
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+pub(crate) fn parse_trade(market_type: MarketType, msg: &str) -> Result<Vec<TradeMsg>> {
+    let raw_trade = serde_json::from_str::<SpotTradeMsg>(msg)?;
+    let price = raw_trade.trade_price;
+    let quantity = raw_trade.trade_volume;
+
+    let trade = TradeMsg {
+        exchange: EXCHANGE_NAME.to_string(),
+        market_type,
+        symbol: raw_trade.code.clone(),
+        pair: crypto_pair::normalize_pair(&raw_trade.code, EXCHANGE_NAME).unwrap(),
+        msg_type: MessageType::Trade,
+        timestamp: raw_trade.trade_timestamp,
+        price,
+        quantity_base: quantity,
+        quantity_quote: price * quantity,
+        quantity_contract: None,
+        // ask_bid is the side of the taker
+        side: if raw_trade.ask_bid == "ASK" {
+            TradeSide::Sell
+        } else {
+            TradeSide::Buy
+        },
+        trade_id: raw_trade.sequential_id.to_string(),
+        raw: serde_json::to_value(&raw_trade).unwrap(),
+    };
+
+    Ok(vec![trade])
+}
+
+fn to_order(price: f64, quantity_base: f64) -> Order {
+    Order {
+        price,
+        quantity_base,
+        quantity_quote: price * quantity_base,
+        quantity_contract: None,
+    }
+}
+
+// Every orderbook message is a snapshot of top 15 levels
+pub(crate) fn parse_l2(market_type: MarketType, msg: &str) -> Result<Vec<OrderBookMsg>> {
+    let raw_orderbook = serde_json::from_str::<SpotOrderbookMsg>(msg)?;
+    let symbol = raw_orderbook.code;
+    let pair = crypto_pair::normalize_pair(&symbol, EXCHANGE_NAME).unwrap();
+
+    let orderbook = OrderBookMsg {
+        exchange: EXCHANGE_NAME.to_string(),
+        market_type,
+        symbol,
+        pair,
+        msg_type: MessageType::L2Event,
+        timestamp: raw_orderbook.timestamp,
+        asks: raw_orderbook
+            .orderbook_units
+            .iter()
+            .map(|x| to_order(x.ask_price, x.ask_size))
+            .collect(),
+        bids: raw_orderbook
+            .orderbook_units
+            .iter()
+            .map(|x| to_order(x.bid_price, x.bid_size))
+            .collect(),
+        snapshot: true,
+        raw: serde_json::from_str(msg)?,
+    };
+
+    Ok(vec![orderbook])
+}
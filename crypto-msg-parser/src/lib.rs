@@ -24,11 +24,14 @@ pub fn parse_trade(exchange: &str, market_type: MarketType, msg: &str) -> Result
         "deribit" => exchanges::deribit::parse_trade(market_type, msg),
         "ftx" => exchanges::ftx::parse_trade(market_type, msg),
         "gate" => exchanges::gate::parse_trade(market_type, msg),
+        "gemini" => exchanges::gemini::parse_trade(market_type, msg),
         "huobi" => exchanges::huobi::parse_trade(market_type, msg),
         "kraken" => exchanges::kraken::parse_trade(market_type, msg),
         "kucoin" => exchanges::kucoin::parse_trade(market_type, msg),
         "mxc" => exchanges::mxc::parse_trade(market_type, msg),
         "okex" => exchanges::okex::parse_trade(market_type, msg),
+        "poloniex" => exchanges::poloniex::parse_trade(market_type, msg),
+        "upbit" => exchanges::upbit::parse_trade(market_type, msg),
         "zbg" => exchanges::zbg::parse_trade(market_type, msg),
         _ => panic!("Unknown exchange {}", exchange),
     }
@@ -49,11 +52,14 @@ pub fn parse_l2(exchange: &str, market_type: MarketType, msg: &str) -> Result<Ve
         "deribit" => exchanges::deribit::parse_l2(market_type, msg),
         "ftx" => exchanges::ftx::parse_l2(market_type, msg),
         "gate" => exchanges::gate::parse_l2(market_type, msg),
+        "gemini" => exchanges::gemini::parse_l2(market_type, msg),
         "huobi" => exchanges::huobi::parse_l2(market_type, msg),
         "kraken" => exchanges::kraken::parse_l2(market_type, msg),
         "kucoin" => exchanges::kucoin::parse_l2(market_type, msg),
         "mxc" => exchanges::mxc::parse_l2(market_type, msg),
         "okex" => exchanges::okex::parse_l2(market_type, msg),
+        "poloniex" => exchanges::poloniex::parse_l2(market_type, msg),
+        "upbit" => exchanges::upbit::parse_l2(market_type, msg),
         "zbg" => exchanges::zbg::parse_l2(market_type, msg),
         _ => panic!("Unknown exchange {}", exchange),
     };
@@ -76,6 +82,9 @@ pub fn parse_l2(exchange: &str, market_type: MarketType, msg: &str) -> Result<Ve
 }
 
 /// Parse funding rate messages.
+///
+/// Bitz and ZBG are not supported, since crypto-crawler doesn't crawl their
+/// funding rates.
 pub fn parse_funding_rate(
     exchange: &str,
     market_type: MarketType,
@@ -83,10 +92,18 @@ pub fn parse_funding_rate(
 ) -> Result<Vec<FundingRateMsg>> {
     let func = match exchange {
         "binance" => exchanges::binance::parse_funding_rate,
+        "bitfinex" => exchanges::bitfinex::parse_funding_rate,
         "bitget" => exchanges::bitget::parse_funding_rate,
         "bitmex" => exchanges::bitmex::parse_funding_rate,
+        "bybit" => exchanges::bybit::parse_funding_rate,
+        "deribit" => exchanges::deribit::parse_funding_rate,
+        "ftx" => exchanges::ftx::parse_funding_rate,
+        "gate" => exchanges::gate::parse_funding_rate,
         "huobi" => exchanges::huobi::parse_funding_rate,
+        "kucoin" => exchanges::kucoin::parse_funding_rate,
+        "mxc" => exchanges::mxc::parse_funding_rate,
         "okex" => exchanges::okex::parse_funding_rate,
+        "bitz" | "zbg" => panic!("{} funding rates are not supported", exchange),
         _ => panic!("{} does NOT have perpetual swap market", exchange),
     };
     func(market_type, msg)
@@ -23,6 +23,10 @@ pub enum MessageType {
     Ticker,
     Candlestick,
     FundingRate,
+    OpenInterest,
+    Liquidation,
+    MarkPrice,
+    IndexPrice,
 }
 
 macro_rules! add_common_fields {
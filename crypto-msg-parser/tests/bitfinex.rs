@@ -231,3 +231,26 @@ mod l2_orderbook {
         assert_eq!(orderbook.bids[0].quantity_contract.unwrap(), 2.27726294);
     }
 }
+
+#[cfg(test)]
+mod funding_rate {
+    use crypto_msg_parser::{parse_funding_rate, MarketType};
+
+    #[test]
+    fn linear_swap() {
+        let raw_msg = r#"[["tBTCF0:USTF0",1616305383000,null,57420,57401,null,1234567.8,null,1616313600000,0.00012,12,null,0.0001,null,null,57410,null,null,12345.6,null,null,null]]"#;
+        let funding_rates =
+            &parse_funding_rate("bitfinex", MarketType::LinearSwap, raw_msg).unwrap();
+
+        assert_eq!(funding_rates.len(), 1);
+
+        for rate in funding_rates.iter() {
+            crate::utils::check_funding_rate_fields("bitfinex", MarketType::LinearSwap, rate);
+        }
+
+        assert_eq!(funding_rates[0].pair, "BTC/USDT".to_string());
+        assert_eq!(funding_rates[0].funding_rate, 0.0001);
+        assert_eq!(funding_rates[0].funding_time, 1616313600000);
+        assert_eq!(funding_rates[0].estimated_rate, Some(0.00012));
+    }
+}
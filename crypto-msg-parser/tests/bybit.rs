@@ -4,6 +4,31 @@ mod utils;
 mod trade {
     use crypto_msg_parser::{parse_trade, MarketType, TradeSide};
 
+    #[test]
+    fn spot() {
+        let raw_msg = r#"{"symbol":"BTCUSDT","symbolName":"BTCUSDT","topic":"trade","params":{"realtimeInterval":"24h","binary":"false"},"data":[{"v":"2290000000008144071","t":1616651250071,"p":"52000.01","q":"0.001","m":true},{"v":"2290000000008144072","t":1616651250080,"p":"52000.02","q":"0.02","m":false}],"f":false,"sendTime":1616651250090}"#;
+        let trades = &parse_trade("bybit", MarketType::Spot, raw_msg).unwrap();
+
+        assert_eq!(trades.len(), 2);
+
+        for trade in trades.iter() {
+            crate::utils::check_trade_fields(
+                "bybit",
+                MarketType::Spot,
+                "BTC/USDT".to_string(),
+                trade,
+            );
+        }
+
+        assert_eq!(trades[0].quantity_base, 0.001);
+        assert_eq!(trades[0].quantity_contract, None);
+        assert_eq!(trades[0].side, TradeSide::Sell);
+        assert_eq!(trades[0].trade_id, "2290000000008144071");
+
+        assert_eq!(trades[1].quantity_base, 0.02);
+        assert_eq!(trades[1].side, TradeSide::Buy);
+    }
+
     #[test]
     fn inverse_future() {
         let raw_msg = r#"{"topic":"trade.BTCUSDM21","data":[{"trade_time_ms":1616304614117,"timestamp":"2021-03-21T05:30:14.000Z","symbol":"BTCUSDM21","side":"Buy","size":100,"price":61094.5,"tick_direction":"ZeroPlusTick","trade_id":"e61fb2dc-a658-5a7d-88fb-d166a4bd29b8","cross_seq":233452601},{"trade_time_ms":1616304614117,"timestamp":"2021-03-21T05:30:14.000Z","symbol":"BTCUSDM21","side":"Sell","size":300,"price":61097.5,"tick_direction":"ZeroPlusTick","trade_id":"2cbeff0d-16da-5946-a7b0-0ccfb78d3ab5","cross_seq":233452601}]}"#;
@@ -80,6 +105,48 @@ mod trade {
 mod l2_orderbook {
     use crypto_msg_parser::{parse_l2, MarketType};
 
+    #[test]
+    fn spot_snapshot() {
+        let raw_msg = r#"{"symbol":"BTCUSDT","symbolName":"BTCUSDT","topic":"diffDepth","params":{"binary":"false"},"data":[{"e":301,"s":"BTCUSDT","t":1616651250071,"v":"112801745_18","b":[["52000.01","0.5"],["51999.5","1.2"]],"a":[["52000.02","0.25"]],"o":0}],"f":true,"sendTime":1616651250090}"#;
+        let orderbook = &parse_l2("bybit", MarketType::Spot, raw_msg).unwrap()[0];
+
+        assert_eq!(orderbook.asks.len(), 1);
+        assert_eq!(orderbook.bids.len(), 2);
+        assert!(orderbook.snapshot);
+
+        crate::utils::check_orderbook_fields(
+            "bybit",
+            MarketType::Spot,
+            "BTC/USDT".to_string(),
+            orderbook,
+        );
+
+        assert_eq!(orderbook.timestamp, 1616651250071);
+
+        assert_eq!(orderbook.bids[0].price, 52000.01);
+        assert_eq!(orderbook.bids[0].quantity_base, 0.5);
+        assert_eq!(orderbook.bids[0].quantity_quote, 52000.01 * 0.5);
+        assert_eq!(orderbook.bids[0].quantity_contract, None);
+
+        assert_eq!(orderbook.asks[0].price, 52000.02);
+        assert_eq!(orderbook.asks[0].quantity_base, 0.25);
+        assert_eq!(orderbook.asks[0].quantity_quote, 52000.02 * 0.25);
+    }
+
+    #[test]
+    fn spot_update() {
+        let raw_msg = r#"{"symbol":"BTCUSDT","symbolName":"BTCUSDT","topic":"diffDepth","params":{"binary":"false"},"data":[{"e":301,"s":"BTCUSDT","t":1616651250171,"v":"112801745_19","b":[["52000.01","0"]],"a":[],"o":0}],"f":false,"sendTime":1616651250190}"#;
+        let orderbook = &parse_l2("bybit", MarketType::Spot, raw_msg).unwrap()[0];
+
+        assert_eq!(orderbook.asks.len(), 0);
+        assert_eq!(orderbook.bids.len(), 1);
+        assert!(!orderbook.snapshot);
+
+        assert_eq!(orderbook.timestamp, 1616651250171);
+        assert_eq!(orderbook.bids[0].price, 52000.01);
+        assert_eq!(orderbook.bids[0].quantity_base, 0.0);
+    }
+
     #[test]
     fn inverse_future_snapshot() {
         let raw_msg = r#"{"topic":"orderBookL2_25.BTCUSDM21","type":"snapshot","data":[{"price":"36338.50","symbol":"BTCUSDM21","id":363385000,"side":"Buy","size":85235},{"price":"36344.50","symbol":"BTCUSDM21","id":363445000,"side":"Buy","size":1947},{"price":"36346.00","symbol":"BTCUSDM21","id":363460000,"side":"Buy","size":234},{"price":"36400.00","symbol":"BTCUSDM21","id":364000000,"side":"Sell","size":12500},{"price":"36407.50","symbol":"BTCUSDM21","id":364075000,"side":"Sell","size":21460},{"price":"36408.00","symbol":"BTCUSDM21","id":364080000,"side":"Sell","size":40076}],"cross_seq":2573025748,"timestamp_e6":1622538339073398}"#;
@@ -299,3 +366,74 @@ mod l2_orderbook {
         assert_eq!(orderbook.bids[0].quantity_contract.unwrap(), 6.906);
     }
 }
+
+#[cfg(test)]
+mod funding_rate {
+    use crypto_msg_parser::{parse_funding_rate, MarketType};
+
+    #[test]
+    fn inverse_swap_snapshot() {
+        let raw_msg = r#"{"topic":"instrument_info.100ms.BTCUSD","type":"snapshot","data":{"id":1,"symbol":"BTCUSD","last_price_e4":574205000,"funding_rate_e6":100,"predicted_funding_rate_e6":-31,"next_funding_time":"2021-03-21T08:00:00Z","countdown_hour":2},"cross_seq":5127382938,"timestamp_e6":1616305383061040}"#;
+        let funding_rates = &parse_funding_rate("bybit", MarketType::InverseSwap, raw_msg).unwrap();
+
+        assert_eq!(funding_rates.len(), 1);
+
+        for rate in funding_rates.iter() {
+            crate::utils::check_funding_rate_fields("bybit", MarketType::InverseSwap, rate);
+        }
+
+        assert_eq!(funding_rates[0].pair, "BTC/USD".to_string());
+        assert_eq!(funding_rates[0].funding_rate, 0.0001);
+        assert_eq!(funding_rates[0].funding_time, 1616313600000);
+        assert_eq!(funding_rates[0].estimated_rate, Some(-0.000031));
+    }
+
+    #[test]
+    fn linear_swap_delta() {
+        let raw_msg = r#"{"topic":"instrument_info.100ms.BTCUSDT","type":"delta","data":{"delete":[],"update":[{"id":1,"symbol":"BTCUSDT","funding_rate_e6":100,"predicted_funding_rate_e6":95,"next_funding_time":"2021-03-21T08:00:00Z","countdown_hour":2}],"insert":[]},"cross_seq":"5127382938","timestamp_e6":"1616305383061040"}"#;
+        let funding_rates = &parse_funding_rate("bybit", MarketType::LinearSwap, raw_msg).unwrap();
+
+        assert_eq!(funding_rates.len(), 1);
+
+        for rate in funding_rates.iter() {
+            crate::utils::check_funding_rate_fields("bybit", MarketType::LinearSwap, rate);
+        }
+
+        assert_eq!(funding_rates[0].pair, "BTC/USDT".to_string());
+        assert_eq!(funding_rates[0].funding_rate, 0.0001);
+        assert_eq!(funding_rates[0].funding_time, 1616313600000);
+        assert_eq!(funding_rates[0].estimated_rate, Some(0.000095));
+    }
+
+    #[test]
+    fn inverse_swap_delta_without_funding() {
+        let raw_msg = r#"{"topic":"instrument_info.100ms.BTCUSD","type":"delta","data":{"delete":[],"update":[{"id":1,"symbol":"BTCUSD","last_price_e4":574210000,"last_tick_direction":"PlusTick"}],"insert":[]},"cross_seq":5127382939,"timestamp_e6":1616305383161040}"#;
+        let funding_rates = &parse_funding_rate("bybit", MarketType::InverseSwap, raw_msg).unwrap();
+
+        assert!(funding_rates.is_empty());
+    }
+
+    #[test]
+    fn inverse_swap_delta_with_predicted_rate_only() {
+        let snapshot_msg = r#"{"topic":"instrument_info.100ms.ETHUSD","type":"snapshot","data":{"id":2,"symbol":"ETHUSD","last_price_e4":17802500,"funding_rate_e6":100,"predicted_funding_rate_e6":80,"next_funding_time":"2021-03-21T08:00:00Z","countdown_hour":2},"cross_seq":2318192310,"timestamp_e6":1616305383061040}"#;
+        let funding_rates =
+            &parse_funding_rate("bybit", MarketType::InverseSwap, snapshot_msg).unwrap();
+        assert_eq!(funding_rates.len(), 1);
+
+        let delta_msg = r#"{"topic":"instrument_info.100ms.ETHUSD","type":"delta","data":{"delete":[],"update":[{"id":2,"symbol":"ETHUSD","predicted_funding_rate_e6":-25,"cross_seq":2318192311,"created_at":"1970-01-01T00:00:00.000Z","updated_at":"2021-03-21T05:43:04.000Z"}],"insert":[]},"cross_seq":2318192311,"timestamp_e6":1616305384061040}"#;
+        let funding_rates =
+            &parse_funding_rate("bybit", MarketType::InverseSwap, delta_msg).unwrap();
+
+        assert_eq!(funding_rates.len(), 1);
+
+        for rate in funding_rates.iter() {
+            crate::utils::check_funding_rate_fields("bybit", MarketType::InverseSwap, rate);
+        }
+
+        assert_eq!(funding_rates[0].pair, "ETH/USD".to_string());
+        assert_eq!(funding_rates[0].funding_rate, 0.0001);
+        assert_eq!(funding_rates[0].funding_time, 1616313600000);
+        assert_eq!(funding_rates[0].estimated_rate, Some(-0.000025));
+        assert_eq!(funding_rates[0].timestamp, 1616305384061);
+    }
+}
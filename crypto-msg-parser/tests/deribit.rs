@@ -261,3 +261,25 @@ mod l2_orderbook {
         assert_eq!(orderbook.asks[2].quantity_contract.unwrap(), 0.5);
     }
 }
+
+#[cfg(test)]
+mod funding_rate {
+    use crypto_msg_parser::{parse_funding_rate, MarketType};
+
+    #[test]
+    fn inverse_swap() {
+        let raw_msg = r#"{"jsonrpc":"2.0","method":"subscription","params":{"channel":"ticker.BTC-PERPETUAL.100ms","data":{"timestamp":1616305383061,"instrument_name":"BTC-PERPETUAL","current_funding":0.0,"funding_8h":0.00013,"mark_price":57420.6,"index_price":57401.3,"open_interest":1134859650}}}"#;
+        let funding_rates =
+            &parse_funding_rate("deribit", MarketType::InverseSwap, raw_msg).unwrap();
+
+        assert_eq!(funding_rates.len(), 1);
+
+        for rate in funding_rates.iter() {
+            crate::utils::check_funding_rate_fields("deribit", MarketType::InverseSwap, rate);
+        }
+
+        assert_eq!(funding_rates[0].pair, "BTC/USD".to_string());
+        assert_eq!(funding_rates[0].funding_rate, 0.00013);
+        assert_eq!(funding_rates[0].funding_time, 1616313600000);
+    }
+}
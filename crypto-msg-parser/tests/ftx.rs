@@ -287,3 +287,24 @@ mod l2_orderbook {
         assert_eq!(orderbook.asks[0].quantity_contract.unwrap(), 19.6097);
     }
 }
+
+#[cfg(test)]
+mod funding_rate {
+    use crypto_msg_parser::{parse_funding_rate, MarketType};
+
+    #[test]
+    fn linear_swap() {
+        let raw_msg = r#"{"success":true,"result":[{"future":"BTC-PERP","rate":0.0001,"time":"2021-03-21T08:00:00+00:00"}]}"#;
+        let funding_rates = &parse_funding_rate("ftx", MarketType::LinearSwap, raw_msg).unwrap();
+
+        assert_eq!(funding_rates.len(), 1);
+
+        for rate in funding_rates.iter() {
+            crate::utils::check_funding_rate_fields("ftx", MarketType::LinearSwap, rate);
+        }
+
+        assert_eq!(funding_rates[0].pair, "BTC/USD".to_string());
+        assert_eq!(funding_rates[0].funding_rate, 0.0001);
+        assert_eq!(funding_rates[0].funding_time, 1616313600000);
+    }
+}
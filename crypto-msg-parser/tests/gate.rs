@@ -353,3 +353,42 @@ mod l2_orderbook {
         assert_eq!(orderbook.bids[0].quantity_contract.unwrap(), 500.0);
     }
 }
+
+#[cfg(test)]
+mod funding_rate {
+    use crypto_msg_parser::{parse_funding_rate, MarketType};
+
+    #[test]
+    fn inverse_swap() {
+        let raw_msg = r#"{"name":"BTC_USD","type":"inverse","quanto_multiplier":"0","mark_price":"57420.6","index_price":"57401.3","funding_rate":"0.000152","funding_interval":28800,"funding_next_apply":1616313600,"funding_rate_indicative":"0.00011"}"#;
+        let funding_rates = &parse_funding_rate("gate", MarketType::InverseSwap, raw_msg).unwrap();
+
+        assert_eq!(funding_rates.len(), 1);
+
+        for rate in funding_rates.iter() {
+            crate::utils::check_funding_rate_fields("gate", MarketType::InverseSwap, rate);
+        }
+
+        assert_eq!(funding_rates[0].pair, "BTC/USD".to_string());
+        assert_eq!(funding_rates[0].funding_rate, 0.000152);
+        assert_eq!(funding_rates[0].funding_time, 1616313600000);
+        assert_eq!(funding_rates[0].estimated_rate, Some(0.00011));
+    }
+
+    #[test]
+    fn linear_swap() {
+        let raw_msg = r#"{"name":"BTC_USDT","type":"direct","quanto_multiplier":"0.0001","mark_price":"57420.6","index_price":"57401.3","funding_rate":"-0.000021","funding_interval":28800,"funding_next_apply":1616313600,"funding_rate_indicative":"0.000035"}"#;
+        let funding_rates = &parse_funding_rate("gate", MarketType::LinearSwap, raw_msg).unwrap();
+
+        assert_eq!(funding_rates.len(), 1);
+
+        for rate in funding_rates.iter() {
+            crate::utils::check_funding_rate_fields("gate", MarketType::LinearSwap, rate);
+        }
+
+        assert_eq!(funding_rates[0].pair, "BTC/USDT".to_string());
+        assert_eq!(funding_rates[0].funding_rate, -0.000021);
+        assert_eq!(funding_rates[0].funding_time, 1616313600000);
+        assert_eq!(funding_rates[0].estimated_rate, Some(0.000035));
+    }
+}
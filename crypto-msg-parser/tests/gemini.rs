@@ -0,0 +1,84 @@
+mod utils;
+
+use crypto_msg_parser::{parse_l2, parse_trade, MarketType, TradeSide};
+
+#[test]
+fn trade() {
+    let raw_msg = r#"{"type":"trade","symbol":"BTCUSD","event_id":1616651250071,"timestamp":1616651250071,"price":"52000.01","quantity":"0.0073173","side":"sell"}"#;
+    let trades = &parse_trade("gemini", MarketType::Spot, raw_msg).unwrap();
+    assert_eq!(trades.len(), 1);
+    let trade = &trades[0];
+
+    crate::utils::check_trade_fields("gemini", MarketType::Spot, "BTC/USD".to_string(), trade);
+
+    assert_eq!(trade.quantity_base, 0.0073173);
+    assert_eq!(trade.timestamp, 1616651250071);
+    assert_eq!(trade.side, TradeSide::Sell);
+}
+
+#[test]
+fn trades_in_snapshot() {
+    let raw_msg = r#"{"type":"l2_updates","symbol":"ETHBTC","changes":[["buy","0.03151","12.5"],["sell","0.03152","3.2"]],"trades":[{"type":"trade","symbol":"ETHBTC","event_id":169841458,"timestamp":1616651250070,"price":"0.03151","quantity":"0.5","side":"buy"},{"type":"trade","symbol":"ETHBTC","event_id":169841459,"timestamp":1616651250071,"price":"0.03152","quantity":"0.25","side":"sell"}]}"#;
+    let trades = &parse_trade("gemini", MarketType::Spot, raw_msg).unwrap();
+    assert_eq!(trades.len(), 2);
+
+    for trade in trades.iter() {
+        crate::utils::check_trade_fields("gemini", MarketType::Spot, "ETH/BTC".to_string(), trade);
+    }
+    assert_eq!(trades[0].side, TradeSide::Buy);
+    assert_eq!(trades[1].side, TradeSide::Sell);
+
+    // l2_updates without trades
+    let raw_msg = r#"{"type":"l2_updates","symbol":"ETHBTC","changes":[["sell","0.03152","0.0"]]}"#;
+    assert!(parse_trade("gemini", MarketType::Spot, raw_msg)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn l2_orderbook_snapshot() {
+    let raw_msg = r#"{"type":"l2_updates","symbol":"BTCUSD","changes":[["buy","52000.01","0.5"],["buy","51999.5","1.25"],["sell","52000.02","0.25"]],"trades":[{"type":"trade","symbol":"BTCUSD","event_id":1616651250071,"timestamp":1616651250071,"price":"52000.01","quantity":"0.01","side":"sell"}]}"#;
+    let orderbook = &parse_l2("gemini", MarketType::Spot, raw_msg).unwrap()[0];
+
+    assert_eq!(orderbook.asks.len(), 1);
+    assert_eq!(orderbook.bids.len(), 2);
+    assert!(orderbook.snapshot);
+
+    crate::utils::check_orderbook_fields(
+        "gemini",
+        MarketType::Spot,
+        "BTC/USD".to_string(),
+        orderbook,
+    );
+
+    assert_eq!(orderbook.bids[0].price, 52000.01);
+    assert_eq!(orderbook.bids[0].quantity_base, 0.5);
+    assert_eq!(orderbook.bids[0].quantity_quote, 52000.01 * 0.5);
+
+    assert_eq!(orderbook.bids[1].price, 51999.5);
+    assert_eq!(orderbook.bids[1].quantity_base, 1.25);
+
+    assert_eq!(orderbook.asks[0].price, 52000.02);
+    assert_eq!(orderbook.asks[0].quantity_base, 0.25);
+}
+
+#[test]
+fn l2_orderbook_update() {
+    let raw_msg =
+        r#"{"type":"l2_updates","symbol":"BTCUSD","changes":[["sell","52000.02","0.0"]]}"#;
+    let orderbook = &parse_l2("gemini", MarketType::Spot, raw_msg).unwrap()[0];
+
+    assert_eq!(orderbook.asks.len(), 1);
+    assert_eq!(orderbook.bids.len(), 0);
+    assert!(!orderbook.snapshot);
+
+    crate::utils::check_orderbook_fields(
+        "gemini",
+        MarketType::Spot,
+        "BTC/USD".to_string(),
+        orderbook,
+    );
+
+    assert_eq!(orderbook.asks[0].price, 52000.02);
+    assert_eq!(orderbook.asks[0].quantity_base, 0.0);
+}
@@ -437,4 +437,33 @@ mod l2_orderbook {
         assert_eq!(orderbook.bids[0].quantity_quote, 39050.8 * 0.04);
         assert_eq!(orderbook.bids[0].quantity_contract.unwrap(), 40.0);
     }
+
+    #[test]
+    fn linear_option_snapshot() {
+        let raw_msg = r#"{"ch":"market.BTC-USDT-210326-C-32000.depth.size_150.high_freq","tick":{"asks":[[2000,50],[2010.5,120]],"bids":[[1980,30]],"ch":"market.BTC-USDT-210326-C-32000.depth.size_150.high_freq","event":"snapshot","id":674495368,"mrid":674495368,"ts":1616246303133,"version":2048},"ts":1616246303142}"#;
+        let orderbook = &parse_l2("huobi", MarketType::EuropeanOption, raw_msg).unwrap()[0];
+
+        assert_eq!(orderbook.asks.len(), 2);
+        assert_eq!(orderbook.bids.len(), 1);
+        assert!(orderbook.snapshot);
+
+        crate::utils::check_orderbook_fields(
+            "huobi",
+            MarketType::EuropeanOption,
+            "BTC/USDT".to_string(),
+            orderbook,
+        );
+
+        assert_eq!(orderbook.timestamp, 1616246303133);
+
+        assert_eq!(orderbook.asks[0].price, 2000.0);
+        assert_eq!(orderbook.asks[0].quantity_base, 0.05);
+        assert_eq!(orderbook.asks[0].quantity_quote, 2000.0 * 0.05);
+        assert_eq!(orderbook.asks[0].quantity_contract.unwrap(), 50.0);
+
+        assert_eq!(orderbook.bids[0].price, 1980.0);
+        assert_eq!(orderbook.bids[0].quantity_base, 0.03);
+        assert_eq!(orderbook.bids[0].quantity_quote, 1980.0 * 0.03);
+        assert_eq!(orderbook.bids[0].quantity_contract.unwrap(), 30.0);
+    }
 }
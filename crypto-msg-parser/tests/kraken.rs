@@ -95,3 +95,97 @@ fn l2_orderbook_update() {
     assert_eq!(orderbook.bids[0].quantity_base, 0.03017320);
     assert_eq!(orderbook.bids[0].quantity_quote, 38800.0 * 0.03017320);
 }
+
+#[test]
+fn futures_trade() {
+    let raw_msg = r#"{"feed":"trade","product_id":"PI_XBTUSD","uid":"05af78ac-a774-478c-a50c-8b9c234e071e","side":"sell","type":"fill","seq":653355,"time":1612266317519,"qty":15000.0,"price":34969.5}"#;
+    let trade = &parse_trade("kraken", MarketType::InverseSwap, raw_msg).unwrap()[0];
+
+    crate::utils::check_trade_fields(
+        "kraken",
+        MarketType::InverseSwap,
+        "BTC/USD".to_string(),
+        trade,
+    );
+
+    assert_eq!(trade.symbol, "PI_XBTUSD");
+    assert_eq!(trade.timestamp, 1612266317519);
+    assert_eq!(trade.quantity_quote, 15000.0);
+    assert_eq!(trade.quantity_base, 15000.0 / 34969.5);
+    assert_eq!(trade.quantity_contract, Some(15000.0));
+    assert_eq!(trade.side, TradeSide::Sell);
+}
+
+#[test]
+fn futures_trade_snapshot() {
+    let raw_msg = r#"{"feed":"trade_snapshot","product_id":"FI_XBTUSD_210625","trades":[{"feed":"trade","product_id":"FI_XBTUSD_210625","uid":"caa9c653-420b-4c24-a9f1-462a054d86f1","side":"buy","type":"fill","seq":2306,"time":1612266317519,"qty":2000.0,"price":36522.5},{"feed":"trade","product_id":"FI_XBTUSD_210625","uid":"45ee9737-1877-4682-bc68-e4ef818ef88a","side":"sell","type":"liquidation","seq":2305,"time":1612266299136,"qty":100.0,"price":36520.0}]}"#;
+    let trades = &parse_trade("kraken", MarketType::InverseFuture, raw_msg).unwrap();
+    assert_eq!(trades.len(), 2);
+
+    for trade in trades.iter() {
+        crate::utils::check_trade_fields(
+            "kraken",
+            MarketType::InverseFuture,
+            "BTC/USD".to_string(),
+            trade,
+        );
+    }
+
+    assert_eq!(trades[0].quantity_contract, Some(2000.0));
+    assert_eq!(trades[0].side, TradeSide::Buy);
+    assert_eq!(trades[1].quantity_contract, Some(100.0));
+    assert_eq!(trades[1].side, TradeSide::Sell);
+}
+
+#[test]
+fn futures_l2_orderbook_snapshot() {
+    let raw_msg = r#"{"feed":"book_snapshot","product_id":"PI_XBTUSD","timestamp":1612269825817,"seq":326072249,"tickSize":null,"bids":[{"price":34892.5,"qty":6385.0},{"price":34892.0,"qty":10924.0}],"asks":[{"price":34911.5,"qty":20598.0},{"price":34912.0,"qty":2300.0}]}"#;
+    let orderbook = &parse_l2("kraken", MarketType::InverseSwap, raw_msg).unwrap()[0];
+
+    assert_eq!(orderbook.asks.len(), 2);
+    assert_eq!(orderbook.bids.len(), 2);
+    assert!(orderbook.snapshot);
+
+    crate::utils::check_orderbook_fields(
+        "kraken",
+        MarketType::InverseSwap,
+        "BTC/USD".to_string(),
+        orderbook,
+    );
+
+    assert_eq!(orderbook.timestamp, 1612269825817);
+
+    assert_eq!(orderbook.bids[0].price, 34892.5);
+    assert_eq!(orderbook.bids[0].quantity_quote, 6385.0);
+    assert_eq!(orderbook.bids[0].quantity_base, 6385.0 / 34892.5);
+    assert_eq!(orderbook.bids[0].quantity_contract.unwrap(), 6385.0);
+
+    assert_eq!(orderbook.asks[1].price, 34912.0);
+    assert_eq!(orderbook.asks[1].quantity_quote, 2300.0);
+    assert_eq!(orderbook.asks[1].quantity_base, 2300.0 / 34912.0);
+    assert_eq!(orderbook.asks[1].quantity_contract.unwrap(), 2300.0);
+}
+
+#[test]
+fn futures_l2_orderbook_update() {
+    let raw_msg = r#"{"feed":"book","product_id":"PI_XBTUSD","side":"sell","seq":326094134,"price":34981.0,"qty":0.0,"timestamp":1612269953629}"#;
+    let orderbook = &parse_l2("kraken", MarketType::InverseSwap, raw_msg).unwrap()[0];
+
+    assert_eq!(orderbook.asks.len(), 1);
+    assert_eq!(orderbook.bids.len(), 0);
+    assert!(!orderbook.snapshot);
+
+    crate::utils::check_orderbook_fields(
+        "kraken",
+        MarketType::InverseSwap,
+        "BTC/USD".to_string(),
+        orderbook,
+    );
+
+    assert_eq!(orderbook.timestamp, 1612269953629);
+
+    assert_eq!(orderbook.asks[0].price, 34981.0);
+    assert_eq!(orderbook.asks[0].quantity_base, 0.0);
+    assert_eq!(orderbook.asks[0].quantity_quote, 0.0);
+    assert_eq!(orderbook.asks[0].quantity_contract.unwrap(), 0.0);
+}
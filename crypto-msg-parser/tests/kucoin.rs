@@ -191,3 +191,41 @@ mod l2_orderbook {
         assert_eq!(orderbook.asks[0].quantity_contract.unwrap(), 11450.0);
     }
 }
+
+#[cfg(test)]
+mod funding_rate {
+    use crypto_msg_parser::{parse_funding_rate, MarketType};
+
+    #[test]
+    fn inverse_swap() {
+        let raw_msg = r#"{"code":"200000","data":{"symbol":"XBTUSDM","rootSymbol":"XBT","type":"FFWCSX","isInverse":true,"fundingFeeRate":0.000312,"predictedFundingFeeRate":0.000298,"nextFundingRateTime":18205000,"markPrice":57420.6,"indexPrice":57401.3}}"#;
+        let funding_rates =
+            &parse_funding_rate("kucoin", MarketType::InverseSwap, raw_msg).unwrap();
+
+        assert_eq!(funding_rates.len(), 1);
+
+        for rate in funding_rates.iter() {
+            crate::utils::check_funding_rate_fields("kucoin", MarketType::InverseSwap, rate);
+        }
+
+        assert_eq!(funding_rates[0].pair, "BTC/USD".to_string());
+        assert_eq!(funding_rates[0].funding_rate, 0.000312);
+        assert_eq!(funding_rates[0].estimated_rate, Some(0.000298));
+    }
+
+    #[test]
+    fn linear_swap() {
+        let raw_msg = r#"{"code":"200000","data":{"symbol":"XBTUSDTM","rootSymbol":"USDT","type":"FFWCSX","isInverse":false,"fundingFeeRate":-0.000045,"predictedFundingFeeRate":0.000012,"nextFundingRateTime":18205000,"markPrice":57420.6,"indexPrice":57401.3}}"#;
+        let funding_rates = &parse_funding_rate("kucoin", MarketType::LinearSwap, raw_msg).unwrap();
+
+        assert_eq!(funding_rates.len(), 1);
+
+        for rate in funding_rates.iter() {
+            crate::utils::check_funding_rate_fields("kucoin", MarketType::LinearSwap, rate);
+        }
+
+        assert_eq!(funding_rates[0].pair, "BTC/USDT".to_string());
+        assert_eq!(funding_rates[0].funding_rate, -0.000045);
+        assert_eq!(funding_rates[0].estimated_rate, Some(0.000012));
+    }
+}
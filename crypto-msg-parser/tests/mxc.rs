@@ -150,3 +150,24 @@ mod l2_orderbook {
         assert_eq!(orderbook.asks[0].quantity_contract.unwrap(), 4172.0);
     }
 }
+
+#[cfg(test)]
+mod funding_rate {
+    use crypto_msg_parser::{parse_funding_rate, MarketType};
+
+    #[test]
+    fn linear_swap() {
+        let raw_msg = r#"{"success":true,"code":0,"data":{"symbol":"BTC_USDT","fundingRate":0.0001,"maxFundingRate":0.003,"minFundingRate":-0.003,"collectCycle":8,"nextSettleTime":1616313600000,"timestamp":1616305383000}}"#;
+        let funding_rates = &parse_funding_rate("mxc", MarketType::LinearSwap, raw_msg).unwrap();
+
+        assert_eq!(funding_rates.len(), 1);
+
+        for rate in funding_rates.iter() {
+            crate::utils::check_funding_rate_fields("mxc", MarketType::LinearSwap, rate);
+        }
+
+        assert_eq!(funding_rates[0].pair, "BTC/USDT".to_string());
+        assert_eq!(funding_rates[0].funding_rate, 0.0001);
+        assert_eq!(funding_rates[0].funding_time, 1616313600000);
+    }
+}
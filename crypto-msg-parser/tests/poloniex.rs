@@ -0,0 +1,71 @@
+mod utils;
+
+use crypto_msg_parser::{parse_l2, parse_trade, MarketType, TradeSide};
+
+#[test]
+fn trade() {
+    let raw_msg = r#"{"channel":"trades","data":[{"symbol":"BTC_USDT","amount":"520.0001","takerSide":"sell","quantity":"0.01","createTime":1616651250070,"price":"52000.01","id":60014521,"ts":1616651250071}]}"#;
+    let trades = &parse_trade("poloniex", MarketType::Spot, raw_msg).unwrap();
+    assert_eq!(trades.len(), 1);
+    let trade = &trades[0];
+
+    crate::utils::check_trade_fields("poloniex", MarketType::Spot, "BTC/USDT".to_string(), trade);
+
+    assert_eq!(trade.quantity_base, 0.01);
+    assert_eq!(trade.timestamp, 1616651250070);
+    assert_eq!(trade.trade_id, "60014521");
+    assert_eq!(trade.side, TradeSide::Sell);
+}
+
+#[test]
+fn l2_orderbook_snapshot() {
+    let raw_msg = r#"{"channel":"book_lv2","data":[{"symbol":"BTC_USDT","createTime":1616651250070,"asks":[["52000.02","0.25"],["52001.5","1.1"]],"bids":[["52000.01","0.5"],["51999.5","1.25"],["51998","2"]],"lastId":164,"id":165,"ts":1616651250071}],"action":"snapshot"}"#;
+    let orderbook = &parse_l2("poloniex", MarketType::Spot, raw_msg).unwrap()[0];
+
+    assert_eq!(orderbook.asks.len(), 2);
+    assert_eq!(orderbook.bids.len(), 3);
+    assert!(orderbook.snapshot);
+
+    crate::utils::check_orderbook_fields(
+        "poloniex",
+        MarketType::Spot,
+        "BTC/USDT".to_string(),
+        orderbook,
+    );
+
+    assert_eq!(orderbook.timestamp, 1616651250071);
+
+    assert_eq!(orderbook.bids[0].price, 52000.01);
+    assert_eq!(orderbook.bids[0].quantity_base, 0.5);
+    assert_eq!(orderbook.bids[0].quantity_quote, 52000.01 * 0.5);
+
+    assert_eq!(orderbook.bids[2].price, 51998.0);
+    assert_eq!(orderbook.bids[2].quantity_base, 2.0);
+
+    assert_eq!(orderbook.asks[0].price, 52000.02);
+    assert_eq!(orderbook.asks[0].quantity_base, 0.25);
+    assert_eq!(orderbook.asks[0].quantity_quote, 52000.02 * 0.25);
+}
+
+#[test]
+fn l2_orderbook_update() {
+    let raw_msg = r#"{"channel":"book_lv2","data":[{"symbol":"BTC_USDT","createTime":1616651250170,"asks":[],"bids":[["52000.01","0"],["51999.6","0.3"]],"lastId":165,"id":166,"ts":1616651250171}],"action":"update"}"#;
+    let orderbook = &parse_l2("poloniex", MarketType::Spot, raw_msg).unwrap()[0];
+
+    assert_eq!(orderbook.asks.len(), 0);
+    assert_eq!(orderbook.bids.len(), 2);
+    assert!(!orderbook.snapshot);
+
+    crate::utils::check_orderbook_fields(
+        "poloniex",
+        MarketType::Spot,
+        "BTC/USDT".to_string(),
+        orderbook,
+    );
+
+    assert_eq!(orderbook.timestamp, 1616651250171);
+    assert_eq!(orderbook.bids[0].price, 52000.01);
+    assert_eq!(orderbook.bids[0].quantity_base, 0.0);
+    assert_eq!(orderbook.bids[1].price, 51999.6);
+    assert_eq!(orderbook.bids[1].quantity_base, 0.3);
+}
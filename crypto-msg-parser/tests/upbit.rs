@@ -0,0 +1,45 @@
+mod utils;
+
+use crypto_msg_parser::{parse_l2, parse_trade, MarketType, TradeSide};
+
+#[test]
+fn trade() {
+    let raw_msg = r#"{"type":"trade","code":"KRW-BTC","timestamp":1616651250071,"trade_date":"2021-03-25","trade_time":"05:47:30","trade_timestamp":1616651250070,"trade_price":62000000.0,"trade_volume":0.01,"ask_bid":"ASK","prev_closing_price":61500000.0,"change":"RISE","change_price":500000.0,"sequential_id":1616651250070000,"stream_type":"REALTIME"}"#;
+    let trades = &parse_trade("upbit", MarketType::Spot, raw_msg).unwrap();
+    assert_eq!(trades.len(), 1);
+    let trade = &trades[0];
+
+    crate::utils::check_trade_fields("upbit", MarketType::Spot, "BTC/KRW".to_string(), trade);
+
+    assert_eq!(trade.symbol, "KRW-BTC");
+    assert_eq!(trade.quantity_base, 0.01);
+    assert_eq!(trade.timestamp, 1616651250070);
+    assert_eq!(trade.side, TradeSide::Sell);
+}
+
+#[test]
+fn l2_orderbook_snapshot() {
+    let raw_msg = r#"{"type":"orderbook","code":"BTC-ETH","timestamp":1616651250071,"total_ask_size":12.5,"total_bid_size":20.25,"orderbook_units":[{"ask_price":0.03152,"bid_price":0.03151,"ask_size":2.5,"bid_size":10.25},{"ask_price":0.03153,"bid_price":0.0315,"ask_size":10.0,"bid_size":10.0}],"stream_type":"REALTIME"}"#;
+    let orderbook = &parse_l2("upbit", MarketType::Spot, raw_msg).unwrap()[0];
+
+    assert_eq!(orderbook.asks.len(), 2);
+    assert_eq!(orderbook.bids.len(), 2);
+    assert!(orderbook.snapshot);
+
+    crate::utils::check_orderbook_fields(
+        "upbit",
+        MarketType::Spot,
+        "ETH/BTC".to_string(),
+        orderbook,
+    );
+
+    assert_eq!(orderbook.timestamp, 1616651250071);
+
+    assert_eq!(orderbook.bids[0].price, 0.03151);
+    assert_eq!(orderbook.bids[0].quantity_base, 10.25);
+    assert_eq!(orderbook.bids[0].quantity_quote, 0.03151 * 10.25);
+
+    assert_eq!(orderbook.asks[1].price, 0.03153);
+    assert_eq!(orderbook.asks[1].quantity_base, 10.0);
+    assert_eq!(orderbook.asks[1].quantity_quote, 0.03153 * 10.0);
+}
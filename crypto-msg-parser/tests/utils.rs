@@ -62,7 +62,7 @@ pub fn check_funding_rate_fields(
     assert!(funding_rate.funding_rate < 1.0);
     if exchange == "bitmex" {
         assert_eq!(funding_rate.funding_time % (4 * 3600000), 0);
-    } else if exchange == "bitget" {
+    } else if exchange == "bitget" || exchange == "ftx" || exchange == "kucoin" {
         assert_eq!(funding_rate.funding_time % 3600000, 0);
     } else {
         assert_eq!(funding_rate.funding_time % (8 * 3600000), 0);
@@ -1,8 +1,15 @@
+// Quote currencies of the spot market besides USDT
+const SPOT_QUOTES: [&str; 5] = ["USDC", "DAI", "BTC", "ETH", "EUR"];
+
 pub(crate) fn normalize_pair(symbol: &str) -> Option<String> {
     let (base, quote) = if symbol.ends_with("USDT") {
-        // linear swap
+        // linear swap and spot
         let base = symbol.strip_suffix("USDT").unwrap();
         (base, "USDT")
+    } else if let Some(quote) = SPOT_QUOTES.iter().find(|quote| symbol.ends_with(*quote)) {
+        // spot
+        let base = symbol.strip_suffix(quote).unwrap();
+        (base, *quote)
     } else if symbol.ends_with("USD") {
         // inverse swap
         let base = symbol.strip_suffix("USD").unwrap();
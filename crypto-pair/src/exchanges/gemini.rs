@@ -0,0 +1,12 @@
+// Longer quotes go first, e.g., btcgusd ends with both gusd and usd
+const QUOTES: [&str; 10] = [
+    "GUSD", "USDT", "USDC", "DAI", "USD", "EUR", "GBP", "SGD", "BTC", "ETH",
+];
+
+pub(crate) fn normalize_pair(symbol: &str) -> Option<String> {
+    let symbol = symbol.to_uppercase();
+    QUOTES
+        .iter()
+        .find(|quote| symbol.ends_with(*quote) && symbol.len() > quote.len())
+        .map(|quote| format!("{}/{}", symbol.strip_suffix(quote).unwrap(), quote))
+}
@@ -15,6 +15,20 @@ pub(crate) fn normalize_currency(mut currency: &str) -> String {
 }
 
 pub(crate) fn normalize_pair(symbol: &str) -> Option<String> {
+    if symbol.starts_with("PI_") || symbol.starts_with("FI_") {
+        // Kraken Futures, e.g., PI_XBTUSD, FI_XBTUSD_210625
+        let underlying = symbol[3..].split('_').next().unwrap();
+        if underlying.len() < 6 {
+            return None;
+        }
+        let (base, quote) = underlying.split_at(underlying.len() - 3);
+        return Some(format!(
+            "{}/{}",
+            normalize_currency(base),
+            normalize_currency(quote)
+        ));
+    }
+
     let (base, quote) = {
         let v: Vec<&str> = symbol.split('/').collect();
         (v[0].to_string(), v[1].to_string())
@@ -8,7 +8,9 @@ pub(super) mod bitstamp;
 pub(super) mod bybit;
 pub(super) mod deribit;
 pub(super) mod ftx;
+pub(super) mod gemini;
 pub(super) mod huobi;
 pub(super) mod kraken;
 pub(super) mod kucoin;
+pub(super) mod upbit;
 pub(super) mod zbg;
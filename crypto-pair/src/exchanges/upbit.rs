@@ -0,0 +1,5 @@
+// Upbit puts the quote currency first, e.g., KRW-BTC
+pub(crate) fn normalize_pair(symbol: &str) -> Option<String> {
+    let (quote, base) = symbol.split_once('-')?;
+    Some(format!("{}/{}", base, quote).to_uppercase())
+}
@@ -34,7 +34,13 @@ pub fn normalize_currency(symbol: &str, exchange: &str) -> String {
 /// assert_eq!(Some("BTC/USDT".to_string()), normalize_pair("BTCUSDT", "binance"));
 /// assert_eq!(Some("BTC/USDT".to_string()), normalize_pair("btcusdt", "huobi"));
 /// assert_eq!(Some("BTC/USDT".to_string()), normalize_pair("BTCUST", "bitfinex"));
+/// assert_eq!(Some("BTC/USD".to_string()), normalize_pair("PI_XBTUSD", "kraken"));
+/// assert_eq!(Some("BTC/KRW".to_string()), normalize_pair("KRW-BTC", "upbit"));
 /// ```
+///
+/// `Poloniex` and `Upbit` are deprecated aliases of `poloniex` and `upbit`,
+/// kept with their original behavior, e.g., `Upbit` returns `KRW/BTC` for
+/// `KRW-BTC`.
 pub fn normalize_pair(symbol: &str, exchange: &str) -> Option<String> {
     match exchange {
         "binance" => exchanges::binance::normalize_pair(symbol),
@@ -56,6 +62,7 @@ pub fn normalize_pair(symbol: &str, exchange: &str) -> Option<String> {
 
             Some(format!("{}/{}", base, quote))
         }
+        "gemini" => exchanges::gemini::normalize_pair(symbol),
         "huobi" => exchanges::huobi::normalize_pair(symbol),
         "kraken" => exchanges::kraken::normalize_pair(symbol),
         "kucoin" => exchanges::kucoin::normalize_pair(symbol),
@@ -64,6 +71,9 @@ pub fn normalize_pair(symbol: &str, exchange: &str) -> Option<String> {
             let v: Vec<&str> = symbol.split('-').collect();
             Some(format!("{}/{}", v[0], v[1]))
         }
+        "poloniex" => Some(symbol.replace("_", "/")),
+        "upbit" => exchanges::upbit::normalize_pair(symbol),
+        // Deprecated, kept for backward compatibility
         "Poloniex" => Some(symbol.replace("_", "/")),
         "Upbit" => Some(symbol.replace("-", "/")),
         "zbg" => exchanges::zbg::normalize_pair(symbol),
@@ -17,6 +17,7 @@ fn main() {
         "Bitstamp",
         "CoinbasePro",
         "Coincheck",
+        "Gemini",
         "Huobi",
         "Kraken",
         "MXC",
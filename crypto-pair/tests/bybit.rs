@@ -48,3 +48,42 @@ fn verify_swap_symbols() {
         assert_eq!(pair.as_str(), pair_expected);
     }
 }
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+struct SpotMarket {
+    name: String,
+    baseCurrency: String,
+    quoteCurrency: String,
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SpotResponse {
+    ret_code: i64,
+    result: Vec<SpotMarket>,
+}
+
+// See https://bybit-exchange.github.io/docs/spot/#t-querysymbol
+fn fetch_spot_markets_raw() -> Vec<SpotMarket> {
+    let txt = http_get("https://api.bybit.com/spot/v1/symbols").unwrap();
+    let resp = serde_json::from_str::<SpotResponse>(&txt).unwrap();
+    assert_eq!(resp.ret_code, 0);
+    resp.result
+}
+
+#[test]
+fn verify_spot_symbols() {
+    let markets = fetch_spot_markets_raw();
+    for market in markets.iter() {
+        let pair = normalize_pair(&market.name, EXCHANGE_NAME).unwrap();
+        let pair_expected = format!(
+            "{}/{}",
+            normalize_currency(&market.baseCurrency, EXCHANGE_NAME),
+            normalize_currency(&market.quoteCurrency, EXCHANGE_NAME)
+        );
+
+        assert_eq!(pair.as_str(), pair_expected);
+    }
+}
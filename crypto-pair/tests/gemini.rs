@@ -0,0 +1,49 @@
+mod utils;
+
+use crypto_pair::{normalize_currency, normalize_pair};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use utils::http_get;
+
+const EXCHANGE_NAME: &str = "gemini";
+
+#[derive(Serialize, Deserialize)]
+struct SymbolDetails {
+    symbol: String,
+    base_currency: String,
+    quote_currency: String,
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+// see <https://docs.gemini.com/rest-api/#symbols>
+fn fetch_spot_symbols() -> Vec<String> {
+    let txt = http_get("https://api.gemini.com/v1/symbols").unwrap();
+    serde_json::from_str::<Vec<String>>(&txt).unwrap()
+}
+
+// see <https://docs.gemini.com/rest-api/#symbol-details>
+fn fetch_symbol_details(symbol: &str) -> SymbolDetails {
+    let txt = http_get(&format!(
+        "https://api.gemini.com/v1/symbols/details/{}",
+        symbol
+    ))
+    .unwrap();
+    serde_json::from_str::<SymbolDetails>(&txt).unwrap()
+}
+
+#[test]
+fn verify_spot_symbols() {
+    for symbol in fetch_spot_symbols().iter() {
+        let details = fetch_symbol_details(symbol);
+        let pair = normalize_pair(&details.symbol, EXCHANGE_NAME).unwrap();
+        let pair_expected = format!(
+            "{}/{}",
+            normalize_currency(&details.base_currency, EXCHANGE_NAME),
+            normalize_currency(&details.quote_currency, EXCHANGE_NAME)
+        );
+
+        assert_eq!(pair.as_str(), pair_expected);
+    }
+}
@@ -51,3 +51,57 @@ fn verify_spot_symbols() {
         assert_eq!(pair.as_str(), pair_expected);
     }
 }
+
+#[derive(Serialize, Deserialize)]
+struct FuturesMarket {
+    symbol: String,
+    #[serde(rename = "type")]
+    type_: Option<String>,
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct FuturesResponse {
+    result: String,
+    instruments: Vec<FuturesMarket>,
+}
+
+// see <https://docs.futures.kraken.com/#http-api-trading-v3-api-instrument-details-get-instruments>
+fn fetch_futures_markets_raw() -> Vec<FuturesMarket> {
+    let txt = http_get("https://futures.kraken.com/derivatives/api/v3/instruments").unwrap();
+    let resp = serde_json::from_str::<FuturesResponse>(&txt).unwrap();
+    resp.instruments
+        .into_iter()
+        .filter(|x| x.type_.as_deref() == Some("futures_inverse"))
+        .collect()
+}
+
+#[test]
+fn verify_futures_symbols() {
+    let markets = fetch_futures_markets_raw();
+    for market in markets.iter() {
+        let symbol = market.symbol.to_uppercase();
+        let pair = normalize_pair(&symbol, EXCHANGE_NAME).unwrap();
+        let underlying = symbol[3..].split('_').next().unwrap();
+        let pair_expected = format!(
+            "{}/{}",
+            normalize_currency(&underlying[..underlying.len() - 3], EXCHANGE_NAME),
+            normalize_currency(&underlying[underlying.len() - 3..], EXCHANGE_NAME)
+        );
+
+        assert_eq!(pair.as_str(), pair_expected);
+    }
+}
+
+#[test]
+fn verify_futures_examples() {
+    assert_eq!(
+        "BTC/USD",
+        normalize_pair("PI_XBTUSD", EXCHANGE_NAME).unwrap()
+    );
+    assert_eq!(
+        "ETH/USD",
+        normalize_pair("FI_ETHUSD_210625", EXCHANGE_NAME).unwrap()
+    );
+}
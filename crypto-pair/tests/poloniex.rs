@@ -0,0 +1,53 @@
+mod utils;
+
+use crypto_pair::{normalize_currency, normalize_pair};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use utils::http_get;
+
+const EXCHANGE_NAME: &str = "poloniex";
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SpotMarket {
+    symbol: String,
+    base_currency_name: String,
+    quote_currency_name: String,
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+// see <https://docs.poloniex.com/#public-endpoints-reference-data-symbol-information>
+fn fetch_spot_markets_raw() -> Vec<SpotMarket> {
+    let txt = http_get("https://api.poloniex.com/markets").unwrap();
+    serde_json::from_str::<Vec<SpotMarket>>(&txt).unwrap()
+}
+
+#[test]
+fn normalize() {
+    assert_eq!(
+        "BTC/USDT".to_string(),
+        normalize_pair("BTC_USDT", EXCHANGE_NAME).unwrap()
+    );
+    // the deprecated alias
+    assert_eq!(
+        "BTC/USDT".to_string(),
+        normalize_pair("BTC_USDT", "Poloniex").unwrap()
+    );
+}
+
+#[test]
+fn verify_spot_symbols() {
+    let markets = fetch_spot_markets_raw();
+    for market in markets.iter() {
+        let pair = normalize_pair(&market.symbol, EXCHANGE_NAME).unwrap();
+        let pair_expected = format!(
+            "{}/{}",
+            normalize_currency(&market.base_currency_name, EXCHANGE_NAME),
+            normalize_currency(&market.quote_currency_name, EXCHANGE_NAME)
+        );
+
+        assert_eq!(pair.as_str(), pair_expected);
+    }
+}
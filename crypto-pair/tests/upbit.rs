@@ -0,0 +1,50 @@
+mod utils;
+
+use crypto_pair::normalize_pair;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use utils::http_get;
+
+const EXCHANGE_NAME: &str = "upbit";
+
+#[derive(Serialize, Deserialize)]
+struct SpotMarket {
+    market: String,
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+// see <https://global-docs.upbit.com/reference/listing-market-list>
+fn fetch_spot_markets_raw() -> Vec<SpotMarket> {
+    let txt = http_get("https://api.upbit.com/v1/market/all").unwrap();
+    serde_json::from_str::<Vec<SpotMarket>>(&txt).unwrap()
+}
+
+#[test]
+fn normalize() {
+    assert_eq!(
+        "BTC/KRW".to_string(),
+        normalize_pair("KRW-BTC", EXCHANGE_NAME).unwrap()
+    );
+    assert_eq!(
+        "ETH/BTC".to_string(),
+        normalize_pair("BTC-ETH", EXCHANGE_NAME).unwrap()
+    );
+    // the deprecated alias keeps the order of the original pair
+    assert_eq!(
+        "KRW/BTC".to_string(),
+        normalize_pair("KRW-BTC", "Upbit").unwrap()
+    );
+}
+
+#[test]
+fn verify_spot_symbols() {
+    let markets = fetch_spot_markets_raw();
+    for market in markets.iter() {
+        let pair = normalize_pair(&market.market, EXCHANGE_NAME).unwrap();
+        let (quote, base) = market.market.split_once('-').unwrap();
+
+        assert_eq!(pair, format!("{}/{}", base, quote));
+    }
+}
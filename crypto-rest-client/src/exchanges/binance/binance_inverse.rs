@@ -63,4 +63,18 @@ impl BinanceInverseRestClient {
         let limit = Some(1000);
         gen_api_binance!("/dapi/v1/depth", symbol, limit)
     }
+
+    /// Get present open interest of a contract.
+    ///
+    /// Equivalent to `/dapi/v1/openInterest`
+    ///
+    /// For example:
+    ///
+    /// - <https://dapi.binance.com/dapi/v1/openInterest?symbol=BTCUSD_PERP>
+    /// - <https://dapi.binance.com/dapi/v1/openInterest?symbol=BTCUSD_210625>
+    pub fn fetch_open_interest(symbol: &str) -> Result<String> {
+        check_symbol(symbol);
+        let symbol = Some(symbol);
+        gen_api_binance!("/dapi/v1/openInterest", symbol)
+    }
 }
@@ -63,4 +63,29 @@ impl BinanceLinearRestClient {
         let limit = Some(1000);
         gen_api_binance!("/fapi/v1/depth", symbol, limit)
     }
+
+    /// Get present open interest of a contract.
+    ///
+    /// Equivalent to `/fapi/v1/openInterest`
+    ///
+    /// For example:
+    ///
+    /// - <https://fapi.binance.com/fapi/v1/openInterest?symbol=BTCUSDT>
+    /// - <https://fapi.binance.com/fapi/v1/openInterest?symbol=BTCUSDT_210625>
+    pub fn fetch_open_interest(symbol: &str) -> Result<String> {
+        check_symbol(symbol);
+        let symbol = Some(symbol);
+        gen_api_binance!("/fapi/v1/openInterest", symbol)
+    }
+
+    /// Get mark price, index price and funding rate of a contract.
+    ///
+    /// Equivalent to `/fapi/v1/premiumIndex`
+    ///
+    /// For example: <https://fapi.binance.com/fapi/v1/premiumIndex?symbol=BTCUSDT>
+    pub fn fetch_premium_index(symbol: &str) -> Result<String> {
+        check_symbol(symbol);
+        let symbol = Some(symbol);
+        gen_api_binance!("/fapi/v1/premiumIndex", symbol)
+    }
 }
@@ -24,3 +24,27 @@ pub(crate) fn fetch_l2_snapshot(market_type: MarketType, symbol: &str) -> Result
 
     func(symbol)
 }
+
+pub(crate) fn fetch_open_interest(market_type: MarketType, symbol: &str) -> Result<String> {
+    let func = match market_type {
+        MarketType::InverseFuture | MarketType::InverseSwap => {
+            binance_inverse::BinanceInverseRestClient::fetch_open_interest
+        }
+        MarketType::LinearFuture | MarketType::LinearSwap => {
+            binance_linear::BinanceLinearRestClient::fetch_open_interest
+        }
+        _ => panic!("Binance {} does NOT have open interest", market_type),
+    };
+
+    func(symbol)
+}
+
+// Coin-margined markets push index prices via WebSocket
+pub(crate) fn fetch_index_price(market_type: MarketType, symbol: &str) -> Result<String> {
+    match market_type {
+        MarketType::LinearFuture | MarketType::LinearSwap => {
+            binance_linear::BinanceLinearRestClient::fetch_premium_index(symbol)
+        }
+        _ => panic!("Binance {} does NOT have index price APIs", market_type),
+    }
+}
@@ -51,6 +51,15 @@ impl BitfinexRestClient {
         gen_api!(format!("/v2/book/{}/{}", symbol, "P0"), len)
     }
 
+    /// Get the status of a derivative contract, including its funding rate.
+    ///
+    /// Equivalent to `/v2/status/deriv` with `keys=Symbol`
+    ///
+    /// For example: <https://api-pub.bitfinex.com/v2/status/deriv?keys=tBTCF0:USTF0>
+    pub fn fetch_deriv_status(symbol: &str) -> Result<String> {
+        gen_api!(format!("/v2/status/deriv?keys={}", symbol))
+    }
+
     /// Get a Level3 snapshot of orderbook.
     ///
     /// Equivalent to `/v2/book/Symbol/R0` with `len=100`
@@ -1,15 +1,19 @@
 use super::utils::http_get;
 use crate::error::Result;
+use crypto_market_type::MarketType;
 use std::collections::HashMap;
 
 const BASE_URL: &str = "https://api.bybit.com/v2";
+const SPOT_BASE_URL: &str = "https://api.bybit.com/spot/quote/v1";
 
 /// The RESTful client for Bybit.
 ///
-/// Bybit has InverseSwap and LinearSwap markets.
+/// Bybit has Spot, InverseSwap, LinearSwap and InverseFuture markets.
 ///
 /// * RESTful API doc: <https://bybit-exchange.github.io/docs/inverse/#t-marketdata>
+/// * Spot RESTful API doc: <https://bybit-exchange.github.io/docs/spot/#t-marketdata>
 /// * Trading at:
+///     * Spot <https://www.bybit.com/trade/spot/>
 ///     * InverseSwap <https://www.bybit.com/trade/inverse/>
 ///     * LinearSwap <https://www.bybit.com/trade/usdt/>
 pub struct BybitRestClient {
@@ -33,4 +37,23 @@ impl BybitRestClient {
     pub fn fetch_l2_snapshot(symbol: &str) -> Result<String> {
         gen_api!(format!("/public/orderBook/L2?symbol={}", symbol))
     }
+
+    /// Get the latest Level2 snapshot of orderbook of the Spot market.
+    ///
+    /// Top 100 bids and asks are returned.
+    ///
+    /// For example: <https://api.bybit.com/spot/quote/v1/depth?symbol=BTCUSDT&limit=100>
+    pub fn fetch_spot_l2_snapshot(symbol: &str) -> Result<String> {
+        gen_api!(format!(
+            "{}/depth?symbol={}&limit=100",
+            SPOT_BASE_URL, symbol
+        ))
+    }
+}
+
+pub(crate) fn fetch_l2_snapshot(market_type: MarketType, symbol: &str) -> Result<String> {
+    match market_type {
+        MarketType::Spot => BybitRestClient::fetch_spot_l2_snapshot(symbol),
+        _ => BybitRestClient::fetch_l2_snapshot(symbol),
+    }
 }
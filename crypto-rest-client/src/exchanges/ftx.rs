@@ -1,6 +1,7 @@
 use super::utils::http_get;
 use crate::error::Result;
 use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 const BASE_URL: &str = "https://ftx.com/api";
 
@@ -32,4 +33,24 @@ impl FtxRestClient {
     pub fn fetch_l2_snapshot(symbol: &str) -> Result<String> {
         gen_api!(format!("/markets/{}/orderbook?depth=100", symbol))
     }
+
+    /// Get a future, including its mark price, index price and open interest.
+    ///
+    /// For example: <https://ftx.com/api/futures/BTC-PERP>,
+    // <https://ftx.com/api/futures/BTC-0625>
+    pub fn fetch_future(symbol: &str) -> Result<String> {
+        gen_api!(format!("/futures/{}", symbol))
+    }
+
+    /// Get funding rates of a perpetual future in the last hour.
+    ///
+    /// For example: <https://ftx.com/api/funding_rates?future=BTC-PERP&start_time=1625097600>
+    pub fn fetch_funding_rates(symbol: &str) -> Result<String> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        gen_api!(format!(
+            "/funding_rates?future={}&start_time={}",
+            symbol,
+            now.as_secs() - 3600
+        ))
+    }
 }
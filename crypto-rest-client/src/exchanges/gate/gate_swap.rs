@@ -42,4 +42,21 @@ impl GateSwapRestClient {
             settle, symbol
         ))
     }
+
+    /// Get a contract, including its funding rate and next funding time.
+    ///
+    /// For example:
+    ///
+    /// - <https://api.gateio.ws/api/v4/futures/btc/contracts/BTC_USD>
+    /// - <https://api.gateio.ws/api/v4/futures/usdt/contracts/BTC_USDT>
+    pub fn fetch_contract(symbol: &str) -> Result<String> {
+        let settle = if symbol.ends_with("_USD") {
+            "btc"
+        } else if symbol.ends_with("_USDT") {
+            "usdt"
+        } else {
+            panic!("Unknown symbol {}", symbol);
+        };
+        gen_api!(format!("/futures/{}/contracts/{}", settle, symbol))
+    }
 }
@@ -0,0 +1,43 @@
+use super::utils::http_get;
+use crate::error::Result;
+use std::collections::HashMap;
+
+const BASE_URL: &str = "https://api.gemini.com";
+
+/// The REST client for Gemini.
+///
+/// Gemini has only Spot market.
+///
+///   * REST API doc: <https://docs.gemini.com/rest-api/>
+///   * Trading at: <https://exchange.gemini.com/>
+pub struct GeminiRestClient {
+    _api_key: Option<String>,
+    _api_secret: Option<String>,
+}
+
+impl GeminiRestClient {
+    pub fn new(api_key: Option<String>, api_secret: Option<String>) -> Self {
+        GeminiRestClient {
+            _api_key: api_key,
+            _api_secret: api_secret,
+        }
+    }
+
+    /// Get the most recent trades.
+    ///
+    /// `/v1/trades/{symbol}`
+    ///
+    /// For example: <https://api.gemini.com/v1/trades/btcusd>
+    pub fn fetch_trades(symbol: &str) -> Result<String> {
+        gen_api!(format!("/v1/trades/{}", symbol))
+    }
+
+    /// Get a full Level2 orderbook snapshot.
+    ///
+    /// Zero limits return all price levels.
+    ///
+    /// For example: <https://api.gemini.com/v1/book/btcusd?limit_bids=0&limit_asks=0>
+    pub fn fetch_l2_snapshot(symbol: &str) -> Result<String> {
+        gen_api!(format!("/v1/book/{}?limit_bids=0&limit_asks=0", symbol))
+    }
+}
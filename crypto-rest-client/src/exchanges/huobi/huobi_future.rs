@@ -24,4 +24,36 @@ impl HuobiFutureRestClient {
     pub fn fetch_l2_snapshot(symbol: &str) -> Result<String> {
         gen_api!(format!("/market/depth?symbol={}&type=step0", symbol))
     }
+
+    /// Get open interest of a contract.
+    ///
+    /// For example: <https://api.hbdm.com/api/v1/contract_open_interest?symbol=BTC&contract_type=quarter>
+    pub fn fetch_open_interest(symbol: &str) -> Result<String> {
+        let (currency, contract_type) = split_symbol(symbol);
+        gen_api!(format!(
+            "/api/v1/contract_open_interest?symbol={}&contract_type={}",
+            currency, contract_type
+        ))
+    }
+
+    /// Get the index price of a currency.
+    ///
+    /// For example: <https://api.hbdm.com/api/v1/contract_index?symbol=BTC>
+    pub fn fetch_index_price(symbol: &str) -> Result<String> {
+        let (currency, _) = split_symbol(symbol);
+        gen_api!(format!("/api/v1/contract_index?symbol={}", currency))
+    }
+}
+
+// Splits a symbol such as BTC_CQ into the currency and the contract type
+fn split_symbol(symbol: &str) -> (&str, &str) {
+    let (currency, suffix) = symbol.split_once('_').unwrap_or((symbol, ""));
+    let contract_type = match suffix {
+        "CW" => "this_week",
+        "NW" => "next_week",
+        "CQ" => "quarter",
+        "NQ" => "next_quarter",
+        _ => panic!("Unknown contract type of {}", symbol),
+    };
+    (currency, contract_type)
 }
@@ -26,4 +26,34 @@ impl HuobiInverseSwapRestClient {
     pub fn fetch_l2_snapshot(symbol: &str) -> Result<String> {
         gen_api!(format!("/market/depth?contract_code={}&type=step0", symbol))
     }
+
+    /// Get open interest of a contract.
+    ///
+    /// For example: <https://api.hbdm.com/swap-api/v1/swap_open_interest?contract_code=BTC-USD>
+    pub fn fetch_open_interest(symbol: &str) -> Result<String> {
+        gen_api!(format!(
+            "https://api.hbdm.com/swap-api/v1/swap_open_interest?contract_code={}",
+            symbol
+        ))
+    }
+
+    /// Get liquidation orders of a contract in the last 7 days.
+    ///
+    /// For example: <https://api.hbdm.com/swap-api/v1/swap_liquidation_orders?contract_code=BTC-USD&trade_type=0&create_date=7>
+    pub fn fetch_liquidation_orders(symbol: &str) -> Result<String> {
+        gen_api!(format!(
+            "https://api.hbdm.com/swap-api/v1/swap_liquidation_orders?contract_code={}&trade_type=0&create_date=7",
+            symbol
+        ))
+    }
+
+    /// Get the index price of a contract.
+    ///
+    /// For example: <https://api.hbdm.com/swap-api/v1/swap_index?contract_code=BTC-USD>
+    pub fn fetch_index_price(symbol: &str) -> Result<String> {
+        gen_api!(format!(
+            "https://api.hbdm.com/swap-api/v1/swap_index?contract_code={}",
+            symbol
+        ))
+    }
 }
@@ -24,3 +24,43 @@ pub(crate) fn fetch_l2_snapshot(market_type: MarketType, symbol: &str) -> Result
 
     func(symbol)
 }
+
+pub(crate) fn fetch_open_interest(market_type: MarketType, symbol: &str) -> Result<String> {
+    let func = match market_type {
+        MarketType::InverseFuture => huobi_future::HuobiFutureRestClient::fetch_open_interest,
+        MarketType::LinearSwap => huobi_linear_swap::HuobiLinearSwapRestClient::fetch_open_interest,
+        MarketType::InverseSwap => {
+            huobi_inverse_swap::HuobiInverseSwapRestClient::fetch_open_interest
+        }
+        _ => panic!("Huobi {} does NOT have open interest", market_type),
+    };
+
+    func(symbol)
+}
+
+pub(crate) fn fetch_index_price(market_type: MarketType, symbol: &str) -> Result<String> {
+    let func = match market_type {
+        MarketType::InverseFuture => huobi_future::HuobiFutureRestClient::fetch_index_price,
+        MarketType::LinearSwap => huobi_linear_swap::HuobiLinearSwapRestClient::fetch_index_price,
+        MarketType::InverseSwap => {
+            huobi_inverse_swap::HuobiInverseSwapRestClient::fetch_index_price
+        }
+        _ => panic!("Huobi {} does NOT have index price", market_type),
+    };
+
+    func(symbol)
+}
+
+pub(crate) fn fetch_liquidation_orders(market_type: MarketType, symbol: &str) -> Result<String> {
+    let func = match market_type {
+        MarketType::LinearSwap => {
+            huobi_linear_swap::HuobiLinearSwapRestClient::fetch_liquidation_orders
+        }
+        MarketType::InverseSwap => {
+            huobi_inverse_swap::HuobiInverseSwapRestClient::fetch_liquidation_orders
+        }
+        _ => panic!("Huobi {} does NOT have liquidation orders", market_type),
+    };
+
+    func(symbol)
+}
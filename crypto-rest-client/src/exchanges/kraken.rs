@@ -1,15 +1,20 @@
 use super::utils::http_get;
 use crate::error::Result;
+use crypto_market_type::MarketType;
 use std::collections::HashMap;
 
 const BASE_URL: &str = "https://api.kraken.com";
+const FUTURES_BASE_URL: &str = "https://futures.kraken.com/derivatives/api/v3";
 
-/// The WebSocket client for Kraken.
+/// The RESTful client for Kraken.
 ///
-/// Kraken has only Spot market.
+/// Kraken has Spot, InverseFuture and InverseSwap markets.
 ///
 ///   * REST API doc: <https://www.kraken.com/features/api>
-///   * Trading at: <https://trade.kraken.com/>
+///   * Futures REST API doc: <https://docs.futures.kraken.com/#http-api>
+///   * Trading at:
+///       * Spot <https://trade.kraken.com/>
+///       * InverseFuture and InverseSwap <https://futures.kraken.com/>
 pub struct KrakenRestClient {
     _api_key: Option<String>,
     _api_secret: Option<String>,
@@ -41,4 +46,23 @@ impl KrakenRestClient {
     pub fn fetch_l2_snapshot(symbol: &str) -> Result<String> {
         gen_api!(format!("/0/public/Depth?pair={}&count=500", symbol))
     }
+
+    /// Get a Level2 snapshot of orderbook of Kraken Futures.
+    ///
+    /// All bids and asks are returned.
+    ///
+    /// For example: <https://futures.kraken.com/derivatives/api/v3/orderbook?symbol=PI_XBTUSD>
+    pub fn fetch_futures_l2_snapshot(symbol: &str) -> Result<String> {
+        gen_api!(format!("{}/orderbook?symbol={}", FUTURES_BASE_URL, symbol))
+    }
+}
+
+pub(crate) fn fetch_l2_snapshot(market_type: MarketType, symbol: &str) -> Result<String> {
+    match market_type {
+        MarketType::Spot => KrakenRestClient::fetch_l2_snapshot(symbol),
+        MarketType::InverseFuture | MarketType::InverseSwap => {
+            KrakenRestClient::fetch_futures_l2_snapshot(symbol)
+        }
+        _ => panic!("Kraken unknown market_type: {}", market_type),
+    }
 }
@@ -38,4 +38,11 @@ impl KuCoinSwapRestClient {
     pub fn fetch_l3_snapshot(symbol: &str) -> Result<String> {
         gen_api!(format!("/api/v2/level3/snapshot?symbol={}", symbol))
     }
+
+    /// Get a contract, including its open interest.
+    ///
+    /// For example: <https://api-futures.kucoin.com/api/v1/contracts/XBTUSDM>,
+    pub fn fetch_contract(symbol: &str) -> Result<String> {
+        gen_api!(format!("/api/v1/contracts/{}", symbol))
+    }
 }
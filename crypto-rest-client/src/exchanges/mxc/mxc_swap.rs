@@ -36,4 +36,11 @@ impl MxcSwapRestClient {
     pub fn fetch_l2_snapshot(symbol: &str) -> Result<String> {
         gen_api!(format!("/api/v1/contract/depth_commits/{}/1000", symbol))
     }
+
+    /// Get the funding rate and next settlement time of a contract.
+    ///
+    /// For example: <https://contract.mxc.com/api/v1/contract/funding_rate/BTC_USDT>
+    pub fn fetch_funding_rate(symbol: &str) -> Result<String> {
+        gen_api!(format!("/api/v1/contract/funding_rate/{}", symbol))
+    }
 }
@@ -59,6 +59,30 @@ impl OkexRestClient {
         ))
     }
 
+    /// Get open interest of a future or swap contract.
+    ///
+    /// For example: <https://www.okex.com/api/swap/v3/instruments/BTC-USDT-SWAP/open_interest>,
+    // <https://www.okex.com/api/futures/v3/instruments/BTC-USD-210625/open_interest>
+    pub fn fetch_open_interest(symbol: &str) -> Result<String> {
+        gen_api!(format!(
+            "/{}/v3/instruments/{}/open_interest",
+            pair_to_market_type(symbol),
+            symbol
+        ))
+    }
+
+    /// Get filled liquidation orders of a future or swap contract in the last 7 days.
+    ///
+    /// For example: <https://www.okex.com/api/swap/v3/instruments/BTC-USDT-SWAP/liquidation?status=1&limit=100>,
+    // <https://www.okex.com/api/futures/v3/instruments/BTC-USD-210625/liquidation?status=1&limit=100>
+    pub fn fetch_liquidation_orders(symbol: &str) -> Result<String> {
+        gen_api!(format!(
+            "/{}/v3/instruments/{}/liquidation?status=1&limit=100",
+            pair_to_market_type(symbol),
+            symbol
+        ))
+    }
+
     /// Get option underlying.
     pub fn fetch_option_underlying() -> Result<Vec<String>> {
         let txt = http_get(
@@ -0,0 +1,43 @@
+use super::utils::http_get;
+use crate::error::Result;
+use std::collections::HashMap;
+
+const BASE_URL: &str = "https://api.poloniex.com";
+
+/// The REST client for Poloniex.
+///
+/// Poloniex has only Spot market.
+///
+///   * REST API doc: <https://docs.poloniex.com/>
+///   * Trading at: <https://poloniex.com/trade>
+pub struct PoloniexRestClient {
+    _api_key: Option<String>,
+    _api_secret: Option<String>,
+}
+
+impl PoloniexRestClient {
+    pub fn new(api_key: Option<String>, api_secret: Option<String>) -> Self {
+        PoloniexRestClient {
+            _api_key: api_key,
+            _api_secret: api_secret,
+        }
+    }
+
+    /// Get the most recent trades, at most 1000.
+    ///
+    /// `/markets/{symbol}/trades`
+    ///
+    /// For example: <https://api.poloniex.com/markets/BTC_USDT/trades?limit=1000>
+    pub fn fetch_trades(symbol: &str) -> Result<String> {
+        gen_api!(format!("/markets/{}/trades?limit=1000", symbol))
+    }
+
+    /// Get a Level2 orderbook snapshot.
+    ///
+    /// Top 150 bids and asks are returned, which is the maximum.
+    ///
+    /// For example: <https://api.poloniex.com/markets/BTC_USDT/orderBook?limit=150>
+    pub fn fetch_l2_snapshot(symbol: &str) -> Result<String> {
+        gen_api!(format!("/markets/{}/orderBook?limit=150", symbol))
+    }
+}
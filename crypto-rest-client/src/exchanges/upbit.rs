@@ -0,0 +1,43 @@
+use super::utils::http_get;
+use crate::error::Result;
+use std::collections::HashMap;
+
+const BASE_URL: &str = "https://api.upbit.com";
+
+/// The REST client for Upbit.
+///
+/// Upbit has only Spot market.
+///
+///   * REST API doc: <https://global-docs.upbit.com/reference>
+///   * Trading at: <https://upbit.com/exchange>
+pub struct UpbitRestClient {
+    _api_key: Option<String>,
+    _api_secret: Option<String>,
+}
+
+impl UpbitRestClient {
+    pub fn new(api_key: Option<String>, api_secret: Option<String>) -> Self {
+        UpbitRestClient {
+            _api_key: api_key,
+            _api_secret: api_secret,
+        }
+    }
+
+    /// Get the most recent trades, at most 500.
+    ///
+    /// `/v1/trades/ticks`
+    ///
+    /// For example: <https://api.upbit.com/v1/trades/ticks?market=KRW-BTC&count=500>
+    pub fn fetch_trades(symbol: &str) -> Result<String> {
+        gen_api!(format!("/v1/trades/ticks?market={}&count=500", symbol))
+    }
+
+    /// Get a Level2 orderbook snapshot.
+    ///
+    /// Top 15 bids and asks are returned.
+    ///
+    /// For example: <https://api.upbit.com/v1/orderbook?markets=KRW-BTC>
+    pub fn fetch_l2_snapshot(symbol: &str) -> Result<String> {
+        gen_api!(format!("/v1/orderbook?markets={}", symbol))
+    }
+}
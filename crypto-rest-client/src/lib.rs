@@ -17,6 +17,7 @@ pub use exchanges::coinbase_pro::CoinbaseProRestClient;
 pub use exchanges::deribit::DeribitRestClient;
 pub use exchanges::ftx::FtxRestClient;
 pub use exchanges::gate::*;
+pub use exchanges::gemini::GeminiRestClient;
 pub use exchanges::huobi::huobi_future::HuobiFutureRestClient;
 pub use exchanges::huobi::huobi_inverse_swap::HuobiInverseSwapRestClient;
 pub use exchanges::huobi::huobi_linear_swap::HuobiLinearSwapRestClient;
@@ -27,6 +28,8 @@ pub use exchanges::kucoin::*;
 pub use exchanges::mxc::mxc_spot::MxcSpotRestClient;
 pub use exchanges::mxc::mxc_swap::MxcSwapRestClient;
 pub use exchanges::okex::OkexRestClient;
+pub use exchanges::poloniex::PoloniexRestClient;
+pub use exchanges::upbit::UpbitRestClient;
 pub use exchanges::zbg::*;
 
 use crypto_market_type::MarketType;
@@ -42,16 +45,19 @@ pub fn fetch_l2_snapshot(exchange: &str, market_type: MarketType, symbol: &str)
         "bitmex" => exchanges::bitmex::BitmexRestClient::fetch_l2_snapshot(symbol),
         "bitstamp" => exchanges::bitstamp::BitstampRestClient::fetch_l2_snapshot(symbol),
         "bitz" => exchanges::bitz::fetch_l2_snapshot(market_type, symbol),
-        "bybit" => exchanges::bybit::BybitRestClient::fetch_l2_snapshot(symbol),
+        "bybit" => exchanges::bybit::fetch_l2_snapshot(market_type, symbol),
         "coinbase_pro" => exchanges::coinbase_pro::CoinbaseProRestClient::fetch_l2_snapshot(symbol),
         "deribit" => exchanges::deribit::DeribitRestClient::fetch_l2_snapshot(symbol),
         "ftx" => exchanges::ftx::FtxRestClient::fetch_l2_snapshot(symbol),
         "gate" => exchanges::gate::fetch_l2_snapshot(market_type, symbol),
+        "gemini" => exchanges::gemini::GeminiRestClient::fetch_l2_snapshot(symbol),
         "huobi" => exchanges::huobi::fetch_l2_snapshot(market_type, symbol),
-        "kraken" => exchanges::kraken::KrakenRestClient::fetch_l2_snapshot(symbol),
+        "kraken" => exchanges::kraken::fetch_l2_snapshot(market_type, symbol),
         "kucoin" => exchanges::kucoin::fetch_l2_snapshot(market_type, symbol),
         "mxc" => exchanges::mxc::fetch_l2_snapshot(market_type, symbol),
         "okex" => exchanges::okex::OkexRestClient::fetch_l2_snapshot(symbol),
+        "poloniex" => exchanges::poloniex::PoloniexRestClient::fetch_l2_snapshot(symbol),
+        "upbit" => exchanges::upbit::UpbitRestClient::fetch_l2_snapshot(symbol),
         "zbg" => exchanges::zbg::fetch_l2_snapshot(market_type, symbol),
         _ => panic!("Unknown exchange {}", exchange),
     }
@@ -70,3 +76,87 @@ pub fn fetch_l3_snapshot(exchange: &str, market_type: MarketType, symbol: &str)
         ),
     }
 }
+
+/// Fetch open interest of a derivative contract.
+///
+/// Only exchanges without open interest channels in WebSocket are supported,
+/// i.e., Binance, FTX, Huobi, KuCoin and OKEx.
+pub fn fetch_open_interest(
+    exchange: &str,
+    market_type: MarketType,
+    symbol: &str,
+) -> Result<String> {
+    match exchange {
+        "binance" => exchanges::binance::fetch_open_interest(market_type, symbol),
+        "ftx" => exchanges::ftx::FtxRestClient::fetch_future(symbol),
+        "huobi" => exchanges::huobi::fetch_open_interest(market_type, symbol),
+        "kucoin" => exchanges::kucoin::KuCoinSwapRestClient::fetch_contract(symbol),
+        "okex" => exchanges::okex::OkexRestClient::fetch_open_interest(symbol),
+        _ => panic!(
+            "{} {} does NOT provide open interest via RESTful APIs",
+            exchange, market_type
+        ),
+    }
+}
+
+/// Fetch liquidation orders of a derivative contract.
+///
+/// Only exchanges without liquidation channels in WebSocket are supported,
+/// i.e., Huobi swap markets and OKEx.
+pub fn fetch_liquidation(exchange: &str, market_type: MarketType, symbol: &str) -> Result<String> {
+    match exchange {
+        "huobi" => exchanges::huobi::fetch_liquidation_orders(market_type, symbol),
+        "okex" => exchanges::okex::OkexRestClient::fetch_liquidation_orders(symbol),
+        _ => panic!(
+            "{} {} does NOT provide liquidation orders via RESTful APIs",
+            exchange, market_type
+        ),
+    }
+}
+
+/// Fetch mark price of a derivative contract.
+///
+/// Only FTX is supported, other exchanges push mark prices via WebSocket.
+pub fn fetch_mark_price(exchange: &str, market_type: MarketType, symbol: &str) -> Result<String> {
+    match exchange {
+        "ftx" => exchanges::ftx::FtxRestClient::fetch_future(symbol),
+        _ => panic!(
+            "{} {} does NOT provide mark price via RESTful APIs",
+            exchange, market_type
+        ),
+    }
+}
+
+/// Fetch index price of a derivative contract.
+///
+/// Only Binance USDT-margined markets, FTX and Huobi are supported, other
+/// exchanges push index prices via WebSocket.
+pub fn fetch_index_price(exchange: &str, market_type: MarketType, symbol: &str) -> Result<String> {
+    match exchange {
+        "binance" => exchanges::binance::fetch_index_price(market_type, symbol),
+        "ftx" => exchanges::ftx::FtxRestClient::fetch_future(symbol),
+        "huobi" => exchanges::huobi::fetch_index_price(market_type, symbol),
+        _ => panic!(
+            "{} {} does NOT provide index price via RESTful APIs",
+            exchange, market_type
+        ),
+    }
+}
+
+/// Fetch the funding rate of a perpetual swap.
+///
+/// Only exchanges without funding rate channels in WebSocket are supported,
+/// i.e., Bitfinex, FTX, Gate, KuCoin and MXC.
+pub fn fetch_funding_rate(exchange: &str, market_type: MarketType, symbol: &str) -> Result<String> {
+    match exchange {
+        "bitfinex" => exchanges::bitfinex::BitfinexRestClient::fetch_deriv_status(symbol),
+        "ftx" => exchanges::ftx::FtxRestClient::fetch_funding_rates(symbol),
+        "gate" => exchanges::gate::GateSwapRestClient::fetch_contract(symbol),
+        "kucoin" => exchanges::kucoin::KuCoinSwapRestClient::fetch_contract(symbol),
+        "mxc" => exchanges::mxc::mxc_swap::MxcSwapRestClient::fetch_funding_rate(symbol),
+        _ => panic!(
+            "{} {} does NOT provide funding rate via RESTful APIs",
+            exchange, market_type
+        ),
+    }
+}
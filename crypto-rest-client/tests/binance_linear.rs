@@ -1,7 +1,9 @@
 #[cfg(test)]
 mod linear_swap {
     use crypto_market_type::MarketType;
-    use crypto_rest_client::{fetch_l2_snapshot, BinanceLinearRestClient};
+    use crypto_rest_client::{
+        fetch_index_price, fetch_l2_snapshot, fetch_open_interest, BinanceLinearRestClient,
+    };
 
     #[test]
     fn test_agg_trades() {
@@ -14,6 +16,18 @@ mod linear_swap {
         let text = fetch_l2_snapshot("binance", MarketType::LinearSwap, "BTCUSDT").unwrap();
         assert!(text.starts_with("{"));
     }
+
+    #[test]
+    fn test_open_interest() {
+        let text = fetch_open_interest("binance", MarketType::LinearSwap, "BTCUSDT").unwrap();
+        assert!(text.contains("\"openInterest\""));
+    }
+
+    #[test]
+    fn test_index_price() {
+        let text = fetch_index_price("binance", MarketType::LinearSwap, "BTCUSDT").unwrap();
+        assert!(text.contains("\"indexPrice\""));
+    }
 }
 
 #[cfg(test)]
@@ -1,5 +1,7 @@
 use crypto_market_type::MarketType;
-use crypto_rest_client::{fetch_l2_snapshot, fetch_l3_snapshot, BitfinexRestClient};
+use crypto_rest_client::{
+    fetch_funding_rate, fetch_l2_snapshot, fetch_l3_snapshot, BitfinexRestClient,
+};
 
 #[test]
 fn test_trades() {
@@ -18,3 +20,9 @@ fn test_l3_snapshot() {
     let text = fetch_l3_snapshot("bitfinex", MarketType::Spot, "tBTCUSD").unwrap();
     assert!(text.starts_with("[["));
 }
+
+#[test]
+fn test_funding_rate() {
+    let text = fetch_funding_rate("bitfinex", MarketType::LinearSwap, "tBTCF0:USTF0").unwrap();
+    assert!(text.starts_with("[[\"tBTCF0:USTF0\""));
+}
@@ -16,3 +16,14 @@ fn test_l2_snapshot(market_type: MarketType, symbol: &str) {
     assert!(result.is_array());
     assert_eq!(result.as_array().unwrap().len(), 50);
 }
+
+#[test]
+fn test_spot_l2_snapshot() {
+    let text = fetch_l2_snapshot("bybit", MarketType::Spot, "BTCUSDT").unwrap();
+
+    let obj = serde_json::from_str::<HashMap<String, Value>>(&text).unwrap();
+    let result = obj.get("result").unwrap();
+
+    assert!(result.get("bids").unwrap().is_array());
+    assert!(result.get("asks").unwrap().is_array());
+}
@@ -1,7 +1,9 @@
 use std::collections::HashMap;
 
 use crypto_market_type::MarketType;
-use crypto_rest_client::fetch_l2_snapshot;
+use crypto_rest_client::{
+    fetch_funding_rate, fetch_index_price, fetch_l2_snapshot, fetch_mark_price, fetch_open_interest,
+};
 use serde_json::Value;
 use test_case::test_case;
 
@@ -19,3 +21,27 @@ fn test_l2_snapshot(market_type: MarketType, symbol: &str) {
     assert!(result.get("asks").unwrap().is_array());
     assert!(result.get("bids").unwrap().is_array())
 }
+
+#[test_case(MarketType::LinearSwap, "BTC-PERP")]
+#[test_case(MarketType::LinearFuture, "BTC-0625")]
+fn test_future(market_type: MarketType, symbol: &str) {
+    for text in [
+        fetch_open_interest("ftx", market_type, symbol).unwrap(),
+        fetch_mark_price("ftx", market_type, symbol).unwrap(),
+        fetch_index_price("ftx", market_type, symbol).unwrap(),
+    ] {
+        let obj = serde_json::from_str::<HashMap<String, Value>>(&text).unwrap();
+        let result = obj.get("result").unwrap().as_object().unwrap();
+
+        assert!(result.get("mark").unwrap().is_number());
+        assert!(result.get("index").unwrap().is_number());
+        assert!(result.get("openInterest").unwrap().is_number());
+    }
+}
+
+#[test]
+fn test_funding_rate() {
+    let text = fetch_funding_rate("ftx", MarketType::LinearSwap, "BTC-PERP").unwrap();
+    let obj = serde_json::from_str::<HashMap<String, Value>>(&text).unwrap();
+    assert!(obj.get("result").unwrap().is_array());
+}
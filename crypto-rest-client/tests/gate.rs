@@ -1,5 +1,5 @@
 use crypto_market_type::MarketType;
-use crypto_rest_client::fetch_l2_snapshot;
+use crypto_rest_client::{fetch_funding_rate, fetch_l2_snapshot};
 use serde_json::Value;
 use std::collections::HashMap;
 use test_case::test_case;
@@ -18,3 +18,12 @@ fn test_l2_snapshot(market_type: MarketType, symbol: &str) {
     let bids = obj.get("bids").unwrap().as_array().unwrap();
     assert!(!bids.is_empty());
 }
+
+#[test_case(MarketType::InverseSwap, "BTC_USD")]
+#[test_case(MarketType::LinearSwap, "BTC_USDT")]
+fn test_funding_rate(market_type: MarketType, symbol: &str) {
+    let text = fetch_funding_rate("gate", market_type, symbol).unwrap();
+    let obj = serde_json::from_str::<HashMap<String, Value>>(&text).unwrap();
+    assert_eq!(symbol, obj.get("name").unwrap().as_str().unwrap());
+    assert!(obj.contains_key("funding_rate"));
+}
@@ -0,0 +1,14 @@
+use crypto_market_type::MarketType;
+use crypto_rest_client::{fetch_l2_snapshot, GeminiRestClient};
+
+#[test]
+fn test_trades() {
+    let text = GeminiRestClient::fetch_trades("btcusd").unwrap();
+    assert!(text.starts_with("[{"));
+}
+
+#[test]
+fn test_l2_snapshot() {
+    let text = fetch_l2_snapshot("gemini", MarketType::Spot, "btcusd").unwrap();
+    assert!(text.starts_with("{"));
+}
@@ -1,5 +1,7 @@
 use crypto_market_type::MarketType;
-use crypto_rest_client::fetch_l2_snapshot;
+use crypto_rest_client::{
+    fetch_index_price, fetch_l2_snapshot, fetch_liquidation, fetch_open_interest,
+};
 use serde_json::Value;
 use std::collections::HashMap;
 use test_case::test_case;
@@ -47,6 +49,33 @@ fn test_l2_snapshot(market_type: MarketType, symbol: &str) {
     }
 }
 
+#[test_case(MarketType::InverseFuture, "BTC_CQ")]
+#[test_case(MarketType::InverseSwap, "BTC-USD")]
+#[test_case(MarketType::LinearSwap, "BTC-USDT")]
+fn test_open_interest(market_type: MarketType, symbol: &str) {
+    let text = fetch_open_interest("huobi", market_type, symbol).unwrap();
+    let obj = serde_json::from_str::<HashMap<String, Value>>(&text).unwrap();
+    assert_eq!("ok", obj.get("status").unwrap().as_str().unwrap());
+    assert!(!obj.get("data").unwrap().as_array().unwrap().is_empty());
+}
+
+#[test_case(MarketType::InverseFuture, "BTC_CQ")]
+#[test_case(MarketType::InverseSwap, "BTC-USD")]
+#[test_case(MarketType::LinearSwap, "BTC-USDT")]
+fn test_index_price(market_type: MarketType, symbol: &str) {
+    let text = fetch_index_price("huobi", market_type, symbol).unwrap();
+    let obj = serde_json::from_str::<HashMap<String, Value>>(&text).unwrap();
+    assert_eq!("ok", obj.get("status").unwrap().as_str().unwrap());
+}
+
+#[test_case(MarketType::InverseSwap, "BTC-USD")]
+#[test_case(MarketType::LinearSwap, "BTC-USDT")]
+fn test_liquidation(market_type: MarketType, symbol: &str) {
+    let text = fetch_liquidation("huobi", market_type, symbol).unwrap();
+    let obj = serde_json::from_str::<HashMap<String, Value>>(&text).unwrap();
+    assert_eq!("ok", obj.get("status").unwrap().as_str().unwrap());
+}
+
 #[cfg(test)]
 mod huobi_spot {
     use crypto_rest_client::HuobiSpotRestClient;
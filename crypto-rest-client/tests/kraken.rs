@@ -12,3 +12,9 @@ fn test_l2_snapshot() {
     let text = fetch_l2_snapshot("kraken", MarketType::Spot, "XXBTZUSD").unwrap();
     assert!(text.starts_with("{"));
 }
+
+#[test]
+fn test_futures_l2_snapshot() {
+    let text = fetch_l2_snapshot("kraken", MarketType::InverseSwap, "PI_XBTUSD").unwrap();
+    assert!(text.contains("orderBook"));
+}
@@ -1,5 +1,5 @@
 use crypto_market_type::MarketType;
-use crypto_rest_client::{fetch_l2_snapshot, fetch_l3_snapshot};
+use crypto_rest_client::{fetch_funding_rate, fetch_l2_snapshot, fetch_l3_snapshot};
 use serde_json::Value;
 use std::collections::HashMap;
 use test_case::test_case;
@@ -41,3 +41,12 @@ fn test_l3_snapshot(market_type: MarketType, symbol: &str) {
     let bids = data.get("bids").unwrap().as_array().unwrap();
     assert!(!bids.is_empty());
 }
+
+#[test_case(MarketType::InverseSwap, "XBTUSDM")]
+#[test_case(MarketType::LinearSwap, "XBTUSDTM")]
+fn test_funding_rate(market_type: MarketType, symbol: &str) {
+    let text = fetch_funding_rate("kucoin", market_type, symbol).unwrap();
+    let obj = serde_json::from_str::<HashMap<String, Value>>(&text).unwrap();
+    let data = obj.get("data").unwrap().as_object().unwrap();
+    assert!(data.contains_key("fundingFeeRate"));
+}
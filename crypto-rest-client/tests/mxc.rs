@@ -18,7 +18,7 @@ mod mxc_spot {
 #[cfg(test)]
 mod mxc_swap {
     use crypto_market_type::MarketType;
-    use crypto_rest_client::{fetch_l2_snapshot, MxcSwapRestClient};
+    use crypto_rest_client::{fetch_funding_rate, fetch_l2_snapshot, MxcSwapRestClient};
 
     #[test]
     fn test_trades() {
@@ -31,4 +31,10 @@ mod mxc_swap {
         let text = fetch_l2_snapshot("mxc", MarketType::LinearSwap, "BTC_USDT").unwrap();
         assert!(text.starts_with("{"));
     }
+
+    #[test]
+    fn test_funding_rate() {
+        let text = fetch_funding_rate("mxc", MarketType::LinearSwap, "BTC_USDT").unwrap();
+        assert!(text.contains("\"fundingRate\""));
+    }
 }
@@ -0,0 +1,14 @@
+use crypto_market_type::MarketType;
+use crypto_rest_client::{fetch_l2_snapshot, PoloniexRestClient};
+
+#[test]
+fn test_trades() {
+    let text = PoloniexRestClient::fetch_trades("BTC_USDT").unwrap();
+    assert!(text.starts_with("[{"));
+}
+
+#[test]
+fn test_l2_snapshot() {
+    let text = fetch_l2_snapshot("poloniex", MarketType::Spot, "BTC_USDT").unwrap();
+    assert!(text.starts_with("{"));
+}
@@ -0,0 +1,14 @@
+use crypto_market_type::MarketType;
+use crypto_rest_client::{fetch_l2_snapshot, UpbitRestClient};
+
+#[test]
+fn test_trades() {
+    let text = UpbitRestClient::fetch_trades("KRW-BTC").unwrap();
+    assert!(text.starts_with("[{"));
+}
+
+#[test]
+fn test_l2_snapshot() {
+    let text = fetch_l2_snapshot("upbit", MarketType::Spot, "KRW-BTC").unwrap();
+    assert!(text.starts_with("[{"));
+}
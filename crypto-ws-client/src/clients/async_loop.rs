@@ -0,0 +1,349 @@
+use super::{
+    auth::run_blocking_async,
+    endpoint::UrlProvider,
+    heartbeat::{Heartbeat, Watchdog},
+    proxy::{connect_async, Proxy},
+    reconnect::ReconnectPolicy,
+    recorder::Direction,
+    ws_client_internal::Handled,
+};
+use std::time::{Duration, Instant};
+
+use futures_util::{SinkExt, StreamExt};
+use log::*;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+use tokio_tungstenite::tungstenite::{Error, Message};
+
+// Everything the async event loop needs to know about a client.
+//
+// The loop owns the socket, while subscribe(), unsubscribe() and close() of
+// the client send messages to the loop via the sender passed to attach().
+pub(super) trait AsyncMessageHandler: Sync {
+    // The endpoint in use
+    fn url(&self) -> &str;
+
+    // The proxy of the client, None means environment variables
+    fn proxy(&self) -> Option<&Proxy>;
+
+    // Builds the url to connect to, None means `url()` as is
+    fn url_provider(&self) -> Option<UrlProvider> {
+        None
+    }
+
+    // Called after a connect or read failure, moves on to the next endpoint
+    fn failover(&self) {}
+
+    // Pings and idle timeouts of the connection
+    fn heartbeat(&self) -> &Heartbeat;
+
+    fn num_unanswered_ping(&self) -> isize {
+        0
+    }
+
+    fn on_ping_sent(&self) {}
+
+    fn on_pong(&self) {}
+
+    fn should_stop(&self) -> bool;
+
+    fn reconnect_policy(&self) -> ReconnectPolicy;
+
+    fn handle_text(&self, txt: &str) -> Handled;
+
+    fn handle_binary(&self, binary: &[u8]) -> Handled;
+
+    // Called for every frame read from or written to the socket
+    fn record(&self, _direction: Direction, _msg: &Message) {}
+
+    // Called for every frame read from the socket
+    fn on_frame(&self, _msg: &Message) {}
+
+    // Called after a read timed out without receiving anything
+    fn on_read_timeout(&self) {}
+
+    // Called after connected, messages sent to `tx` will be written to the
+    // socket; returns commands to subscribe all channels, which are allowed by
+    // the rate limit.
+    fn attach(&self, tx: UnboundedSender<Message>) -> Vec<String>;
+
+    // How often to call flush_commands(), None if commands are never queued
+    fn command_period(&self) -> Option<Duration> {
+        None
+    }
+
+    // Sends queued commands allowed by the rate limit to `tx` of attach()
+    fn flush_commands(&self) {}
+
+    // Called after the connection dropped
+    fn detach(&self);
+
+    // Called after connected and subscribed all channels for the first time
+    fn on_connected(&self);
+
+    // Called after the connection dropped, before reconnecting
+    fn on_disconnected(&self, reason: String);
+
+    // Called after reconnected and resubscribed all channels
+    fn on_reconnected(&self, attempts: u32);
+}
+
+// Timer period in seconds of disabled timers
+const FAR_FUTURE: u64 = 86400;
+
+// How a connection ended
+enum Exit {
+    Stop,
+    Reconnect(String), // why the connection is considered dropped
+}
+
+// Sleeps for `duration`, returns false immediately if the client was closed.
+async fn interruptible_sleep<H: AsyncMessageHandler + ?Sized>(
+    handler: &H,
+    duration: Duration,
+) -> bool {
+    let start = Instant::now();
+    while start.elapsed() < duration {
+        if handler.should_stop() {
+            return false;
+        }
+        tokio::time::sleep(std::cmp::min(
+            Duration::from_millis(100),
+            duration - start.elapsed().min(duration),
+        ))
+        .await;
+    }
+    !handler.should_stop()
+}
+
+// Connects, subscribes and reads messages until time is up or the client is
+// closed, reconnects following the reconnect policy if the connection drops.
+pub(super) async fn run_async<H: AsyncMessageHandler + ?Sized>(handler: &H, duration: Option<u64>) {
+    let start_timstamp = Instant::now();
+    let mut connected_before = false;
+    let mut needs_backoff = false;
+    let mut attempt = 0;
+    while !handler.should_stop() {
+        if needs_backoff {
+            attempt += 1;
+            let policy = handler.reconnect_policy();
+            if let Some(max_attempts) = policy.max_attempts {
+                if attempt > max_attempts {
+                    error!(
+                        "Failed to reconnect to {} after {} attempts, giving up",
+                        handler.url(),
+                        max_attempts
+                    );
+                    break;
+                }
+            }
+            let delay = policy.delay(attempt);
+            warn!(
+                "Reconnecting to {} in {} ms, attempt {}",
+                handler.url(),
+                delay.as_millis(),
+                attempt
+            );
+            if !interruptible_sleep(handler, delay).await {
+                break;
+            }
+        }
+
+        let url = match handler.url_provider() {
+            // The provider may send HTTP requests, which must not block the worker
+            Some(provider) => run_blocking_async(provider).await,
+            None => Ok(handler.url().to_string()),
+        };
+        let connected = match url {
+            Ok(url) => connect_async(&url, handler.proxy()).await,
+            Err(err) => Err(Error::Io(err)),
+        };
+        let ws_stream = match connected {
+            Ok((ws_stream, _)) => ws_stream,
+            Err(err) => {
+                warn!("Error connecting to {}, {}", handler.url(), err);
+                handler.failover();
+                needs_backoff = true;
+                continue;
+            }
+        };
+        let (mut write, mut read) = ws_stream.split();
+        let (tx, mut rx) = unbounded_channel::<Message>();
+        let commands = handler.attach(tx);
+        for command in commands {
+            let msg = Message::Text(command);
+            handler.record(Direction::Outbound, &msg);
+            if let Err(err) = write.send(msg).await {
+                error!("{}", err);
+            }
+        }
+        if connected_before {
+            handler.on_reconnected(attempt);
+        } else {
+            handler.on_connected();
+        }
+        connected_before = true;
+        needs_backoff = true;
+        attempt = 0;
+
+        let heartbeat = handler.heartbeat();
+        let mut watchdog = Watchdog::new(heartbeat);
+        let client_ping = heartbeat.ping.as_ref();
+        let ping_period = client_ping.map_or(Duration::from_secs(FAR_FUTURE), |x| x.0);
+        let mut ping_interval =
+            tokio::time::interval_at(tokio::time::Instant::now() + ping_period, ping_period);
+        let command_period = handler.command_period();
+        let flush_period = command_period.unwrap_or(Duration::from_secs(FAR_FUTURE));
+        let mut flush_interval =
+            tokio::time::interval_at(tokio::time::Instant::now() + flush_period, flush_period);
+        let idle_period = Duration::from_secs(heartbeat.read_timeout.unwrap_or(FAR_FUTURE));
+        let idle = tokio::time::sleep(idle_period);
+        tokio::pin!(idle);
+
+        let exit = loop {
+            if handler.should_stop() {
+                handler.record(Direction::Outbound, &Message::Close(None));
+                let _ = write.close().await;
+                break Exit::Stop;
+            }
+            tokio::select! {
+                resp = read.next() => {
+                    let msg = match resp {
+                        Some(Ok(msg)) => msg,
+                        Some(Err(err)) => {
+                            error!("Error thrown from read(): {}, {}", err, handler.url());
+                            handler.failover();
+                            break Exit::Reconnect(err.to_string());
+                        }
+                        None => {
+                            error!("Server closed connection {}", handler.url());
+                            break Exit::Reconnect("Server closed connection".to_string());
+                        }
+                    };
+                    watchdog.on_frame();
+                    idle.as_mut().reset(tokio::time::Instant::now() + idle_period);
+                    handler.record(Direction::Inbound, &msg);
+                    handler.on_frame(&msg);
+                    let handled = match msg {
+                        Message::Text(txt) => handler.handle_text(&txt),
+                        Message::Binary(binary) => handler.handle_binary(&binary),
+                        Message::Ping(resp) => {
+                            info!(
+                                "Received a ping frame: {}",
+                                String::from_utf8_lossy(&resp)
+                            );
+                            let pong = Message::Pong(resp);
+                            handler.record(Direction::Outbound, &pong);
+                            if let Err(err) = write.send(pong).await {
+                                error!("{}", err);
+                            }
+                            Handled::Misc
+                        }
+                        Message::Pong(resp) => {
+                            info!("Received a pong frame: {}", String::from_utf8_lossy(&resp));
+                            handler.on_pong();
+                            Handled::Misc
+                        }
+                        Message::Close(resp) => {
+                            match resp {
+                                Some(frame) => warn!("Received a Message::Close message with a CloseFrame: code: {}, reason: {}", frame.code, frame.reason),
+                                None => warn!("Received a close message without CloseFrame"),
+                            }
+                            Handled::Misc
+                        }
+                    };
+                    match handled {
+                        Handled::Reconnect => {
+                            break Exit::Reconnect("The server asked to reconnect".to_string())
+                        }
+                        Handled::ReconnectAfter(duration) => {
+                            if !interruptible_sleep(handler, duration).await {
+                                break Exit::Stop;
+                            }
+                            break Exit::Reconnect("The server asked to reconnect".to_string())
+                        }
+                        Handled::Pause(duration) => {
+                            if !interruptible_sleep(handler, duration).await {
+                                break Exit::Stop;
+                            }
+                            // nothing is expected while paused
+                            watchdog.reset();
+                            idle.as_mut().reset(tokio::time::Instant::now() + idle_period);
+                        }
+                        Handled::Normal => {
+                            watchdog.on_data();
+                            if let Some(seconds) = duration {
+                                if start_timstamp.elapsed() > Duration::from_secs(seconds) {
+                                    handler.record(Direction::Outbound, &Message::Close(None));
+                                    let _ = write.close().await;
+                                    break Exit::Stop;
+                                }
+                            }
+                        }
+                        Handled::Misc => (),
+                    }
+                    if let Some(reason) = watchdog.check(handler.num_unanswered_ping()) {
+                        error!("{}, duration: {} seconds", reason, start_timstamp.elapsed().as_secs());
+                        handler.failover();
+                        break Exit::Reconnect(reason);
+                    }
+                }
+                Some(msg) = rx.recv() => {
+                    let is_close = matches!(msg, Message::Close(_));
+                    handler.record(Direction::Outbound, &msg);
+                    if let Err(err) = write.send(msg).await {
+                        error!("{}", err);
+                    }
+                    if is_close {
+                        break Exit::Stop;
+                    }
+                }
+                _ = ping_interval.tick(), if client_ping.is_some() => {
+                    let (_, ping_msg) = client_ping.unwrap();
+                    let num_unanswered_ping = handler.num_unanswered_ping();
+                    if let Some(reason) = watchdog.check(num_unanswered_ping) {
+                        error!("{}, duration: {} seconds", reason, start_timstamp.elapsed().as_secs());
+                        handler.failover();
+                        break Exit::Reconnect(reason);
+                    }
+                    info!("Sending ping: {}", ping_msg);
+                    let ping_msg = if ping_msg.is_empty() {
+                        Message::Ping(Vec::new())
+                    } else {
+                        Message::Text(ping_msg.to_string())
+                    };
+                    handler.record(Direction::Outbound, &ping_msg);
+                    if let Err(err) = write.send(ping_msg).await {
+                        error!("{}", err);
+                    } else {
+                        watchdog.on_ping_sent(num_unanswered_ping);
+                        handler.on_ping_sent();
+                    }
+                }
+                _ = flush_interval.tick(), if command_period.is_some() => {
+                    handler.flush_commands();
+                }
+                _ = &mut idle, if heartbeat.read_timeout.is_some() => {
+                    info!("read() timeout");
+                    watchdog.on_read_timeout();
+                    handler.on_read_timeout();
+                    idle.as_mut().reset(tokio::time::Instant::now() + idle_period);
+                    if let Some(reason) = watchdog.check(handler.num_unanswered_ping()) {
+                        error!("{}, duration: {} seconds", reason, start_timstamp.elapsed().as_secs());
+                        handler.failover();
+                        break Exit::Reconnect(reason);
+                    }
+                }
+            }
+        };
+        handler.detach();
+        match exit {
+            Exit::Stop => break,
+            Exit::Reconnect(reason) => {
+                if handler.should_stop() {
+                    break;
+                }
+                handler.on_disconnected(reason);
+            }
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use std::{
+    fmt,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use hmac::{Hmac, Mac};
+use sha2::{Sha256, Sha512};
+
+/// API credentials of an exchange account, used by private clients.
+#[derive(Clone)]
+pub struct Credentials {
+    /// API key
+    pub api_key: String,
+    /// API secret
+    pub api_secret: String,
+    /// Passphrase, only OKEx needs it
+    pub passphrase: Option<String>,
+}
+
+impl Credentials {
+    pub fn new(api_key: &str, api_secret: &str) -> Self {
+        Credentials {
+            api_key: api_key.to_string(),
+            api_secret: api_secret.to_string(),
+            passphrase: None,
+        }
+    }
+
+    pub fn with_passphrase(api_key: &str, api_secret: &str, passphrase: &str) -> Self {
+        Credentials {
+            api_key: api_key.to_string(),
+            api_secret: api_secret.to_string(),
+            passphrase: Some(passphrase.to_string()),
+        }
+    }
+}
+
+// Never prints the secret and passphrase
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credentials")
+            .field("api_key", &self.api_key)
+            .finish()
+    }
+}
+
+// How a private client authenticates
+pub(super) enum Auth {
+    // Generates commands to authenticate a connection, they're sent on every
+    // connection before subscribing channels, since signatures expire.
+    Login(Box<dyn Fn() -> Vec<String> + Send + Sync>),
+    // A token attached to private channels, e.g., Binance listenKey and Kraken
+    // websocket token, the connection itself is not authenticated.
+    Token(String),
+}
+
+pub(super) fn hmac_sha256(secret: &[u8], msg: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).unwrap();
+    mac.update(msg);
+    mac.finalize().into_bytes().to_vec()
+}
+
+pub(super) fn hmac_sha512(secret: &[u8], msg: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha512>::new_from_slice(secret).unwrap();
+    mac.update(msg);
+    mac.finalize().into_bytes().to_vec()
+}
+
+// Milliseconds since UNIX epoch
+pub(super) fn current_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+// Sends a HTTP request to a REST API, e.g., to create a listenKey or a token.
+pub(super) fn http_request(
+    method: reqwest::Method,
+    url: &str,
+    headers: &[(&'static str, String)],
+    body: Option<String>,
+) -> reqwest::Result<String> {
+    let mut header_map = reqwest::header::HeaderMap::new();
+    for (name, value) in headers.iter() {
+        header_map.insert(
+            *name,
+            reqwest::header::HeaderValue::from_str(value).unwrap(),
+        );
+    }
+    let url = url.to_string();
+
+    run_blocking(move || {
+        let client = reqwest::blocking::Client::builder()
+            .default_headers(header_map)
+            .gzip(true)
+            .build()?;
+        let mut request = client.request(method, url);
+        if let Some(body) = body {
+            request = request
+                .header(
+                    reqwest::header::CONTENT_TYPE,
+                    "application/x-www-form-urlencoded",
+                )
+                .body(body);
+        }
+        let response = request.send()?;
+
+        match response.error_for_status() {
+            Ok(resp) => Ok(resp.text()?),
+            Err(error) => Err(error),
+        }
+    })
+}
+
+// Runs a blocking HTTP call. The blocking reqwest client panics inside a
+// tokio runtime, e.g., when a client is created in async code, so there the
+// call runs on a thread of its own. The caller still waits for it, which
+// blocks the calling thread, so async code awaits run_blocking_async()
+// instead.
+pub(super) fn run_blocking<T, F>(f: F) -> T
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    if tokio::runtime::Handle::try_current().is_ok() {
+        match std::thread::spawn(f).join() {
+            Ok(result) => result,
+            Err(panic) => std::panic::resume_unwind(panic),
+        }
+    } else {
+        f()
+    }
+}
+
+// Runs a blocking HTTP call on the blocking thread pool of tokio, so that
+// the worker keeps running other tasks while waiting.
+pub(super) async fn run_blocking_async<T, F>(f: F) -> T
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    match tokio::task::spawn_blocking(f).await {
+        Ok(result) => result,
+        Err(err) => std::panic::resume_unwind(err.into_panic()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_hmac() {
+        // Test case 2 of RFC 4231
+        let key = b"Jefe";
+        let data = b"what do ya want for nothing?";
+        assert_eq!(
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+            hex::encode(super::hmac_sha256(key, data))
+        );
+        assert_eq!(
+            "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea2505549758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737",
+            hex::encode(super::hmac_sha512(key, data))
+        );
+    }
+
+    #[test]
+    fn test_run_blocking_in_runtime() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        // Dropping a blocking client inside a runtime panics unless it is on
+        // another thread
+        let created = runtime.block_on(async {
+            super::run_blocking(|| reqwest::blocking::Client::builder().build().is_ok())
+        });
+        assert!(created);
+    }
+
+    #[test]
+    fn test_run_blocking_async() {
+        use std::sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        };
+        use std::time::Duration;
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        // The only worker keeps running other tasks while the call blocks
+        let ticked = Arc::new(AtomicBool::new(false));
+        let ticked_clone = ticked.clone();
+        let ticked_during_call = runtime.block_on(async move {
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                ticked_clone.store(true, Ordering::Release);
+            });
+            super::run_blocking_async(move || {
+                std::thread::sleep(Duration::from_millis(200));
+                assert!(reqwest::blocking::Client::builder().build().is_ok());
+                ticked.load(Ordering::Acquire)
+            })
+            .await
+        });
+        assert!(ticked_during_call);
+    }
+}
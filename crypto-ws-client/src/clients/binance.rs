@@ -1,19 +1,31 @@
-use crate::WSClient;
+use crate::{
+    Channel, ChannelKind, Credentials, PrivateWSClient, WSClient, WSClientOptions, WsError,
+};
 use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+use std::time::Duration;
 
 use super::{
+    auth::{self, Auth},
+    request,
     ws_client_internal::{MiscMessage, WSClientInternal},
-    Candlestick, OrderBook, OrderBookSnapshot, Ticker, Trade, BBO,
+    ChannelTranslator,
 };
+use crossbeam_channel::Sender;
 use log::*;
 use serde_json::Value;
 
 pub(super) const EXCHANGE_NAME: &str = "binance";
 
-const SPOT_WEBSOCKET_URL: &str = "wss://stream.binance.com:9443/stream";
-const LINEAR_WEBSOCKET_URL: &str = "wss://fstream.binance.com/stream";
-const INVERSE_WEBSOCKET_URL: &str = "wss://dstream.binance.com/stream";
+const SPOT_WEBSOCKET_URLS: &[&str] = &[
+    "wss://stream.binance.com:9443/stream",
+    "wss://stream.binance.com:443/stream",
+];
+const LINEAR_WEBSOCKET_URLS: &[&str] = &["wss://fstream.binance.com/stream"];
+const INVERSE_WEBSOCKET_URLS: &[&str] = &["wss://dstream.binance.com/stream"];
 
 // A single connection can listen to a maximum of 200 streams
 const MAX_NUM_CHANNELS: usize = 200;
@@ -21,9 +33,112 @@ const MAX_NUM_CHANNELS: usize = 200;
 // The websocket server will send a ping frame every 5 minutes
 const SERVER_PING_INTERVAL: u64 = 300;
 
+// REST endpoints to create and keep alive a listenKey
+const SPOT_LISTEN_KEY_URL: &str = "https://api.binance.com/api/v3/userDataStream";
+const LINEAR_LISTEN_KEY_URL: &str = "https://fapi.binance.com/fapi/v1/listenKey";
+const INVERSE_LISTEN_KEY_URL: &str = "https://dapi.binance.com/dapi/v1/listenKey";
+
+// A listenKey expires after 60 minutes without keepalive
+const LISTEN_KEY_KEEPALIVE_INTERVAL: u64 = 1800;
+
+// Seconds to wait before retrying to create a listenKey
+const LISTEN_KEY_RETRY_INTERVAL: u64 = 60;
+
 // Internal unified client
 struct BinanceWSClient<'a> {
     client: WSClientInternal<'a>,
+    keepalive: Option<ListenKeyKeepalive>, // Some if it is a private client
+}
+
+// Keeps a listenKey alive in a background thread until dropped
+struct ListenKeyKeepalive {
+    stopped: Arc<AtomicBool>,
+}
+
+impl ListenKeyKeepalive {
+    // Keeps `listen_key` alive, and re-creates it if it expired, e.g., after
+    // the keepalive failed for an hour, then sends the new one to `tx`.
+    fn start(
+        url: &'static str,
+        api_key: String,
+        mut listen_key: String,
+        tx: Sender<String>,
+    ) -> Self {
+        let stopped = Arc::new(AtomicBool::new(false));
+        let stopped_clone = stopped.clone();
+        std::thread::spawn(move || {
+            let mut elapsed = 0;
+            while !stopped_clone.load(Ordering::Acquire) {
+                std::thread::sleep(Duration::from_secs(1));
+                elapsed += 1;
+                if elapsed >= LISTEN_KEY_KEEPALIVE_INTERVAL {
+                    elapsed = 0;
+                    let result = auth::http_request(
+                        reqwest::Method::PUT,
+                        &format!("{}?listenKey={}", url, listen_key),
+                        &[("X-MBX-APIKEY", api_key.clone())],
+                        None,
+                    );
+                    if let Err(err) = result {
+                        warn!("Failed to keep alive listenKey, {}, {}", err, EXCHANGE_NAME);
+                        // Creating a listenKey returns the current one if it
+                        // is still valid, otherwise a new one
+                        match create_listen_key(url, &api_key) {
+                            Ok(new_listen_key) => {
+                                if new_listen_key != listen_key {
+                                    info!("Re-created listenKey, {}", EXCHANGE_NAME);
+                                    listen_key = new_listen_key;
+                                    if tx.send(listen_key.clone()).is_err() {
+                                        break; // the client was dropped
+                                    }
+                                }
+                            }
+                            Err(err) => {
+                                error!("{}", err);
+                                elapsed = LISTEN_KEY_KEEPALIVE_INTERVAL - LISTEN_KEY_RETRY_INTERVAL;
+                            }
+                        }
+                    }
+                }
+            }
+        });
+        ListenKeyKeepalive { stopped }
+    }
+}
+
+impl Drop for ListenKeyKeepalive {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Release);
+    }
+}
+
+// see https://binance-docs.github.io/apidocs/spot/en/#listen-key-spot
+fn create_listen_key(url: &str, api_key: &str) -> Result<String, WsError> {
+    let txt = auth::http_request(
+        reqwest::Method::POST,
+        url,
+        &[("X-MBX-APIKEY", api_key.to_string())],
+        None,
+    )
+    .map_err(|err| {
+        WsError::auth(
+            EXCHANGE_NAME,
+            &format!("failed to create listenKey, {}", err),
+        )
+    })?;
+    serde_json::from_str::<HashMap<String, Value>>(&txt)
+        .ok()
+        .and_then(|obj| {
+            obj.get("listenKey")
+                .and_then(|x| x.as_str())
+                .map(String::from)
+        })
+        .ok_or_else(|| {
+            WsError::auth(
+                EXCHANGE_NAME,
+                &format!("failed to create listenKey, {}", txt),
+            )
+        })
 }
 
 /// Binance Spot market.
@@ -51,17 +166,45 @@ pub struct BinanceLinearWSClient<'a> {
 }
 
 impl<'a> BinanceWSClient<'a> {
-    fn new(url: &str, on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>) -> Self {
+    fn new(
+        endpoints: &[&str],
+        on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>,
+        options: &WSClientOptions<'a>,
+    ) -> Self {
         BinanceWSClient {
             client: WSClientInternal::new(
                 EXCHANGE_NAME,
-                url,
+                endpoints,
+                None,
                 on_msg,
                 Self::on_misc_msg,
                 Self::channels_to_commands,
                 None,
                 Some(SERVER_PING_INTERVAL),
+                options,
             ),
+            keepalive: None,
+        }
+    }
+
+    fn new_async(
+        endpoints: &[&str],
+        on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>,
+        options: &WSClientOptions<'a>,
+    ) -> Self {
+        BinanceWSClient {
+            client: WSClientInternal::new_async(
+                EXCHANGE_NAME,
+                endpoints,
+                None,
+                on_msg,
+                Self::on_misc_msg,
+                Self::channels_to_commands,
+                None,
+                Some(SERVER_PING_INTERVAL),
+                options,
+            ),
+            keepalive: None,
         }
     }
 
@@ -72,32 +215,22 @@ impl<'a> BinanceWSClient<'a> {
             .map(|s| s.to_string())
             .collect();
 
-        let mut chunk: Vec<String> = Vec::new();
-        for channel in channels.iter().filter(|ch| !ch.starts_with('{')) {
-            chunk.push(channel.clone());
-            if chunk.len() >= MAX_NUM_CHANNELS {
-                let command = format!(
-                    r#"{{"id":9527,"method":"{}","params":{}}}"#,
-                    if subscribe {
-                        "SUBSCRIBE"
-                    } else {
-                        "UNSUBSCRIBE"
-                    },
-                    serde_json::to_string(&chunk).unwrap()
-                );
-                all_commands.push(command);
-                chunk.clear();
-            }
-        }
-        if !chunk.is_empty() {
+        let plain_channels: Vec<String> = channels
+            .iter()
+            .filter(|ch| !ch.starts_with('{'))
+            .cloned()
+            .collect();
+        for chunk in plain_channels.chunks(MAX_NUM_CHANNELS) {
+            // Each command has its own id, which the ack echoes
             let command = format!(
-                r#"{{"id":9527,"method":"{}","params":{}}}"#,
+                r#"{{"id":{},"method":"{}","params":{}}}"#,
+                request::register(chunk, subscribe),
                 if subscribe {
                     "SUBSCRIBE"
                 } else {
                     "UNSUBSCRIBE"
                 },
-                serde_json::to_string(&chunk).unwrap()
+                serde_json::to_string(chunk).unwrap()
             );
             all_commands.push(command);
         }
@@ -105,6 +238,13 @@ impl<'a> BinanceWSClient<'a> {
         all_commands
     }
 
+    // The user data stream is a stream named by the listenKey, which pushes
+    // orders, fills and balances altogether.
+    fn subscribe_user_data(&self) {
+        let listen_key = self.client.auth_token().expect("Not a private client");
+        self.client.subscribe(&[listen_key]);
+    }
+
     fn on_misc_msg(msg: &str) -> MiscMessage {
         let resp = serde_json::from_str::<HashMap<String, Value>>(&msg);
         if resp.is_err() {
@@ -113,14 +253,26 @@ impl<'a> BinanceWSClient<'a> {
         }
         let obj = resp.unwrap();
 
-        if obj.contains_key("error") {
+        let id = obj.get("id").and_then(|x| x.as_u64());
+        if let Some(err) = obj.get("error") {
+            // {"error":{"code":2,"msg":"Invalid request: unknown variant `SUBSCRIBEX`"},"id":9527}
             error!("Received {} from {}", msg, EXCHANGE_NAME);
-            panic!("Received {} from {}", msg, EXCHANGE_NAME);
+            // The error doesn't tell which channels are invalid, so all
+            // channels of the command are rejected
+            return request::on_error(
+                id,
+                err.get("code").and_then(|x| x.as_i64()),
+                err.get("msg").and_then(|x| x.as_str()).unwrap_or(msg),
+            );
         }
 
         if let Some(result) = obj.get("result") {
             if serde_json::Value::Null == *result {
-                return MiscMessage::Misc;
+                // {"result":null,"id":9527}
+                return match id {
+                    Some(id) => request::on_ack(id),
+                    None => MiscMessage::Misc,
+                };
             }
         }
 
@@ -137,18 +289,7 @@ fn to_raw_channel(channel: &str, pair: &str) -> String {
     format!("{}@{}", pair.to_lowercase(), channel)
 }
 
-#[rustfmt::skip]
-impl_trait!(Trade, BinanceWSClient, subscribe_trade, "aggTrade", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(Ticker, BinanceWSClient, subscribe_ticker, "ticker", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(BBO, BinanceWSClient, subscribe_bbo, "bookTicker", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(OrderBook, BinanceWSClient, subscribe_orderbook, "depth@100ms", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(OrderBookSnapshot, BinanceWSClient, subscribe_orderbook_snapshot, "depth20", to_raw_channel);
-
-fn to_candlestick_raw_channel(pair: &str, interval: u32) -> String {
+fn to_candlestick_raw_channel(pair: &str, interval: u32) -> Option<String> {
     let interval_str = match interval {
         60 => "1m",
         180 => "3m",
@@ -165,49 +306,103 @@ fn to_candlestick_raw_channel(pair: &str, interval: u32) -> String {
         259200 => "3d",
         604800 => "1w",
         2592000 => "1M",
-        _ => panic!("Binance has intervals 1m,3m,5m,15m,30m,1h,2h,4h,6h,8h,12h,1d,3d,1w,1M"),
+        _ => return None, // Binance has intervals 1m,3m,5m,15m,30m,1h,2h,4h,6h,8h,12h,1d,3d,1w,1M
     };
-    format!("{}@kline_{}", pair, interval_str)
+    Some(format!("{}@kline_{}", pair, interval_str))
 }
 
-impl_candlestick!(BinanceWSClient);
+impl<'a> ChannelTranslator for BinanceWSClient<'a> {
+    fn translate(&self, channel: &Channel) -> Result<String, WsError> {
+        match channel {
+            Channel::Trade(pair) => Some(to_raw_channel("aggTrade", pair)),
+            Channel::Ticker(pair) => Some(to_raw_channel("ticker", pair)),
+            Channel::BBO(pair) => Some(to_raw_channel("bookTicker", pair)),
+            Channel::L2 {
+                pair,
+                depth: None,
+                speed: None | Some(100),
+            } => Some(to_raw_channel("depth@100ms", pair)),
+            Channel::L2 {
+                pair,
+                depth: None,
+                speed: Some(1000),
+            } => Some(to_raw_channel("depth", pair)),
+            Channel::L2Snapshot(pair) => Some(to_raw_channel("depth20", pair)),
+            Channel::Kline { pair, interval } => {
+                return to_candlestick_raw_channel(pair, *interval)
+                    .ok_or_else(|| WsError::invalid_interval(EXCHANGE_NAME, *interval));
+            }
+            Channel::MarkPrice(pair) => Some(to_raw_channel("markPrice", pair)),
+            // Index prices are pushed per pair, e.g., btcusd of btcusd_perp
+            Channel::IndexPrice(pair) => Some(to_raw_channel(
+                "indexPrice",
+                pair.split('_').next().unwrap(),
+            )),
+            Channel::Liquidation(pair) => Some(to_raw_channel("forceOrder", pair)),
+            _ => None,
+        }
+        .ok_or_else(|| WsError::unsupported(EXCHANGE_NAME, channel))
+    }
+
+    // Channels of all markets, see define_market_client! for derivatives
+    fn capabilities(&self) -> Vec<ChannelKind> {
+        vec![
+            ChannelKind::Trade,
+            ChannelKind::L2,
+            ChannelKind::L2Snapshot,
+            ChannelKind::BBO,
+            ChannelKind::Ticker,
+            ChannelKind::Kline,
+        ]
+    }
+}
 
-/// Define market specific client.
+/// Define market specific client, `$kind`s are channels of the market besides
+/// those of all markets.
 macro_rules! define_market_client {
-    ($struct_name:ident, $default_url:ident) => {
+    ($struct_name:ident, $default_urls:ident $(, $kind:ident)*) => {
+        impl<'a> ChannelTranslator for $struct_name<'a> {
+            fn translate(&self, channel: &Channel) -> Result<String, WsError> {
+                self.client.translate(channel)
+            }
+
+            fn capabilities(&self) -> Vec<ChannelKind> {
+                let mut kinds = self.client.capabilities();
+                kinds.extend_from_slice(&[$(ChannelKind::$kind),*]);
+                kinds
+            }
+        }
+
         impl<'a> WSClient<'a> for $struct_name<'a> {
-            fn new(on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>, url: Option<&str>) -> Self {
-                let real_url = match url {
-                    Some(endpoint) => endpoint,
-                    None => $default_url,
-                };
-                $struct_name {
-                    client: BinanceWSClient::new(real_url, on_msg),
-                }
+            fn new_with_options(
+                on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>,
+                options: $crate::WSClientOptions<'a>,
+            ) -> Self {
+                $struct_name::new_blocking(on_msg, &options)
             }
 
-            fn subscribe_trade(&self, channels: &[String]) {
-                <$struct_name as Trade>::subscribe_trade(self, channels);
+            fn endpoint(&self) -> &str {
+                self.client.client.url()
             }
 
-            fn subscribe_orderbook(&self, channels: &[String]) {
-                <$struct_name as OrderBook>::subscribe_orderbook(self, channels);
+            fn connection_limits(&self) -> $crate::ConnectionLimits {
+                self.client.client.connection_limits()
             }
 
-            fn subscribe_orderbook_snapshot(&self, channels: &[String]) {
-                <$struct_name as OrderBookSnapshot>::subscribe_orderbook_snapshot(self, channels);
+            fn num_pending_commands(&self) -> usize {
+                self.client.client.num_pending_commands()
             }
 
-            fn subscribe_ticker(&self, channels: &[String]) {
-                <$struct_name as Ticker>::subscribe_ticker(self, channels);
+            fn metrics(&self) -> $crate::Metrics {
+                self.client.client.metrics()
             }
 
-            fn subscribe_bbo(&self, channels: &[String]) {
-                <$struct_name as BBO>::subscribe_bbo(self, channels);
+            fn translate_channels(&self, channels: &[Channel]) -> Result<Vec<String>, WsError> {
+                self.to_raw_channels(EXCHANGE_NAME, channels)
             }
 
-            fn subscribe_candlestick(&self, pairs: &[String], interval: u32) {
-                <$struct_name as Candlestick>::subscribe_candlestick(self, pairs, interval);
+            fn capabilities(&self) -> Vec<ChannelKind> {
+                ChannelTranslator::capabilities(self)
             }
 
             fn subscribe(&self, channels: &[String]) {
@@ -218,6 +413,12 @@ macro_rules! define_market_client {
                 self.client.client.unsubscribe(channels);
             }
 
+            fn subscriptions(
+                &self,
+            ) -> std::collections::HashMap<String, $crate::SubscriptionState> {
+                self.client.client.subscriptions()
+            }
+
             fn run(&self, duration: Option<u64>) {
                 self.client.client.run(duration);
             }
@@ -225,123 +426,250 @@ macro_rules! define_market_client {
             fn close(&self) {
                 self.client.client.close();
             }
-        }
-    };
-}
 
-define_market_client!(BinanceSpotWSClient, SPOT_WEBSOCKET_URL);
-define_market_client!(BinanceInverseWSClient, INVERSE_WEBSOCKET_URL);
-define_market_client!(BinanceLinearWSClient, LINEAR_WEBSOCKET_URL);
+            fn set_reconnect_policy(&self, policy: $crate::ReconnectPolicy) {
+                self.client.client.set_reconnect_policy(policy);
+            }
 
-macro_rules! impl_trade {
-    ($struct_name:ident) => {
-        impl<'a> Trade for $struct_name<'a> {
-            fn subscribe_trade(&self, pairs: &[String]) {
-                self.client.subscribe_trade(pairs);
+            fn on_reconnect(
+                &self,
+                callback: Arc<Mutex<dyn FnMut($crate::ReconnectEvent) + 'a + Send>>,
+            ) {
+                self.client.client.on_reconnect(callback);
             }
-        }
-    };
-}
 
-impl_trade!(BinanceSpotWSClient);
-impl_trade!(BinanceInverseWSClient);
-impl_trade!(BinanceLinearWSClient);
+            fn set_recorder(&self, recorder: $crate::Recorder) {
+                self.client.client.set_recorder(recorder);
+            }
 
-macro_rules! impl_ticker {
-    ($struct_name:ident) => {
-        impl<'a> Ticker for $struct_name<'a> {
-            fn subscribe_ticker(&self, pairs: &[String]) {
-                self.client.subscribe_ticker(pairs);
+            fn replay(
+                on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>,
+                replayer: &$crate::Replayer,
+            ) {
+                let options =
+                    $crate::WSClientOptions::from_url(replayer.url());
+                let client = $struct_name::new_async(on_msg, &options);
+                client.client.client.replay(replayer);
             }
         }
-    };
-}
-
-impl_ticker!(BinanceSpotWSClient);
-impl_ticker!(BinanceInverseWSClient);
-impl_ticker!(BinanceLinearWSClient);
 
-macro_rules! impl_bbo {
-    ($struct_name:ident) => {
-        impl<'a> BBO for $struct_name<'a> {
-            fn subscribe_bbo(&self, pairs: &[String]) {
-                self.client.subscribe_bbo(pairs);
+        impl<'a> $struct_name<'a> {
+            fn new_blocking(
+                on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>,
+                options: &$crate::WSClientOptions<'a>,
+            ) -> Self {
+                let urls = options.endpoint_urls();
+                let endpoints = if urls.is_empty() { $default_urls } else { &urls[..] };
+                $struct_name {
+                    client: BinanceWSClient::new(endpoints, on_msg, options),
+                }
             }
-        }
-    };
-}
 
-impl_bbo!(BinanceSpotWSClient);
-impl_bbo!(BinanceInverseWSClient);
-impl_bbo!(BinanceLinearWSClient);
+            fn new_async(
+                on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>,
+                options: &$crate::WSClientOptions<'a>,
+            ) -> Self {
+                let urls = options.endpoint_urls();
+                let endpoints = if urls.is_empty() { $default_urls } else { &urls[..] };
+                $struct_name {
+                    client: BinanceWSClient::new_async(endpoints, on_msg, options),
+                }
+            }
 
-macro_rules! impl_orderbook {
-    ($struct_name:ident) => {
-        impl<'a> OrderBook for $struct_name<'a> {
-            fn subscribe_orderbook(&self, pairs: &[String]) {
-                self.client.subscribe_orderbook(pairs);
+            async fn run_async(&self, duration: Option<u64>) {
+                self.client.client.run_async(duration).await;
             }
         }
     };
 }
 
-impl_orderbook!(BinanceSpotWSClient);
-impl_orderbook!(BinanceInverseWSClient);
-impl_orderbook!(BinanceLinearWSClient);
+macro_rules! impl_private {
+    ($struct_name:ident, $listen_key_url:ident) => {
+        impl<'a> PrivateWSClient<'a> for $struct_name<'a> {
+            fn new_private(
+                on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>,
+                credentials: Credentials,
+                url: Option<&str>,
+            ) -> Result<Self, WsError> {
+                let listen_key = create_listen_key($listen_key_url, &credentials.api_key)?;
+                let mut ws_client = $struct_name::new(on_msg, url);
+                ws_client
+                    .client
+                    .client
+                    .set_auth(Auth::Token(listen_key.clone()));
+                let (tx, rx) = crossbeam_channel::unbounded();
+                ws_client.client.client.set_token_renewal(rx);
+                ws_client.client.keepalive = Some(ListenKeyKeepalive::start(
+                    $listen_key_url,
+                    credentials.api_key,
+                    listen_key,
+                    tx,
+                ));
+                Ok(ws_client)
+            }
 
-macro_rules! impl_orderbook_snapshot {
-    ($struct_name:ident) => {
-        impl<'a> OrderBookSnapshot for $struct_name<'a> {
-            fn subscribe_orderbook_snapshot(&self, pairs: &[String]) {
-                self.client.subscribe_orderbook_snapshot(pairs);
+            // The user data stream contains orders of all symbols
+            fn subscribe_orders(&self, _symbols: &[String]) {
+                self.client.subscribe_user_data();
             }
-        }
-    };
-}
 
-impl_orderbook_snapshot!(BinanceSpotWSClient);
-impl_orderbook_snapshot!(BinanceInverseWSClient);
-impl_orderbook_snapshot!(BinanceLinearWSClient);
+            // The user data stream contains fills of all symbols
+            fn subscribe_fills(&self, _symbols: &[String]) {
+                self.client.subscribe_user_data();
+            }
 
-macro_rules! impl_candlestick {
-    ($struct_name:ident) => {
-        impl<'a> Candlestick for $struct_name<'a> {
-            fn subscribe_candlestick(&self, pairs: &[String], interval: u32) {
-                self.client.subscribe_candlestick(pairs, interval);
+            // The user data stream contains balances of all currencies
+            fn subscribe_balances(&self, _currencies: &[String]) {
+                self.client.subscribe_user_data();
             }
         }
     };
 }
 
-impl_candlestick!(BinanceSpotWSClient);
-impl_candlestick!(BinanceInverseWSClient);
-impl_candlestick!(BinanceLinearWSClient);
+define_market_client!(BinanceSpotWSClient, SPOT_WEBSOCKET_URLS);
+define_async_client!(BinanceSpotAsyncWSClient, BinanceSpotWSClient);
+define_market_client!(
+    BinanceInverseWSClient,
+    INVERSE_WEBSOCKET_URLS,
+    MarkPrice,
+    IndexPrice,
+    Liquidation
+);
+define_async_client!(BinanceInverseAsyncWSClient, BinanceInverseWSClient);
+// USDT-margined futures push index prices within markPrice
+define_market_client!(
+    BinanceLinearWSClient,
+    LINEAR_WEBSOCKET_URLS,
+    MarkPrice,
+    Liquidation
+);
+define_async_client!(BinanceLinearAsyncWSClient, BinanceLinearWSClient);
+
+impl_private!(BinanceSpotWSClient, SPOT_LISTEN_KEY_URL);
+impl_private!(BinanceInverseWSClient, INVERSE_LISTEN_KEY_URL);
+impl_private!(BinanceLinearWSClient, LINEAR_LISTEN_KEY_URL);
 
 #[cfg(test)]
 mod tests {
+    use super::super::request::Requests;
+    use super::super::ws_client_internal::MiscMessage;
+    use serde_json::{json, Value};
+
     #[test]
     fn test_one_channel() {
-        let commands = super::BinanceWSClient::channels_to_commands(
-            &vec!["btcusdt@aggTrade".to_string()],
-            true,
-        );
-        assert_eq!(1, commands.len());
-        assert_eq!(
-            r#"{"id":9527,"method":"SUBSCRIBE","params":["btcusdt@aggTrade"]}"#,
-            commands[0]
-        );
+        // ids are registered to the requests of a client
+        Requests::default().scope(|| {
+            let commands = super::BinanceWSClient::channels_to_commands(
+                &vec!["btcusdt@aggTrade".to_string()],
+                true,
+            );
+            assert_eq!(1, commands.len());
+            let command = serde_json::from_str::<Value>(&commands[0]).unwrap();
+            assert_eq!(json!("SUBSCRIBE"), command["method"]);
+            assert_eq!(json!(["btcusdt@aggTrade"]), command["params"]);
+
+            // the ack confirms channels of the command with the same id
+            let ack = format!(r#"{{"result":null,"id":{}}}"#, command["id"]);
+            assert!(matches!(
+                super::BinanceWSClient::on_misc_msg(&ack),
+                MiscMessage::Subscribed(channels) if channels == vec!["btcusdt@aggTrade".to_string()]
+            ));
+        });
     }
 
     #[test]
     fn test_two_channels() {
-        let commands = super::BinanceWSClient::channels_to_commands(
-            &vec!["btcusdt@aggTrade".to_string(), "btcusdt@ticker".to_string()],
-            true,
+        // ids are registered to the requests of a client
+        Requests::default().scope(|| {
+            let commands = super::BinanceWSClient::channels_to_commands(
+                &vec!["btcusdt@aggTrade".to_string(), "btcusdt@ticker".to_string()],
+                true,
+            );
+            assert_eq!(1, commands.len());
+            let command = serde_json::from_str::<Value>(&commands[0]).unwrap();
+            assert_eq!(
+                json!(["btcusdt@aggTrade", "btcusdt@ticker"]),
+                command["params"]
+            );
+
+            let error = format!(
+                r#"{{"error":{{"code":2,"msg":"Invalid request"}},"id":{}}}"#,
+                command["id"]
+            );
+            assert!(matches!(
+                super::BinanceWSClient::on_misc_msg(&error),
+                MiscMessage::Rejected { channels, code: Some(2), .. } if channels.len() == 2
+            ));
+        });
+    }
+
+    #[test]
+    fn test_chunks() {
+        let channels: Vec<String> = (0..250).map(|i| format!("pair{}@aggTrade", i)).collect();
+        let commands = super::BinanceWSClient::channels_to_commands(&channels, false);
+        assert_eq!(2, commands.len());
+        let ids: Vec<u64> = commands
+            .iter()
+            .map(|x| {
+                serde_json::from_str::<Value>(x).unwrap()["id"]
+                    .as_u64()
+                    .unwrap()
+            })
+            .collect();
+        assert_ne!(ids[0], ids[1]);
+    }
+
+    #[test]
+    fn test_translate() {
+        use super::super::channel::Channel;
+        use crate::WSClient;
+        use std::sync::{Arc, Mutex};
+
+        let ws_client = super::BinanceSpotWSClient::new_async(
+            Arc::new(Mutex::new(|_| {})),
+            &Default::default(),
         );
-        assert_eq!(1, commands.len());
+        // channels in examples of Channel and README.md
+        let channels = vec![
+            Channel::Trade("btcusdt".to_string()),
+            Channel::l2("btcusdt").speed(1000),
+            Channel::l2("btcusdt").speed(100),
+            Channel::kline("btcusdt", 60),
+        ];
         assert_eq!(
-            r#"{"id":9527,"method":"SUBSCRIBE","params":["btcusdt@aggTrade","btcusdt@ticker"]}"#,
-            commands[0]
+            vec![
+                "btcusdt@aggTrade".to_string(),
+                "btcusdt@depth".to_string(),
+                "btcusdt@depth@100ms".to_string(),
+                "btcusdt@kline_1m".to_string(),
+            ],
+            ws_client.translate_channels(&channels).unwrap()
+        );
+        assert!(ws_client
+            .translate_channels(&[Channel::l2("btcusdt").speed(500)])
+            .is_err());
+    }
+
+    #[test]
+    fn test_renew_listen_key() {
+        use std::sync::{Arc, Mutex};
+
+        // the async client connects in run(), so nothing is sent
+        let ws_client = super::BinanceSpotWSClient::new_async(
+            Arc::new(Mutex::new(|_| {})),
+            &Default::default(),
         );
+        let client = &ws_client.client.client;
+        client.set_auth(super::Auth::Token("old_key".to_string()));
+        let (tx, rx) = crossbeam_channel::unbounded();
+        client.set_token_renewal(rx);
+        ws_client.client.subscribe_user_data();
+
+        tx.send("new_key".to_string()).unwrap();
+        client.subscribe(&["btcusdt@aggTrade".to_string()]);
+        assert_eq!(Some("new_key".to_string()), client.auth_token());
+        let subscriptions = client.subscriptions();
+        assert!(subscriptions.contains_key("new_key"));
+        assert!(!subscriptions.contains_key("old_key"));
     }
 }
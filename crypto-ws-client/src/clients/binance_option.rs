@@ -1,10 +1,11 @@
-use crate::WSClient;
+use crate::{Channel, ChannelKind, WSClient, WsError};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
 use super::{
+    request,
     ws_client_internal::{MiscMessage, WSClientInternal},
-    Candlestick, OrderBook, OrderBookSnapshot, Ticker, Trade, BBO,
+    ChannelTranslator,
 };
 use log::*;
 use serde_json::Value;
@@ -33,8 +34,10 @@ fn channels_to_commands(channels: &[String], subscribe: bool) -> Vec<String> {
         .collect();
 
     if !channels_to_parse.is_empty() {
+        let raw_channels: Vec<String> = channels_to_parse.iter().map(|x| x.to_string()).collect();
         all_commands.append(&mut vec![format!(
-            r#"{{"id":9527,"method":"{}","params":{}}}"#,
+            r#"{{"id":{},"method":"{}","params":{}}}"#,
+            request::register(&raw_channels, subscribe),
             if subscribe {
                 "SUBSCRIBE"
             } else {
@@ -48,9 +51,7 @@ fn channels_to_commands(channels: &[String], subscribe: bool) -> Vec<String> {
 }
 
 fn on_misc_msg(msg: &str) -> MiscMessage {
-    if msg == r#"{"id":9527}"# {
-        return MiscMessage::Misc;
-    } else if msg == r#"{"event":"pong"}"# {
+    if msg == r#"{"event":"pong"}"# {
         return MiscMessage::Pong;
     }
 
@@ -60,16 +61,26 @@ fn on_misc_msg(msg: &str) -> MiscMessage {
         return MiscMessage::Misc;
     }
     let obj = resp.unwrap();
+    let id = obj.get("id").and_then(|x| x.as_u64());
 
-    if obj.contains_key("error") {
+    if let Some(err) = obj.get("error") {
+        // {"error":{"code":2,"msg":"Invalid request: unknown variant `SUBSCRIBEX`"},"id":9527}
         error!("Received {} from {}", msg, EXCHANGE_NAME);
-        panic!("Received {} from {}", msg, EXCHANGE_NAME);
+        // The error doesn't tell which channels are invalid, so all channels
+        // of the command are rejected
+        return request::on_error(
+            id,
+            err.get("code").and_then(|x| x.as_i64()),
+            err.get("msg").and_then(|x| x.as_str()).unwrap_or(msg),
+        );
     }
 
-    if let Some(result) = obj.get("result") {
-        if serde_json::Value::Null == *result {
-            return MiscMessage::Misc;
-        }
+    // {"id":9527}, or {"result":null,"id":9527}
+    if obj.len() == 1 || obj.get("result") == Some(&Value::Null) {
+        return match id {
+            Some(id) => request::on_ack(id),
+            None => MiscMessage::Misc,
+        };
     }
 
     if !obj.contains_key("stream") || !obj.contains_key("data") {
@@ -89,23 +100,13 @@ define_client!(
     Some(CLIENT_PING_INTERVAL_AND_MSG),
     None
 );
+define_async_client!(BinanceOptionAsyncWSClient, BinanceOptionWSClient);
 
 fn to_raw_channel(channel: &str, pair: &str) -> String {
     format!("{}@{}", pair, channel)
 }
 
-#[rustfmt::skip]
-impl_trait!(Trade, BinanceOptionWSClient, subscribe_trade, "trade", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(Ticker, BinanceOptionWSClient, subscribe_ticker, "ticker", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(BBO, BinanceOptionWSClient, subscribe_bbo, "bookTicker", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(OrderBook, BinanceOptionWSClient, subscribe_orderbook, "depth@100ms", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(OrderBookSnapshot, BinanceOptionWSClient, subscribe_orderbook_snapshot, "depth100", to_raw_channel);
-
-fn to_candlestick_raw_channel(pair: &str, interval: u32) -> String {
+fn to_candlestick_raw_channel(pair: &str, interval: u32) -> Option<String> {
     let interval_str = match interval {
         60 => "1m",
         300 => "5m",
@@ -115,9 +116,40 @@ fn to_candlestick_raw_channel(pair: &str, interval: u32) -> String {
         14400 => "4h",
         86400 => "1d",
         604800 => "1w",
-        _ => panic!("Binance has intervals 1m,5m,15m,30m,1h4h,1d,1w"),
+        _ => return None, // Binance has intervals 1m,5m,15m,30m,1h4h,1d,1w
     };
-    format!("{}@kline_{}", pair, interval_str)
+    Some(format!("{}@kline_{}", pair, interval_str))
 }
 
-impl_candlestick!(BinanceOptionWSClient);
+impl<'a> ChannelTranslator for BinanceOptionWSClient<'a> {
+    fn translate(&self, channel: &Channel) -> Result<String, WsError> {
+        match channel {
+            Channel::Trade(pair) => Some(to_raw_channel("trade", pair)),
+            Channel::Ticker(pair) => Some(to_raw_channel("ticker", pair)),
+            Channel::BBO(pair) => Some(to_raw_channel("bookTicker", pair)),
+            Channel::L2 {
+                pair,
+                depth: None,
+                speed: None | Some(100),
+            } => Some(to_raw_channel("depth@100ms", pair)),
+            Channel::L2Snapshot(pair) => Some(to_raw_channel("depth100", pair)),
+            Channel::Kline { pair, interval } => {
+                return to_candlestick_raw_channel(pair, *interval)
+                    .ok_or_else(|| WsError::invalid_interval(EXCHANGE_NAME, *interval));
+            }
+            _ => None,
+        }
+        .ok_or_else(|| WsError::unsupported(EXCHANGE_NAME, channel))
+    }
+
+    fn capabilities(&self) -> Vec<ChannelKind> {
+        vec![
+            ChannelKind::Trade,
+            ChannelKind::L2,
+            ChannelKind::L2Snapshot,
+            ChannelKind::BBO,
+            ChannelKind::Ticker,
+            ChannelKind::Kline,
+        ]
+    }
+}
@@ -1,22 +1,38 @@
-use crate::{Level3OrderBook, WSClient};
+use crate::{
+    Channel, ChannelKind, ConnectionLimits, Level3OrderBook, ReconnectEvent, ReconnectPolicy,
+    WSClient, WSClientOptions, WsError, WsEvent,
+};
 
 use std::sync::{
-    atomic::{AtomicBool, Ordering},
+    atomic::{AtomicBool, AtomicIsize, Ordering},
     Arc, Mutex,
 };
 use std::{
-    collections::{HashMap, HashSet},
+    collections::HashMap,
     time::{Duration, Instant},
 };
 
 use super::{
-    utils::{connect_with_retry, CHANNEL_PAIR_DELIMITER},
-    Candlestick, OrderBook, OrderBookSnapshot, Ticker, Trade, BBO,
+    async_loop::{run_async, AsyncMessageHandler},
+    endpoint::Endpoints,
+    event::{self, EventCallback},
+    heartbeat::{Heartbeat, Watchdog},
+    limits::connection_limits,
+    metrics::{Metrics, MetricsCollector},
+    proxy::Proxy,
+    rate_limit::RateLimiter,
+    reconnect::{interruptible_sleep, reconnect_with_backoff, ReconnectCallback},
+    recorder::{Direction, RecordedFrame, Recorder, Replayer},
+    subscription::{SubscriptionState, Subscriptions},
+    utils::{connect_with_retry, set_read_timeout, CHANNEL_PAIR_DELIMITER},
+    ws_client_internal::Handled,
+    ChannelTranslator,
 };
 
 use log::*;
 use serde_json::Value;
-use tungstenite::{client::AutoStream, error::ProtocolError, Error, Message, WebSocket};
+use tokio::sync::mpsc::UnboundedSender;
+use tungstenite::{client::AutoStream, Error, Message, WebSocket};
 
 pub(super) const EXCHANGE_NAME: &str = "bitfinex";
 
@@ -33,14 +49,26 @@ const SERVER_PING_INTERVAL: u64 = 15;
 /// * Swap: <https://trading.bitfinex.com/t/BTCF0:USTF0>
 /// * Funding: <https://trading.bitfinex.com/funding>
 pub struct BitfinexWSClient<'a> {
-    ws_stream: Mutex<WebSocket<AutoStream>>,
-    channels: Mutex<HashSet<String>>, // subscribed channels
-    on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>, // user defined message callback
-    channel_id_meta: Mutex<HashMap<i64, String>>, // CHANNEL_ID information
-    should_stop: AtomicBool,          // used by close() and run()
+    endpoints: Endpoints,                                // Websocket base urls
+    ws_stream: Mutex<Option<WebSocket<AutoStream>>>,     // None if created by new_async()
+    command_tx: Mutex<Option<UnboundedSender<Message>>>, // sends messages to the async run loop
+    subscriptions: Subscriptions,                        // subscribed channels and their states
+    on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>,   // user defined message callback
+    on_event: Option<EventCallback<'a>>,                 // user defined event callback
+    channel_id_meta: Mutex<HashMap<i64, String>>,        // CHANNEL_ID information
+    should_stop: AtomicBool,                             // used by close() and run()
+    heartbeat: Heartbeat,                                // pings and idle timeouts
+    num_unanswered_ping: AtomicIsize,                    // client pings without a pong
+    reconnect_policy: Mutex<ReconnectPolicy>,
+    on_reconnect: Mutex<Option<ReconnectCallback<'a>>>,
+    rate_limiter: RateLimiter, // queues commands over the rate limit
+    recorder: Mutex<Option<Recorder>>, // records all frames if Some
+    metrics: MetricsCollector, // health metrics of the connection
 }
 
-fn channel_to_command(channel: &str, subscribe: bool) -> String {
+// Only channels like `trades:tBTCUSD` are converted, JSON raw channels are
+// subscribe commands already.
+fn channel_to_command(channel: &str) -> String {
     if channel.starts_with('{') {
         return channel.to_string();
     }
@@ -49,118 +77,128 @@ fn channel_to_command(channel: &str, subscribe: bool) -> String {
     let symbol = &channel[(delim + 1)..];
 
     format!(
-        r#"{{"event": "{}", "channel": "{}", "symbol": "{}"}}"#,
-        if subscribe {
-            "subscribe"
-        } else {
-            "unsubscribe"
-        },
-        ch,
-        symbol
+        r#"{{"event": "subscribe", "channel": "{}", "symbol": "{}"}}"#,
+        ch, symbol
     )
 }
 
-fn channels_to_commands(channels: &[String], subscribe: bool) -> Vec<String> {
-    channels
+fn channels_to_commands(channels: &[String]) -> Vec<String> {
+    channels.iter().map(|s| channel_to_command(s)).collect()
+}
+
+// Bitfinex unsubscribes by the CHANNEL_ID assigned when subscribed
+fn unsubscribe_command(chan_id: i64) -> String {
+    format!(r#"{{"event":"unsubscribe","chanId":{}}}"#, chan_id)
+}
+
+// Fields which identify a channel, both in subscribe commands and in
+// subscribed and error events.
+const CHANNEL_FIELDS: [&str; 6] = ["channel", "symbol", "key", "prec", "freq", "len"];
+
+// Identifying fields of a raw channel or of an event, `len` is a number in
+// commands but a string in events, so all values are strings. `freq` of books
+// defaults to F0, which raw book commands omit.
+fn channel_fields(obj: &HashMap<String, Value>) -> Vec<String> {
+    let is_book = obj.get("channel").and_then(|x| x.as_str()) == Some("book");
+    CHANNEL_FIELDS
         .iter()
-        .map(|s| channel_to_command(s, subscribe))
+        .map(|field| match obj.get(*field) {
+            Some(Value::String(s)) => s.clone(),
+            Some(value) => value.to_string(),
+            None if *field == "freq" && is_book => "F0".to_string(),
+            None => String::new(),
+        })
         .collect()
 }
 
-macro_rules! impl_trait_for_bitfinex {
-    ($trait_name:ident, $method_name:ident, $channel_name:expr) => {
-        impl<'a> $trait_name for BitfinexWSClient<'a> {
-            fn $method_name(&self, symbols: &[String]) {
-                let symbol_to_raw_channel =
-                    |symbol: &String| format!("{}:{}", $channel_name, symbol);
-
-                let channels = symbols
-                    .iter()
-                    .map(symbol_to_raw_channel)
-                    .collect::<Vec<String>>();
-                self.subscribe(&channels);
+fn raw_channel_fields(channel: &str) -> Option<Vec<String>> {
+    let obj = if channel.starts_with('{') {
+        serde_json::from_str::<HashMap<String, Value>>(channel).ok()?
+    } else {
+        let (ch, symbol) = channel.split_once(CHANNEL_PAIR_DELIMITER)?;
+        let mut obj = HashMap::new();
+        obj.insert("channel".to_string(), Value::from(ch));
+        obj.insert("symbol".to_string(), Value::from(symbol));
+        obj
+    };
+    Some(channel_fields(&obj))
+}
+
+// Bitfinex WebSocket does NOT have orderbook snapshot channel
+impl<'a> ChannelTranslator for BitfinexWSClient<'a> {
+    fn translate(&self, channel: &Channel) -> Result<String, WsError> {
+        match channel {
+            Channel::Trade(symbol) => Some(format!("trades{}{}", CHANNEL_PAIR_DELIMITER, symbol)),
+            Channel::Ticker(symbol) => Some(format!("ticker{}{}", CHANNEL_PAIR_DELIMITER, symbol)),
+            Channel::BBO(symbol) => Some(book_raw_channel(symbol, "R0", 1)),
+            Channel::L2 {
+                pair,
+                depth: None,
+                speed: None,
+            } => Some(book_raw_channel(pair, "P0", 25)),
+            Channel::L2 {
+                pair,
+                depth: Some(len @ (1 | 25 | 100 | 250)),
+                speed: None,
+            } => Some(book_raw_channel(pair, "P0", *len)),
+            Channel::L3(symbol) => Some(book_raw_channel(symbol, "R0", 250)),
+            Channel::Kline { pair, interval } => {
+                return to_candlestick_raw_channel(pair, *interval)
+                    .ok_or_else(|| WsError::invalid_interval(EXCHANGE_NAME, *interval));
             }
+            _ => None,
         }
-    };
+        .ok_or_else(|| WsError::unsupported(EXCHANGE_NAME, channel))
+    }
+
+    fn capabilities(&self) -> Vec<ChannelKind> {
+        vec![
+            ChannelKind::Trade,
+            ChannelKind::L2,
+            ChannelKind::L3,
+            ChannelKind::BBO,
+            ChannelKind::Ticker,
+            ChannelKind::Kline,
+        ]
+    }
 }
 
-impl_trait_for_bitfinex!(Trade, subscribe_trade, "trades");
-impl_trait_for_bitfinex!(Ticker, subscribe_ticker, "ticker");
+impl<'a> Level3OrderBook for BitfinexWSClient<'a> {
+    fn subscribe_l3_orderbook(&self, symbols: &[String]) -> Result<(), WsError> {
+        let channels: Vec<Channel> = symbols.iter().cloned().map(Channel::L3).collect();
+        self.subscribe_channels(&channels)
+    }
+}
 
-impl<'a> BBO for BitfinexWSClient<'a> {
-    fn subscribe_bbo(&self, symbols: &[String]) {
-        let raw_channels = symbols
-            .iter()
-            .map(|symbol| {
-                format!(
-                    r#"{{
+// The `book` channel, `prec` is R0 for raw books, P0 for aggregated books.
+fn book_raw_channel(symbol: &str, prec: &str, len: u32) -> String {
+    if prec == "R0" {
+        format!(
+            r#"{{
                 "event": "subscribe",
                 "channel": "book",
                 "symbol": "{}",
                 "prec": "R0",
-                "len": 1
-              }}"#,
-                    symbol
-                )
-            })
-            .collect::<Vec<String>>();
-
-        self.subscribe(&raw_channels);
-    }
-}
-
-impl<'a> OrderBook for BitfinexWSClient<'a> {
-    fn subscribe_orderbook(&self, symbols: &[String]) {
-        let raw_channels = symbols
-            .iter()
-            .map(|symbol| {
-                format!(
-                    r#"{{
+                "len": {}
+            }}"#,
+            symbol, len
+        )
+    } else {
+        format!(
+            r#"{{
                 "event": "subscribe",
                 "channel": "book",
                 "symbol": "{}",
-                "prec": "P0",
-                "frec": "F0",
-                "len": 25
-              }}"#,
-                    symbol
-                )
-            })
-            .collect::<Vec<String>>();
-
-        self.subscribe(&raw_channels);
-    }
-}
-
-impl<'a> OrderBookSnapshot for BitfinexWSClient<'a> {
-    fn subscribe_orderbook_snapshot(&self, _symbols: &[String]) {
-        panic!("Bitfinex does NOT have orderbook snapshot channel");
+                "prec": "{}",
+                "freq": "F0",
+                "len": {}
+            }}"#,
+            symbol, prec, len
+        )
     }
 }
 
-impl<'a> Level3OrderBook for BitfinexWSClient<'a> {
-    fn subscribe_l3_orderbook(&self, symbols: &[String]) {
-        let raw_channels = symbols
-            .iter()
-            .map(|symbol| {
-                format!(
-                    r#"{{
-                        "event": "subscribe",
-                        "channel": "book",
-                        "symbol": "{}",
-                        "prec": "R0",
-                        "len": 250
-                    }}"#,
-                    symbol
-                )
-            })
-            .collect::<Vec<String>>();
-
-        self.subscribe(&raw_channels);
-    }
-}
-
-fn to_candlestick_raw_channel(symbol: &str, interval: u32) -> String {
+fn to_candlestick_raw_channel(symbol: &str, interval: u32) -> Option<String> {
     let interval_str = match interval {
         60 => "1m",
         300 => "5m",
@@ -174,83 +212,182 @@ fn to_candlestick_raw_channel(symbol: &str, interval: u32) -> String {
         604800 => "7D",
         1209600 => "14D",
         2592000 => "1M",
-        _ => panic!("Bitfinex has intervals 1m,5m,15m,30m,1h,3h,6h,12h,1D,7D,14D,1M"),
+        _ => return None, // Bitfinex has intervals 1m,5m,15m,30m,1h,3h,6h,12h,1D,7D,14D,1M
     };
 
-    format!(
+    Some(format!(
         r#"{{
             "event": "subscribe",
             "channel": "candles",
             "key": "trade:{}:{}"
         }}"#,
         interval_str, symbol
-    )
+    ))
 }
 
-impl<'a> Candlestick for BitfinexWSClient<'a> {
-    fn subscribe_candlestick(&self, symbols: &[String], interval: u32) {
-        let raw_channels: Vec<String> = symbols
+impl<'a> BitfinexWSClient<'a> {
+    fn subscribe_or_unsubscribe(&self, channels: &[String], subscribe: bool) {
+        let diff = if subscribe {
+            self.subscriptions.add(channels)
+        } else {
+            self.subscriptions.remove(channels)
+        };
+
+        if diff.is_empty() {
+            return;
+        }
+        if subscribe {
+            self.send_commands(channels_to_commands(&diff));
+        } else {
+            let commands = diff
+                .iter()
+                .filter_map(|channel| match self.channel_id(channel) {
+                    Some(chan_id) => Some(unsubscribe_command(chan_id)),
+                    None => {
+                        warn!("{} is not subscribed yet, {}", channel, EXCHANGE_NAME);
+                        None
+                    }
+                })
+                .collect::<Vec<String>>();
+            if !commands.is_empty() {
+                self.send_commands(commands);
+            }
+        }
+    }
+
+    // The raw channel of a subscribed or error event, among the channels of
+    // this client with the same identifying fields.
+    fn to_raw_channel(&self, event: &HashMap<String, Value>) -> Option<String> {
+        let fields = channel_fields(event);
+        self.subscriptions
+            .snapshot()
+            .into_keys()
+            .find(|channel| raw_channel_fields(channel).as_ref() == Some(&fields))
+    }
+
+    // The CHANNEL_ID of a raw channel in the current connection
+    fn channel_id(&self, channel: &str) -> Option<i64> {
+        let fields = raw_channel_fields(channel)?;
+        self.channel_id_meta
+            .lock()
+            .unwrap()
             .iter()
-            .map(|symbol| to_candlestick_raw_channel(symbol, interval))
-            .collect();
-        self.subscribe(&raw_channels);
+            .find(|(_, meta)| {
+                serde_json::from_str::<HashMap<String, Value>>(meta)
+                    .map(|obj| channel_fields(&obj) == fields)
+                    .unwrap_or(false)
+            })
+            .map(|(chan_id, _)| *chan_id)
     }
-}
 
-impl<'a> BitfinexWSClient<'a> {
-    fn subscribe_or_unsubscribe(&self, channels: &[String], subscribe: bool) {
-        let mut diff = Vec::<String>::new();
-        {
-            let mut guard = self.channels.lock().unwrap();
-            for ch in channels.iter() {
-                if guard.insert(ch.clone()) {
-                    diff.push(ch.clone());
-                }
+    // Sends commands as fast as the rate limit allows, the rest are queued
+    // and sent by the run loop later.
+    fn send_commands(&self, commands: Vec<String>) {
+        self.rate_limiter.push(commands);
+        self.flush_commands();
+    }
+
+    // Sends queued commands allowed by the rate limit.
+    fn flush_commands(&self) {
+        if self.ws_stream.lock().unwrap().is_none() && self.command_tx.lock().unwrap().is_none() {
+            return; // not connected yet, all channels are subscribed after connected
+        }
+        for command in self.rate_limiter.pop_ready() {
+            self.write_message(Message::Text(command));
+        }
+    }
+
+    // Writes to the blocking socket, or passes to the async run loop.
+    fn write_message(&self, msg: Message) {
+        if let Some(ws_stream) = self.ws_stream.lock().unwrap().as_mut() {
+            self.record(Direction::Outbound, &msg);
+            if let Err(err) = ws_stream.write_message(msg) {
+                error!("{}", err);
+            }
+        } else if let Some(tx) = self.command_tx.lock().unwrap().as_ref() {
+            if tx.send(msg).is_err() {
+                error!("The async run loop of {} has exited", EXCHANGE_NAME);
             }
         }
+    }
 
-        if !diff.is_empty() {
-            let commands = channels_to_commands(&diff, subscribe);
-            let mut ws_stream = self.ws_stream.lock().unwrap();
-            commands.into_iter().for_each(|command| {
-                let ret = ws_stream.write_message(Message::Text(command));
-                if let Err(err) = ret {
-                    error!("{}", err);
-                }
-            });
+    fn record(&self, direction: Direction, msg: &Message) {
+        if let Some(recorder) = self.recorder.lock().unwrap().as_ref() {
+            recorder.record_message(direction, msg);
         }
     }
 
-    // reconnect and subscribe all channels
-    fn _reconnect(&self) {
-        warn!("Reconnecting to {}", WEBSOCKET_URL);
-        {
-            let mut guard = self.ws_stream.lock().unwrap();
-            *guard = connect_with_retry(WEBSOCKET_URL, Some(SERVER_PING_INTERVAL));
+    fn record_connected(&self) {
+        if let Some(recorder) = self.recorder.lock().unwrap().as_ref() {
+            recorder.record(
+                Direction::Outbound,
+                RecordedFrame::Connected(self.url().to_string()),
+            );
         }
+    }
 
-        let channels = self
-            .channels
-            .lock()
-            .unwrap()
-            .iter()
-            .map(|s| s.to_string())
-            .collect::<Vec<String>>();
+    fn notify(&self, event: WsEvent) {
+        event::notify(&self.on_event, event);
+    }
+
+    fn notify_reconnected(&self, attempts: u32) {
+        self.metrics.on_reconnected();
+        let num_channels = self.subscriptions.len();
+        info!(
+            "Reconnected to {} after {} attempts, resubscribed {} channels",
+            self.url(),
+            attempts,
+            num_channels
+        );
+
+        let reconnect_event = ReconnectEvent {
+            url: self.url().to_string(),
+            attempts,
+            num_channels,
+        };
+        let callback = self.on_reconnect.lock().unwrap().clone();
+        if let Some(on_reconnect) = callback {
+            (on_reconnect.lock().unwrap())(reconnect_event.clone());
+        }
+        self.notify(WsEvent::Reconnected(reconnect_event));
+    }
+
+    // Reconnects and subscribes all channels again.
+    //
+    // Returns false if the client was closed or the reconnect policy gave up.
+    fn reconnect(&self, reason: String) -> bool {
+        if self.should_stop.load(Ordering::Acquire) {
+            return false;
+        }
+        self.notify(WsEvent::Disconnected { reason });
+        warn!("Reconnecting to {}", self.url());
+        let policy = self.reconnect_policy.lock().unwrap().clone();
+        let (stream, attempts) = match reconnect_with_backoff(
+            &self.endpoints,
+            self.heartbeat.read_timeout,
+            &policy,
+            &self.should_stop,
+        ) {
+            Some(x) => x,
+            None => return false,
+        };
+        *self.ws_stream.lock().unwrap() = Some(stream);
+        self.record_connected();
+        self.num_unanswered_ping.store(0, Ordering::Release);
+        // channel IDs are assigned per connection
+        self.channel_id_meta.lock().unwrap().clear();
+
+        self.rate_limiter.clear();
+        let channels = self.subscriptions.reset();
         if !channels.is_empty() {
-            let commands = channels_to_commands(&channels, true);
-            let mut ws_stream = self.ws_stream.lock().unwrap();
-            commands.into_iter().for_each(|command| {
-                let ret = ws_stream.write_message(Message::Text(command));
-                if let Err(err) = ret {
-                    error!("{}", err);
-                }
-            });
+            self.send_commands(channels_to_commands(&channels));
         }
+        self.notify_reconnected(attempts);
+        true
     }
 
     // Handle a text msg from Message::Text or Message::Binary
-    // Returns true if gets a normal message, otherwise false
-    fn handle_msg(&self, txt: &str) -> bool {
+    fn handle_msg(&self, txt: &str) -> Handled {
         if txt.starts_with('{') {
             let mut obj = serde_json::from_str::<HashMap<String, Value>>(txt).unwrap();
             let event = obj.get("event").unwrap().as_str().unwrap();
@@ -264,13 +401,20 @@ impl<'a> BitfinexWSClient<'a> {
                             // 10000: Unknown event
                             warn!("{} from {}", txt, EXCHANGE_NAME);
                         }
-                        10300 | 10400 | 10302 => {
+                        10300 | 10400 | 10302 | 10001 | 10305 => {
                             // 10300, 10400:Subscription failed
                             // 10302: Unknown channel
                             // 10001: Unknown pair
                             // 10305: Reached limit of open channels
                             error!("{} from {}", txt, EXCHANGE_NAME);
-                            panic!("{} from {}", txt, EXCHANGE_NAME);
+                            let channels: Vec<String> =
+                                self.to_raw_channel(&obj).into_iter().collect();
+                            self.subscriptions
+                                .update(&channels, SubscriptionState::Rejected);
+                            let reason = obj.get("msg").and_then(|x| x.as_str()).unwrap_or(txt);
+                            for event in event::subscribe_failed(&channels, Some(code), reason) {
+                                self.notify(event);
+                            }
                         }
                         _ => warn!("{} from {}", txt, EXCHANGE_NAME),
                     }
@@ -288,49 +432,56 @@ impl<'a> BitfinexWSClient<'a> {
                             .as_i64()
                             .unwrap();
                         if status == 0 {
-                            std::thread::sleep(Duration::from_secs(15));
+                            return Handled::Pause(Duration::from_secs(15));
                         }
                     } else {
                         let code = obj.get("code").unwrap().as_i64().unwrap();
                         match code {
                             20051 => {
                                 // Stop/Restart Websocket Server (please reconnect)
-                                // self.reconnect();
-                                error!("Stop/Restart Websocket Server, exiting now...");
-                                std::process::exit(0); // fail fast, pm2 will restart
+                                warn!("Stop/Restart Websocket Server, reconnecting...");
+                                return Handled::Reconnect;
                             }
                             20060 => {
                                 // Entering in Maintenance mode. Please pause any activity and resume
                                 // after receiving the info message 20061 (it should take 120 seconds
                                 // at most).
-                                std::thread::sleep(Duration::from_secs(15));
+                                return Handled::Pause(Duration::from_secs(15));
                             }
                             20061 => {
                                 // Maintenance ended. You can resume normal activity. It is advised
                                 // to unsubscribe/subscribe again all channels.
-                                let channels = self
-                                    .channels
+                                // Subscribing a channel still subscribed fails with 10301.
+                                let mut commands: Vec<String> = self
+                                    .channel_id_meta
                                     .lock()
                                     .unwrap()
-                                    .iter()
-                                    .map(|s| s.to_string())
-                                    .collect::<Vec<String>>();
-                                let commands = channels_to_commands(&channels, true);
-                                let mut ws_stream = self.ws_stream.lock().unwrap();
-                                commands.into_iter().for_each(|command| {
-                                    let ret = ws_stream.write_message(Message::Text(command));
-                                    if let Err(err) = ret {
-                                        error!("{}", err);
-                                    }
-                                });
+                                    .keys()
+                                    .map(|chan_id| unsubscribe_command(*chan_id))
+                                    .collect();
+                                self.rate_limiter.clear();
+                                commands
+                                    .append(&mut channels_to_commands(&self.subscriptions.reset()));
+                                self.send_commands(commands);
                             }
                             _ => info!("{} from {}", txt, EXCHANGE_NAME),
                         }
                     }
                 }
-                "pong" => debug!("{} from {}", txt, EXCHANGE_NAME),
+                "pong" => {
+                    debug!("{} from {}", txt, EXCHANGE_NAME);
+                    self.num_unanswered_ping.store(0, Ordering::Release);
+                }
                 "conf" => warn!("{} from {}", txt, EXCHANGE_NAME),
                 "subscribed" => {
+                    if let Some(raw_channel) = self.to_raw_channel(&obj) {
+                        let confirmed = self
+                            .subscriptions
+                            .update(&[raw_channel], SubscriptionState::Confirmed);
+                        for channel in confirmed {
+                            self.notify(WsEvent::Subscribed { channel });
+                        }
+                    }
                     let chan_id = obj.get("chanId").unwrap().as_i64().unwrap();
                     obj.remove("event");
                     obj.remove("chanId");
@@ -346,7 +497,7 @@ impl<'a> BitfinexWSClient<'a> {
                 }
                 _ => (),
             }
-            false
+            Handled::Misc
         } else {
             debug_assert!(txt.starts_with('['));
             let arr = serde_json::from_str::<Vec<Value>>(&txt).unwrap();
@@ -354,15 +505,8 @@ impl<'a> BitfinexWSClient<'a> {
                 // If there is no activity in the channel for 15 seconds, the Websocket server
                 // will send you a heartbeat message in this format.
                 // see <https://docs.bitfinex.com/docs/ws-general#heartbeating>
-                if let Err(err) = self
-                    .ws_stream
-                    .lock()
-                    .unwrap()
-                    .write_message(Message::Text(r#"{"event":"ping"}"#.to_string()))
-                {
-                    error!("{}", err);
-                }
-                false
+                self.write_message(Message::Text(r#"{"event":"ping"}"#.to_string()));
+                Handled::Misc
             } else {
                 // replace CHANNEL_ID with meta info
                 let i = txt.find(',').unwrap(); // first comma, for example, te, tu, see https://blog.bitfinex.com/api/websocket-api-update/
@@ -372,52 +516,143 @@ impl<'a> BitfinexWSClient<'a> {
                     .lock()
                     .unwrap()
                     .get(&channel_id)
-                    .unwrap()
-                    .clone();
+                    .cloned();
+                // e.g., frames still in flight after unsubscribed
+                let channel_info = match channel_info {
+                    Some(channel_info) => channel_info,
+                    None => {
+                        warn!(
+                            "Skipped a message of unknown CHANNEL_ID {}, {}",
+                            channel_id, EXCHANGE_NAME
+                        );
+                        return Handled::Misc;
+                    }
+                };
                 let new_txt = format!("[{}{}", channel_info, &txt[i..]);
 
+                self.metrics.on_normal_msg(&new_txt);
                 (self.on_msg.lock().unwrap())(new_txt);
 
-                true
+                Handled::Normal
             }
         }
     }
-}
 
-impl<'a> WSClient<'a> for BitfinexWSClient<'a> {
-    fn new(on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>, _url: Option<&str>) -> Self {
-        let stream = connect_with_retry(WEBSOCKET_URL, Some(SERVER_PING_INTERVAL));
+    // Handles a frame read from the socket, or replayed from a recording
+    fn handle_message(&self, msg: Message) -> Handled {
+        match msg {
+            Message::Text(txt) => self.handle_msg(&txt),
+            Message::Binary(_) => {
+                warn!("Skipped a binary frame of unknown format from Bitfinex");
+                Handled::Misc
+            }
+            Message::Ping(resp) => {
+                info!("Received a ping frame: {}", String::from_utf8_lossy(&resp));
+                self.write_message(Message::Pong(resp));
+                Handled::Misc
+            }
+            Message::Pong(resp) => {
+                warn!("Received a pong frame: {}", String::from_utf8_lossy(&resp));
+                self.num_unanswered_ping.store(0, Ordering::Release);
+                Handled::Misc
+            }
+            Message::Close(resp) => {
+                match resp {
+                    Some(frame) => warn!(
+                        "Received a Message::Close message with a CloseFrame: code: {}, reason: {}",
+                        frame.code, frame.reason
+                    ),
+                    None => warn!("Received a close message without CloseFrame"),
+                }
+                Handled::Misc
+            }
+        }
+    }
+
+    // Creates a client without connecting, run_async() connects later.
+    fn new_async(
+        on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>,
+        options: &WSClientOptions<'a>,
+    ) -> Self {
+        let heartbeat = options.config.resolve(None, Some(SERVER_PING_INTERVAL));
+        let urls = options.endpoint_urls();
+        let endpoints = Endpoints::new(
+            if urls.is_empty() {
+                &[WEBSOCKET_URL]
+            } else {
+                &urls
+            },
+            options.proxy.clone(),
+        );
+        let limits = connection_limits(EXCHANGE_NAME, endpoints.current());
         BitfinexWSClient {
-            ws_stream: Mutex::new(stream),
-            channels: Mutex::new(HashSet::new()),
+            endpoints,
+            ws_stream: Mutex::new(None),
+            command_tx: Mutex::new(None),
+            subscriptions: Subscriptions::new(),
             on_msg,
+            on_event: options.events.clone(),
             channel_id_meta: Mutex::new(HashMap::new()),
             should_stop: AtomicBool::new(false),
+            heartbeat,
+            num_unanswered_ping: AtomicIsize::new(0),
+            reconnect_policy: Mutex::new(ReconnectPolicy::default()),
+            on_reconnect: Mutex::new(None),
+            rate_limiter: RateLimiter::new(limits.max_messages_per_second),
+            recorder: Mutex::new(None),
+            metrics: MetricsCollector::default(),
         }
     }
 
-    fn subscribe_trade(&self, channels: &[String]) {
-        <Self as Trade>::subscribe_trade(self, channels);
+    fn new_blocking(
+        on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>,
+        options: &WSClientOptions<'a>,
+    ) -> Self {
+        let client = Self::new_async(on_msg, options);
+        let stream = connect_with_retry(&client.endpoints, client.heartbeat.read_timeout);
+        *client.ws_stream.lock().unwrap() = Some(stream);
+        client.notify(WsEvent::Connected {
+            url: client.url().to_string(),
+        });
+        client
     }
 
-    fn subscribe_orderbook(&self, channels: &[String]) {
-        <Self as OrderBook>::subscribe_orderbook(self, channels);
+    async fn run_async(&self, duration: Option<u64>) {
+        run_async(self, duration).await;
     }
+}
 
-    fn subscribe_orderbook_snapshot(&self, channels: &[String]) {
-        <Self as OrderBookSnapshot>::subscribe_orderbook_snapshot(self, channels);
+impl<'a> WSClient<'a> for BitfinexWSClient<'a> {
+    fn new_with_options(
+        on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>,
+        options: WSClientOptions<'a>,
+    ) -> Self {
+        Self::new_blocking(on_msg, &options)
     }
 
-    fn subscribe_ticker(&self, channels: &[String]) {
-        <Self as Ticker>::subscribe_ticker(self, channels);
+    fn endpoint(&self) -> &str {
+        self.url()
     }
 
-    fn subscribe_bbo(&self, channels: &[String]) {
-        <Self as BBO>::subscribe_bbo(self, channels);
+    fn connection_limits(&self) -> ConnectionLimits {
+        connection_limits(EXCHANGE_NAME, self.url())
     }
 
-    fn subscribe_candlestick(&self, symbols: &[String], interval: u32) {
-        <Self as Candlestick>::subscribe_candlestick(self, symbols, interval);
+    fn num_pending_commands(&self) -> usize {
+        self.rate_limiter.len()
+    }
+
+    fn metrics(&self) -> Metrics {
+        self.metrics
+            .snapshot(self.num_unanswered_ping.load(Ordering::Acquire))
+    }
+
+    fn translate_channels(&self, channels: &[Channel]) -> Result<Vec<String>, WsError> {
+        self.to_raw_channels(EXCHANGE_NAME, channels)
+    }
+
+    fn capabilities(&self) -> Vec<ChannelKind> {
+        ChannelTranslator::capabilities(self)
     }
 
     fn subscribe(&self, channels: &[String]) {
@@ -428,90 +663,100 @@ impl<'a> WSClient<'a> for BitfinexWSClient<'a> {
         self.subscribe_or_unsubscribe(channels, false);
     }
 
+    fn subscriptions(&self) -> HashMap<String, SubscriptionState> {
+        self.subscriptions.snapshot()
+    }
+
     fn run(&self, duration: Option<u64>) {
         let now = Instant::now();
-        let mut num_read_timeout = 0;
+        let mut watchdog = Watchdog::new(&self.heartbeat);
+        let timeout = self.heartbeat.read_timeout.map(Duration::from_secs);
+        // true if the read timeout is shortened to send queued commands on time
+        let mut throttled = false;
         while !self.should_stop.load(Ordering::Acquire) {
-            let resp = self.ws_stream.lock().unwrap().read_message();
+            self.flush_commands();
+            let delay = self.rate_limiter.delay();
+            let resp = {
+                let mut guard = self.ws_stream.lock().unwrap();
+                let ws_stream = guard
+                    .as_mut()
+                    .expect("Created by new_async(), call run_async() instead");
+                if delay.is_some() || throttled {
+                    let delay = delay.map(|x| x.max(Duration::from_millis(1)));
+                    set_read_timeout(ws_stream, delay.or(timeout));
+                    throttled = delay.is_some();
+                }
+                ws_stream.read_message()
+            };
             let mut succeeded = false;
+            // Some(reason) if the connection is considered dropped
+            let mut disconnected: Option<String> = None;
             match resp {
                 Ok(msg) => {
-                    num_read_timeout = 0;
-                    match msg {
-                        Message::Text(txt) => succeeded = self.handle_msg(&txt),
-                        Message::Binary(_) => panic!("Unknown binary format from Bitfinex"),
-                        Message::Ping(resp) => {
-                            info!(
-                                "Received a ping frame: {}",
-                                std::str::from_utf8(&resp).unwrap()
-                            );
-                            if let Err(err) = self.ws_stream.lock().unwrap().write_message(Message::Pong(resp)) {
-                                error!("{}", err);
-                            }
+                    watchdog.on_frame();
+                    self.record(Direction::Inbound, &msg);
+                    self.metrics.on_frame(&msg);
+                    match self.handle_message(msg) {
+                        Handled::Normal => {
+                            watchdog.on_data();
+                            succeeded = true;
                         }
-                        Message::Pong(resp) => {
-                            let tmp = std::str::from_utf8(&resp);
-                            warn!("Received a pong frame: {}", tmp.unwrap());
+                        Handled::Misc => (),
+                        Handled::Pause(duration) => {
+                            interruptible_sleep(duration, &self.should_stop);
+                            watchdog.reset(); // nothing is expected while paused
                         }
-                        Message::Close(resp) => {
-                            match resp {
-                                Some(frame) => warn!("Received a Message::Close message with a CloseFrame: code: {}, reason: {}", frame.code, frame.reason),
-                                None => warn!("Received a close message without CloseFrame"),
-                            }
+                        Handled::Reconnect => {
+                            disconnected = Some("The server asked to reconnect".to_string())
+                        }
+                        Handled::ReconnectAfter(duration) => {
+                            interruptible_sleep(duration, &self.should_stop);
+                            disconnected = Some("The server asked to reconnect".to_string())
                         }
                     }
                 }
+                Err(Error::Io(io_err)) if io_err.kind() == std::io::ErrorKind::WouldBlock => {
+                    if !throttled {
+                        info!("read_message() timeout");
+                        watchdog.on_read_timeout();
+                        self.metrics.on_read_timeout();
+                    }
+                }
                 Err(err) => {
-                    match err {
-                        Error::ConnectionClosed => {
-                            error!("Server closed connection, exiting now...");
-                            // self.reconnect();
-                            std::thread::sleep(Duration::from_secs(5));
-                            std::process::exit(0); // fail fast, pm2 will restart
-                        }
-                        Error::AlreadyClosed => {
-                            error!("Impossible to happen, fix the bug in the code");
-                            panic!("Impossible to happen, fix the bug in the code");
-                        }
-                        Error::Io(io_err) => {
-                            if io_err.kind() == std::io::ErrorKind::WouldBlock {
-                                info!("read_message() timeout");
-                                num_read_timeout += 1;
-                            } else {
-                                error!(
-                                    "I/O error thrown from read_message(): {}, {:?}",
-                                    io_err,
-                                    io_err.kind()
-                                );
-                                // self.reconnect();
-                                std::thread::sleep(Duration::from_secs(5));
-                                std::process::exit(0); // fail fast, pm2 will restart
-                            }
-                        }
-                        Error::Protocol(protocol_err) => {
-                            if protocol_err == ProtocolError::ResetWithoutClosingHandshake {
-                                error!("ResetWithoutClosingHandshake");
-                                // self.reconnect();
-                                std::thread::sleep(Duration::from_secs(5));
-                                std::process::exit(0); // fail fast, pm2 will restart
-                            } else {
-                                error!(
-                                    "Protocol error thrown from read_message(): {}",
-                                    protocol_err
-                                );
-                            }
-                        }
-                        _ => {
-                            error!("Error thrown from read_message(): {}", err);
-                            panic!("Error thrown from read_message(): {}", err);
-                        }
+                    // Every other error means the connection is unusable
+                    error!("Error thrown from read_message(): {}, {}", err, self.url());
+                    if !matches!(err, Error::ConnectionClosed | Error::AlreadyClosed) {
+                        self.endpoints.failover();
                     }
+                    disconnected = Some(err.to_string());
                 }
             };
 
-            if num_read_timeout > 3 {
-                error!("num_read_timeout: {}", num_read_timeout);
-                break; // fail fast, pm2 will restart
+            if disconnected.is_none() {
+                let num_unanswered_ping = self.num_unanswered_ping.load(Ordering::Acquire);
+                if let Some(reason) = watchdog.check(num_unanswered_ping) {
+                    error!("{}", reason);
+                    disconnected = Some(reason);
+                    self.endpoints.failover();
+                } else if watchdog.ping_due() {
+                    let ping_msg = &self.heartbeat.ping.as_ref().unwrap().1;
+                    info!("Sending ping: {}", ping_msg);
+                    let ping_msg = if ping_msg.is_empty() {
+                        Message::Ping(Vec::new())
+                    } else {
+                        Message::Text(ping_msg.to_string())
+                    };
+                    self.write_message(ping_msg);
+                    watchdog.on_ping_sent(num_unanswered_ping);
+                    self.num_unanswered_ping.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+
+            if let Some(reason) = disconnected {
+                if !self.reconnect(reason) {
+                    break;
+                }
+                watchdog.reset();
             }
 
             if let Some(seconds) = duration {
@@ -524,25 +769,155 @@ impl<'a> WSClient<'a> for BitfinexWSClient<'a> {
 
     fn close(&self) {
         self.should_stop.store(true, Ordering::Release);
-        let ret = self.ws_stream.lock().unwrap().close(None);
-        if let Err(err) = ret {
-            error!("{}", err);
+        if let Some(ws_stream) = self.ws_stream.lock().unwrap().as_mut() {
+            self.record(Direction::Outbound, &Message::Close(None));
+            if let Err(err) = ws_stream.close(None) {
+                error!("{}", err);
+            }
+        } else if let Some(tx) = self.command_tx.lock().unwrap().as_ref() {
+            let _ = tx.send(Message::Close(None));
         }
     }
+
+    fn set_reconnect_policy(&self, policy: ReconnectPolicy) {
+        *self.reconnect_policy.lock().unwrap() = policy;
+    }
+
+    fn on_reconnect(&self, callback: Arc<Mutex<dyn FnMut(ReconnectEvent) + 'a + Send>>) {
+        *self.on_reconnect.lock().unwrap() = Some(callback);
+    }
+
+    fn set_recorder(&self, recorder: Recorder) {
+        if self.ws_stream.lock().unwrap().is_some() || self.command_tx.lock().unwrap().is_some() {
+            recorder.record(
+                Direction::Outbound,
+                RecordedFrame::Connected(self.url().to_string()),
+            );
+        }
+        *self.recorder.lock().unwrap() = Some(recorder);
+    }
+
+    fn replay(on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>, replayer: &Replayer) {
+        let options = WSClientOptions::from_url(replayer.url());
+        let client = Self::new_async(on_msg, &options);
+        replayer.replay(|msg| {
+            if let Handled::Reconnect | Handled::ReconnectAfter(_) = client.handle_message(msg) {
+                warn!("The server asked {} to reconnect", EXCHANGE_NAME);
+            }
+        });
+    }
 }
 
+impl<'a> AsyncMessageHandler for BitfinexWSClient<'a> {
+    fn url(&self) -> &str {
+        self.endpoints.current()
+    }
+
+    fn proxy(&self) -> Option<&Proxy> {
+        self.endpoints.proxy()
+    }
+
+    fn failover(&self) {
+        self.endpoints.failover();
+    }
+
+    fn heartbeat(&self) -> &Heartbeat {
+        &self.heartbeat
+    }
+
+    fn num_unanswered_ping(&self) -> isize {
+        self.num_unanswered_ping.load(Ordering::Acquire)
+    }
+
+    fn on_ping_sent(&self) {
+        self.num_unanswered_ping.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn on_pong(&self) {
+        self.num_unanswered_ping.store(0, Ordering::Release);
+    }
+
+    fn should_stop(&self) -> bool {
+        self.should_stop.load(Ordering::Acquire)
+    }
+
+    fn reconnect_policy(&self) -> ReconnectPolicy {
+        self.reconnect_policy.lock().unwrap().clone()
+    }
+
+    fn handle_text(&self, txt: &str) -> Handled {
+        self.handle_msg(txt)
+    }
+
+    fn handle_binary(&self, _binary: &[u8]) -> Handled {
+        warn!("Skipped a binary frame of unknown format from Bitfinex");
+        Handled::Misc
+    }
+
+    fn record(&self, direction: Direction, msg: &Message) {
+        BitfinexWSClient::record(self, direction, msg);
+    }
+
+    fn on_frame(&self, msg: &Message) {
+        self.metrics.on_frame(msg);
+    }
+
+    fn on_read_timeout(&self) {
+        self.metrics.on_read_timeout();
+    }
+
+    fn attach(&self, tx: UnboundedSender<Message>) -> Vec<String> {
+        *self.command_tx.lock().unwrap() = Some(tx);
+        self.record_connected();
+        self.num_unanswered_ping.store(0, Ordering::Release);
+        // channel IDs are assigned per connection
+        self.channel_id_meta.lock().unwrap().clear();
+        self.rate_limiter.clear();
+        self.rate_limiter
+            .push(channels_to_commands(&self.subscriptions.reset()));
+        self.rate_limiter.pop_ready()
+    }
+
+    fn command_period(&self) -> Option<Duration> {
+        self.rate_limiter.period()
+    }
+
+    fn flush_commands(&self) {
+        BitfinexWSClient::flush_commands(self);
+    }
+
+    fn detach(&self) {
+        *self.command_tx.lock().unwrap() = None;
+    }
+
+    fn on_connected(&self) {
+        self.notify(WsEvent::Connected {
+            url: self.url().to_string(),
+        });
+    }
+
+    fn on_disconnected(&self, reason: String) {
+        self.notify(WsEvent::Disconnected { reason });
+    }
+
+    fn on_reconnected(&self, attempts: u32) {
+        self.notify_reconnected(attempts);
+    }
+}
+
+define_async_client!(BitfinexAsyncWSClient, BitfinexWSClient);
+
 #[cfg(test)]
 mod tests {
+    use super::{channel_fields, raw_channel_fields};
+    use serde_json::Value;
+    use std::collections::HashMap;
+
     #[test]
     fn test_spot_command() {
         assert_eq!(
             r#"{"event": "subscribe", "channel": "trades", "symbol": "tBTCUSD"}"#,
-            super::channel_to_command("trades:tBTCUSD", true)
-        );
-
-        assert_eq!(
-            r#"{"event": "unsubscribe", "channel": "trades", "symbol": "tBTCUSD"}"#,
-            super::channel_to_command("trades:tBTCUSD", false)
+            super::channel_to_command("trades:tBTCUSD")
         );
     }
 
@@ -550,12 +925,43 @@ mod tests {
     fn test_swap_command() {
         assert_eq!(
             r#"{"event": "subscribe", "channel": "trades", "symbol": "tBTCF0:USTF0"}"#,
-            super::channel_to_command("trades:tBTCF0:USTF0", true)
+            super::channel_to_command("trades:tBTCF0:USTF0")
         );
+    }
 
+    #[test]
+    fn test_channel_fields() {
+        let subscribed = |txt: &str| {
+            let obj = serde_json::from_str::<HashMap<String, Value>>(txt).unwrap();
+            channel_fields(&obj)
+        };
+
+        assert_eq!(
+            subscribed(r#"{"channel":"trades","symbol":"tBTCF0:USTF0"}"#),
+            raw_channel_fields("trades:tBTCF0:USTF0").unwrap()
+        );
+        assert_eq!(
+            subscribed(
+                r#"{"channel":"book","symbol":"tBTCUSD","prec":"P0","freq":"F0","len":"25"}"#
+            ),
+            raw_channel_fields(&super::book_raw_channel("tBTCUSD", "P0", 25)).unwrap()
+        );
+        assert_ne!(
+            subscribed(
+                r#"{"channel":"book","symbol":"tBTCUSD","prec":"P0","freq":"F0","len":"25"}"#
+            ),
+            raw_channel_fields(&super::book_raw_channel("tBTCUSD", "R0", 25)).unwrap()
+        );
+        // raw book commands omit the default freq
+        assert_eq!(
+            subscribed(
+                r#"{"channel":"book","symbol":"tBTCUSD","prec":"R0","freq":"F0","len":"250"}"#
+            ),
+            raw_channel_fields(&super::book_raw_channel("tBTCUSD", "R0", 250)).unwrap()
+        );
         assert_eq!(
-            r#"{"event": "unsubscribe", "channel": "trades", "symbol": "tBTCF0:USTF0"}"#,
-            super::channel_to_command("trades:tBTCF0:USTF0", false)
+            subscribed(r#"{"channel":"candles","key":"trade:1m:tBTCUSD"}"#),
+            raw_channel_fields(&super::to_candlestick_raw_channel("tBTCUSD", 60).unwrap()).unwrap()
         );
     }
 }
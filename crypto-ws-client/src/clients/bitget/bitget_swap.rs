@@ -1,9 +1,9 @@
-use crate::WSClient;
+use crate::{Channel, ChannelKind, WSClient, WsError};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
 use super::super::ws_client_internal::{MiscMessage, WSClientInternal};
-use super::super::{Candlestick, OrderBook, OrderBookSnapshot, Ticker, Trade, BBO};
+use super::super::ChannelTranslator;
 
 use log::*;
 use serde_json::Value;
@@ -59,7 +59,11 @@ fn on_misc_msg(msg: &str) -> MiscMessage {
             panic!("Received {} from {}", msg, EXCHANGE_NAME);
         } else {
             info!("Received {} from {}", msg, EXCHANGE_NAME);
-            MiscMessage::Misc
+            match (event, obj.get("channel").and_then(|x| x.as_str())) {
+                // {"event":"subscribe","channel":"swap/trade:BTCUSDT"}
+                ("subscribe", Some(channel)) => MiscMessage::Subscribed(vec![channel.to_string()]),
+                _ => MiscMessage::Misc,
+            }
         }
     } else if obj.contains_key("table") && obj.contains_key("data") {
         MiscMessage::Normal
@@ -81,36 +85,46 @@ fn to_raw_channel(channel: &str, pair: &str) -> String {
     format!("swap/{}:{}", channel, pair)
 }
 
-#[rustfmt::skip]
-impl_trait!(Trade, BitgetSwapWSClient, subscribe_trade, "trade", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(BBO, BitgetSwapWSClient, subscribe_bbo, "depth5", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(OrderBook, BitgetSwapWSClient, subscribe_orderbook, "depth", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(Ticker, BitgetSwapWSClient, subscribe_ticker, "ticker", to_raw_channel);
-
-impl<'a> OrderBookSnapshot for BitgetSwapWSClient<'a> {
-    fn subscribe_orderbook_snapshot(&self, _pairs: &[String]) {
-        panic!("Bitget does NOT have orderbook snapshot channel");
-    }
-}
-
-fn to_candlestick_raw_channel(pair: &str, interval: u32) -> String {
+fn to_candlestick_raw_channel(pair: &str, interval: u32) -> Option<String> {
     let valid_set: Vec<u32> = vec![60, 300, 900, 1800, 3600, 14400, 43200, 86400, 604800];
     if !valid_set.contains(&interval) {
-        let joined = valid_set
-            .into_iter()
-            .map(|x| x.to_string())
-            .collect::<Vec<String>>()
-            .join(",");
-        panic!("Bitget available intervals: {}", joined);
+        return None;
     }
     let channel = format!("candle{}s", interval);
-    to_raw_channel(&channel, pair)
+    Some(to_raw_channel(&channel, pair))
 }
 
-impl_candlestick!(BitgetSwapWSClient);
+// Bitget does NOT have orderbook snapshot channel
+impl<'a> ChannelTranslator for BitgetSwapWSClient<'a> {
+    fn translate(&self, channel: &Channel) -> Result<String, WsError> {
+        match channel {
+            Channel::Trade(pair) => Some(to_raw_channel("trade", pair)),
+            Channel::BBO(pair) => Some(to_raw_channel("depth5", pair)),
+            Channel::L2 {
+                pair,
+                depth: None,
+                speed: None,
+            } => Some(to_raw_channel("depth", pair)),
+            Channel::Ticker(pair) => Some(to_raw_channel("ticker", pair)),
+            Channel::Kline { pair, interval } => {
+                return to_candlestick_raw_channel(pair, *interval)
+                    .ok_or_else(|| WsError::invalid_interval(EXCHANGE_NAME, *interval));
+            }
+            _ => None,
+        }
+        .ok_or_else(|| WsError::unsupported(EXCHANGE_NAME, channel))
+    }
+
+    fn capabilities(&self) -> Vec<ChannelKind> {
+        vec![
+            ChannelKind::Trade,
+            ChannelKind::L2,
+            ChannelKind::BBO,
+            ChannelKind::Ticker,
+            ChannelKind::Kline,
+        ]
+    }
+}
 
 define_client!(
     BitgetSwapWSClient,
@@ -121,3 +135,4 @@ define_client!(
     Some(CLIENT_PING_INTERVAL_AND_MSG),
     None
 );
+define_async_client!(BitgetSwapAsyncWSClient, BitgetSwapWSClient);
@@ -2,6 +2,6 @@
 mod bitget_swap;
 
 // pub use bitget_spot::BitgetSpotWSClient;
-pub use bitget_swap::BitgetSwapWSClient;
+pub use bitget_swap::{BitgetSwapAsyncWSClient, BitgetSwapWSClient};
 
 pub(super) const EXCHANGE_NAME: &str = "bitget";
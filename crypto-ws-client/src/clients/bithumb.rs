@@ -1,11 +1,11 @@
-use crate::WSClient;
+use crate::{Channel, ChannelKind, WSClient, WsError};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
 use super::{
     utils::CHANNEL_PAIR_DELIMITER,
     ws_client_internal::{MiscMessage, WSClientInternal},
-    Candlestick, OrderBook, OrderBookSnapshot, Ticker, Trade, BBO,
+    ChannelTranslator,
 };
 
 use log::*;
@@ -68,6 +68,12 @@ fn on_misc_msg(msg: &str) -> MiscMessage {
                 }
             }
             7 => MiscMessage::Normal,
+            1 => {
+                // {"code":"00001","data":null,"timestamp":1616651250071}, the
+                // ack of a subscribe command doesn't tell which channels
+                debug!("Received {} from {}", msg, EXCHANGE_NAME);
+                MiscMessage::SubscribedPending
+            }
             _ => {
                 debug!("Received {} from {}", msg, EXCHANGE_NAME);
                 MiscMessage::Misc
@@ -83,28 +89,24 @@ fn to_raw_channel(channel: &str, pair: &str) -> String {
     format!("{}{}{}", channel, CHANNEL_PAIR_DELIMITER, pair)
 }
 
-#[rustfmt::skip]
-impl_trait!(Trade, BithumbWSClient, subscribe_trade, "TRADE", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(Ticker, BithumbWSClient, subscribe_ticker, "TICKER", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(OrderBook, BithumbWSClient, subscribe_orderbook, "ORDERBOOK", to_raw_channel);
-
-impl<'a> BBO for BithumbWSClient<'a> {
-    fn subscribe_bbo(&self, _pairs: &[String]) {
-        panic!("CoinbasePro WebSocket does NOT have BBO channel");
-    }
-}
-
-impl<'a> OrderBookSnapshot for BithumbWSClient<'a> {
-    fn subscribe_orderbook_snapshot(&self, _pairs: &[String]) {
-        panic!("CoinbasePro does NOT have orderbook snapshot channel");
+// Bithumb WebSocket does NOT have BBO, orderbook snapshot and candlestick channels
+impl<'a> ChannelTranslator for BithumbWSClient<'a> {
+    fn translate(&self, channel: &Channel) -> Result<String, WsError> {
+        match channel {
+            Channel::Trade(pair) => Some(to_raw_channel("TRADE", pair)),
+            Channel::Ticker(pair) => Some(to_raw_channel("TICKER", pair)),
+            Channel::L2 {
+                pair,
+                depth: None,
+                speed: None,
+            } => Some(to_raw_channel("ORDERBOOK", pair)),
+            _ => None,
+        }
+        .ok_or_else(|| WsError::unsupported(EXCHANGE_NAME, channel))
     }
-}
 
-impl<'a> Candlestick for BithumbWSClient<'a> {
-    fn subscribe_candlestick(&self, _pairs: &[String], _interval: u32) {
-        panic!("CoinbasePro does NOT have candlestick channel");
+    fn capabilities(&self) -> Vec<ChannelKind> {
+        vec![ChannelKind::Trade, ChannelKind::L2, ChannelKind::Ticker]
     }
 }
 
@@ -117,3 +119,4 @@ define_client!(
     Some(CLIENT_PING_INTERVAL_AND_MSG),
     None
 );
+define_async_client!(BithumbAsyncWSClient, BithumbWSClient);
@@ -1,11 +1,12 @@
-use crate::WSClient;
+use crate::{Channel, ChannelKind, Credentials, PrivateWSClient, WSClient, WsError};
 use std::sync::{Arc, Mutex};
 use std::{collections::HashMap, time::Duration};
 
 use super::{
+    auth::{self, Auth},
     utils::CHANNEL_PAIR_DELIMITER,
     ws_client_internal::{MiscMessage, WSClientInternal},
-    Candlestick, OrderBook, OrderBookSnapshot, Ticker, Trade, BBO,
+    ChannelTranslator,
 };
 use log::*;
 use serde_json::Value;
@@ -70,12 +71,16 @@ fn on_misc_msg(msg: &str) -> MiscMessage {
             // Rate limit exceeded
             429 => {
                 error!("Received {} from {}", msg, EXCHANGE_NAME);
-                std::thread::sleep(Duration::from_secs(3));
+                return MiscMessage::Pause(Duration::from_secs(3));
             }
             400 => {
                 if error_msg.starts_with("Unknown or expired symbol") {
                     error!("Received {} from {}", msg, EXCHANGE_NAME);
-                    panic!("Received {} from {}", msg, EXCHANGE_NAME);
+                    return MiscMessage::Rejected {
+                        channels: request_args(&obj).unwrap_or_default(),
+                        code: Some(code),
+                        reason: error_msg.to_string(),
+                    };
                 } else if error_msg.starts_with("You are already subscribed to this topic") {
                     info!("Received {} from {}", msg, EXCHANGE_NAME)
                 } else {
@@ -87,7 +92,11 @@ fn on_misc_msg(msg: &str) -> MiscMessage {
         MiscMessage::Misc
     } else if obj.contains_key("success") || obj.contains_key("info") {
         info!("Received {} from {}", msg, EXCHANGE_NAME);
-        MiscMessage::Misc
+        // {"success":true,"subscribe":"trade:XBTUSD","request":{"op":"subscribe","args":["trade:XBTUSD"]}}
+        match obj.get("subscribe").and_then(|x| x.as_str()) {
+            Some(channel) => MiscMessage::Subscribed(vec![channel.to_string()]),
+            None => MiscMessage::Misc,
+        }
     } else if obj.contains_key("table") && obj.contains_key("action") && obj.contains_key("data") {
         MiscMessage::Normal
     } else {
@@ -96,37 +105,124 @@ fn on_misc_msg(msg: &str) -> MiscMessage {
     }
 }
 
-fn to_raw_channel(channel: &str, pair: &str) -> String {
-    format!("{}{}{}", channel, CHANNEL_PAIR_DELIMITER, pair)
+// Raw channels in the request of a response
+fn request_args(obj: &HashMap<String, Value>) -> Option<Vec<String>> {
+    let args = obj.get("request")?.get("args")?.as_array()?;
+    Some(
+        args.iter()
+            .filter_map(|x| x.as_str())
+            .map(|x| x.to_string())
+            .collect(),
+    )
 }
 
-#[rustfmt::skip]
-impl_trait!(Trade, BitmexWSClient, subscribe_trade, "trade", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(BBO, BitmexWSClient, subscribe_bbo, "quote", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(OrderBook, BitmexWSClient, subscribe_orderbook, "orderBookL2_25", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(OrderBookSnapshot, BitmexWSClient, subscribe_orderbook_snapshot, "orderBook10", to_raw_channel);
-
-impl<'a> Ticker for BitmexWSClient<'a> {
-    fn subscribe_ticker(&self, _pairs: &[String]) {
-        panic!("BitMEX WebSocket does NOT have ticker channel");
-    }
+fn to_raw_channel(channel: &str, pair: &str) -> String {
+    format!("{}{}{}", channel, CHANNEL_PAIR_DELIMITER, pair)
 }
 
-fn to_candlestick_raw_channel(pair: &str, interval: u32) -> String {
+fn to_candlestick_raw_channel(pair: &str, interval: u32) -> Option<String> {
     let interval_str = match interval {
         60 => "1m",
         300 => "5m",
         3600 => "1h",
         86400 => "1d",
-        _ => panic!("BitMEX has intervals 1m,5m,1h,1d"),
+        _ => return None, // BitMEX has intervals 1m,5m,1h,1d
     };
-    format!("tradeBin{}:{}", interval_str, pair)
+    Some(format!("tradeBin{}:{}", interval_str, pair))
+}
+
+// BitMEX WebSocket does NOT have ticker channel
+impl<'a> ChannelTranslator for BitmexWSClient<'a> {
+    fn translate(&self, channel: &Channel) -> Result<String, WsError> {
+        match channel {
+            Channel::Trade(pair) => Some(to_raw_channel("trade", pair)),
+            Channel::BBO(pair) => Some(to_raw_channel("quote", pair)),
+            Channel::L2 {
+                pair,
+                depth: None | Some(25),
+                speed: None,
+            } => Some(to_raw_channel("orderBookL2_25", pair)),
+            Channel::L2Snapshot(pair) => Some(to_raw_channel("orderBook10", pair)),
+            Channel::Kline { pair, interval } => {
+                return to_candlestick_raw_channel(pair, *interval)
+                    .ok_or_else(|| WsError::invalid_interval(EXCHANGE_NAME, *interval));
+            }
+            // instrument contains markPrice, indicativeSettlePrice and openInterest
+            Channel::MarkPrice(pair) | Channel::IndexPrice(pair) | Channel::OpenInterest(pair) => {
+                Some(to_raw_channel("instrument", pair))
+            }
+            Channel::Liquidation(pair) => Some(to_raw_channel("liquidation", pair)),
+            _ => None,
+        }
+        .ok_or_else(|| WsError::unsupported(EXCHANGE_NAME, channel))
+    }
+
+    fn capabilities(&self) -> Vec<ChannelKind> {
+        vec![
+            ChannelKind::Trade,
+            ChannelKind::L2,
+            ChannelKind::L2Snapshot,
+            ChannelKind::BBO,
+            ChannelKind::Kline,
+            ChannelKind::MarkPrice,
+            ChannelKind::IndexPrice,
+            ChannelKind::Liquidation,
+            ChannelKind::OpenInterest,
+        ]
+    }
+}
+
+// see https://www.bitmex.com/app/apiKeysUsage#Authenticating-with-an-API-Key
+fn sign(api_secret: &str, verb: &str, path: &str, expires: u64, data: &str) -> String {
+    let msg = format!("{}{}{}{}", verb, path, expires, data);
+    hex::encode(auth::hmac_sha256(api_secret.as_bytes(), msg.as_bytes()))
+}
+
+// Subscribes to all symbols if no symbol specified
+fn to_private_channels(channel: &str, symbols: &[String]) -> Vec<String> {
+    if symbols.is_empty() {
+        vec![channel.to_string()]
+    } else {
+        symbols
+            .iter()
+            .map(|symbol| to_raw_channel(channel, symbol))
+            .collect()
+    }
 }
 
-impl_candlestick!(BitmexWSClient);
+impl<'a> PrivateWSClient<'a> for BitmexWSClient<'a> {
+    fn new_private(
+        on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>,
+        credentials: Credentials,
+        url: Option<&str>,
+    ) -> Result<Self, WsError> {
+        let ws_client = BitmexWSClient::new(on_msg, url);
+        ws_client.client.set_auth(Auth::Login(Box::new(move || {
+            let expires = auth::current_millis() / 1000 + 60;
+            let signature = sign(&credentials.api_secret, "GET", "/realtime", expires, "");
+            vec![format!(
+                r#"{{"op":"authKeyExpires","args":["{}",{},"{}"]}}"#,
+                credentials.api_key, expires, signature
+            )]
+        })));
+        Ok(ws_client)
+    }
+
+    fn subscribe_orders(&self, symbols: &[String]) {
+        self.client
+            .subscribe(&to_private_channels("order", symbols));
+    }
+
+    fn subscribe_fills(&self, symbols: &[String]) {
+        self.client
+            .subscribe(&to_private_channels("execution", symbols));
+    }
+
+    // BitMEX margin is in XBt only
+    fn subscribe_balances(&self, _currencies: &[String]) {
+        self.client.subscribe(&["margin".to_string()]);
+    }
+}
 
 define_client!(
     BitmexWSClient,
@@ -137,9 +233,30 @@ define_client!(
     Some(CLIENT_PING_INTERVAL_AND_MSG),
     None
 );
+define_async_client!(BitmexAsyncWSClient, BitmexWSClient);
 
 #[cfg(test)]
 mod tests {
+    #[test]
+    fn test_sign() {
+        // https://www.bitmex.com/app/apiKeysUsage
+        let secret = "chNOOS4KvNXR_Xq4k4c9qsfoKWvnDecLATCRlcBwyKDYnWgO";
+        assert_eq!(
+            "c7682d435d0cfe87c16098df34ef2eb5a549d4c5a3c2b1f0f77b8af73423bf00",
+            super::sign(secret, "GET", "/api/v1/instrument", 1518064236, "")
+        );
+        assert_eq!(
+            "1749cd2ccae4aa49048ae09f0b95110cee706e0944e6a14ad0b3a8cb45bd336b",
+            super::sign(
+                secret,
+                "POST",
+                "/api/v1/order",
+                1518064238,
+                r#"{"symbol":"XBTM15","price":219.0,"clOrdID":"mm_bitmex_1a/oemUeQ4CAJZgP3fjHsA","orderQty":98}"#
+            )
+        );
+    }
+
     #[test]
     fn test_one_channel() {
         let commands = super::channels_to_commands(&vec!["trade:XBTUSD".to_string()], true);
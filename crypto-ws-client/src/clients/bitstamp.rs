@@ -1,9 +1,9 @@
-use crate::{Level3OrderBook, WSClient};
+use crate::{Channel, ChannelKind, Level3OrderBook, WSClient, WsError};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
 use super::ws_client_internal::{MiscMessage, WSClientInternal};
-use super::{Candlestick, OrderBook, OrderBookSnapshot, Ticker, Trade, BBO};
+use super::ChannelTranslator;
 use log::*;
 use serde_json::Value;
 
@@ -56,21 +56,35 @@ fn on_misc_msg(msg: &str) -> MiscMessage {
 
     let event = obj.get("event").unwrap().as_str().unwrap();
     match event {
-        "bts:subscription_succeeded" | "bts:unsubscription_succeeded" => {
+        "bts:subscription_succeeded" => {
+            debug!("Received {} from {}", msg, EXCHANGE_NAME);
+            let channel = obj.get("channel").unwrap().as_str().unwrap();
+            MiscMessage::Subscribed(vec![channel.to_string()])
+        }
+        "bts:unsubscription_succeeded" => {
             debug!("Received {} from {}", msg, EXCHANGE_NAME);
             MiscMessage::Misc
         }
         "bts:error" => {
+            // {"event":"bts:error","channel":"","data":{"code":null,"message":"Bad subscription string."}}
             error!("Received {} from {}", msg, EXCHANGE_NAME);
-            panic!("Received {} from {}", msg, EXCHANGE_NAME);
+            let data = obj.get("data");
+            MiscMessage::Rejected {
+                channels: Vec::new(),
+                code: data.and_then(|x| x.get("code")).and_then(|x| x.as_i64()),
+                reason: data
+                    .and_then(|x| x.get("message"))
+                    .and_then(|x| x.as_str())
+                    .unwrap_or(msg)
+                    .to_string(),
+            }
         }
         "bts:request_reconnect" => {
             warn!(
                 "Received {}, which means Bitstamp is under maintenance",
                 msg
             );
-            std::thread::sleep(std::time::Duration::from_secs(20));
-            MiscMessage::Reconnect
+            MiscMessage::ReconnectAfter(std::time::Duration::from_secs(20))
         }
         _ => MiscMessage::Normal,
     }
@@ -80,30 +94,37 @@ fn to_raw_channel(channel: &str, pair: &str) -> String {
     format!("{}_{}", channel, pair)
 }
 
-#[rustfmt::skip]
-impl_trait!(Trade, BitstampWSClient, subscribe_trade, "live_trades", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(OrderBook, BitstampWSClient, subscribe_orderbook, "diff_order_book", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(OrderBookSnapshot, BitstampWSClient, subscribe_orderbook_snapshot, "order_book", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(Level3OrderBook, BitstampWSClient, subscribe_l3_orderbook, "live_orders", to_raw_channel);
-
-impl<'a> Ticker for BitstampWSClient<'a> {
-    fn subscribe_ticker(&self, _pairs: &[String]) {
-        panic!("Bitstamp WebSocket does NOT have ticker channel");
+// Bitstamp WebSocket does NOT have ticker, BBO and candlestick channels
+impl<'a> ChannelTranslator for BitstampWSClient<'a> {
+    fn translate(&self, channel: &Channel) -> Result<String, WsError> {
+        match channel {
+            Channel::Trade(pair) => Some(to_raw_channel("live_trades", pair)),
+            Channel::L2 {
+                pair,
+                depth: None | Some(100),
+                speed: None,
+            } => Some(to_raw_channel("diff_order_book", pair)),
+            Channel::L2Snapshot(pair) => Some(to_raw_channel("order_book", pair)),
+            Channel::L3(pair) => Some(to_raw_channel("live_orders", pair)),
+            _ => None,
+        }
+        .ok_or_else(|| WsError::unsupported(EXCHANGE_NAME, channel))
     }
-}
 
-impl<'a> BBO for BitstampWSClient<'a> {
-    fn subscribe_bbo(&self, _pairs: &[String]) {
-        panic!("Bitstamp WebSocket does NOT have BBO channel");
+    fn capabilities(&self) -> Vec<ChannelKind> {
+        vec![
+            ChannelKind::Trade,
+            ChannelKind::L2,
+            ChannelKind::L2Snapshot,
+            ChannelKind::L3,
+        ]
     }
 }
 
-impl<'a> Candlestick for BitstampWSClient<'a> {
-    fn subscribe_candlestick(&self, _pairs: &[String], _interval: u32) {
-        panic!("Bitstamp does NOT have candlestick channel");
+impl<'a> Level3OrderBook for BitstampWSClient<'a> {
+    fn subscribe_l3_orderbook(&self, symbols: &[String]) -> Result<(), WsError> {
+        let channels: Vec<Channel> = symbols.iter().cloned().map(Channel::L3).collect();
+        self.subscribe_channels(&channels)
     }
 }
 
@@ -116,6 +137,7 @@ define_client!(
     Some(CLIENT_PING_INTERVAL_AND_MSG),
     None
 );
+define_async_client!(BitstampAsyncWSClient, BitstampWSClient);
 
 #[cfg(test)]
 mod tests {
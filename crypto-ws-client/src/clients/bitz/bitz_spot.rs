@@ -1,10 +1,11 @@
-use crate::{clients::utils::CHANNEL_PAIR_DELIMITER, WSClient};
+use crate::{clients::utils::CHANNEL_PAIR_DELIMITER, Channel, ChannelKind, WSClient, WsError};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use super::super::request;
 use super::super::ws_client_internal::{MiscMessage, WSClientInternal};
-use super::super::{Candlestick, OrderBook, OrderBookSnapshot, Ticker, Trade, BBO};
+use super::super::ChannelTranslator;
 
 use log::*;
 use serde_json::Value;
@@ -25,15 +26,16 @@ pub struct BitzSpotWSClient<'a> {
 }
 
 fn pair_channels_to_command(pair: &str, channels: &[String], subscribe: bool) -> String {
+    let raw_channels: Vec<String> = channels
+        .iter()
+        .map(|channel| to_raw_channel(channel, pair))
+        .collect();
     format!(
         r#"{{"action":"Topic.{}", "data":{{"symbol":"{}", "type":"{}", "_CDID":"100002", "dataType":"1"}}, "msg_id":{}}}"#,
         if subscribe { "sub" } else { "unsub" },
         pair,
         channels.join(","),
-        SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_millis(),
+        request::register(&raw_channels, subscribe),
     )
 }
 
@@ -82,23 +84,31 @@ fn on_misc_msg(msg: &str) -> MiscMessage {
         MiscMessage::Normal
     } else if obj.contains_key("status") {
         let status = obj.get("status").unwrap().as_i64().unwrap();
+        // the msg_id of the command
+        let id = obj
+            .get("msg_id")
+            .or_else(|| obj.get("msgId"))
+            .and_then(|x| x.as_u64());
         // see https://apidocv2.bitz.plus/en/#error
         match status {
-            -101001 => {
-                error!("Subscription type parameter error: {}", msg);
-                panic!("Subscription type parameter error: {}", msg);
+            200 => match id {
+                Some(id) => request::on_ack(id),
+                None => MiscMessage::Misc,
+            },
+            -101003..=-101001 => {
+                // subscription type, symbol or k-line resolution error
+                error!("Received {} from {}", msg, EXCHANGE_NAME);
+                request::on_error(
+                    id,
+                    Some(status),
+                    obj.get("msg").and_then(|x| x.as_str()).unwrap_or(msg),
+                )
             }
-            -101002 => {
-                error!("Fail to get subscribed symbol of trading pair: {}", msg);
-                panic!("Fail to get subscribed symbol of trading pair: {}", msg);
+            _ => {
+                warn!("Received {} from {}", msg, EXCHANGE_NAME);
+                MiscMessage::Misc
             }
-            -101003 => {
-                error!("k-line scale resolution error: {}", msg);
-                panic!("k-line scale resolution error: {}", msg);
-            }
-            _ => warn!("Received {} from {}", msg, EXCHANGE_NAME),
         }
-        MiscMessage::Misc
     } else {
         warn!("Received {} from {}", msg, EXCHANGE_NAME);
         MiscMessage::Misc
@@ -109,26 +119,7 @@ fn to_raw_channel(channel: &str, pair: &str) -> String {
     format!("{}{}{}", channel, CHANNEL_PAIR_DELIMITER, pair)
 }
 
-#[rustfmt::skip]
-impl_trait!(Trade, BitzSpotWSClient, subscribe_trade, "order", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(OrderBook, BitzSpotWSClient, subscribe_orderbook, "depth", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(Ticker, BitzSpotWSClient, subscribe_ticker, "market", to_raw_channel);
-
-impl<'a> BBO for BitzSpotWSClient<'a> {
-    fn subscribe_bbo(&self, _pairs: &[String]) {
-        panic!("Bitz does NOT have BBO channel");
-    }
-}
-
-impl<'a> OrderBookSnapshot for BitzSpotWSClient<'a> {
-    fn subscribe_orderbook_snapshot(&self, _pairs: &[String]) {
-        panic!("Bitz does NOT have orderbook snapshot channel");
-    }
-}
-
-fn to_candlestick_raw_channel(pair: &str, interval: u32) -> String {
+fn to_candlestick_raw_channel(pair: &str, interval: u32) -> Option<String> {
     let interval_str = match interval {
         60 => "1min",
         300 => "5min",
@@ -140,11 +131,9 @@ fn to_candlestick_raw_channel(pair: &str, interval: u32) -> String {
         432000 => "5day",
         604800 => "1week",
         2592000 => "1mon",
-        _ => panic!(
-            "Bitz available intervals 1min,5min,15min,30min,60min,4hour,1day,5day,1week,1mon"
-        ),
+        _ => return None, // Bitz available intervals 1min,5min,15min,30min,60min,4hour,1day,5day,1week,1mon
     };
-    format!(
+    Some(format!(
         r#"{{"action":"Topic.sub", "data":{{"symbol":"{}", "type":"kline", "resolution":"{}", "_CDID":"100002", "dataType":"1"}}, "msg_id":{}}}"#,
         pair,
         interval_str,
@@ -152,10 +141,38 @@ fn to_candlestick_raw_channel(pair: &str, interval: u32) -> String {
             .duration_since(UNIX_EPOCH)
             .unwrap()
             .as_millis(),
-    )
+    ))
 }
 
-impl_candlestick!(BitzSpotWSClient);
+// Bitz does NOT have BBO and orderbook snapshot channels
+impl<'a> ChannelTranslator for BitzSpotWSClient<'a> {
+    fn translate(&self, channel: &Channel) -> Result<String, WsError> {
+        match channel {
+            Channel::Trade(pair) => Some(to_raw_channel("order", pair)),
+            Channel::L2 {
+                pair,
+                depth: None,
+                speed: None,
+            } => Some(to_raw_channel("depth", pair)),
+            Channel::Ticker(pair) => Some(to_raw_channel("market", pair)),
+            Channel::Kline { pair, interval } => {
+                return to_candlestick_raw_channel(pair, *interval)
+                    .ok_or_else(|| WsError::invalid_interval(EXCHANGE_NAME, *interval));
+            }
+            _ => None,
+        }
+        .ok_or_else(|| WsError::unsupported(EXCHANGE_NAME, channel))
+    }
+
+    fn capabilities(&self) -> Vec<ChannelKind> {
+        vec![
+            ChannelKind::Trade,
+            ChannelKind::L2,
+            ChannelKind::Ticker,
+            ChannelKind::Kline,
+        ]
+    }
+}
 
 define_client!(
     BitzSpotWSClient,
@@ -166,3 +183,4 @@ define_client!(
     Some(CLIENT_PING_INTERVAL_AND_MSG),
     None
 );
+define_async_client!(BitzSpotAsyncWSClient, BitzSpotWSClient);
@@ -1,7 +1,7 @@
 mod bitz_spot;
 // mod bitz_swap;
 
-pub use bitz_spot::BitzSpotWSClient;
+pub use bitz_spot::{BitzSpotAsyncWSClient, BitzSpotWSClient};
 // pub use bitz_swap::BitzSwapWSClient;
 
 pub(super) const EXCHANGE_NAME: &str = "bitz";
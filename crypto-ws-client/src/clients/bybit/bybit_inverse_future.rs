@@ -1,8 +1,8 @@
-use crate::WSClient;
+use crate::{Channel, ChannelKind, WSClient, WsError};
 use std::sync::{Arc, Mutex};
 
 use super::super::ws_client_internal::WSClientInternal;
-use super::super::{Candlestick, OrderBook, OrderBookSnapshot, Ticker, Trade, BBO};
+use super::super::ChannelTranslator;
 use super::utils::{
     channels_to_commands, on_misc_msg, to_raw_channel, CLIENT_PING_INTERVAL_AND_MSG, EXCHANGE_NAME,
 };
@@ -17,22 +17,7 @@ pub struct BybitInverseFutureWSClient<'a> {
     client: WSClientInternal<'a>,
 }
 
-#[rustfmt::skip]
-impl_trait!(Trade, BybitInverseFutureWSClient, subscribe_trade, "trade", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(BBO, BybitInverseFutureWSClient, subscribe_bbo, "orderBookL2_25", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(OrderBook, BybitInverseFutureWSClient, subscribe_orderbook, "orderBookL2_25", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(Ticker, BybitInverseFutureWSClient, subscribe_ticker, "instrument_info.100ms", to_raw_channel);
-
-impl<'a> OrderBookSnapshot for BybitInverseFutureWSClient<'a> {
-    fn subscribe_orderbook_snapshot(&self, _pairs: &[String]) {
-        panic!("FTX does NOT have orderbook snapshot channel");
-    }
-}
-
-fn to_candlestick_raw_channel(pair: &str, interval: u32) -> String {
+fn to_candlestick_raw_channel(pair: &str, interval: u32) -> Option<String> {
     let interval_str = match interval {
         60 => "1",
         180 => "3",
@@ -46,12 +31,56 @@ fn to_candlestick_raw_channel(pair: &str, interval: u32) -> String {
         86400 => "D",
         604800 => "W",
         2592000 => "M",
-        _ => panic!("Huobi has intervals 1min,5min,15min,30min,60min,4hour,1day,1week,1mon"),
+        _ => return None,
     };
-    format!("klineV2.{}.{}", interval_str, pair)
+    Some(format!("klineV2.{}.{}", interval_str, pair))
 }
 
-impl_candlestick!(BybitInverseFutureWSClient);
+// Bybit does NOT have orderbook snapshot channel
+impl<'a> ChannelTranslator for BybitInverseFutureWSClient<'a> {
+    fn translate(&self, channel: &Channel) -> Result<String, WsError> {
+        match channel {
+            Channel::Trade(pair) => Some(to_raw_channel("trade", pair)),
+            Channel::BBO(pair) => Some(to_raw_channel("orderBookL2_25", pair)),
+            Channel::L2 {
+                pair,
+                depth: None | Some(25),
+                speed: None,
+            } => Some(to_raw_channel("orderBookL2_25", pair)),
+            Channel::L2 {
+                pair,
+                depth: Some(200),
+                speed: None | Some(100),
+            } => Some(to_raw_channel("orderBook_200.100ms", pair)),
+            Channel::Ticker(pair) => Some(to_raw_channel("instrument_info.100ms", pair)),
+            Channel::Kline { pair, interval } => {
+                return to_candlestick_raw_channel(pair, *interval)
+                    .ok_or_else(|| WsError::invalid_interval(EXCHANGE_NAME, *interval));
+            }
+            // instrument_info contains mark_price, index_price and open_interest
+            Channel::MarkPrice(pair) | Channel::IndexPrice(pair) | Channel::OpenInterest(pair) => {
+                Some(to_raw_channel("instrument_info.100ms", pair))
+            }
+            Channel::Liquidation(pair) => Some(to_raw_channel("liquidation", pair)),
+            _ => None,
+        }
+        .ok_or_else(|| WsError::unsupported(EXCHANGE_NAME, channel))
+    }
+
+    fn capabilities(&self) -> Vec<ChannelKind> {
+        vec![
+            ChannelKind::Trade,
+            ChannelKind::L2,
+            ChannelKind::BBO,
+            ChannelKind::Ticker,
+            ChannelKind::Kline,
+            ChannelKind::MarkPrice,
+            ChannelKind::IndexPrice,
+            ChannelKind::Liquidation,
+            ChannelKind::OpenInterest,
+        ]
+    }
+}
 
 define_client!(
     BybitInverseFutureWSClient,
@@ -62,3 +91,4 @@ define_client!(
     Some(CLIENT_PING_INTERVAL_AND_MSG),
     None
 );
+define_async_client!(BybitInverseFutureAsyncWSClient, BybitInverseFutureWSClient);
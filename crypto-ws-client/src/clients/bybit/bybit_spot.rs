@@ -0,0 +1,190 @@
+use crate::{Channel, ChannelKind, WSClient, WsError};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use super::super::ws_client_internal::{MiscMessage, WSClientInternal};
+use super::super::ChannelTranslator;
+use super::utils::{to_raw_channel, EXCHANGE_NAME};
+
+use log::*;
+use serde_json::Value;
+
+const WEBSOCKET_URL: &str = "wss://stream.bybit.com/spot/quote/ws/v1";
+
+/// See https://bybit-exchange.github.io/docs/spot/#t-heartbeat
+const CLIENT_PING_INTERVAL_AND_MSG: (u64, &str) = (30, r#"{"ping":1535975085052}"#);
+
+/// Bybit Spot market.
+///
+/// * WebSocket API doc: <https://bybit-exchange.github.io/docs/spot/#t-websocket>
+/// * Trading at: <https://www.bybit.com/trade/spot/>
+pub struct BybitSpotWSClient<'a> {
+    client: WSClientInternal<'a>,
+}
+
+// Topics of the same type share one command, symbols are separated by commas
+fn channels_to_commands(channels: &[String], subscribe: bool) -> Vec<String> {
+    let mut all_commands: Vec<String> = channels
+        .iter()
+        .filter(|ch| ch.starts_with('{'))
+        .map(|s| s.to_string())
+        .collect();
+
+    let mut topic_symbols = BTreeMap::<&str, Vec<&str>>::new();
+    for s in channels.iter().filter(|ch| !ch.starts_with('{')) {
+        let (topic, symbol) = s.split_once('.').unwrap();
+        topic_symbols.entry(topic).or_default().push(symbol);
+    }
+
+    for (topic, symbols) in topic_symbols {
+        all_commands.push(format!(
+            r#"{{"topic":"{}","event":"{}","symbol":"{}","params":{{"binary":false}}}}"#,
+            topic,
+            if subscribe { "sub" } else { "cancel" },
+            symbols.join(",")
+        ));
+    }
+
+    all_commands
+}
+
+fn on_misc_msg(msg: &str) -> MiscMessage {
+    let resp = serde_json::from_str::<HashMap<String, Value>>(msg);
+    if resp.is_err() {
+        error!("{} is not a JSON object, {}", msg, EXCHANGE_NAME);
+        return MiscMessage::Misc;
+    }
+    let obj = resp.unwrap();
+
+    if obj.contains_key("pong") {
+        // {"pong":1535975085052}
+        debug!("Received {} from {}", msg, EXCHANGE_NAME);
+        MiscMessage::Pong
+    } else if obj.contains_key("event") {
+        // {"topic":"trade","event":"sub","symbol":"BTCUSDT","params":{"binary":"false","symbolName":"BTCUSDT"},"code":"0","msg":"Success"}
+        let topic = obj
+            .get("topic")
+            .and_then(|x| x.as_str())
+            .unwrap_or_default();
+        let raw_channels: Vec<String> = obj
+            .get("symbol")
+            .and_then(|x| x.as_str())
+            .map(|symbols| {
+                symbols
+                    .split(',')
+                    .map(|symbol| to_raw_channel(topic, symbol))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let code = obj.get("code").and_then(|x| x.as_str()).unwrap_or("0");
+        if code != "0" {
+            error!("Received {} from {}", msg, EXCHANGE_NAME);
+            let reason = obj.get("msg").and_then(|x| x.as_str()).unwrap_or(msg);
+            MiscMessage::Rejected {
+                channels: raw_channels,
+                code: code.parse().ok(),
+                reason: reason.to_string(),
+            }
+        } else if obj.get("event").and_then(|x| x.as_str()) == Some("sub") {
+            info!("Received {} from {}", msg, EXCHANGE_NAME);
+            MiscMessage::Subscribed(raw_channels)
+        } else {
+            info!("Received {} from {}", msg, EXCHANGE_NAME);
+            MiscMessage::Misc
+        }
+    } else if obj.contains_key("topic") && obj.contains_key("data") {
+        MiscMessage::Normal
+    } else {
+        // {"code":"-10009","desc":"Invalid period!"}
+        warn!("Received {} from {}", msg, EXCHANGE_NAME);
+        MiscMessage::Misc
+    }
+}
+
+fn to_candlestick_raw_channel(pair: &str, interval: u32) -> Option<String> {
+    let interval_str = match interval {
+        60 => "1m",
+        180 => "3m",
+        300 => "5m",
+        900 => "15m",
+        1800 => "30m",
+        3600 => "1h",
+        7200 => "2h",
+        14400 => "4h",
+        21600 => "6h",
+        43200 => "12h",
+        86400 => "1d",
+        604800 => "1w",
+        2592000 => "1M",
+        _ => return None,
+    };
+    Some(to_raw_channel(&format!("kline_{}", interval_str), pair))
+}
+
+// The depth topic pushes snapshots, diffDepth pushes a snapshot first, then
+// incremental updates
+impl<'a> ChannelTranslator for BybitSpotWSClient<'a> {
+    fn translate(&self, channel: &Channel) -> Result<String, WsError> {
+        match channel {
+            Channel::Trade(pair) => Some(to_raw_channel("trade", pair)),
+            Channel::BBO(pair) => Some(to_raw_channel("bookTicker", pair)),
+            Channel::L2 {
+                pair,
+                depth: None,
+                speed: None,
+            } => Some(to_raw_channel("diffDepth", pair)),
+            Channel::L2Snapshot(pair) => Some(to_raw_channel("depth", pair)),
+            Channel::Ticker(pair) => Some(to_raw_channel("realtimes", pair)),
+            Channel::Kline { pair, interval } => {
+                return to_candlestick_raw_channel(pair, *interval)
+                    .ok_or_else(|| WsError::invalid_interval(EXCHANGE_NAME, *interval));
+            }
+            _ => None,
+        }
+        .ok_or_else(|| WsError::unsupported(EXCHANGE_NAME, channel))
+    }
+
+    fn capabilities(&self) -> Vec<ChannelKind> {
+        vec![
+            ChannelKind::Trade,
+            ChannelKind::L2,
+            ChannelKind::L2Snapshot,
+            ChannelKind::BBO,
+            ChannelKind::Ticker,
+            ChannelKind::Kline,
+        ]
+    }
+}
+
+define_client!(
+    BybitSpotWSClient,
+    EXCHANGE_NAME,
+    WEBSOCKET_URL,
+    channels_to_commands,
+    on_misc_msg,
+    Some(CLIENT_PING_INTERVAL_AND_MSG),
+    None
+);
+define_async_client!(BybitSpotAsyncWSClient, BybitSpotWSClient);
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_channels_to_commands() {
+        let commands = super::channels_to_commands(
+            &[
+                "trade.BTCUSDT".to_string(),
+                "trade.ETHUSDT".to_string(),
+                "kline_1m.BTCUSDT".to_string(),
+            ],
+            true,
+        );
+        assert_eq!(
+            vec![
+                r#"{"topic":"kline_1m","event":"sub","symbol":"BTCUSDT","params":{"binary":false}}"#,
+                r#"{"topic":"trade","event":"sub","symbol":"BTCUSDT,ETHUSDT","params":{"binary":false}}"#,
+            ],
+            commands
+        );
+    }
+}
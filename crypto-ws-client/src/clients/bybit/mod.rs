@@ -1,8 +1,10 @@
 mod bybit_inverse_future;
 mod bybit_inverse_swap;
 mod bybit_linear_swap;
+mod bybit_spot;
 mod utils;
 
-pub use bybit_inverse_future::BybitInverseFutureWSClient;
-pub use bybit_inverse_swap::BybitInverseSwapWSClient;
-pub use bybit_linear_swap::BybitLinearSwapWSClient;
+pub use bybit_inverse_future::{BybitInverseFutureAsyncWSClient, BybitInverseFutureWSClient};
+pub use bybit_inverse_swap::{BybitInverseSwapAsyncWSClient, BybitInverseSwapWSClient};
+pub use bybit_linear_swap::{BybitLinearSwapAsyncWSClient, BybitLinearSwapWSClient};
+pub use bybit_spot::{BybitSpotAsyncWSClient, BybitSpotWSClient};
@@ -43,6 +43,19 @@ pub(super) fn on_misc_msg(msg: &str) -> MiscMessage {
         MiscMessage::Normal
     } else {
         if obj.contains_key("success") {
+            // {"success":true,"ret_msg":"","conn_id":"...","request":{"op":"subscribe","args":["trade.BTCUSD"]}}
+            let subscribe_args = obj.get("request").and_then(|request| {
+                if request.get("op").and_then(|x| x.as_str()) == Some("subscribe") {
+                    request.get("args").and_then(|x| x.as_array()).map(|args| {
+                        args.iter()
+                            .filter_map(|x| x.as_str())
+                            .map(|x| x.to_string())
+                            .collect::<Vec<String>>()
+                    })
+                } else {
+                    None
+                }
+            });
             if obj.get("success").unwrap().as_bool().unwrap() {
                 info!("Received {} from {}", msg, EXCHANGE_NAME);
                 if obj.contains_key("ret_msg")
@@ -50,9 +63,18 @@ pub(super) fn on_misc_msg(msg: &str) -> MiscMessage {
                 {
                     return MiscMessage::Pong;
                 }
+                if let Some(args) = subscribe_args {
+                    return MiscMessage::Subscribed(args);
+                }
             } else {
                 error!("Received {} from {}", msg, EXCHANGE_NAME);
-                panic!("Received {} from {}", msg, EXCHANGE_NAME);
+                // {"success":false,"ret_msg":"error:topic:trade.BTCUSD1 not found","conn_id":"...","request":{"op":"subscribe","args":["trade.BTCUSD1"]}}
+                let ret_msg = obj.get("ret_msg").and_then(|x| x.as_str()).unwrap_or(msg);
+                return MiscMessage::Rejected {
+                    channels: subscribe_args.unwrap_or_default(),
+                    code: None,
+                    reason: ret_msg.to_string(),
+                };
             }
         } else {
             warn!("Received {} from {}", msg, EXCHANGE_NAME);
@@ -0,0 +1,179 @@
+/// A typed channel, which every client translates to a raw channel of its
+/// exchange.
+///
+/// Pairs are in exchange specific formats, the same as `subscribe_trade()`
+/// and other high level APIs. A channel not supported by an exchange is
+/// rejected by `WSClient::subscribe_channels()` with `WsError::Unsupported`.
+///
+/// ## Example
+///
+/// ```no_run
+/// use std::sync::{Arc, Mutex};
+/// use crypto_ws_client::{BinanceSpotWSClient, Channel, WSClient};
+///
+/// let ws_client = BinanceSpotWSClient::new(Arc::new(Mutex::new(|msg| println!("{}", msg))), None);
+/// let channels = vec![
+///     Channel::Trade("btcusdt".to_string()),
+///     Channel::l2("btcusdt").speed(1000),
+///     Channel::kline("btcusdt", 60),
+/// ];
+/// ws_client.subscribe_channels(&channels).unwrap();
+/// ws_client.run(None);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Channel {
+    /// Tick-by-tick trades
+    Trade(String),
+    /// Incremental level2 orderbook, a snapshot followed by updates.
+    ///
+    /// `depth` is the number of price levels and `speed` is the update
+    /// interval in milliseconds, None means the default of the exchange.
+    L2 {
+        pair: String,
+        depth: Option<u32>,
+        speed: Option<u32>,
+    },
+    /// Level2 orderbook snapshots sent at interval
+    L2Snapshot(String),
+    /// Level3 orderbook, non-aggregated and updated tick-by-tick
+    L3(String),
+    /// Best bid & offer
+    #[allow(clippy::upper_case_acronyms)]
+    BBO(String),
+    /// 24hr rolling window ticker
+    Ticker(String),
+    /// Candlesticks, `interval` is in seconds
+    Kline { pair: String, interval: u32 },
+    /// Mark price of a derivative contract
+    MarkPrice(String),
+    /// Index price of a derivative contract
+    IndexPrice(String),
+    /// Liquidation orders
+    Liquidation(String),
+    /// Open interest of a derivative contract
+    OpenInterest(String),
+    /// Funding rate of a perpetual swap
+    FundingRate(String),
+    /// A raw channel, which is subscribed as is, see `WSClient::subscribe()`
+    Raw(String),
+}
+
+/// The kind of a `Channel`, regardless of its pair and parameters, see
+/// `WSClient::capabilities()`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ChannelKind {
+    Trade,
+    L2,
+    L2Snapshot,
+    L3,
+    #[allow(clippy::upper_case_acronyms)]
+    BBO,
+    Ticker,
+    Kline,
+    MarkPrice,
+    IndexPrice,
+    Liquidation,
+    OpenInterest,
+    FundingRate,
+}
+
+impl Channel {
+    /// Creates an incremental level2 orderbook channel with the default
+    /// depth and speed.
+    pub fn l2(pair: &str) -> Channel {
+        Channel::L2 {
+            pair: pair.to_string(),
+            depth: None,
+            speed: None,
+        }
+    }
+
+    /// Creates a candlestick channel, `interval` is in seconds.
+    pub fn kline(pair: &str, interval: u32) -> Channel {
+        Channel::Kline {
+            pair: pair.to_string(),
+            interval,
+        }
+    }
+
+    /// Sets the number of price levels of a level2 orderbook channel, no
+    /// effect on other channels.
+    pub fn depth(mut self, levels: u32) -> Channel {
+        if let Channel::L2 { depth, .. } = &mut self {
+            *depth = Some(levels);
+        }
+        self
+    }
+
+    /// Sets the update interval in milliseconds of a level2 orderbook
+    /// channel, no effect on other channels.
+    pub fn speed(mut self, millis: u32) -> Channel {
+        if let Channel::L2 { speed, .. } = &mut self {
+            *speed = Some(millis);
+        }
+        self
+    }
+
+    /// Returns the kind of this channel, None for raw channels.
+    pub fn kind(&self) -> Option<ChannelKind> {
+        match self {
+            Channel::Trade(_) => Some(ChannelKind::Trade),
+            Channel::L2 { .. } => Some(ChannelKind::L2),
+            Channel::L2Snapshot(_) => Some(ChannelKind::L2Snapshot),
+            Channel::L3(_) => Some(ChannelKind::L3),
+            Channel::BBO(_) => Some(ChannelKind::BBO),
+            Channel::Ticker(_) => Some(ChannelKind::Ticker),
+            Channel::Kline { .. } => Some(ChannelKind::Kline),
+            Channel::MarkPrice(_) => Some(ChannelKind::MarkPrice),
+            Channel::IndexPrice(_) => Some(ChannelKind::IndexPrice),
+            Channel::Liquidation(_) => Some(ChannelKind::Liquidation),
+            Channel::OpenInterest(_) => Some(ChannelKind::OpenInterest),
+            Channel::FundingRate(_) => Some(ChannelKind::FundingRate),
+            Channel::Raw(_) => None,
+        }
+    }
+
+    /// Returns the pair of this channel, None for raw channels.
+    pub fn pair(&self) -> Option<&str> {
+        match self {
+            Channel::Trade(pair)
+            | Channel::L2 { pair, .. }
+            | Channel::L2Snapshot(pair)
+            | Channel::L3(pair)
+            | Channel::BBO(pair)
+            | Channel::Ticker(pair)
+            | Channel::Kline { pair, .. }
+            | Channel::MarkPrice(pair)
+            | Channel::IndexPrice(pair)
+            | Channel::Liquidation(pair)
+            | Channel::OpenInterest(pair)
+            | Channel::FundingRate(pair) => Some(pair),
+            Channel::Raw(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Channel, ChannelKind};
+
+    #[test]
+    fn test_builder() {
+        assert_eq!(
+            Channel::L2 {
+                pair: "btcusdt".to_string(),
+                depth: Some(20),
+                speed: Some(100),
+            },
+            Channel::l2("btcusdt").depth(20).speed(100)
+        );
+        assert_eq!(
+            Channel::Trade("btcusdt".to_string()),
+            Channel::Trade("btcusdt".to_string()).depth(20)
+        );
+        assert_eq!(Some("XBTUSD"), Channel::kline("XBTUSD", 60).pair());
+        assert_eq!(None, Channel::Raw("instrument".to_string()).pair());
+        assert_eq!(Some(ChannelKind::L2), Channel::l2("XBTUSD").kind());
+        assert_eq!(None, Channel::Raw("instrument".to_string()).kind());
+    }
+}
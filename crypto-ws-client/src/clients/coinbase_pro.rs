@@ -1,11 +1,11 @@
-use crate::{Level3OrderBook, WSClient};
+use crate::{Channel, ChannelKind, Level3OrderBook, WSClient, WsError};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
 use super::{
     utils::CHANNEL_PAIR_DELIMITER,
     ws_client_internal::{MiscMessage, WSClientInternal},
-    Candlestick, OrderBook, OrderBookSnapshot, Ticker, Trade, BBO,
+    ChannelTranslator,
 };
 
 use log::*;
@@ -89,12 +89,35 @@ fn on_misc_msg(msg: &str) -> MiscMessage {
 
     match obj.get("type").unwrap().as_str().unwrap() {
         "error" => {
+            // {"type":"error","message":"Failed to subscribe","reason":"BTC-USD1 is not a valid product"}
             error!("Received {} from {}", msg, EXCHANGE_NAME);
-            panic!("Received {} from {}", msg, EXCHANGE_NAME);
+            MiscMessage::Rejected {
+                channels: Vec::new(),
+                code: None,
+                reason: obj
+                    .get("reason")
+                    .or_else(|| obj.get("message"))
+                    .and_then(|x| x.as_str())
+                    .unwrap_or(msg)
+                    .to_string(),
+            }
         }
         "subscriptions" => {
             info!("Received {} from {}", msg, EXCHANGE_NAME);
-            MiscMessage::Misc
+            // {"type":"subscriptions","channels":[{"name":"matches","product_ids":["BTC-USD"]}]}
+            let mut raw_channels = Vec::<String>::new();
+            if let Some(channels) = obj.get("channels").and_then(|x| x.as_array()) {
+                for channel in channels.iter() {
+                    let name = channel.get("name").and_then(|x| x.as_str());
+                    let product_ids = channel.get("product_ids").and_then(|x| x.as_array());
+                    if let (Some(name), Some(product_ids)) = (name, product_ids) {
+                        for product_id in product_ids.iter().filter_map(|x| x.as_str()) {
+                            raw_channels.push(to_raw_channel(name, product_id));
+                        }
+                    }
+                }
+            }
+            MiscMessage::Subscribed(raw_channels)
         }
         "heartbeat" => {
             debug!("Received {} from {}", msg, EXCHANGE_NAME);
@@ -108,38 +131,37 @@ fn to_raw_channel(channel: &str, pair: &str) -> String {
     format!("{}{}{}", channel, CHANNEL_PAIR_DELIMITER, pair)
 }
 
-#[rustfmt::skip]
-impl_trait!(Trade, CoinbaseProWSClient, subscribe_trade, "matches", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(Ticker, CoinbaseProWSClient, subscribe_ticker, "ticker", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(OrderBook, CoinbaseProWSClient, subscribe_orderbook, "level2", to_raw_channel);
-
-impl<'a> BBO for CoinbaseProWSClient<'a> {
-    fn subscribe_bbo(&self, _pairs: &[String]) {
-        panic!("CoinbasePro WebSocket does NOT have BBO channel");
-    }
-}
-
-impl<'a> OrderBookSnapshot for CoinbaseProWSClient<'a> {
-    fn subscribe_orderbook_snapshot(&self, _pairs: &[String]) {
-        panic!("CoinbasePro does NOT have orderbook snapshot channel");
+// CoinbasePro WebSocket does NOT have BBO, orderbook snapshot and candlestick channels
+impl<'a> ChannelTranslator for CoinbaseProWSClient<'a> {
+    fn translate(&self, channel: &Channel) -> Result<String, WsError> {
+        match channel {
+            Channel::Trade(pair) => Some(to_raw_channel("matches", pair)),
+            Channel::Ticker(pair) => Some(to_raw_channel("ticker", pair)),
+            Channel::L2 {
+                pair,
+                depth: None,
+                speed: None,
+            } => Some(to_raw_channel("level2", pair)),
+            Channel::L3(pair) => Some(to_raw_channel("full", pair)),
+            _ => None,
+        }
+        .ok_or_else(|| WsError::unsupported(EXCHANGE_NAME, channel))
     }
-}
 
-impl<'a> Candlestick for CoinbaseProWSClient<'a> {
-    fn subscribe_candlestick(&self, _pairs: &[String], _interval: u32) {
-        panic!("CoinbasePro does NOT have candlestick channel");
+    fn capabilities(&self) -> Vec<ChannelKind> {
+        vec![
+            ChannelKind::Trade,
+            ChannelKind::L2,
+            ChannelKind::L3,
+            ChannelKind::Ticker,
+        ]
     }
 }
 
 impl<'a> Level3OrderBook for CoinbaseProWSClient<'a> {
-    fn subscribe_l3_orderbook(&self, symbols: &[String]) {
-        let raw_channels: Vec<String> = symbols
-            .iter()
-            .map(|symbol| to_raw_channel("full", symbol))
-            .collect();
-        self.client.subscribe(&raw_channels);
+    fn subscribe_l3_orderbook(&self, symbols: &[String]) -> Result<(), WsError> {
+        let channels: Vec<Channel> = symbols.iter().cloned().map(Channel::L3).collect();
+        self.subscribe_channels(&channels)
     }
 }
 
@@ -152,6 +174,7 @@ define_client!(
     None,
     None
 );
+define_async_client!(CoinbaseProAsyncWSClient, CoinbaseProWSClient);
 
 #[cfg(test)]
 mod tests {
@@ -1,11 +1,13 @@
-use crate::WSClient;
+use crate::{Channel, ChannelKind, Credentials, PrivateWSClient, WSClient, WsError};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
+use super::auth::{self, Auth};
 use super::ws_client_internal::{MiscMessage, WSClientInternal};
-use super::{Candlestick, OrderBook, OrderBookSnapshot, Ticker, Trade, BBO};
+use super::ChannelTranslator;
 
 use log::*;
+use rand::Rng;
 use serde_json::Value;
 use tungstenite::Message;
 
@@ -26,25 +28,31 @@ pub struct DeribitWSClient<'a> {
 }
 
 fn channels_to_commands(channels: &[String], subscribe: bool) -> Vec<String> {
-    let channels_to_parse: Vec<&String> =
-        channels.iter().filter(|ch| !ch.starts_with('{')).collect();
     let mut all_commands: Vec<String> = channels
         .iter()
         .filter(|ch| ch.starts_with('{'))
         .map(|s| s.to_string())
         .collect();
 
-    if !channels_to_parse.is_empty() {
-        all_commands.append(&mut vec![format!(
-            r#"{{"method": "public/{}", "params": {{"channels": {}}}}}"#,
-            if subscribe {
-                "subscribe"
-            } else {
-                "unsubscribe"
-            },
-            serde_json::to_string(channels).unwrap()
-        )])
-    };
+    // user.* channels are private, which need private/subscribe
+    let (private_channels, public_channels): (Vec<&String>, Vec<&String>) = channels
+        .iter()
+        .filter(|ch| !ch.starts_with('{'))
+        .partition(|ch| ch.starts_with("user."));
+    for (scope, channels_to_parse) in [("public", public_channels), ("private", private_channels)] {
+        if !channels_to_parse.is_empty() {
+            all_commands.push(format!(
+                r#"{{"method": "{}/{}", "params": {{"channels": {}}}}}"#,
+                scope,
+                if subscribe {
+                    "subscribe"
+                } else {
+                    "unsubscribe"
+                },
+                serde_json::to_string(&channels_to_parse).unwrap()
+            ))
+        }
+    }
 
     all_commands
         .push(r#"{"method": "public/set_heartbeat", "params": {"interval": 10}}"#.to_string());
@@ -55,9 +63,18 @@ fn channels_to_commands(channels: &[String], subscribe: bool) -> Vec<String> {
 fn on_misc_msg(msg: &str) -> MiscMessage {
     let obj = serde_json::from_str::<HashMap<String, Value>>(&msg).unwrap();
 
-    if obj.contains_key("error") {
+    if let Some(err) = obj.get("error") {
+        // {"jsonrpc":"2.0","id":1,"error":{"message":"Invalid params","code":-32602}}
         error!("Received {} from {}", msg, EXCHANGE_NAME);
-        panic!("Received {} from {}", msg, EXCHANGE_NAME);
+        MiscMessage::Rejected {
+            channels: Vec::new(),
+            code: err.get("code").and_then(|x| x.as_i64()),
+            reason: err
+                .get("message")
+                .and_then(|x| x.as_str())
+                .unwrap_or(msg)
+                .to_string(),
+        }
     } else if obj.contains_key("result") {
         let result = obj.get("result").unwrap();
         if result.is_string() {
@@ -73,13 +90,24 @@ fn on_misc_msg(msg: &str) -> MiscMessage {
                 if arr[0].is_object() {
                     MiscMessage::Normal
                 } else {
-                    MiscMessage::Misc
+                    // subscribed channels, e.g., {"jsonrpc":"2.0","id":1,"result":["trades.BTC-PERPETUAL.raw"]}
+                    let channels: Vec<String> = arr
+                        .iter()
+                        .filter_map(|x| x.as_str())
+                        .map(|x| x.to_string())
+                        .collect();
+                    MiscMessage::Subscribed(channels)
                 }
             } else {
                 panic!("Subscribed invalid symbols, {}, {}", msg, EXCHANGE_NAME);
             }
         } else if result.is_object() {
-            if result.as_object().unwrap().contains_key("version") {
+            let result = result.as_object().unwrap();
+            if result.contains_key("version") {
+                MiscMessage::Misc
+            } else if result.contains_key("access_token") {
+                // response of public/auth
+                info!("Authenticated with {}", EXCHANGE_NAME);
                 MiscMessage::Misc
             } else {
                 MiscMessage::Normal
@@ -120,29 +148,7 @@ fn on_misc_msg(msg: &str) -> MiscMessage {
     }
 }
 
-fn to_raw_channel(channel: &str, pair: &str) -> String {
-    match channel {
-        "trade" => format!("trades.{}.raw", pair),
-        "ticker" => format!("ticker.{}.100ms", pair),
-        "orderbook" => format!("book.{}.100ms", pair),
-        "orderbook_snapshot" => format!("book.{}.none.20.100ms", pair),
-        "bbo" => format!("quote.{}", pair),
-        _ => panic!("Unknown channel {}", channel),
-    }
-}
-
-#[rustfmt::skip]
-impl_trait!(Trade, DeribitWSClient, subscribe_trade, "trade", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(Ticker, DeribitWSClient, subscribe_ticker, "ticker", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(OrderBook, DeribitWSClient, subscribe_orderbook, "orderbook", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(OrderBookSnapshot, DeribitWSClient, subscribe_orderbook_snapshot, "orderbook_snapshot", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(BBO, DeribitWSClient, subscribe_bbo, "bbo", to_raw_channel);
-
-fn to_candlestick_raw_channel(pair: &str, interval: u32) -> String {
+fn to_candlestick_raw_channel(pair: &str, interval: u32) -> Option<String> {
     let interval_str = match interval {
         60 => "1",
         180 => "3",
@@ -156,12 +162,105 @@ fn to_candlestick_raw_channel(pair: &str, interval: u32) -> String {
         21600 => "360",
         43200 => "720",
         86400 => "1D",
-        _ => panic!("Unknown interval {}", interval),
+        _ => return None,
     };
-    format!("chart.trades.{}.{}", pair, interval_str)
+    Some(format!("chart.trades.{}.{}", pair, interval_str))
 }
 
-impl_candlestick!(DeribitWSClient);
+impl<'a> ChannelTranslator for DeribitWSClient<'a> {
+    fn translate(&self, channel: &Channel) -> Result<String, WsError> {
+        match channel {
+            Channel::Trade(pair) => Some(format!("trades.{}.raw", pair)),
+            Channel::Ticker(pair) => Some(format!("ticker.{}.100ms", pair)),
+            Channel::L2 {
+                pair,
+                depth: None,
+                speed: None | Some(100),
+            } => Some(format!("book.{}.100ms", pair)),
+            Channel::L2Snapshot(pair) => Some(format!("book.{}.none.20.100ms", pair)),
+            Channel::BBO(pair) => Some(format!("quote.{}", pair)),
+            Channel::Kline { pair, interval } => {
+                return to_candlestick_raw_channel(pair, *interval)
+                    .ok_or_else(|| WsError::invalid_interval(EXCHANGE_NAME, *interval));
+            }
+            // ticker contains mark_price and open_interest
+            Channel::MarkPrice(pair) | Channel::OpenInterest(pair) => {
+                Some(format!("ticker.{}.100ms", pair))
+            }
+            // Index prices are pushed per currency, e.g., btc_usd of BTC-PERPETUAL
+            Channel::IndexPrice(pair) => Some(format!(
+                "deribit_price_index.{}_usd",
+                pair.split('-').next().unwrap().to_lowercase()
+            )),
+            _ => None,
+        }
+        .ok_or_else(|| WsError::unsupported(EXCHANGE_NAME, channel))
+    }
+
+    fn capabilities(&self) -> Vec<ChannelKind> {
+        vec![
+            ChannelKind::Trade,
+            ChannelKind::L2,
+            ChannelKind::L2Snapshot,
+            ChannelKind::BBO,
+            ChannelKind::Ticker,
+            ChannelKind::Kline,
+            ChannelKind::MarkPrice,
+            ChannelKind::IndexPrice,
+            ChannelKind::OpenInterest,
+        ]
+    }
+}
+
+// see https://docs.deribit.com/#authentication
+fn sign(api_secret: &str, timestamp: u64, nonce: &str, data: &str) -> String {
+    let msg = format!("{}\n{}\n{}", timestamp, nonce, data);
+    hex::encode(auth::hmac_sha256(api_secret.as_bytes(), msg.as_bytes()))
+}
+
+impl<'a> PrivateWSClient<'a> for DeribitWSClient<'a> {
+    fn new_private(
+        on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>,
+        credentials: Credentials,
+        url: Option<&str>,
+    ) -> Result<Self, WsError> {
+        let ws_client = DeribitWSClient::new(on_msg, url);
+        ws_client.client.set_auth(Auth::Login(Box::new(move || {
+            let timestamp = auth::current_millis();
+            let nonce = format!("{:08x}", rand::thread_rng().gen::<u32>());
+            let signature = sign(&credentials.api_secret, timestamp, &nonce, "");
+            vec![format!(
+                r#"{{"method": "public/auth", "params": {{"grant_type": "client_signature", "client_id": "{}", "timestamp": {}, "signature": "{}", "nonce": "{}", "data": ""}}}}"#,
+                credentials.api_key, timestamp, signature, nonce
+            )]
+        })));
+        Ok(ws_client)
+    }
+
+    fn subscribe_orders(&self, symbols: &[String]) {
+        let channels: Vec<String> = symbols
+            .iter()
+            .map(|symbol| format!("user.orders.{}.raw", symbol))
+            .collect();
+        self.client.subscribe(&channels);
+    }
+
+    fn subscribe_fills(&self, symbols: &[String]) {
+        let channels: Vec<String> = symbols
+            .iter()
+            .map(|symbol| format!("user.trades.{}.raw", symbol))
+            .collect();
+        self.client.subscribe(&channels);
+    }
+
+    fn subscribe_balances(&self, currencies: &[String]) {
+        let channels: Vec<String> = currencies
+            .iter()
+            .map(|currency| format!("user.portfolio.{}", currency))
+            .collect();
+        self.client.subscribe(&channels);
+    }
+}
 
 define_client!(
     DeribitWSClient,
@@ -172,6 +271,7 @@ define_client!(
     None,
     None
 );
+define_async_client!(DeribitAsyncWSClient, DeribitWSClient);
 
 #[cfg(test)]
 mod tests {
@@ -209,4 +309,33 @@ mod tests {
             commands[1]
         );
     }
+
+    #[test]
+    fn test_private_channel() {
+        let commands = super::channels_to_commands(
+            &[
+                "trades.BTC-PERPETUAL.raw".to_string(),
+                "user.trades.BTC-PERPETUAL.raw".to_string(),
+            ],
+            true,
+        );
+        assert_eq!(3, commands.len());
+        assert_eq!(
+            r#"{"method": "public/subscribe", "params": {"channels": ["trades.BTC-PERPETUAL.raw"]}}"#,
+            commands[0]
+        );
+        assert_eq!(
+            r#"{"method": "private/subscribe", "params": {"channels": ["user.trades.BTC-PERPETUAL.raw"]}}"#,
+            commands[1]
+        );
+    }
+
+    #[test]
+    fn test_sign() {
+        // https://docs.deribit.com/#authentication
+        assert_eq!(
+            "56590594f97921b09b18f166befe0d1319b198bbcdad7ca73382de2f88fe9aa1",
+            super::sign("AMANDASECRECT", 1576074319000, "1iqt2wls", "")
+        );
+    }
 }
@@ -0,0 +1,121 @@
+use super::proxy::Proxy;
+use log::*;
+use std::{
+    io,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+// Builds the url to connect to right before each connection, e.g., KuCoin
+// urls carry a connect token which expires, so every connection applies for
+// a new one.
+pub(super) type UrlProvider = fn() -> io::Result<String>;
+
+// An ordered list of websocket urls of an exchange, and the proxy to reach
+// them.
+//
+// A client sticks to the current endpoint as long as it is healthy, and moves
+// on to the next one after a connect or read failure, wrapping around at the
+// end of the list.
+pub(super) struct Endpoints {
+    urls: Vec<String>,
+    current: AtomicUsize, // index of the endpoint in use
+    proxy: Option<Proxy>, // None means environment variables, see proxy_for()
+    // Some if the url to connect to is built lazily, `urls` then only
+    // identify the endpoint in logs and events
+    provider: Option<UrlProvider>,
+}
+
+impl Endpoints {
+    pub fn new(urls: &[&str], proxy: Option<Proxy>) -> Self {
+        assert!(!urls.is_empty(), "At least one endpoint is required");
+        Endpoints {
+            urls: urls.iter().map(|url| url.to_string()).collect(),
+            current: AtomicUsize::new(0),
+            proxy,
+            provider: None,
+        }
+    }
+
+    pub fn with_provider(mut self, provider: UrlProvider) -> Self {
+        self.provider = Some(provider);
+        self
+    }
+
+    // The endpoint in use
+    pub fn current(&self) -> &str {
+        &self.urls[self.current.load(Ordering::Acquire)]
+    }
+
+    pub fn provider(&self) -> Option<UrlProvider> {
+        self.provider
+    }
+
+    // The url to connect to, built by the provider if any.
+    pub fn connect_url(&self) -> io::Result<String> {
+        match self.provider {
+            Some(provider) => provider(),
+            None => Ok(self.current().to_string()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.urls.len()
+    }
+
+    pub fn proxy(&self) -> Option<&Proxy> {
+        self.proxy.as_ref()
+    }
+
+    // Moves on to the next endpoint, returns the new one.
+    pub fn failover(&self) -> &str {
+        if self.urls.len() == 1 {
+            return self.current();
+        }
+        let prev = self.current.load(Ordering::Acquire);
+        let next = (prev + 1) % self.urls.len();
+        self.current.store(next, Ordering::Release);
+        warn!(
+            "Failing over from {} to {}",
+            self.urls[prev], self.urls[next]
+        );
+        &self.urls[next]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Endpoints;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_failover() {
+        let endpoints = Endpoints::new(&["wss://a", "wss://b", "wss://c"], None);
+        assert_eq!("wss://a", endpoints.current());
+        assert_eq!("wss://b", endpoints.failover());
+        assert_eq!("wss://b", endpoints.current()); // sticky
+        assert_eq!("wss://c", endpoints.failover());
+        assert_eq!("wss://a", endpoints.failover());
+    }
+
+    #[test]
+    fn test_single_endpoint() {
+        let endpoints = Endpoints::new(&["wss://a"], None);
+        assert_eq!("wss://a", endpoints.failover());
+        assert_eq!("wss://a", endpoints.current());
+    }
+
+    #[test]
+    fn test_provider() {
+        static TOKENS: AtomicUsize = AtomicUsize::new(0);
+        let endpoints = Endpoints::new(&["wss://a"], None);
+        assert_eq!("wss://a", endpoints.connect_url().unwrap());
+        // a new token for every connection
+        let endpoints = endpoints.with_provider(|| {
+            let token = TOKENS.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok(format!("wss://a?token={}", token))
+        });
+        assert_eq!("wss://a?token=1", endpoints.connect_url().unwrap());
+        assert_eq!("wss://a?token=2", endpoints.connect_url().unwrap());
+        assert_eq!("wss://a", endpoints.current());
+    }
+}
@@ -0,0 +1,80 @@
+use crate::Channel;
+
+use std::fmt;
+
+/// Errors returned by `WSClient::subscribe_channels()` and other subscribe APIs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WsError {
+    /// The exchange, or this market of the exchange, doesn't have the channel.
+    Unsupported { exchange: String, channel: Channel },
+    /// The symbol is empty, malformed or not listed by the exchange.
+    InvalidSymbol { exchange: String, symbol: String },
+    /// The exchange doesn't have candlesticks of this interval, in seconds.
+    InvalidInterval { exchange: String, interval: u32 },
+    /// Failed to authenticate with the credentials, e.g., to create a token.
+    Auth { exchange: String, reason: String },
+    /// An I/O error, such as a failure to read or write a file.
+    Io(String),
+}
+
+impl WsError {
+    pub(super) fn unsupported(exchange: &str, channel: &Channel) -> WsError {
+        WsError::Unsupported {
+            exchange: exchange.to_string(),
+            channel: channel.clone(),
+        }
+    }
+
+    pub(super) fn invalid_symbol(exchange: &str, symbol: &str) -> WsError {
+        WsError::InvalidSymbol {
+            exchange: exchange.to_string(),
+            symbol: symbol.to_string(),
+        }
+    }
+
+    pub(super) fn invalid_interval(exchange: &str, interval: u32) -> WsError {
+        WsError::InvalidInterval {
+            exchange: exchange.to_string(),
+            interval,
+        }
+    }
+
+    pub(super) fn auth(exchange: &str, reason: &str) -> WsError {
+        WsError::Auth {
+            exchange: exchange.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for WsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WsError::Unsupported { exchange, channel } => {
+                write!(f, "{} does NOT support {:?}", exchange, channel)
+            }
+            WsError::InvalidSymbol { exchange, symbol } => {
+                write!(f, "Invalid symbol {:?} of {}", symbol, exchange)
+            }
+            WsError::InvalidInterval { exchange, interval } => {
+                write!(
+                    f,
+                    "{} does NOT have candlesticks of {}s",
+                    exchange, interval
+                )
+            }
+            WsError::Auth { exchange, reason } => {
+                write!(f, "Failed to authenticate with {}, {}", exchange, reason)
+            }
+            WsError::Io(err) => write!(f, "I/O error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for WsError {}
+
+impl From<std::io::Error> for WsError {
+    fn from(err: std::io::Error) -> Self {
+        WsError::Io(err.to_string())
+    }
+}
@@ -0,0 +1,70 @@
+use super::reconnect::ReconnectEvent;
+use std::sync::{Arc, Mutex};
+
+/// Connection and subscription events of a client, passed to the `events`
+/// callback of `WSClientOptions`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WsEvent {
+    /// Connected to the server for the first time
+    Connected {
+        /// The websocket url connected to
+        url: String,
+    },
+    /// The server confirmed the subscription of a raw channel
+    Subscribed {
+        /// The confirmed raw channel
+        channel: String,
+    },
+    /// The server rejected a subscription, for example, the symbol doesn't
+    /// exist or there are too many subscriptions
+    SubscribeFailed {
+        /// The rejected raw channel, None if the exchange doesn't tell which one
+        channel: Option<String>,
+        /// Exchange specific error code
+        code: Option<i64>,
+        /// Error message from the exchange
+        reason: String,
+    },
+    /// The connection dropped, the client is going to reconnect
+    Disconnected {
+        /// Why the connection is considered dropped
+        reason: String,
+    },
+    /// Reconnected and resubscribed all channels
+    Reconnected(ReconnectEvent),
+}
+
+// User defined callback to process events
+pub(super) type EventCallback<'a> = Arc<Mutex<dyn FnMut(WsEvent) + 'a + Send>>;
+
+// Passes an event to the callback, if any.
+pub(super) fn notify(on_event: &Option<EventCallback<'_>>, event: WsEvent) {
+    if let Some(on_event) = on_event {
+        (on_event.lock().unwrap())(event);
+    }
+}
+
+// One SubscribeFailed event per rejected channel, or a single event without
+// channel if the exchange doesn't identify channels in its error message.
+pub(super) fn subscribe_failed(
+    channels: &[String],
+    code: Option<i64>,
+    reason: &str,
+) -> Vec<WsEvent> {
+    if channels.is_empty() {
+        vec![WsEvent::SubscribeFailed {
+            channel: None,
+            code,
+            reason: reason.to_string(),
+        }]
+    } else {
+        channels
+            .iter()
+            .map(|channel| WsEvent::SubscribeFailed {
+                channel: Some(channel.clone()),
+                code,
+                reason: reason.to_string(),
+            })
+            .collect()
+    }
+}
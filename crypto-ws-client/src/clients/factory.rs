@@ -0,0 +1,125 @@
+use crate::*;
+
+use crypto_market_type::MarketType;
+use std::sync::{Arc, Mutex};
+
+/// Creates a client of an exchange and market type picked at runtime.
+///
+/// # Arguments
+///
+/// * `exchange` - The exchange name, e.g., `binance`, `huobi`, `okex`
+/// * `market_type` - The market type, some exchanges share one client among
+///   several market types
+/// * `on_msg` - A callback function to process original JSON messages
+///
+/// # Panics
+///
+/// Panics if the exchange is unknown, or has no websocket client of the
+/// market type.
+///
+/// ## Example
+///
+/// ```no_run
+/// use crypto_ws_client::{create_client, MarketType};
+/// use std::sync::{Arc, Mutex};
+///
+/// let ws_client = create_client("binance", MarketType::LinearSwap, Arc::new(Mutex::new(|msg| println!("{}", msg))));
+/// ws_client.subscribe_trade(&["BTCUSDT".to_string()]).unwrap();
+/// ws_client.run(Some(60));
+/// ```
+pub fn create_client<'a>(
+    exchange: &str,
+    market_type: MarketType,
+    on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>,
+) -> Box<dyn WSClient<'a> + Send + 'a> {
+    match (exchange, market_type) {
+        ("binance", MarketType::Spot) => Box::new(BinanceSpotWSClient::new(on_msg, None)),
+        ("binance", MarketType::InverseFuture | MarketType::InverseSwap) => {
+            Box::new(BinanceInverseWSClient::new(on_msg, None))
+        }
+        ("binance", MarketType::LinearFuture | MarketType::LinearSwap) => {
+            Box::new(BinanceLinearWSClient::new(on_msg, None))
+        }
+        ("binance", MarketType::EuropeanOption) => {
+            Box::new(BinanceOptionWSClient::new(on_msg, None))
+        }
+        ("bitfinex", MarketType::Spot | MarketType::LinearSwap) => {
+            Box::new(BitfinexWSClient::new(on_msg, None))
+        }
+        ("bitget", MarketType::InverseSwap | MarketType::LinearSwap) => {
+            Box::new(BitgetSwapWSClient::new(on_msg, None))
+        }
+        ("bithumb", MarketType::Spot) => Box::new(BithumbWSClient::new(on_msg, None)),
+        (
+            "bitmex",
+            MarketType::InverseSwap
+            | MarketType::QuantoSwap
+            | MarketType::LinearFuture
+            | MarketType::InverseFuture
+            | MarketType::QuantoFuture,
+        ) => Box::new(BitmexWSClient::new(on_msg, None)),
+        ("bitstamp", MarketType::Spot) => Box::new(BitstampWSClient::new(on_msg, None)),
+        ("bitz", MarketType::Spot) => Box::new(BitzSpotWSClient::new(on_msg, None)),
+        ("bybit", MarketType::Spot) => Box::new(BybitSpotWSClient::new(on_msg, None)),
+        ("bybit", MarketType::InverseFuture) => {
+            Box::new(BybitInverseFutureWSClient::new(on_msg, None))
+        }
+        ("bybit", MarketType::InverseSwap) => Box::new(BybitInverseSwapWSClient::new(on_msg, None)),
+        ("bybit", MarketType::LinearSwap) => Box::new(BybitLinearSwapWSClient::new(on_msg, None)),
+        ("coinbase_pro", MarketType::Spot) => Box::new(CoinbaseProWSClient::new(on_msg, None)),
+        (
+            "deribit",
+            MarketType::InverseFuture | MarketType::InverseSwap | MarketType::EuropeanOption,
+        ) => Box::new(DeribitWSClient::new(on_msg, None)),
+        (
+            "ftx",
+            MarketType::Spot
+            | MarketType::LinearFuture
+            | MarketType::LinearSwap
+            | MarketType::Move
+            | MarketType::BVOL,
+        ) => Box::new(FtxWSClient::new(on_msg, None)),
+        ("gate", MarketType::Spot) => Box::new(GateSpotWSClient::new(on_msg, None)),
+        ("gate", MarketType::LinearFuture) => Box::new(GateLinearFutureWSClient::new(on_msg, None)),
+        ("gate", MarketType::InverseSwap) => Box::new(GateInverseSwapWSClient::new(on_msg, None)),
+        ("gate", MarketType::LinearSwap) => Box::new(GateLinearSwapWSClient::new(on_msg, None)),
+        ("gemini", MarketType::Spot) => Box::new(GeminiWSClient::new(on_msg, None)),
+        ("huobi", MarketType::Spot) => Box::new(HuobiSpotWSClient::new(on_msg, None)),
+        ("huobi", MarketType::InverseFuture) => Box::new(HuobiFutureWSClient::new(on_msg, None)),
+        ("huobi", MarketType::InverseSwap) => Box::new(HuobiInverseSwapWSClient::new(on_msg, None)),
+        ("huobi", MarketType::LinearSwap) => Box::new(HuobiLinearSwapWSClient::new(on_msg, None)),
+        ("huobi", MarketType::EuropeanOption) => Box::new(HuobiOptionWSClient::new(on_msg, None)),
+        ("kraken", MarketType::Spot) => Box::new(KrakenWSClient::new(on_msg, None)),
+        ("kraken", MarketType::InverseFuture | MarketType::InverseSwap) => {
+            Box::new(KrakenFuturesWSClient::new(on_msg, None))
+        }
+        ("kucoin", MarketType::Spot) => Box::new(KuCoinSpotWSClient::new(on_msg, None)),
+        (
+            "kucoin",
+            MarketType::InverseSwap | MarketType::LinearSwap | MarketType::InverseFuture,
+        ) => Box::new(KuCoinSwapWSClient::new(on_msg, None)),
+        ("mxc", MarketType::Spot) => Box::new(MxcSpotWSClient::new(on_msg, None)),
+        ("mxc", MarketType::LinearSwap | MarketType::InverseSwap) => {
+            Box::new(MxcSwapWSClient::new(on_msg, None))
+        }
+        (
+            "okex",
+            MarketType::Spot
+            | MarketType::LinearFuture
+            | MarketType::InverseFuture
+            | MarketType::LinearSwap
+            | MarketType::InverseSwap
+            | MarketType::EuropeanOption,
+        ) => Box::new(OkexWSClient::new(on_msg, None)),
+        ("poloniex", MarketType::Spot) => Box::new(PoloniexWSClient::new(on_msg, None)),
+        ("upbit", MarketType::Spot) => Box::new(UpbitWSClient::new(on_msg, None)),
+        ("zbg", MarketType::Spot) => Box::new(ZbgSpotWSClient::new(on_msg, None)),
+        ("zbg", MarketType::InverseSwap | MarketType::LinearSwap) => {
+            Box::new(ZbgSwapWSClient::new(on_msg, None))
+        }
+        _ => panic!(
+            "{} does NOT have a websocket client of the {} market type",
+            exchange, market_type
+        ),
+    }
+}
@@ -1,12 +1,13 @@
-use crate::WSClient;
+use crate::{Channel, ChannelKind, Credentials, PrivateWSClient, WSClient, WsError};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
+use super::ChannelTranslator;
 use super::{
+    auth::{self, Auth},
     utils::CHANNEL_PAIR_DELIMITER,
     ws_client_internal::{MiscMessage, WSClientInternal},
 };
-use super::{Candlestick, OrderBook, OrderBookSnapshot, Ticker, Trade, BBO};
 
 use log::*;
 use serde_json::Value;
@@ -35,19 +36,22 @@ fn channels_to_commands(channels: &[String], subscribe: bool) -> Vec<String> {
         .collect();
 
     for s in channels.iter().filter(|ch| !ch.starts_with('{')) {
+        let op = if subscribe {
+            "subscribe"
+        } else {
+            "unsubscribe"
+        };
         let v: Vec<&str> = s.split(CHANNEL_PAIR_DELIMITER).collect();
-        let channel = v[0];
-        let pair = v[1];
-        all_commands.append(&mut vec![format!(
-            r#"{{"op":"{}","channel":"{}","market":"{}"}}"#,
-            if subscribe {
-                "subscribe"
-            } else {
-                "unsubscribe"
-            },
-            channel,
-            pair
-        )])
+        let command = if v.len() == 1 {
+            // private channels have no market, e.g., fills and orders
+            format!(r#"{{"op":"{}","channel":"{}"}}"#, op, v[0])
+        } else {
+            format!(
+                r#"{{"op":"{}","channel":"{}","market":"{}"}}"#,
+                op, v[0], v[1]
+            )
+        };
+        all_commands.push(command);
     }
 
     all_commands
@@ -60,14 +64,32 @@ fn on_misc_msg(msg: &str) -> MiscMessage {
     match msg_type {
         // see https://docs.ftx.com/#response-format
         "pong" => MiscMessage::Pong,
-        "subscribed" | "unsubscribed" | "info" => {
+        "subscribed" => {
+            info!("Received {} from {}", msg, EXCHANGE_NAME);
+            // {"type":"subscribed","channel":"trades","market":"BTC-PERP"}
+            let channel = obj.get("channel").unwrap().as_str().unwrap();
+            match obj.get("market").and_then(|x| x.as_str()) {
+                Some(market) => MiscMessage::Subscribed(vec![to_raw_channel(channel, market)]),
+                None => MiscMessage::Subscribed(vec![channel.to_string()]),
+            }
+        }
+        "unsubscribed" | "info" => {
             info!("Received {} from {}", msg, EXCHANGE_NAME);
             MiscMessage::Misc
         }
         "partial" | "update" => MiscMessage::Normal,
         "error" => {
+            // {"type":"error","code":404,"msg":"No such market: BTC-PERP1"}
             error!("Received {} from {}", msg, EXCHANGE_NAME);
-            panic!("Received {} from {}", msg, EXCHANGE_NAME);
+            MiscMessage::Rejected {
+                channels: Vec::new(),
+                code: obj.get("code").and_then(|x| x.as_i64()),
+                reason: obj
+                    .get("msg")
+                    .and_then(|x| x.as_str())
+                    .unwrap_or(msg)
+                    .to_string(),
+            }
         }
         _ => {
             warn!("Received {} from {}", msg, EXCHANGE_NAME);
@@ -80,28 +102,63 @@ fn to_raw_channel(channel: &str, pair: &str) -> String {
     format!("{}{}{}", channel, CHANNEL_PAIR_DELIMITER, pair)
 }
 
-#[rustfmt::skip]
-impl_trait!(Trade, FtxWSClient, subscribe_trade, "trades", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(BBO, FtxWSClient, subscribe_bbo, "ticker", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(OrderBook, FtxWSClient, subscribe_orderbook, "orderbook", to_raw_channel);
+// FTX does NOT have orderbook snapshot, ticker and candlestick channels, nor
+// channels of derivatives, such as mark price and open interest
+impl<'a> ChannelTranslator for FtxWSClient<'a> {
+    fn translate(&self, channel: &Channel) -> Result<String, WsError> {
+        match channel {
+            Channel::Trade(pair) => Some(to_raw_channel("trades", pair)),
+            Channel::BBO(pair) => Some(to_raw_channel("ticker", pair)),
+            Channel::L2 {
+                pair,
+                depth: None,
+                speed: None,
+            } => Some(to_raw_channel("orderbook", pair)),
+            _ => None,
+        }
+        .ok_or_else(|| WsError::unsupported(EXCHANGE_NAME, channel))
+    }
 
-impl<'a> OrderBookSnapshot for FtxWSClient<'a> {
-    fn subscribe_orderbook_snapshot(&self, _pairs: &[String]) {
-        panic!("FTX does NOT have orderbook snapshot channel");
+    fn capabilities(&self) -> Vec<ChannelKind> {
+        vec![ChannelKind::Trade, ChannelKind::L2, ChannelKind::BBO]
     }
 }
 
-impl<'a> Ticker for FtxWSClient<'a> {
-    fn subscribe_ticker(&self, _pairs: &[String]) {
-        panic!("FTX does NOT have ticker channel");
-    }
+// see https://docs.ftx.com/#authentication
+fn sign(api_secret: &str, payload: &str) -> String {
+    hex::encode(auth::hmac_sha256(api_secret.as_bytes(), payload.as_bytes()))
 }
 
-impl<'a> Candlestick for FtxWSClient<'a> {
-    fn subscribe_candlestick(&self, _pairs: &[String], _interval: u32) {
-        panic!("FTX does NOT have candlestick channel");
+impl<'a> PrivateWSClient<'a> for FtxWSClient<'a> {
+    fn new_private(
+        on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>,
+        credentials: Credentials,
+        url: Option<&str>,
+    ) -> Result<Self, WsError> {
+        let ws_client = FtxWSClient::new(on_msg, url);
+        ws_client.client.set_auth(Auth::Login(Box::new(move || {
+            let time = auth::current_millis();
+            let signature = sign(&credentials.api_secret, &format!("{}websocket_login", time));
+            vec![format!(
+                r#"{{"op":"login","args":{{"key":"{}","sign":"{}","time":{}}}}}"#,
+                credentials.api_key, signature, time
+            )]
+        })));
+        Ok(ws_client)
+    }
+
+    // FTX pushes orders of all markets
+    fn subscribe_orders(&self, _symbols: &[String]) {
+        self.client.subscribe(&["orders".to_string()]);
+    }
+
+    // FTX pushes fills of all markets
+    fn subscribe_fills(&self, _symbols: &[String]) {
+        self.client.subscribe(&["fills".to_string()]);
+    }
+
+    fn subscribe_balances(&self, _currencies: &[String]) {
+        panic!("FTX does NOT have balance channel");
     }
 }
 
@@ -114,3 +171,26 @@ define_client!(
     Some(CLIENT_PING_INTERVAL_AND_MSG),
     None
 );
+define_async_client!(FtxAsyncWSClient, FtxWSClient);
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_private_channel() {
+        let commands = super::channels_to_commands(&["fills".to_string()], true);
+        assert_eq!(1, commands.len());
+        assert_eq!(r#"{"op":"subscribe","channel":"fills"}"#, commands[0]);
+    }
+
+    #[test]
+    fn test_sign() {
+        // https://docs.ftx.com/#authentication
+        assert_eq!(
+            "dbc62ec300b2624c580611858d94f2332ac636bb86eccfa1167a7777c496ee6f",
+            super::sign(
+                "T4lPid48QtjNxjLUFOcUZghD7CUJ7sTVsfuvQZF2",
+                "1588591511721GET/api/markets"
+            )
+        );
+    }
+}
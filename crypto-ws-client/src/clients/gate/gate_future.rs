@@ -1,11 +1,11 @@
-use crate::WSClient;
+use crate::{Channel, ChannelKind, WSClient, WsError};
 use std::sync::{Arc, Mutex};
 
 use super::super::ws_client_internal::WSClientInternal;
-use super::super::{Candlestick, OrderBook, OrderBookSnapshot, Ticker, Trade, BBO};
+use super::super::ChannelTranslator;
 use super::utils::{
-    channels_to_commands, on_misc_msg, to_candlestick_raw_channel, to_raw_channel,
-    CLIENT_PING_INTERVAL_AND_MSG, EXCHANGE_NAME,
+    capabilities, channels_to_commands, on_misc_msg, translate, CLIENT_PING_INTERVAL_AND_MSG,
+    EXCHANGE_NAME,
 };
 
 const LINEAR_FUTURE_WEBSOCKET_URL: &str = "wss://fx-ws.gateio.ws/v4/ws/delivery/usdt";
@@ -18,27 +18,16 @@ pub struct GateLinearFutureWSClient<'a> {
     client: WSClientInternal<'a>,
 }
 
-#[rustfmt::skip]
-impl_trait!(Trade, GateLinearFutureWSClient, subscribe_trade, "trades", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(OrderBook, GateLinearFutureWSClient, subscribe_orderbook, "order_book", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(Ticker, GateLinearFutureWSClient, subscribe_ticker, "tickers", to_raw_channel);
-
-impl<'a> BBO for GateLinearFutureWSClient<'a> {
-    fn subscribe_bbo(&self, _pairs: &[String]) {
-        panic!("Gate does NOT have BBO channel");
+impl<'a> ChannelTranslator for GateLinearFutureWSClient<'a> {
+    fn translate(&self, channel: &Channel) -> Result<String, WsError> {
+        translate(channel)
     }
-}
 
-impl<'a> OrderBookSnapshot for GateLinearFutureWSClient<'a> {
-    fn subscribe_orderbook_snapshot(&self, _pairs: &[String]) {
-        panic!("Gate does NOT have orderbook snapshot channel");
+    fn capabilities(&self) -> Vec<ChannelKind> {
+        capabilities()
     }
 }
 
-impl_candlestick!(GateLinearFutureWSClient);
-
 define_client!(
     GateLinearFutureWSClient,
     EXCHANGE_NAME,
@@ -48,3 +37,4 @@ define_client!(
     Some(CLIENT_PING_INTERVAL_AND_MSG),
     None
 );
+define_async_client!(GateLinearFutureAsyncWSClient, GateLinearFutureWSClient);
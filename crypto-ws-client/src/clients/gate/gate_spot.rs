@@ -1,9 +1,10 @@
-use crate::{clients::utils::CHANNEL_PAIR_DELIMITER, WSClient};
+use crate::{clients::utils::CHANNEL_PAIR_DELIMITER, Channel, ChannelKind, WSClient, WsError};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
+use super::super::request;
 use super::super::ws_client_internal::{MiscMessage, WSClientInternal};
-use super::super::{Candlestick, OrderBook, OrderBookSnapshot, Ticker, Trade, BBO};
+use super::super::ChannelTranslator;
 
 use log::*;
 use serde_json::Value;
@@ -35,8 +36,13 @@ fn channel_pairs_to_command(channel: &str, pairs: &[String], subscribe: bool) ->
         })
         .collect();
 
+    let raw_channels: Vec<String> = pairs
+        .iter()
+        .map(|pair| to_raw_channel(channel, pair))
+        .collect();
     format!(
-        r#"{{"id":9527, "method":"{}.{}", "params":{}}}"#,
+        r#"{{"id":{}, "method":"{}.{}", "params":{}}}"#,
+        request::register(&raw_channels, subscribe),
         channel,
         if subscribe {
             "subscribe"
@@ -47,11 +53,32 @@ fn channel_pairs_to_command(channel: &str, pairs: &[String], subscribe: bool) ->
     )
 }
 
+// JSON raw channels are subscribe commands, e.g., candlestick channels, an id
+// is assigned to each command, and unsubscribe commands call the unsubscribe
+// method instead.
+fn json_channel_to_command(channel: &str, subscribe: bool) -> String {
+    let mut obj = match serde_json::from_str::<HashMap<String, Value>>(channel) {
+        Ok(obj) => obj,
+        Err(_) => return channel.to_string(),
+    };
+    if let Some(method) = obj.get("method").and_then(|x| x.as_str()) {
+        if !subscribe {
+            let method = method.replace(".subscribe", ".unsubscribe");
+            obj.insert("method".to_string(), Value::from(method));
+        }
+        obj.insert(
+            "id".to_string(),
+            Value::from(request::register(&[channel.to_string()], subscribe)),
+        );
+    }
+    serde_json::to_string(&obj).unwrap()
+}
+
 fn channels_to_commands(channels: &[String], subscribe: bool) -> Vec<String> {
     let mut all_commands: Vec<String> = channels
         .iter()
         .filter(|ch| ch.starts_with('{'))
-        .map(|s| s.to_string())
+        .map(|s| json_channel_to_command(s, subscribe))
         .collect();
 
     let mut channel_pairs = HashMap::<String, Vec<String>>::new();
@@ -88,15 +115,16 @@ fn on_misc_msg(msg: &str) -> MiscMessage {
             }
         }
     };
+    let id = obj.get("id").and_then(|x| x.as_u64());
     if !error.is_null() {
-        let err = error.as_object().unwrap();
+        // {"error":{"code":1,"message":"invalid argument"},"result":null,"id":9527}
         // see https://www.gatecn.io/docs/websocket/index.html#error
-        let code = err.get("code").unwrap().as_i64().unwrap();
-        match code {
-            1 | 4 => panic!("Received {} from {}", msg, EXCHANGE_NAME),
-            _ => error!("Received {} from {}", msg, EXCHANGE_NAME),
-        }
-        MiscMessage::Misc
+        error!("Received {} from {}", msg, EXCHANGE_NAME);
+        request::on_error(
+            id,
+            error.get("code").and_then(|x| x.as_i64()),
+            error.get("message").and_then(|x| x.as_str()).unwrap_or(msg),
+        )
     } else if obj.contains_key("method")
         && obj
             .get("method")
@@ -113,6 +141,9 @@ fn on_misc_msg(msg: &str) -> MiscMessage {
             return MiscMessage::Pong;
         } else if result == serde_json::json!({"status": "success"}) {
             debug!("Received {} from {}", msg, EXCHANGE_NAME);
+            if let Some(id) = id {
+                return request::on_ack(id);
+            }
         } else {
             warn!("Received {} from {}", msg, EXCHANGE_NAME);
         }
@@ -127,47 +158,51 @@ fn to_raw_channel(channel: &str, pair: &str) -> String {
     format!("{}{}{}", channel, CHANNEL_PAIR_DELIMITER, pair)
 }
 
-#[rustfmt::skip]
-impl_trait!(Trade, GateSpotWSClient, subscribe_trade, "trades", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(OrderBook, GateSpotWSClient, subscribe_orderbook, "depth", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(Ticker, GateSpotWSClient, subscribe_ticker, "ticker", to_raw_channel);
-
-impl<'a> BBO for GateSpotWSClient<'a> {
-    fn subscribe_bbo(&self, _pairs: &[String]) {
-        panic!("Bitz does NOT have BBO channel");
-    }
-}
-
-impl<'a> OrderBookSnapshot for GateSpotWSClient<'a> {
-    fn subscribe_orderbook_snapshot(&self, _pairs: &[String]) {
-        panic!("Bitz does NOT have orderbook snapshot channel");
-    }
-}
-
-fn to_candlestick_raw_channel(pair: &str, interval: u32) -> String {
+fn to_candlestick_raw_channel(pair: &str, interval: u32) -> Option<String> {
     let valid_set: Vec<u32> = vec![
         10, 60, 300, 900, 1800, 3600, 14400, 28800, 86400, 604800, 2592000,
     ];
     if !valid_set.contains(&interval) {
-        let joined = valid_set
-            .into_iter()
-            .map(|x| x.to_string())
-            .collect::<Vec<String>>()
-            .join(",");
-        panic!("Gate available intervals {}", joined);
+        return None;
     }
 
     let arr = serde_json::json!([pair, interval]);
 
-    format!(
+    Some(format!(
         r#"{{"id":9527, "method":"kline.subscribe", "params":{}}}"#,
         serde_json::to_string(&arr).unwrap(),
-    )
+    ))
 }
 
-impl_candlestick!(GateSpotWSClient);
+// Gate does NOT have BBO and orderbook snapshot channels
+impl<'a> ChannelTranslator for GateSpotWSClient<'a> {
+    fn translate(&self, channel: &Channel) -> Result<String, WsError> {
+        match channel {
+            Channel::Trade(pair) => Some(to_raw_channel("trades", pair)),
+            Channel::L2 {
+                pair,
+                depth: None,
+                speed: None,
+            } => Some(to_raw_channel("depth", pair)),
+            Channel::Ticker(pair) => Some(to_raw_channel("ticker", pair)),
+            Channel::Kline { pair, interval } => {
+                return to_candlestick_raw_channel(pair, *interval)
+                    .ok_or_else(|| WsError::invalid_interval(EXCHANGE_NAME, *interval));
+            }
+            _ => None,
+        }
+        .ok_or_else(|| WsError::unsupported(EXCHANGE_NAME, channel))
+    }
+
+    fn capabilities(&self) -> Vec<ChannelKind> {
+        vec![
+            ChannelKind::Trade,
+            ChannelKind::L2,
+            ChannelKind::Ticker,
+            ChannelKind::Kline,
+        ]
+    }
+}
 
 define_client!(
     GateSpotWSClient,
@@ -178,3 +213,4 @@ define_client!(
     Some(CLIENT_PING_INTERVAL_AND_MSG),
     None
 );
+define_async_client!(GateSpotAsyncWSClient, GateSpotWSClient);
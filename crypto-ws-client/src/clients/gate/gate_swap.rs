@@ -1,11 +1,11 @@
-use crate::WSClient;
+use crate::{Channel, ChannelKind, WSClient, WsError};
 use std::sync::{Arc, Mutex};
 
 use super::super::ws_client_internal::WSClientInternal;
-use super::super::{Candlestick, OrderBook, OrderBookSnapshot, Ticker, Trade, BBO};
+use super::super::ChannelTranslator;
 use super::utils::{
-    channels_to_commands, on_misc_msg, to_candlestick_raw_channel, to_raw_channel,
-    CLIENT_PING_INTERVAL_AND_MSG, EXCHANGE_NAME,
+    capabilities, channels_to_commands, on_misc_msg, translate, CLIENT_PING_INTERVAL_AND_MSG,
+    EXCHANGE_NAME,
 };
 
 const INVERSE_SWAP_WEBSOCKET_URL: &str = "wss://fx-ws.gateio.ws/v4/ws/btc";
@@ -27,44 +27,26 @@ pub struct GateLinearSwapWSClient<'a> {
     client: WSClientInternal<'a>,
 }
 
-#[rustfmt::skip]
-impl_trait!(Trade, GateInverseSwapWSClient, subscribe_trade, "trades", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(OrderBook, GateInverseSwapWSClient, subscribe_orderbook, "order_book", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(Ticker, GateInverseSwapWSClient, subscribe_ticker, "tickers", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(Trade, GateLinearSwapWSClient, subscribe_trade, "trades", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(OrderBook, GateLinearSwapWSClient, subscribe_orderbook, "order_book", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(Ticker, GateLinearSwapWSClient, subscribe_ticker, "tickers", to_raw_channel);
-
-impl<'a> BBO for GateInverseSwapWSClient<'a> {
-    fn subscribe_bbo(&self, _pairs: &[String]) {
-        panic!("Gate does NOT have BBO channel");
+impl<'a> ChannelTranslator for GateInverseSwapWSClient<'a> {
+    fn translate(&self, channel: &Channel) -> Result<String, WsError> {
+        translate(channel)
     }
-}
-impl<'a> BBO for GateLinearSwapWSClient<'a> {
-    fn subscribe_bbo(&self, _pairs: &[String]) {
-        panic!("Gate does NOT have BBO channel");
+
+    fn capabilities(&self) -> Vec<ChannelKind> {
+        capabilities()
     }
 }
 
-impl<'a> OrderBookSnapshot for GateInverseSwapWSClient<'a> {
-    fn subscribe_orderbook_snapshot(&self, _pairs: &[String]) {
-        panic!("Gate does NOT have orderbook snapshot channel");
+impl<'a> ChannelTranslator for GateLinearSwapWSClient<'a> {
+    fn translate(&self, channel: &Channel) -> Result<String, WsError> {
+        translate(channel)
     }
-}
-impl<'a> OrderBookSnapshot for GateLinearSwapWSClient<'a> {
-    fn subscribe_orderbook_snapshot(&self, _pairs: &[String]) {
-        panic!("Gate does NOT have orderbook snapshot channel");
+
+    fn capabilities(&self) -> Vec<ChannelKind> {
+        capabilities()
     }
 }
 
-impl_candlestick!(GateInverseSwapWSClient);
-impl_candlestick!(GateLinearSwapWSClient);
-
 define_client!(
     GateInverseSwapWSClient,
     EXCHANGE_NAME,
@@ -74,6 +56,7 @@ define_client!(
     Some(CLIENT_PING_INTERVAL_AND_MSG),
     None
 );
+define_async_client!(GateInverseSwapAsyncWSClient, GateInverseSwapWSClient);
 
 define_client!(
     GateLinearSwapWSClient,
@@ -84,3 +67,4 @@ define_client!(
     Some(CLIENT_PING_INTERVAL_AND_MSG),
     None
 );
+define_async_client!(GateLinearSwapAsyncWSClient, GateLinearSwapWSClient);
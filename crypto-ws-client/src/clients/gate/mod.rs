@@ -3,7 +3,7 @@ mod gate_spot;
 mod gate_swap;
 mod utils;
 
-pub use gate_future::GateLinearFutureWSClient;
-pub use gate_spot::GateSpotWSClient;
-pub use gate_swap::GateInverseSwapWSClient;
-pub use gate_swap::GateLinearSwapWSClient;
+pub use gate_future::{GateLinearFutureAsyncWSClient, GateLinearFutureWSClient};
+pub use gate_spot::{GateSpotAsyncWSClient, GateSpotWSClient};
+pub use gate_swap::{GateInverseSwapAsyncWSClient, GateInverseSwapWSClient};
+pub use gate_swap::{GateLinearSwapAsyncWSClient, GateLinearSwapWSClient};
@@ -1,4 +1,4 @@
-use crate::clients::utils::CHANNEL_PAIR_DELIMITER;
+use crate::{clients::utils::CHANNEL_PAIR_DELIMITER, Channel, ChannelKind, WsError};
 use std::collections::HashMap;
 
 use super::super::ws_client_internal::MiscMessage;
@@ -76,7 +76,7 @@ pub(super) fn channels_to_commands(channels: &[String], subscribe: bool) -> Vec<
     all_commands
 }
 
-pub(super) fn to_raw_channel(channel: &str, pair: &str) -> String {
+fn to_raw_channel(channel: &str, pair: &str) -> String {
     format!("{}{}{}", channel, CHANNEL_PAIR_DELIMITER, pair)
 }
 
@@ -115,7 +115,7 @@ pub(super) fn on_misc_msg(msg: &str) -> MiscMessage {
     }
 }
 
-pub(super) fn to_candlestick_raw_channel(pair: &str, interval: u32) -> String {
+fn to_candlestick_raw_channel(pair: &str, interval: u32) -> Option<String> {
     let interval_str = match interval {
         10 => "10s",
         60 => "1m",
@@ -127,10 +127,47 @@ pub(super) fn to_candlestick_raw_channel(pair: &str, interval: u32) -> String {
         28800 => "8h",
         86400 => "1d",
         604800 => "7d",
-        _ => panic!("Gate available intervals 10s,1m,5m,15m,30m,1h,4h,8h,1d,7d"),
+        _ => return None, // Gate available intervals 10s,1m,5m,15m,30m,1h,4h,8h,1d,7d
     };
-    format!(
+    Some(format!(
         r#"{{"channel": "futures.candlesticks", "event": "subscribe", "payload" : ["{}", "{}"]}}"#,
         interval_str, pair
-    )
+    ))
+}
+
+// Translates typed channels of Gate futures and swap markets, which do NOT
+// have BBO, orderbook snapshot and public liquidation channels.
+pub(super) fn translate(channel: &Channel) -> Result<String, WsError> {
+    match channel {
+        Channel::Trade(pair) => Some(to_raw_channel("trades", pair)),
+        Channel::L2 {
+            pair,
+            depth: None,
+            speed: None,
+        } => Some(to_raw_channel("order_book", pair)),
+        // tickers contain mark_price, index_price and total_size, i.e., open interest
+        Channel::Ticker(pair)
+        | Channel::MarkPrice(pair)
+        | Channel::IndexPrice(pair)
+        | Channel::OpenInterest(pair) => Some(to_raw_channel("tickers", pair)),
+        Channel::Kline { pair, interval } => {
+            return to_candlestick_raw_channel(pair, *interval)
+                .ok_or_else(|| WsError::invalid_interval(EXCHANGE_NAME, *interval));
+        }
+        _ => None,
+    }
+    .ok_or_else(|| WsError::unsupported(EXCHANGE_NAME, channel))
+}
+
+// Kinds of channels of Gate futures and swap markets.
+pub(super) fn capabilities() -> Vec<ChannelKind> {
+    vec![
+        ChannelKind::Trade,
+        ChannelKind::L2,
+        ChannelKind::Ticker,
+        ChannelKind::Kline,
+        ChannelKind::MarkPrice,
+        ChannelKind::IndexPrice,
+        ChannelKind::OpenInterest,
+    ]
 }
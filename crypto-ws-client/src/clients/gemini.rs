@@ -0,0 +1,181 @@
+use crate::{Channel, ChannelKind, WSClient, WsError};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use super::{
+    utils::CHANNEL_PAIR_DELIMITER,
+    ws_client_internal::{MiscMessage, WSClientInternal},
+    ChannelTranslator,
+};
+
+use log::*;
+use serde_json::Value;
+
+pub(super) const EXCHANGE_NAME: &str = "gemini";
+
+const WEBSOCKET_URL: &str = "wss://api.gemini.com/v2/marketdata";
+
+// Empty string means an empty ping frame
+const CLIENT_PING_INTERVAL_AND_MSG: (u64, &str) = (60, "");
+
+/// The WebSocket client for Gemini.
+///
+/// Gemini has only Spot market.
+///
+///   * WebSocket API doc: <https://docs.gemini.com/websocket-api/#market-data-version-2>
+///   * Trading at: <https://exchange.gemini.com/trade/BTCUSD>
+pub struct GeminiWSClient<'a> {
+    client: WSClientInternal<'a>,
+}
+
+// All subscriptions are sent in one command, symbols of the same name are
+// grouped together
+fn channels_to_commands(channels: &[String], subscribe: bool) -> Vec<String> {
+    let mut all_commands: Vec<String> = channels
+        .iter()
+        .filter(|ch| ch.starts_with('{'))
+        .map(|s| s.to_string())
+        .collect();
+
+    let mut name_symbols = BTreeMap::<&str, Vec<&str>>::new();
+    for s in channels.iter().filter(|ch| !ch.starts_with('{')) {
+        let (name, symbol) = s.split_once(CHANNEL_PAIR_DELIMITER).unwrap();
+        name_symbols.entry(name).or_default().push(symbol);
+    }
+
+    if !name_symbols.is_empty() {
+        let subscriptions: Vec<String> = name_symbols
+            .iter()
+            .map(|(name, symbols)| {
+                format!(
+                    r#"{{"name":"{}","symbols":{}}}"#,
+                    name,
+                    serde_json::to_string(symbols).unwrap()
+                )
+            })
+            .collect();
+        all_commands.push(format!(
+            r#"{{"type":"{}","subscriptions":[{}]}}"#,
+            if subscribe {
+                "subscribe"
+            } else {
+                "unsubscribe"
+            },
+            subscriptions.join(",")
+        ));
+    }
+
+    all_commands
+}
+
+// Gemini doesn't confirm subscriptions
+fn on_misc_msg(msg: &str) -> MiscMessage {
+    let resp = serde_json::from_str::<HashMap<String, Value>>(msg);
+    if resp.is_err() {
+        error!("{} is not a JSON object, {}", msg, EXCHANGE_NAME);
+        return MiscMessage::Misc;
+    }
+    let obj = resp.unwrap();
+
+    if obj.get("result").and_then(|x| x.as_str()) == Some("error") {
+        // {"result":"error","reason":"InvalidJson","message":"Failed to parse subscription"}
+        error!("Received {} from {}", msg, EXCHANGE_NAME);
+        let reason = obj
+            .get("message")
+            .or_else(|| obj.get("reason"))
+            .and_then(|x| x.as_str())
+            .unwrap_or(msg);
+        return MiscMessage::Rejected {
+            channels: Vec::new(),
+            code: None,
+            reason: reason.to_string(),
+        };
+    }
+
+    match obj.get("type").and_then(|x| x.as_str()) {
+        Some("heartbeat") => {
+            debug!("Received {} from {}", msg, EXCHANGE_NAME);
+            MiscMessage::Misc
+        }
+        Some(_) => MiscMessage::Normal,
+        None => {
+            warn!("Received {} from {}", msg, EXCHANGE_NAME);
+            MiscMessage::Misc
+        }
+    }
+}
+
+fn to_raw_channel(name: &str, symbol: &str) -> String {
+    format!("{}{}{}", name, CHANNEL_PAIR_DELIMITER, symbol)
+}
+
+fn to_candlestick_raw_channel(symbol: &str, interval: u32) -> Option<String> {
+    let interval_str = match interval {
+        60 => "1m",
+        300 => "5m",
+        900 => "15m",
+        1800 => "30m",
+        3600 => "1h",
+        21600 => "6h",
+        86400 => "1d",
+        _ => return None,
+    };
+    Some(to_raw_channel(&format!("candles_{}", interval_str), symbol))
+}
+
+// The l2 subscription carries both trades and orderbook updates, it sends a
+// snapshot with recent trades first, then incremental updates
+impl<'a> ChannelTranslator for GeminiWSClient<'a> {
+    fn translate(&self, channel: &Channel) -> Result<String, WsError> {
+        match channel {
+            Channel::Trade(pair)
+            | Channel::L2 {
+                pair,
+                depth: None,
+                speed: None,
+            } => Some(to_raw_channel("l2", pair)),
+            Channel::Kline { pair, interval } => {
+                return to_candlestick_raw_channel(pair, *interval)
+                    .ok_or_else(|| WsError::invalid_interval(EXCHANGE_NAME, *interval));
+            }
+            _ => None,
+        }
+        .ok_or_else(|| WsError::unsupported(EXCHANGE_NAME, channel))
+    }
+
+    fn capabilities(&self) -> Vec<ChannelKind> {
+        vec![ChannelKind::Trade, ChannelKind::L2, ChannelKind::Kline]
+    }
+}
+
+define_client!(
+    GeminiWSClient,
+    EXCHANGE_NAME,
+    WEBSOCKET_URL,
+    channels_to_commands,
+    on_misc_msg,
+    Some(CLIENT_PING_INTERVAL_AND_MSG),
+    None
+);
+define_async_client!(GeminiAsyncWSClient, GeminiWSClient);
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_channels_to_commands() {
+        let commands = super::channels_to_commands(
+            &[
+                "l2:BTCUSD".to_string(),
+                "l2:ETHUSD".to_string(),
+                "candles_1m:BTCUSD".to_string(),
+            ],
+            true,
+        );
+        assert_eq!(
+            vec![
+                r#"{"type":"subscribe","subscriptions":[{"name":"candles_1m","symbols":["BTCUSD"]},{"name":"l2","symbols":["BTCUSD","ETHUSD"]}]}"#
+            ],
+            commands
+        );
+    }
+}
@@ -0,0 +1,264 @@
+use std::time::{Duration, Instant};
+
+/// Heartbeat and idle-timeout settings of a connection, see
+/// `WSClientOptions::config`.
+///
+/// Fields left as None keep the defaults of the exchange, so
+/// `ConnectionConfig::default()` behaves the same as `WSClient::new()`.
+///
+/// ## Example
+///
+/// ```no_run
+/// use std::{sync::{Arc, Mutex}, time::Duration};
+/// use crypto_ws_client::{ConnectionConfig, DeribitWSClient, WSClient, WSClientOptions};
+///
+/// // Illiquid options may be silent for minutes
+/// let config = ConnectionConfig {
+///     max_read_timeouts: Some(20),
+///     ..Default::default()
+/// };
+/// let options = WSClientOptions::default().config(config);
+/// let ws_client = DeribitWSClient::new_with_options(Arc::new(Mutex::new(|msg| println!("{}", msg))), options);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConnectionConfig {
+    /// How often the client sends a ping, it enables client pings on
+    /// exchanges which send pings from the server side
+    pub ping_interval: Option<Duration>,
+    /// Text message of client pings, an empty string means ping frames
+    pub ping_msg: Option<String>,
+    /// Reconnects if a ping is not answered within this time, checked when a
+    /// frame arrives or a read times out; None means only
+    /// `max_unanswered_pings` is checked
+    pub pong_timeout: Option<Duration>,
+    /// Reconnects after this many pings are unanswered, 5 by default
+    pub max_unanswered_pings: Option<u32>,
+    /// A read times out if no frame at all, including pings and pongs, arrives
+    /// within this time, rounded up to whole seconds; derived from the ping
+    /// interval by default
+    pub read_timeout: Option<Duration>,
+    /// Reconnects after this many read timeouts in a row, 5 by default;
+    /// ignored if the client sends pings, unanswered pings are checked instead
+    pub max_read_timeouts: Option<u32>,
+    /// Reconnects if no normal message arrives within this time, None means
+    /// never, which is the default
+    pub data_timeout: Option<Duration>,
+}
+
+// Unanswered pings or read timeouts tolerated by default
+const MAX_MISSES: u32 = 5;
+
+// ConnectionConfig with defaults of the exchange filled in
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct Heartbeat {
+    // how often the client sends a ping and the ping message, empty means
+    // ping frames; None means the server sends pings instead
+    pub ping: Option<(Duration, String)>,
+    pub pong_timeout: Option<Duration>,
+    pub max_unanswered_pings: isize,
+    // in seconds, None means reads never time out
+    pub read_timeout: Option<u64>,
+    pub max_read_timeouts: u32,
+    pub data_timeout: Option<Duration>,
+}
+
+impl ConnectionConfig {
+    // Fills in defaults of the exchange.
+    //
+    // `client_ping_interval_and_msg` and `server_ping_interval` are the same
+    // as those of `WSClientInternal`, only one of them should exist.
+    pub(super) fn resolve(
+        &self,
+        client_ping_interval_and_msg: Option<(u64, &str)>,
+        server_ping_interval: Option<u64>,
+    ) -> Heartbeat {
+        if client_ping_interval_and_msg.is_some() && server_ping_interval.is_some() {
+            panic!(
+                "Only one of client_ping_interval_and_msg and server_ping_interval can have value"
+            );
+        }
+        // The client pings twice as often as the exchange requires
+        let ping = match (self.ping_interval, client_ping_interval_and_msg) {
+            (Some(interval), default) => Some((
+                interval.max(Duration::from_millis(1)),
+                self.ping_msg
+                    .clone()
+                    .unwrap_or_else(|| default.map_or("", |x| x.1).to_string()),
+            )),
+            (None, Some((interval, msg))) => Some((
+                Duration::from_secs(interval / 2),
+                self.ping_msg.clone().unwrap_or_else(|| msg.to_string()),
+            )),
+            (None, None) => None,
+        };
+        let read_timeout = self.read_timeout.or_else(|| match &ping {
+            Some((interval, _)) => Some(*interval),
+            None => server_ping_interval.map(Duration::from_secs),
+        });
+        Heartbeat {
+            ping,
+            pong_timeout: self.pong_timeout,
+            max_unanswered_pings: self.max_unanswered_pings.unwrap_or(MAX_MISSES) as isize,
+            read_timeout: read_timeout.map(|x| std::cmp::max(1, x.as_secs_f64().ceil() as u64)),
+            max_read_timeouts: self.max_read_timeouts.unwrap_or(MAX_MISSES),
+            data_timeout: self.data_timeout,
+        }
+    }
+}
+
+// Decides when a connection is considered dropped, shared by the blocking
+// and async run loops.
+pub(super) struct Watchdog<'h> {
+    heartbeat: &'h Heartbeat,
+    num_read_timeout: u32,
+    last_ping: Instant,
+    // when the oldest unanswered ping was sent
+    first_unanswered_ping: Option<Instant>,
+    last_data: Instant,
+}
+
+impl<'h> Watchdog<'h> {
+    pub fn new(heartbeat: &'h Heartbeat) -> Self {
+        Watchdog {
+            heartbeat,
+            num_read_timeout: 0,
+            last_ping: Instant::now(),
+            first_unanswered_ping: None,
+            last_data: Instant::now(),
+        }
+    }
+
+    // Called after connected or reconnected
+    pub fn reset(&mut self) {
+        *self = Watchdog::new(self.heartbeat);
+    }
+
+    // Called for every frame received
+    pub fn on_frame(&mut self) {
+        self.num_read_timeout = 0;
+    }
+
+    // Called for every normal message passed to on_msg
+    pub fn on_data(&mut self) {
+        self.last_data = Instant::now();
+    }
+
+    pub fn on_read_timeout(&mut self) {
+        self.num_read_timeout += 1;
+    }
+
+    // True if it is time to send a ping
+    pub fn ping_due(&self) -> bool {
+        match &self.heartbeat.ping {
+            Some((interval, _)) => self.last_ping.elapsed() >= *interval,
+            None => false,
+        }
+    }
+
+    // Called after a ping was sent, `num_unanswered_ping` excludes this ping
+    pub fn on_ping_sent(&mut self, num_unanswered_ping: isize) {
+        self.last_ping = Instant::now();
+        if num_unanswered_ping <= 0 || self.first_unanswered_ping.is_none() {
+            self.first_unanswered_ping = Some(self.last_ping);
+        }
+    }
+
+    // Returns why the connection is considered dropped, None if it is healthy.
+    pub fn check(&mut self, num_unanswered_ping: isize) -> Option<String> {
+        if self.heartbeat.ping.is_some() {
+            if num_unanswered_ping <= 0 {
+                self.first_unanswered_ping = None;
+            } else if num_unanswered_ping > self.heartbeat.max_unanswered_pings {
+                return Some(format!("{} pings unanswered", num_unanswered_ping));
+            } else if let (Some(timeout), Some(sent)) =
+                (self.heartbeat.pong_timeout, self.first_unanswered_ping)
+            {
+                if sent.elapsed() > timeout {
+                    return Some(format!("No pong in {} ms", timeout.as_millis()));
+                }
+            }
+        } else if self.num_read_timeout > self.heartbeat.max_read_timeouts {
+            return Some(format!("{} read timeouts in a row", self.num_read_timeout));
+        }
+        match self.heartbeat.data_timeout {
+            Some(timeout) if self.last_data.elapsed() > timeout => {
+                Some(format!("No data in {} ms", timeout.as_millis()))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConnectionConfig, Watchdog};
+    use std::time::Duration;
+
+    #[test]
+    fn test_resolve() {
+        let default = ConnectionConfig::default();
+        let heartbeat = default.resolve(Some((30, r#"{"op":"ping"}"#)), None);
+        assert_eq!(
+            Some((Duration::from_secs(15), r#"{"op":"ping"}"#.to_string())),
+            heartbeat.ping
+        );
+        assert_eq!(Some(15), heartbeat.read_timeout);
+        assert_eq!(5, heartbeat.max_unanswered_pings);
+
+        let heartbeat = default.resolve(None, Some(60));
+        assert_eq!(None, heartbeat.ping);
+        assert_eq!(Some(60), heartbeat.read_timeout);
+        assert_eq!(5, heartbeat.max_read_timeouts);
+
+        let heartbeat = default.resolve(None, None);
+        assert_eq!(None, heartbeat.read_timeout);
+
+        // client pings on an exchange which sends pings from the server side
+        let config = ConnectionConfig {
+            ping_interval: Some(Duration::from_secs(10)),
+            read_timeout: Some(Duration::from_millis(2500)),
+            max_read_timeouts: Some(20),
+            ..Default::default()
+        };
+        let heartbeat = config.resolve(None, Some(60));
+        assert_eq!(
+            Some((Duration::from_secs(10), String::new())),
+            heartbeat.ping
+        );
+        assert_eq!(Some(3), heartbeat.read_timeout);
+        assert_eq!(20, heartbeat.max_read_timeouts);
+    }
+
+    #[test]
+    fn test_watchdog() {
+        let heartbeat = ConnectionConfig::default().resolve(None, Some(60));
+        let mut watchdog = Watchdog::new(&heartbeat);
+        for _ in 0..5 {
+            watchdog.on_read_timeout();
+            assert_eq!(None, watchdog.check(0));
+        }
+        watchdog.on_read_timeout();
+        assert_eq!(
+            Some("6 read timeouts in a row".to_string()),
+            watchdog.check(0)
+        );
+        watchdog.on_frame();
+        assert_eq!(None, watchdog.check(0));
+
+        let config = ConnectionConfig {
+            ping_interval: Some(Duration::from_secs(10)),
+            pong_timeout: Some(Duration::ZERO),
+            data_timeout: Some(Duration::from_secs(3600)),
+            ..Default::default()
+        };
+        let heartbeat = config.resolve(None, None);
+        let mut watchdog = Watchdog::new(&heartbeat);
+        assert!(!watchdog.ping_due());
+        assert_eq!(None, watchdog.check(0));
+        watchdog.on_ping_sent(0);
+        std::thread::sleep(Duration::from_millis(1));
+        assert_eq!(Some("No pong in 0 ms".to_string()), watchdog.check(1));
+        assert_eq!(None, watchdog.check(0)); // answered
+        assert_eq!(Some("7 pings unanswered".to_string()), watchdog.check(7));
+    }
+}
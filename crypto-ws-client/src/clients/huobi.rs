@@ -1,4 +1,4 @@
-use crate::WSClient;
+use crate::{Channel, ChannelKind, WSClient, WSClientOptions, WsError};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
@@ -7,19 +7,24 @@ use serde_json::Value;
 use tungstenite::Message;
 
 use super::ws_client_internal::{MiscMessage, WSClientInternal};
-use super::{Candlestick, OrderBook, OrderBookSnapshot, Ticker, Trade, BBO};
+use super::ChannelTranslator;
 
 pub(super) const EXCHANGE_NAME: &str = "huobi";
 
-const SPOT_WEBSOCKET_URL: &str = "wss://api.huobi.pro/ws";
-// const FUTURES_WEBSOCKET_URL: &str = "wss://www.hbdm.com/ws";
-// const COIN_SWAP_WEBSOCKET_URL: &str = "wss://api.hbdm.com/swap-ws";
-// const USDT_SWAP_WEBSOCKET_URL: &str = "wss://api.hbdm.com/linear-swap-ws";
-// const OPTION_WEBSOCKET_URL: &str = "wss://api.hbdm.com/option-ws";
-const FUTURES_WEBSOCKET_URL: &str = "wss://futures.huobi.com/ws";
-const COIN_SWAP_WEBSOCKET_URL: &str = "wss://futures.huobi.com/swap-ws";
-const USDT_SWAP_WEBSOCKET_URL: &str = "wss://futures.huobi.com/linear-swap-ws";
-const OPTION_WEBSOCKET_URL: &str = "wss://futures.huobi.com/option-ws";
+const SPOT_WEBSOCKET_URLS: &[&str] = &["wss://api.huobi.pro/ws", "wss://api-aws.huobi.pro/ws"];
+const FUTURES_WEBSOCKET_URLS: &[&str] = &["wss://futures.huobi.com/ws", "wss://api.hbdm.com/ws"];
+const COIN_SWAP_WEBSOCKET_URLS: &[&str] = &[
+    "wss://futures.huobi.com/swap-ws",
+    "wss://api.hbdm.com/swap-ws",
+];
+const USDT_SWAP_WEBSOCKET_URLS: &[&str] = &[
+    "wss://futures.huobi.com/linear-swap-ws",
+    "wss://api.hbdm.com/linear-swap-ws",
+];
+const OPTION_WEBSOCKET_URLS: &[&str] = &[
+    "wss://futures.huobi.com/option-ws",
+    "wss://api.hbdm.com/option-ws",
+];
 
 // The server will send a heartbeat every 5 seconds
 const SERVER_PING_INTERVAL: u64 = 5;
@@ -74,16 +79,42 @@ pub struct HuobiOptionWSClient<'a> {
 }
 
 impl<'a> HuobiWSClient<'a> {
-    fn new(url: &str, on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>) -> Self {
+    fn new(
+        endpoints: &[&str],
+        on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>,
+        options: &WSClientOptions<'a>,
+    ) -> Self {
         HuobiWSClient {
             client: WSClientInternal::new(
                 EXCHANGE_NAME,
-                url,
+                endpoints,
+                None,
                 on_msg,
                 Self::on_misc_msg,
                 Self::channels_to_commands,
                 None,
                 Some(SERVER_PING_INTERVAL),
+                options,
+            ),
+        }
+    }
+
+    fn new_async(
+        endpoints: &[&str],
+        on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>,
+        options: &WSClientOptions<'a>,
+    ) -> Self {
+        HuobiWSClient {
+            client: WSClientInternal::new_async(
+                EXCHANGE_NAME,
+                endpoints,
+                None,
+                on_msg,
+                Self::on_misc_msg,
+                Self::channels_to_commands,
+                None,
+                Some(SERVER_PING_INTERVAL),
+                options,
             ),
         }
     }
@@ -152,13 +183,26 @@ impl<'a> HuobiWSClient<'a> {
         } else {
             if let Some(status) = obj.get("status") {
                 match status.as_str().unwrap() {
-                    "ok" => info!("Received {} from {}", msg, EXCHANGE_NAME),
+                    "ok" => {
+                        info!("Received {} from {}", msg, EXCHANGE_NAME);
+                        // {"id":"crypto-ws-client","status":"ok","subbed":"market.btcusdt.trade.detail","ts":1616831426470}
+                        if let Some(subbed) = obj.get("subbed").and_then(|x| x.as_str()) {
+                            return MiscMessage::Subscribed(vec![subbed.to_string()]);
+                        }
+                    }
                     "error" => {
                         error!("Received {} from {}", msg, EXCHANGE_NAME);
+                        // {"status":"error","err-code":"bad-request","err-msg":"invalid topic market.btcusdt1.trade.detail","ts":1616831426470}
                         let err_msg = obj.get("err-msg").unwrap().as_str().unwrap();
-                        if err_msg.starts_with("invalid topic") {
-                            panic!("Received {} from {}", msg, EXCHANGE_NAME);
-                        }
+                        let channels = match err_msg.strip_prefix("invalid topic ") {
+                            Some(topic) => vec![topic.to_string()],
+                            None => Vec::new(),
+                        };
+                        return MiscMessage::Rejected {
+                            channels,
+                            code: None,
+                            reason: err_msg.to_string(),
+                        };
                     }
                     _ => warn!("Received {} from {}", msg, EXCHANGE_NAME),
                 }
@@ -183,33 +227,7 @@ fn to_raw_channel(channel: &str, pair: &str) -> String {
     format!("market.{}.{}", pair, channel)
 }
 
-#[rustfmt::skip]
-impl_trait!(Trade, HuobiWSClient, subscribe_trade, "trade.detail", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(Ticker, HuobiWSClient, subscribe_ticker, "detail", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(BBO, HuobiWSClient, subscribe_bbo, "bbo", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(OrderBookSnapshot, HuobiWSClient, subscribe_orderbook_snapshot, "depth.step0", to_raw_channel);
-
-impl<'a> OrderBook for HuobiWSClient<'a> {
-    fn subscribe_orderbook(&self, pairs: &[String]) {
-        let pair_to_raw_channel = |pair: &String| {
-            format!(
-                r#"{{"sub": "market.{}.depth.size_150.high_freq","data_type":"incremental","id": "crypto-ws-client"}}"#,
-                pair
-            )
-        };
-
-        let channels = pairs
-            .iter()
-            .map(pair_to_raw_channel)
-            .collect::<Vec<String>>();
-        self.client.subscribe(&channels);
-    }
-}
-
-fn to_candlestick_raw_channel(pair: &str, interval: u32) -> String {
+fn to_candlestick_raw_channel(pair: &str, interval: u32) -> Option<String> {
     let interval_str = match interval {
         60 => "1min",
         300 => "5min",
@@ -220,49 +238,83 @@ fn to_candlestick_raw_channel(pair: &str, interval: u32) -> String {
         86400 => "1day",
         604800 => "1week",
         2592000 => "1mon",
-        _ => panic!("Huobi has intervals 1min,5min,15min,30min,60min,4hour,1day,1week,1mon"),
+        _ => return None, // Huobi has intervals 1min,5min,15min,30min,60min,4hour,1day,1week,1mon
     };
-    format!("market.{}.kline.{}", pair, interval_str)
+    Some(format!("market.{}.kline.{}", pair, interval_str))
 }
 
-impl_candlestick!(HuobiWSClient);
+impl<'a> ChannelTranslator for HuobiWSClient<'a> {
+    fn translate(&self, channel: &Channel) -> Result<String, WsError> {
+        match channel {
+            Channel::Trade(pair) => Some(to_raw_channel("trade.detail", pair)),
+            Channel::Ticker(pair) => Some(to_raw_channel("detail", pair)),
+            Channel::BBO(pair) => Some(to_raw_channel("bbo", pair)),
+            Channel::L2 {
+                pair,
+                depth: depth @ (None | Some(20) | Some(150)),
+                speed: None,
+            } => Some(format!(
+                r#"{{"sub": "market.{}.depth.size_{}.high_freq","data_type":"incremental","id": "crypto-ws-client"}}"#,
+                pair,
+                depth.unwrap_or(150)
+            )),
+            Channel::L2Snapshot(pair) => Some(to_raw_channel("depth.step0", pair)),
+            Channel::Kline { pair, interval } => {
+                return to_candlestick_raw_channel(pair, *interval)
+                    .ok_or_else(|| WsError::invalid_interval(EXCHANGE_NAME, *interval));
+            }
+            // The latest 1min candlestick of mark prices
+            Channel::MarkPrice(pair) => Some(to_raw_channel("mark_price.kline.1min", pair)),
+            _ => None,
+        }
+        .ok_or_else(|| WsError::unsupported(EXCHANGE_NAME, channel))
+    }
+
+    fn capabilities(&self) -> Vec<ChannelKind> {
+        vec![
+            ChannelKind::Trade,
+            ChannelKind::L2,
+            ChannelKind::L2Snapshot,
+            ChannelKind::BBO,
+            ChannelKind::Ticker,
+            ChannelKind::Kline,
+        ]
+    }
+}
 
 /// Define market specific client.
 macro_rules! define_market_client {
-    ($struct_name:ident, $default_url:ident) => {
+    ($struct_name:ident, $default_urls:ident) => {
         impl<'a> WSClient<'a> for $struct_name<'a> {
-            fn new(on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>, url: Option<&str>) -> Self {
-                let real_url = match url {
-                    Some(endpoint) => endpoint,
-                    None => $default_url,
-                };
-                $struct_name {
-                    client: HuobiWSClient::new(real_url, on_msg),
-                }
+            fn new_with_options(
+                on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>,
+                options: $crate::WSClientOptions<'a>,
+            ) -> Self {
+                $struct_name::new_blocking(on_msg, &options)
             }
 
-            fn subscribe_trade(&self, channels: &[String]) {
-                <$struct_name as Trade>::subscribe_trade(self, channels);
+            fn endpoint(&self) -> &str {
+                self.client.client.url()
             }
 
-            fn subscribe_orderbook(&self, channels: &[String]) {
-                <$struct_name as OrderBook>::subscribe_orderbook(self, channels);
+            fn connection_limits(&self) -> $crate::ConnectionLimits {
+                self.client.client.connection_limits()
             }
 
-            fn subscribe_orderbook_snapshot(&self, channels: &[String]) {
-                <$struct_name as OrderBookSnapshot>::subscribe_orderbook_snapshot(self, channels);
+            fn num_pending_commands(&self) -> usize {
+                self.client.client.num_pending_commands()
             }
 
-            fn subscribe_ticker(&self, channels: &[String]) {
-                <$struct_name as Ticker>::subscribe_ticker(self, channels);
+            fn metrics(&self) -> $crate::Metrics {
+                self.client.client.metrics()
             }
 
-            fn subscribe_bbo(&self, channels: &[String]) {
-                <$struct_name as BBO>::subscribe_bbo(self, channels);
+            fn translate_channels(&self, channels: &[Channel]) -> Result<Vec<String>, WsError> {
+                self.to_raw_channels(EXCHANGE_NAME, channels)
             }
 
-            fn subscribe_candlestick(&self, pairs: &[String], interval: u32) {
-                <$struct_name as Candlestick>::subscribe_candlestick(self, pairs, interval);
+            fn capabilities(&self) -> Vec<ChannelKind> {
+                ChannelTranslator::capabilities(self)
             }
 
             fn subscribe(&self, channels: &[String]) {
@@ -273,6 +325,12 @@ macro_rules! define_market_client {
                 self.client.client.unsubscribe(channels);
             }
 
+            fn subscriptions(
+                &self,
+            ) -> std::collections::HashMap<String, $crate::SubscriptionState> {
+                self.client.client.subscriptions()
+            }
+
             fn run(&self, duration: Option<u64>) {
                 self.client.client.run(duration);
             }
@@ -280,117 +338,135 @@ macro_rules! define_market_client {
             fn close(&self) {
                 self.client.client.close();
             }
-        }
-    };
-}
 
-define_market_client!(HuobiSpotWSClient, SPOT_WEBSOCKET_URL);
-define_market_client!(HuobiFutureWSClient, FUTURES_WEBSOCKET_URL);
-define_market_client!(HuobiInverseSwapWSClient, COIN_SWAP_WEBSOCKET_URL);
-define_market_client!(HuobiLinearSwapWSClient, USDT_SWAP_WEBSOCKET_URL);
-define_market_client!(HuobiOptionWSClient, OPTION_WEBSOCKET_URL);
-
-macro_rules! impl_trade {
-    ($struct_name:ident) => {
-        impl<'a> Trade for $struct_name<'a> {
-            fn subscribe_trade(&self, pairs: &[String]) {
-                self.client.subscribe_trade(pairs);
+            fn set_reconnect_policy(&self, policy: $crate::ReconnectPolicy) {
+                self.client.client.set_reconnect_policy(policy);
+            }
+
+            fn on_reconnect(
+                &self,
+                callback: Arc<Mutex<dyn FnMut($crate::ReconnectEvent) + 'a + Send>>,
+            ) {
+                self.client.client.on_reconnect(callback);
             }
-        }
-    };
-}
 
-impl_trade!(HuobiSpotWSClient);
-impl_trade!(HuobiFutureWSClient);
-impl_trade!(HuobiInverseSwapWSClient);
-impl_trade!(HuobiLinearSwapWSClient);
-impl_trade!(HuobiOptionWSClient);
-
-macro_rules! impl_ticker {
-    ($struct_name:ident) => {
-        impl<'a> Ticker for $struct_name<'a> {
-            fn subscribe_ticker(&self, pairs: &[String]) {
-                self.client.subscribe_ticker(pairs);
+            fn set_recorder(&self, recorder: $crate::Recorder) {
+                self.client.client.set_recorder(recorder);
+            }
+
+            fn replay(
+                on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>,
+                replayer: &$crate::Replayer,
+            ) {
+                let options = $crate::WSClientOptions::from_url(replayer.url());
+                let client = $struct_name::new_async(on_msg, &options);
+                client.client.client.replay(replayer);
             }
         }
-    };
-}
 
-impl_ticker!(HuobiSpotWSClient);
-impl_ticker!(HuobiFutureWSClient);
-impl_ticker!(HuobiInverseSwapWSClient);
-impl_ticker!(HuobiLinearSwapWSClient);
-impl_ticker!(HuobiOptionWSClient);
-
-macro_rules! impl_bbo {
-    ($struct_name:ident) => {
-        impl<'a> BBO for $struct_name<'a> {
-            fn subscribe_bbo(&self, pairs: &[String]) {
-                self.client.subscribe_bbo(pairs);
+        impl<'a> $struct_name<'a> {
+            fn new_blocking(
+                on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>,
+                options: &$crate::WSClientOptions<'a>,
+            ) -> Self {
+                let urls = options.endpoint_urls();
+                let endpoints = if urls.is_empty() {
+                    $default_urls
+                } else {
+                    &urls[..]
+                };
+                $struct_name {
+                    client: HuobiWSClient::new(endpoints, on_msg, options),
+                }
+            }
+
+            fn new_async(
+                on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>,
+                options: &$crate::WSClientOptions<'a>,
+            ) -> Self {
+                let urls = options.endpoint_urls();
+                let endpoints = if urls.is_empty() {
+                    $default_urls
+                } else {
+                    &urls[..]
+                };
+                $struct_name {
+                    client: HuobiWSClient::new_async(endpoints, on_msg, options),
+                }
+            }
+
+            async fn run_async(&self, duration: Option<u64>) {
+                self.client.client.run_async(duration).await;
             }
         }
     };
 }
 
-impl_bbo!(HuobiSpotWSClient);
-impl_bbo!(HuobiFutureWSClient);
-impl_bbo!(HuobiInverseSwapWSClient);
-impl_bbo!(HuobiLinearSwapWSClient);
-impl_bbo!(HuobiOptionWSClient);
-
-macro_rules! impl_orderbook {
-    ($struct_name:ident) => {
-        impl<'a> OrderBook for $struct_name<'a> {
-            fn subscribe_orderbook(&self, pairs: &[String]) {
-                self.client.subscribe_orderbook(pairs);
+define_market_client!(HuobiSpotWSClient, SPOT_WEBSOCKET_URLS);
+define_async_client!(HuobiSpotAsyncWSClient, HuobiSpotWSClient);
+define_market_client!(HuobiFutureWSClient, FUTURES_WEBSOCKET_URLS);
+define_async_client!(HuobiFutureAsyncWSClient, HuobiFutureWSClient);
+define_market_client!(HuobiInverseSwapWSClient, COIN_SWAP_WEBSOCKET_URLS);
+define_async_client!(HuobiInverseSwapAsyncWSClient, HuobiInverseSwapWSClient);
+define_market_client!(HuobiLinearSwapWSClient, USDT_SWAP_WEBSOCKET_URLS);
+define_async_client!(HuobiLinearSwapAsyncWSClient, HuobiLinearSwapWSClient);
+define_market_client!(HuobiOptionWSClient, OPTION_WEBSOCKET_URLS);
+define_async_client!(HuobiOptionAsyncWSClient, HuobiOptionWSClient);
+
+// `$kind`s are channels of the market besides those of all markets.
+//
+// Index prices and liquidations are pushed by other endpoints, while open
+// interest is available via RESTful APIs only.
+macro_rules! impl_translator {
+    ($struct_name:ident $(, $kind:ident)*) => {
+        impl<'a> ChannelTranslator for $struct_name<'a> {
+            fn translate(&self, channel: &Channel) -> Result<String, WsError> {
+                self.client.translate(channel)
+            }
+
+            fn capabilities(&self) -> Vec<ChannelKind> {
+                let mut kinds = self.client.capabilities();
+                kinds.extend_from_slice(&[$(ChannelKind::$kind),*]);
+                kinds
             }
         }
     };
 }
 
-impl_orderbook!(HuobiFutureWSClient);
-impl_orderbook!(HuobiInverseSwapWSClient);
-impl_orderbook!(HuobiLinearSwapWSClient);
-impl_orderbook!(HuobiOptionWSClient);
-impl<'a> OrderBook for HuobiSpotWSClient<'a> {
-    fn subscribe_orderbook(&self, pairs: &[String]) {
-        if self.client.client.url.as_str() == "wss://api.huobi.pro/feed"
-            || self.client.client.url.as_str() == "wss://api-aws.huobi.pro/feed"
-        {
-            let pair_to_raw_channel = |pair: &String| to_raw_channel("mbp.150", pair);
+impl_translator!(HuobiFutureWSClient, MarkPrice);
+impl_translator!(HuobiInverseSwapWSClient, MarkPrice);
+impl_translator!(HuobiLinearSwapWSClient, MarkPrice);
+impl_translator!(HuobiOptionWSClient);
 
-            let channels = pairs
-                .iter()
-                .map(pair_to_raw_channel)
-                .collect::<Vec<String>>();
-            self.client.client.subscribe(&channels);
-        } else {
-            panic!("Huobi Spot market.$symbol.mbp.$levels must use wss://api.huobi.pro/feed or wss://api-aws.huobi.pro/feed");
-        }
+impl<'a> HuobiSpotWSClient<'a> {
+    // market.$symbol.mbp.$levels must use wss://api.huobi.pro/feed or wss://api-aws.huobi.pro/feed
+    fn is_feed_url(&self) -> bool {
+        let url = self.client.client.url();
+        url == "wss://api.huobi.pro/feed" || url == "wss://api-aws.huobi.pro/feed"
     }
 }
 
-impl_candlestick!(HuobiSpotWSClient);
-impl_candlestick!(HuobiFutureWSClient);
-impl_candlestick!(HuobiInverseSwapWSClient);
-impl_candlestick!(HuobiLinearSwapWSClient);
-impl_candlestick!(HuobiOptionWSClient);
-
-macro_rules! impl_orderbook_snapshot {
-    ($struct_name:ident) => {
-        impl<'a> OrderBookSnapshot for $struct_name<'a> {
-            fn subscribe_orderbook_snapshot(&self, pairs: &[String]) {
-                self.client.subscribe_orderbook_snapshot(pairs);
-            }
+impl<'a> ChannelTranslator for HuobiSpotWSClient<'a> {
+    fn translate(&self, channel: &Channel) -> Result<String, WsError> {
+        match channel {
+            Channel::L2 {
+                pair,
+                depth: None | Some(150),
+                speed: None,
+            } if self.is_feed_url() => Ok(to_raw_channel("mbp.150", pair)),
+            Channel::L2 { .. } => Err(WsError::unsupported(EXCHANGE_NAME, channel)),
+            _ => self.client.translate(channel),
         }
-    };
-}
+    }
 
-impl_orderbook_snapshot!(HuobiSpotWSClient);
-impl_orderbook_snapshot!(HuobiFutureWSClient);
-impl_orderbook_snapshot!(HuobiInverseSwapWSClient);
-impl_orderbook_snapshot!(HuobiLinearSwapWSClient);
-impl_orderbook_snapshot!(HuobiOptionWSClient);
+    fn capabilities(&self) -> Vec<ChannelKind> {
+        let mut kinds = self.client.capabilities();
+        if !self.is_feed_url() {
+            kinds.retain(|kind| *kind != ChannelKind::L2);
+        }
+        kinds
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,195 @@
+use crate::{Channel, ChannelKind, WSClient, WsError};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use super::super::{
+    utils::CHANNEL_PAIR_DELIMITER,
+    ws_client_internal::{MiscMessage, WSClientInternal},
+    ChannelTranslator,
+};
+use super::kraken_spot::EXCHANGE_NAME;
+
+use log::*;
+use serde_json::Value;
+
+const WEBSOCKET_URL: &str = "wss://futures.kraken.com/ws/v1";
+
+// The server closes connections without any message in 60 seconds
+// https://docs.futures.kraken.com/#websocket-api-websocket-api-introduction-subscriptions
+const CLIENT_PING_INTERVAL_AND_MSG: (u64, &str) = (60, "");
+
+/// The WebSocket client for Kraken Futures market.
+///
+/// Kraken Futures has InverseFuture and InverseSwap markets.
+///
+///   * WebSocket API doc: <https://docs.futures.kraken.com/#websocket-api>
+///   * Trading at: <https://futures.kraken.com/>
+pub struct KrakenFuturesWSClient<'a> {
+    client: WSClientInternal<'a>,
+}
+
+fn feed_product_ids_to_command(feed: &str, product_ids: &[String], subscribe: bool) -> String {
+    format!(
+        r#"{{"event":"{}","feed":"{}","product_ids":{}}}"#,
+        if subscribe {
+            "subscribe"
+        } else {
+            "unsubscribe"
+        },
+        feed,
+        serde_json::to_string(product_ids).unwrap()
+    )
+}
+
+fn channels_to_commands(channels: &[String], subscribe: bool) -> Vec<String> {
+    let mut all_commands: Vec<String> = channels
+        .iter()
+        .filter(|ch| ch.starts_with('{'))
+        .map(|s| s.to_string())
+        .collect();
+
+    let mut feed_product_ids = HashMap::<String, Vec<String>>::new();
+    for s in channels.iter().filter(|ch| !ch.starts_with('{')) {
+        let v: Vec<&str> = s.split(CHANNEL_PAIR_DELIMITER).collect();
+        let feed = v[0];
+        let product_id = v[1];
+        match feed_product_ids.get_mut(feed) {
+            Some(product_ids) => product_ids.push(product_id.to_string()),
+            None => {
+                feed_product_ids.insert(feed.to_string(), vec![product_id.to_string()]);
+            }
+        }
+    }
+
+    for (feed, product_ids) in feed_product_ids.iter() {
+        all_commands.push(feed_product_ids_to_command(feed, product_ids, subscribe));
+    }
+
+    all_commands
+}
+
+fn on_misc_msg(msg: &str) -> MiscMessage {
+    let resp = serde_json::from_str::<HashMap<String, Value>>(msg);
+    if resp.is_err() {
+        error!("{} is not a JSON object, {}", msg, EXCHANGE_NAME);
+        return MiscMessage::Misc;
+    }
+    let obj = resp.unwrap();
+
+    match obj.get("event").and_then(|x| x.as_str()) {
+        // {"event":"subscribed","feed":"trade","product_ids":["PI_XBTUSD"]}
+        Some("subscribed") => {
+            info!("Received {} from {}", msg, EXCHANGE_NAME);
+            let feed = obj.get("feed").and_then(|x| x.as_str()).unwrap_or_default();
+            let raw_channels: Vec<String> = obj
+                .get("product_ids")
+                .and_then(|x| x.as_array())
+                .map(|arr| {
+                    arr.iter()
+                        .filter_map(|x| x.as_str())
+                        .map(|product_id| to_raw_channel(feed, product_id))
+                        .collect()
+                })
+                .unwrap_or_default();
+            MiscMessage::Subscribed(raw_channels)
+        }
+        // {"event":"error","message":"Invalid product id"}
+        Some("error") => {
+            error!("Received {} from {}", msg, EXCHANGE_NAME);
+            let reason = obj.get("message").and_then(|x| x.as_str()).unwrap_or(msg);
+            MiscMessage::Rejected {
+                channels: Vec::new(),
+                code: None,
+                reason: reason.to_string(),
+            }
+        }
+        Some("info") | Some("unsubscribed") => {
+            info!("Received {} from {}", msg, EXCHANGE_NAME);
+            MiscMessage::Misc
+        }
+        Some(_) => {
+            warn!("Received {} from {}", msg, EXCHANGE_NAME);
+            MiscMessage::Misc
+        }
+        None => {
+            if obj.get("feed").and_then(|x| x.as_str()) == Some("heartbeat") {
+                debug!("Received {} from {}", msg, EXCHANGE_NAME);
+                MiscMessage::Misc
+            } else if obj.contains_key("feed") {
+                MiscMessage::Normal
+            } else {
+                warn!("Received {} from {}", msg, EXCHANGE_NAME);
+                MiscMessage::Misc
+            }
+        }
+    }
+}
+
+fn to_raw_channel(feed: &str, product_id: &str) -> String {
+    format!("{}{}{}", feed, CHANNEL_PAIR_DELIMITER, product_id)
+}
+
+// The book feed sends a book_snapshot first, then incremental updates
+impl<'a> ChannelTranslator for KrakenFuturesWSClient<'a> {
+    fn translate(&self, channel: &Channel) -> Result<String, WsError> {
+        match channel {
+            Channel::Trade(pair) => Some(to_raw_channel("trade", pair)),
+            Channel::L2 {
+                pair,
+                depth: None,
+                speed: None,
+            } => Some(to_raw_channel("book", pair)),
+            // ticker contains best bid & ask, mark price, index price and open interest
+            Channel::Ticker(pair)
+            | Channel::BBO(pair)
+            | Channel::MarkPrice(pair)
+            | Channel::IndexPrice(pair)
+            | Channel::OpenInterest(pair) => Some(to_raw_channel("ticker", pair)),
+            _ => None,
+        }
+        .ok_or_else(|| WsError::unsupported(EXCHANGE_NAME, channel))
+    }
+
+    fn capabilities(&self) -> Vec<ChannelKind> {
+        vec![
+            ChannelKind::Trade,
+            ChannelKind::L2,
+            ChannelKind::BBO,
+            ChannelKind::Ticker,
+            ChannelKind::MarkPrice,
+            ChannelKind::IndexPrice,
+            ChannelKind::OpenInterest,
+        ]
+    }
+}
+
+define_client!(
+    KrakenFuturesWSClient,
+    EXCHANGE_NAME,
+    WEBSOCKET_URL,
+    channels_to_commands,
+    on_misc_msg,
+    Some(CLIENT_PING_INTERVAL_AND_MSG),
+    None
+);
+define_async_client!(KrakenFuturesAsyncWSClient, KrakenFuturesWSClient);
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_two_product_ids() {
+        assert_eq!(
+            r#"{"event":"subscribe","feed":"trade","product_ids":["PI_XBTUSD","PI_ETHUSD"]}"#,
+            super::feed_product_ids_to_command(
+                "trade",
+                &["PI_XBTUSD".to_string(), "PI_ETHUSD".to_string()],
+                true
+            )
+        );
+
+        assert_eq!(
+            r#"{"event":"unsubscribe","feed":"book","product_ids":["FI_XBTUSD_210625"]}"#,
+            super::feed_product_ids_to_command("book", &["FI_XBTUSD_210625".to_string()], false)
+        );
+    }
+}
@@ -0,0 +1,352 @@
+use crate::{Channel, ChannelKind, Credentials, PrivateWSClient, WSClient, WsError};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use super::super::{
+    auth::{self, Auth},
+    utils::CHANNEL_PAIR_DELIMITER,
+    ws_client_internal::{MiscMessage, WSClientInternal},
+    ChannelTranslator,
+};
+
+use log::*;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tungstenite::Message;
+
+pub(super) const EXCHANGE_NAME: &str = "kraken";
+
+const WEBSOCKET_URL: &str = "wss://ws.kraken.com";
+// Private channels are only available on this endpoint
+const PRIVATE_WEBSOCKET_URL: &str = "wss://ws-auth.kraken.com";
+
+// Client can ping server to determine whether connection is alive
+// https://docs.kraken.com/websockets/#message-ping
+const CLIENT_PING_INTERVAL_AND_MSG: (u64, &str) = (10, r#"{"event":"ping"}"#);
+
+/// The WebSocket client for Kraken Spot market.
+///
+///   * WebSocket API doc: <https://docs.kraken.com/websockets/>
+///   * Trading at: <https://trade.kraken.com/>
+
+pub struct KrakenWSClient<'a> {
+    client: WSClientInternal<'a>,
+}
+
+fn name_pairs_to_command(name: &str, pairs: &[String], subscribe: bool) -> String {
+    format!(
+        r#"{{"event":"{}","pair":{},"subscription":{{"name":"{}"}}}}"#,
+        if subscribe {
+            "subscribe"
+        } else {
+            "unsubscribe"
+        },
+        serde_json::to_string(pairs).unwrap(),
+        name
+    )
+}
+
+fn channels_to_commands(channels: &[String], subscribe: bool) -> Vec<String> {
+    let mut all_commands: Vec<String> = channels
+        .iter()
+        .filter(|ch| ch.starts_with('{'))
+        .map(|s| s.to_string())
+        .collect();
+
+    let mut name_pairs = HashMap::<String, Vec<String>>::new();
+    for s in channels.iter().filter(|ch| !ch.starts_with('{')) {
+        let v: Vec<&str> = s.split(CHANNEL_PAIR_DELIMITER).collect();
+        let name = v[0];
+        let pair = v[1];
+        match name_pairs.get_mut(name) {
+            Some(pairs) => pairs.push(pair.to_string()),
+            None => {
+                name_pairs.insert(name.to_string(), vec![pair.to_string()]);
+            }
+        }
+    }
+
+    for (name, pairs) in name_pairs.iter() {
+        all_commands.push(name_pairs_to_command(name, pairs, subscribe));
+    }
+
+    all_commands
+}
+
+fn on_misc_msg(msg: &str) -> MiscMessage {
+    let resp = serde_json::from_str::<Value>(&msg);
+    if resp.is_err() {
+        error!("{} is not a JSON string, {}", msg, EXCHANGE_NAME);
+        return MiscMessage::Misc;
+    }
+    let value = resp.unwrap();
+
+    if value.is_object() {
+        let obj = value.as_object().unwrap();
+        let event = obj.get("event").unwrap().as_str().unwrap();
+        match event {
+            "heartbeat" => {
+                debug!("Received {} from {}", msg, EXCHANGE_NAME);
+                let ping = r#"{
+                    "event": "ping",
+                    "reqid": 9527
+                }"#;
+                MiscMessage::WebSocket(Message::Text(ping.to_string()))
+            }
+            "pong" => {
+                debug!("Received {} from {}", msg, EXCHANGE_NAME);
+                MiscMessage::Pong
+            }
+            "subscriptionStatus" => {
+                let status = obj.get("status").unwrap().as_str().unwrap();
+                // raw channels like trade:XBT/USD
+                let raw_channel = obj
+                    .get("subscription")
+                    .and_then(|x| x.get("name"))
+                    .and_then(|x| x.as_str())
+                    .zip(obj.get("pair").and_then(|x| x.as_str()))
+                    .map(|(name, pair)| to_raw_channel(name, pair));
+                match status {
+                    "subscribed" => {
+                        info!("Received {} from {}", msg, EXCHANGE_NAME);
+                        if let Some(raw_channel) = raw_channel {
+                            return MiscMessage::Subscribed(vec![raw_channel]);
+                        }
+                    }
+                    "unsubscribed" => info!("Received {} from {}", msg, EXCHANGE_NAME),
+                    "error" => {
+                        error!("Received {} from {}", msg, EXCHANGE_NAME);
+                        // e.g., Currency pair not supported XBT/USD1
+                        let error_msg = obj.get("errorMessage").unwrap().as_str().unwrap();
+                        return MiscMessage::Rejected {
+                            channels: raw_channel.into_iter().collect(),
+                            code: None,
+                            reason: error_msg.to_string(),
+                        };
+                    }
+                    _ => warn!("Received {} from {}", msg, EXCHANGE_NAME),
+                }
+
+                MiscMessage::Misc
+            }
+            "systemStatus" => {
+                let status = obj.get("status").unwrap().as_str().unwrap();
+                match status {
+                    "maintenance" | "cancel_only" => {
+                        warn!(
+                            "Received {}, which means Kraken is in maintenance mode",
+                            msg
+                        );
+                        MiscMessage::ReconnectAfter(std::time::Duration::from_secs(20))
+                    }
+                    _ => {
+                        info!("Received {} from {}", msg, EXCHANGE_NAME);
+                        MiscMessage::Misc
+                    }
+                }
+            }
+            _ => {
+                warn!("Received {} from {}", msg, EXCHANGE_NAME);
+                MiscMessage::Misc
+            }
+        }
+    } else {
+        MiscMessage::Normal
+    }
+}
+
+fn to_raw_channel(channel: &str, pair: &str) -> String {
+    format!("{}{}{}", channel, CHANNEL_PAIR_DELIMITER, pair)
+}
+
+fn to_candlestick_raw_channel(pair: &str, interval: u32) -> Option<String> {
+    let valid_set: Vec<u32> = vec![1, 5, 15, 30, 60, 240, 1440, 10080, 21600]
+        .into_iter()
+        .map(|x| x * 60)
+        .collect();
+    if !valid_set.contains(&interval) {
+        return None;
+    }
+
+    Some(format!(
+        r#"{{"event":"subscribe","pair":{},"subscription":{{"name":"ohlc", "interval":{}}}}}"#,
+        serde_json::to_string(&[pair]).unwrap(),
+        interval / 60
+    ))
+}
+
+// Kraken does NOT have orderbook snapshot channel
+impl<'a> ChannelTranslator for KrakenWSClient<'a> {
+    fn translate(&self, channel: &Channel) -> Result<String, WsError> {
+        match channel {
+            Channel::Trade(pair) => Some(to_raw_channel("trade", pair)),
+            Channel::Ticker(pair) => Some(to_raw_channel("ticker", pair)),
+            Channel::BBO(pair) => Some(to_raw_channel("spread", pair)),
+            Channel::L2 {
+                pair,
+                depth: depth @ (None | Some(10) | Some(25) | Some(100) | Some(500) | Some(1000)),
+                speed: None,
+            } => Some(format!(
+                r#"{{"event":"subscribe","pair":{},"subscription":{{"name":"book", "depth":{}}}}}"#,
+                serde_json::to_string(&[pair]).unwrap(),
+                depth.unwrap_or(25)
+            )),
+            Channel::Kline { pair, interval } => {
+                return to_candlestick_raw_channel(pair, *interval)
+                    .ok_or_else(|| WsError::invalid_interval(EXCHANGE_NAME, *interval));
+            }
+            _ => None,
+        }
+        .ok_or_else(|| WsError::unsupported(EXCHANGE_NAME, channel))
+    }
+
+    fn capabilities(&self) -> Vec<ChannelKind> {
+        vec![
+            ChannelKind::Trade,
+            ChannelKind::L2,
+            ChannelKind::BBO,
+            ChannelKind::Ticker,
+            ChannelKind::Kline,
+        ]
+    }
+}
+
+// see https://docs.kraken.com/rest/#section/Authentication/Headers-and-Signature
+fn sign(api_secret: &[u8], path: &str, nonce: u64, post_data: &str) -> String {
+    let sha256 = Sha256::digest(format!("{}{}", nonce, post_data).as_bytes());
+    let mut msg = path.as_bytes().to_vec();
+    msg.extend_from_slice(&sha256);
+    base64::encode(auth::hmac_sha512(api_secret, &msg))
+}
+
+// see https://docs.kraken.com/rest/#operation/getWebsocketsToken
+fn fetch_ws_token(credentials: &Credentials) -> Result<String, WsError> {
+    // The API secret is base64 encoded
+    let api_secret = base64::decode(&credentials.api_secret)
+        .map_err(|err| WsError::auth(EXCHANGE_NAME, &format!("invalid API secret, {}", err)))?;
+    let path = "/0/private/GetWebSocketsToken";
+    let nonce = auth::current_millis();
+    let post_data = format!("nonce={}", nonce);
+    let signature = sign(&api_secret, path, nonce, &post_data);
+    let txt = auth::http_request(
+        reqwest::Method::POST,
+        &format!("https://api.kraken.com{}", path),
+        &[
+            ("API-Key", credentials.api_key.clone()),
+            ("API-Sign", signature),
+        ],
+        Some(post_data),
+    )
+    .map_err(|err| WsError::auth(EXCHANGE_NAME, &format!("failed to get token, {}", err)))?;
+    serde_json::from_str::<HashMap<String, Value>>(&txt)
+        .ok()
+        .and_then(|obj| {
+            obj.get("result")
+                .and_then(|x| x.get("token"))
+                .and_then(|x| x.as_str())
+                .map(String::from)
+        })
+        .ok_or_else(|| WsError::auth(EXCHANGE_NAME, &format!("failed to get token, {}", txt)))
+}
+
+impl<'a> KrakenWSClient<'a> {
+    fn subscribe_private(&self, name: &str) {
+        let token = self.client.auth_token().expect("Not a private client");
+        let command = format!(
+            r#"{{"event":"subscribe","subscription":{{"name":"{}","token":"{}"}}}}"#,
+            name, token
+        );
+        self.client.subscribe(&[command]);
+    }
+}
+
+/// The token of private channels is valid while the connection is alive,
+/// a client can NOT reconnect after 15 minutes of disconnection.
+impl<'a> PrivateWSClient<'a> for KrakenWSClient<'a> {
+    fn new_private(
+        on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>,
+        credentials: Credentials,
+        url: Option<&str>,
+    ) -> Result<Self, WsError> {
+        let token = fetch_ws_token(&credentials)?;
+        let ws_client = KrakenWSClient::new(on_msg, Some(url.unwrap_or(PRIVATE_WEBSOCKET_URL)));
+        ws_client.client.set_auth(Auth::Token(token));
+        Ok(ws_client)
+    }
+
+    // Kraken pushes orders of all pairs
+    fn subscribe_orders(&self, _symbols: &[String]) {
+        self.subscribe_private("openOrders");
+    }
+
+    // Kraken pushes own trades of all pairs
+    fn subscribe_fills(&self, _symbols: &[String]) {
+        self.subscribe_private("ownTrades");
+    }
+
+    fn subscribe_balances(&self, _currencies: &[String]) {
+        panic!("Kraken WebSocket does NOT have balance channel");
+    }
+}
+
+define_client!(
+    KrakenWSClient,
+    EXCHANGE_NAME,
+    WEBSOCKET_URL,
+    channels_to_commands,
+    on_misc_msg,
+    Some(CLIENT_PING_INTERVAL_AND_MSG),
+    None
+);
+define_async_client!(KrakenAsyncWSClient, KrakenWSClient);
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_sign() {
+        // https://docs.kraken.com/rest/#section/Authentication/Headers-and-Signature
+        assert_eq!(
+            "4/dpxb3iT4tp/ZCVEwSnEsLxx0bqyhLpdfOpc6fn7OR8+UClSV5n9E6aSS8MPtnRfp32bAb0nmbRn6H8ndwLUQ==",
+            super::sign(
+                &base64::decode("kQH5HW/8p1uGOVjbgWA7FunAmGO8lsSUXNsu3eow76sz84Q18fWxnyRzBHCd3pd5nE9qa99HAZtuZuj6F1huXg==").unwrap(),
+                "/0/private/AddOrder",
+                1616492376594,
+                "nonce=1616492376594&ordertype=limit&pair=XBTUSD&price=37500&type=buy&volume=1.25"
+            )
+        );
+    }
+
+    #[test]
+    fn test_one_pair() {
+        assert_eq!(
+            r#"{"event":"subscribe","pair":["XBT/USD"],"subscription":{"name":"trade"}}"#,
+            super::name_pairs_to_command("trade", &vec!["XBT/USD".to_string()], true)
+        );
+
+        assert_eq!(
+            r#"{"event":"unsubscribe","pair":["XBT/USD"],"subscription":{"name":"trade"}}"#,
+            super::name_pairs_to_command("trade", &vec!["XBT/USD".to_string()], false)
+        );
+    }
+
+    #[test]
+    fn test_two_pairs() {
+        assert_eq!(
+            r#"{"event":"subscribe","pair":["XBT/USD","ETH/USD"],"subscription":{"name":"trade"}}"#,
+            super::name_pairs_to_command(
+                "trade",
+                &vec!["XBT/USD".to_string(), "ETH/USD".to_string()],
+                true
+            )
+        );
+
+        assert_eq!(
+            r#"{"event":"unsubscribe","pair":["XBT/USD","ETH/USD"],"subscription":{"name":"trade"}}"#,
+            super::name_pairs_to_command(
+                "trade",
+                &vec!["XBT/USD".to_string(), "ETH/USD".to_string()],
+                false
+            )
+        );
+    }
+}
@@ -0,0 +1,5 @@
+mod kraken_futures;
+mod kraken_spot;
+
+pub use kraken_futures::{KrakenFuturesAsyncWSClient, KrakenFuturesWSClient};
+pub use kraken_spot::{KrakenAsyncWSClient, KrakenWSClient};
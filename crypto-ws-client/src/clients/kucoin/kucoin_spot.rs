@@ -1,20 +1,13 @@
-use crate::{Level3OrderBook, WSClient};
+use crate::{Channel, ChannelKind, Level3OrderBook, WSClient, WsError};
 use std::sync::{Arc, Mutex};
 
 use super::super::ws_client_internal::WSClientInternal;
-use super::super::{Candlestick, OrderBook, OrderBookSnapshot, Ticker, Trade, BBO};
+use super::super::ChannelTranslator;
 use super::utils::{
-    channels_to_commands, fetch_ws_token, on_misc_msg, to_raw_channel, WebsocketToken,
-    CLIENT_PING_INTERVAL_AND_MSG, EXCHANGE_NAME,
+    channels_to_commands, fetch_ws_url, on_misc_msg, to_raw_channel, CLIENT_PING_INTERVAL_AND_MSG,
+    EXCHANGE_NAME, WEBSOCKET_URL,
 };
 
-use lazy_static::lazy_static;
-
-lazy_static! {
-    static ref WS_TOKEN: WebsocketToken = fetch_ws_token();
-    static ref WEBSOCKET_URL: String = format!("{}?token={}", WS_TOKEN.endpoint, WS_TOKEN.token);
-}
-
 /// The WebSocket client for KuCoin Spot market.
 ///
 /// * WebSocket API doc: <https://docs.kucoin.com/#websocket-feed>
@@ -23,18 +16,7 @@ pub struct KuCoinSpotWSClient<'a> {
     client: WSClientInternal<'a>,
 }
 
-#[rustfmt::skip]
-impl_trait!(Trade, KuCoinSpotWSClient, subscribe_trade, "/market/match", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(BBO, KuCoinSpotWSClient, subscribe_bbo, "/market/ticker", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(OrderBook, KuCoinSpotWSClient, subscribe_orderbook, "/market/level2", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(OrderBookSnapshot, KuCoinSpotWSClient, subscribe_orderbook_snapshot, "/spotMarket/level2Depth50", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(Ticker, KuCoinSpotWSClient, subscribe_ticker, "/market/snapshot", to_raw_channel);
-
-fn to_candlestick_raw_channel(pair: &str, interval: u32) -> String {
+fn to_candlestick_raw_channel(pair: &str, interval: u32) -> Option<String> {
     let interval_str = match interval {
         60 => "1min",
         180 => "3min",
@@ -49,34 +31,64 @@ fn to_candlestick_raw_channel(pair: &str, interval: u32) -> String {
         43200 => "12hour",
         86400 => "1day",
         604800 => "1week",
-        _ => panic!(
-            "KuCoin available intervals 1min,3min,5min,15min,30min,1hour,2hour,4hour,6hour,8hour,12hour,1day,1week"
-        ),
+        _ => return None,
     };
-    format!(
+    Some(format!(
         r#"{{"id":"crypto-ws-client","type":"subscribe","topic":"/market/candles:{}_{}","privateChannel":false,"response":true}}"#,
         pair, interval_str,
-    )
+    ))
 }
 
-impl_candlestick!(KuCoinSpotWSClient);
+impl<'a> ChannelTranslator for KuCoinSpotWSClient<'a> {
+    fn translate(&self, channel: &Channel) -> Result<String, WsError> {
+        match channel {
+            Channel::Trade(pair) => Some(to_raw_channel("/market/match", pair)),
+            Channel::BBO(pair) => Some(to_raw_channel("/market/ticker", pair)),
+            Channel::L2 {
+                pair,
+                depth: None,
+                speed: None,
+            } => Some(to_raw_channel("/market/level2", pair)),
+            Channel::L2Snapshot(pair) => Some(to_raw_channel("/spotMarket/level2Depth50", pair)),
+            Channel::L3(pair) => Some(to_raw_channel("/spotMarket/level3", pair)),
+            Channel::Ticker(pair) => Some(to_raw_channel("/market/snapshot", pair)),
+            Channel::Kline { pair, interval } => {
+                return to_candlestick_raw_channel(pair, *interval)
+                    .ok_or_else(|| WsError::invalid_interval(EXCHANGE_NAME, *interval));
+            }
+            _ => None,
+        }
+        .ok_or_else(|| WsError::unsupported(EXCHANGE_NAME, channel))
+    }
+
+    fn capabilities(&self) -> Vec<ChannelKind> {
+        vec![
+            ChannelKind::Trade,
+            ChannelKind::L2,
+            ChannelKind::L2Snapshot,
+            ChannelKind::L3,
+            ChannelKind::BBO,
+            ChannelKind::Ticker,
+            ChannelKind::Kline,
+        ]
+    }
+}
 
 impl<'a> Level3OrderBook for KuCoinSpotWSClient<'a> {
-    fn subscribe_l3_orderbook(&self, symbols: &[String]) {
-        let raw_channels: Vec<String> = symbols
-            .iter()
-            .map(|symbol| to_raw_channel("/spotMarket/level3", symbol))
-            .collect();
-        self.client.subscribe(&raw_channels);
+    fn subscribe_l3_orderbook(&self, symbols: &[String]) -> Result<(), WsError> {
+        let channels: Vec<Channel> = symbols.iter().cloned().map(Channel::L3).collect();
+        self.subscribe_channels(&channels)
     }
 }
 
 define_client!(
     KuCoinSpotWSClient,
     EXCHANGE_NAME,
-    WEBSOCKET_URL.as_str(),
+    WEBSOCKET_URL,
     channels_to_commands,
     on_misc_msg,
     Some(CLIENT_PING_INTERVAL_AND_MSG),
-    None
+    None,
+    Some(fetch_ws_url)
 );
+define_async_client!(KuCoinSpotAsyncWSClient, KuCoinSpotWSClient);
@@ -1,20 +1,13 @@
-use crate::{Level3OrderBook, WSClient};
+use crate::{Channel, ChannelKind, Level3OrderBook, WSClient, WsError};
 use std::sync::{Arc, Mutex};
 
 use super::super::ws_client_internal::WSClientInternal;
-use super::super::{Candlestick, OrderBook, OrderBookSnapshot, Ticker, Trade, BBO};
+use super::super::ChannelTranslator;
 use super::utils::{
-    channels_to_commands, fetch_ws_token, on_misc_msg, to_raw_channel, WebsocketToken,
-    CLIENT_PING_INTERVAL_AND_MSG, EXCHANGE_NAME,
+    channels_to_commands, fetch_ws_url, on_misc_msg, to_raw_channel, CLIENT_PING_INTERVAL_AND_MSG,
+    EXCHANGE_NAME, WEBSOCKET_URL,
 };
 
-use lazy_static::lazy_static;
-
-lazy_static! {
-    static ref WS_TOKEN: WebsocketToken = fetch_ws_token();
-    static ref WEBSOCKET_URL: String = format!("{}?token={}", WS_TOKEN.endpoint, WS_TOKEN.token);
-}
-
 /// The WebSocket client for KuCoin Swap markets.
 ///
 /// * WebSocket API doc: <https://docs.kucoin.cc/futures/#websocket-2>
@@ -23,54 +16,78 @@ pub struct KuCoinSwapWSClient<'a> {
     client: WSClientInternal<'a>,
 }
 
-#[rustfmt::skip]
-impl_trait!(Trade, KuCoinSwapWSClient, subscribe_trade, "/contractMarket/execution", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(BBO, KuCoinSwapWSClient, subscribe_bbo, "/contractMarket/ticker", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(OrderBook, KuCoinSwapWSClient, subscribe_orderbook, "/contractMarket/level2", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(OrderBookSnapshot, KuCoinSwapWSClient, subscribe_orderbook_snapshot, "/contractMarket/level2Depth50", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(Ticker, KuCoinSwapWSClient, subscribe_ticker, "/contractMarket/snapshot", to_raw_channel);
-
-fn to_candlestick_raw_channel(pair: &str, interval: u32) -> String {
+fn to_candlestick_raw_channel(pair: &str, interval: u32) -> Option<String> {
     let valid_set: Vec<u32> = vec![
         60, 300, 900, 1800, 3600, 7200, 14400, 28800, 43200, 86400, 604800,
     ];
     if !valid_set.contains(&interval) {
-        let joined = valid_set
-            .into_iter()
-            .map(|x| x.to_string())
-            .collect::<Vec<String>>()
-            .join(",");
-        panic!("KuCoin Swap available intervals {}", joined);
+        return None;
     }
-    format!(
+    Some(format!(
         r#"{{"id":"crypto-ws-client","type":"subscribe","topic":"/contractMarket/candle:{}_{}","privateChannel":false,"response":true}}"#,
         pair,
         interval / 60,
-    )
+    ))
 }
 
-impl_candlestick!(KuCoinSwapWSClient);
+impl<'a> ChannelTranslator for KuCoinSwapWSClient<'a> {
+    fn translate(&self, channel: &Channel) -> Result<String, WsError> {
+        match channel {
+            Channel::Trade(pair) => Some(to_raw_channel("/contractMarket/execution", pair)),
+            Channel::BBO(pair) => Some(to_raw_channel("/contractMarket/ticker", pair)),
+            Channel::L2 {
+                pair,
+                depth: None,
+                speed: None,
+            } => Some(to_raw_channel("/contractMarket/level2", pair)),
+            Channel::L2Snapshot(pair) => {
+                Some(to_raw_channel("/contractMarket/level2Depth50", pair))
+            }
+            Channel::L3(pair) => Some(to_raw_channel("/contractMarket/level3v2", pair)),
+            Channel::Ticker(pair) => Some(to_raw_channel("/contractMarket/snapshot", pair)),
+            Channel::Kline { pair, interval } => {
+                return to_candlestick_raw_channel(pair, *interval)
+                    .ok_or_else(|| WsError::invalid_interval(EXCHANGE_NAME, *interval));
+            }
+            // instrument pushes mark.index.price and funding.rate
+            Channel::MarkPrice(pair) | Channel::IndexPrice(pair) => {
+                Some(to_raw_channel("/contract/instrument", pair))
+            }
+            _ => None,
+        }
+        .ok_or_else(|| WsError::unsupported(EXCHANGE_NAME, channel))
+    }
+
+    fn capabilities(&self) -> Vec<ChannelKind> {
+        vec![
+            ChannelKind::Trade,
+            ChannelKind::L2,
+            ChannelKind::L2Snapshot,
+            ChannelKind::L3,
+            ChannelKind::BBO,
+            ChannelKind::Ticker,
+            ChannelKind::Kline,
+            ChannelKind::MarkPrice,
+            ChannelKind::IndexPrice,
+        ]
+    }
+}
 
 impl<'a> Level3OrderBook for KuCoinSwapWSClient<'a> {
-    fn subscribe_l3_orderbook(&self, symbols: &[String]) {
-        let raw_channels: Vec<String> = symbols
-            .iter()
-            .map(|symbol| to_raw_channel("/contractMarket/level3v2", symbol))
-            .collect();
-        self.client.subscribe(&raw_channels);
+    fn subscribe_l3_orderbook(&self, symbols: &[String]) -> Result<(), WsError> {
+        let channels: Vec<Channel> = symbols.iter().cloned().map(Channel::L3).collect();
+        self.subscribe_channels(&channels)
     }
 }
 
 define_client!(
     KuCoinSwapWSClient,
     EXCHANGE_NAME,
-    WEBSOCKET_URL.as_str(),
+    WEBSOCKET_URL,
     channels_to_commands,
     on_misc_msg,
     Some(CLIENT_PING_INTERVAL_AND_MSG),
-    None
+    None,
+    Some(fetch_ws_url)
 );
+define_async_client!(KuCoinSwapAsyncWSClient, KuCoinSwapWSClient);
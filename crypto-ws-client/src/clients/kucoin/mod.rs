@@ -2,5 +2,5 @@ mod kucoin_spot;
 mod kucoin_swap;
 mod utils;
 
-pub use kucoin_spot::KuCoinSpotWSClient;
-pub use kucoin_swap::KuCoinSwapWSClient;
+pub use kucoin_spot::{KuCoinSpotAsyncWSClient, KuCoinSpotWSClient};
+pub use kucoin_swap::{KuCoinSwapAsyncWSClient, KuCoinSwapWSClient};
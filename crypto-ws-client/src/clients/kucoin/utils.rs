@@ -4,7 +4,9 @@ use log::*;
 use reqwest::{header, Result};
 use serde_json::Value;
 
-use crate::clients::{utils::CHANNEL_PAIR_DELIMITER, ws_client_internal::MiscMessage};
+use crate::clients::{
+    auth::run_blocking, request, utils::CHANNEL_PAIR_DELIMITER, ws_client_internal::MiscMessage,
+};
 
 pub(super) const EXCHANGE_NAME: &str = "kucoin";
 
@@ -18,6 +20,10 @@ pub(super) const CLIENT_PING_INTERVAL_AND_MSG: (u64, &str) =
 // See https://docs.kucoin.cc/#request-rate-limit
 const MAX_SUBSCRIPTIONS_PER_TIME: usize = 100;
 
+// Connections go to the instance server returned with each token, this url
+// only identifies the endpoint in logs and events.
+pub(super) const WEBSOCKET_URL: &str = "wss://ws-api.kucoin.com/endpoint";
+
 pub(super) struct WebsocketToken {
     pub token: String,
     pub endpoint: String,
@@ -25,48 +31,77 @@ pub(super) struct WebsocketToken {
 }
 
 fn http_post(url: &str) -> Result<String> {
-    let mut headers = header::HeaderMap::new();
-    headers.insert(
-        header::CONTENT_TYPE,
-        header::HeaderValue::from_static("application/json"),
-    );
-
-    let client = reqwest::blocking::Client::builder()
-         .default_headers(headers)
-         .user_agent("Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/87.0.4280.88 Safari/537.36")
-         .gzip(true)
-         .build()?;
-    let response = client.post(url).send()?;
-
-    match response.error_for_status() {
-        Ok(resp) => Ok(resp.text()?),
-        Err(error) => Err(error),
-    }
+    let url = url.to_string();
+    // Tokens are fetched when a client is created, which may be in a runtime
+    run_blocking(move || {
+        let mut headers = header::HeaderMap::new();
+        headers.insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_static("application/json"),
+        );
+
+        let client = reqwest::blocking::Client::builder()
+             .default_headers(headers)
+             .user_agent("Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/87.0.4280.88 Safari/537.36")
+             .gzip(true)
+             .build()?;
+        let response = client.post(url).send()?;
+
+        match response.error_for_status() {
+            Ok(resp) => Ok(resp.text()?),
+            Err(error) => Err(error),
+        }
+    })
 }
 
 // See <https://docs.kucoin.com/#apply-connect-token>
-pub(super) fn fetch_ws_token() -> WebsocketToken {
-    let txt = http_post("https://openapi-v2.kucoin.com/api/v1/bullet-public").unwrap();
-    let obj = serde_json::from_str::<HashMap<String, Value>>(&txt).unwrap();
-    let code = obj.get("code").unwrap().as_str().unwrap();
+pub(super) fn fetch_ws_token() -> std::result::Result<WebsocketToken, String> {
+    let txt = http_post("https://openapi-v2.kucoin.com/api/v1/bullet-public")
+        .map_err(|err| format!("Failed to get token, {}", err))?;
+    let invalid = || format!("Failed to get token, {}", txt);
+    let obj = serde_json::from_str::<HashMap<String, Value>>(&txt).map_err(|_| invalid())?;
+    let code = obj
+        .get("code")
+        .and_then(|x| x.as_str())
+        .ok_or_else(invalid)?;
     if code != "200000" {
-        panic!("Failed to get token, code is {}", code);
+        return Err(format!("Failed to get token, code is {}", code));
     }
-    let data = obj.get("data").unwrap().as_object().unwrap();
-    let token = data.get("token").unwrap().as_str().unwrap();
-    let servers = data.get("instanceServers").unwrap().as_array().unwrap();
-    let server = servers[0].as_object().unwrap();
+    let data = obj
+        .get("data")
+        .and_then(|x| x.as_object())
+        .ok_or_else(invalid)?;
+    let token = data
+        .get("token")
+        .and_then(|x| x.as_str())
+        .ok_or_else(invalid)?;
+    let server = data
+        .get("instanceServers")
+        .and_then(|x| x.as_array())
+        .and_then(|servers| servers.first())
+        .and_then(|x| x.as_object())
+        .ok_or_else(invalid)?;
 
-    WebsocketToken {
+    Ok(WebsocketToken {
         token: token.to_string(),
         endpoint: server
             .get("endpoint")
-            .unwrap()
-            .as_str()
-            .unwrap()
+            .and_then(|x| x.as_str())
+            .ok_or_else(invalid)?
             .to_string(),
-        ping_interval: server.get("pingInterval").unwrap().as_i64().unwrap(),
-    }
+        ping_interval: server
+            .get("pingInterval")
+            .and_then(|x| x.as_i64())
+            .ok_or_else(invalid)?,
+    })
+}
+
+// Applies for a new token and returns the url to connect to, since tokens
+// expire, it is called before every connection, see `UrlProvider`.
+pub(super) fn fetch_ws_url() -> std::io::Result<String> {
+    let ws_token =
+        fetch_ws_token().map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+    Ok(format!("{}?token={}", ws_token.endpoint, ws_token.token))
 }
 
 pub(super) fn on_misc_msg(msg: &str) -> MiscMessage {
@@ -74,18 +109,50 @@ pub(super) fn on_misc_msg(msg: &str) -> MiscMessage {
     let msg_type = obj.get("type").unwrap().as_str().unwrap();
     match msg_type {
         "pong" => MiscMessage::Pong,
-        "welcome" | "ack" => {
+        "welcome" => {
             debug!("Received {} from {}", msg, EXCHANGE_NAME);
             MiscMessage::Misc
         }
+        "ack" => {
+            // {"id":"1","type":"ack"}, the id of the command
+            debug!("Received {} from {}", msg, EXCHANGE_NAME);
+            match request_id(&obj) {
+                Some(id) => request::on_ack(id),
+                None => MiscMessage::Misc,
+            }
+        }
         "notice" | "command" => {
             info!("Received {} from {}", msg, EXCHANGE_NAME);
             MiscMessage::Misc
         }
         "message" => MiscMessage::Normal,
         "error" => {
+            // {"id":"crypto-ws-client","type":"error","code":404,"data":"topic /market/match:BTC-USDT1 is not found"}
             error!("Received {} from {}", msg, EXCHANGE_NAME);
-            panic!("Received {} from {}", msg, EXCHANGE_NAME);
+            let code = obj.get("code").and_then(|x| {
+                x.as_i64()
+                    .or_else(|| x.as_str().and_then(|x| x.parse::<i64>().ok()))
+            });
+            let data = obj.get("data").and_then(|x| x.as_str()).unwrap_or(msg);
+            match request::on_error(request_id(&obj), code, data) {
+                // a topic with multiple pairs doesn't tell which pair is invalid
+                MiscMessage::Rejected {
+                    channels,
+                    code,
+                    reason,
+                } if channels.is_empty() => MiscMessage::Rejected {
+                    channels: match data
+                        .strip_prefix("topic ")
+                        .and_then(|x| x.strip_suffix(" is not found"))
+                    {
+                        Some(topic) if !topic.contains(',') => vec![topic.to_string()],
+                        _ => Vec::new(),
+                    },
+                    code,
+                    reason,
+                },
+                other => other,
+            }
         }
         _ => {
             error!("Received {} from {}", msg, EXCHANGE_NAME);
@@ -94,9 +161,21 @@ pub(super) fn on_misc_msg(msg: &str) -> MiscMessage {
     }
 }
 
+// The id of the command which an ack or error answers
+fn request_id(obj: &HashMap<String, Value>) -> Option<u64> {
+    obj.get("id")
+        .and_then(|x| x.as_str())
+        .and_then(|x| x.parse::<u64>().ok())
+}
+
 fn channel_pairs_to_command(channel: &str, pairs: &[String], subscribe: bool) -> String {
+    let raw_channels: Vec<String> = pairs
+        .iter()
+        .map(|pair| to_raw_channel(channel, pair))
+        .collect();
     format!(
-        r#"{{"id":"crypto-ws-client","type":"{}","topic":"{}:{}","privateChannel":false,"response":true}}"#,
+        r#"{{"id":"{}","type":"{}","topic":"{}:{}","privateChannel":false,"response":true}}"#,
+        request::register(&raw_channels, subscribe),
         if subscribe {
             "subscribe"
         } else {
@@ -150,9 +229,46 @@ pub(super) fn to_raw_channel(channel: &str, pair: &str) -> String {
 
 #[cfg(test)]
 mod tests {
+    use super::super::super::request::Requests;
+    use super::super::super::ws_client_internal::MiscMessage;
+    use serde_json::Value;
+
+    #[test]
+    fn test_ack() {
+        // ids are registered to the requests of a client
+        Requests::default().scope(|| {
+            let commands = super::channels_to_commands(
+                &[
+                    "/market/match:BTC-USDT".to_string(),
+                    "/market/match:ETH-USDT".to_string(),
+                ],
+                true,
+            );
+            assert_eq!(1, commands.len());
+            let command = serde_json::from_str::<Value>(&commands[0]).unwrap();
+            let id = command["id"].as_str().unwrap();
+
+            let error = format!(
+                r#"{{"id":"{}","type":"error","code":404,"data":"topic /market/match:BTC-USDT,ETH-USDT is not found"}}"#,
+                id
+            );
+            assert!(matches!(
+                super::on_misc_msg(&error),
+                MiscMessage::Rejected { channels, code: Some(404), .. } if channels.len() == 2
+            ));
+
+            // an error of an unknown command tells the topic only
+            let error = r#"{"id":"crypto-ws-client","type":"error","code":404,"data":"topic /market/match:BTC-USDT1 is not found"}"#;
+            assert!(matches!(
+                super::on_misc_msg(error),
+                MiscMessage::Rejected { channels, .. } if channels == vec!["/market/match:BTC-USDT1".to_string()]
+            ));
+        });
+    }
+
     #[test]
     fn fetch_ws_token() {
-        let ws_token = super::fetch_ws_token();
+        let ws_token = super::fetch_ws_token().unwrap();
         assert!(!ws_token.token.is_empty())
     }
 }
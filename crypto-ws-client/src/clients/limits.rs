@@ -0,0 +1,80 @@
+/// Limits of a single WebSocket connection imposed by an exchange.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConnectionLimits {
+    /// Maximum number of channels per connection
+    pub max_channels: usize,
+    /// Maximum number of messages a client can send per second, None means unlimited
+    pub max_messages_per_second: Option<u32>,
+}
+
+impl Default for ConnectionLimits {
+    fn default() -> Self {
+        ConnectionLimits {
+            max_channels: usize::MAX,
+            max_messages_per_second: None,
+        }
+    }
+}
+
+// Limits of a connection to `url` of an exchange.
+pub(super) fn connection_limits(exchange: &str, url: &str) -> ConnectionLimits {
+    match exchange {
+        // see https://binance-docs.github.io/apidocs/spot/en/#websocket-limits
+        // and https://binance-docs.github.io/apidocs/futures/en/#websocket-market-streams
+        "binance" => {
+            if url.contains("fstream") || url.contains("dstream") {
+                ConnectionLimits {
+                    max_channels: 200,
+                    max_messages_per_second: Some(10),
+                }
+            } else {
+                ConnectionLimits {
+                    max_channels: 1024,
+                    max_messages_per_second: Some(5),
+                }
+            }
+        }
+        // see https://docs.bitfinex.com/docs/ws-general#subscribe-to-channels,
+        // the message rate is not documented, bursts of subscriptions are throttled
+        "bitfinex" => ConnectionLimits {
+            max_channels: 30,
+            max_messages_per_second: Some(5),
+        },
+        "bitmex" => ConnectionLimits {
+            max_channels: 40,
+            max_messages_per_second: None,
+        },
+        // see https://docs.kucoin.com/#request-rate-limit
+        "kucoin" => ConnectionLimits {
+            max_channels: 300,
+            max_messages_per_second: Some(10),
+        },
+        // the message rate is not documented, bursts of subscriptions are throttled
+        "okex" => ConnectionLimits {
+            max_channels: 256,
+            max_messages_per_second: Some(5),
+        },
+        _ => ConnectionLimits::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::connection_limits;
+
+    #[test]
+    fn test_binance() {
+        assert_eq!(
+            1024,
+            connection_limits("binance", "wss://stream.binance.com:9443/stream").max_channels
+        );
+        assert_eq!(
+            200,
+            connection_limits("binance", "wss://fstream.binance.com/stream").max_channels
+        );
+        assert_eq!(
+            usize::MAX,
+            connection_limits("huobi", "wss://api.huobi.pro/ws").max_channels
+        );
+    }
+}
@@ -0,0 +1,302 @@
+use std::{
+    ops::Deref,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use crossbeam_channel::{bounded, Receiver, SendTimeoutError, TryRecvError, TrySendError};
+use futures_util::{stream::Stream, task::AtomicWaker};
+
+/// What to do with a new message when the channel is full.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BackpressurePolicy {
+    /// Block the network thread until the consumer catches up
+    Block,
+    /// Drop the oldest message in the channel to make room for the new one
+    DropOldest,
+    /// Drop the new message
+    DropNewest,
+}
+
+// State shared by both halves of a channel
+struct Shared {
+    policy: BackpressurePolicy,
+    num_dropped: AtomicU64,
+    closed: AtomicBool, // the receiving half was dropped
+    waker: AtomicWaker, // wakes up MessageStream
+}
+
+/// The sending half of a bounded message channel, cheap to clone.
+pub struct MessageSender<T> {
+    tx: Option<crossbeam_channel::Sender<T>>, // None only in drop()
+    rx: Receiver<T>,                          // used to drop the oldest message
+    shared: Arc<Shared>,
+}
+
+impl<T> Clone for MessageSender<T> {
+    fn clone(&self) -> Self {
+        MessageSender {
+            tx: self.tx.clone(),
+            rx: self.rx.clone(),
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> MessageSender<T> {
+    /// Sends a message following the backpressure policy.
+    ///
+    /// Returns false if the receiving half was dropped.
+    pub fn send(&self, msg: T) -> bool {
+        if self.shared.closed.load(Ordering::Acquire) {
+            return false;
+        }
+        let tx = self.tx.as_ref().unwrap();
+        let sent = match self.shared.policy {
+            BackpressurePolicy::Block => {
+                let mut msg = msg;
+                loop {
+                    // wake up periodically in case the receiver was dropped
+                    match tx.send_timeout(msg, Duration::from_millis(100)) {
+                        Ok(()) => break true,
+                        Err(SendTimeoutError::Timeout(m)) => {
+                            if self.shared.closed.load(Ordering::Acquire) {
+                                break false;
+                            }
+                            msg = m;
+                        }
+                        Err(SendTimeoutError::Disconnected(_)) => break false,
+                    }
+                }
+            }
+            BackpressurePolicy::DropNewest => match tx.try_send(msg) {
+                Ok(()) => true,
+                Err(TrySendError::Full(_)) => {
+                    self.shared.num_dropped.fetch_add(1, Ordering::Relaxed);
+                    true
+                }
+                Err(TrySendError::Disconnected(_)) => false,
+            },
+            BackpressurePolicy::DropOldest => {
+                let mut msg = msg;
+                loop {
+                    match tx.try_send(msg) {
+                        Ok(()) => break true,
+                        Err(TrySendError::Full(m)) => {
+                            if self.rx.try_recv().is_ok() {
+                                self.shared.num_dropped.fetch_add(1, Ordering::Relaxed);
+                            }
+                            msg = m;
+                        }
+                        Err(TrySendError::Disconnected(_)) => break false,
+                    }
+                }
+            }
+        };
+        self.shared.waker.wake();
+        sent
+    }
+
+    /// Number of messages dropped because the channel was full.
+    pub fn num_dropped(&self) -> u64 {
+        self.shared.num_dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl<T> Drop for MessageSender<T> {
+    fn drop(&mut self) {
+        // disconnect first, so that a woken up MessageStream sees the end
+        self.tx = None;
+        self.shared.waker.wake();
+    }
+}
+
+/// The receiving half of a bounded message channel.
+///
+/// It dereferences to a `crossbeam_channel::Receiver`, so `recv()`,
+/// `try_recv()`, `iter()`, etc. are all available. The channel is
+/// disconnected after the client or crawler feeding it exits.
+pub struct MessageReceiver<T> {
+    rx: Receiver<T>,
+    shared: Arc<Shared>,
+}
+
+impl<T> MessageReceiver<T> {
+    /// Number of messages dropped because the channel was full.
+    pub fn num_dropped(&self) -> u64 {
+        self.shared.num_dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl<T> Deref for MessageReceiver<T> {
+    type Target = Receiver<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.rx
+    }
+}
+
+impl<T> Drop for MessageReceiver<T> {
+    fn drop(&mut self) {
+        self.shared.closed.store(true, Ordering::Release);
+    }
+}
+
+/// The receiving half of a bounded message channel as an async `Stream`.
+///
+/// The stream ends after the client or crawler feeding it exits.
+pub struct MessageStream<T> {
+    rx: Receiver<T>,
+    shared: Arc<Shared>,
+}
+
+impl<T> MessageStream<T> {
+    /// Number of messages dropped because the channel was full.
+    pub fn num_dropped(&self) -> u64 {
+        self.shared.num_dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl<T> Stream for MessageStream<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        match self.rx.try_recv() {
+            Ok(msg) => return Poll::Ready(Some(msg)),
+            Err(TryRecvError::Disconnected) => return Poll::Ready(None),
+            Err(TryRecvError::Empty) => (),
+        }
+        self.shared.waker.register(cx.waker());
+        // check again in case a message arrived before the waker was registered
+        match self.rx.try_recv() {
+            Ok(msg) => Poll::Ready(Some(msg)),
+            Err(TryRecvError::Disconnected) => Poll::Ready(None),
+            Err(TryRecvError::Empty) => Poll::Pending,
+        }
+    }
+}
+
+impl<T> Drop for MessageStream<T> {
+    fn drop(&mut self) {
+        self.shared.closed.store(true, Ordering::Release);
+    }
+}
+
+fn new_shared(capacity: usize, policy: BackpressurePolicy) -> Arc<Shared> {
+    assert!(capacity > 0, "capacity must be positive");
+    Arc::new(Shared {
+        policy,
+        num_dropped: AtomicU64::new(0),
+        closed: AtomicBool::new(false),
+        waker: AtomicWaker::new(),
+    })
+}
+
+/// Creates a bounded channel which holds at most `capacity` messages.
+pub fn message_channel<T>(
+    capacity: usize,
+    policy: BackpressurePolicy,
+) -> (MessageSender<T>, MessageReceiver<T>) {
+    let shared = new_shared(capacity, policy);
+    let (tx, rx) = bounded(capacity);
+    (
+        MessageSender {
+            tx: Some(tx),
+            rx: rx.clone(),
+            shared: shared.clone(),
+        },
+        MessageReceiver { rx, shared },
+    )
+}
+
+/// Creates a bounded channel which holds at most `capacity` messages,
+/// with an async `Stream` as the receiving half.
+///
+/// With `BackpressurePolicy::Block`, a full channel blocks the thread of the
+/// sender, so do not run the sender and the stream on the same thread. For
+/// this reason `AsyncWSClient::new_with_stream()` rejects it.
+pub fn message_stream<T>(
+    capacity: usize,
+    policy: BackpressurePolicy,
+) -> (MessageSender<T>, MessageStream<T>) {
+    let shared = new_shared(capacity, policy);
+    let (tx, rx) = bounded(capacity);
+    (
+        MessageSender {
+            tx: Some(tx),
+            rx: rx.clone(),
+            shared: shared.clone(),
+        },
+        MessageStream { rx, shared },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{message_channel, message_stream, BackpressurePolicy};
+    use futures_util::StreamExt;
+
+    #[test]
+    fn test_drop_newest() {
+        let (tx, rx) = message_channel(2, BackpressurePolicy::DropNewest);
+        for i in 0..5 {
+            assert!(tx.send(i));
+        }
+        assert_eq!(3, rx.num_dropped());
+        assert_eq!(vec![0, 1], rx.try_iter().collect::<Vec<i32>>());
+    }
+
+    #[test]
+    fn test_drop_oldest() {
+        let (tx, rx) = message_channel(2, BackpressurePolicy::DropOldest);
+        for i in 0..5 {
+            assert!(tx.send(i));
+        }
+        assert_eq!(3, tx.num_dropped());
+        assert_eq!(vec![3, 4], rx.try_iter().collect::<Vec<i32>>());
+    }
+
+    #[test]
+    fn test_block() {
+        let (tx, rx) = message_channel(1, BackpressurePolicy::Block);
+        let handle = std::thread::spawn(move || {
+            for i in 0..10 {
+                assert!(tx.send(i));
+            }
+        });
+        assert_eq!(
+            (0..10).collect::<Vec<i32>>(),
+            rx.iter().collect::<Vec<i32>>()
+        );
+        handle.join().unwrap();
+        assert_eq!(0, rx.num_dropped());
+    }
+
+    #[test]
+    fn test_receiver_dropped() {
+        let (tx, rx) = message_channel(1, BackpressurePolicy::Block);
+        assert!(tx.send(0));
+        drop(rx);
+        assert!(!tx.send(1)); // returns instead of blocking forever
+    }
+
+    #[tokio::test]
+    async fn test_stream() {
+        let (tx, mut stream) = message_stream(16, BackpressurePolicy::DropNewest);
+        std::thread::spawn(move || {
+            for i in 0..3 {
+                tx.send(i);
+            }
+        });
+        let mut messages = Vec::new();
+        while let Some(msg) = stream.next().await {
+            messages.push(msg);
+        }
+        assert_eq!(vec![0, 1, 2], messages);
+    }
+}
@@ -0,0 +1,274 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use serde_json::Value;
+use tungstenite::Message;
+
+/// Health metrics of a client, see `WSClient::metrics()`.
+///
+/// Counters start from zero when the client is created and survive
+/// reconnects.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Metrics {
+    /// Normal messages passed to `on_msg`, per channel.
+    ///
+    /// The channel is read from the message, for example, `stream` of
+    /// Binance, `ch` of Huobi and `table` of BitMEX, messages without a
+    /// recognizable channel are counted as `unknown`.
+    pub messages: HashMap<String, u64>,
+    /// Bytes of all frames received, before decompression
+    pub bytes: u64,
+    /// Binary frames which failed to decompress
+    pub decompression_failures: u64,
+    /// Successful reconnects
+    pub reconnects: u64,
+    /// Client pings without a pong so far
+    pub unanswered_pings: isize,
+    /// Reads which timed out without receiving anything
+    pub read_timeouts: u64,
+    /// Time since the last frame was received, None if nothing was received
+    pub last_message_age: Option<Duration>,
+    /// Moving average of the local time minus the exchange timestamp of
+    /// messages, in milliseconds, None if messages have no timestamps.
+    ///
+    /// It includes the clock offset between the exchange and the local
+    /// machine, thus may be negative.
+    pub latency_millis: Option<i64>,
+}
+
+impl Metrics {
+    // Adds up metrics of another connection, see `WSClientPool`.
+    pub(super) fn merge(&mut self, other: Metrics) {
+        for (channel, count) in other.messages {
+            *self.messages.entry(channel).or_insert(0) += count;
+        }
+        self.bytes += other.bytes;
+        self.decompression_failures += other.decompression_failures;
+        self.reconnects += other.reconnects;
+        self.unanswered_pings = self.unanswered_pings.max(other.unanswered_pings);
+        self.read_timeouts += other.read_timeouts;
+        self.last_message_age = match (self.last_message_age, other.last_message_age) {
+            (Some(x), Some(y)) => Some(x.min(y)),
+            (x, y) => x.or(y),
+        };
+        self.latency_millis = match (self.latency_millis, other.latency_millis) {
+            (Some(x), Some(y)) => Some((x + y) / 2),
+            (x, y) => x.or(y),
+        };
+    }
+}
+
+// Weight of the latest sample in the moving average of latency
+const LATENCY_ALPHA: f64 = 0.1;
+
+// Keys of channels in messages, in order of precedence
+const CHANNEL_KEYS: &[&str] = &[
+    "stream", "ch", "topic", "table", "channel", "subject", "method", "action", "type",
+];
+
+// Keys of exchange timestamps in messages, in order of precedence
+const TIMESTAMP_KEYS: &[&str] = &["E", "ts", "T", "time", "timestamp"];
+
+#[derive(Default)]
+struct State {
+    messages: HashMap<String, u64>,
+    bytes: u64,
+    decompression_failures: u64,
+    reconnects: u64,
+    read_timeouts: u64,
+    last_frame: Option<Instant>,
+    latency_millis: Option<f64>,
+}
+
+// Collects metrics of a connection, updated by the run loop.
+#[derive(Default)]
+pub(super) struct MetricsCollector {
+    state: Mutex<State>,
+}
+
+impl MetricsCollector {
+    // Called for every frame received
+    pub fn on_frame(&self, msg: &Message) {
+        let mut state = self.state.lock().unwrap();
+        state.bytes += msg.len() as u64;
+        state.last_frame = Some(Instant::now());
+    }
+
+    // Called for every normal message passed to on_msg
+    pub fn on_normal_msg(&self, txt: &str) {
+        let (channel, timestamp) = match serde_json::from_str::<Value>(txt) {
+            Ok(value) => (channel_of(&value), timestamp_of(&value)),
+            Err(_) => (None, None),
+        };
+        let mut state = self.state.lock().unwrap();
+        *state
+            .messages
+            .entry(channel.unwrap_or_else(|| "unknown".to_string()))
+            .or_insert(0) += 1;
+        if let Some(timestamp) = timestamp {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as f64;
+            let sample = now - timestamp;
+            state.latency_millis = Some(match state.latency_millis {
+                Some(avg) => avg + LATENCY_ALPHA * (sample - avg),
+                None => sample,
+            });
+        }
+    }
+
+    pub fn on_decompression_failure(&self) {
+        self.state.lock().unwrap().decompression_failures += 1;
+    }
+
+    pub fn on_reconnected(&self) {
+        self.state.lock().unwrap().reconnects += 1;
+    }
+
+    pub fn on_read_timeout(&self) {
+        self.state.lock().unwrap().read_timeouts += 1;
+    }
+
+    pub fn snapshot(&self, unanswered_pings: isize) -> Metrics {
+        let state = self.state.lock().unwrap();
+        Metrics {
+            messages: state.messages.clone(),
+            bytes: state.bytes,
+            decompression_failures: state.decompression_failures,
+            reconnects: state.reconnects,
+            unanswered_pings,
+            read_timeouts: state.read_timeouts,
+            last_message_age: state.last_frame.map(|x| x.elapsed()),
+            latency_millis: state.latency_millis.map(|x| x.round() as i64),
+        }
+    }
+}
+
+// Finds the channel of a message, e.g., `btcusdt@aggTrade` of Binance.
+fn channel_of(value: &Value) -> Option<String> {
+    if let Some(Value::Object(meta)) = value.as_array().and_then(|arr| arr.first()) {
+        // Bitfinex prepends channel info to arrays
+        if let (Some(channel), Some(symbol)) = (meta.get("channel"), meta.get("symbol")) {
+            return Some(format!("{}:{}", channel.as_str()?, symbol.as_str()?));
+        }
+    }
+    let obj = value.as_object()?;
+    // Deribit and other JSON-RPC exchanges
+    obj.get("params")
+        .and_then(|x| x.get("channel"))
+        .and_then(|x| x.as_str())
+        .or_else(|| {
+            CHANNEL_KEYS
+                .iter()
+                .find_map(|key| obj.get(*key).and_then(|x| x.as_str()))
+        })
+        .map(|x| x.to_string())
+}
+
+// Finds the exchange timestamp of a message in milliseconds, in the message
+// itself or its `data`.
+fn timestamp_of(value: &Value) -> Option<f64> {
+    let data = value
+        .get("data")
+        .or_else(|| value.get("params").and_then(|x| x.get("data")));
+    let data = match data {
+        Some(Value::Array(arr)) => arr.first(),
+        x => x,
+    };
+    std::iter::once(value)
+        .chain(data)
+        .filter_map(|x| x.as_object())
+        .find_map(|obj| {
+            TIMESTAMP_KEYS
+                .iter()
+                .find_map(|key| obj.get(*key).and_then(|x| x.as_f64()))
+        })
+        .and_then(to_millis)
+}
+
+// Converts an epoch timestamp in seconds, milliseconds, microseconds or
+// nanoseconds to milliseconds.
+fn to_millis(timestamp: f64) -> Option<f64> {
+    if timestamp >= 1e18 {
+        Some(timestamp / 1e6)
+    } else if timestamp >= 1e15 {
+        Some(timestamp / 1e3)
+    } else if timestamp >= 1e12 {
+        Some(timestamp)
+    } else if timestamp >= 1e9 {
+        Some(timestamp * 1e3)
+    } else {
+        None // not an epoch timestamp
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{channel_of, timestamp_of, Metrics, MetricsCollector};
+    use serde_json::Value;
+    use std::time::Duration;
+    use tungstenite::Message;
+
+    #[test]
+    fn test_channel_and_timestamp() {
+        let binance: Value = serde_json::from_str(r#"{"stream":"btcusdt@aggTrade","data":{"e":"aggTrade","E":1616651250071,"s":"BTCUSDT","p":"52000.01"}}"#).unwrap();
+        assert_eq!(Some("btcusdt@aggTrade".to_string()), channel_of(&binance));
+        assert_eq!(Some(1616651250071.0), timestamp_of(&binance));
+
+        let deribit: Value = serde_json::from_str(r#"{"jsonrpc":"2.0","method":"subscription","params":{"channel":"trades.BTC-PERPETUAL.raw","data":[{"timestamp":1616651250071,"price":52000.5}]}}"#).unwrap();
+        assert_eq!(
+            Some("trades.BTC-PERPETUAL.raw".to_string()),
+            channel_of(&deribit)
+        );
+        assert_eq!(Some(1616651250071.0), timestamp_of(&deribit));
+
+        let kraken: Value = serde_json::from_str(
+            r#"[0,[["52000.1","0.01","1616651250.071","b","l",""]],"trade","XBT/USD"]"#,
+        )
+        .unwrap();
+        assert_eq!(None, channel_of(&kraken));
+
+        let bitfinex: Value = serde_json::from_str(
+            r#"[{"channel":"trades","symbol":"tBTCUSD"},"te",[1,1616651250071,0.01,52000]]"#,
+        )
+        .unwrap();
+        assert_eq!(Some("trades:tBTCUSD".to_string()), channel_of(&bitfinex));
+        assert_eq!(None, timestamp_of(&kraken));
+
+        let bybit: Value = serde_json::from_str(r#"{"topic":"trade.BTCUSD","data":[{"trade_time_ms":1616651250071,"timestamp":"2021-03-25T05:47:30.000Z"}]}"#).unwrap();
+        assert_eq!(Some("trade.BTCUSD".to_string()), channel_of(&bybit));
+        assert_eq!(None, timestamp_of(&bybit));
+    }
+
+    #[test]
+    fn test_collector() {
+        let collector = MetricsCollector::default();
+        assert_eq!(Metrics::default(), collector.snapshot(0));
+
+        collector.on_frame(&Message::Text("hello".to_string()));
+        collector.on_normal_msg(r#"{"ch":"market.btcusdt.trade.detail","ts":1616651250071}"#);
+        collector.on_normal_msg(r#"{"ch":"market.btcusdt.trade.detail","ts":1616651250071}"#);
+        collector.on_normal_msg("not json");
+        collector.on_decompression_failure();
+        collector.on_reconnected();
+        collector.on_read_timeout();
+
+        let metrics = collector.snapshot(2);
+        assert_eq!(
+            Some(&2),
+            metrics.messages.get("market.btcusdt.trade.detail")
+        );
+        assert_eq!(Some(&1), metrics.messages.get("unknown"));
+        assert_eq!(5, metrics.bytes);
+        assert_eq!(1, metrics.decompression_failures);
+        assert_eq!(1, metrics.reconnects);
+        assert_eq!(2, metrics.unanswered_pings);
+        assert_eq!(1, metrics.read_timeouts);
+        assert!(metrics.last_message_age.unwrap() < Duration::from_secs(60));
+        assert!(metrics.latency_millis.unwrap() > 0);
+    }
+}
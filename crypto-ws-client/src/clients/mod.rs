@@ -1,13 +1,14 @@
-#[macro_use]
 mod common_traits;
 
 #[macro_use]
 mod ws_client_internal;
 
+mod async_loop;
 mod utils;
 
 use common_traits::*;
 
+pub(super) mod auth;
 pub(super) mod binance;
 pub(super) mod binance_option;
 pub(super) mod bitfinex;
@@ -17,13 +18,33 @@ pub(super) mod bitmex;
 pub(super) mod bitstamp;
 pub(super) mod bitz;
 pub(super) mod bybit;
+pub(super) mod channel;
 pub(super) mod coinbase_pro;
 pub(super) mod deribit;
+pub(super) mod endpoint;
+pub(super) mod error;
+pub(super) mod event;
+pub(super) mod factory;
 pub(super) mod ftx;
 pub(super) mod gate;
+pub(super) mod gemini;
+pub(super) mod heartbeat;
 pub(super) mod huobi;
 pub(super) mod kraken;
 pub(super) mod kucoin;
+pub(super) mod limits;
+pub(super) mod message_channel;
+pub(super) mod metrics;
 pub(super) mod mxc;
 pub(super) mod okex;
+pub(super) mod options;
+pub(super) mod poloniex;
+pub(super) mod pool;
+pub(super) mod proxy;
+pub(super) mod rate_limit;
+pub(super) mod reconnect;
+pub(super) mod recorder;
+pub(super) mod request;
+pub(super) mod subscription;
+pub(super) mod upbit;
 pub(super) mod zbg;
@@ -1,12 +1,12 @@
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
-use crate::WSClient;
+use crate::{Channel, ChannelKind, WSClient, WsError};
 
 use super::{
     utils::CHANNEL_PAIR_DELIMITER,
     ws_client_internal::{MiscMessage, WSClientInternal},
-    Candlestick, OrderBook, OrderBookSnapshot, Ticker, Trade, BBO,
+    ChannelTranslator,
 };
 
 use log::*;
@@ -139,6 +139,13 @@ fn on_misc_msg(msg: &str) -> MiscMessage {
                 _ => {
                     if obj.contains_key("symbol") && channel.starts_with("push.") {
                         MiscMessage::Normal
+                    } else if channel.starts_with("rs.sub.")
+                        && obj.get("data").and_then(|x| x.as_str()) == Some("success")
+                    {
+                        // {"channel":"rs.sub.deal","data":"success","ts":1616651250071},
+                        // the ack doesn't tell which symbol
+                        debug!("Received {} from {}", msg, EXCHANGE_NAME);
+                        MiscMessage::SubscribedPending
                     } else {
                         info!("Received {} from {}", msg, EXCHANGE_NAME);
                         MiscMessage::Misc
@@ -156,89 +163,89 @@ fn to_raw_channel(channel: &str, pair: &str) -> String {
     format!("{}{}{}", channel, CHANNEL_PAIR_DELIMITER, pair)
 }
 
-#[rustfmt::skip]
-impl_trait!(Trade, MxcSpotWSClient, subscribe_trade, "symbol", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(Trade, MxcSwapWSClient, subscribe_trade, "deal", to_raw_channel);
-
-impl<'a> Ticker for MxcSpotWSClient<'a> {
-    fn subscribe_ticker(&self, _pairs: &[String]) {
-        panic!("MXC Spot WebSocket does NOT have ticker channel");
-    }
-}
-#[rustfmt::skip]
-impl_trait!(Ticker, MxcSwapWSClient, subscribe_ticker, "ticker", to_raw_channel);
-
-#[rustfmt::skip]
-impl_trait!(OrderBook, MxcSpotWSClient, subscribe_orderbook, "symbol", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(OrderBook, MxcSwapWSClient, subscribe_orderbook, "depth", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(OrderBookSnapshot, MxcSpotWSClient, subscribe_orderbook_snapshot, "get.depth", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(OrderBookSnapshot, MxcSwapWSClient, subscribe_orderbook_snapshot, "depth.full", to_raw_channel);
-
-impl<'a> BBO for MxcSpotWSClient<'a> {
-    fn subscribe_bbo(&self, _pairs: &[String]) {
-        panic!("MXC Spot WebSocket does NOT have BBO channel");
-    }
-}
-impl<'a> BBO for MxcSwapWSClient<'a> {
-    fn subscribe_bbo(&self, _pairs: &[String]) {
-        panic!("MXC Swap WebSocket does NOT have BBO channel");
+fn interval_to_string(interval: u32) -> Option<&'static str> {
+    match interval {
+        60 => Some("Min1"),
+        300 => Some("Min5"),
+        900 => Some("Min15"),
+        1800 => Some("Min30"),
+        3600 => Some("Min60"),
+        14400 => Some("Hour4"),
+        28800 => Some("Hour8"),
+        86400 => Some("Day1"),
+        604800 => Some("Week1"),
+        2592000 => Some("Month1"),
+        _ => None,
     }
 }
 
-fn interval_to_string(interval: u32) -> String {
-    let tmp = match interval {
-        60 => "Min1",
-        300 => "Min5",
-        900 => "Min15",
-        1800 => "Min30",
-        3600 => "Min60",
-        14400 => "Hour4",
-        28800 => "Hour8",
-        86400 => "Day1",
-        604800 => "Week1",
-        2592000 => "Month1",
-        _ => panic!("MXC has intervals Min1,Min5,Min15,Min30,Min60,Hour4,Hour8,Day1,Week1,Month1"),
-    };
-    tmp.to_string()
-}
-
-impl<'a> Candlestick for MxcSpotWSClient<'a> {
-    fn subscribe_candlestick(&self, pairs: &[String], interval: u32) {
-        let interval_str = interval_to_string(interval);
-
-        let channels = pairs
-            .iter()
-            .map(|pair| {
-                format!(
+// MXC Spot WebSocket does NOT have ticker and BBO channels
+impl<'a> ChannelTranslator for MxcSpotWSClient<'a> {
+    fn translate(&self, channel: &Channel) -> Result<String, WsError> {
+        match channel {
+            Channel::Trade(pair) => Some(to_raw_channel("symbol", pair)),
+            Channel::L2 {
+                pair,
+                depth: None,
+                speed: None,
+            } => Some(to_raw_channel("symbol", pair)),
+            Channel::L2Snapshot(pair) => Some(to_raw_channel("get.depth", pair)),
+            Channel::Kline { pair, interval } => {
+                let interval_str = interval_to_string(*interval)
+                    .ok_or_else(|| WsError::invalid_interval(EXCHANGE_NAME, *interval))?;
+                Some(format!(
                     r#"["sub.kline",{{"symbol":"{}","interval":"{}"}}]"#,
                     pair, interval_str
-                )
-            })
-            .collect::<Vec<String>>();
+                ))
+            }
+            _ => None,
+        }
+        .ok_or_else(|| WsError::unsupported(EXCHANGE_NAME, channel))
+    }
 
-        self.client.subscribe(&channels);
+    fn capabilities(&self) -> Vec<ChannelKind> {
+        vec![
+            ChannelKind::Trade,
+            ChannelKind::L2,
+            ChannelKind::L2Snapshot,
+            ChannelKind::Kline,
+        ]
     }
 }
 
-impl<'a> Candlestick for MxcSwapWSClient<'a> {
-    fn subscribe_candlestick(&self, pairs: &[String], interval: u32) {
-        let interval_str = interval_to_string(interval);
-
-        let channels = pairs
-            .iter()
-            .map(|pair| {
-                format!(
+// MXC Swap WebSocket does NOT have BBO channel
+impl<'a> ChannelTranslator for MxcSwapWSClient<'a> {
+    fn translate(&self, channel: &Channel) -> Result<String, WsError> {
+        match channel {
+            Channel::Trade(pair) => Some(to_raw_channel("deal", pair)),
+            Channel::Ticker(pair) => Some(to_raw_channel("ticker", pair)),
+            Channel::L2 {
+                pair,
+                depth: None,
+                speed: None,
+            } => Some(to_raw_channel("depth", pair)),
+            Channel::L2Snapshot(pair) => Some(to_raw_channel("depth.full", pair)),
+            Channel::Kline { pair, interval } => {
+                let interval_str = interval_to_string(*interval)
+                    .ok_or_else(|| WsError::invalid_interval(EXCHANGE_NAME, *interval))?;
+                Some(format!(
                     r#"{{"method":"sub.kline","param":{{"symbol":"{}","interval":"{}"}}}}"#,
                     pair, interval_str
-                )
-            })
-            .collect::<Vec<String>>();
+                ))
+            }
+            _ => None,
+        }
+        .ok_or_else(|| WsError::unsupported(EXCHANGE_NAME, channel))
+    }
 
-        self.client.subscribe(&channels);
+    fn capabilities(&self) -> Vec<ChannelKind> {
+        vec![
+            ChannelKind::Trade,
+            ChannelKind::L2,
+            ChannelKind::L2Snapshot,
+            ChannelKind::Ticker,
+            ChannelKind::Kline,
+        ]
     }
 }
 
@@ -251,6 +258,7 @@ define_client!(
     Some(SPOT_CLIENT_PING_INTERVAL_AND_MSG),
     None
 );
+define_async_client!(MxcSpotAsyncWSClient, MxcSpotWSClient);
 define_client!(
     MxcSwapWSClient,
     EXCHANGE_NAME,
@@ -260,6 +268,7 @@ define_client!(
     Some(SWAP_CLIENT_PING_INTERVAL_AND_MSG),
     None
 );
+define_async_client!(MxcSwapAsyncWSClient, MxcSwapWSClient);
 
 #[cfg(test)]
 mod tests {
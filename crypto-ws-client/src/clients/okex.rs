@@ -1,9 +1,10 @@
-use crate::WSClient;
+use crate::{Channel, ChannelKind, Credentials, PrivateWSClient, WSClient, WsError};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
+use super::auth::{self, Auth};
 use super::ws_client_internal::{MiscMessage, WSClientInternal};
-use super::{Candlestick, OrderBook, OrderBookSnapshot, Ticker, Trade, BBO};
+use super::ChannelTranslator;
 
 use log::*;
 use serde_json::Value;
@@ -68,19 +69,31 @@ fn on_misc_msg(msg: &str) -> MiscMessage {
         match event.as_str().unwrap() {
             "error" => {
                 error!("Received {} from {}", msg, EXCHANGE_NAME);
-                if let Some(error_code) = obj.get("errorCode") {
-                    #[allow(clippy::single_match)]
-                    match error_code.as_i64().unwrap() {
-                        30040 => {
-                            // channel doesn't exist
-                            panic!("Received {} from {}", msg, EXCHANGE_NAME);
-                        }
-                        _ => (),
-                    }
+                let error_code = obj.get("errorCode").and_then(|x| x.as_i64());
+                let error_msg = obj.get("message").and_then(|x| x.as_str()).unwrap_or(msg);
+                let channels = match error_code {
+                    // channel doesn't exist, for example:
+                    // {"event":"error","message":"Channel spot/trade:BTC-USDT1 doesn't exist","errorCode":30040}
+                    Some(30040) => error_msg
+                        .strip_prefix("Channel ")
+                        .and_then(|x| x.strip_suffix(" doesn't exist"))
+                        .map(|channel| vec![channel.to_string()])
+                        .unwrap_or_default(),
+                    _ => Vec::new(),
+                };
+                return MiscMessage::Rejected {
+                    channels,
+                    code: error_code,
+                    reason: error_msg.to_string(),
+                };
+            }
+            "subscribe" => {
+                info!("Received {} from {}", msg, EXCHANGE_NAME);
+                if let Some(channel) = obj.get("channel").and_then(|x| x.as_str()) {
+                    return MiscMessage::Subscribed(vec![channel.to_string()]);
                 }
             }
-            "subscribe" => info!("Received {} from {}", msg, EXCHANGE_NAME),
-            "unsubscribe" => info!("Received {} from {}", msg, EXCHANGE_NAME),
+            "unsubscribe" | "login" => info!("Received {} from {}", msg, EXCHANGE_NAME),
             _ => warn!("Received {} from {}", msg, EXCHANGE_NAME),
         }
         MiscMessage::Misc
@@ -114,43 +127,111 @@ fn to_raw_channel(channel: &str, pair: &str) -> String {
     format!("{}/{}:{}", pair_to_market_type(pair), channel, pair)
 }
 
-#[rustfmt::skip]
-impl_trait!(Trade, OkexWSClient, subscribe_trade, "trade", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(Ticker, OkexWSClient, subscribe_ticker, "ticker", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(OrderBook, OkexWSClient, subscribe_orderbook, "depth_l2_tbt", to_raw_channel);
-impl_trait!(
-    OrderBookSnapshot,
-    OkexWSClient,
-    subscribe_orderbook_snapshot,
-    "depth5",
-    to_raw_channel
-);
-
-impl<'a> BBO for OkexWSClient<'a> {
-    fn subscribe_bbo(&self, _pairs: &[String]) {
-        panic!("OKEx WebSocket does NOT have BBO channel");
-    }
-}
-
-fn to_candlestick_raw_channel(pair: &str, interval: u32) -> String {
+fn to_candlestick_raw_channel(pair: &str, interval: u32) -> Option<String> {
     let valid_set: Vec<u32> = vec![
         60, 180, 300, 900, 1800, 3600, 7200, 14400, 21600, 43200, 86400, 604800,
     ];
     if !valid_set.contains(&interval) {
-        let joined = valid_set
-            .into_iter()
-            .map(|x| x.to_string())
-            .collect::<Vec<String>>()
-            .join(",");
-        panic!("OKEx has intervals {}", joined);
+        return None;
     }
     let channel = format!("candle{}s", interval);
-    to_raw_channel(&channel, pair)
+    Some(to_raw_channel(&channel, pair))
+}
+
+// OKEx WebSocket does NOT have BBO, liquidation and open interest channels
+impl<'a> ChannelTranslator for OkexWSClient<'a> {
+    fn translate(&self, channel: &Channel) -> Result<String, WsError> {
+        match channel {
+            Channel::Trade(pair) => Some(to_raw_channel("trade", pair)),
+            Channel::Ticker(pair) => Some(to_raw_channel("ticker", pair)),
+            Channel::L2 {
+                pair,
+                depth: None,
+                speed: None,
+            } => Some(to_raw_channel("depth_l2_tbt", pair)),
+            Channel::L2Snapshot(pair) => Some(to_raw_channel("depth5", pair)),
+            Channel::Kline { pair, interval } => {
+                return to_candlestick_raw_channel(pair, *interval)
+                    .ok_or_else(|| WsError::invalid_interval(EXCHANGE_NAME, *interval));
+            }
+            Channel::MarkPrice(pair) => match pair_to_market_type(pair) {
+                "futures" | "swap" => Some(to_raw_channel("mark_price", pair)),
+                _ => None,
+            },
+            // Index prices are pushed per underlying, e.g., BTC-USD of BTC-USD-SWAP
+            Channel::IndexPrice(pair) => Some(format!(
+                "index/ticker:{}",
+                pair.splitn(3, '-').take(2).collect::<Vec<&str>>().join("-")
+            )),
+            _ => None,
+        }
+        .ok_or_else(|| WsError::unsupported(EXCHANGE_NAME, channel))
+    }
+
+    fn capabilities(&self) -> Vec<ChannelKind> {
+        vec![
+            ChannelKind::Trade,
+            ChannelKind::L2,
+            ChannelKind::L2Snapshot,
+            ChannelKind::Ticker,
+            ChannelKind::Kline,
+            ChannelKind::MarkPrice,
+            ChannelKind::IndexPrice,
+        ]
+    }
 }
 
-impl_candlestick!(OkexWSClient);
+// see https://www.okex.com/docs/en/#question-login
+fn sign(api_secret: &str, timestamp: &str) -> String {
+    let msg = format!("{}GET/users/self/verify", timestamp);
+    base64::encode(auth::hmac_sha256(api_secret.as_bytes(), msg.as_bytes()))
+}
+
+impl<'a> PrivateWSClient<'a> for OkexWSClient<'a> {
+    fn new_private(
+        on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>,
+        credentials: Credentials,
+        url: Option<&str>,
+    ) -> Result<Self, WsError> {
+        let passphrase = credentials
+            .passphrase
+            .clone()
+            .ok_or_else(|| WsError::auth(EXCHANGE_NAME, "OKEx needs a passphrase"))?;
+        let ws_client = OkexWSClient::new(on_msg, url);
+        ws_client.client.set_auth(Auth::Login(Box::new(move || {
+            let millis = auth::current_millis();
+            let timestamp = format!("{}.{:03}", millis / 1000, millis % 1000);
+            let signature = sign(&credentials.api_secret, &timestamp);
+            vec![format!(
+                r#"{{"op":"login","args":["{}","{}","{}","{}"]}}"#,
+                credentials.api_key, passphrase, timestamp, signature
+            )]
+        })));
+        Ok(ws_client)
+    }
+
+    fn subscribe_orders(&self, symbols: &[String]) {
+        let channels: Vec<String> = symbols
+            .iter()
+            .map(|symbol| to_raw_channel("order", symbol))
+            .collect();
+        self.client.subscribe(&channels);
+    }
+
+    // Fills are pushed by the order channel, with last_fill_px and last_fill_qty
+    fn subscribe_fills(&self, symbols: &[String]) {
+        self.subscribe_orders(symbols);
+    }
+
+    // Spot account only
+    fn subscribe_balances(&self, currencies: &[String]) {
+        let channels: Vec<String> = currencies
+            .iter()
+            .map(|currency| format!("spot/account:{}", currency))
+            .collect();
+        self.client.subscribe(&channels);
+    }
+}
 
 define_client!(
     OkexWSClient,
@@ -161,6 +242,7 @@ define_client!(
     Some(CLIENT_PING_INTERVAL_AND_MSG),
     None
 );
+define_async_client!(OkexAsyncWSClient, OkexWSClient);
 
 #[cfg(test)]
 mod tests {
@@ -190,6 +272,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_sign() {
+        assert_eq!(
+            "kxeu+amF0VVK30XKQqFTQMEsL9eE/t/a+gWQLoE/9rI=",
+            super::sign("22582BD0CFF14C41EDBF1AB98506286D", "1538054050.975")
+        );
+    }
+
     #[test]
     fn test_pair_to_market_type() {
         assert_eq!("spot", super::pair_to_market_type("BTC-USDT"));
@@ -0,0 +1,88 @@
+use super::{
+    event::{EventCallback, WsEvent},
+    heartbeat::ConnectionConfig,
+    proxy::Proxy,
+};
+use std::sync::{Arc, Mutex};
+
+/// Options of a new client, see `WSClient::new_with_options()`.
+///
+/// Options can be combined freely, `WSClientOptions::default()` behaves the
+/// same as `WSClient::new()` without a url.
+///
+/// ## Example
+///
+/// ```no_run
+/// use std::{sync::{Arc, Mutex}, time::Duration};
+/// use crypto_ws_client::{BinanceSpotWSClient, ConnectionConfig, WSClient, WSClientOptions, WsEvent};
+///
+/// let options = WSClientOptions::default()
+///     .endpoints(&["wss://stream.binance.com:9443/stream", "wss://stream.binance.com:443/stream"])
+///     .events(Arc::new(Mutex::new(|event: WsEvent| println!("{:?}", event))))
+///     .config(ConnectionConfig {
+///         data_timeout: Some(Duration::from_secs(60)),
+///         ..Default::default()
+///     });
+/// let ws_client = BinanceSpotWSClient::new_with_options(Arc::new(Mutex::new(|msg| println!("{}", msg))), options);
+/// ```
+#[derive(Clone, Default)]
+pub struct WSClientOptions<'a> {
+    /// Server urls in order of preference, empty means the default urls of
+    /// the exchange.
+    ///
+    /// The client connects to the first url, and moves on to the next one
+    /// after a connect or read failure, wrapping around at the end of the
+    /// list. A healthy endpoint is kept until it fails.
+    pub endpoints: Vec<String>,
+    /// A callback function to process connection and subscription events,
+    /// see `WsEvent`.
+    ///
+    /// Exchanges reject subscriptions of invalid or delisted symbols, the
+    /// `SubscribeFailed` event tells the caller which channel to drop.
+    pub events: Option<EventCallback<'a>>,
+    /// Heartbeat and idle-timeout settings, which override defaults of the
+    /// exchange, see `ConnectionConfig`.
+    pub config: ConnectionConfig,
+    /// The proxy server to tunnel connections through, None means the proxy
+    /// in environment variables, see `Proxy`.
+    pub proxy: Option<Proxy>,
+}
+
+impl<'a> WSClientOptions<'a> {
+    /// Sets server urls in order of preference.
+    pub fn endpoints(mut self, endpoints: &[&str]) -> Self {
+        self.endpoints = endpoints.iter().map(|url| url.to_string()).collect();
+        self
+    }
+
+    /// Sets the callback of connection and subscription events.
+    pub fn events(mut self, on_event: Arc<Mutex<dyn FnMut(WsEvent) + 'a + Send>>) -> Self {
+        self.events = Some(on_event);
+        self
+    }
+
+    /// Sets heartbeat and idle-timeout settings.
+    pub fn config(mut self, config: ConnectionConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Sets the proxy server, overriding environment variables.
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    // Options with the optional url of WSClient::new(), or of a recording
+    pub(crate) fn from_url(url: Option<&str>) -> Self {
+        match url {
+            Some(url) => WSClientOptions::default().endpoints(&[url]),
+            None => WSClientOptions::default(),
+        }
+    }
+
+    // Endpoints borrowed as the constructors of clients take them
+    pub(super) fn endpoint_urls(&self) -> Vec<&str> {
+        self.endpoints.iter().map(|url| url.as_str()).collect()
+    }
+}
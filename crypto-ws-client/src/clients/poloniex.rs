@@ -0,0 +1,208 @@
+use crate::{Channel, ChannelKind, WSClient, WsError};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use super::{
+    utils::CHANNEL_PAIR_DELIMITER,
+    ws_client_internal::{MiscMessage, WSClientInternal},
+    ChannelTranslator,
+};
+
+use log::*;
+use serde_json::Value;
+
+pub(super) const EXCHANGE_NAME: &str = "poloniex";
+
+const WEBSOCKET_URL: &str = "wss://ws.poloniex.com/ws/public";
+
+// The server closes connections without any ping in 30 seconds
+const CLIENT_PING_INTERVAL_AND_MSG: (u64, &str) = (30, r#"{"event":"ping"}"#);
+
+/// The WebSocket client for Poloniex.
+///
+/// Poloniex has only Spot market.
+///
+///   * WebSocket API doc: <https://docs.poloniex.com/#public-channels>
+///   * Trading at: <https://poloniex.com/trade>
+pub struct PoloniexWSClient<'a> {
+    client: WSClientInternal<'a>,
+}
+
+// Channels of the same name share one command
+fn channels_to_commands(channels: &[String], subscribe: bool) -> Vec<String> {
+    let mut all_commands: Vec<String> = channels
+        .iter()
+        .filter(|ch| ch.starts_with('{'))
+        .map(|s| s.to_string())
+        .collect();
+
+    let mut channel_symbols = BTreeMap::<&str, Vec<&str>>::new();
+    for s in channels.iter().filter(|ch| !ch.starts_with('{')) {
+        let (channel, symbol) = s.split_once(CHANNEL_PAIR_DELIMITER).unwrap();
+        channel_symbols.entry(channel).or_default().push(symbol);
+    }
+
+    for (channel, symbols) in channel_symbols {
+        all_commands.push(format!(
+            r#"{{"event":"{}","channel":["{}"],"symbols":{}}}"#,
+            if subscribe {
+                "subscribe"
+            } else {
+                "unsubscribe"
+            },
+            channel,
+            serde_json::to_string(&symbols).unwrap()
+        ));
+    }
+
+    all_commands
+}
+
+fn on_misc_msg(msg: &str) -> MiscMessage {
+    let resp = serde_json::from_str::<HashMap<String, Value>>(msg);
+    if resp.is_err() {
+        error!("{} is not a JSON object, {}", msg, EXCHANGE_NAME);
+        return MiscMessage::Misc;
+    }
+    let obj = resp.unwrap();
+
+    match obj.get("event").and_then(|x| x.as_str()) {
+        // {"event":"pong"}
+        Some("pong") => {
+            debug!("Received {} from {}", msg, EXCHANGE_NAME);
+            MiscMessage::Pong
+        }
+        // {"event":"subscribe","channel":"trades","symbols":["BTC_USDT"]}
+        Some("subscribe") => {
+            info!("Received {} from {}", msg, EXCHANGE_NAME);
+            let channel = obj
+                .get("channel")
+                .and_then(|x| x.as_str())
+                .unwrap_or_default();
+            let raw_channels: Vec<String> = obj
+                .get("symbols")
+                .and_then(|x| x.as_array())
+                .map(|arr| {
+                    arr.iter()
+                        .filter_map(|x| x.as_str())
+                        .map(|symbol| to_raw_channel(channel, symbol))
+                        .collect()
+                })
+                .unwrap_or_default();
+            MiscMessage::Subscribed(raw_channels)
+        }
+        // {"event":"error","message":"Invalid symbols"}
+        Some("error") => {
+            error!("Received {} from {}", msg, EXCHANGE_NAME);
+            let reason = obj.get("message").and_then(|x| x.as_str()).unwrap_or(msg);
+            MiscMessage::Rejected {
+                channels: Vec::new(),
+                code: None,
+                reason: reason.to_string(),
+            }
+        }
+        Some(_) => {
+            info!("Received {} from {}", msg, EXCHANGE_NAME);
+            MiscMessage::Misc
+        }
+        None => {
+            if obj.contains_key("channel") && obj.contains_key("data") {
+                MiscMessage::Normal
+            } else {
+                warn!("Received {} from {}", msg, EXCHANGE_NAME);
+                MiscMessage::Misc
+            }
+        }
+    }
+}
+
+fn to_raw_channel(channel: &str, symbol: &str) -> String {
+    format!("{}{}{}", channel, CHANNEL_PAIR_DELIMITER, symbol)
+}
+
+fn to_candlestick_raw_channel(symbol: &str, interval: u32) -> Option<String> {
+    let interval_str = match interval {
+        60 => "minute_1",
+        300 => "minute_5",
+        600 => "minute_10",
+        900 => "minute_15",
+        1800 => "minute_30",
+        3600 => "hour_1",
+        7200 => "hour_2",
+        14400 => "hour_4",
+        21600 => "hour_6",
+        43200 => "hour_12",
+        86400 => "day_1",
+        259200 => "day_3",
+        604800 => "week_1",
+        2592000 => "month_1",
+        _ => return None,
+    };
+    Some(to_raw_channel(&format!("candles_{}", interval_str), symbol))
+}
+
+// The book_lv2 channel sends a snapshot first, then incremental updates, the
+// book channel pushes top 5 snapshots
+impl<'a> ChannelTranslator for PoloniexWSClient<'a> {
+    fn translate(&self, channel: &Channel) -> Result<String, WsError> {
+        match channel {
+            Channel::Trade(pair) => Some(to_raw_channel("trades", pair)),
+            Channel::L2 {
+                pair,
+                depth: None,
+                speed: None,
+            } => Some(to_raw_channel("book_lv2", pair)),
+            Channel::L2Snapshot(pair) => Some(to_raw_channel("book", pair)),
+            Channel::Ticker(pair) => Some(to_raw_channel("ticker", pair)),
+            Channel::Kline { pair, interval } => {
+                return to_candlestick_raw_channel(pair, *interval)
+                    .ok_or_else(|| WsError::invalid_interval(EXCHANGE_NAME, *interval));
+            }
+            _ => None,
+        }
+        .ok_or_else(|| WsError::unsupported(EXCHANGE_NAME, channel))
+    }
+
+    fn capabilities(&self) -> Vec<ChannelKind> {
+        vec![
+            ChannelKind::Trade,
+            ChannelKind::L2,
+            ChannelKind::L2Snapshot,
+            ChannelKind::Ticker,
+            ChannelKind::Kline,
+        ]
+    }
+}
+
+define_client!(
+    PoloniexWSClient,
+    EXCHANGE_NAME,
+    WEBSOCKET_URL,
+    channels_to_commands,
+    on_misc_msg,
+    Some(CLIENT_PING_INTERVAL_AND_MSG),
+    None
+);
+define_async_client!(PoloniexAsyncWSClient, PoloniexWSClient);
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_channels_to_commands() {
+        let commands = super::channels_to_commands(
+            &[
+                "trades:BTC_USDT".to_string(),
+                "trades:ETH_USDT".to_string(),
+                "book_lv2:BTC_USDT".to_string(),
+            ],
+            true,
+        );
+        assert_eq!(
+            vec![
+                r#"{"event":"subscribe","channel":["book_lv2"],"symbols":["BTC_USDT"]}"#,
+                r#"{"event":"subscribe","channel":["trades"],"symbols":["BTC_USDT","ETH_USDT"]}"#,
+            ],
+            commands
+        );
+    }
+}
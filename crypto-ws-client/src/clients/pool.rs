@@ -0,0 +1,278 @@
+use crate::{
+    Channel, ChannelKind, ConnectionLimits, Metrics, ReconnectEvent, ReconnectPolicy, Recorder,
+    Replayer, SubscriptionState, WSClient, WSClientOptions, WsError,
+};
+
+use super::reconnect::ReconnectCallback;
+
+use log::*;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+type MessageCallback<'a> = Arc<Mutex<dyn FnMut(String) + 'a + Send>>;
+
+/// A client which splits channels over multiple connections, so that each
+/// connection stays within `ConnectionLimits` of the exchange.
+///
+/// All connections pass messages to the same `on_msg`, so a pool is used the
+/// same way as a single client. New connections are created on demand while
+/// subscribing; each pair passed to high level APIs such as
+/// `subscribe_trade()` counts as one channel.
+///
+/// ## Example
+///
+/// ```no_run
+/// use std::sync::{Arc, Mutex};
+/// use crypto_ws_client::{BinanceLinearWSClient, WSClient, WSClientPool};
+///
+/// let on_msg = Arc::new(Mutex::new(|msg: String| println!("{}", msg)));
+/// let ws_client = WSClientPool::<BinanceLinearWSClient>::new(on_msg, None);
+/// let channels: Vec<String> = (0..500).map(|i| format!("pair{}@aggTrade", i)).collect();
+/// ws_client.subscribe(&channels); // 3 connections, 200 channels at most each
+/// ws_client.run(None);
+/// ```
+pub struct WSClientPool<'a, C: WSClient<'a>> {
+    on_msg: MessageCallback<'a>,
+    options: WSClientOptions<'a>, // options of every connection
+    limits: ConnectionLimits,
+    first: Arc<C>,              // the connection created by the constructor
+    shards: Mutex<Vec<Arc<C>>>, // all connections, including the first one
+    reconnect_policy: Mutex<Option<ReconnectPolicy>>,
+    on_reconnect: Mutex<Option<ReconnectCallback<'a>>>,
+    recorder: Mutex<Option<Recorder>>,
+}
+
+impl<'a, C: WSClient<'a> + Send + Sync + 'a> WSClientPool<'a, C> {
+    /// Creates a pool with custom limits, for example, a lower `max_channels`
+    /// than the exchange allows.
+    ///
+    /// # Arguments
+    ///
+    /// * `on_msg` - A callback function to process original JSON messages
+    /// * `endpoints` - Server urls, empty means the default urls of the exchange
+    /// * `limits` - Limits of each connection
+    pub fn new_with_limits(
+        on_msg: MessageCallback<'a>,
+        endpoints: &[&str],
+        limits: ConnectionLimits,
+    ) -> Self {
+        Self::new_pool(
+            on_msg,
+            WSClientOptions::default().endpoints(endpoints),
+            Some(limits),
+        )
+    }
+
+    /// Returns the number of connections.
+    pub fn num_connections(&self) -> usize {
+        self.shards.lock().unwrap().len()
+    }
+
+    fn new_pool(
+        on_msg: MessageCallback<'a>,
+        options: WSClientOptions<'a>,
+        limits: Option<ConnectionLimits>,
+    ) -> Self {
+        let first = Arc::new(Self::connect(&on_msg, &options, 0));
+        let limits = limits.unwrap_or_else(|| first.connection_limits());
+        if limits.max_channels == 0 {
+            panic!("max_channels must be positive");
+        }
+        WSClientPool {
+            on_msg,
+            options,
+            limits,
+            first: first.clone(),
+            shards: Mutex::new(vec![first]),
+            reconnect_policy: Mutex::new(None),
+            on_reconnect: Mutex::new(None),
+            recorder: Mutex::new(None),
+        }
+    }
+
+    // Creates the index-th connection, connections start from different
+    // endpoints so that they spread over all endpoints.
+    fn connect(on_msg: &MessageCallback<'a>, options: &WSClientOptions<'a>, index: usize) -> C {
+        let mut options = options.clone();
+        let n = options.endpoints.len();
+        if n > 0 {
+            options.endpoints.rotate_left(index % n);
+        }
+        C::new_with_options(on_msg.clone(), options)
+    }
+
+    // Splits `items` over connections with spare capacity, creates new
+    // connections if all are full.
+    fn dispatch<F: Fn(&C, &[String])>(&self, items: &[String], subscribe: F) {
+        let mut shards = self.shards.lock().unwrap();
+        let mut rest = items;
+        let mut index = 0;
+        while !rest.is_empty() {
+            if index == shards.len() {
+                info!(
+                    "All {} connections to {} are full, creating a new one",
+                    shards.len(),
+                    self.first.endpoint()
+                );
+                let shard = Self::connect(&self.on_msg, &self.options, index);
+                if let Some(policy) = self.reconnect_policy.lock().unwrap().clone() {
+                    shard.set_reconnect_policy(policy);
+                }
+                if let Some(callback) = self.on_reconnect.lock().unwrap().clone() {
+                    shard.on_reconnect(callback);
+                }
+                if let Some(recorder) = self.recorder.lock().unwrap().clone() {
+                    shard.set_recorder(recorder);
+                }
+                shards.push(Arc::new(shard));
+            }
+            let shard = &shards[index];
+            let spare = self
+                .limits
+                .max_channels
+                .saturating_sub(shard.subscriptions().len());
+            if spare > 0 {
+                let n = std::cmp::min(spare, rest.len());
+                subscribe(shard, &rest[..n]);
+                rest = &rest[n..];
+            }
+            index += 1;
+        }
+    }
+}
+
+impl<'a, C: WSClient<'a> + Send + Sync + 'a> WSClient<'a> for WSClientPool<'a, C> {
+    fn new_with_options(on_msg: MessageCallback<'a>, options: WSClientOptions<'a>) -> Self {
+        Self::new_pool(on_msg, options, None)
+    }
+
+    // The endpoint of the first connection
+    fn endpoint(&self) -> &str {
+        self.first.endpoint()
+    }
+
+    fn connection_limits(&self) -> ConnectionLimits {
+        self.limits
+    }
+
+    // Commands waiting in all connections
+    fn num_pending_commands(&self) -> usize {
+        let shards = self.shards.lock().unwrap().clone();
+        shards
+            .iter()
+            .map(|shard| shard.num_pending_commands())
+            .sum()
+    }
+
+    // Metrics of all connections added up
+    fn metrics(&self) -> Metrics {
+        let shards = self.shards.lock().unwrap().clone();
+        let mut metrics = Metrics::default();
+        for shard in shards.iter() {
+            metrics.merge(shard.metrics());
+        }
+        metrics
+    }
+
+    fn translate_channels(&self, channels: &[Channel]) -> Result<Vec<String>, WsError> {
+        self.first.translate_channels(channels)
+    }
+
+    fn capabilities(&self) -> Vec<ChannelKind> {
+        self.first.capabilities()
+    }
+
+    fn subscribe(&self, raw_channels: &[String]) {
+        let subscribed = self.subscriptions();
+        let raw_channels: Vec<String> = raw_channels
+            .iter()
+            .filter(|channel| !subscribed.contains_key(*channel))
+            .cloned()
+            .collect();
+        self.dispatch(&raw_channels, |shard, channels| shard.subscribe(channels));
+    }
+
+    fn unsubscribe(&self, raw_channels: &[String]) {
+        let shards = self.shards.lock().unwrap().clone();
+        for shard in shards {
+            let subscribed = shard.subscriptions();
+            let channels: Vec<String> = raw_channels
+                .iter()
+                .filter(|channel| subscribed.contains_key(*channel))
+                .cloned()
+                .collect();
+            if !channels.is_empty() {
+                shard.unsubscribe(&channels);
+            }
+        }
+    }
+
+    fn subscriptions(&self) -> HashMap<String, SubscriptionState> {
+        let shards = self.shards.lock().unwrap().clone();
+        shards
+            .iter()
+            .flat_map(|shard| shard.subscriptions())
+            .collect()
+    }
+
+    // Runs all connections in their own threads, connections created while
+    // running are started too.
+    fn run(&self, duration: Option<u64>) {
+        let start_timstamp = Instant::now();
+        thread::scope(|scope| {
+            let mut handles = Vec::new();
+            loop {
+                let shards = self.shards.lock().unwrap().clone();
+                for shard in shards.into_iter().skip(handles.len()) {
+                    let remaining = duration
+                        .map(|seconds| seconds.saturating_sub(start_timstamp.elapsed().as_secs()));
+                    handles.push(scope.spawn(move || shard.run(remaining)));
+                }
+                if handles.iter().all(|handle| handle.is_finished()) {
+                    break;
+                }
+                thread::sleep(Duration::from_millis(100));
+            }
+        });
+    }
+
+    fn close(&self) {
+        let shards = self.shards.lock().unwrap().clone();
+        for shard in shards {
+            shard.close();
+        }
+    }
+
+    fn set_reconnect_policy(&self, policy: ReconnectPolicy) {
+        *self.reconnect_policy.lock().unwrap() = Some(policy.clone());
+        let shards = self.shards.lock().unwrap().clone();
+        for shard in shards {
+            shard.set_reconnect_policy(policy.clone());
+        }
+    }
+
+    fn on_reconnect(&self, callback: Arc<Mutex<dyn FnMut(ReconnectEvent) + 'a + Send>>) {
+        *self.on_reconnect.lock().unwrap() = Some(callback.clone());
+        let shards = self.shards.lock().unwrap().clone();
+        for shard in shards {
+            shard.on_reconnect(callback.clone());
+        }
+    }
+
+    // All connections write to the same recorder
+    fn set_recorder(&self, recorder: Recorder) {
+        *self.recorder.lock().unwrap() = Some(recorder.clone());
+        let shards = self.shards.lock().unwrap().clone();
+        for shard in shards {
+            shard.set_recorder(recorder.clone());
+        }
+    }
+
+    fn replay(on_msg: MessageCallback<'a>, replayer: &Replayer) {
+        C::replay(on_msg, replayer);
+    }
+}
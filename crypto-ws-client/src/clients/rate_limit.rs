@@ -0,0 +1,121 @@
+use std::{
+    collections::VecDeque,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+// A token bucket which limits the rate of outgoing commands, commands over
+// the limit are queued and sent later by the run loop.
+//
+// The bucket holds at most one second of tokens, so a burst after idling
+// never exceeds the per-second limit.
+pub(super) struct RateLimiter {
+    max_per_second: Option<u32>, // None means unlimited
+    state: Mutex<State>,
+}
+
+struct State {
+    tokens: f64,
+    last_refill: Instant,
+    queue: VecDeque<String>, // commands waiting for tokens
+}
+
+impl RateLimiter {
+    pub fn new(max_per_second: Option<u32>) -> Self {
+        assert_ne!(Some(0), max_per_second, "The rate limit must be positive");
+        RateLimiter {
+            max_per_second,
+            state: Mutex::new(State {
+                tokens: max_per_second.unwrap_or_default() as f64,
+                last_refill: Instant::now(),
+                queue: VecDeque::new(),
+            }),
+        }
+    }
+
+    // How often the run loop should check queued commands, None if unlimited
+    pub fn period(&self) -> Option<Duration> {
+        self.max_per_second
+            .map(|rate| Duration::from_secs_f64(1.0 / rate as f64))
+    }
+
+    pub fn push(&self, commands: Vec<String>) {
+        self.state.lock().unwrap().queue.extend(commands);
+    }
+
+    // Pops queued commands which can be sent now.
+    pub fn pop_ready(&self) -> Vec<String> {
+        let mut state = self.state.lock().unwrap();
+        let rate = match self.max_per_second {
+            Some(rate) => rate as f64,
+            None => return state.queue.drain(..).collect(),
+        };
+        let now = Instant::now();
+        let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * rate).min(rate);
+        state.last_refill = now;
+
+        let n = std::cmp::min(state.tokens as usize, state.queue.len());
+        state.tokens -= n as f64;
+        state.queue.drain(..n).collect()
+    }
+
+    // Time until the next queued command can be sent, None if nothing is queued.
+    pub fn delay(&self) -> Option<Duration> {
+        let state = self.state.lock().unwrap();
+        if state.queue.is_empty() {
+            return None;
+        }
+        let rate = self.max_per_second? as f64;
+        let elapsed = state.last_refill.elapsed().as_secs_f64();
+        let missing = 1.0 - (state.tokens + elapsed * rate);
+        Some(Duration::from_secs_f64(missing.max(0.0) / rate))
+    }
+
+    // Drops queued commands, the connection they were meant for is gone.
+    pub fn clear(&self) {
+        self.state.lock().unwrap().queue.clear();
+    }
+
+    // Number of queued commands
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().queue.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RateLimiter;
+    use std::time::Duration;
+
+    fn commands(n: usize) -> Vec<String> {
+        (0..n).map(|i| i.to_string()).collect()
+    }
+
+    #[test]
+    fn test_burst_then_queue() {
+        let limiter = RateLimiter::new(Some(5));
+        limiter.push(commands(8));
+        assert_eq!(commands(5), limiter.pop_ready());
+        assert_eq!(3, limiter.len());
+        assert!(limiter.pop_ready().is_empty());
+
+        let delay = limiter.delay().unwrap();
+        assert!(delay <= Duration::from_millis(200));
+        std::thread::sleep(delay + Duration::from_millis(10));
+        assert_eq!(vec!["5".to_string()], limiter.pop_ready());
+        assert_eq!(2, limiter.len());
+
+        limiter.clear();
+        assert_eq!(None, limiter.delay());
+    }
+
+    #[test]
+    fn test_unlimited() {
+        let limiter = RateLimiter::new(None);
+        assert_eq!(None, limiter.period());
+        limiter.push(commands(100));
+        assert_eq!(100, limiter.pop_ready().len());
+        assert_eq!(0, limiter.len());
+    }
+}
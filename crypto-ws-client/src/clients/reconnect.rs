@@ -0,0 +1,164 @@
+use super::{endpoint::Endpoints, utils::connect_endpoint};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use log::*;
+use rand::Rng;
+use tungstenite::{client::AutoStream, WebSocket};
+
+/// Exponential backoff with jitter, used to reconnect after a connection drops.
+///
+/// The delay before the n-th attempt is `initial_delay * multiplier^(n-1)`,
+/// capped at `max_delay`, then randomized by `±jitter`.
+#[derive(Clone, Debug)]
+pub struct ReconnectPolicy {
+    /// Delay before the first reconnect attempt
+    pub initial_delay: Duration,
+    /// Upper bound of the delay between two attempts
+    pub max_delay: Duration,
+    /// Growth factor of the delay after each failed attempt
+    pub multiplier: f64,
+    /// Jitter ratio in `[0, 1]`, for example, 0.2 means ±20%
+    pub jitter: f64,
+    /// Give up after this many consecutive failures, None means retry forever
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        ReconnectPolicy {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+            multiplier: 2.0,
+            jitter: 0.2,
+            max_attempts: None,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Returns the delay before the `attempt`-th reconnect, starting from 1.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(64) as i32;
+        let base = (self.initial_delay.as_secs_f64() * self.multiplier.powi(exponent))
+            .min(self.max_delay.as_secs_f64());
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        let factor = if jitter > 0.0 {
+            rand::thread_rng().gen_range((1.0 - jitter)..=(1.0 + jitter))
+        } else {
+            1.0
+        };
+        Duration::from_secs_f64(base * factor)
+    }
+}
+
+/// Sent to the user after the client reconnected and resubscribed all channels.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReconnectEvent {
+    /// The websocket url reconnected to
+    pub url: String,
+    /// How many attempts it took
+    pub attempts: u32,
+    /// Number of channels resubscribed
+    pub num_channels: usize,
+}
+
+// User defined callback invoked after each successful reconnect
+pub(super) type ReconnectCallback<'a> = Arc<Mutex<dyn FnMut(ReconnectEvent) + 'a + Send>>;
+
+// Sleeps for `duration`, returns false immediately if `should_stop` becomes true.
+pub(super) fn interruptible_sleep(duration: Duration, should_stop: &AtomicBool) -> bool {
+    let start = Instant::now();
+    while start.elapsed() < duration {
+        if should_stop.load(Ordering::Acquire) {
+            return false;
+        }
+        std::thread::sleep(std::cmp::min(
+            Duration::from_millis(100),
+            duration - start.elapsed().min(duration),
+        ));
+    }
+    !should_stop.load(Ordering::Acquire)
+}
+
+// Connects to `endpoints` again and again following `policy`, failing over
+// to the next endpoint after each failed attempt.
+//
+// Returns the new stream and the number of attempts, or None if `should_stop`
+// was set or `policy.max_attempts` was exhausted.
+pub(super) fn reconnect_with_backoff(
+    endpoints: &Endpoints,
+    timeout: Option<u64>,
+    policy: &ReconnectPolicy,
+    should_stop: &AtomicBool,
+) -> Option<(WebSocket<AutoStream>, u32)> {
+    let mut attempt = 0;
+    loop {
+        let url = endpoints.current();
+        attempt += 1;
+        if let Some(max_attempts) = policy.max_attempts {
+            if attempt > max_attempts {
+                error!(
+                    "Failed to reconnect to {} after {} attempts, giving up",
+                    url, max_attempts
+                );
+                return None;
+            }
+        }
+        let delay = policy.delay(attempt);
+        warn!(
+            "Reconnecting to {} in {} ms, attempt {}",
+            url,
+            delay.as_millis(),
+            attempt
+        );
+        if !interruptible_sleep(delay, should_stop) {
+            return None;
+        }
+        match connect_endpoint(endpoints, timeout) {
+            Ok((ws_stream, _)) => return Some((ws_stream, attempt)),
+            Err(err) => {
+                warn!("Error reconnecting to {}, {}", url, err);
+                endpoints.failover();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReconnectPolicy;
+    use std::time::Duration;
+
+    #[test]
+    fn test_exponential_delay() {
+        let policy = ReconnectPolicy {
+            jitter: 0.0,
+            ..Default::default()
+        };
+        assert_eq!(Duration::from_secs(1), policy.delay(1));
+        assert_eq!(Duration::from_secs(2), policy.delay(2));
+        assert_eq!(Duration::from_secs(4), policy.delay(3));
+        assert_eq!(Duration::from_secs(60), policy.delay(10));
+        assert_eq!(Duration::from_secs(60), policy.delay(u32::MAX));
+    }
+
+    #[test]
+    fn test_jitter() {
+        let policy = ReconnectPolicy {
+            initial_delay: Duration::from_secs(10),
+            jitter: 0.5,
+            ..Default::default()
+        };
+        for _ in 0..100 {
+            let delay = policy.delay(1);
+            assert!(delay >= Duration::from_secs(5));
+            assert!(delay <= Duration::from_secs(15));
+        }
+    }
+}
@@ -0,0 +1,332 @@
+use crate::WsError;
+
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, LineWriter, Write},
+    path::Path,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use log::*;
+use tungstenite::{
+    protocol::{frame::coding::CloseCode, CloseFrame},
+    Message,
+};
+
+/// Direction of a recorded frame.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Direction {
+    /// Received from the server
+    Inbound,
+    /// Sent by the client, including connecting
+    Outbound,
+}
+
+/// A frame or connection event in a recording.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RecordedFrame {
+    /// Connected to the url, recorded when recording starts and after every
+    /// reconnect
+    Connected(String),
+    Text(String),
+    /// A binary frame, before decompression
+    Binary(Vec<u8>),
+    Ping(Vec<u8>),
+    Pong(Vec<u8>),
+    /// A close frame with an optional code and reason
+    Close(Option<(u16, String)>),
+}
+
+/// A line of a recording.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Record {
+    /// Monotonic time since the recording started
+    pub elapsed: Duration,
+    /// Wall-clock time in milliseconds since UNIX epoch
+    pub timestamp: u64,
+    pub direction: Direction,
+    pub frame: RecordedFrame,
+}
+
+impl RecordedFrame {
+    pub(super) fn from_message(msg: &Message) -> RecordedFrame {
+        match msg {
+            Message::Text(txt) => RecordedFrame::Text(txt.clone()),
+            Message::Binary(binary) => RecordedFrame::Binary(binary.clone()),
+            Message::Ping(payload) => RecordedFrame::Ping(payload.clone()),
+            Message::Pong(payload) => RecordedFrame::Pong(payload.clone()),
+            Message::Close(frame) => RecordedFrame::Close(
+                frame
+                    .as_ref()
+                    .map(|frame| (u16::from(frame.code), frame.reason.to_string())),
+            ),
+        }
+    }
+
+    // None for connection events, which are not frames
+    fn to_message(&self) -> Option<Message> {
+        match self {
+            RecordedFrame::Connected(_) => None,
+            RecordedFrame::Text(txt) => Some(Message::Text(txt.clone())),
+            RecordedFrame::Binary(binary) => Some(Message::Binary(binary.clone())),
+            RecordedFrame::Ping(payload) => Some(Message::Ping(payload.clone())),
+            RecordedFrame::Pong(payload) => Some(Message::Pong(payload.clone())),
+            RecordedFrame::Close(frame) => {
+                Some(Message::Close(frame.as_ref().map(|(code, reason)| {
+                    CloseFrame {
+                        code: CloseCode::from(*code),
+                        reason: reason.clone().into(),
+                    }
+                })))
+            }
+        }
+    }
+}
+
+impl Record {
+    // One line per record: `elapsed_micros timestamp_millis </> type payload`,
+    // texts are JSON strings and binaries are base64 encoded.
+    fn to_line(&self) -> String {
+        let (kind, payload) = match &self.frame {
+            RecordedFrame::Connected(url) => ("connected", json_string(url)),
+            RecordedFrame::Text(txt) => ("text", json_string(txt)),
+            RecordedFrame::Binary(binary) => ("binary", base64::encode(binary)),
+            RecordedFrame::Ping(payload) => ("ping", base64::encode(payload)),
+            RecordedFrame::Pong(payload) => ("pong", base64::encode(payload)),
+            RecordedFrame::Close(None) => ("close", String::new()),
+            RecordedFrame::Close(Some((code, reason))) => {
+                ("close", format!("{} {}", code, json_string(reason)))
+            }
+        };
+        format!(
+            "{} {} {} {} {}",
+            self.elapsed.as_micros(),
+            self.timestamp,
+            match self.direction {
+                Direction::Inbound => '<',
+                Direction::Outbound => '>',
+            },
+            kind,
+            payload
+        )
+    }
+
+    fn from_line(line: &str) -> Option<Record> {
+        let mut fields = line.splitn(5, ' ');
+        let elapsed = Duration::from_micros(fields.next()?.parse().ok()?);
+        let timestamp = fields.next()?.parse().ok()?;
+        let direction = match fields.next()? {
+            "<" => Direction::Inbound,
+            ">" => Direction::Outbound,
+            _ => return None,
+        };
+        let kind = fields.next()?;
+        let payload = fields.next().unwrap_or_default();
+        let frame = match kind {
+            "connected" => RecordedFrame::Connected(serde_json::from_str(payload).ok()?),
+            "text" => RecordedFrame::Text(serde_json::from_str(payload).ok()?),
+            "binary" => RecordedFrame::Binary(base64::decode(payload).ok()?),
+            "ping" => RecordedFrame::Ping(base64::decode(payload).ok()?),
+            "pong" => RecordedFrame::Pong(base64::decode(payload).ok()?),
+            "close" if payload.is_empty() => RecordedFrame::Close(None),
+            "close" => {
+                let (code, reason) = payload.split_once(' ')?;
+                RecordedFrame::Close(Some((
+                    code.parse().ok()?,
+                    serde_json::from_str(reason).ok()?,
+                )))
+            }
+            _ => return None,
+        };
+        Some(Record {
+            elapsed,
+            timestamp,
+            direction,
+            frame,
+        })
+    }
+}
+
+fn json_string(s: &str) -> String {
+    serde_json::to_string(s).unwrap()
+}
+
+/// Records every frame of a connection to a log file, see
+/// `WSClient::set_recorder()`.
+///
+/// Inbound frames, outbound commands, ping/pong and close frames are written
+/// one per line, with monotonic and wall-clock timestamps; binary frames are
+/// written before decompression. A recorder can be cloned and shared by
+/// multiple clients.
+///
+/// ## Example
+///
+/// ```no_run
+/// use std::sync::{Arc, Mutex};
+/// use crypto_ws_client::{BinanceSpotWSClient, Recorder, WSClient};
+///
+/// let ws_client = BinanceSpotWSClient::new(Arc::new(Mutex::new(|msg| println!("{}", msg))), None);
+/// ws_client.set_recorder(Recorder::create("binance.log").unwrap());
+/// ws_client.subscribe_trade(&["btcusdt".to_string()]).unwrap();
+/// ws_client.run(Some(60));
+/// ```
+#[derive(Clone)]
+pub struct Recorder {
+    start: Instant,
+    writer: Arc<Mutex<LineWriter<File>>>,
+}
+
+impl Recorder {
+    /// Creates a log file, truncates it if it exists.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Recorder, WsError> {
+        let file = File::create(path)?;
+        Ok(Recorder {
+            start: Instant::now(),
+            writer: Arc::new(Mutex::new(LineWriter::new(file))),
+        })
+    }
+
+    pub(super) fn record(&self, direction: Direction, frame: RecordedFrame) {
+        let record = Record {
+            elapsed: self.start.elapsed(),
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as u64,
+            direction,
+            frame,
+        };
+        let mut writer = self.writer.lock().unwrap();
+        if let Err(err) = writeln!(writer, "{}", record.to_line()) {
+            error!("Failed to write the recording, {}", err);
+        }
+    }
+
+    pub(super) fn record_message(&self, direction: Direction, msg: &Message) {
+        self.record(direction, RecordedFrame::from_message(msg));
+    }
+}
+
+/// Replays a log file written by `Recorder`, see `WSClient::replay()`.
+///
+/// Inbound frames are fed to the same path as live frames, including
+/// decompression, `on_misc_msg` and `on_msg`, so bugs of parsing can be
+/// reproduced offline. Outbound frames are skipped.
+///
+/// ## Example
+///
+/// ```no_run
+/// use std::sync::{Arc, Mutex};
+/// use crypto_ws_client::{BinanceSpotWSClient, Replayer, WSClient};
+///
+/// let replayer = Replayer::open("binance.log").unwrap().speed(10.0);
+/// BinanceSpotWSClient::replay(Arc::new(Mutex::new(|msg| println!("{}", msg))), &replayer);
+/// ```
+pub struct Replayer {
+    records: Vec<Record>,
+    speed: f64,
+}
+
+impl Replayer {
+    /// Reads a log file, fails if any line is malformed.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Replayer, WsError> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut records = Vec::new();
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.is_empty() {
+                continue;
+            }
+            match Record::from_line(&line) {
+                Some(record) => records.push(record),
+                None => return Err(WsError::Io(format!("Malformed line {}: {}", i + 1, line))),
+            }
+        }
+        Ok(Replayer {
+            records,
+            speed: 1.0,
+        })
+    }
+
+    /// Replays `speed` times as fast as the original, 1.0 by default.
+    ///
+    /// `f64::INFINITY` replays without waiting.
+    pub fn speed(mut self, speed: f64) -> Replayer {
+        assert!(speed > 0.0, "speed must be positive");
+        self.speed = speed;
+        self
+    }
+
+    /// All records in the log file.
+    pub fn records(&self) -> &[Record] {
+        &self.records
+    }
+
+    /// The url connected first, None if not recorded.
+    pub fn url(&self) -> Option<&str> {
+        self.records.iter().find_map(|record| match &record.frame {
+            RecordedFrame::Connected(url) => Some(url.as_str()),
+            _ => None,
+        })
+    }
+
+    // Feeds inbound frames to `handle` at the recorded pace.
+    pub(super) fn replay<F: FnMut(Message)>(&self, mut handle: F) {
+        let start = Instant::now();
+        let first = self.records.first().map_or(Duration::ZERO, |x| x.elapsed);
+        for record in self.records.iter() {
+            if record.direction != Direction::Inbound {
+                continue;
+            }
+            if let Some(msg) = record.frame.to_message() {
+                let due = (record.elapsed.saturating_sub(first)).div_f64(self.speed);
+                if let Some(delay) = due.checked_sub(start.elapsed()) {
+                    std::thread::sleep(delay);
+                }
+                handle(msg);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Direction, Record, RecordedFrame};
+    use std::time::Duration;
+
+    #[test]
+    fn test_line() {
+        let frames = vec![
+            RecordedFrame::Connected("wss://stream.binance.com:9443/stream".to_string()),
+            RecordedFrame::Text("{\"a\":\"b c\"}\n".to_string()),
+            RecordedFrame::Binary(vec![0x1f, 0x8b, 0, 255]),
+            RecordedFrame::Ping(Vec::new()),
+            RecordedFrame::Pong(b"pong".to_vec()),
+            RecordedFrame::Close(None),
+            RecordedFrame::Close(Some((1000, "bye bye".to_string()))),
+        ];
+        for frame in frames {
+            let record = Record {
+                elapsed: Duration::from_micros(1234),
+                timestamp: 1616651250071,
+                direction: Direction::Inbound,
+                frame,
+            };
+            let line = record.to_line();
+            assert!(!line.contains('\n'));
+            assert_eq!(Some(record), Record::from_line(&line));
+        }
+        assert_eq!(
+            "5 1616651250071 > text \"ping\"",
+            Record {
+                elapsed: Duration::from_micros(5),
+                timestamp: 1616651250071,
+                direction: Direction::Outbound,
+                frame: RecordedFrame::Text("ping".to_string()),
+            }
+            .to_line()
+        );
+        assert_eq!(None, Record::from_line("5 1616651250071 > text ping"));
+    }
+}
@@ -0,0 +1,177 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use super::ws_client_internal::MiscMessage;
+
+// Commands in flight of a client, for exchanges which echo the id of a command
+// in its ack, e.g., Binance and KuCoin.
+//
+// `channels_to_commands()` and `on_misc_msg()` of an exchange have no state,
+// so a client calls them inside `scope()`, where `register()`, `on_ack()` and
+// `on_error()` work on the commands of that client. Commands are dropped after
+// reconnecting, since the old connection never answers them.
+#[derive(Clone, Default)]
+pub(super) struct Requests {
+    pending: Arc<Mutex<HashMap<u64, Request>>>,
+}
+
+thread_local! {
+    // Requests of the client calling exchange functions on this thread
+    static CURRENT: RefCell<Option<Requests>> = const { RefCell::new(None) };
+}
+
+// Ids are unique in the whole process, so that an id is never reused by the
+// same client after its commands were dropped
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+// Commands which are never answered are dropped once there are too many
+const MAX_PENDING: usize = 10000;
+
+struct Request {
+    channels: Vec<String>,
+    subscribe: bool,
+}
+
+impl Requests {
+    // Calls `f` with commands registered to and answered from this client.
+    pub fn scope<T>(&self, f: impl FnOnce() -> T) -> T {
+        // Restores the previous requests even if `f` panics
+        struct Restore(Option<Requests>);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                let prev = self.0.take();
+                CURRENT.with(|current| *current.borrow_mut() = prev);
+            }
+        }
+
+        let prev = CURRENT.with(|current| current.replace(Some(self.clone())));
+        let _restore = Restore(prev);
+        f()
+    }
+
+    // Drops all commands in flight, e.g., after reconnecting.
+    pub fn clear(&self) {
+        self.pending.lock().unwrap().clear();
+    }
+
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.pending.lock().unwrap().len()
+    }
+}
+
+// Calls `f` with commands in flight of the current client, None outside of
+// `Requests::scope()`.
+fn with_pending<T>(f: impl FnOnce(&mut HashMap<u64, Request>) -> T) -> Option<T> {
+    CURRENT.with(|current| {
+        current
+            .borrow()
+            .as_ref()
+            .map(|requests| f(&mut requests.pending.lock().unwrap()))
+    })
+}
+
+// Allocates an id for a command of `channels`.
+pub(super) fn register(channels: &[String], subscribe: bool) -> u64 {
+    let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+    with_pending(|pending| {
+        if pending.len() >= MAX_PENDING {
+            let oldest = *pending.keys().min().unwrap();
+            pending.remove(&oldest);
+        }
+        pending.insert(
+            id,
+            Request {
+                channels: channels.to_vec(),
+                subscribe,
+            },
+        );
+    });
+    id
+}
+
+// The server accepted the command `id`, confirms its channels if it is a
+// subscribe command.
+pub(super) fn on_ack(id: u64) -> MiscMessage {
+    match with_pending(|pending| pending.remove(&id)).flatten() {
+        Some(request) if request.subscribe => MiscMessage::Subscribed(request.channels),
+        _ => MiscMessage::Misc,
+    }
+}
+
+// The server rejected the command `id`, channels are empty if the command is
+// unknown, e.g., sent before reconnecting or by another process.
+pub(super) fn on_error(id: Option<u64>, code: Option<i64>, reason: &str) -> MiscMessage {
+    let request = id.and_then(|id| with_pending(|pending| pending.remove(&id)).flatten());
+    match request {
+        Some(request) if !request.subscribe => MiscMessage::Misc, // failed to unsubscribe
+        _ => MiscMessage::Rejected {
+            channels: request.map(|x| x.channels).unwrap_or_default(),
+            code,
+            reason: reason.to_string(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::ws_client_internal::MiscMessage;
+    use super::Requests;
+
+    #[test]
+    fn test_ack() {
+        let channels = vec!["btcusdt@aggTrade".to_string()];
+        Requests::default().scope(|| {
+            let id = super::register(&channels, true);
+            assert_ne!(id, super::register(&channels, true));
+            assert!(matches!(super::on_ack(id), MiscMessage::Subscribed(x) if x == channels));
+            // an id is answered only once
+            assert!(matches!(super::on_ack(id), MiscMessage::Misc));
+
+            let id = super::register(&channels, false);
+            assert!(matches!(super::on_ack(id), MiscMessage::Misc));
+        });
+    }
+
+    #[test]
+    fn test_error() {
+        let channels = vec!["btcusdt@aggTrade".to_string()];
+        Requests::default().scope(|| {
+            let id = super::register(&channels, true);
+            assert!(matches!(
+                super::on_error(Some(id), Some(2), "Invalid request"),
+                MiscMessage::Rejected { channels: x, code: Some(2), .. } if x == channels
+            ));
+            assert!(matches!(
+                super::on_error(None, None, "Invalid request"),
+                MiscMessage::Rejected { channels: x, .. } if x.is_empty()
+            ));
+        });
+    }
+
+    #[test]
+    fn test_clients() {
+        let channels = vec!["btcusdt@aggTrade".to_string()];
+        let (client1, client2) = (Requests::default(), Requests::default());
+        let id = client1.scope(|| super::register(&channels, true));
+        assert_eq!(1, client1.len());
+        assert_eq!(0, client2.len());
+        // an id is unknown to other clients
+        assert!(matches!(
+            client2.scope(|| super::on_ack(id)),
+            MiscMessage::Misc
+        ));
+        // and after reconnecting
+        client1.clear();
+        assert!(matches!(
+            client1.scope(|| super::on_ack(id)),
+            MiscMessage::Misc
+        ));
+    }
+}
@@ -0,0 +1,163 @@
+use std::{collections::HashMap, sync::Mutex};
+
+/// The state of a subscribed channel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SubscriptionState {
+    /// The subscribe command was sent, the server hasn't confirmed it yet
+    Pending,
+    /// The server confirmed the subscription
+    Confirmed,
+    /// The server rejected the subscription, it won't be resubscribed after reconnecting
+    Rejected,
+}
+
+// Subscribed channels and their states.
+pub(super) struct Subscriptions {
+    channels: Mutex<HashMap<String, SubscriptionState>>,
+}
+
+impl Subscriptions {
+    pub fn new() -> Self {
+        Subscriptions {
+            channels: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Adds channels as pending, returns the ones which need subscribe commands.
+    //
+    // A rejected channel can be subscribed again.
+    pub fn add(&self, channels: &[String]) -> Vec<String> {
+        let mut guard = self.channels.lock().unwrap();
+        let mut diff = Vec::<String>::new();
+        for ch in channels.iter() {
+            let state = guard.get(ch);
+            if state.is_none() || state == Some(&SubscriptionState::Rejected) {
+                guard.insert(ch.clone(), SubscriptionState::Pending);
+                diff.push(ch.clone());
+            }
+        }
+        diff
+    }
+
+    // Removes channels, returns the ones which need unsubscribe commands.
+    pub fn remove(&self, channels: &[String]) -> Vec<String> {
+        let mut guard = self.channels.lock().unwrap();
+        channels
+            .iter()
+            .filter(|ch| match guard.remove(*ch) {
+                Some(state) => state != SubscriptionState::Rejected,
+                None => false,
+            })
+            .cloned()
+            .collect()
+    }
+
+    // Marks all channels except rejected ones as pending after reconnecting,
+    // returns them so that they can be subscribed again.
+    pub fn reset(&self) -> Vec<String> {
+        let mut guard = self.channels.lock().unwrap();
+        guard
+            .iter_mut()
+            .filter(|(_, state)| **state != SubscriptionState::Rejected)
+            .map(|(ch, state)| {
+                *state = SubscriptionState::Pending;
+                ch.clone()
+            })
+            .collect()
+    }
+
+    // Updates states of known channels, unknown channels are ignored.
+    //
+    // Returns the updated channels.
+    pub fn update(&self, channels: &[String], new_state: SubscriptionState) -> Vec<String> {
+        let mut guard = self.channels.lock().unwrap();
+        let mut updated = Vec::<String>::new();
+        for ch in channels.iter() {
+            if let Some(state) = guard.get_mut(ch) {
+                *state = new_state;
+                updated.push(ch.clone());
+            }
+        }
+        updated
+    }
+
+    // Channels which the server hasn't answered yet
+    pub fn pending(&self) -> Vec<String> {
+        self.channels
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, state)| **state == SubscriptionState::Pending)
+            .map(|(ch, _)| ch.clone())
+            .collect()
+    }
+
+    pub fn snapshot(&self) -> HashMap<String, SubscriptionState> {
+        self.channels.lock().unwrap().clone()
+    }
+
+    // Number of channels which are subscribed after reconnecting
+    pub fn len(&self) -> usize {
+        self.channels
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|state| **state != SubscriptionState::Rejected)
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SubscriptionState, Subscriptions};
+
+    fn channels(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_subscribe_and_unsubscribe() {
+        let subscriptions = Subscriptions::new();
+        assert_eq!(
+            channels(&["a", "b"]),
+            subscriptions.add(&channels(&["a", "b"]))
+        );
+        // already subscribed
+        assert!(subscriptions.add(&channels(&["a"])).is_empty());
+        assert_eq!(
+            channels(&["a"]),
+            subscriptions.remove(&channels(&["a", "c"]))
+        );
+        // unknown channels are not added by unsubscribing
+        assert_eq!(1, subscriptions.len());
+        assert!(!subscriptions.snapshot().contains_key("c"));
+        assert!(subscriptions.remove(&channels(&["a"])).is_empty());
+    }
+
+    #[test]
+    fn test_states() {
+        let subscriptions = Subscriptions::new();
+        subscriptions.add(&channels(&["a", "b", "c"]));
+        assert_eq!(
+            channels(&["a"]),
+            subscriptions.update(&channels(&["a", "x"]), SubscriptionState::Confirmed)
+        );
+        subscriptions.update(&channels(&["b"]), SubscriptionState::Rejected);
+
+        let snapshot = subscriptions.snapshot();
+        assert_eq!(3, snapshot.len());
+        assert_eq!(SubscriptionState::Confirmed, snapshot["a"]);
+        assert_eq!(SubscriptionState::Rejected, snapshot["b"]);
+        assert_eq!(SubscriptionState::Pending, snapshot["c"]);
+
+        // rejected channels are not resubscribed
+        let mut resubscribed = subscriptions.reset();
+        resubscribed.sort();
+        assert_eq!(channels(&["a", "c"]), resubscribed);
+        assert_eq!(SubscriptionState::Pending, subscriptions.snapshot()["a"]);
+
+        // but can be subscribed again explicitly
+        assert_eq!(channels(&["b"]), subscriptions.add(&channels(&["b"])));
+        assert_eq!(SubscriptionState::Pending, subscriptions.snapshot()["b"]);
+    }
+}
@@ -0,0 +1,161 @@
+use crate::{Channel, ChannelKind, WSClient, WsError};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use super::{
+    utils::CHANNEL_PAIR_DELIMITER,
+    ws_client_internal::{MiscMessage, WSClientInternal},
+    ChannelTranslator,
+};
+
+use log::*;
+use serde_json::Value;
+
+pub(super) const EXCHANGE_NAME: &str = "upbit";
+
+const WEBSOCKET_URL: &str = "wss://api.upbit.com/websocket/v1";
+
+// The server closes connections idle for 120 seconds, empty string means an
+// empty ping frame
+const CLIENT_PING_INTERVAL_AND_MSG: (u64, &str) = (120, "");
+
+/// The WebSocket client for Upbit.
+///
+/// Upbit has only Spot market.
+///
+/// Every request replaces previous subscriptions and Upbit has no
+/// unsubscribe command, so subscribe all channels in one call.
+///
+///   * WebSocket API doc: <https://global-docs.upbit.com/reference/websocket-guide>
+///   * Trading at: <https://upbit.com/exchange>
+pub struct UpbitWSClient<'a> {
+    client: WSClientInternal<'a>,
+}
+
+// All channels are sent in one request, codes of the same type are grouped
+// together
+fn channels_to_commands(channels: &[String], subscribe: bool) -> Vec<String> {
+    if !subscribe {
+        warn!("{} does NOT support unsubscribing", EXCHANGE_NAME);
+        return Vec::new();
+    }
+    let mut all_commands: Vec<String> = channels
+        .iter()
+        .filter(|ch| ch.starts_with('['))
+        .map(|s| s.to_string())
+        .collect();
+
+    let mut type_codes = BTreeMap::<&str, Vec<&str>>::new();
+    for s in channels.iter().filter(|ch| !ch.starts_with('[')) {
+        let (type_, code) = s.split_once(CHANNEL_PAIR_DELIMITER).unwrap();
+        type_codes.entry(type_).or_default().push(code);
+    }
+
+    if !type_codes.is_empty() {
+        let mut request = vec![r#"{"ticket":"crypto-ws-client"}"#.to_string()];
+        for (type_, codes) in type_codes {
+            request.push(format!(
+                r#"{{"type":"{}","codes":{}}}"#,
+                type_,
+                serde_json::to_string(&codes).unwrap()
+            ));
+        }
+        all_commands.push(format!("[{}]", request.join(",")));
+    }
+
+    all_commands
+}
+
+// Upbit doesn't confirm subscriptions
+fn on_misc_msg(msg: &str) -> MiscMessage {
+    let resp = serde_json::from_str::<HashMap<String, Value>>(msg);
+    if resp.is_err() {
+        error!("{} is not a JSON object, {}", msg, EXCHANGE_NAME);
+        return MiscMessage::Misc;
+    }
+    let obj = resp.unwrap();
+
+    if let Some(error) = obj.get("error") {
+        // {"error":{"name":"INVALID_PARAM","message":"..."}}
+        error!("Received {} from {}", msg, EXCHANGE_NAME);
+        let reason = error
+            .get("message")
+            .or_else(|| error.get("name"))
+            .and_then(|x| x.as_str())
+            .unwrap_or(msg);
+        MiscMessage::Rejected {
+            channels: Vec::new(),
+            code: None,
+            reason: reason.to_string(),
+        }
+    } else if obj.contains_key("status") {
+        // {"status":"UP"}
+        debug!("Received {} from {}", msg, EXCHANGE_NAME);
+        MiscMessage::Pong
+    } else if obj.contains_key("type") && obj.contains_key("code") {
+        MiscMessage::Normal
+    } else {
+        warn!("Received {} from {}", msg, EXCHANGE_NAME);
+        MiscMessage::Misc
+    }
+}
+
+fn to_raw_channel(type_: &str, code: &str) -> String {
+    format!("{}{}{}", type_, CHANNEL_PAIR_DELIMITER, code)
+}
+
+// The orderbook type pushes top 15 snapshots, Upbit has no candlestick
+// channel
+impl<'a> ChannelTranslator for UpbitWSClient<'a> {
+    fn translate(&self, channel: &Channel) -> Result<String, WsError> {
+        match channel {
+            Channel::Trade(pair) => Some(to_raw_channel("trade", pair)),
+            Channel::L2Snapshot(pair) => Some(to_raw_channel("orderbook", pair)),
+            Channel::Ticker(pair) => Some(to_raw_channel("ticker", pair)),
+            _ => None,
+        }
+        .ok_or_else(|| WsError::unsupported(EXCHANGE_NAME, channel))
+    }
+
+    fn capabilities(&self) -> Vec<ChannelKind> {
+        vec![
+            ChannelKind::Trade,
+            ChannelKind::L2Snapshot,
+            ChannelKind::Ticker,
+        ]
+    }
+}
+
+define_client!(
+    UpbitWSClient,
+    EXCHANGE_NAME,
+    WEBSOCKET_URL,
+    channels_to_commands,
+    on_misc_msg,
+    Some(CLIENT_PING_INTERVAL_AND_MSG),
+    None
+);
+define_async_client!(UpbitAsyncWSClient, UpbitWSClient);
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_channels_to_commands() {
+        let commands = super::channels_to_commands(
+            &[
+                "trade:KRW-BTC".to_string(),
+                "trade:KRW-ETH".to_string(),
+                "orderbook:KRW-BTC".to_string(),
+            ],
+            true,
+        );
+        assert_eq!(
+            vec![
+                r#"[{"ticket":"crypto-ws-client"},{"type":"orderbook","codes":["KRW-BTC"]},{"type":"trade","codes":["KRW-BTC","KRW-ETH"]}]"#,
+            ],
+            commands
+        );
+
+        assert!(super::channels_to_commands(&["trade:KRW-BTC".to_string()], false).is_empty());
+    }
+}
@@ -1,3 +1,7 @@
+use super::{
+    endpoint::Endpoints,
+    proxy::{proxy_for, Proxy},
+};
 use http::Uri;
 use log::*;
 use rustls::{ClientConfig, ClientSession, StreamOwned};
@@ -62,8 +66,9 @@ fn connect_to_some(
 
 // Usually ws_stream.read_message() blocks forever,
 // with this function, it returns after `timeout` seconds if no data comming in
-fn connect_with_timeout(
+pub(super) fn connect_with_timeout(
     url: &str,
+    proxy: Option<&Proxy>,
     timeout: Option<u64>,
 ) -> Result<(WebSocket<AutoStream>, Response)> {
     let request = url.into_client_request()?;
@@ -78,8 +83,19 @@ fn connect_with_timeout(
         Mode::Plain => 80,
         Mode::Tls => 443,
     });
-    let addrs = (host, port).to_socket_addrs()?;
-    let mut stream = connect_to_some(addrs.as_slice(), &request.uri(), mode, timeout)?;
+    let mut stream = match proxy_for(proxy, host, matches!(mode, Mode::Tls)) {
+        Some(proxy) => {
+            let raw_stream = proxy.connect(host, port)?;
+            if let Some(seconds) = timeout {
+                raw_stream.set_read_timeout(Some(Duration::from_secs(seconds)))?;
+            }
+            wrap_stream(raw_stream, host, mode)?
+        }
+        None => {
+            let addrs = (host, port).to_socket_addrs()?;
+            connect_to_some(addrs.as_slice(), request.uri(), mode, timeout)?
+        }
+    };
     NoDelay::set_nodelay(&mut stream, true)?;
     tungstenite::client(request, stream).map_err(|e| match e {
         HandshakeError::Failure(f) => f,
@@ -87,26 +103,62 @@ fn connect_with_timeout(
     })
 }
 
+// Connects to the endpoint in use, building its url first if needed.
+#[allow(clippy::result_large_err)]
+pub(super) fn connect_endpoint(
+    endpoints: &Endpoints,
+    timeout: Option<u64>,
+) -> Result<(WebSocket<AutoStream>, Response)> {
+    let url = endpoints.connect_url()?;
+    connect_with_timeout(&url, endpoints.proxy(), timeout)
+}
+
+// Changes the read timeout of the underlying TCP socket.
+pub(super) fn set_read_timeout(ws_stream: &mut WebSocket<AutoStream>, timeout: Option<Duration>) {
+    let result = match ws_stream.get_mut() {
+        StreamSwitcher::Plain(stream) => stream.set_read_timeout(timeout),
+        StreamSwitcher::Tls(stream) => stream.sock.set_read_timeout(timeout),
+    };
+    if let Err(err) = result {
+        error!("Failed to set read timeout, {}", err);
+    }
+}
+
 // This function is equivalent to tungstenite::connect(), with an additional benefit that
-// it can make read_message() timeout after 5 seconds
-pub(super) fn connect_with_retry(url: &str, timeout: Option<u64>) -> WebSocket<AutoStream> {
-    let count = 3;
+// it can make read_message() timeout after 5 seconds.
+//
+// Each endpoint is tried 3 times, failing over to the next endpoint after each failure.
+pub(super) fn connect_with_retry(
+    endpoints: &Endpoints,
+    timeout: Option<u64>,
+) -> WebSocket<AutoStream> {
+    let count = 3 * endpoints.len();
     let mut error_msg: String = String::new();
     for i in 0..count {
-        let res = connect_with_timeout(url, timeout);
+        let url = endpoints.current();
+        let res = connect_endpoint(endpoints, timeout);
         match res {
             Ok((ws_stream, _)) => return ws_stream,
             Err(err) => {
-                warn!("Error connecting to {}, {}, aborted", url, err);
-                thread::sleep(time::Duration::from_secs(3));
+                warn!("Error connecting to {}, {}", url, err);
                 if i == count - 1 {
                     error_msg = err.to_string();
+                } else {
+                    endpoints.failover();
+                    // all endpoints failed in this round
+                    if (i + 1) % endpoints.len() == 0 {
+                        thread::sleep(time::Duration::from_secs(3));
+                    }
                 }
             }
         }
     }
 
-    panic!("Error connecting to {}, {}, aborted", url, error_msg);
+    panic!(
+        "Error connecting to {}, {}, aborted",
+        endpoints.current(),
+        error_msg
+    );
 }
 
 pub(super) const CHANNEL_PAIR_DELIMITER: char = ':';
@@ -1,6 +1,25 @@
-use super::utils::connect_with_retry;
+use super::{
+    async_loop::{run_async, AsyncMessageHandler},
+    auth::Auth,
+    endpoint::{Endpoints, UrlProvider},
+    event::{self, EventCallback, WsEvent},
+    heartbeat::{Heartbeat, Watchdog},
+    limits::{self, ConnectionLimits},
+    metrics::{Metrics, MetricsCollector},
+    options::WSClientOptions,
+    proxy::Proxy,
+    rate_limit::RateLimiter,
+    reconnect::{
+        interruptible_sleep, reconnect_with_backoff, ReconnectCallback, ReconnectEvent,
+        ReconnectPolicy,
+    },
+    recorder::{Direction, RecordedFrame, Recorder, Replayer},
+    request::Requests,
+    subscription::{SubscriptionState, Subscriptions},
+    utils::{connect_with_retry, set_read_timeout},
+};
 use std::{
-    collections::HashSet,
+    collections::HashMap,
     io::prelude::*,
     sync::{
         atomic::{AtomicBool, AtomicIsize, Ordering},
@@ -9,71 +28,260 @@ use std::{
     time::{Duration, Instant},
 };
 
+use crossbeam_channel::Receiver;
 use flate2::read::{DeflateDecoder, GzDecoder};
 use log::*;
-use tungstenite::{client::AutoStream, error::ProtocolError, Error, Message, WebSocket};
+use tokio::sync::mpsc::UnboundedSender;
+use tungstenite::{client::AutoStream, Error, Message, WebSocket};
 
 pub(super) enum MiscMessage {
-    WebSocket(Message), // WebSocket message that needs to be sent to the server
-    Reconnect,          // Needs to reconnect
-    Misc,               // Misc message
-    Pong,               // Pong message
-    Normal,             // Normal message will be passed to on_msg
+    WebSocket(Message),       // WebSocket message that needs to be sent to the server
+    Reconnect,                // Needs to reconnect and resubscribe
+    Misc,                     // Misc message
+    Pong,                     // Pong message
+    Normal,                   // Normal message will be passed to on_msg
+    Pause(Duration),          // Stops reading for a while, e.g., rate limited or under maintenance
+    ReconnectAfter(Duration), // Needs to reconnect after a while, e.g., under maintenance
+    Subscribed(Vec<String>),  // The server confirmed subscriptions of these raw channels
+    // The server confirmed subscriptions without telling which channels, all
+    // pending channels are confirmed
+    SubscribedPending,
+    // The server rejected subscriptions of these raw channels, channels are
+    // empty if the exchange doesn't identify them in the error message
+    Rejected {
+        channels: Vec<String>,
+        code: Option<i64>,
+        reason: String,
+    },
+}
+
+// What the run loop should do after handling a message
+pub(super) enum Handled {
+    Normal,    // A normal message was passed to on_msg
+    Misc,      // Misc message
+    Reconnect, // Needs to reconnect and resubscribe
+    // Stops reading for a while, the blocking loop sleeps, while the async
+    // loop awaits so that it doesn't block the tokio worker
+    Pause(Duration),
+    ReconnectAfter(Duration), // Needs to reconnect after a while
 }
 
 pub(super) struct WSClientInternal<'a> {
     exchange: &'static str, // Eexchange name
-    pub(super) url: String, // Websocket base url
-    ws_stream: Mutex<WebSocket<AutoStream>>,
-    channels: Mutex<HashSet<String>>, // subscribed channels
+    endpoints: Endpoints,   // Websocket base urls, the client fails over among them
+    // None if created by new_async(), the async run loop owns the socket instead
+    ws_stream: Mutex<Option<WebSocket<AutoStream>>>,
+    // sends messages to the async run loop, Some only while it is connected
+    command_tx: Mutex<Option<UnboundedSender<Message>>>,
+    subscriptions: Subscriptions, // subscribed channels and their states
     on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>, // user defined message callback
+    on_event: Option<EventCallback<'a>>, // user defined event callback
     on_misc_msg: fn(&str) -> MiscMessage, // handle misc messages
     // converts raw channels to subscribe/unsubscribe commands
     channels_to_commands: fn(&[String], bool) -> Vec<String>,
     should_stop: AtomicBool, // used by close() and run()
-    // how often the client should send a ping, None means the client doesn't need to send
-    // ping, instead the server will send ping and the client just needs to reply a pong
-    client_ping_interval_and_msg: Option<(u64, &'static str)>,
-    // Number of unanswered client ping messages, if greater than 3, the process will exit
+    // pings and idle timeouts, defaults of the exchange overridden by ConnectionConfig
+    heartbeat: Heartbeat,
+    // Number of unanswered client ping messages, the client will reconnect if
+    // greater than max_unanswered_pings
     num_unanswered_ping: AtomicIsize,
-    // How often the server sends a ping, only one of client_ping_interval_and_msg
-    // and server_ping_interval should exist
-    #[allow(dead_code)]
-    server_ping_interval: Option<u64>,
+    reconnect_policy: Mutex<ReconnectPolicy>,
+    on_reconnect: Mutex<Option<ReconnectCallback<'a>>>,
+    auth: Mutex<Option<Auth>>, // Some if it is a private client
+    token_renewal: Mutex<Option<Receiver<String>>>, // tokens renewed in the background
+    rate_limiter: RateLimiter, // queues commands over the rate limit of the exchange
+    requests: Requests,        // commands in flight, whose acks echo their ids
+    recorder: Mutex<Option<Recorder>>, // records all frames if Some
+    metrics: MetricsCollector, // health metrics of the connection
 }
 
 impl<'a> WSClientInternal<'a> {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         exchange: &'static str,
-        url: &str,
+        endpoints: &[&str],
+        url_provider: Option<UrlProvider>,
         on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>,
         on_misc_msg: fn(&str) -> MiscMessage,
         channels_to_commands: fn(&[String], bool) -> Vec<String>,
         client_ping_interval_and_msg: Option<(u64, &'static str)>,
         server_ping_interval: Option<u64>,
+        options: &WSClientOptions<'a>,
     ) -> Self {
-        let timeout = if client_ping_interval_and_msg.is_some() && server_ping_interval.is_some() {
-            panic!(
-                "Only one of client_ping_interval_and_msg and server_ping_interval can have value"
-            );
-        } else if let Some(timeout) = client_ping_interval_and_msg {
-            Some(timeout.0 / 2)
-        } else {
-            server_ping_interval
-        };
-        let stream = connect_with_retry(url, timeout);
+        let client = Self::new_async(
+            exchange,
+            endpoints,
+            url_provider,
+            on_msg,
+            on_misc_msg,
+            channels_to_commands,
+            client_ping_interval_and_msg,
+            server_ping_interval,
+            options,
+        );
+        let stream = connect_with_retry(&client.endpoints, client.heartbeat.read_timeout);
+        *client.ws_stream.lock().unwrap() = Some(stream);
+        client.notify(WsEvent::Connected {
+            url: client.url().to_string(),
+        });
+        client
+    }
+
+    // Creates a client without connecting, run_async() connects later.
+    //
+    // `endpoints` are `options.endpoints`, or default urls of the exchange if
+    // empty, `url_provider` builds the url to connect to of default urls.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_async(
+        exchange: &'static str,
+        endpoints: &[&str],
+        url_provider: Option<UrlProvider>,
+        on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>,
+        on_misc_msg: fn(&str) -> MiscMessage,
+        channels_to_commands: fn(&[String], bool) -> Vec<String>,
+        client_ping_interval_and_msg: Option<(u64, &'static str)>,
+        server_ping_interval: Option<u64>,
+        options: &WSClientOptions<'a>,
+    ) -> Self {
+        let mut endpoints = Endpoints::new(endpoints, options.proxy.clone());
+        if let Some(provider) = url_provider {
+            endpoints = endpoints.with_provider(provider);
+        }
+        let limits = limits::connection_limits(exchange, endpoints.current());
         WSClientInternal {
             exchange,
-            url: url.to_string(),
-            ws_stream: Mutex::new(stream),
+            endpoints,
+            ws_stream: Mutex::new(None),
+            command_tx: Mutex::new(None),
             on_msg,
+            on_event: options.events.clone(),
             on_misc_msg,
-            channels: Mutex::new(HashSet::new()),
+            subscriptions: Subscriptions::new(),
             channels_to_commands,
             should_stop: AtomicBool::new(false),
-            client_ping_interval_and_msg,
+            heartbeat: options
+                .config
+                .resolve(client_ping_interval_and_msg, server_ping_interval),
             num_unanswered_ping: AtomicIsize::new(0),
-            server_ping_interval,
+            reconnect_policy: Mutex::new(ReconnectPolicy::default()),
+            on_reconnect: Mutex::new(None),
+            auth: Mutex::new(None),
+            token_renewal: Mutex::new(None),
+            rate_limiter: RateLimiter::new(limits.max_messages_per_second),
+            requests: Requests::default(),
+            recorder: Mutex::new(None),
+            metrics: MetricsCollector::default(),
+        }
+    }
+
+    // The endpoint in use
+    pub fn url(&self) -> &str {
+        self.endpoints.current()
+    }
+
+    pub fn connection_limits(&self) -> ConnectionLimits {
+        limits::connection_limits(self.exchange, self.url())
+    }
+
+    // Number of commands waiting for the rate limit
+    pub fn num_pending_commands(&self) -> usize {
+        self.rate_limiter.len()
+    }
+
+    pub fn metrics(&self) -> Metrics {
+        self.metrics
+            .snapshot(self.num_unanswered_ping.load(Ordering::Acquire))
+    }
+
+    pub fn set_reconnect_policy(&self, policy: ReconnectPolicy) {
+        *self.reconnect_policy.lock().unwrap() = policy;
+    }
+
+    pub fn on_reconnect(&self, callback: Arc<Mutex<dyn FnMut(ReconnectEvent) + 'a + Send>>) {
+        *self.on_reconnect.lock().unwrap() = Some(callback);
+    }
+
+    // Records all frames from now on, starting with the endpoint in use if
+    // connected.
+    pub fn set_recorder(&self, recorder: Recorder) {
+        if self.ws_stream.lock().unwrap().is_some() || self.command_tx.lock().unwrap().is_some() {
+            recorder.record(
+                Direction::Outbound,
+                RecordedFrame::Connected(self.url().to_string()),
+            );
+        }
+        *self.recorder.lock().unwrap() = Some(recorder);
+    }
+
+    fn record(&self, direction: Direction, msg: &Message) {
+        if let Some(recorder) = self.recorder.lock().unwrap().as_ref() {
+            recorder.record_message(direction, msg);
+        }
+    }
+
+    fn record_connected(&self) {
+        if let Some(recorder) = self.recorder.lock().unwrap().as_ref() {
+            recorder.record(
+                Direction::Outbound,
+                RecordedFrame::Connected(self.url().to_string()),
+            );
+        }
+    }
+
+    // Feeds inbound frames of a recording to the same path as live frames.
+    pub fn replay(&self, replayer: &Replayer) {
+        replayer.replay(|msg| {
+            if let Handled::Reconnect | Handled::ReconnectAfter(_) = self.handle_message(msg) {
+                warn!("The server asked {} to reconnect", self.exchange);
+            }
+        });
+    }
+
+    // Makes it a private client, authenticates the current connection if
+    // connected, and every connection from now on.
+    pub fn set_auth(&self, auth: Auth) {
+        *self.auth.lock().unwrap() = Some(auth);
+        self.send_commands(self.login_commands());
+    }
+
+    // Token attached to private channels, see `Auth::Token`
+    pub fn auth_token(&self) -> Option<String> {
+        match self.auth.lock().unwrap().as_ref() {
+            Some(Auth::Token(token)) => Some(token.clone()),
+            _ => None,
+        }
+    }
+
+    // Receives new tokens renewed in the background, see `renew_token()`.
+    pub fn set_token_renewal(&self, rx: Receiver<String>) {
+        *self.token_renewal.lock().unwrap() = Some(rx);
+    }
+
+    // Replaces the token with the latest one renewed in the background, and
+    // moves the private channel named by the old token to the new one, e.g.,
+    // the user data stream of Binance after its listenKey expired.
+    fn renew_token(&self) {
+        let token = match self.token_renewal.lock().unwrap().as_ref() {
+            Some(rx) => rx.try_iter().last(),
+            None => None,
+        };
+        if let Some(token) = token {
+            let old_token = self.auth_token();
+            *self.auth.lock().unwrap() = Some(Auth::Token(token.clone()));
+            info!("Renewed the token of {}", self.url());
+            // the old token expired already, no need to unsubscribe
+            if let Some(old_token) = old_token {
+                if !self.subscriptions.remove(&[old_token]).is_empty() {
+                    self.subscribe(&[token]);
+                }
+            }
+        }
+    }
+
+    fn login_commands(&self) -> Vec<String> {
+        match self.auth.lock().unwrap().as_ref() {
+            Some(Auth::Login(login)) => login(),
+            _ => Vec::new(),
         }
     }
 
@@ -85,258 +293,352 @@ impl<'a> WSClientInternal<'a> {
         self.subscribe_or_unsubscribe(channels, false);
     }
 
+    pub fn subscriptions(&self) -> HashMap<String, SubscriptionState> {
+        self.subscriptions.snapshot()
+    }
+
     fn subscribe_or_unsubscribe(&self, channels: &[String], subscribe: bool) {
-        let mut diff = Vec::<String>::new();
-        {
-            let mut guard = self.channels.lock().unwrap();
-            for ch in channels.iter() {
-                if guard.insert(ch.clone()) {
-                    diff.push(ch.clone());
-                }
+        let diff = if subscribe {
+            self.subscriptions.add(channels)
+        } else {
+            self.subscriptions.remove(channels)
+        };
+
+        if !diff.is_empty() {
+            self.send_commands(self.channels_to_commands(&diff, subscribe));
+        }
+    }
+
+    // Converts raw channels to commands, ids in commands are registered to
+    // this client.
+    fn channels_to_commands(&self, channels: &[String], subscribe: bool) -> Vec<String> {
+        self.requests
+            .scope(|| (self.channels_to_commands)(channels, subscribe))
+    }
+
+    // Sends commands as fast as the rate limit allows, the rest are queued
+    // and sent by the run loop later.
+    fn send_commands(&self, commands: Vec<String>) {
+        self.rate_limiter.push(commands);
+        self.flush_commands();
+    }
+
+    // Sends queued commands allowed by the rate limit.
+    fn flush_commands(&self) {
+        self.renew_token();
+        if self.ws_stream.lock().unwrap().is_none() && self.command_tx.lock().unwrap().is_none() {
+            return; // not connected yet, all channels are subscribed after connected
+        }
+        for command in self.rate_limiter.pop_ready() {
+            self.write_message(Message::Text(command));
+        }
+    }
+
+    // Writes to the blocking socket, or passes to the async run loop.
+    //
+    // Before the async run loop connects, messages are dropped, because it
+    // subscribes all channels after connected.
+    fn write_message(&self, msg: Message) {
+        if let Some(ws_stream) = self.ws_stream.lock().unwrap().as_mut() {
+            self.record(Direction::Outbound, &msg);
+            if let Err(err) = ws_stream.write_message(msg) {
+                error!("{}", err);
+            }
+        } else if let Some(tx) = self.command_tx.lock().unwrap().as_ref() {
+            if tx.send(msg).is_err() {
+                error!("The async run loop of {} has exited", self.url());
             }
         }
+    }
 
-        if !diff.is_empty() {
-            let commands = (self.channels_to_commands)(&diff, subscribe);
-            let mut ws_stream = self.ws_stream.lock().unwrap();
-            commands.into_iter().for_each(|command| {
-                let ret = ws_stream.write_message(Message::Text(command));
-                if let Err(err) = ret {
-                    error!("{}", err);
-                }
-            });
+    fn notify(&self, event: WsEvent) {
+        event::notify(&self.on_event, event);
+    }
+
+    fn notify_reconnected(&self, attempts: u32) {
+        self.metrics.on_reconnected();
+        let num_channels = self.subscriptions.len();
+        info!(
+            "Reconnected to {} after {} attempts, resubscribed {} channels",
+            self.url(),
+            attempts,
+            num_channels
+        );
+
+        let reconnect_event = ReconnectEvent {
+            url: self.url().to_string(),
+            attempts,
+            num_channels,
+        };
+        let callback = self.on_reconnect.lock().unwrap().clone();
+        if let Some(on_reconnect) = callback {
+            (on_reconnect.lock().unwrap())(reconnect_event.clone());
         }
+        self.notify(WsEvent::Reconnected(reconnect_event));
     }
 
-    // reconnect and subscribe all channels
-    fn _reconnect(&self) {
-        warn!("Reconnecting to {}", &self.url);
-        {
-            let mut guard = self.ws_stream.lock().unwrap();
-            let timeout = if self.client_ping_interval_and_msg.is_some()
-                && self.server_ping_interval.is_some()
-            {
-                panic!(
-                    "Only one of client_ping_interval_and_msg and server_ping_interval can have value"
-                );
-            } else if let Some(timeout) = self.client_ping_interval_and_msg {
-                Some(timeout.0 / 2)
-            } else {
-                self.server_ping_interval
-            };
-            *guard = connect_with_retry(self.url.as_str(), timeout);
+    // Reconnects and subscribes all channels again.
+    //
+    // Returns false if the client was closed or the reconnect policy gave up.
+    fn reconnect(&self, reason: String) -> bool {
+        if self.should_stop.load(Ordering::Acquire) {
+            return false;
         }
-        let channels = self
-            .channels
-            .lock()
-            .unwrap()
-            .iter()
-            .map(|s| s.to_string())
-            .collect::<Vec<String>>();
+        self.notify(WsEvent::Disconnected { reason });
+        warn!("Reconnecting to {}", self.url());
+        let timeout = self.heartbeat.read_timeout;
+        let policy = self.reconnect_policy.lock().unwrap().clone();
+        let (stream, attempts) =
+            match reconnect_with_backoff(&self.endpoints, timeout, &policy, &self.should_stop) {
+                Some(x) => x,
+                None => return false,
+            };
+        *self.ws_stream.lock().unwrap() = Some(stream);
+        self.record_connected();
+        self.num_unanswered_ping.store(0, Ordering::Release);
+
+        self.rate_limiter.clear();
+        self.requests.clear();
+        let mut commands = self.login_commands();
+        let channels = self.subscriptions.reset();
         if !channels.is_empty() {
-            let commands = (self.channels_to_commands)(&channels, true);
-            let mut ws_stream = self.ws_stream.lock().unwrap();
-            commands.into_iter().for_each(|command| {
-                let ret = ws_stream.write_message(Message::Text(command));
-                if let Err(err) = ret {
-                    error!("{}", err);
-                }
-            });
+            commands.append(&mut self.channels_to_commands(&channels, true));
+        }
+        self.send_commands(commands);
+        self.notify_reconnected(attempts);
+        true
+    }
+
+    fn confirm(&self, channels: &[String]) {
+        let confirmed = self
+            .subscriptions
+            .update(channels, SubscriptionState::Confirmed);
+        for channel in confirmed {
+            self.notify(WsEvent::Subscribed { channel });
         }
     }
 
     // Handle a text msg from Message::Text or Message::Binary
-    // Returns true if gets a normal message, otherwise false
-    fn handle_msg(&self, txt: &str) -> bool {
-        match (self.on_misc_msg)(txt) {
-            MiscMessage::Misc => false,
+    fn handle_msg(&self, txt: &str) -> Handled {
+        match self.requests.scope(|| (self.on_misc_msg)(txt)) {
+            MiscMessage::Misc => Handled::Misc,
             MiscMessage::Pong => {
                 self.num_unanswered_ping.store(0, Ordering::Release);
-                false
+                Handled::Misc
             }
-            MiscMessage::Reconnect => {
-                // self.reconnect();
-                std::thread::sleep(Duration::from_secs(5));
-                std::process::exit(0); // fail fast, pm2 will restart
+            MiscMessage::Reconnect => Handled::Reconnect,
+            MiscMessage::Pause(duration) => Handled::Pause(duration),
+            MiscMessage::ReconnectAfter(duration) => Handled::ReconnectAfter(duration),
+            MiscMessage::Subscribed(channels) => {
+                self.confirm(&channels);
+                Handled::Misc
             }
-            MiscMessage::WebSocket(ws_msg) => {
-                let ret = self.ws_stream.lock().unwrap().write_message(ws_msg);
-                if let Err(err) = ret {
-                    error!("{}", err);
+            MiscMessage::SubscribedPending => {
+                self.confirm(&self.subscriptions.pending());
+                Handled::Misc
+            }
+            MiscMessage::Rejected {
+                channels,
+                code,
+                reason,
+            } => {
+                error!(
+                    "{} rejected subscriptions of {:?}, {}",
+                    self.exchange, channels, reason
+                );
+                self.subscriptions
+                    .update(&channels, SubscriptionState::Rejected);
+                for event in event::subscribe_failed(&channels, code, &reason) {
+                    self.notify(event);
                 }
-                false
+                Handled::Misc
+            }
+            MiscMessage::WebSocket(ws_msg) => {
+                self.write_message(ws_msg);
+                Handled::Misc
             }
             MiscMessage::Normal => {
-                if self.exchange == super::mxc::EXCHANGE_NAME
-                    && self.url.as_str() == super::mxc::SPOT_WEBSOCKET_URL
-                {
-                    // special logic for MXC Spot
-                    match txt.strip_prefix("42") {
-                        Some(msg) => (self.on_msg.lock().unwrap())(msg.to_string()),
-                        None => error!(
-                            "{}, Not possible, should be handled by {}.on_misc_msg() previously",
-                            txt, self.exchange
-                        ),
-                    }
+                if self.exchange == super::mxc::EXCHANGE_NAME && txt.starts_with("42") {
+                    // special logic for MXC Spot, which uses socket.io
+                    self.metrics.on_normal_msg(&txt[2..]);
+                    (self.on_msg.lock().unwrap())(txt[2..].to_string());
                 } else {
+                    self.metrics.on_normal_msg(txt);
                     (self.on_msg.lock().unwrap())(txt.to_string());
                 }
-                true
+                Handled::Normal
+            }
+        }
+    }
+
+    // Decompresses a binary message and handles it
+    fn handle_binary(&self, binary: &[u8]) -> Handled {
+        let mut txt = String::new();
+        let resp = match self.exchange {
+            super::huobi::EXCHANGE_NAME
+            | super::binance::EXCHANGE_NAME
+            | super::bitget::EXCHANGE_NAME
+            | super::bitz::EXCHANGE_NAME => {
+                let mut decoder = GzDecoder::new(binary);
+                decoder.read_to_string(&mut txt)
+            }
+            super::okex::EXCHANGE_NAME => {
+                let mut decoder = DeflateDecoder::new(binary);
+                decoder.read_to_string(&mut txt)
+            }
+            // Upbit sends uncompressed JSON in binary frames
+            super::upbit::EXCHANGE_NAME => {
+                let mut reader = binary;
+                reader.read_to_string(&mut txt)
+            }
+            _ => {
+                warn!(
+                    "Skipped a binary frame of unknown format from {}",
+                    self.url()
+                );
+                return Handled::Misc;
+            }
+        };
+
+        match resp {
+            Ok(_) => self.handle_msg(&txt),
+            Err(err) => {
+                error!("Decompression failed, {}", err);
+                self.metrics.on_decompression_failure();
+                Handled::Misc
+            }
+        }
+    }
+
+    // Handles a frame read from the socket, or replayed from a recording
+    fn handle_message(&self, msg: Message) -> Handled {
+        match msg {
+            Message::Text(txt) => self.handle_msg(&txt),
+            Message::Binary(binary) => self.handle_binary(&binary),
+            Message::Ping(resp) => {
+                info!("Received a ping frame: {}", String::from_utf8_lossy(&resp));
+                self.write_message(Message::Pong(resp));
+                Handled::Misc
+            }
+            Message::Pong(resp) => {
+                info!("Received a pong frame: {}", String::from_utf8_lossy(&resp));
+                self.num_unanswered_ping.store(0, Ordering::Release);
+                Handled::Misc
+            }
+            Message::Close(resp) => {
+                match resp {
+                    Some(frame) => warn!(
+                        "Received a Message::Close message with a CloseFrame: code: {}, reason: {}",
+                        frame.code, frame.reason
+                    ),
+                    None => warn!("Received a close message without CloseFrame"),
+                }
+                Handled::Misc
             }
         }
     }
 
     pub fn run(&self, duration: Option<u64>) {
         let start_timstamp = Instant::now();
-        let mut last_ping_timestamp = Instant::now();
-        let mut num_read_timeout = 0;
+        let mut watchdog = Watchdog::new(&self.heartbeat);
+        let timeout = self.heartbeat.read_timeout.map(Duration::from_secs);
+        // true if the read timeout is shortened to send queued commands on time
+        let mut throttled = false;
         while !self.should_stop.load(Ordering::Acquire) {
-            let resp = self.ws_stream.lock().unwrap().read_message();
+            self.flush_commands();
+            let delay = self.rate_limiter.delay();
+            let resp = {
+                let mut guard = self.ws_stream.lock().unwrap();
+                let ws_stream = guard
+                    .as_mut()
+                    .expect("Created by new_async(), call run_async() instead");
+                if delay.is_some() || throttled {
+                    let delay = delay.map(|x| x.max(Duration::from_millis(1)));
+                    set_read_timeout(ws_stream, delay.or(timeout));
+                    throttled = delay.is_some();
+                }
+                ws_stream.read_message()
+            };
+            // Some(reason) if the connection is considered dropped
+            let mut disconnected: Option<String> = None;
             let normal = match resp {
                 Ok(msg) => {
-                    num_read_timeout = 0;
-                    match msg {
-                        Message::Text(txt) => self.handle_msg(&txt),
-                        Message::Binary(binary) => {
-                            let mut txt = String::new();
-                            let resp = match self.exchange {
-                                super::huobi::EXCHANGE_NAME
-                                | super::binance::EXCHANGE_NAME
-                                | super::bitget::EXCHANGE_NAME
-                                | super::bitz::EXCHANGE_NAME => {
-                                    let mut decoder = GzDecoder::new(&binary[..]);
-                                    decoder.read_to_string(&mut txt)
-                                }
-                                super::okex::EXCHANGE_NAME => {
-                                    let mut decoder = DeflateDecoder::new(&binary[..]);
-                                    decoder.read_to_string(&mut txt)
-                                }
-                                _ => {
-                                    error!("Unknown binary format from {}", self.url);
-                                    panic!("Unknown binary format from {}", self.url);
-                                }
-                            };
-
-                            match resp {
-                                Ok(_) => self.handle_msg(&txt),
-                                Err(err) => {
-                                    error!("Decompression failed, {}", err);
-                                    false
-                                }
-                            }
+                    watchdog.on_frame();
+                    self.record(Direction::Inbound, &msg);
+                    self.metrics.on_frame(&msg);
+                    let handled = self.handle_message(msg);
+                    match handled {
+                        Handled::Normal => {
+                            watchdog.on_data();
+                            true
                         }
-                        Message::Ping(resp) => {
-                            info!(
-                                "Received a ping frame: {}",
-                                std::str::from_utf8(&resp).unwrap()
-                            );
-                            let ret = self
-                                .ws_stream
-                                .lock()
-                                .unwrap()
-                                .write_message(Message::Pong(resp));
-                            if let Err(err) = ret {
-                                error!("{}", err);
-                            }
+                        Handled::Misc => false,
+                        Handled::Pause(duration) => {
+                            interruptible_sleep(duration, &self.should_stop);
+                            watchdog.reset(); // nothing is expected while paused
                             false
                         }
-                        Message::Pong(resp) => {
-                            let tmp = std::str::from_utf8(&resp);
-                            info!("Received a pong frame: {}", tmp.unwrap());
-                            self.num_unanswered_ping.store(0, Ordering::Release);
+                        Handled::Reconnect => {
+                            disconnected = Some("The server asked to reconnect".to_string());
                             false
                         }
-                        Message::Close(resp) => {
-                            match resp {
-                                Some(frame) => warn!("Received a Message::Close message with a CloseFrame: code: {}, reason: {}", frame.code, frame.reason),
-                                None => warn!("Received a close message without CloseFrame"),
-                            }
+                        Handled::ReconnectAfter(duration) => {
+                            interruptible_sleep(duration, &self.should_stop);
+                            disconnected = Some("The server asked to reconnect".to_string());
                             false
                         }
                     }
                 }
+                Err(Error::Io(io_err)) if io_err.kind() == std::io::ErrorKind::WouldBlock => {
+                    if !throttled {
+                        info!("read_message() timeout");
+                        watchdog.on_read_timeout();
+                        self.metrics.on_read_timeout();
+                    }
+                    false
+                }
                 Err(err) => {
-                    match err {
-                        Error::ConnectionClosed => {
-                            error!("Server closed connection, exiting now...");
-                            // self.reconnect();
-                            std::thread::sleep(Duration::from_secs(5));
-                            break; // fail fast, pm2 will restart
-                        }
-                        Error::AlreadyClosed => {
-                            error!("Impossible to happen, fix the bug in the code");
-                            panic!("Impossible to happen, fix the bug in the code");
-                        }
-                        Error::Io(io_err) => {
-                            if io_err.kind() == std::io::ErrorKind::WouldBlock {
-                                info!("read_message() timeout");
-                                num_read_timeout += 1;
-                            } else {
-                                error!(
-                                    "I/O error thrown from read_message(): {}, {:?}",
-                                    io_err,
-                                    io_err.kind()
-                                );
-                                // self.reconnect();
-                                std::thread::sleep(Duration::from_secs(5));
-                                break; // fail fast, pm2 will restart
-                            }
-                        }
-                        Error::Protocol(protocol_err) => {
-                            if protocol_err == ProtocolError::ResetWithoutClosingHandshake {
-                                error!("ResetWithoutClosingHandshake");
-                                // self.reconnect();
-                                std::thread::sleep(Duration::from_secs(5));
-                                break; // fail fast, pm2 will restart
-                            } else {
-                                error!(
-                                    "Protocol error thrown from read_message(): {}",
-                                    protocol_err
-                                );
-                            }
-                        }
-                        _ => {
-                            error!("Error thrown from read_message(): {}", err);
-                            panic!("Error thrown from read_message(): {}", err);
-                        }
+                    // Every other error means the connection is unusable, such as
+                    // closed, reset, TLS, protocol or capacity errors
+                    error!("Error thrown from read_message(): {}, {}", err, self.url());
+                    if !matches!(err, Error::ConnectionClosed | Error::AlreadyClosed) {
+                        self.endpoints.failover();
                     }
-
+                    disconnected = Some(err.to_string());
                     false
                 }
             };
 
-            if let Some(interval_and_msg) = self.client_ping_interval_and_msg {
+            if disconnected.is_none() {
                 let num_unanswered_ping = self.num_unanswered_ping.load(Ordering::Acquire);
-                if num_unanswered_ping > 5 {
+                if let Some(reason) = watchdog.check(num_unanswered_ping) {
                     error!(
-                        "num_unanswered_ping: {}, duration: {} seconds",
-                        num_unanswered_ping,
+                        "{}, duration: {} seconds",
+                        reason,
                         start_timstamp.elapsed().as_secs()
                     );
-                    break; // fail fast, pm2 will restart
-                }
-                if last_ping_timestamp.elapsed() >= Duration::from_secs(interval_and_msg.0 / 2) {
-                    info!("Sending ping: {}", interval_and_msg.1);
+                    disconnected = Some(reason);
+                    self.endpoints.failover();
+                } else if watchdog.ping_due() {
+                    let ping_msg = &self.heartbeat.ping.as_ref().unwrap().1;
+                    info!("Sending ping: {}", ping_msg);
                     // send ping
-                    let ping_msg = if interval_and_msg.1.is_empty() {
+                    let ping_msg = if ping_msg.is_empty() {
                         Message::Ping(Vec::new())
                     } else {
-                        Message::Text(interval_and_msg.1.to_string())
+                        Message::Text(ping_msg.to_string())
                     };
-                    last_ping_timestamp = Instant::now();
-                    if let Err(err) = self.ws_stream.lock().unwrap().write_message(ping_msg) {
-                        error!("{}", err);
-                    } else {
-                        self.num_unanswered_ping.fetch_add(1, Ordering::SeqCst);
-                    }
+                    self.write_message(ping_msg);
+                    watchdog.on_ping_sent(num_unanswered_ping);
+                    self.num_unanswered_ping.fetch_add(1, Ordering::SeqCst);
                 }
-            } else if num_read_timeout > 5 {
-                error!(
-                    "num_read_timeout: {}, duration: {} seconds",
-                    num_read_timeout,
-                    start_timstamp.elapsed().as_secs()
-                );
-                break; // fail fast, pm2 will restart
+            }
+
+            if let Some(reason) = disconnected {
+                if !self.reconnect(reason) {
+                    break;
+                }
+                watchdog.reset();
             }
 
             if let Some(seconds) = duration {
@@ -347,78 +649,477 @@ impl<'a> WSClientInternal<'a> {
         }
     }
 
+    pub async fn run_async(&self, duration: Option<u64>) {
+        run_async(self, duration).await;
+    }
+
     pub fn close(&self) {
         self.should_stop.store(true, Ordering::Release);
-        let ret = self.ws_stream.lock().unwrap().close(None);
-        if let Err(err) = ret {
-            error!("{}", err);
+        if let Some(ws_stream) = self.ws_stream.lock().unwrap().as_mut() {
+            self.record(Direction::Outbound, &Message::Close(None));
+            if let Err(err) = ws_stream.close(None) {
+                error!("{}", err);
+            }
+        } else if let Some(tx) = self.command_tx.lock().unwrap().as_ref() {
+            let _ = tx.send(Message::Close(None));
+        }
+    }
+}
+
+impl<'a> AsyncMessageHandler for WSClientInternal<'a> {
+    fn url(&self) -> &str {
+        self.endpoints.current()
+    }
+
+    fn proxy(&self) -> Option<&Proxy> {
+        self.endpoints.proxy()
+    }
+
+    fn url_provider(&self) -> Option<UrlProvider> {
+        self.endpoints.provider()
+    }
+
+    fn failover(&self) {
+        self.endpoints.failover();
+    }
+
+    fn heartbeat(&self) -> &Heartbeat {
+        &self.heartbeat
+    }
+
+    fn num_unanswered_ping(&self) -> isize {
+        self.num_unanswered_ping.load(Ordering::Acquire)
+    }
+
+    fn on_ping_sent(&self) {
+        self.num_unanswered_ping.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn on_pong(&self) {
+        self.num_unanswered_ping.store(0, Ordering::Release);
+    }
+
+    fn should_stop(&self) -> bool {
+        self.should_stop.load(Ordering::Acquire)
+    }
+
+    fn reconnect_policy(&self) -> ReconnectPolicy {
+        self.reconnect_policy.lock().unwrap().clone()
+    }
+
+    fn handle_text(&self, txt: &str) -> Handled {
+        self.handle_msg(txt)
+    }
+
+    fn handle_binary(&self, binary: &[u8]) -> Handled {
+        self.handle_binary(binary)
+    }
+
+    fn record(&self, direction: Direction, msg: &Message) {
+        WSClientInternal::record(self, direction, msg);
+    }
+
+    fn on_frame(&self, msg: &Message) {
+        self.metrics.on_frame(msg);
+    }
+
+    fn on_read_timeout(&self) {
+        self.metrics.on_read_timeout();
+    }
+
+    fn attach(&self, tx: UnboundedSender<Message>) -> Vec<String> {
+        *self.command_tx.lock().unwrap() = Some(tx);
+        self.record_connected();
+        self.num_unanswered_ping.store(0, Ordering::Release);
+        self.rate_limiter.clear();
+        self.requests.clear();
+        let mut commands = self.login_commands();
+        let channels = self.subscriptions.reset();
+        if !channels.is_empty() {
+            commands.append(&mut self.channels_to_commands(&channels, true));
         }
+        self.rate_limiter.push(commands);
+        self.rate_limiter.pop_ready()
+    }
+
+    fn command_period(&self) -> Option<Duration> {
+        self.rate_limiter.period()
+    }
+
+    fn flush_commands(&self) {
+        WSClientInternal::flush_commands(self);
+    }
+
+    fn detach(&self) {
+        *self.command_tx.lock().unwrap() = None;
+    }
+
+    fn on_connected(&self) {
+        self.notify(WsEvent::Connected {
+            url: self.url().to_string(),
+        });
+    }
+
+    fn on_disconnected(&self, reason: String) {
+        self.notify(WsEvent::Disconnected { reason });
+    }
+
+    fn on_reconnected(&self, attempts: u32) {
+        self.notify_reconnected(attempts);
     }
 }
 
 /// Define exchange specific client.
 macro_rules! define_client {
     ($struct_name:ident, $exchange:ident, $default_url:expr, $channels_to_commands:ident, $on_misc_msg:ident, $client_ping_interval_and_msg:expr, $server_ping_interval:expr) => {
+        define_client!(
+            $struct_name,
+            $exchange,
+            $default_url,
+            $channels_to_commands,
+            $on_misc_msg,
+            $client_ping_interval_and_msg,
+            $server_ping_interval,
+            None
+        );
+    };
+    // `$url_provider` builds the url to connect to of `$default_url`, see `UrlProvider`
+    ($struct_name:ident, $exchange:ident, $default_url:expr, $channels_to_commands:ident, $on_misc_msg:ident, $client_ping_interval_and_msg:expr, $server_ping_interval:expr, $url_provider:expr) => {
         impl<'a> WSClient<'a> for $struct_name<'a> {
-            fn new(
+            fn new_with_options(
                 on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>,
-                url: Option<&str>,
+                options: $crate::WSClientOptions<'a>,
             ) -> $struct_name<'a> {
-                let real_url = match url {
-                    Some(endpoint) => endpoint,
-                    None => $default_url,
-                };
+                $struct_name::new_blocking(on_msg, &options)
+            }
+
+            fn endpoint(&self) -> &str {
+                self.client.url()
+            }
+
+            fn connection_limits(&self) -> $crate::ConnectionLimits {
+                self.client.connection_limits()
+            }
+
+            fn num_pending_commands(&self) -> usize {
+                self.client.num_pending_commands()
+            }
+
+            fn metrics(&self) -> $crate::Metrics {
+                self.client.metrics()
+            }
+
+            fn translate_channels(
+                &self,
+                channels: &[$crate::Channel],
+            ) -> Result<Vec<String>, $crate::WsError> {
+                self.to_raw_channels($exchange, channels)
+            }
+
+            fn capabilities(&self) -> Vec<$crate::ChannelKind> {
+                ChannelTranslator::capabilities(self)
+            }
+
+            fn subscribe(&self, channels: &[String]) {
+                self.client.subscribe(channels);
+            }
+
+            fn unsubscribe(&self, channels: &[String]) {
+                self.client.unsubscribe(channels);
+            }
+
+            fn subscriptions(
+                &self,
+            ) -> std::collections::HashMap<String, $crate::SubscriptionState> {
+                self.client.subscriptions()
+            }
+
+            fn run(&self, duration: Option<u64>) {
+                self.client.run(duration);
+            }
+
+            fn close(&self) {
+                self.client.close();
+            }
+
+            fn set_reconnect_policy(&self, policy: $crate::ReconnectPolicy) {
+                self.client.set_reconnect_policy(policy);
+            }
+
+            fn on_reconnect(
+                &self,
+                callback: Arc<Mutex<dyn FnMut($crate::ReconnectEvent) + 'a + Send>>,
+            ) {
+                self.client.on_reconnect(callback);
+            }
+
+            fn set_recorder(&self, recorder: $crate::Recorder) {
+                self.client.set_recorder(recorder);
+            }
+
+            fn replay(
+                on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>,
+                replayer: &$crate::Replayer,
+            ) {
+                let options = $crate::WSClientOptions::from_url(replayer.url());
+                let client = $struct_name::new_async(on_msg, &options);
+                client.client.replay(replayer);
+            }
+        }
+
+        impl<'a> $struct_name<'a> {
+            fn new_blocking(
+                on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>,
+                options: &$crate::WSClientOptions<'a>,
+            ) -> $struct_name<'a> {
+                let urls = options.endpoint_urls();
+                // The provider only builds urls of the exchange
+                let (endpoints, url_provider): (_, Option<$crate::clients::endpoint::UrlProvider>) =
+                    if urls.is_empty() {
+                        (vec![$default_url], $url_provider)
+                    } else {
+                        (urls, None)
+                    };
                 $struct_name {
                     client: WSClientInternal::new(
                         $exchange,
-                        real_url,
+                        &endpoints,
+                        url_provider,
                         on_msg,
                         $on_misc_msg,
                         $channels_to_commands,
                         $client_ping_interval_and_msg,
                         $server_ping_interval,
+                        options,
                     ),
                 }
             }
 
-            fn subscribe_trade(&self, channels: &[String]) {
-                <$struct_name as Trade>::subscribe_trade(self, channels);
+            fn new_async(
+                on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>,
+                options: &$crate::WSClientOptions<'a>,
+            ) -> $struct_name<'a> {
+                let urls = options.endpoint_urls();
+                // The provider only builds urls of the exchange
+                let (endpoints, url_provider): (_, Option<$crate::clients::endpoint::UrlProvider>) =
+                    if urls.is_empty() {
+                        (vec![$default_url], $url_provider)
+                    } else {
+                        (urls, None)
+                    };
+                $struct_name {
+                    client: WSClientInternal::new_async(
+                        $exchange,
+                        &endpoints,
+                        url_provider,
+                        on_msg,
+                        $on_misc_msg,
+                        $channels_to_commands,
+                        $client_ping_interval_and_msg,
+                        $server_ping_interval,
+                        options,
+                    ),
+                }
             }
 
-            fn subscribe_orderbook(&self, channels: &[String]) {
-                <$struct_name as OrderBook>::subscribe_orderbook(self, channels);
+            async fn run_async(&self, duration: Option<u64>) {
+                self.client.run_async(duration).await;
+            }
+        }
+    };
+}
+
+/// Define the async version of an exchange specific client.
+///
+/// `$client` must have `new_async()` and `run_async()`, everything else is
+/// delegated to its `WSClient` implementation, which never blocks because
+/// the async run loop owns the socket.
+macro_rules! define_async_client {
+    ($struct_name:ident, $client:ident) => {
+        #[doc = concat!("The async version of [`", stringify!($client), "`].")]
+        pub struct $struct_name<'a> {
+            client: $client<'a>,
+            // notified once the stream of new_with_stream() is dropped
+            stream_dropped: Option<Arc<tokio::sync::Notify>>,
+        }
+
+        #[async_trait::async_trait]
+        impl<'a> $crate::AsyncWSClient<'a> for $struct_name<'a> {
+            fn new_with_options(
+                on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>,
+                options: $crate::WSClientOptions<'a>,
+            ) -> $struct_name<'a> {
+                $struct_name {
+                    client: $client::new_async(on_msg, &options),
+                    stream_dropped: None,
+                }
+            }
+
+            fn new_with_stream(
+                capacity: usize,
+                policy: $crate::BackpressurePolicy,
+                options: $crate::WSClientOptions<'a>,
+            ) -> ($struct_name<'a>, $crate::MessageStream<String>) {
+                // on_msg is called on a tokio worker, which must not block
+                assert!(
+                    policy != $crate::BackpressurePolicy::Block,
+                    "BackpressurePolicy::Block is not allowed in async clients"
+                );
+                let (tx, stream) = $crate::message_stream(capacity, policy);
+                let stream_dropped = Arc::new(tokio::sync::Notify::new());
+                let stream_dropped_clone = stream_dropped.clone();
+                let on_msg = Arc::new(Mutex::new(move |msg: String| {
+                    if !tx.send(msg) {
+                        stream_dropped_clone.notify_one();
+                    }
+                }));
+                let ws_client = $struct_name {
+                    client: $client::new_async(on_msg, &options),
+                    stream_dropped: Some(stream_dropped),
+                };
+                (ws_client, stream)
             }
 
-            fn subscribe_orderbook_snapshot(&self, channels: &[String]) {
-                <$struct_name as OrderBookSnapshot>::subscribe_orderbook_snapshot(self, channels);
+            fn endpoint(&self) -> &str {
+                <$client as WSClient>::endpoint(&self.client)
             }
 
-            fn subscribe_ticker(&self, channels: &[String]) {
-                <$struct_name as Ticker>::subscribe_ticker(self, channels);
+            fn connection_limits(&self) -> $crate::ConnectionLimits {
+                <$client as WSClient>::connection_limits(&self.client)
             }
 
-            fn subscribe_bbo(&self, channels: &[String]) {
-                <$struct_name as BBO>::subscribe_bbo(self, channels);
+            fn num_pending_commands(&self) -> usize {
+                <$client as WSClient>::num_pending_commands(&self.client)
             }
 
-            fn subscribe_candlestick(&self, pairs: &[String], interval: u32) {
-                <$struct_name as Candlestick>::subscribe_candlestick(self, pairs, interval);
+            fn metrics(&self) -> $crate::Metrics {
+                <$client as WSClient>::metrics(&self.client)
             }
 
-            fn subscribe(&self, channels: &[String]) {
-                self.client.subscribe(channels);
+            async fn subscribe_trade(&self, pairs: &[String]) -> Result<(), $crate::WsError> {
+                <$client as WSClient>::subscribe_trade(&self.client, pairs)
             }
 
-            fn unsubscribe(&self, channels: &[String]) {
-                self.client.unsubscribe(channels);
+            async fn subscribe_bbo(&self, pairs: &[String]) -> Result<(), $crate::WsError> {
+                <$client as WSClient>::subscribe_bbo(&self.client, pairs)
             }
 
-            fn run(&self, duration: Option<u64>) {
-                self.client.run(duration);
+            async fn subscribe_orderbook(&self, pairs: &[String]) -> Result<(), $crate::WsError> {
+                <$client as WSClient>::subscribe_orderbook(&self.client, pairs)
+            }
+
+            async fn subscribe_orderbook_snapshot(
+                &self,
+                pairs: &[String],
+            ) -> Result<(), $crate::WsError> {
+                <$client as WSClient>::subscribe_orderbook_snapshot(&self.client, pairs)
+            }
+
+            async fn subscribe_ticker(&self, pairs: &[String]) -> Result<(), $crate::WsError> {
+                <$client as WSClient>::subscribe_ticker(&self.client, pairs)
+            }
+
+            async fn subscribe_candlestick(
+                &self,
+                pairs: &[String],
+                interval: u32,
+            ) -> Result<(), $crate::WsError> {
+                <$client as WSClient>::subscribe_candlestick(&self.client, pairs, interval)
+            }
+
+            async fn subscribe_mark_price(&self, pairs: &[String]) -> Result<(), $crate::WsError> {
+                <$client as WSClient>::subscribe_mark_price(&self.client, pairs)
+            }
+
+            async fn subscribe_index_price(&self, pairs: &[String]) -> Result<(), $crate::WsError> {
+                <$client as WSClient>::subscribe_index_price(&self.client, pairs)
+            }
+
+            async fn subscribe_liquidation(&self, pairs: &[String]) -> Result<(), $crate::WsError> {
+                <$client as WSClient>::subscribe_liquidation(&self.client, pairs)
+            }
+
+            async fn subscribe_open_interest(
+                &self,
+                pairs: &[String],
+            ) -> Result<(), $crate::WsError> {
+                <$client as WSClient>::subscribe_open_interest(&self.client, pairs)
+            }
+
+            fn translate_channels(
+                &self,
+                channels: &[$crate::Channel],
+            ) -> Result<Vec<String>, $crate::WsError> {
+                <$client as WSClient>::translate_channels(&self.client, channels)
+            }
+
+            fn capabilities(&self) -> Vec<$crate::ChannelKind> {
+                <$client as WSClient>::capabilities(&self.client)
+            }
+
+            async fn subscribe_channels(
+                &self,
+                channels: &[$crate::Channel],
+            ) -> Result<(), $crate::WsError> {
+                <$client as WSClient>::subscribe_channels(&self.client, channels)
+            }
+
+            async fn unsubscribe_channels(
+                &self,
+                channels: &[$crate::Channel],
+            ) -> Result<(), $crate::WsError> {
+                <$client as WSClient>::unsubscribe_channels(&self.client, channels)
+            }
+
+            async fn subscribe(&self, raw_channels: &[String]) {
+                <$client as WSClient>::subscribe(&self.client, raw_channels);
+            }
+
+            async fn unsubscribe(&self, raw_channels: &[String]) {
+                <$client as WSClient>::unsubscribe(&self.client, raw_channels);
+            }
+
+            fn subscriptions(
+                &self,
+            ) -> std::collections::HashMap<String, $crate::SubscriptionState> {
+                <$client as WSClient>::subscriptions(&self.client)
+            }
+
+            async fn run(&self, duration: Option<u64>) {
+                match self.stream_dropped.as_ref() {
+                    Some(stream_dropped) => {
+                        let run = self.client.run_async(duration);
+                        tokio::pin!(run);
+                        tokio::select! {
+                            _ = &mut run => {}
+                            _ = stream_dropped.notified() => {
+                                // closes the connection gracefully
+                                <$client as WSClient>::close(&self.client);
+                                run.await;
+                            }
+                        }
+                    }
+                    None => self.client.run_async(duration).await,
+                }
             }
 
             fn close(&self) {
-                self.client.close();
+                <$client as WSClient>::close(&self.client);
+            }
+
+            fn set_reconnect_policy(&self, policy: $crate::ReconnectPolicy) {
+                <$client as WSClient>::set_reconnect_policy(&self.client, policy);
+            }
+
+            fn on_reconnect(
+                &self,
+                callback: Arc<Mutex<dyn FnMut($crate::ReconnectEvent) + 'a + Send>>,
+            ) {
+                <$client as WSClient>::on_reconnect(&self.client, callback);
+            }
+
+            fn set_recorder(&self, recorder: $crate::Recorder) {
+                <$client as WSClient>::set_recorder(&self.client, recorder);
             }
         }
     };
@@ -2,5 +2,5 @@ mod utils;
 mod zbg_spot;
 mod zbg_swap;
 
-pub use zbg_spot::ZbgSpotWSClient;
-pub use zbg_swap::ZbgSwapWSClient;
+pub use zbg_spot::{ZbgSpotAsyncWSClient, ZbgSpotWSClient};
+pub use zbg_swap::{ZbgSwapAsyncWSClient, ZbgSwapWSClient};
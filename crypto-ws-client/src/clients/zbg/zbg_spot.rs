@@ -1,11 +1,11 @@
-use crate::WSClient;
+use crate::{Channel, ChannelKind, WSClient, WsError};
 use std::{
     collections::HashMap,
     sync::{Arc, Mutex, RwLock},
 };
 
 use super::super::ws_client_internal::{MiscMessage, WSClientInternal};
-use super::super::{Candlestick, OrderBook, OrderBookSnapshot, Ticker, Trade, BBO};
+use super::super::ChannelTranslator;
 use super::utils::fetch_symbol_id_map_spot;
 
 use lazy_static::lazy_static;
@@ -67,44 +67,29 @@ fn on_misc_msg(msg: &str) -> MiscMessage {
     }
 }
 
-fn to_raw_channel(channel: &str, pair: &str) -> String {
+fn get_symbol_id(pair: &str) -> Result<String, WsError> {
     if !SYMBOL_ID_MAP.read().unwrap().contains_key(pair) {
         // found new symbols
         reload_symbol_ids();
     }
-    let symbol_id = SYMBOL_ID_MAP
+    SYMBOL_ID_MAP
         .read()
         .unwrap()
         .get(pair)
-        .unwrap_or_else(|| panic!("Failed to find symbol_id for {}", pair))
-        .clone();
-    if channel == "TRADE_STATISTIC_24H" {
-        format!("{}_{}", symbol_id, channel)
-    } else {
-        format!("{}_{}_{}", symbol_id, channel, pair.to_uppercase())
-    }
+        .cloned()
+        .ok_or_else(|| WsError::invalid_symbol(EXCHANGE_NAME, pair))
 }
 
-#[rustfmt::skip]
-impl_trait!(Trade, ZbgSpotWSClient, subscribe_trade, "TRADE", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(OrderBook, ZbgSpotWSClient, subscribe_orderbook, "ENTRUST_ADD", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(Ticker, ZbgSpotWSClient, subscribe_ticker, "TRADE_STATISTIC_24H", to_raw_channel);
-
-impl<'a> BBO for ZbgSpotWSClient<'a> {
-    fn subscribe_bbo(&self, _pairs: &[String]) {
-        panic!("ZBG does NOT have BBO channel");
-    }
-}
-
-impl<'a> OrderBookSnapshot for ZbgSpotWSClient<'a> {
-    fn subscribe_orderbook_snapshot(&self, _pairs: &[String]) {
-        panic!("Bitget does NOT have orderbook snapshot channel");
+fn to_raw_channel(channel: &str, pair: &str) -> Result<String, WsError> {
+    let symbol_id = get_symbol_id(pair)?;
+    if channel == "TRADE_STATISTIC_24H" {
+        Ok(format!("{}_{}", symbol_id, channel))
+    } else {
+        Ok(format!("{}_{}_{}", symbol_id, channel, pair.to_uppercase()))
     }
 }
 
-fn to_candlestick_raw_channel(pair: &str, interval: u32) -> String {
+fn to_candlestick_raw_channel(pair: &str, interval: u32) -> Result<String, WsError> {
     let interval_str = match interval {
         60 => "1M",
         300 => "5M",
@@ -114,29 +99,44 @@ fn to_candlestick_raw_channel(pair: &str, interval: u32) -> String {
         14400 => "4H",
         86400 => "1D",
         604800 => "1W",
-        _ => panic!("ZBG spot available intervals 1M,5M,15M,30M,1H,4H,1D,1W"),
+        // ZBG spot available intervals 1M,5M,15M,30M,1H,4H,1D,1W
+        _ => return Err(WsError::invalid_interval(EXCHANGE_NAME, interval)),
     };
+    let symbol_id = get_symbol_id(pair)?;
 
-    if !SYMBOL_ID_MAP.read().unwrap().contains_key(pair) {
-        // found new symbols
-        reload_symbol_ids();
-    }
-    let symbol_id = SYMBOL_ID_MAP
-        .read()
-        .unwrap()
-        .get(pair)
-        .unwrap_or_else(|| panic!("Failed to find symbol_id for {}", pair))
-        .clone();
-
-    format!(
+    Ok(format!(
         "{}_KLINE_{}_{}",
         symbol_id,
         interval_str,
         pair.to_uppercase()
-    )
+    ))
 }
 
-impl_candlestick!(ZbgSpotWSClient);
+// ZBG does NOT have BBO and orderbook snapshot channels
+impl<'a> ChannelTranslator for ZbgSpotWSClient<'a> {
+    fn translate(&self, channel: &Channel) -> Result<String, WsError> {
+        match channel {
+            Channel::Trade(pair) => to_raw_channel("TRADE", pair),
+            Channel::L2 {
+                pair,
+                depth: None,
+                speed: None,
+            } => to_raw_channel("ENTRUST_ADD", pair),
+            Channel::Ticker(pair) => to_raw_channel("TRADE_STATISTIC_24H", pair),
+            Channel::Kline { pair, interval } => to_candlestick_raw_channel(pair, *interval),
+            _ => Err(WsError::unsupported(EXCHANGE_NAME, channel)),
+        }
+    }
+
+    fn capabilities(&self) -> Vec<ChannelKind> {
+        vec![
+            ChannelKind::Trade,
+            ChannelKind::L2,
+            ChannelKind::Ticker,
+            ChannelKind::Kline,
+        ]
+    }
+}
 
 define_client!(
     ZbgSpotWSClient,
@@ -147,3 +147,4 @@ define_client!(
     Some(CLIENT_PING_INTERVAL_AND_MSG),
     None
 );
+define_async_client!(ZbgSpotAsyncWSClient, ZbgSpotWSClient);
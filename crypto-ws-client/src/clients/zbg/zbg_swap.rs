@@ -1,11 +1,11 @@
-use crate::WSClient;
+use crate::{Channel, ChannelKind, WSClient, WsError};
 use std::{
     collections::HashMap,
     sync::{Arc, Mutex, RwLock},
 };
 
 use super::super::ws_client_internal::{MiscMessage, WSClientInternal};
-use super::super::{Candlestick, OrderBook, OrderBookSnapshot, Ticker, Trade, BBO};
+use super::super::ChannelTranslator;
 use super::utils::fetch_symbol_contract_id_map_swap;
 
 use lazy_static::lazy_static;
@@ -71,61 +71,61 @@ fn on_misc_msg(msg: &str) -> MiscMessage {
     }
 }
 
-fn to_raw_channel(channel: &str, pair: &str) -> String {
+fn get_contract_id(pair: &str) -> Result<i64, WsError> {
     if !SYMBOL_CONTRACT_ID_MAP.read().unwrap().contains_key(pair) {
         // found new symbols
         reload_contract_ids();
     }
-    let contract_id = *SYMBOL_CONTRACT_ID_MAP
+    SYMBOL_CONTRACT_ID_MAP
         .read()
         .unwrap()
         .get(pair)
-        .unwrap_or_else(|| panic!("Failed to find contract_id for {}", pair));
-    format!("{}-{}", channel, contract_id)
+        .copied()
+        .ok_or_else(|| WsError::invalid_symbol(EXCHANGE_NAME, pair))
 }
 
-#[rustfmt::skip]
-impl_trait!(Trade, ZbgSwapWSClient, subscribe_trade, "future_tick", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(OrderBook, ZbgSwapWSClient, subscribe_orderbook, "future_snapshot_depth", to_raw_channel);
-#[rustfmt::skip]
-impl_trait!(Ticker, ZbgSwapWSClient, subscribe_ticker, "future_snapshot_indicator", to_raw_channel);
-
-impl<'a> BBO for ZbgSwapWSClient<'a> {
-    fn subscribe_bbo(&self, _pairs: &[String]) {
-        panic!("ZBG does NOT have BBO channel");
-    }
+fn to_raw_channel(channel: &str, pair: &str) -> Result<String, WsError> {
+    let contract_id = get_contract_id(pair)?;
+    Ok(format!("{}-{}", channel, contract_id))
 }
 
-impl<'a> OrderBookSnapshot for ZbgSwapWSClient<'a> {
-    fn subscribe_orderbook_snapshot(&self, _pairs: &[String]) {
-        panic!("Bitget does NOT have orderbook snapshot channel");
-    }
-}
-
-fn to_candlestick_raw_channel(pair: &str, interval: u32) -> String {
+fn to_candlestick_raw_channel(pair: &str, interval: u32) -> Result<String, WsError> {
     let valid_set: Vec<u32> = vec![
         60, 180, 300, 900, 1800, 3600, 7200, 14400, 21600, 43200, 86400, 604800,
     ];
     if !valid_set.contains(&interval) {
-        let joined = valid_set
-            .into_iter()
-            .map(|x| x.to_string())
-            .collect::<Vec<String>>()
-            .join(",");
-        panic!("ZBG Swap available intervals {}", joined);
+        return Err(WsError::invalid_interval(EXCHANGE_NAME, interval));
     }
+    let contract_id = get_contract_id(pair)?;
 
-    let contract_id = *SYMBOL_CONTRACT_ID_MAP
-        .read()
-        .unwrap()
-        .get(pair)
-        .unwrap_or_else(|| panic!("Failed to find contract_id for {}", pair));
-
-    format!("future_kline-{}-{}", contract_id, interval * 1000)
+    Ok(format!("future_kline-{}-{}", contract_id, interval * 1000))
 }
 
-impl_candlestick!(ZbgSwapWSClient);
+// ZBG does NOT have BBO and orderbook snapshot channels
+impl<'a> ChannelTranslator for ZbgSwapWSClient<'a> {
+    fn translate(&self, channel: &Channel) -> Result<String, WsError> {
+        match channel {
+            Channel::Trade(pair) => to_raw_channel("future_tick", pair),
+            Channel::L2 {
+                pair,
+                depth: None,
+                speed: None,
+            } => to_raw_channel("future_snapshot_depth", pair),
+            Channel::Ticker(pair) => to_raw_channel("future_snapshot_indicator", pair),
+            Channel::Kline { pair, interval } => to_candlestick_raw_channel(pair, *interval),
+            _ => Err(WsError::unsupported(EXCHANGE_NAME, channel)),
+        }
+    }
+
+    fn capabilities(&self) -> Vec<ChannelKind> {
+        vec![
+            ChannelKind::Trade,
+            ChannelKind::L2,
+            ChannelKind::Ticker,
+            ChannelKind::Kline,
+        ]
+    }
+}
 
 define_client!(
     ZbgSwapWSClient,
@@ -136,3 +136,4 @@ define_client!(
     Some(CLIENT_PING_INTERVAL_AND_MSG),
     None
 );
+define_async_client!(ZbgSwapAsyncWSClient, ZbgSwapWSClient);
@@ -20,8 +20,14 @@
 //! * `subscribe_orderbook(&mut self, pairs: &[String])`
 //! * `subscribe_ticker(&mut self, pairs: &[String])`
 //! * `subscribe_candlestick(&mut self, pairs: &[String], interval: u32)`
+//! * `subscribe_mark_price(&mut self, pairs: &[String])`
+//! * `subscribe_index_price(&mut self, pairs: &[String])`
+//! * `subscribe_liquidation(&mut self, pairs: &[String])`
+//! * `subscribe_open_interest(&mut self, pairs: &[String])`
 //!
-//! They are easier to use and cover mostly used scenarios.
+//! They are easier to use and cover mostly used scenarios. They return
+//! `WsError::Unsupported` if the exchange doesn't have the channel, see
+//! `capabilities()`.
 //!
 //! ## Low Level APIs
 //!
@@ -61,8 +67,12 @@
 
 mod clients;
 
-use std::sync::{Arc, Mutex};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
 
+pub use clients::auth::Credentials;
 pub use clients::binance::*;
 pub use clients::binance_option::*;
 // pub use clients::bitfinex::*;
@@ -73,26 +83,118 @@ pub use clients::bitmex::*;
 pub use clients::bitstamp::*;
 pub use clients::bitz::*;
 pub use clients::bybit::*;
+pub use clients::channel::{Channel, ChannelKind};
 pub use clients::coinbase_pro::*;
 pub use clients::deribit::*;
+pub use clients::error::WsError;
+pub use clients::event::WsEvent;
+pub use clients::factory::create_client;
 pub use clients::ftx::*;
 pub use clients::gate::*;
+pub use clients::gemini::*;
+pub use clients::heartbeat::ConnectionConfig;
 pub use clients::huobi::*;
 pub use clients::kraken::*;
 pub use clients::kucoin::*;
+pub use clients::limits::ConnectionLimits;
+pub use clients::message_channel::{
+    message_channel, message_stream, BackpressurePolicy, MessageReceiver, MessageSender,
+    MessageStream,
+};
+pub use clients::metrics::Metrics;
 pub use clients::mxc::*;
 pub use clients::okex::*;
+pub use clients::options::WSClientOptions;
+pub use clients::poloniex::*;
+pub use clients::pool::WSClientPool;
+pub use clients::proxy::{Proxy, ProxyKind};
+pub use clients::reconnect::{ReconnectEvent, ReconnectPolicy};
+pub use clients::recorder::{Direction, Record, RecordedFrame, Recorder, Replayer};
+pub use clients::subscription::SubscriptionState;
+pub use clients::upbit::*;
 pub use clients::zbg::*;
+pub use crypto_market_type::MarketType;
 
 /// The public interface of every WebSocket client.
+///
+/// Constructors require `Self: Sized`, all other methods can be called on a
+/// `Box<dyn WSClient>`, see `create_client()`.
 pub trait WSClient<'a> {
     /// Creates a new client.
     ///
+    /// A shorthand of `new_with_options()` with a single url.
+    ///
     /// # Arguments
     ///
     /// * `on_msg` - A callback function to process original JSON messages
     /// * `url` - Optional server url, usually you don't need specify it
-    fn new(on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>, url: Option<&str>) -> Self;
+    fn new(on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>, url: Option<&str>) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new_with_options(on_msg, WSClientOptions::from_url(url))
+    }
+
+    /// Creates a new client with endpoints, an event callback and
+    /// heartbeat settings, see `WSClientOptions`.
+    ///
+    /// # Arguments
+    ///
+    /// * `on_msg` - A callback function to process original JSON messages
+    /// * `options` - Options of the client, any combination of them
+    fn new_with_options(
+        on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>,
+        options: WSClientOptions<'a>,
+    ) -> Self
+    where
+        Self: Sized;
+
+    /// Returns the server url in use.
+    fn endpoint(&self) -> &str;
+
+    /// Returns limits of a connection imposed by the exchange, see
+    /// `WSClientPool` which splits channels over multiple connections.
+    fn connection_limits(&self) -> ConnectionLimits;
+
+    /// Returns the number of subscribe and unsubscribe commands waiting to be
+    /// sent.
+    ///
+    /// Commands are sent no faster than `max_messages_per_second` of
+    /// `connection_limits()`, including resubscribing after reconnecting;
+    /// commands over the limit are queued and sent by `run()`.
+    fn num_pending_commands(&self) -> usize;
+
+    /// Returns health metrics of the connection, see `Metrics`.
+    ///
+    /// A `WSClientPool` adds up metrics of all its connections.
+    fn metrics(&self) -> Metrics;
+
+    /// Creates a new client which sends messages to a bounded channel
+    /// instead of calling a callback.
+    ///
+    /// The network thread only pushes messages into the channel, so consumers
+    /// can process messages in other threads; when the channel is full,
+    /// `policy` decides whether to block or drop messages.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - Maximum number of messages in the channel
+    /// * `policy` - What to do when the channel is full
+    /// * `options` - Options of the client, see `WSClientOptions`
+    fn new_with_channel(
+        capacity: usize,
+        policy: BackpressurePolicy,
+        options: WSClientOptions<'a>,
+    ) -> (Self, MessageReceiver<String>)
+    where
+        Self: Sized,
+    {
+        let (tx, rx) = message_channel(capacity, policy);
+        let on_msg = Arc::new(Mutex::new(move |msg: String| {
+            tx.send(msg);
+        }));
+        (Self::new_with_options(on_msg, options), rx)
+    }
 
     /// Subscribes to trade channels.
     ///
@@ -104,7 +206,15 @@ pub trait WSClient<'a> {
     /// * BitMEX `XBTUSD`, `XBTM21`
     /// * Binance `btcusdt`, `btcusd_perp`
     /// * OKEx `BTC-USDT`
-    fn subscribe_trade(&self, pairs: &[String]);
+    fn subscribe_trade(&self, pairs: &[String]) -> Result<(), WsError> {
+        self.subscribe_channels(
+            &pairs
+                .iter()
+                .cloned()
+                .map(Channel::Trade)
+                .collect::<Vec<_>>(),
+        )
+    }
 
     /// Subscribes to BBO(best bid & offer) channels.
     ///
@@ -113,11 +223,13 @@ pub trait WSClient<'a> {
     /// is updated per tick and non-aggregated.
     ///
     /// Not all exchanges have the BBO channel, calling this function with
-    /// these exchanges will panic.
+    /// these exchanges returns `WsError::Unsupported`.
     ///
     /// * Binance, BitMEX, Huobi and Kraken have BBO directly.
     /// * Bitfinex uses `book` channel with `len=1` and `prec="R0"` to get BBO data.
-    fn subscribe_bbo(&self, pairs: &[String]);
+    fn subscribe_bbo(&self, pairs: &[String]) -> Result<(), WsError> {
+        self.subscribe_channels(&pairs.iter().cloned().map(Channel::BBO).collect::<Vec<_>>())
+    }
 
     /// Subscribes to incremental level2 orderbook channels.
     ///
@@ -138,7 +250,14 @@ pub trait WSClient<'a> {
     /// * Kraken `book` with `depth=25`
     /// * MXC `depth` for Swap, `symbol` for Spot
     /// * OKEx `depth_l2_tbt`, top 100
-    fn subscribe_orderbook(&self, pairs: &[String]);
+    fn subscribe_orderbook(&self, pairs: &[String]) -> Result<(), WsError> {
+        self.subscribe_channels(
+            &pairs
+                .iter()
+                .map(|pair| Channel::l2(pair))
+                .collect::<Vec<_>>(),
+        )
+    }
 
     /// Subscribes to level2 orderbook snapshot channels.
     ///
@@ -155,7 +274,15 @@ pub trait WSClient<'a> {
     /// * Kraken has no snapshot channel
     /// * MXC `depth.full` for Swap, top 20, every 100ms; `get.depth` for Spot, full, every 26s
     /// * OKEx `depth5`, top 5, every 100ms
-    fn subscribe_orderbook_snapshot(&self, pairs: &[String]);
+    fn subscribe_orderbook_snapshot(&self, pairs: &[String]) -> Result<(), WsError> {
+        self.subscribe_channels(
+            &pairs
+                .iter()
+                .cloned()
+                .map(Channel::L2Snapshot)
+                .collect::<Vec<_>>(),
+        )
+    }
 
     /// Subscribes to ticker channels.
     ///
@@ -163,8 +290,16 @@ pub trait WSClient<'a> {
     /// which contains OHLCV information.
     ///
     /// Not all exchanges have the ticker channel, for example, BitMEX,
-    /// Bitstamp, MXC Spot, etc.
-    fn subscribe_ticker(&self, pairs: &[String]);
+    /// Bitstamp, MXC Spot, etc, they return `WsError::Unsupported`.
+    fn subscribe_ticker(&self, pairs: &[String]) -> Result<(), WsError> {
+        self.subscribe_channels(
+            &pairs
+                .iter()
+                .cloned()
+                .map(Channel::Ticker)
+                .collect::<Vec<_>>(),
+        )
+    }
 
     /// Subscribes to candlestick channels.
     ///
@@ -173,8 +308,108 @@ pub trait WSClient<'a> {
     /// `interval` specifies the interval of candlesticks in seconds.
     ///
     /// Not all exchanges have candlestick channels, for example, Bitstamp
-    /// and CoinbasePro.
-    fn subscribe_candlestick(&self, pairs: &[String], interval: u32);
+    /// and CoinbasePro, they return `WsError::Unsupported`; an interval not
+    /// provided by the exchange returns `WsError::InvalidInterval`.
+    fn subscribe_candlestick(&self, pairs: &[String], interval: u32) -> Result<(), WsError> {
+        self.subscribe_channels(
+            &pairs
+                .iter()
+                .map(|pair| Channel::kline(pair, interval))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Subscribes to mark price channels of derivative contracts.
+    ///
+    /// Some exchanges push the mark price along with other fields of the
+    /// contract, for example, BitMEX `instrument`, Bybit `instrument_info`
+    /// and Gate `futures.tickers`, which are shared by mark price, index
+    /// price and open interest channels.
+    ///
+    /// Spot markets and exchanges without the channel, for example, FTX,
+    /// return `WsError::Unsupported`.
+    fn subscribe_mark_price(&self, pairs: &[String]) -> Result<(), WsError> {
+        self.subscribe_channels(
+            &pairs
+                .iter()
+                .cloned()
+                .map(Channel::MarkPrice)
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Subscribes to index price channels of derivative contracts.
+    ///
+    /// Binance Coin-margined, Deribit and OKEx push prices of indexes
+    /// rather than contracts, the index is derived from the pair, for
+    /// example, `BTC-PERPETUAL` of Deribit subscribes to `btc_usd`.
+    ///
+    /// Exchanges without the channel, for example, Binance USDT-margined,
+    /// FTX and Huobi, return `WsError::Unsupported`.
+    fn subscribe_index_price(&self, pairs: &[String]) -> Result<(), WsError> {
+        self.subscribe_channels(
+            &pairs
+                .iter()
+                .cloned()
+                .map(Channel::IndexPrice)
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Subscribes to liquidation channels.
+    ///
+    /// Only Binance futures, BitMEX and Bybit have public liquidation
+    /// channels, other exchanges return `WsError::Unsupported`.
+    fn subscribe_liquidation(&self, pairs: &[String]) -> Result<(), WsError> {
+        self.subscribe_channels(
+            &pairs
+                .iter()
+                .cloned()
+                .map(Channel::Liquidation)
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Subscribes to open interest channels of derivative contracts.
+    ///
+    /// Binance, FTX, Huobi, KuCoin and OKEx provide open interest via
+    /// RESTful APIs only, they return `WsError::Unsupported`.
+    fn subscribe_open_interest(&self, pairs: &[String]) -> Result<(), WsError> {
+        self.subscribe_channels(
+            &pairs
+                .iter()
+                .cloned()
+                .map(Channel::OpenInterest)
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Translates typed channels to raw channels of the exchange, fails if
+    /// any of them is not supported by the exchange.
+    fn translate_channels(&self, channels: &[Channel]) -> Result<Vec<String>, WsError>;
+
+    /// Returns kinds of channels the exchange has.
+    ///
+    /// A kind missing here is rejected by `subscribe_channels()` with
+    /// `WsError::Unsupported`, while a supported kind may still be rejected
+    /// because of its parameters, such as the candlestick interval.
+    fn capabilities(&self) -> Vec<ChannelKind>;
+
+    /// Subscribes to typed channels, see `Channel`.
+    ///
+    /// Nothing is subscribed if any channel is not supported by the exchange.
+    fn subscribe_channels(&self, channels: &[Channel]) -> Result<(), WsError> {
+        let raw_channels = self.translate_channels(channels)?;
+        self.subscribe(&raw_channels);
+        Ok(())
+    }
+
+    /// Unsubscribes from typed channels, see `subscribe_channels()`.
+    fn unsubscribe_channels(&self, channels: &[Channel]) -> Result<(), WsError> {
+        let raw_channels = self.translate_channels(channels)?;
+        self.unsubscribe(&raw_channels);
+        Ok(())
+    }
 
     /// Subscribes to raw channels, lower level API.
     ///
@@ -197,7 +432,19 @@ pub trait WSClient<'a> {
     /// Unsubscribes from raw channels, lower level API.
     fn unsubscribe(&self, raw_channels: &[String]);
 
-    /// Starts the infinite loop until time is up or the server closes the connection.
+    /// Returns subscribed raw channels and their states.
+    ///
+    /// A channel is pending until the server confirms or rejects it. Some
+    /// exchanges don't identify channels in their responses, so channels
+    /// of these exchanges stay pending. Unsubscribed channels are removed
+    /// immediately.
+    fn subscriptions(&self) -> HashMap<String, SubscriptionState>;
+
+    /// Starts the infinite loop until time is up or `close()` is called.
+    ///
+    /// If the connection drops, the client reconnects following its
+    /// `ReconnectPolicy` and subscribes all channels again, so this function
+    /// returns early only if the policy gives up.
     ///
     /// # Arguments
     ///
@@ -206,6 +453,215 @@ pub trait WSClient<'a> {
 
     /// Breaks the loop and closes the connection.
     fn close(&self);
+
+    /// Sets the backoff policy used to reconnect, see `ReconnectPolicy`.
+    fn set_reconnect_policy(&self, policy: ReconnectPolicy);
+
+    /// Registers a callback which is called after every successful reconnect.
+    fn on_reconnect(&self, callback: Arc<Mutex<dyn FnMut(ReconnectEvent) + 'a + Send>>);
+
+    /// Records all frames from now on to a log file, see `Recorder`.
+    fn set_recorder(&self, recorder: Recorder);
+
+    /// Replays a recording offline, see `Replayer`.
+    ///
+    /// Inbound frames go through the same decompression and message handling
+    /// as a live connection before reaching `on_msg`, nothing is sent to the
+    /// network.
+    fn replay(on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>, replayer: &Replayer)
+    where
+        Self: Sized;
+}
+
+/// Private channels of an exchange account, which need API credentials.
+///
+/// A private client authenticates every connection, including reconnections,
+/// before resubscribing channels, so private channels share the same
+/// connection layer as market data.
+///
+/// ## Example
+///
+/// ```no_run
+/// use std::sync::{Arc, Mutex};
+/// use crypto_ws_client::{BitmexWSClient, Credentials, PrivateWSClient, WSClient};
+///
+/// let credentials = Credentials::new("api_key", "api_secret");
+/// let ws_client = BitmexWSClient::new_private(Arc::new(Mutex::new(|msg| println!("{}", msg))), credentials, None).unwrap();
+/// ws_client.subscribe_fills(&vec!["XBTUSD".to_string()]);
+/// ws_client.run(None);
+/// ```
+pub trait PrivateWSClient<'a>: WSClient<'a> {
+    /// Creates a new authenticated client.
+    ///
+    /// Returns `WsError::Auth` if the exchange rejected the credentials,
+    /// e.g., failed to create the token of private channels.
+    ///
+    /// # Arguments
+    ///
+    /// * `on_msg` - A callback function to process original JSON messages
+    /// * `credentials` - API key, secret and passphrase of the account
+    /// * `url` - Optional server url, usually you don't need specify it
+    fn new_private(
+        on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>,
+        credentials: Credentials,
+        url: Option<&str>,
+    ) -> Result<Self, WsError>
+    where
+        Self: Sized;
+
+    /// Subscribes to order updates of given symbols.
+    fn subscribe_orders(&self, symbols: &[String]);
+
+    /// Subscribes to fills(own trades) of given symbols.
+    fn subscribe_fills(&self, symbols: &[String]);
+
+    /// Subscribes to balance updates of given currencies.
+    fn subscribe_balances(&self, currencies: &[String]);
+}
+
+/// The async version of `WSClient`, built on tokio.
+///
+/// Subscribing never blocks: channels are recorded and sent to the server by
+/// the future returned from `run()`, which owns the connection. Channels
+/// subscribed before `run()` are sent right after connected.
+///
+/// ## Example
+///
+/// ```no_run
+/// use std::sync::{Arc, Mutex};
+/// use crypto_ws_client::{AsyncWSClient, BinanceSpotAsyncWSClient};
+///
+/// # async fn example() {
+/// let ws_client = BinanceSpotAsyncWSClient::new(Arc::new(Mutex::new(|msg| println!("{}", msg))), None);
+/// ws_client.subscribe_trade(&vec!["btcusdt".to_string()]).await.unwrap();
+/// ws_client.run(Some(2)).await; // run for 2 seconds
+/// # }
+/// ```
+#[async_trait::async_trait]
+pub trait AsyncWSClient<'a> {
+    /// Creates a new client, the connection is established by `run()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `on_msg` - A callback function to process original JSON messages
+    /// * `url` - Optional server url, usually you don't need specify it
+    fn new(on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>, url: Option<&str>) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new_with_options(on_msg, WSClientOptions::from_url(url))
+    }
+
+    /// Creates a new client with options, see `WSClient::new_with_options()`.
+    fn new_with_options(
+        on_msg: Arc<Mutex<dyn FnMut(String) + 'a + Send>>,
+        options: WSClientOptions<'a>,
+    ) -> Self
+    where
+        Self: Sized;
+
+    /// Returns the server url in use, see `WSClient::endpoint()`.
+    fn endpoint(&self) -> &str;
+
+    /// Returns limits of a connection, see `WSClient::connection_limits()`.
+    fn connection_limits(&self) -> ConnectionLimits;
+
+    /// Returns the number of commands waiting to be sent, see
+    /// `WSClient::num_pending_commands()`.
+    fn num_pending_commands(&self) -> usize;
+
+    /// Returns health metrics of the connection, see `WSClient::metrics()`.
+    fn metrics(&self) -> Metrics;
+
+    /// Creates a new client which sends messages to a bounded channel,
+    /// consumed as an async `Stream`, see `WSClient::new_with_channel()`.
+    ///
+    /// Dropping the stream closes the client, and `run()` returns after the
+    /// next message.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `policy` is `BackpressurePolicy::Block`, since messages are
+    /// sent from the tokio worker running the client, which must not block.
+    fn new_with_stream(
+        capacity: usize,
+        policy: BackpressurePolicy,
+        options: WSClientOptions<'a>,
+    ) -> (Self, MessageStream<String>)
+    where
+        Self: Sized;
+
+    /// Subscribes to trade channels, see `WSClient::subscribe_trade()`.
+    async fn subscribe_trade(&self, pairs: &[String]) -> Result<(), WsError>;
+
+    /// Subscribes to BBO channels, see `WSClient::subscribe_bbo()`.
+    async fn subscribe_bbo(&self, pairs: &[String]) -> Result<(), WsError>;
+
+    /// Subscribes to incremental level2 orderbook channels, see `WSClient::subscribe_orderbook()`.
+    async fn subscribe_orderbook(&self, pairs: &[String]) -> Result<(), WsError>;
+
+    /// Subscribes to level2 orderbook snapshot channels, see `WSClient::subscribe_orderbook_snapshot()`.
+    async fn subscribe_orderbook_snapshot(&self, pairs: &[String]) -> Result<(), WsError>;
+
+    /// Subscribes to ticker channels, see `WSClient::subscribe_ticker()`.
+    async fn subscribe_ticker(&self, pairs: &[String]) -> Result<(), WsError>;
+
+    /// Subscribes to candlestick channels, see `WSClient::subscribe_candlestick()`.
+    async fn subscribe_candlestick(&self, pairs: &[String], interval: u32) -> Result<(), WsError>;
+
+    /// Subscribes to mark price channels, see `WSClient::subscribe_mark_price()`.
+    async fn subscribe_mark_price(&self, pairs: &[String]) -> Result<(), WsError>;
+
+    /// Subscribes to index price channels, see `WSClient::subscribe_index_price()`.
+    async fn subscribe_index_price(&self, pairs: &[String]) -> Result<(), WsError>;
+
+    /// Subscribes to liquidation channels, see `WSClient::subscribe_liquidation()`.
+    async fn subscribe_liquidation(&self, pairs: &[String]) -> Result<(), WsError>;
+
+    /// Subscribes to open interest channels, see `WSClient::subscribe_open_interest()`.
+    async fn subscribe_open_interest(&self, pairs: &[String]) -> Result<(), WsError>;
+
+    /// Translates typed channels to raw channels, see `WSClient::translate_channels()`.
+    fn translate_channels(&self, channels: &[Channel]) -> Result<Vec<String>, WsError>;
+
+    /// Returns kinds of channels the exchange has, see `WSClient::capabilities()`.
+    fn capabilities(&self) -> Vec<ChannelKind>;
+
+    /// Subscribes to typed channels, see `WSClient::subscribe_channels()`.
+    async fn subscribe_channels(&self, channels: &[Channel]) -> Result<(), WsError>;
+
+    /// Unsubscribes from typed channels, see `WSClient::unsubscribe_channels()`.
+    async fn unsubscribe_channels(&self, channels: &[Channel]) -> Result<(), WsError>;
+
+    /// Subscribes to raw channels, see `WSClient::subscribe()`.
+    async fn subscribe(&self, raw_channels: &[String]);
+
+    /// Unsubscribes from raw channels, lower level API.
+    async fn unsubscribe(&self, raw_channels: &[String]);
+
+    /// Returns subscribed raw channels and their states, see `WSClient::subscriptions()`.
+    fn subscriptions(&self) -> HashMap<String, SubscriptionState>;
+
+    /// Connects and processes messages until time is up or `close()` is called.
+    ///
+    /// Reconnects the same way as `WSClient::run()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` - How many seconds to run, None means infinite.
+    async fn run(&self, duration: Option<u64>);
+
+    /// Makes the future returned by `run()` complete and closes the connection.
+    fn close(&self);
+
+    /// Sets the backoff policy used to reconnect, see `ReconnectPolicy`.
+    fn set_reconnect_policy(&self, policy: ReconnectPolicy);
+
+    /// Registers a callback which is called after every successful reconnect.
+    fn on_reconnect(&self, callback: Arc<Mutex<dyn FnMut(ReconnectEvent) + 'a + Send>>);
+
+    /// Records all frames from now on to a log file, see `WSClient::set_recorder()`.
+    fn set_recorder(&self, recorder: Recorder);
 }
 
 /// Level3 orderbook data.
@@ -214,5 +670,5 @@ pub trait Level3OrderBook {
     ///
     /// The level3 orderbook is the orginal orderbook of an exchange, it is
     /// non-aggregated by price level and updated tick-by-tick.
-    fn subscribe_l3_orderbook(&self, symbols: &[String]);
+    fn subscribe_l3_orderbook(&self, symbols: &[String]) -> Result<(), WsError>;
 }
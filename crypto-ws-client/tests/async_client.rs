@@ -0,0 +1,74 @@
+use crypto_ws_client::{
+    AsyncWSClient, BinanceSpotAsyncWSClient, BitfinexAsyncWSClient, BitmexAsyncWSClient,
+    CoinbaseProAsyncWSClient, HuobiSpotAsyncWSClient, KrakenAsyncWSClient, OkexAsyncWSClient,
+};
+use std::sync::{Arc, Mutex};
+
+#[macro_use]
+mod utils;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn binance_spot_subscribe_trade() {
+    gen_async_test_code!(
+        BinanceSpotAsyncWSClient,
+        subscribe_trade,
+        &["btcusdt".to_string()]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn bitfinex_subscribe_trade() {
+    gen_async_test_code!(
+        BitfinexAsyncWSClient,
+        subscribe_trade,
+        &["tBTCUSD".to_string()]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn bitmex_subscribe() {
+    gen_async_test_code!(
+        BitmexAsyncWSClient,
+        subscribe,
+        &["trade:XBTUSD".to_string(), "quote:XBTUSD".to_string()]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn coinbase_pro_subscribe_ticker() {
+    gen_async_test_code!(
+        CoinbaseProAsyncWSClient,
+        subscribe_ticker,
+        &["BTC-USD".to_string()]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn huobi_spot_subscribe_trade() {
+    gen_async_test_code!(
+        HuobiSpotAsyncWSClient,
+        subscribe_trade,
+        &["btcusdt".to_string()]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn kraken_subscribe_bbo() {
+    gen_async_test_code!(KrakenAsyncWSClient, subscribe_bbo, &["XBT/USD".to_string()]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn okex_subscribe_orderbook_snapshot() {
+    gen_async_test_code!(
+        OkexAsyncWSClient,
+        subscribe_orderbook_snapshot,
+        &["BTC-USDT".to_string()]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn close_before_run() {
+    let ws_client = BinanceSpotAsyncWSClient::new(Arc::new(Mutex::new(|_msg| {})), None);
+    ws_client.close();
+    ws_client.run(None).await; // returns immediately
+}
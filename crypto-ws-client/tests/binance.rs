@@ -116,6 +116,24 @@ mod binance_inverse_future {
         );
     }
 
+    #[test]
+    fn subscribe_mark_price() {
+        gen_test_code!(
+            BinanceInverseWSClient,
+            subscribe_mark_price,
+            &["btcusd_210924".to_string()]
+        );
+    }
+
+    #[test]
+    fn subscribe_index_price() {
+        gen_test_code!(
+            BinanceInverseWSClient,
+            subscribe_index_price,
+            &["btcusd_210924".to_string()]
+        );
+    }
+
     #[test]
     fn subscribe_ticker() {
         gen_test_code!(
@@ -199,6 +217,15 @@ mod binance_linear_future {
         );
     }
 
+    #[test]
+    fn subscribe_mark_price() {
+        gen_test_code!(
+            BinanceLinearWSClient,
+            subscribe_mark_price,
+            &["btcusdt_210924".to_string()]
+        );
+    }
+
     #[test]
     fn subscribe_ticker() {
         gen_test_code!(
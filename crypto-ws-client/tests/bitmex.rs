@@ -37,6 +37,24 @@ mod bitmex_inverse_swap {
         gen_test_code!(BitmexWSClient, subscribe_trade, &vec!["XBTUSD".to_string()]);
     }
 
+    #[test]
+    fn subscribe_mark_price() {
+        gen_test_code!(
+            BitmexWSClient,
+            subscribe_mark_price,
+            &["XBTUSD".to_string()]
+        );
+    }
+
+    #[test]
+    fn subscribe_open_interest() {
+        gen_test_code!(
+            BitmexWSClient,
+            subscribe_open_interest,
+            &["XBTUSD".to_string()]
+        );
+    }
+
     #[test]
     fn subscribe_bbo() {
         gen_test_code!(BitmexWSClient, subscribe_bbo, &vec!["XBTUSD".to_string()]);
@@ -1,6 +1,45 @@
 #[macro_use]
 mod utils;
 
+#[cfg(test)]
+mod bybit_spot {
+    use crypto_ws_client::{BybitSpotWSClient, WSClient};
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn subscribe() {
+        gen_test_code!(BybitSpotWSClient, subscribe, &["trade.BTCUSDT".to_string()]);
+    }
+
+    #[test]
+    fn subscribe_trade() {
+        gen_test_code!(
+            BybitSpotWSClient,
+            subscribe_trade,
+            &["BTCUSDT".to_string(), "ETHUSDT".to_string()]
+        );
+    }
+
+    #[test]
+    fn subscribe_orderbook() {
+        gen_test_code!(
+            BybitSpotWSClient,
+            subscribe_orderbook,
+            &["BTCUSDT".to_string()]
+        );
+    }
+
+    #[test]
+    fn subscribe_bbo() {
+        gen_test_code!(BybitSpotWSClient, subscribe_bbo, &["BTCUSDT".to_string()]);
+    }
+
+    #[test]
+    fn subscribe_candlestick() {
+        gen_test_subscribe_candlestick!(BybitSpotWSClient, &["BTCUSDT".to_string()], 60);
+    }
+}
+
 #[cfg(test)]
 mod bybit_inverse_future {
     use crypto_ws_client::{BybitInverseFutureWSClient, WSClient};
@@ -107,6 +146,24 @@ mod bybit_inverse_swap {
         );
     }
 
+    #[test]
+    fn subscribe_mark_price() {
+        gen_test_code!(
+            BybitInverseSwapWSClient,
+            subscribe_mark_price,
+            &["BTCUSD".to_string()]
+        );
+    }
+
+    #[test]
+    fn subscribe_open_interest() {
+        gen_test_code!(
+            BybitInverseSwapWSClient,
+            subscribe_open_interest,
+            &["BTCUSD".to_string()]
+        );
+    }
+
     #[test]
     fn subscribe_bbo() {
         gen_test_code!(
@@ -97,6 +97,24 @@ mod deribit_inverse_swap {
         );
     }
 
+    #[test]
+    fn subscribe_mark_price() {
+        gen_test_code!(
+            DeribitWSClient,
+            subscribe_mark_price,
+            &["BTC-PERPETUAL".to_string()]
+        );
+    }
+
+    #[test]
+    fn subscribe_index_price() {
+        gen_test_code!(
+            DeribitWSClient,
+            subscribe_index_price,
+            &["BTC-PERPETUAL".to_string()]
+        );
+    }
+
     #[test]
     fn subscribe_ticker() {
         gen_test_code!(
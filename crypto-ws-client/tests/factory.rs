@@ -0,0 +1,39 @@
+use crypto_ws_client::{create_client, MarketType, WSClient};
+use std::sync::{Arc, Mutex};
+
+fn crawl_trade(exchange: &str, market_type: MarketType, pairs: &[String]) -> Vec<String> {
+    let mut messages = Vec::<String>::new();
+    {
+        let on_msg = Arc::new(Mutex::new(|msg: String| messages.push(msg)));
+        let ws_client: Box<dyn WSClient> = create_client(exchange, market_type, on_msg);
+        ws_client.subscribe_trade(pairs).unwrap();
+        ws_client.run(Some(0)); // return immediately once after a normal message
+        ws_client.close();
+    }
+    messages
+}
+
+#[test]
+fn binance_spot() {
+    assert!(!crawl_trade("binance", MarketType::Spot, &["btcusdt".to_string()]).is_empty());
+}
+
+#[test]
+fn binance_linear_swap() {
+    assert!(!crawl_trade("binance", MarketType::LinearSwap, &["btcusdt".to_string()]).is_empty());
+}
+
+#[test]
+fn huobi_inverse_swap() {
+    assert!(!crawl_trade("huobi", MarketType::InverseSwap, &["BTC-USD".to_string()]).is_empty());
+}
+
+#[test]
+#[should_panic(expected = "bitget does NOT have a websocket client of the spot market type")]
+fn unsupported_market_type() {
+    create_client(
+        "bitget",
+        MarketType::Spot,
+        Arc::new(Mutex::new(|msg| println!("{}", msg))),
+    );
+}
@@ -140,6 +140,15 @@ mod gate_linear_swap {
         );
     }
 
+    #[test]
+    fn subscribe_mark_price() {
+        gen_test_code!(
+            GateLinearSwapWSClient,
+            subscribe_mark_price,
+            &["BTC_USDT".to_string()]
+        );
+    }
+
     #[test]
     fn subscribe_orderbook() {
         gen_test_code!(
@@ -0,0 +1,50 @@
+use crypto_ws_client::{GeminiWSClient, WSClient};
+use std::sync::{Arc, Mutex};
+
+#[macro_use]
+mod utils;
+
+#[test]
+fn subscribe() {
+    gen_test_code!(
+        GeminiWSClient,
+        subscribe,
+        &["l2:BTCUSD".to_string(), "l2:ETHUSD".to_string()]
+    );
+}
+
+#[test]
+fn subscribe_raw_json() {
+    gen_test_code!(
+        GeminiWSClient,
+        subscribe,
+        &[
+            r#"{"type":"subscribe","subscriptions":[{"name":"l2","symbols":["BTCUSD","ETHUSD"]}]}"#
+                .to_string()
+        ]
+    );
+}
+
+#[test]
+fn subscribe_trade() {
+    gen_test_code!(
+        GeminiWSClient,
+        subscribe_trade,
+        &["BTCUSD".to_string(), "ETHUSD".to_string()]
+    );
+}
+
+#[test]
+fn subscribe_orderbook() {
+    gen_test_code!(
+        GeminiWSClient,
+        subscribe_orderbook,
+        &["BTCUSD".to_string(), "ETHUSD".to_string()]
+    );
+}
+
+#[test]
+fn subscribe_candlestick() {
+    gen_test_subscribe_candlestick!(GeminiWSClient, &["BTCUSD".to_string()], 60);
+    gen_test_subscribe_candlestick!(GeminiWSClient, &["BTCUSD".to_string()], 86400);
+}
@@ -60,7 +60,9 @@ mod huobi_spot {
                 Arc::new(Mutex::new(on_msg)),
                 Some("wss://api.huobi.pro/feed"),
             );
-            ws_client.subscribe_orderbook(&vec!["btcusdt".to_string()]);
+            ws_client
+                .subscribe_orderbook(&["btcusdt".to_string()])
+                .unwrap();
             ws_client.run(Some(0)); // return immediately once after getting a normal message
         }
         assert!(!messages.is_empty());
@@ -289,6 +291,15 @@ mod huobi_inverse_swap {
         );
     }
 
+    #[test]
+    fn subscribe_mark_price() {
+        gen_test_code!(
+            HuobiInverseSwapWSClient,
+            subscribe_mark_price,
+            &["BTC-USD".to_string()]
+        );
+    }
+
     #[test]
     fn subscribe_ticker() {
         gen_test_code!(
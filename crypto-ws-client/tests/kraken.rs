@@ -80,3 +80,45 @@ fn subscribe_candlestick() {
         1296000
     );
 }
+
+#[cfg(test)]
+mod kraken_futures {
+    use crypto_ws_client::{KrakenFuturesWSClient, WSClient};
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn subscribe() {
+        gen_test_code!(
+            KrakenFuturesWSClient,
+            subscribe,
+            &["trade:PI_XBTUSD".to_string(), "book:PI_XBTUSD".to_string()]
+        );
+    }
+
+    #[test]
+    fn subscribe_trade() {
+        gen_test_code!(
+            KrakenFuturesWSClient,
+            subscribe_trade,
+            &["PI_XBTUSD".to_string(), "PI_ETHUSD".to_string()]
+        );
+    }
+
+    #[test]
+    fn subscribe_orderbook() {
+        gen_test_code!(
+            KrakenFuturesWSClient,
+            subscribe_orderbook,
+            &["PI_XBTUSD".to_string()]
+        );
+    }
+
+    #[test]
+    fn subscribe_ticker() {
+        gen_test_code!(
+            KrakenFuturesWSClient,
+            subscribe_ticker,
+            &["PI_XBTUSD".to_string()]
+        );
+    }
+}
@@ -117,6 +117,15 @@ mod kucoin_inverse_swap {
         );
     }
 
+    #[test]
+    fn subscribe_mark_price() {
+        gen_test_code!(
+            KuCoinSwapWSClient,
+            subscribe_mark_price,
+            &["XBTUSDM".to_string()]
+        );
+    }
+
     #[test]
     fn subscribe_bbo() {
         gen_test_code!(
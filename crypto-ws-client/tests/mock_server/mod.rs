@@ -0,0 +1,344 @@
+// A local WebSocket server which mimics an exchange, so that clients can be
+// tested offline by passing `MockServer::url()` to `WSClient::new()`.
+//
+// The server sends welcome frames after each connection is established, waits
+// for subscription commands, then replays recorded frames. Control frames in
+// the script inject faults, and the script continues on the next connection
+// after a fault. Once the script is exhausted, the server closes the connection
+// and stops listening, so a client with a bounded `ReconnectPolicy` gives up
+// and `run()` returns.
+#![allow(dead_code)]
+
+use std::{
+    collections::{HashMap, VecDeque},
+    io::{ErrorKind, Write},
+    net::{TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crypto_ws_client::ReconnectPolicy;
+use flate2::{
+    write::{DeflateEncoder, GzEncoder},
+    Compression,
+};
+use tungstenite::{Error, Message, WebSocket};
+
+/// A frame in the script of a mock server.
+#[derive(Clone, Debug)]
+pub enum Frame {
+    /// A text frame, sent as is
+    Text(String),
+    /// A gzip compressed text, used by Binance, Bitget, Bitz and Huobi
+    Gzip(String),
+    /// A raw deflate compressed text, used by OKEx
+    Deflate(String),
+    /// A binary frame sent as is, for example, corrupted compressed data
+    Binary(Vec<u8>),
+    /// A text frame built from the last command received, e.g., an ack which
+    /// echoes the id of the command
+    Reply(fn(&str) -> String),
+    /// Drops the TCP connection without a closing handshake
+    Disconnect,
+    /// Sends nothing until the client drops the connection, e.g., after missed pongs
+    Hang,
+}
+
+impl Frame {
+    pub fn text(txt: &str) -> Frame {
+        Frame::Text(txt.to_string())
+    }
+
+    pub fn gzip(txt: &str) -> Frame {
+        Frame::Gzip(txt.to_string())
+    }
+
+    pub fn deflate(txt: &str) -> Frame {
+        Frame::Deflate(txt.to_string())
+    }
+
+    fn to_message(&self) -> Message {
+        match self {
+            Frame::Text(txt) => Message::Text(txt.clone()),
+            Frame::Gzip(txt) => {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(txt.as_bytes()).unwrap();
+                Message::Binary(encoder.finish().unwrap())
+            }
+            Frame::Deflate(txt) => {
+                let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(txt.as_bytes()).unwrap();
+                Message::Binary(encoder.finish().unwrap())
+            }
+            Frame::Binary(bytes) => Message::Binary(bytes.clone()),
+            Frame::Reply(_) | Frame::Disconnect | Frame::Hang => {
+                panic!("{:?} is not a data frame", self)
+            }
+        }
+    }
+}
+
+/// Describes how a mock exchange behaves.
+pub struct MockExchange {
+    welcome: Vec<Frame>,
+    pongs: HashMap<String, String>,
+    ignore_pings: bool,
+    frames: Vec<Frame>,
+}
+
+impl MockExchange {
+    /// Creates a mock exchange which replays `frames` after subscription.
+    pub fn new(frames: Vec<Frame>) -> Self {
+        MockExchange {
+            welcome: Vec::new(),
+            pongs: HashMap::new(),
+            ignore_pings: false,
+            frames,
+        }
+    }
+
+    /// Frames sent right after each connection is established.
+    pub fn welcome(mut self, frames: Vec<Frame>) -> Self {
+        self.welcome = frames;
+        self
+    }
+
+    /// Replies `pong` to the text message `ping`.
+    pub fn pong(mut self, ping: &str, pong: &str) -> Self {
+        self.pongs.insert(ping.to_string(), pong.to_string());
+        self
+    }
+
+    /// Never replies to pings.
+    pub fn ignore_pings(mut self) -> Self {
+        self.ignore_pings = true;
+        self
+    }
+
+    /// Listens on a random local port and serves in a background thread.
+    pub fn start(self) -> MockServer {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let num_connections = Arc::new(AtomicUsize::new(0));
+
+        let received_clone = received.clone();
+        let num_connections_clone = num_connections.clone();
+        let handle = thread::spawn(move || {
+            let mut frames: VecDeque<Frame> = self.frames.iter().cloned().collect();
+            let mut next = accept(&listener);
+            while let Some(stream) = next {
+                num_connections_clone.fetch_add(1, Ordering::SeqCst);
+                next = match self.serve(stream, &listener, &mut frames, &received_clone) {
+                    Next::Accept => accept(&listener),
+                    Next::Serve(stream) => Some(stream),
+                    Next::Stop => None,
+                };
+            }
+            // The listener is dropped here, so reconnecting fails from now on
+        });
+
+        MockServer {
+            url,
+            received,
+            num_connections,
+            handle: Some(handle),
+        }
+    }
+
+    // Serves one connection until it is dropped or the script is exhausted.
+    fn serve(
+        &self,
+        stream: TcpStream,
+        listener: &TcpListener,
+        frames: &mut VecDeque<Frame>,
+        received: &Mutex<Vec<String>>,
+    ) -> Next {
+        stream.set_nonblocking(false).unwrap();
+        let mut ws = match tungstenite::accept(stream) {
+            Ok(ws) => ws,
+            Err(_) => return Next::Accept,
+        };
+        ws.get_ref()
+            .set_read_timeout(Some(Duration::from_millis(20)))
+            .unwrap();
+        for frame in self.welcome.iter() {
+            if ws.write_message(frame.to_message()).is_err() {
+                return Next::Accept;
+            }
+        }
+
+        let mut subscribed = false;
+        loop {
+            // Replays only after all pending commands have been read
+            let idle = match ws.read_message() {
+                Ok(Message::Text(txt)) => {
+                    if let Some(pong) = self.pongs.get(&txt) {
+                        if !self.ignore_pings {
+                            let _ = ws.write_message(Message::Text(pong.clone()));
+                        }
+                    } else {
+                        received.lock().unwrap().push(txt);
+                        subscribed = true;
+                    }
+                    false
+                }
+                Ok(Message::Close(_)) => {
+                    let _ = ws.write_pending();
+                    return Next::Accept;
+                }
+                Ok(_) => false,
+                Err(Error::Io(err)) if is_timeout(&err) => true,
+                Err(_) => {
+                    // The client dropped the connection
+                    if let Some(Frame::Hang) = frames.front() {
+                        frames.pop_front();
+                    }
+                    return Next::Accept;
+                }
+            };
+            if !subscribed || !idle {
+                continue;
+            }
+
+            while let Some(frame) = frames.front() {
+                match frame {
+                    Frame::Hang => {
+                        // The client reconnects before dropping the hung connection
+                        if let Ok((stream, _)) = listener.accept() {
+                            frames.pop_front();
+                            return Next::Serve(stream);
+                        }
+                        break;
+                    }
+                    Frame::Disconnect => {
+                        frames.pop_front();
+                        return Next::Accept;
+                    }
+                    Frame::Reply(reply) => {
+                        let last = received.lock().unwrap().last().cloned();
+                        let txt = reply(&last.unwrap_or_default());
+                        if ws.write_message(Message::Text(txt)).is_err() {
+                            return Next::Accept;
+                        }
+                        frames.pop_front();
+                    }
+                    _ => {
+                        if ws.write_message(frame.to_message()).is_err() {
+                            return Next::Accept;
+                        }
+                        frames.pop_front();
+                    }
+                }
+            }
+            if frames.is_empty() {
+                close(ws, received);
+                return Next::Stop;
+            }
+        }
+    }
+}
+
+// What the server does after a connection ends
+enum Next {
+    Accept,           // Waits for the next connection
+    Serve(TcpStream), // Serves a connection which is already accepted
+    Stop,             // The script is exhausted
+}
+
+// Waits for the next connection, the listener is non-blocking so that
+// a hung connection can check for reconnections.
+fn accept(listener: &TcpListener) -> Option<TcpStream> {
+    listener.set_nonblocking(false).unwrap();
+    let stream = listener.accept().ok().map(|(stream, _)| stream);
+    listener.set_nonblocking(true).unwrap();
+    stream
+}
+
+fn is_timeout(err: &std::io::Error) -> bool {
+    err.kind() == ErrorKind::WouldBlock || err.kind() == ErrorKind::TimedOut
+}
+
+// Starts the closing handshake and waits for the reply of the client.
+fn close(mut ws: WebSocket<TcpStream>, received: &Mutex<Vec<String>>) {
+    if ws.close(None).is_err() {
+        return;
+    }
+    for _ in 0..50 {
+        match ws.read_message() {
+            Ok(Message::Text(txt)) => received.lock().unwrap().push(txt),
+            Ok(_) => {}
+            Err(Error::Io(err)) if is_timeout(&err) => {}
+            Err(_) => return,
+        }
+    }
+}
+
+/// A running mock exchange.
+pub struct MockServer {
+    url: String,
+    received: Arc<Mutex<Vec<String>>>,
+    num_connections: Arc<AtomicUsize>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MockServer {
+    /// The url to pass to `WSClient::new()`.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Text messages received from clients, excluding pings.
+    pub fn received(&self) -> Vec<String> {
+        self.received.lock().unwrap().clone()
+    }
+
+    /// Number of accepted connections.
+    pub fn num_connections(&self) -> usize {
+        self.num_connections.load(Ordering::SeqCst)
+    }
+
+    /// Waits until the script is exhausted.
+    pub fn join(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            handle.join().unwrap();
+        }
+    }
+}
+
+/// Reconnects immediately and gives up soon after the server stops listening.
+pub fn fast_reconnect() -> ReconnectPolicy {
+    ReconnectPolicy {
+        initial_delay: Duration::from_millis(10),
+        max_delay: Duration::from_millis(10),
+        multiplier: 1.0,
+        jitter: 0.0,
+        max_attempts: Some(3),
+    }
+}
+
+// Subscribes to `$channels` on a mock server, runs the client until the server
+// finishes its script, and returns all messages passed to on_msg.
+#[allow(unused_macros)]
+macro_rules! run_offline {
+    ($client:ident, $server:expr, $channels:expr) => {{
+        run_offline!($client, $server, $channels, WSClientOptions::default())
+    }};
+    ($client:ident, $server:expr, $channels:expr, $options:expr) => {{
+        let mut messages = Vec::<String>::new();
+        {
+            let on_msg = Arc::new(Mutex::new(|msg: String| messages.push(msg)));
+            let options = $options.endpoints(&[$server.url()]);
+            let ws_client = $client::new_with_options(on_msg.clone(), options);
+            ws_client.set_reconnect_policy(mock_server::fast_reconnect());
+            ws_client.subscribe($channels);
+            ws_client.run(None); // returns after the server stops listening
+            ws_client.close();
+        }
+        messages
+    }};
+}
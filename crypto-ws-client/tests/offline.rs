@@ -0,0 +1,1867 @@
+use crypto_ws_client::*;
+use std::sync::{Arc, Mutex};
+
+#[macro_use]
+mod mock_server;
+
+use mock_server::{Frame, MockExchange};
+
+#[cfg(test)]
+mod binance {
+    use super::*;
+
+    const ACK: &str = r#"{"result":null,"id":9527}"#;
+    const TRADE: &str = r#"{"stream":"btcusdt@aggTrade","data":{"e":"aggTrade","E":1616651250071,"s":"BTCUSDT","a":626337164,"p":"52000.01","q":"0.00100000","f":715427467,"l":715427467,"T":1616651250070,"m":true,"M":true}}"#;
+    const INVERSE_TRADE: &str = r#"{"stream":"btcusd_perp@aggTrade","data":{"e":"aggTrade","E":1616651250071,"a":86718930,"s":"BTCUSD_PERP","p":"52000.1","q":"1","f":139950290,"l":139950290,"T":1616651250070,"m":false}}"#;
+    const LINEAR_TRADE: &str = r#"{"stream":"btcusdt@aggTrade","data":{"e":"aggTrade","E":1616651250071,"a":402340130,"s":"BTCUSDT","p":"52010.00","q":"0.010","f":631476002,"l":631476002,"T":1616651250070,"m":true}}"#;
+
+    fn id_of(command: &str) -> serde_json::Value {
+        serde_json::from_str::<serde_json::Value>(command).unwrap()["id"].clone()
+    }
+
+    // Replies the ack of the last command
+    fn ack(command: &str) -> String {
+        format!(r#"{{"result":null,"id":{}}}"#, id_of(command))
+    }
+
+    // Rejects the last command
+    fn invalid(command: &str) -> String {
+        format!(
+            r#"{{"error":{{"code":2,"msg":"Invalid request"}},"id":{}}}"#,
+            id_of(command)
+        )
+    }
+
+    // Every command has its own id
+    fn without_id(commands: Vec<String>) -> Vec<String> {
+        commands
+            .iter()
+            .map(|command| {
+                assert!(id_of(command).is_u64());
+                command.replacen(&format!(r#""id":{},"#, id_of(command)), "", 1)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn spot() {
+        let mut server = MockExchange::new(vec![Frame::Reply(ack), Frame::text(TRADE)]).start();
+        let messages = run_offline!(
+            BinanceSpotWSClient,
+            server,
+            &["btcusdt@aggTrade".to_string()]
+        );
+        server.join();
+        assert_eq!(vec![TRADE.to_string()], messages);
+        assert_eq!(
+            vec![r#"{"method":"SUBSCRIBE","params":["btcusdt@aggTrade"]}"#.to_string()],
+            without_id(server.received())
+        );
+    }
+
+    #[test]
+    fn subscription_states() {
+        let mut server = MockExchange::new(vec![
+            Frame::Reply(ack),
+            Frame::text(TRADE),
+            Frame::Disconnect,
+            // the command after reconnecting is rejected
+            Frame::Reply(invalid),
+            Frame::text(TRADE),
+        ])
+        .start();
+        let events = Arc::new(Mutex::new(Vec::<WsEvent>::new()));
+        let subscriptions = {
+            let events_clone = events.clone();
+            let on_event = Arc::new(Mutex::new(move |event: WsEvent| {
+                events_clone.lock().unwrap().push(event)
+            }));
+            let ws_client = BinanceSpotWSClient::new_with_options(
+                Arc::new(Mutex::new(|_msg: String| {})),
+                WSClientOptions::default()
+                    .endpoints(&[server.url()])
+                    .events(on_event),
+            );
+            ws_client.set_reconnect_policy(mock_server::fast_reconnect());
+            ws_client.subscribe(&["btcusdt@aggTrade".to_string()]);
+            ws_client.run(None);
+            ws_client.close();
+            ws_client.subscriptions()
+        };
+        server.join();
+
+        let channel = "btcusdt@aggTrade".to_string();
+        let events = events.lock().unwrap();
+        assert!(events.contains(&WsEvent::Subscribed {
+            channel: channel.clone()
+        }));
+        assert!(events.contains(&WsEvent::SubscribeFailed {
+            channel: Some(channel.clone()),
+            code: Some(2),
+            reason: "Invalid request".to_string(),
+        }));
+        assert_eq!(
+            Some(&SubscriptionState::Rejected),
+            subscriptions.get(&channel)
+        );
+        let received = server.received();
+        assert_eq!(2, received.len());
+        assert_ne!(id_of(&received[0]), id_of(&received[1]));
+    }
+
+    #[test]
+    fn metrics() {
+        let mut server = MockExchange::new(vec![Frame::text(ACK), Frame::text(TRADE)]).start();
+        let mut messages = Vec::<String>::new();
+        let metrics = {
+            let on_msg = Arc::new(Mutex::new(|msg: String| messages.push(msg)));
+            let ws_client = BinanceSpotWSClient::new(on_msg, Some(server.url()));
+            ws_client.set_reconnect_policy(mock_server::fast_reconnect());
+            assert_eq!(Metrics::default(), ws_client.metrics());
+            ws_client.subscribe_trade(&["btcusdt".to_string()]).unwrap();
+            ws_client.run(Some(0));
+            ws_client.close();
+            ws_client.metrics()
+        };
+        server.join();
+        assert_eq!(vec![TRADE.to_string()], messages);
+        assert_eq!(Some(&1), metrics.messages.get("btcusdt@aggTrade"));
+        assert_eq!((ACK.len() + TRADE.len()) as u64, metrics.bytes);
+        assert_eq!(0, metrics.decompression_failures);
+        assert_eq!(0, metrics.reconnects);
+        assert!(metrics.last_message_age.is_some());
+        assert!(metrics.latency_millis.is_some());
+    }
+
+    #[test]
+    fn inverse_gzip() {
+        let mut server =
+            MockExchange::new(vec![Frame::gzip(ACK), Frame::gzip(INVERSE_TRADE)]).start();
+        let messages = run_offline!(
+            BinanceInverseWSClient,
+            server,
+            &["btcusd_perp@aggTrade".to_string()]
+        );
+        server.join();
+        assert_eq!(vec![INVERSE_TRADE.to_string()], messages);
+    }
+
+    #[test]
+    fn derivative_channels() {
+        const MARK_PRICE: &str = r#"{"stream":"btcusd_perp@markPrice","data":{"e":"markPriceUpdate","E":1616651250071,"s":"BTCUSD_PERP","p":"52000.12345678","P":"52010.50000000","r":"0.00010000","T":1616659200000}}"#;
+        let mut server = MockExchange::new(vec![Frame::text(ACK), Frame::text(MARK_PRICE)]).start();
+        let mut messages = Vec::<String>::new();
+        {
+            let on_msg = Arc::new(Mutex::new(|msg: String| messages.push(msg)));
+            let ws_client = BinanceInverseWSClient::new(on_msg, Some(server.url()));
+            ws_client.set_reconnect_policy(mock_server::fast_reconnect());
+
+            assert_eq!(
+                Ok(vec![
+                    "btcusd_perp@markPrice".to_string(),
+                    "btcusd@indexPrice".to_string(),
+                    "btcusd_perp@forceOrder".to_string(),
+                ]),
+                ws_client.translate_channels(&[
+                    Channel::MarkPrice("btcusd_perp".to_string()),
+                    Channel::IndexPrice("btcusd_perp".to_string()),
+                    Channel::Liquidation("btcusd_perp".to_string()),
+                ])
+            );
+            // Binance has open interest via RESTful APIs only
+            assert_eq!(
+                Err(WsError::Unsupported {
+                    exchange: "binance".to_string(),
+                    channel: Channel::OpenInterest("btcusd_perp".to_string()),
+                }),
+                ws_client.subscribe_open_interest(&["btcusd_perp".to_string()])
+            );
+            ws_client
+                .subscribe_mark_price(&["btcusd_perp".to_string()])
+                .unwrap();
+            ws_client.run(None);
+            ws_client.close();
+        }
+        server.join();
+        assert_eq!(vec![MARK_PRICE.to_string()], messages);
+        assert_eq!(
+            vec![r#"{"method":"SUBSCRIBE","params":["btcusd_perp@markPrice"]}"#.to_string()],
+            without_id(server.received())
+        );
+    }
+
+    #[test]
+    fn linear() {
+        let mut server =
+            MockExchange::new(vec![Frame::text(ACK), Frame::text(LINEAR_TRADE)]).start();
+        let messages = run_offline!(
+            BinanceLinearWSClient,
+            server,
+            &["btcusdt@aggTrade".to_string()]
+        );
+        server.join();
+        assert_eq!(vec![LINEAR_TRADE.to_string()], messages);
+    }
+
+    #[test]
+    fn option() {
+        const TRADE: &str = r#"{"stream":"BTC-210430-60000-C@trade","data":{"e":"trade","E":1616651250071,"s":"BTC-210430-60000-C","t":[{"t":"20","p":"1500","q":"0.0100","b":4611781675939004417,"a":4611781675939004418,"T":1616651250070,"s":"-1"}]}}"#;
+        let mut server = MockExchange::new(vec![Frame::text(r#"{"id":9527}"#), Frame::text(TRADE)])
+            .pong(r#"{"event":"ping"}"#, r#"{"event":"pong"}"#)
+            .start();
+        let messages = run_offline!(
+            BinanceOptionWSClient,
+            server,
+            &["BTC-210430-60000-C@trade".to_string()]
+        );
+        server.join();
+        assert_eq!(vec![TRADE.to_string()], messages);
+    }
+
+    #[test]
+    fn failover() {
+        let mut server = MockExchange::new(vec![Frame::text(ACK), Frame::text(TRADE)]).start();
+        let mut messages = Vec::<String>::new();
+        {
+            let on_msg = Arc::new(Mutex::new(|msg: String| messages.push(msg)));
+            // nothing listens on port 1, so the client moves on to the mock exchange
+            let ws_client = BinanceSpotWSClient::new_with_options(
+                on_msg,
+                WSClientOptions::default().endpoints(&["ws://127.0.0.1:1", server.url()]),
+            );
+            assert_eq!(server.url(), ws_client.endpoint());
+            ws_client.set_reconnect_policy(mock_server::fast_reconnect());
+            ws_client.subscribe(&["btcusdt@aggTrade".to_string()]);
+            ws_client.run(None);
+            ws_client.close();
+        }
+        server.join();
+        assert_eq!(vec![TRADE.to_string()], messages);
+        assert_eq!(1, server.num_connections());
+    }
+
+    #[test]
+    fn combined_options() {
+        let mut server = MockExchange::new(vec![Frame::text(ACK), Frame::text(TRADE)]).start();
+        let mut messages = Vec::<String>::new();
+        let events = Arc::new(Mutex::new(Vec::<WsEvent>::new()));
+        {
+            let events_clone = events.clone();
+            let on_event = Arc::new(Mutex::new(move |event: WsEvent| {
+                events_clone.lock().unwrap().push(event)
+            }));
+            let on_msg = Arc::new(Mutex::new(|msg: String| messages.push(msg)));
+            let ws_client = BinanceSpotWSClient::new_with_options(
+                on_msg,
+                WSClientOptions::default()
+                    .endpoints(&["ws://127.0.0.1:1", server.url()])
+                    .events(on_event)
+                    .config(ConnectionConfig {
+                        max_read_timeouts: Some(20),
+                        ..Default::default()
+                    }),
+            );
+            assert_eq!(server.url(), ws_client.endpoint());
+            ws_client.set_reconnect_policy(mock_server::fast_reconnect());
+            ws_client.subscribe(&["btcusdt@aggTrade".to_string()]);
+            ws_client.run(None);
+            ws_client.close();
+        }
+        server.join();
+        assert_eq!(vec![TRADE.to_string()], messages);
+        assert!(events.lock().unwrap().contains(&WsEvent::Connected {
+            url: server.url().to_string()
+        }));
+    }
+
+    #[test]
+    fn pool() {
+        const ETH_TRADE: &str = r#"{"stream":"ethusdt@aggTrade","data":{"e":"aggTrade","E":1616651250071,"s":"ETHUSDT","a":380421574,"p":"1600.01","q":"0.10000000","f":407530381,"l":407530381,"T":1616651250070,"m":false,"M":true}}"#;
+        let mut server_a = MockExchange::new(vec![Frame::text(ACK), Frame::text(TRADE)]).start();
+        let mut server_b =
+            MockExchange::new(vec![Frame::text(ACK), Frame::text(ETH_TRADE)]).start();
+        let mut messages = Vec::<String>::new();
+        {
+            let on_msg = Arc::new(Mutex::new(|msg: String| messages.push(msg)));
+            // one channel per connection, connections start from different endpoints
+            let ws_client = WSClientPool::<BinanceSpotWSClient>::new_with_limits(
+                on_msg,
+                &[server_a.url(), server_b.url()],
+                ConnectionLimits {
+                    max_channels: 1,
+                    max_messages_per_second: None,
+                },
+            );
+            ws_client.set_reconnect_policy(mock_server::fast_reconnect());
+            ws_client.subscribe(&[
+                "btcusdt@aggTrade".to_string(),
+                "ethusdt@aggTrade".to_string(),
+            ]);
+            assert_eq!(2, ws_client.num_connections());
+            assert_eq!(2, ws_client.subscriptions().len());
+            ws_client.run(None);
+            ws_client.close();
+        }
+        server_a.join();
+        server_b.join();
+
+        messages.sort();
+        assert_eq!(vec![TRADE.to_string(), ETH_TRADE.to_string()], messages);
+        assert_eq!(
+            vec![r#"{"method":"SUBSCRIBE","params":["btcusdt@aggTrade"]}"#.to_string()],
+            without_id(server_a.received())
+        );
+        assert_eq!(
+            vec![r#"{"method":"SUBSCRIBE","params":["ethusdt@aggTrade"]}"#.to_string()],
+            without_id(server_b.received())
+        );
+    }
+
+    #[test]
+    fn rate_limit() {
+        let mut server = MockExchange::new(vec![
+            Frame::text(ACK),
+            Frame::text(TRADE),
+            Frame::Disconnect,
+            Frame::text(TRADE),
+        ])
+        .start();
+        let channels: Vec<String> = (0..7).map(|i| format!("pair{}@aggTrade", i)).collect();
+        {
+            let on_msg = Arc::new(Mutex::new(|_msg: String| {}));
+            let ws_client = BinanceSpotWSClient::new(on_msg, Some(server.url()));
+            assert_eq!(
+                Some(5),
+                ws_client.connection_limits().max_messages_per_second
+            );
+            ws_client.set_reconnect_policy(mock_server::fast_reconnect());
+            for channel in channels.iter() {
+                ws_client.subscribe(std::slice::from_ref(channel));
+            }
+            // 5 commands per second at most
+            assert_eq!(2, ws_client.num_pending_commands());
+            ws_client.run(None);
+            ws_client.close();
+        }
+        server.join();
+
+        // queued commands are dropped after disconnected, since all channels
+        // are resubscribed anyway
+        let received = without_id(server.received());
+        assert_eq!(6, received.len());
+        for (i, channel) in channels.iter().take(5).enumerate() {
+            assert_eq!(
+                format!(r#"{{"method":"SUBSCRIBE","params":["{}"]}}"#, channel),
+                received[i]
+            );
+        }
+        assert!(channels.iter().all(|channel| received[5].contains(channel)));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn failover_async() {
+        let mut server = MockExchange::new(vec![Frame::text(ACK), Frame::text(TRADE)]).start();
+        let messages = Arc::new(Mutex::new(Vec::<String>::new()));
+        {
+            let messages_clone = messages.clone();
+            let on_msg = Arc::new(Mutex::new(move |msg: String| {
+                messages_clone.lock().unwrap().push(msg)
+            }));
+            let ws_client = BinanceSpotAsyncWSClient::new_with_options(
+                on_msg,
+                WSClientOptions::default().endpoints(&["ws://127.0.0.1:1", server.url()]),
+            );
+            assert_eq!("ws://127.0.0.1:1", ws_client.endpoint());
+            ws_client.set_reconnect_policy(mock_server::fast_reconnect());
+            ws_client.subscribe(&["btcusdt@aggTrade".to_string()]).await;
+            ws_client.run(None).await;
+            ws_client.close();
+        }
+        server.join();
+        assert_eq!(vec![TRADE.to_string()], *messages.lock().unwrap());
+        assert_eq!(1, server.num_connections());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn spot_async() {
+        let mut server = MockExchange::new(vec![
+            Frame::text(ACK),
+            Frame::text(TRADE),
+            Frame::Disconnect,
+            Frame::gzip(TRADE),
+        ])
+        .start();
+        let messages = Arc::new(Mutex::new(Vec::<String>::new()));
+        {
+            let messages_clone = messages.clone();
+            let on_msg = Arc::new(Mutex::new(move |msg: String| {
+                messages_clone.lock().unwrap().push(msg)
+            }));
+            let ws_client = BinanceSpotAsyncWSClient::new(on_msg, Some(server.url()));
+            ws_client.set_reconnect_policy(mock_server::fast_reconnect());
+            ws_client.subscribe(&["btcusdt@aggTrade".to_string()]).await;
+            ws_client.run(None).await;
+            ws_client.close();
+        }
+        server.join();
+        assert_eq!(
+            vec![TRADE.to_string(), TRADE.to_string()],
+            *messages.lock().unwrap()
+        );
+        assert_eq!(2, server.num_connections());
+        assert_eq!(2, server.received().len());
+    }
+
+    // A current-thread runtime, which is stalled if on_msg blocks
+    #[tokio::test]
+    async fn spot_async_stream() {
+        let mut server = MockExchange::new(vec![Frame::text(ACK), Frame::text(TRADE)]).start();
+        let (ws_client, stream) = BinanceSpotAsyncWSClient::new_with_stream(
+            16,
+            BackpressurePolicy::DropNewest,
+            WSClientOptions::default().endpoints(&[server.url()]),
+        );
+        ws_client.set_reconnect_policy(mock_server::fast_reconnect());
+        ws_client.subscribe(&["btcusdt@aggTrade".to_string()]).await;
+        ws_client.run(None).await;
+        drop(ws_client);
+        server.join();
+        assert_eq!(
+            vec![TRADE.to_string()],
+            futures_util::StreamExt::collect::<Vec<String>>(stream).await
+        );
+    }
+
+    #[tokio::test]
+    async fn spot_async_stream_dropped() {
+        let server =
+            MockExchange::new(vec![Frame::text(ACK), Frame::text(TRADE), Frame::Hang]).start();
+        let (ws_client, stream) = BinanceSpotAsyncWSClient::new_with_stream(
+            16,
+            BackpressurePolicy::DropNewest,
+            WSClientOptions::default().endpoints(&[server.url()]),
+        );
+        drop(stream);
+        ws_client.subscribe(&["btcusdt@aggTrade".to_string()]).await;
+        // Without closing itself, the client would wait on the hung connection
+        let result =
+            tokio::time::timeout(std::time::Duration::from_secs(10), ws_client.run(None)).await;
+        assert!(result.is_ok());
+        assert_eq!(1, server.num_connections());
+    }
+
+    #[test]
+    #[should_panic(expected = "BackpressurePolicy::Block")]
+    fn spot_async_stream_block() {
+        let _ = BinanceSpotAsyncWSClient::new_with_stream(
+            1,
+            BackpressurePolicy::Block,
+            WSClientOptions::default(),
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn spot_async_events() {
+        let error = r#"{"error":{"code":2,"msg":"Invalid request: unknown variant `SUBSCRIBEX`"},"id":9527}"#;
+        let mut server = MockExchange::new(vec![
+            Frame::text(error),
+            Frame::text(TRADE),
+            Frame::Disconnect,
+            Frame::text(TRADE),
+        ])
+        .start();
+        let events = Arc::new(Mutex::new(Vec::<WsEvent>::new()));
+        {
+            let events_clone = events.clone();
+            let on_event = Arc::new(Mutex::new(move |event: WsEvent| {
+                events_clone.lock().unwrap().push(event)
+            }));
+            let ws_client = BinanceSpotAsyncWSClient::new_with_options(
+                Arc::new(Mutex::new(|_msg: String| {})),
+                WSClientOptions::default()
+                    .endpoints(&[server.url()])
+                    .events(on_event),
+            );
+            ws_client.set_reconnect_policy(mock_server::fast_reconnect());
+            ws_client.subscribe(&["btcusdt@aggTrade".to_string()]).await;
+            ws_client.run(None).await;
+            ws_client.close();
+        }
+        server.join();
+
+        let events = events.lock().unwrap();
+        assert_eq!(5, events.len());
+        assert_eq!(
+            WsEvent::Connected {
+                url: server.url().to_string()
+            },
+            events[0]
+        );
+        assert_eq!(
+            WsEvent::SubscribeFailed {
+                channel: None,
+                code: Some(2),
+                reason: "Invalid request: unknown variant `SUBSCRIBEX`".to_string()
+            },
+            events[1]
+        );
+        assert!(matches!(events[2], WsEvent::Disconnected { .. }));
+        assert!(matches!(&events[3], WsEvent::Reconnected(event) if event.num_channels == 1));
+        // the server stopped listening
+        assert!(matches!(events[4], WsEvent::Disconnected { .. }));
+    }
+}
+
+#[cfg(test)]
+mod bitfinex {
+    use super::*;
+
+    const INFO: &str = r#"{"event":"info","version":2,"serverId":"b9c5ec3b-7ab1-4c3f-8d5e-7f25a35f7c47","platform":{"status":1}}"#;
+    const SUBSCRIBED: &str = r#"{"event":"subscribed","channel":"trades","chanId":17470,"symbol":"tBTCUSD","pair":"BTCUSD"}"#;
+    const TRADE: &str = r#"[17470,"te",[401597395,1574694478808,0.005,7245.3]]"#;
+
+    #[test]
+    fn trades() {
+        let mut server = MockExchange::new(vec![
+            Frame::text(SUBSCRIBED),
+            Frame::text(TRADE),
+            Frame::Disconnect,
+            // channel IDs are assigned per connection
+            Frame::text(&SUBSCRIBED.replace("17470", "17471")),
+            Frame::text(&TRADE.replace("17470", "17471")),
+        ])
+        .welcome(vec![Frame::text(INFO)])
+        .start();
+        let messages = run_offline!(BitfinexWSClient, server, &["trades:tBTCUSD".to_string()]);
+        server.join();
+
+        assert_eq!(2, messages.len());
+        for msg in messages {
+            // CHANNEL_ID is replaced with the channel info
+            assert!(msg.starts_with("[{"));
+            assert!(msg.contains(r#""symbol":"tBTCUSD""#));
+            assert!(msg.ends_with(r#","te",[401597395,1574694478808,0.005,7245.3]]"#));
+        }
+        assert_eq!(2, server.num_connections());
+        assert_eq!(
+            vec![
+                r#"{"event": "subscribe", "channel": "trades", "symbol": "tBTCUSD"}"#.to_string();
+                2
+            ],
+            server.received()
+        );
+    }
+
+    #[test]
+    fn skip_binary_frames() {
+        let mut server = MockExchange::new(vec![
+            Frame::text(SUBSCRIBED),
+            Frame::Binary(vec![0, 1, 2, 3]),
+            Frame::text(TRADE),
+        ])
+        .welcome(vec![Frame::text(INFO)])
+        .start();
+        let messages = run_offline!(BitfinexWSClient, server, &["trades:tBTCUSD".to_string()]);
+        server.join();
+
+        assert_eq!(1, messages.len());
+        assert_eq!(1, server.num_connections());
+    }
+
+    #[test]
+    fn resubscribe_after_maintenance() {
+        const MAINTENANCE_ENDED: &str =
+            r#"{"event":"info","code":20061,"msg":"Maintenance ended"}"#;
+        let mut server = MockExchange::new(vec![
+            Frame::text(SUBSCRIBED),
+            Frame::text(TRADE),
+            Frame::text(MAINTENANCE_ENDED),
+            Frame::text(r#"{"event":"unsubscribed","status":"OK","chanId":17470}"#),
+            Frame::text(&SUBSCRIBED.replace("17470", "17471")),
+            Frame::text(&TRADE.replace("17470", "17471")),
+        ])
+        .welcome(vec![Frame::text(INFO)])
+        .start();
+        let messages = run_offline!(BitfinexWSClient, server, &["trades:tBTCUSD".to_string()]);
+        server.join();
+
+        assert_eq!(2, messages.len());
+        assert_eq!(1, server.num_connections());
+        // Unsubscribes first, otherwise subscribing again fails with 10301
+        let subscribe = r#"{"event": "subscribe", "channel": "trades", "symbol": "tBTCUSD"}"#;
+        assert_eq!(
+            vec![
+                subscribe.to_string(),
+                r#"{"event":"unsubscribe","chanId":17470}"#.to_string(),
+                subscribe.to_string(),
+            ],
+            server.received()
+        );
+    }
+
+    #[test]
+    fn skip_unknown_channel_ids() {
+        // e.g., a frame still in flight after unsubscribed
+        let mut server = MockExchange::new(vec![
+            Frame::text(SUBSCRIBED),
+            Frame::text(&TRADE.replace("17470", "17472")),
+            Frame::text(TRADE),
+        ])
+        .welcome(vec![Frame::text(INFO)])
+        .start();
+        let messages = run_offline!(BitfinexWSClient, server, &["trades:tBTCUSD".to_string()]);
+        server.join();
+
+        assert_eq!(1, messages.len());
+        assert_eq!(1, server.num_connections());
+    }
+
+    #[test]
+    fn subscription_states() {
+        const BOOK_SUBSCRIBED: &str = r#"{"event":"subscribed","channel":"book","chanId":8,"symbol":"tBTCUSD","prec":"R0","freq":"F0","len":"250","pair":"BTCUSD"}"#;
+        const CANDLES_FAILED: &str = r#"{"event":"error","msg":"key: invalid","code":10300,"channel":"candles","key":"trade:1m:tBTCUSDX"}"#;
+        let mut server = MockExchange::new(vec![
+            Frame::text(BOOK_SUBSCRIBED),
+            Frame::text(CANDLES_FAILED),
+        ])
+        .welcome(vec![Frame::text(INFO)])
+        .start();
+        let events = Arc::new(Mutex::new(Vec::<WsEvent>::new()));
+        let events_clone = events.clone();
+        let (book, candles) = {
+            let on_msg = Arc::new(Mutex::new(|_msg: String| {}));
+            let options = WSClientOptions::default()
+                .endpoints(&[server.url()])
+                .events(Arc::new(Mutex::new(move |event: WsEvent| {
+                    events_clone.lock().unwrap().push(event)
+                })));
+            let ws_client = BitfinexWSClient::new_with_options(on_msg, options);
+            ws_client.set_reconnect_policy(mock_server::fast_reconnect());
+            let book = ws_client
+                .translate_channels(&[Channel::L3("tBTCUSD".to_string())])
+                .unwrap()
+                .remove(0);
+            let candles = ws_client
+                .translate_channels(&[Channel::Kline {
+                    pair: "tBTCUSDX".to_string(),
+                    interval: 60,
+                }])
+                .unwrap()
+                .remove(0);
+            ws_client.subscribe(&[book.clone(), candles.clone()]);
+            ws_client.run(None);
+            ws_client.close();
+            let subscriptions = ws_client.subscriptions();
+            assert_eq!(
+                Some(&SubscriptionState::Confirmed),
+                subscriptions.get(&book)
+            );
+            assert_eq!(
+                Some(&SubscriptionState::Rejected),
+                subscriptions.get(&candles)
+            );
+            (book, candles)
+        };
+        server.join();
+
+        let events = events.lock().unwrap();
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, WsEvent::Subscribed { channel } if *channel == book)));
+        assert!(events.iter().any(
+            |event| matches!(event, WsEvent::SubscribeFailed { channel: Some(channel), code: Some(10300), .. } if *channel == candles)
+        ));
+    }
+
+    #[test]
+    fn unsubscribe_by_channel_id() {
+        const BOOK_SUBSCRIBED: &str = r#"{"event":"subscribed","channel":"book","chanId":8,"symbol":"tBTCUSD","prec":"P0","freq":"F0","len":"25","pair":"BTCUSD"}"#;
+        const BOOK: &str = r#"[8,[7254.7,3,3.3]]"#;
+        let mut server = MockExchange::new(vec![Frame::text(BOOK_SUBSCRIBED), Frame::text(BOOK)])
+            .welcome(vec![Frame::text(INFO)])
+            .start();
+        let subscriptions = {
+            let on_msg = Arc::new(Mutex::new(|_msg: String| {}));
+            let ws_client = BitfinexWSClient::new(on_msg, Some(server.url()));
+            ws_client.set_reconnect_policy(mock_server::fast_reconnect());
+            let book = Channel::L2 {
+                pair: "tBTCUSD".to_string(),
+                depth: None,
+                speed: None,
+            };
+            ws_client
+                .subscribe_channels(std::slice::from_ref(&book))
+                .unwrap();
+            ws_client.run(Some(0));
+            ws_client.unsubscribe_channels(&[book]).unwrap();
+            ws_client.close();
+            ws_client.subscriptions()
+        };
+        server.join();
+
+        assert!(subscriptions.is_empty());
+        let received = server.received();
+        assert_eq!(2, received.len());
+        assert!(received[0].contains(r#""event": "subscribe""#));
+        // JSON channels are unsubscribed by CHANNEL_ID instead of being sent again
+        assert_eq!(r#"{"event":"unsubscribe","chanId":8}"#, received[1]);
+    }
+}
+
+#[cfg(test)]
+mod bitget {
+    use super::*;
+
+    #[test]
+    fn swap_gzip() {
+        const TRADE: &str = r#"{"data":[{"instrument_id":"btcusd","price":"52000.5","side":"buy","size":"12","timestamp":"1616651250071"}],"table":"swap/trade"}"#;
+        let mut server = MockExchange::new(vec![
+            Frame::gzip(r#"{"event":"subscribe","channel":"swap/trade:btcusd"}"#),
+            Frame::gzip(TRADE),
+        ])
+        .pong("ping", "pong")
+        .start();
+        let messages = run_offline!(
+            BitgetSwapWSClient,
+            server,
+            &["swap/trade:btcusd".to_string()]
+        );
+        server.join();
+        assert_eq!(vec![TRADE.to_string()], messages);
+    }
+}
+
+#[cfg(test)]
+mod bithumb {
+    use super::*;
+
+    #[test]
+    fn trade() {
+        const TRADE: &str = r#"{"code":"00007","data":{"p":"52000.01","symbol":"BTC-USDT","ver":"1","s":"buy","t":"1616651250","v":"0.001"},"timestamp":1616651250071,"topic":"TRADE"}"#;
+        let mut server = MockExchange::new(vec![
+            Frame::text(r#"{"code":"00001","data":null,"timestamp":1616651250071}"#),
+            Frame::text(TRADE),
+        ])
+        .pong(
+            r#"{"cmd":"ping"}"#,
+            r#"{"code":"0","message":"Pong","timestamp":1616651250071}"#,
+        )
+        .start();
+        let messages = run_offline!(BithumbWSClient, server, &["TRADE:BTC-USDT".to_string()]);
+        server.join();
+        assert_eq!(vec![TRADE.to_string()], messages);
+    }
+}
+
+#[cfg(test)]
+mod bitmex {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    const WELCOME: &str = r#"{"info":"Welcome to the BitMEX Realtime API.","version":"2021-03-24T20:57:32.000Z","timestamp":"2021-03-25T05:47:29.819Z","docs":"https://www.bitmex.com/app/wsAPI","limit":{"remaining":39}}"#;
+    const ACK: &str = r#"{"success":true,"subscribe":"trade:XBTUSD","request":{"op":"subscribe","args":["trade:XBTUSD"]}}"#;
+    const TRADE: &str = r#"{"table":"trade","action":"insert","data":[{"timestamp":"2021-03-25T05:47:30.071Z","symbol":"XBTUSD","side":"Buy","size":100,"price":52000.5,"tickDirection":"PlusTick","trdMatchID":"0e8a7a5f-2c77-9b9c-0a9e-6b7ff1d3c0d8","grossValue":192307,"homeNotional":0.00192307,"foreignNotional":100}]}"#;
+    const COMMAND: &str = r#"{"op":"subscribe","args":["trade:XBTUSD"]}"#;
+
+    fn mock_exchange(frames: Vec<Frame>) -> MockExchange {
+        MockExchange::new(frames)
+            .welcome(vec![Frame::text(WELCOME)])
+            .pong("ping", "pong")
+    }
+
+    #[test]
+    fn trade() {
+        let mut server = mock_exchange(vec![Frame::text(ACK), Frame::text(TRADE)]).start();
+        let messages = run_offline!(BitmexWSClient, server, &["trade:XBTUSD".to_string()]);
+        server.join();
+        assert_eq!(vec![TRADE.to_string()], messages);
+        assert_eq!(vec![COMMAND.to_string()], server.received());
+    }
+
+    #[test]
+    fn resubscribe_after_disconnect() {
+        let mut server = mock_exchange(vec![
+            Frame::text(ACK),
+            Frame::text(TRADE),
+            Frame::Disconnect,
+            Frame::text(ACK),
+            Frame::text(TRADE),
+            Frame::Disconnect,
+            Frame::text(TRADE),
+        ])
+        .start();
+        let num_reconnects = Arc::new(Mutex::new(0));
+        let mut messages = Vec::<String>::new();
+        {
+            let on_msg = Arc::new(Mutex::new(|msg: String| messages.push(msg)));
+            let ws_client = BitmexWSClient::new(on_msg, Some(server.url()));
+            ws_client.set_reconnect_policy(mock_server::fast_reconnect());
+            let num_reconnects_clone = num_reconnects.clone();
+            ws_client.on_reconnect(Arc::new(Mutex::new(move |event: ReconnectEvent| {
+                assert_eq!(1, event.num_channels);
+                *num_reconnects_clone.lock().unwrap() += 1;
+            })));
+            ws_client.subscribe(&["trade:XBTUSD".to_string()]);
+            ws_client.run(None);
+            ws_client.close();
+        }
+        server.join();
+
+        assert_eq!(vec![TRADE.to_string(); 3], messages);
+        assert_eq!(3, server.num_connections());
+        assert_eq!(2, *num_reconnects.lock().unwrap());
+        assert_eq!(vec![COMMAND.to_string(); 3], server.received());
+    }
+
+    #[test]
+    fn skip_malformed_frames() {
+        let mut server = mock_exchange(vec![
+            Frame::text("{\"table\":\"trade\",\"action\":"),
+            Frame::text(TRADE),
+            Frame::text("not a JSON string"),
+            Frame::text(TRADE),
+        ])
+        .start();
+        let messages = run_offline!(BitmexWSClient, server, &["trade:XBTUSD".to_string()]);
+        server.join();
+        assert_eq!(vec![TRADE.to_string(); 2], messages);
+        assert_eq!(1, server.num_connections());
+    }
+
+    #[test]
+    fn skip_unknown_binary_frames() {
+        let mut server = mock_exchange(vec![
+            Frame::gzip(TRADE),
+            Frame::text(TRADE),
+            Frame::Binary(vec![0, 1, 2, 3]),
+            Frame::text(TRADE),
+        ])
+        .start();
+        let messages = run_offline!(BitmexWSClient, server, &["trade:XBTUSD".to_string()]);
+        server.join();
+        assert_eq!(vec![TRADE.to_string(); 2], messages);
+        assert_eq!(1, server.num_connections());
+    }
+
+    #[test]
+    fn reconnect_after_missed_pongs() {
+        let mut server = mock_exchange(vec![Frame::text(TRADE), Frame::Hang, Frame::text(TRADE)])
+            .ignore_pings()
+            .start();
+        let messages = run_offline!(BitmexWSClient, server, &["trade:XBTUSD".to_string()]);
+        server.join();
+        assert_eq!(vec![TRADE.to_string(); 2], messages);
+        assert_eq!(2, server.num_connections());
+        assert_eq!(vec![COMMAND.to_string(); 2], server.received());
+    }
+
+    #[test]
+    fn reconnect_after_pong_timeout() {
+        let mut server = mock_exchange(vec![Frame::text(TRADE), Frame::Hang, Frame::text(TRADE)])
+            .ignore_pings()
+            .start();
+        let config = ConnectionConfig {
+            ping_interval: Some(Duration::from_millis(100)),
+            pong_timeout: Some(Duration::from_millis(300)),
+            ..Default::default()
+        };
+        let mut messages = Vec::<String>::new();
+        let start = Instant::now();
+        {
+            let on_msg = Arc::new(Mutex::new(|msg: String| messages.push(msg)));
+            let ws_client = BitmexWSClient::new_with_options(
+                on_msg,
+                WSClientOptions::default()
+                    .endpoints(&[server.url()])
+                    .config(config),
+            );
+            ws_client.set_reconnect_policy(mock_server::fast_reconnect());
+            ws_client.subscribe(&["trade:XBTUSD".to_string()]);
+            ws_client.run(None);
+            ws_client.close();
+        }
+        server.join();
+        assert_eq!(vec![TRADE.to_string(); 2], messages);
+        assert_eq!(2, server.num_connections());
+        // far less than 5 unanswered pings of 5 seconds
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+
+    #[test]
+    fn reconnect_after_data_timeout() {
+        // pings are answered, but no data arrives on the hung connection
+        let mut server =
+            mock_exchange(vec![Frame::text(TRADE), Frame::Hang, Frame::text(TRADE)]).start();
+        let config = ConnectionConfig {
+            read_timeout: Some(Duration::from_secs(1)),
+            data_timeout: Some(Duration::from_secs(1)),
+            ..Default::default()
+        };
+        let mut messages = Vec::<String>::new();
+        {
+            let on_msg = Arc::new(Mutex::new(|msg: String| messages.push(msg)));
+            let ws_client = BitmexWSClient::new_with_options(
+                on_msg,
+                WSClientOptions::default()
+                    .endpoints(&[server.url()])
+                    .config(config),
+            );
+            ws_client.set_reconnect_policy(mock_server::fast_reconnect());
+            ws_client.subscribe(&["trade:XBTUSD".to_string()]);
+            ws_client.run(None);
+            ws_client.close();
+        }
+        server.join();
+        assert_eq!(vec![TRADE.to_string(); 2], messages);
+        assert_eq!(2, server.num_connections());
+    }
+
+    #[test]
+    fn subscription_states() {
+        let error = r#"{"status":400,"error":"Unknown or expired symbol. Please check the symbol and try again.","meta":{},"request":{"op":"subscribe","args":["trade:UNKNOWN"]}}"#;
+        let mut server = mock_exchange(vec![
+            Frame::text(ACK),
+            Frame::text(error),
+            Frame::text(TRADE),
+            Frame::Disconnect,
+            Frame::text(ACK),
+            Frame::text(TRADE),
+        ])
+        .start();
+        let mut messages = Vec::<String>::new();
+        let subscriptions = {
+            let on_msg = Arc::new(Mutex::new(|msg: String| messages.push(msg)));
+            let ws_client = BitmexWSClient::new(on_msg, Some(server.url()));
+            ws_client.set_reconnect_policy(mock_server::fast_reconnect());
+            ws_client.subscribe(&["trade:XBTUSD".to_string()]);
+            ws_client.subscribe(&["trade:UNKNOWN".to_string()]);
+            assert_eq!(
+                Some(&SubscriptionState::Pending),
+                ws_client.subscriptions().get("trade:XBTUSD")
+            );
+            ws_client.run(None);
+            ws_client.close();
+            ws_client.subscriptions()
+        };
+        server.join();
+
+        assert_eq!(vec![TRADE.to_string(); 2], messages);
+        assert_eq!(2, subscriptions.len());
+        assert_eq!(SubscriptionState::Confirmed, subscriptions["trade:XBTUSD"]);
+        assert_eq!(SubscriptionState::Rejected, subscriptions["trade:UNKNOWN"]);
+        // the rejected channel is not resubscribed after reconnecting
+        assert_eq!(
+            vec![
+                COMMAND.to_string(),
+                r#"{"op":"subscribe","args":["trade:UNKNOWN"]}"#.to_string(),
+                COMMAND.to_string()
+            ],
+            server.received()
+        );
+    }
+
+    #[test]
+    fn events() {
+        let error = r#"{"status":400,"error":"Unknown or expired symbol.","meta":{},"request":{"op":"subscribe","args":["trade:UNKNOWN"]}}"#;
+        let mut server = mock_exchange(vec![
+            Frame::text(ACK),
+            Frame::text(error),
+            Frame::text(TRADE),
+            Frame::Disconnect,
+            Frame::text(ACK),
+            Frame::text(TRADE),
+        ])
+        .start();
+        let mut events = Vec::<WsEvent>::new();
+        {
+            let on_event = Arc::new(Mutex::new(|event: WsEvent| events.push(event)));
+            let ws_client = BitmexWSClient::new_with_options(
+                Arc::new(Mutex::new(|_msg: String| {})),
+                WSClientOptions::default()
+                    .endpoints(&[server.url()])
+                    .events(on_event),
+            );
+            ws_client.set_reconnect_policy(mock_server::fast_reconnect());
+            ws_client.subscribe(&["trade:XBTUSD".to_string()]);
+            ws_client.subscribe(&["trade:UNKNOWN".to_string()]);
+            ws_client.run(None);
+            ws_client.close();
+        }
+        server.join();
+
+        let subscribed = WsEvent::Subscribed {
+            channel: "trade:XBTUSD".to_string(),
+        };
+        assert_eq!(7, events.len());
+        assert_eq!(
+            WsEvent::Connected {
+                url: server.url().to_string()
+            },
+            events[0]
+        );
+        assert_eq!(subscribed, events[1]);
+        assert_eq!(
+            WsEvent::SubscribeFailed {
+                channel: Some("trade:UNKNOWN".to_string()),
+                code: Some(400),
+                reason: "Unknown or expired symbol.".to_string()
+            },
+            events[2]
+        );
+        assert!(matches!(events[3], WsEvent::Disconnected { .. }));
+        assert!(matches!(&events[4], WsEvent::Reconnected(event) if event.num_channels == 1));
+        assert_eq!(subscribed, events[5]);
+        // the server stopped listening
+        assert!(matches!(events[6], WsEvent::Disconnected { .. }));
+    }
+
+    #[test]
+    fn unsubscribe() {
+        let mut server = mock_exchange(vec![Frame::text(ACK), Frame::text(TRADE)]).start();
+        let subscriptions = {
+            let on_msg = Arc::new(Mutex::new(|_msg: String| {}));
+            let ws_client = BitmexWSClient::new(on_msg, Some(server.url()));
+            ws_client.set_reconnect_policy(mock_server::fast_reconnect());
+            ws_client.subscribe(&["trade:XBTUSD".to_string()]);
+            ws_client.unsubscribe(&["trade:XBTUSD".to_string(), "trade:ETHUSD".to_string()]);
+            ws_client.run(None);
+            ws_client.close();
+            ws_client.subscriptions()
+        };
+        server.join();
+
+        assert!(subscriptions.is_empty());
+        // channels which were never subscribed are not unsubscribed
+        assert_eq!(
+            vec![
+                COMMAND.to_string(),
+                r#"{"op":"unsubscribe","args":["trade:XBTUSD"]}"#.to_string()
+            ],
+            server.received()
+        );
+    }
+
+    #[test]
+    fn typed_channels() {
+        let mut server = mock_exchange(vec![Frame::text(ACK), Frame::text(TRADE)]).start();
+        let mut messages = Vec::<String>::new();
+        {
+            let on_msg = Arc::new(Mutex::new(|msg: String| messages.push(msg)));
+            let ws_client = BitmexWSClient::new(on_msg, Some(server.url()));
+            ws_client.set_reconnect_policy(mock_server::fast_reconnect());
+
+            // BitMEX has no ticker channel, nothing is subscribed
+            let err = ws_client
+                .subscribe_channels(&[
+                    Channel::Trade("XBTUSD".to_string()),
+                    Channel::Ticker("XBTUSD".to_string()),
+                ])
+                .unwrap_err();
+            assert_eq!(
+                WsError::Unsupported {
+                    exchange: "bitmex".to_string(),
+                    channel: Channel::Ticker("XBTUSD".to_string()),
+                },
+                err
+            );
+            assert!(ws_client.subscriptions().is_empty());
+            assert_eq!(
+                Err(WsError::InvalidInterval {
+                    exchange: "bitmex".to_string(),
+                    interval: 7,
+                }),
+                ws_client.subscribe_candlestick(&["XBTUSD".to_string()], 7)
+            );
+            assert_eq!(
+                Err(WsError::InvalidSymbol {
+                    exchange: "bitmex".to_string(),
+                    symbol: "".to_string(),
+                }),
+                ws_client.subscribe_trade(&["".to_string()])
+            );
+            assert!(!ws_client.capabilities().contains(&ChannelKind::Ticker));
+            assert!(ws_client.capabilities().contains(&ChannelKind::Kline));
+
+            assert_eq!(
+                Ok(vec![
+                    "trade:XBTUSD".to_string(),
+                    "orderBookL2_25:XBTUSD".to_string(),
+                    "tradeBin1m:XBTUSD".to_string(),
+                    "instrument".to_string(),
+                ]),
+                ws_client.translate_channels(&[
+                    Channel::Trade("XBTUSD".to_string()),
+                    Channel::l2("XBTUSD").depth(25),
+                    Channel::kline("XBTUSD", 60),
+                    Channel::Raw("instrument".to_string()),
+                ])
+            );
+            ws_client
+                .subscribe_channels(&[Channel::Trade("XBTUSD".to_string())])
+                .unwrap();
+            ws_client.run(None);
+            ws_client.close();
+        }
+        server.join();
+        assert_eq!(vec![TRADE.to_string()], messages);
+        assert_eq!(vec![COMMAND.to_string()], server.received());
+    }
+}
+
+#[cfg(test)]
+mod bitstamp {
+    use super::*;
+
+    #[test]
+    fn live_trades() {
+        const TRADE: &str = r#"{"data":{"buy_order_id":1347385936494592,"amount":0.01,"price":52000.0,"timestamp":"1616651250","microtimestamp":"1616651250071000","id":161234567,"type":0,"sell_order_id":1347385928286208},"channel":"live_trades_btcusd","event":"trade"}"#;
+        let mut server = MockExchange::new(vec![
+            Frame::text(
+                r#"{"event":"bts:subscription_succeeded","channel":"live_trades_btcusd","data":{}}"#,
+            ),
+            Frame::text(TRADE),
+        ])
+        .start();
+        let messages = run_offline!(
+            BitstampWSClient,
+            server,
+            &["live_trades_btcusd".to_string()]
+        );
+        server.join();
+        assert_eq!(vec![TRADE.to_string()], messages);
+    }
+}
+
+#[cfg(test)]
+mod bitz {
+    use super::*;
+
+    #[test]
+    fn spot_gzip() {
+        const TRADE: &str = r#"{"msgId":0,"params":{"symbol":"btc_usdt"},"action":"Pushdata.order","data":[{"id":"1593187447","t":"13:47:30","T":1616651250,"p":"52000.01","n":"0.0100","s":"buy"}],"time":1616651250071,"source":"sub-api"}"#;
+        let mut server = MockExchange::new(vec![Frame::gzip(TRADE)])
+            .pong("ping", "pong")
+            .start();
+        let messages = run_offline!(BitzSpotWSClient, server, &["order:btc_usdt".to_string()]);
+        server.join();
+        assert_eq!(vec![TRADE.to_string()], messages);
+    }
+}
+
+#[cfg(test)]
+mod bybit {
+    use super::*;
+
+    const ACK: &str = r#"{"success":true,"ret_msg":"","conn_id":"d3b4c3a4-0f5c-4d8b-a8c4-b9d8c0e7a0b1","request":{"op":"subscribe","args":["trade.BTCUSD"]}}"#;
+    const TRADE: &str = r#"{"topic":"trade.BTCUSD","data":[{"trade_time_ms":1616651250071,"timestamp":"2021-03-25T05:47:30.000Z","symbol":"BTCUSD","side":"Buy","size":100,"price":52000.5,"tick_direction":"PlusTick","trade_id":"8a6d1a1c-7b3a-5c4d-9f2e-4d5b6a7c8e9f","cross_seq":5617329341}]}"#;
+
+    fn mock_exchange() -> MockExchange {
+        MockExchange::new(vec![Frame::text(ACK), Frame::text(TRADE)]).pong(
+            r#"{"op":"ping"}"#,
+            r#"{"success":true,"ret_msg":"pong","conn_id":"d3b4c3a4-0f5c-4d8b-a8c4-b9d8c0e7a0b1","request":{"op":"ping","args":null}}"#,
+        )
+    }
+
+    #[test]
+    fn inverse_future() {
+        let mut server = mock_exchange().start();
+        let messages = run_offline!(
+            BybitInverseFutureWSClient,
+            server,
+            &["trade.BTCUSD".to_string()]
+        );
+        server.join();
+        assert_eq!(vec![TRADE.to_string()], messages);
+    }
+
+    #[test]
+    fn inverse_swap() {
+        let mut server = mock_exchange().start();
+        let messages = run_offline!(
+            BybitInverseSwapWSClient,
+            server,
+            &["trade.BTCUSD".to_string()]
+        );
+        server.join();
+        assert_eq!(vec![TRADE.to_string()], messages);
+    }
+
+    #[test]
+    fn linear_swap() {
+        let mut server = mock_exchange().start();
+        let messages = run_offline!(
+            BybitLinearSwapWSClient,
+            server,
+            &["trade.BTCUSD".to_string()]
+        );
+        server.join();
+        assert_eq!(vec![TRADE.to_string()], messages);
+    }
+
+    #[test]
+    fn spot() {
+        const SPOT_TRADE: &str = r#"{"symbol":"BTCUSDT","symbolName":"BTCUSDT","topic":"trade","params":{"realtimeInterval":"24h","binary":"false"},"data":[{"v":"2290000000008144071","t":1616651250071,"p":"52000.01","q":"0.001","m":true}],"f":false,"sendTime":1616651250090}"#;
+        let mut server = MockExchange::new(vec![
+            Frame::text(
+                r#"{"topic":"trade","event":"sub","symbol":"BTCUSDT","params":{"binary":"false","symbolName":"BTCUSDT"},"code":"0","msg":"Success"}"#,
+            ),
+            Frame::text(SPOT_TRADE),
+        ])
+        .pong(r#"{"ping":1535975085052}"#, r#"{"pong":1535975085052}"#)
+        .start();
+        let messages = run_offline!(BybitSpotWSClient, server, &["trade.BTCUSDT".to_string()]);
+        server.join();
+        assert_eq!(vec![SPOT_TRADE.to_string()], messages);
+    }
+}
+
+#[cfg(test)]
+mod coinbase_pro {
+    use super::*;
+
+    #[test]
+    fn matches() {
+        const MATCH: &str = r#"{"type":"match","trade_id":151562712,"maker_order_id":"5b2b4c3a-1f0e-4d8b-a8c4-b9d8c0e7a0b1","taker_order_id":"6c3c5d4b-2a1f-5e9c-b9d5-c0e9d1f8b1c2","side":"buy","size":"0.01000000","price":"52000.01","product_id":"BTC-USD","sequence":23055208963,"time":"2021-03-25T05:47:30.071000Z"}"#;
+        let mut server = MockExchange::new(vec![
+            Frame::text(
+                r#"{"type":"subscriptions","channels":[{"name":"matches","product_ids":["BTC-USD"]}]}"#,
+            ),
+            Frame::text(MATCH),
+        ])
+        .start();
+        let messages = run_offline!(
+            CoinbaseProWSClient,
+            server,
+            &["matches:BTC-USD".to_string()]
+        );
+        server.join();
+        assert_eq!(vec![MATCH.to_string()], messages);
+    }
+}
+
+#[cfg(test)]
+mod deribit {
+    use super::*;
+
+    #[test]
+    fn trades() {
+        const TRADE: &str = r#"{"jsonrpc":"2.0","method":"subscription","params":{"channel":"trades.BTC-PERPETUAL.raw","data":[{"trade_seq":71279461,"trade_id":"114123456","timestamp":1616651250071,"tick_direction":0,"price":52000.5,"mark_price":52001.2,"instrument_name":"BTC-PERPETUAL","index_price":51998.7,"direction":"buy","amount":100.0}]}}"#;
+        let mut server = MockExchange::new(vec![
+            Frame::text(r#"{"jsonrpc":"2.0","result":["trades.BTC-PERPETUAL.raw"]}"#),
+            Frame::text(r#"{"jsonrpc":"2.0","result":"ok"}"#),
+            Frame::text(TRADE),
+        ])
+        .start();
+        let messages = run_offline!(
+            DeribitWSClient,
+            server,
+            &["trades.BTC-PERPETUAL.raw".to_string()]
+        );
+        server.join();
+        assert_eq!(vec![TRADE.to_string()], messages);
+    }
+}
+
+#[cfg(test)]
+mod ftx {
+    use super::*;
+
+    #[test]
+    fn trades() {
+        const TRADE: &str = r#"{"channel":"trades","market":"BTC-PERP","type":"update","data":[{"id":1031045561,"price":52000.0,"size":0.01,"side":"buy","liquidation":false,"time":"2021-03-25T05:47:30.071000+00:00"}]}"#;
+        let mut server = MockExchange::new(vec![
+            Frame::text(r#"{"type":"subscribed","channel":"trades","market":"BTC-PERP"}"#),
+            Frame::text(TRADE),
+        ])
+        .pong(r#"{"op":"ping"}"#, r#"{"type":"pong"}"#)
+        .start();
+        let messages = run_offline!(FtxWSClient, server, &["trades:BTC-PERP".to_string()]);
+        server.join();
+        assert_eq!(vec![TRADE.to_string()], messages);
+    }
+}
+
+#[cfg(test)]
+mod gate {
+    use super::*;
+
+    const FUTURES_ACK: &str = r#"{"time":1616651250,"channel":"futures.trades","event":"subscribe","error":null,"result":{"status":"success"}}"#;
+    const FUTURES_TRADE: &str = r#"{"time":1616651250,"channel":"futures.trades","event":"update","error":null,"result":[{"size":-10,"id":21483789,"create_time":1616651250,"create_time_ms":1616651250071,"price":"52000.5","contract":"BTC_USDT"}]}"#;
+
+    fn futures_exchange() -> MockExchange {
+        MockExchange::new(vec![Frame::text(FUTURES_ACK), Frame::text(FUTURES_TRADE)]).pong(
+            r#"{"channel":"futures.ping"}"#,
+            r#"{"time":1616651250,"channel":"futures.pong","event":"","error":null,"result":null}"#,
+        )
+    }
+
+    #[test]
+    fn spot() {
+        const TRADE: &str = r#"{"method":"trades.update","params":["BTC_USDT",[{"id":1616651250,"time":1616651250.071,"price":"52000.01","amount":"0.01","type":"buy"}]],"id":null}"#;
+        let mut server = MockExchange::new(vec![
+            Frame::text(r#"{"error":null,"result":{"status":"success"},"id":9527}"#),
+            Frame::text(TRADE),
+        ])
+        .pong(
+            r#"{"id":9527,"method":"server.ping", "params":[]}"#,
+            r#"{"error":null,"result":"pong","id":9527}"#,
+        )
+        .start();
+        let messages = run_offline!(GateSpotWSClient, server, &["trades:BTC_USDT".to_string()]);
+        server.join();
+        assert_eq!(vec![TRADE.to_string()], messages);
+    }
+
+    #[test]
+    fn linear_future() {
+        let mut server = futures_exchange().start();
+        let messages = run_offline!(
+            GateLinearFutureWSClient,
+            server,
+            &["trades:BTC_USDT_20210625".to_string()]
+        );
+        server.join();
+        assert_eq!(vec![FUTURES_TRADE.to_string()], messages);
+    }
+
+    #[test]
+    fn inverse_swap() {
+        let mut server = futures_exchange().start();
+        let messages = run_offline!(
+            GateInverseSwapWSClient,
+            server,
+            &["trades:BTC_USD".to_string()]
+        );
+        server.join();
+        assert_eq!(vec![FUTURES_TRADE.to_string()], messages);
+    }
+
+    #[test]
+    fn linear_swap() {
+        let mut server = futures_exchange().start();
+        let messages = run_offline!(
+            GateLinearSwapWSClient,
+            server,
+            &["trades:BTC_USDT".to_string()]
+        );
+        server.join();
+        assert_eq!(vec![FUTURES_TRADE.to_string()], messages);
+    }
+}
+
+#[cfg(test)]
+mod gemini {
+    use super::*;
+
+    #[test]
+    fn l2() {
+        const SNAPSHOT: &str = r#"{"type":"l2_updates","symbol":"BTCUSD","changes":[["buy","52000.01","0.5"],["sell","52000.02","0.25"]],"trades":[{"type":"trade","symbol":"BTCUSD","event_id":1616651250071,"timestamp":1616651250071,"price":"52000.01","quantity":"0.01","side":"sell"}]}"#;
+        const TRADE: &str = r#"{"type":"trade","symbol":"BTCUSD","event_id":1616651250072,"timestamp":1616651250072,"price":"52000.02","quantity":"0.02","side":"buy"}"#;
+        let mut server = MockExchange::new(vec![
+            Frame::text(SNAPSHOT),
+            Frame::text(r#"{"type":"heartbeat","timestamp":1616651250}"#),
+            Frame::text(TRADE),
+        ])
+        .start();
+        let messages = run_offline!(GeminiWSClient, server, &["l2:BTCUSD".to_string()]);
+        server.join();
+        assert_eq!(vec![SNAPSHOT.to_string(), TRADE.to_string()], messages);
+        assert_eq!(
+            vec![
+                r#"{"type":"subscribe","subscriptions":[{"name":"l2","symbols":["BTCUSD"]}]}"#
+                    .to_string()
+            ],
+            server.received()
+        );
+    }
+}
+
+#[cfg(test)]
+mod huobi {
+    use super::*;
+
+    const TRADE: &str = r#"{"ch":"market.btcusdt.trade.detail","ts":1616651250071,"tick":{"id":123140716701,"ts":1616651250070,"data":[{"id":123140716701236887569076664,"ts":1616651250070,"tradeId":102211963580,"amount":0.01,"price":52000.01,"direction":"buy"}]}}"#;
+    const PING: &str = r#"{"ping":1616651250071}"#;
+
+    fn ack(topic: &str) -> Frame {
+        Frame::gzip(&format!(
+            r#"{{"id":"crypto-ws-client","status":"ok","subbed":"{}","ts":1616651250071}}"#,
+            topic
+        ))
+    }
+
+    fn replace_topic(topic: &str) -> Frame {
+        Frame::gzip(&TRADE.replace("market.btcusdt.trade.detail", topic))
+    }
+
+    #[test]
+    fn spot() {
+        let mut server = MockExchange::new(vec![
+            ack("market.btcusdt.trade.detail"),
+            Frame::gzip(PING),
+            Frame::gzip(TRADE),
+        ])
+        .start();
+        let messages = run_offline!(
+            HuobiSpotWSClient,
+            server,
+            &["market.btcusdt.trade.detail".to_string()]
+        );
+        server.join();
+        assert_eq!(vec![TRADE.to_string()], messages);
+        // the server ping was answered
+        assert!(server
+            .received()
+            .contains(&r#"{"pong":1616651250071}"#.to_string()));
+    }
+
+    #[test]
+    fn spot_resubscribe_and_skip_corrupted_frames() {
+        let mut server = MockExchange::new(vec![
+            Frame::Binary(vec![0x1f, 0x8b, 0x08, 0x00, 0xde, 0xad, 0xbe, 0xef]),
+            Frame::gzip(TRADE),
+            Frame::Disconnect,
+            Frame::Binary(b"not gzip".to_vec()),
+            Frame::gzip(TRADE),
+        ])
+        .start();
+        let messages = run_offline!(
+            HuobiSpotWSClient,
+            server,
+            &["market.btcusdt.trade.detail".to_string()]
+        );
+        server.join();
+        assert_eq!(vec![TRADE.to_string(); 2], messages);
+        assert_eq!(2, server.num_connections());
+        assert_eq!(
+            vec![r#"{"sub":"market.btcusdt.trade.detail","id":"crypto-ws-client"}"#.to_string(); 2],
+            server.received()
+        );
+    }
+
+    #[test]
+    fn future() {
+        let topic = "market.BTC_CQ.trade.detail";
+        let mut server = MockExchange::new(vec![ack(topic), replace_topic(topic)]).start();
+        let messages = run_offline!(HuobiFutureWSClient, server, &[topic.to_string()]);
+        server.join();
+        assert_eq!(
+            vec![TRADE.replace("market.btcusdt.trade.detail", topic)],
+            messages
+        );
+    }
+
+    #[test]
+    fn inverse_swap() {
+        let topic = "market.BTC-USD.trade.detail";
+        let mut server = MockExchange::new(vec![ack(topic), replace_topic(topic)]).start();
+        let messages = run_offline!(HuobiInverseSwapWSClient, server, &[topic.to_string()]);
+        server.join();
+        assert_eq!(1, messages.len());
+    }
+
+    #[test]
+    fn linear_swap() {
+        let topic = "market.BTC-USDT.trade.detail";
+        let mut server = MockExchange::new(vec![ack(topic), replace_topic(topic)]).start();
+        let messages = run_offline!(HuobiLinearSwapWSClient, server, &[topic.to_string()]);
+        server.join();
+        assert_eq!(1, messages.len());
+    }
+
+    #[test]
+    fn option() {
+        let topic = "market.BTC-USDT-210326-C-32000.trade.detail";
+        let mut server = MockExchange::new(vec![ack(topic), replace_topic(topic)]).start();
+        let messages = run_offline!(HuobiOptionWSClient, server, &[topic.to_string()]);
+        server.join();
+        assert_eq!(1, messages.len());
+    }
+}
+
+#[cfg(test)]
+mod kraken {
+    use super::*;
+
+    #[test]
+    fn trade() {
+        const TRADE: &str = r#"[0,[["52000.00000","0.01000000","1616651250.071234","b","m",""]],"trade","XBT/USD"]"#;
+        let mut server = MockExchange::new(vec![
+            Frame::text(
+                r#"{"channelID":0,"channelName":"trade","event":"subscriptionStatus","pair":"XBT/USD","status":"subscribed","subscription":{"name":"trade"}}"#,
+            ),
+            Frame::text(r#"{"event":"heartbeat"}"#),
+            Frame::text(TRADE),
+        ])
+        .welcome(vec![Frame::text(
+            r#"{"connectionID":8628615390848610222,"event":"systemStatus","status":"online","version":"1.8.1"}"#,
+        )])
+        .pong(r#"{"event":"ping"}"#, r#"{"event":"pong"}"#)
+        .start();
+        let messages = run_offline!(KrakenWSClient, server, &["trade:XBT/USD".to_string()]);
+        server.join();
+        assert_eq!(vec![TRADE.to_string()], messages);
+    }
+
+    #[test]
+    fn futures() {
+        const TRADE: &str = r#"{"feed":"trade","product_id":"PI_XBTUSD","uid":"05af78ac-a774-478c-a50c-8b9c234e071e","side":"sell","type":"fill","seq":653355,"time":1616651250071,"qty":5000.0,"price":52000.5}"#;
+        let mut server = MockExchange::new(vec![
+            Frame::text(r#"{"event":"subscribed","feed":"trade","product_ids":["PI_XBTUSD"]}"#),
+            Frame::text(r#"{"feed":"heartbeat","time":1616651250000}"#),
+            Frame::text(TRADE),
+        ])
+        .welcome(vec![Frame::text(r#"{"event":"info","version":1}"#)])
+        .start();
+        let messages = run_offline!(
+            KrakenFuturesWSClient,
+            server,
+            &["trade:PI_XBTUSD".to_string()]
+        );
+        server.join();
+        assert_eq!(vec![TRADE.to_string()], messages);
+    }
+}
+
+#[cfg(test)]
+mod kucoin {
+    use super::*;
+
+    const WELCOME: &str = r#"{"id":"hQvf8jkno","type":"welcome"}"#;
+    const ACK: &str = r#"{"id":"crypto-ws-client","type":"ack"}"#;
+
+    #[test]
+    fn spot() {
+        const TRADE: &str = r#"{"type":"message","topic":"/market/match:BTC-USDT","subject":"trade.l3match","data":{"sequence":"1614558431926","symbol":"BTC-USDT","side":"buy","size":"0.01","price":"52000.01","takerOrderId":"605c2462cf6b4f0006a3b0e1","time":"1616651250071000000","type":"match","makerOrderId":"605c245e76e8e30006a0cc52","tradeId":"605c2462ebc0a4f3b0e2b1c3"}}"#;
+        let mut server = MockExchange::new(vec![Frame::text(ACK), Frame::text(TRADE)])
+            .welcome(vec![Frame::text(WELCOME)])
+            .start();
+        // The url of a mock server needs no token
+        let messages = run_offline!(
+            KuCoinSpotWSClient,
+            server,
+            &["/market/match:BTC-USDT".to_string()]
+        );
+        server.join();
+        assert_eq!(vec![TRADE.to_string()], messages);
+    }
+
+    #[test]
+    fn swap() {
+        const TRADE: &str = r#"{"type":"message","topic":"/contractMarket/execution:XBTUSDM","subject":"match","data":{"symbol":"XBTUSDM","sequence":1616651250,"side":"buy","matchSize":100,"size":100,"price":52000,"takerOrderId":"605c2462cf6b4f0006a3b0e1","ts":1616651250071000000,"makerOrderId":"605c245e76e8e30006a0cc52","tradeId":"605c2462ebc0a4f3b0e2b1c3"}}"#;
+        let mut server = MockExchange::new(vec![Frame::text(ACK), Frame::text(TRADE)])
+            .welcome(vec![Frame::text(WELCOME)])
+            .start();
+        let messages = run_offline!(
+            KuCoinSwapWSClient,
+            server,
+            &["/contractMarket/execution:XBTUSDM".to_string()]
+        );
+        server.join();
+        assert_eq!(vec![TRADE.to_string()], messages);
+    }
+}
+
+#[cfg(test)]
+mod mxc {
+    use super::*;
+
+    #[test]
+    fn spot_socketio() {
+        const SYMBOL: &str = r#"["push.symbol",{"symbol":"BTC_USDT","data":{"deals":[{"t":1616651250071,"p":"52000.01","q":"0.010000","T":1}]}}]"#;
+        let mut server = MockExchange::new(vec![Frame::text(&format!("42{}", SYMBOL))])
+            .welcome(vec![
+                Frame::text(
+                    r#"0{"sid":"nP9hGrAJt8sgzYFHAAVx","upgrades":[],"pingInterval":25000,"pingTimeout":60000}"#,
+                ),
+                Frame::text("40"),
+            ])
+            .pong("2", "3")
+            .start();
+        let messages = run_offline!(MxcSpotWSClient, server, &["symbol:BTC_USDT".to_string()]);
+        server.join();
+        // the socket.io prefix is removed
+        assert_eq!(vec![SYMBOL.to_string()], messages);
+        assert_eq!(
+            vec![r#"42["sub.symbol",{"symbol":"BTC_USDT"}]"#.to_string()],
+            server.received()
+        );
+    }
+
+    #[test]
+    fn swap() {
+        const DEAL: &str = r#"{"channel":"push.deal","data":{"M":1,"O":1,"T":1,"p":52000.5,"t":1616651250071,"v":10},"symbol":"BTC_USDT","ts":1616651250071}"#;
+        let mut server = MockExchange::new(vec![
+            Frame::text(r#"{"channel":"rs.sub.deal","data":"success","ts":1616651250071}"#),
+            Frame::text(DEAL),
+        ])
+        .pong(
+            r#"{"method":"ping"}"#,
+            r#"{"channel":"pong","data":1616651250071,"ts":1616651250071}"#,
+        )
+        .start();
+        let messages = run_offline!(MxcSwapWSClient, server, &["deal:BTC_USDT".to_string()]);
+        server.join();
+        assert_eq!(vec![DEAL.to_string()], messages);
+    }
+}
+
+#[cfg(test)]
+mod okex {
+    use super::*;
+
+    const ACK: &str = r#"{"event":"subscribe","channel":"spot/trade:BTC-USDT"}"#;
+    const TRADE: &str = r#"{"table":"spot/trade","data":[{"side":"buy","trade_id":"228465128","price":"52000.1","size":"0.01","instrument_id":"BTC-USDT","timestamp":"2021-03-25T05:47:30.071Z"}]}"#;
+
+    #[test]
+    fn spot_deflate() {
+        let mut server = MockExchange::new(vec![Frame::deflate(ACK), Frame::deflate(TRADE)])
+            .pong("ping", "pong")
+            .start();
+        let messages = run_offline!(OkexWSClient, server, &["spot/trade:BTC-USDT".to_string()]);
+        server.join();
+        assert_eq!(vec![TRADE.to_string()], messages);
+        assert_eq!(
+            vec![r#"{"op":"subscribe","args":["spot/trade:BTC-USDT"]}"#.to_string()],
+            server.received()
+        );
+    }
+
+    #[test]
+    fn derivative_channels() {
+        const MARK_PRICE_ACK: &str =
+            r#"{"event":"subscribe","channel":"swap/mark_price:BTC-USD-SWAP"}"#;
+        const MARK_PRICE: &str = r#"{"table":"swap/mark_price","data":[{"instrument_id":"BTC-USD-SWAP","mark_price":"52000.5","timestamp":"2021-03-25T05:47:30.071Z"}]}"#;
+        let mut server = MockExchange::new(vec![
+            Frame::deflate(MARK_PRICE_ACK),
+            Frame::deflate(MARK_PRICE),
+        ])
+        .pong("ping", "pong")
+        .start();
+        let mut messages = Vec::<String>::new();
+        {
+            let on_msg = Arc::new(Mutex::new(|msg: String| messages.push(msg)));
+            let ws_client = OkexWSClient::new(on_msg, Some(server.url()));
+            ws_client.set_reconnect_policy(mock_server::fast_reconnect());
+
+            assert_eq!(
+                Ok(vec![
+                    "futures/mark_price:BTC-USD-210625".to_string(),
+                    "index/ticker:BTC-USD".to_string(),
+                    "index/ticker:BTC-USDT".to_string(),
+                ]),
+                ws_client.translate_channels(&[
+                    Channel::MarkPrice("BTC-USD-210625".to_string()),
+                    Channel::IndexPrice("BTC-USD-SWAP".to_string()),
+                    Channel::IndexPrice("BTC-USDT".to_string()),
+                ])
+            );
+            // Spot markets have no mark prices
+            assert_eq!(
+                Err(WsError::Unsupported {
+                    exchange: "okex".to_string(),
+                    channel: Channel::MarkPrice("BTC-USDT".to_string()),
+                }),
+                ws_client.subscribe_mark_price(&["BTC-USDT".to_string()])
+            );
+            assert!(!ws_client.capabilities().contains(&ChannelKind::Liquidation));
+            ws_client
+                .subscribe_mark_price(&["BTC-USD-SWAP".to_string()])
+                .unwrap();
+            ws_client.run(None);
+            ws_client.close();
+        }
+        server.join();
+        assert_eq!(vec![MARK_PRICE.to_string()], messages);
+        assert_eq!(
+            vec![r#"{"op":"subscribe","args":["swap/mark_price:BTC-USD-SWAP"]}"#.to_string()],
+            server.received()
+        );
+    }
+
+    #[test]
+    fn resubscribe_and_skip_corrupted_frames() {
+        let mut server = MockExchange::new(vec![
+            Frame::deflate(ACK),
+            Frame::Binary(vec![0xff, 0xff, 0xff, 0xff]),
+            Frame::deflate(TRADE),
+            Frame::Disconnect,
+            Frame::deflate(ACK),
+            Frame::deflate(TRADE),
+        ])
+        .pong("ping", "pong")
+        .start();
+        let messages = run_offline!(OkexWSClient, server, &["spot/trade:BTC-USDT".to_string()]);
+        server.join();
+        assert_eq!(vec![TRADE.to_string(); 2], messages);
+        assert_eq!(2, server.num_connections());
+        assert_eq!(2, server.received().len());
+    }
+
+    #[test]
+    fn reject_nonexistent_channel() {
+        let error = r#"{"event":"error","message":"Channel spot/trade:BTC-USDT1 doesn't exist","errorCode":30040}"#;
+        let mut server = MockExchange::new(vec![
+            Frame::deflate(ACK),
+            Frame::deflate(error),
+            Frame::deflate(TRADE),
+        ])
+        .pong("ping", "pong")
+        .start();
+        let mut events = Vec::<WsEvent>::new();
+        let subscriptions = {
+            let on_event = Arc::new(Mutex::new(|event: WsEvent| events.push(event)));
+            let ws_client = OkexWSClient::new_with_options(
+                Arc::new(Mutex::new(|_msg: String| {})),
+                WSClientOptions::default()
+                    .endpoints(&[server.url()])
+                    .events(on_event),
+            );
+            ws_client.set_reconnect_policy(mock_server::fast_reconnect());
+            ws_client.subscribe(&[
+                "spot/trade:BTC-USDT".to_string(),
+                "spot/trade:BTC-USDT1".to_string(),
+            ]);
+            ws_client.run(None);
+            ws_client.close();
+            ws_client.subscriptions()
+        };
+        server.join();
+
+        assert_eq!(
+            SubscriptionState::Confirmed,
+            subscriptions["spot/trade:BTC-USDT"]
+        );
+        assert_eq!(
+            SubscriptionState::Rejected,
+            subscriptions["spot/trade:BTC-USDT1"]
+        );
+        assert!(events.contains(&WsEvent::SubscribeFailed {
+            channel: Some("spot/trade:BTC-USDT1".to_string()),
+            code: Some(30040),
+            reason: "Channel spot/trade:BTC-USDT1 doesn't exist".to_string()
+        }));
+    }
+}
+
+#[cfg(test)]
+mod poloniex {
+    use super::*;
+
+    #[test]
+    fn trades() {
+        const TRADE: &str = r#"{"channel":"trades","data":[{"symbol":"BTC_USDT","amount":"520.0001","takerSide":"buy","quantity":"0.01","createTime":1616651250070,"price":"52000.01","id":60014521,"ts":1616651250071}]}"#;
+        let mut server = MockExchange::new(vec![
+            Frame::text(r#"{"event":"subscribe","channel":"trades","symbols":["BTC_USDT"]}"#),
+            Frame::text(TRADE),
+        ])
+        .pong(r#"{"event":"ping"}"#, r#"{"event":"pong"}"#)
+        .start();
+        let messages = run_offline!(PoloniexWSClient, server, &["trades:BTC_USDT".to_string()]);
+        server.join();
+        assert_eq!(vec![TRADE.to_string()], messages);
+    }
+}
+
+#[cfg(test)]
+mod upbit {
+    use super::*;
+
+    // Upbit sends uncompressed JSON in binary frames
+    #[test]
+    fn trade_binary() {
+        const TRADE: &str = r#"{"type":"trade","code":"KRW-BTC","timestamp":1616651250071,"trade_date":"2021-03-25","trade_time":"05:47:30","trade_timestamp":1616651250070,"trade_price":62000000.0,"trade_volume":0.01,"ask_bid":"BID","prev_closing_price":61500000.0,"change":"RISE","change_price":500000.0,"sequential_id":1616651250070000,"stream_type":"REALTIME"}"#;
+        let mut server = MockExchange::new(vec![Frame::Binary(TRADE.as_bytes().to_vec())]).start();
+        let messages = run_offline!(UpbitWSClient, server, &["trade:KRW-BTC".to_string()]);
+        server.join();
+        assert_eq!(vec![TRADE.to_string()], messages);
+        assert_eq!(
+            vec![
+                r#"[{"ticket":"crypto-ws-client"},{"type":"trade","codes":["KRW-BTC"]}]"#
+                    .to_string()
+            ],
+            server.received()
+        );
+    }
+}
+
+#[cfg(test)]
+mod zbg {
+    use super::*;
+
+    #[test]
+    fn spot() {
+        const TRADE: &str = r#"["T","329","1616651250","BTC_USDT","bid","52000.01","0.0100"]"#;
+        let mut server = MockExchange::new(vec![Frame::text(TRADE)]).start();
+        let messages = run_offline!(ZbgSpotWSClient, server, &["329_TRADE_BTC_USDT".to_string()]);
+        server.join();
+        assert_eq!(vec![TRADE.to_string()], messages);
+    }
+
+    #[test]
+    fn swap() {
+        const TICK: &str = r#"["future_tick",{"contractId":1000000,"trades":["1616651250071000","52000.5","10","1"]}]"#;
+        let mut server = MockExchange::new(vec![Frame::text(TICK)])
+            .pong("PING", "Pong")
+            .start();
+        let messages = run_offline!(
+            ZbgSwapWSClient,
+            server,
+            &["future_tick-1000000".to_string()]
+        );
+        server.join();
+        assert_eq!(vec![TICK.to_string()], messages);
+    }
+}
+
+#[cfg(test)]
+mod recorder {
+    use super::*;
+
+    const TRADE: &str = r#"{"msgId":0,"params":{"symbol":"btc_usdt"},"action":"Pushdata.order","data":[{"id":"1593187447","t":"13:47:30","T":1616651250,"p":"52000.01","n":"0.0100","s":"buy"}],"time":1616651250071,"source":"sub-api"}"#;
+
+    #[test]
+    fn record_and_replay() {
+        let path =
+            std::env::temp_dir().join(format!("record_and_replay_{}.log", std::process::id()));
+
+        let mut server = MockExchange::new(vec![Frame::gzip(TRADE)])
+            .pong("ping", "pong")
+            .start();
+        let mut recorded = Vec::<String>::new();
+        {
+            let on_msg = Arc::new(Mutex::new(|msg: String| recorded.push(msg)));
+            let ws_client = BitzSpotWSClient::new(on_msg, Some(server.url()));
+            ws_client.set_reconnect_policy(mock_server::fast_reconnect());
+            ws_client.set_recorder(Recorder::create(&path).unwrap());
+            ws_client.subscribe(&["order:btc_usdt".to_string()]);
+            ws_client.run(None);
+            ws_client.close();
+        }
+        server.join();
+        assert_eq!(vec![TRADE.to_string()], recorded);
+
+        let replayer = Replayer::open(&path).unwrap().speed(f64::INFINITY);
+        assert_eq!(Some(server.url()), replayer.url());
+        // the trade is recorded compressed, as received
+        assert!(replayer
+            .records()
+            .iter()
+            .any(|record| record.direction == Direction::Inbound
+                && matches!(record.frame, RecordedFrame::Binary(_))));
+        assert!(replayer
+            .records()
+            .iter()
+            .any(|record| record.direction == Direction::Outbound
+                && matches!(&record.frame, RecordedFrame::Text(txt) if txt.contains("order"))));
+
+        let mut replayed = Vec::<String>::new();
+        BitzSpotWSClient::replay(
+            Arc::new(Mutex::new(|msg: String| replayed.push(msg))),
+            &replayer,
+        );
+        assert_eq!(recorded, replayed);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
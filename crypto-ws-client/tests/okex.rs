@@ -155,6 +155,24 @@ mod okex_swap {
         );
     }
 
+    #[test]
+    fn subscribe_mark_price() {
+        gen_test_code!(
+            OkexWSClient,
+            subscribe_mark_price,
+            &["BTC-USDT-SWAP".to_string()]
+        );
+    }
+
+    #[test]
+    fn subscribe_index_price() {
+        gen_test_code!(
+            OkexWSClient,
+            subscribe_index_price,
+            &["BTC-USDT-SWAP".to_string()]
+        );
+    }
+
     #[test]
     fn subscribe_ticker() {
         gen_test_code!(
@@ -0,0 +1,68 @@
+use crypto_ws_client::{PoloniexWSClient, WSClient};
+use std::sync::{Arc, Mutex};
+
+#[macro_use]
+mod utils;
+
+#[test]
+fn subscribe() {
+    gen_test_code!(
+        PoloniexWSClient,
+        subscribe,
+        &["trades:BTC_USDT".to_string(), "trades:ETH_USDT".to_string()]
+    );
+}
+
+#[test]
+fn subscribe_raw_json() {
+    gen_test_code!(
+        PoloniexWSClient,
+        subscribe,
+        &[
+            r#"{"event":"subscribe","channel":["trades"],"symbols":["BTC_USDT","ETH_USDT"]}"#
+                .to_string()
+        ]
+    );
+}
+
+#[test]
+fn subscribe_trade() {
+    gen_test_code!(
+        PoloniexWSClient,
+        subscribe_trade,
+        &["BTC_USDT".to_string(), "ETH_USDT".to_string()]
+    );
+}
+
+#[test]
+fn subscribe_orderbook() {
+    gen_test_code!(
+        PoloniexWSClient,
+        subscribe_orderbook,
+        &["BTC_USDT".to_string(), "ETH_USDT".to_string()]
+    );
+}
+
+#[test]
+fn subscribe_orderbook_snapshot() {
+    gen_test_code!(
+        PoloniexWSClient,
+        subscribe_orderbook_snapshot,
+        &["BTC_USDT".to_string(), "ETH_USDT".to_string()]
+    );
+}
+
+#[test]
+fn subscribe_ticker() {
+    gen_test_code!(
+        PoloniexWSClient,
+        subscribe_ticker,
+        &["BTC_USDT".to_string(), "ETH_USDT".to_string()]
+    );
+}
+
+#[test]
+fn subscribe_candlestick() {
+    gen_test_subscribe_candlestick!(PoloniexWSClient, &["BTC_USDT".to_string()], 60);
+    gen_test_subscribe_candlestick!(PoloniexWSClient, &["BTC_USDT".to_string()], 2592000);
+}
@@ -0,0 +1,256 @@
+use crypto_ws_client::*;
+use std::{
+    io::{Read, Write},
+    net::{Shutdown, TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+    thread,
+};
+
+#[macro_use]
+mod mock_server;
+
+use mock_server::{Frame, MockExchange};
+
+const ACK: &str = r#"{"result":null,"id":9527}"#;
+const TRADE: &str = r#"{"stream":"btcusdt@aggTrade","data":{"e":"aggTrade","E":1616651250071,"s":"BTCUSDT","a":626337164,"p":"52000.01","q":"0.00100000","f":715427467,"l":715427467,"T":1616651250070,"m":true,"M":true}}"#;
+
+// A local stand-in of HTTP and SOCKS5 proxy servers, which records the
+// target and credentials of every established tunnel, then relays bytes in
+// both directions.
+// (target, credentials) of each tunnel
+type Tunnels = Arc<Mutex<Vec<(String, Option<String>)>>>;
+
+struct MockProxy {
+    port: u16,
+    tunnels: Tunnels,
+}
+
+impl MockProxy {
+    fn start(kind: ProxyKind) -> MockProxy {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let tunnels: Tunnels = Arc::new(Mutex::new(Vec::new()));
+        let tunnels_clone = tunnels.clone();
+        thread::spawn(move || {
+            for client in listener.incoming() {
+                let mut client = client.unwrap();
+                let (target, credentials) = match kind {
+                    ProxyKind::Http => http_handshake(&mut client),
+                    ProxyKind::Socks5 => socks5_handshake(&mut client),
+                };
+                // the mock exchange stops listening after its script is exhausted
+                let server = TcpStream::connect(&target);
+                let reply: &[u8] = match (kind, server.is_ok()) {
+                    (ProxyKind::Http, true) => b"HTTP/1.1 200 Connection established\r\n\r\n",
+                    (ProxyKind::Http, false) => b"HTTP/1.1 502 Bad Gateway\r\n\r\n",
+                    (ProxyKind::Socks5, true) => &[5, 0, 0, 1, 0, 0, 0, 0, 0, 0],
+                    (ProxyKind::Socks5, false) => &[5, 5, 0, 1, 0, 0, 0, 0, 0, 0],
+                };
+                let _ = client.write_all(reply);
+                if let Ok(server) = server {
+                    tunnels_clone.lock().unwrap().push((target, credentials));
+                    relay(client.try_clone().unwrap(), server.try_clone().unwrap());
+                    relay(server, client);
+                }
+            }
+        });
+        MockProxy { port, tunnels }
+    }
+
+    fn proxy(&self, kind: ProxyKind, auth: Option<(&str, &str)>) -> Proxy {
+        Proxy {
+            kind,
+            host: "127.0.0.1".to_string(),
+            port: self.port,
+            auth: auth.map(|(user, pass)| (user.to_string(), pass.to_string())),
+        }
+    }
+
+    fn tunnels(&self) -> Vec<(String, Option<String>)> {
+        self.tunnels.lock().unwrap().clone()
+    }
+}
+
+fn relay(mut from: TcpStream, mut to: TcpStream) {
+    thread::spawn(move || {
+        let mut buf = [0u8; 4096];
+        loop {
+            match from.read(&mut buf) {
+                Ok(n) if n > 0 => {
+                    if to.write_all(&buf[..n]).is_err() {
+                        break;
+                    }
+                }
+                _ => break,
+            }
+        }
+        let _ = from.shutdown(Shutdown::Both);
+        let _ = to.shutdown(Shutdown::Both);
+    });
+}
+
+fn http_handshake(client: &mut TcpStream) -> (String, Option<String>) {
+    let mut request = Vec::new();
+    let mut byte = [0u8; 1];
+    while !request.ends_with(b"\r\n\r\n") {
+        client.read_exact(&mut byte).unwrap();
+        request.push(byte[0]);
+    }
+    let request = String::from_utf8(request).unwrap();
+    let target = request
+        .strip_prefix("CONNECT ")
+        .unwrap()
+        .split_whitespace()
+        .next()
+        .unwrap()
+        .to_string();
+    let credentials = request
+        .lines()
+        .find_map(|line| line.strip_prefix("Proxy-Authorization: Basic "))
+        .map(|x| String::from_utf8(base64::decode(x).unwrap()).unwrap());
+    (target, credentials)
+}
+
+fn socks5_handshake(client: &mut TcpStream) -> (String, Option<String>) {
+    let mut header = [0u8; 2];
+    client.read_exact(&mut header).unwrap();
+    let mut methods = vec![0u8; header[1] as usize];
+    client.read_exact(&mut methods).unwrap();
+
+    let credentials = if methods.contains(&2) {
+        client.write_all(&[5, 2]).unwrap();
+        client.read_exact(&mut header).unwrap();
+        let mut user = vec![0u8; header[1] as usize];
+        client.read_exact(&mut user).unwrap();
+        let mut len = [0u8; 1];
+        client.read_exact(&mut len).unwrap();
+        let mut pass = vec![0u8; len[0] as usize];
+        client.read_exact(&mut pass).unwrap();
+        client.write_all(&[1, 0]).unwrap();
+        Some(format!(
+            "{}:{}",
+            String::from_utf8(user).unwrap(),
+            String::from_utf8(pass).unwrap()
+        ))
+    } else {
+        client.write_all(&[5, 0]).unwrap();
+        None
+    };
+
+    let mut request = [0u8; 5];
+    client.read_exact(&mut request).unwrap();
+    assert_eq!([5, 1, 0, 3], request[..4]); // CONNECT with a domain name
+    let mut host = vec![0u8; request[4] as usize];
+    client.read_exact(&mut host).unwrap();
+    let mut port = [0u8; 2];
+    client.read_exact(&mut port).unwrap();
+
+    let target = format!(
+        "{}:{}",
+        String::from_utf8(host).unwrap(),
+        u16::from_be_bytes(port)
+    );
+    (target, credentials)
+}
+
+#[test]
+fn http_proxy() {
+    let proxy = MockProxy::start(ProxyKind::Http);
+    let mut server = MockExchange::new(vec![Frame::text(ACK), Frame::text(TRADE)]).start();
+    let messages = run_offline!(
+        BinanceSpotWSClient,
+        server,
+        &["btcusdt@aggTrade".to_string()],
+        WSClientOptions::default().proxy(proxy.proxy(ProxyKind::Http, Some(("user", "secret"))))
+    );
+    server.join();
+
+    assert_eq!(vec![TRADE.to_string()], messages);
+    let target = server.url().strip_prefix("ws://").unwrap().to_string();
+    assert_eq!(
+        vec![(target, Some("user:secret".to_string()))],
+        proxy.tunnels()
+    );
+}
+
+#[test]
+fn socks5_proxy() {
+    let proxy = MockProxy::start(ProxyKind::Socks5);
+    let mut server = MockExchange::new(vec![
+        Frame::text(ACK),
+        Frame::text(TRADE),
+        Frame::Disconnect,
+        Frame::text(ACK),
+        Frame::text(TRADE),
+    ])
+    .start();
+    let messages = run_offline!(
+        BinanceSpotWSClient,
+        server,
+        &["btcusdt@aggTrade".to_string()],
+        WSClientOptions::default().proxy(proxy.proxy(ProxyKind::Socks5, Some(("user", "secret"))))
+    );
+    server.join();
+
+    assert_eq!(vec![TRADE.to_string(), TRADE.to_string()], messages);
+    // reconnections go through the proxy too
+    let target = server.url().strip_prefix("ws://").unwrap().to_string();
+    assert_eq!(
+        vec![(target.clone(), Some("user:secret".to_string())); 2],
+        proxy.tunnels()
+    );
+}
+
+#[test]
+fn socks5_proxy_async() {
+    let proxy = MockProxy::start(ProxyKind::Socks5);
+    let mut server = MockExchange::new(vec![Frame::text(ACK), Frame::text(TRADE)]).start();
+    let messages = Arc::new(Mutex::new(Vec::<String>::new()));
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        let messages_clone = messages.clone();
+        let on_msg = Arc::new(Mutex::new(move |msg: String| {
+            messages_clone.lock().unwrap().push(msg)
+        }));
+        let ws_client = BinanceSpotAsyncWSClient::new_with_options(
+            on_msg,
+            WSClientOptions::default()
+                .endpoints(&[server.url()])
+                .proxy(proxy.proxy(ProxyKind::Socks5, None)),
+        );
+        ws_client.set_reconnect_policy(mock_server::fast_reconnect());
+        ws_client.subscribe(&["btcusdt@aggTrade".to_string()]).await;
+        ws_client.run(None).await;
+        ws_client.close();
+    });
+    server.join();
+
+    assert_eq!(vec![TRADE.to_string()], *messages.lock().unwrap());
+    let target = server.url().strip_prefix("ws://").unwrap().to_string();
+    assert_eq!(vec![(target, None)], proxy.tunnels());
+}
+
+#[test]
+fn refused_by_proxy() {
+    // a proxy server which refuses every CONNECT
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    thread::spawn(move || {
+        for client in listener.incoming() {
+            let mut client = client.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = client.read(&mut buf);
+            let _ = client.write_all(b"HTTP/1.1 407 Proxy Authentication Required\r\n\r\n");
+        }
+    });
+    let proxy = Proxy::parse(&format!("http://127.0.0.1:{}", port)).unwrap();
+    let result = std::panic::catch_unwind(|| {
+        BinanceSpotWSClient::new_with_options(
+            Arc::new(Mutex::new(|_msg: String| {})),
+            WSClientOptions::default()
+                .endpoints(&["ws://127.0.0.1:1"])
+                .proxy(proxy),
+        )
+    });
+    assert!(result.is_err());
+}
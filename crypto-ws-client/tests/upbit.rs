@@ -0,0 +1,50 @@
+use crypto_ws_client::{UpbitWSClient, WSClient};
+use std::sync::{Arc, Mutex};
+
+#[macro_use]
+mod utils;
+
+#[test]
+fn subscribe() {
+    gen_test_code!(
+        UpbitWSClient,
+        subscribe,
+        &["trade:KRW-BTC".to_string(), "trade:KRW-ETH".to_string()]
+    );
+}
+
+#[test]
+fn subscribe_raw_json() {
+    gen_test_code!(
+        UpbitWSClient,
+        subscribe,
+        &[r#"[{"ticket":"test"},{"type":"trade","codes":["KRW-BTC","KRW-ETH"]}]"#.to_string()]
+    );
+}
+
+#[test]
+fn subscribe_trade() {
+    gen_test_code!(
+        UpbitWSClient,
+        subscribe_trade,
+        &["KRW-BTC".to_string(), "KRW-ETH".to_string()]
+    );
+}
+
+#[test]
+fn subscribe_orderbook_snapshot() {
+    gen_test_code!(
+        UpbitWSClient,
+        subscribe_orderbook_snapshot,
+        &["KRW-BTC".to_string(), "KRW-ETH".to_string()]
+    );
+}
+
+#[test]
+fn subscribe_ticker() {
+    gen_test_code!(
+        UpbitWSClient,
+        subscribe_ticker,
+        &["KRW-BTC".to_string(), "KRW-ETH".to_string()]
+    );
+}
@@ -1,10 +1,27 @@
+// Unwraps results of high level APIs, raw `subscribe()` returns nothing.
+#[allow(dead_code)]
+pub trait Subscribed {
+    fn check(self);
+}
+
+impl Subscribed for () {
+    fn check(self) {}
+}
+
+impl<E: std::fmt::Debug> Subscribed for Result<(), E> {
+    fn check(self) {
+        self.unwrap();
+    }
+}
+
+#[allow(unused_macros)]
 macro_rules! gen_test_code {
     ($client:ident, $func_name:ident, $pairs:expr) => {
         let mut messages = Vec::<String>::new();
         {
             let on_msg = Arc::new(Mutex::new(|msg: String| messages.push(msg)));
             let ws_client = $client::new(on_msg.clone(), None);
-            ws_client.$func_name($pairs);
+            crate::utils::Subscribed::check(ws_client.$func_name($pairs));
             ws_client.run(Some(0)); // return immediately once after a normal message
             ws_client.close();
         }
@@ -20,10 +37,28 @@ macro_rules! gen_test_subscribe_candlestick {
         {
             let on_msg = Arc::new(Mutex::new(|msg: String| messages.push(msg)));
             let ws_client = $client::new(on_msg.clone(), None);
-            ws_client.subscribe_candlestick($pairs, $interval);
+            ws_client.subscribe_candlestick($pairs, $interval).unwrap();
             ws_client.run(Some(0)); // return immediately once after a normal message
             ws_client.close();
         }
         assert!(!messages.is_empty());
     };
 }
+
+#[allow(unused_macros)]
+macro_rules! gen_async_test_code {
+    ($client:ident, $func_name:ident, $pairs:expr) => {
+        let messages = Arc::new(Mutex::new(Vec::<String>::new()));
+        {
+            let messages_clone = messages.clone();
+            let on_msg = Arc::new(Mutex::new(move |msg: String| {
+                messages_clone.lock().unwrap().push(msg)
+            }));
+            let ws_client = $client::new(on_msg, None);
+            crate::utils::Subscribed::check(ws_client.$func_name($pairs).await);
+            ws_client.run(Some(0)).await; // return immediately once after a normal message
+            ws_client.close();
+        }
+        assert!(!messages.lock().unwrap().is_empty());
+    };
+}